@@ -0,0 +1,219 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+use echolayer_backend::{models, services};
+
+use models::content::{Content, Propagation, ReactionType};
+use models::echo_index::{AudienceMetrics, EchoIndexCalculator};
+use models::Platform;
+use services::link_quality::LinkQualityAnalyzer;
+use services::propagation::{NodeType, PropagationNode};
+use services::{
+    BotDetector, ContentFingerprintService, EchoEngine, EchoEngineConfig, EchoIndexPercentileCache, EchoService,
+    PropagationService,
+};
+
+const PLATFORMS: [Platform; 4] = [Platform::Twitter, Platform::Telegram, Platform::LinkedIn, Platform::Reddit];
+
+const SENTENCE: &str = "Decentralized social networks reward the people whose ideas travel furthest and \
+                        keep resonating long after they were first shared.";
+
+/// `count` propagations of one content item spread over a day, by a tenth as many users
+fn propagations(content_id: Uuid, count: usize) -> Vec<Propagation> {
+    let users: Vec<Uuid> = (0..count.div_ceil(10)).map(|_| Uuid::new_v4()).collect();
+    let start = Utc::now() - Duration::days(1);
+    (0..count)
+        .map(|i| Propagation {
+            id: Uuid::new_v4(),
+            content_id,
+            from_user_id: users[i % users.len()],
+            to_user_id: Some(Uuid::new_v4()),
+            platform: PLATFORMS[i % PLATFORMS.len()].clone(),
+            propagation_type: if i % 3 == 0 { "quote" } else { "share" }.to_string(),
+            depth: (i % 5) as i32 + 1,
+            weight: 0.5,
+            timestamp: start + Duration::seconds((i * 86_400 / count) as i64),
+            reach: 100 + (i as i64 % 900),
+            engagement: i as i64 % 40,
+            bot_score: 0.0,
+            fingerprint_checked: true,
+            duplicate_of: None,
+        })
+        .collect()
+}
+
+/// A full content score, from text analysis to weighted total, as propagations pile up
+fn bench_calculate_echo_index(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let calculator = EchoIndexCalculator::default();
+    let language_factors = HashMap::new();
+    let content = Content::new(
+        Uuid::new_v4(),
+        [SENTENCE; 8].join(" "),
+        Platform::Twitter,
+        "https://twitter.com/echolayer/status/1".to_string(),
+    );
+    let bot_detector = BotDetector::new(content.created_at);
+    let interactions = [AudienceMetrics {
+        total_interactions: 500,
+        quality_interactions: 120,
+        audience_diversity: 0.6,
+        influencer_ratio: 0.1,
+        engagement_depth: 0.4,
+    }];
+
+    let mut group = c.benchmark_group("calculate_echo_index");
+    for count in [10, 100, 1000] {
+        let propagations = propagations(content.id, count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &propagations, |b, propagations| {
+            b.iter(|| {
+                runtime.block_on(EchoService::score(
+                    black_box(&content),
+                    black_box(propagations),
+                    &interactions,
+                    &calculator,
+                    &language_factors,
+                    &bot_detector,
+                ))
+            })
+        });
+    }
+    group.finish();
+}
+
+/// The engine's score of a post a day into its life, as rewards calculate it
+fn bench_calculate_complete_echo_index(c: &mut Criterion) {
+    let engine = EchoEngine::new(EchoEngineConfig::default());
+    let reactions = HashMap::from([
+        (ReactionType::Like, 1250),
+        (ReactionType::ThreadReply, 180),
+        (ReactionType::Repost, 340),
+        (ReactionType::Bookmark, 95),
+    ]);
+    let links = LinkQualityAnalyzer::new(&Platform::Twitter).score_links(&[
+        "https://arxiv.org/abs/2401.00001".to_string(),
+        "https://github.com/EchoLayerS/EchoLayer".to_string(),
+    ]);
+    let created_at = (Utc::now() - Duration::days(1)).timestamp();
+    let last_interaction = Utc::now().timestamp();
+
+    let mut group = c.benchmark_group("calculate_complete_echo_index");
+    group.bench_with_input(BenchmarkId::from_parameter("twitter"), &Platform::Twitter, |b, platform| {
+        b.iter(|| {
+            engine.calculate_complete_echo_index(
+                black_box(platform),
+                210,
+                340,
+                48_000,
+                &reactions,
+                42.5,
+                18_300,
+                created_at,
+                last_interaction,
+                3.2,
+                0.35,
+                0.8,
+                0.7,
+                0.9,
+                &links,
+            )
+        })
+    });
+    group.finish();
+}
+
+fn node(id: String) -> PropagationNode {
+    PropagationNode {
+        id,
+        node_type: NodeType::User,
+        influence_weight: 0.5,
+        reach: 1_000,
+        engagement_rate: 0.1,
+        timestamp: Utc::now(),
+    }
+}
+
+/// Workers adding propagations to the same Echo Loop at once, each extending its own path
+fn bench_add_propagation_event(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group("add_propagation_event");
+    for workers in [1, 8, 64] {
+        group.bench_with_input(BenchmarkId::new("concurrent_workers", workers), &workers, |b, &workers| {
+            b.iter_batched(
+                || {
+                    let service = Arc::new(PropagationService::new());
+                    let loop_id = runtime.block_on(service.create_echo_loop("content_bench".to_string())).unwrap();
+                    (service, loop_id)
+                },
+                |(service, loop_id)| {
+                    runtime.block_on(async {
+                        let events: Vec<_> = (0..workers)
+                            .map(|worker| {
+                                let (service, loop_id) = (service.clone(), loop_id.clone());
+                                tokio::spawn(async move {
+                                    let (from, to) = (format!("from_{}", worker), format!("to_{}", worker));
+                                    service.add_propagation_event(&loop_id, node(from), node(to), 1.0).await
+                                })
+                            })
+                            .collect();
+                        for event in events {
+                            event.await.unwrap().unwrap();
+                        }
+                    })
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Ranking a score against the whole platform, as every score response does
+fn bench_percentile_for(c: &mut Criterion) {
+    let scored = 100_000;
+    let cache = EchoIndexPercentileCache::new();
+    for i in 0..scored {
+        cache.record(Uuid::new_v4(), (i % 10_000) as f64 / 100.0);
+    }
+
+    let mut group = c.benchmark_group("percentile_for");
+    group.bench_with_input(BenchmarkId::new("cached_scores", scored), &cache, |b, cache| {
+        b.iter(|| cache.percentile_for(black_box(73.5)))
+    });
+    group.finish();
+}
+
+/// MinHash fingerprint of a long article, computed for every piece of content created
+fn bench_fingerprint(c: &mut Criterion) {
+    let fingerprints = ContentFingerprintService::new();
+    let words = 5000;
+    let document = SENTENCE
+        .split_whitespace()
+        .cycle()
+        .take(words)
+        .enumerate()
+        .map(|(i, word)| format!("{}{}", word, i % 97))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut group = c.benchmark_group("compute_minhash");
+    group.bench_with_input(BenchmarkId::new("words", words), &document, |b, document| {
+        b.iter(|| fingerprints.fingerprint(black_box(document)))
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_calculate_echo_index,
+    bench_calculate_complete_echo_index,
+    bench_add_propagation_event,
+    bench_percentile_for,
+    bench_fingerprint
+);
+criterion_main!(benches);
@@ -0,0 +1,31 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::net::IpAddr;
+use std::time::Duration;
+
+use echolayer_backend::middleware::rate_limit::{RateLimitConfig, RateLimiter};
+
+/// The limiter sits in front of every request, so a check must stay well under 100µs
+fn bench_rate_limiter(c: &mut Criterion) {
+    let limiter = RateLimiter::new(RateLimitConfig {
+        max_requests: u32::MAX,
+        window: Duration::from_secs(60),
+        trust_forwarded_for: false,
+    });
+
+    let single_ip: IpAddr = "203.0.113.1".parse().unwrap();
+    c.bench_function("rate_limiter_check_single_ip", |b| {
+        b.iter(|| limiter.check(black_box(single_ip)))
+    });
+
+    let mut counter: u32 = 0;
+    c.bench_function("rate_limiter_check_many_ips", |b| {
+        b.iter(|| {
+            counter = counter.wrapping_add(1);
+            let ip = IpAddr::from(counter.to_be_bytes());
+            limiter.check(black_box(ip))
+        })
+    });
+}
+
+criterion_group!(benches, bench_rate_limiter);
+criterion_main!(benches);
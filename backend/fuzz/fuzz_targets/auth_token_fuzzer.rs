@@ -0,0 +1,44 @@
+#![no_main]
+
+use echolayer_backend::handlers::auth::{AuthService, JwtConfig};
+use echolayer_backend::models::user::Role;
+use echolayer_backend::services::TokenBlacklist;
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    user_id: String,
+    wallet_address: String,
+    session_id: String,
+    admin: bool,
+    totp_verified: bool,
+}
+
+// Whatever the claims hold, the token signed for them validates back to the same claims
+fuzz_target!(|input: Input| {
+    let config = JwtConfig::new("fuzz-secret");
+    let role = if input.admin { Role::Admin } else { Role::User };
+    let token = match AuthService::generate_access_token(
+        &input.user_id,
+        &input.wallet_address,
+        &input.session_id,
+        role,
+        input.totp_verified,
+        &config,
+    ) {
+        Ok(token) => token,
+        Err(_) => return,
+    };
+
+    match AuthService::validate_access_token(&token, &config, &TokenBlacklist::new()) {
+        Ok(claims) => {
+            assert_eq!(claims.sub, input.user_id);
+            assert_eq!(claims.wallet, input.wallet_address);
+            assert_eq!(claims.session_id, input.session_id);
+            assert_eq!(claims.role, role);
+            assert_eq!(claims.totp_verified, input.totp_verified);
+        }
+        Err(e) => panic!("freshly signed token was rejected: {}", e),
+    }
+});
@@ -0,0 +1,52 @@
+#![no_main]
+
+use echolayer_backend::handlers::auth::{AuthService, WalletType};
+use echolayer_backend::services::MpcWalletVerifier;
+use futures::future::BoxFuture;
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+
+/// Sign-in fields as they arrive from the network, before any UTF-8 validation
+#[derive(Arbitrary, Debug)]
+struct Input {
+    wallet_address: Vec<u8>,
+    signature: Vec<u8>,
+    message: Vec<u8>,
+}
+
+/// Accepts every signature, so MPC inputs run through address parsing without a provider
+struct AcceptingVerifier;
+
+impl MpcWalletVerifier for AcceptingVerifier {
+    fn verify<'a>(
+        &'a self,
+        _wallet_address: &'a str,
+        _signature: &'a str,
+        _message: &'a str,
+    ) -> BoxFuture<'a, Result<bool, String>> {
+        Box::pin(async { Ok(true) })
+    }
+}
+
+// Any input must be answered with a verdict or an error, never a panic
+fuzz_target!(|input: Input| {
+    let wallet_address = String::from_utf8_lossy(&input.wallet_address);
+    let signature = String::from_utf8_lossy(&input.signature);
+    let message = String::from_utf8_lossy(&input.message);
+
+    for wallet_type in [
+        WalletType::MPC,
+        WalletType::Phantom,
+        WalletType::Solflare,
+        WalletType::MetaMask,
+        WalletType::WalletConnect,
+    ] {
+        let _ = futures::executor::block_on(AuthService::verify_wallet_signature(
+            &wallet_address,
+            &signature,
+            &message,
+            &wallet_type,
+            &AcceptingVerifier,
+        ));
+    }
+});
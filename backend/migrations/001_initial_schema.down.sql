@@ -0,0 +1,36 @@
+-- EchoLayer Database Schema Migration 001 (revert)
+-- Description: Drop the initial database schema
+-- Created: 2024-03-18
+-- Version: 1.0.0
+
+-- Extensions are left installed; other schemas in the database may rely on them
+
+DROP VIEW IF EXISTS platform_stats;
+DROP VIEW IF EXISTS content_analytics;
+DROP VIEW IF EXISTS user_stats;
+
+DROP FUNCTION IF EXISTS process_reward(UUID, DECIMAL, reward_type, TEXT, UUID, UUID);
+DROP FUNCTION IF EXISTS update_content_echo_index(UUID, DECIMAL);
+DROP FUNCTION IF EXISTS calculate_user_ranks();
+
+DROP TABLE IF EXISTS system_settings;
+DROP TABLE IF EXISTS notifications;
+DROP TABLE IF EXISTS user_sessions;
+DROP TABLE IF EXISTS analytics_events;
+DROP TABLE IF EXISTS rewards;
+DROP TABLE IF EXISTS echo_index_calculations;
+DROP TABLE IF EXISTS propagations;
+DROP TABLE IF EXISTS content;
+DROP TABLE IF EXISTS social_platforms;
+DROP TABLE IF EXISTS users;
+
+DROP FUNCTION IF EXISTS update_updated_at_column();
+
+DROP TYPE IF EXISTS notification_type;
+DROP TYPE IF EXISTS event_type;
+DROP TYPE IF EXISTS reward_status;
+DROP TYPE IF EXISTS reward_type;
+DROP TYPE IF EXISTS propagation_type;
+DROP TYPE IF EXISTS content_status;
+DROP TYPE IF EXISTS content_type;
+DROP TYPE IF EXISTS platform_type;
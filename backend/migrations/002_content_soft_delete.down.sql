@@ -0,0 +1,8 @@
+-- EchoLayer Database Schema Migration 002 (revert)
+-- Description: Remove soft deletion for content
+-- Created: 2024-03-18
+-- Version: 1.0.1
+
+DROP INDEX IF EXISTS idx_content_live_created_at;
+
+ALTER TABLE content DROP COLUMN IF EXISTS deleted_at;
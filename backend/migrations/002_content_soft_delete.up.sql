@@ -0,0 +1,9 @@
+-- EchoLayer Database Schema Migration 002
+-- Description: Soft deletion for content
+-- Created: 2024-01-15
+-- Version: 1.0.1
+
+ALTER TABLE content ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
+
+-- Listings only ever read live rows, newest first
+CREATE INDEX idx_content_live_created_at ON content(created_at DESC, id DESC) WHERE deleted_at IS NULL;
@@ -0,0 +1,8 @@
+-- EchoLayer Database Schema Migration 003 (revert)
+-- Description: Drop Echo Index score history
+-- Created: 2024-03-18
+-- Version: 1.0.2
+
+DROP TABLE IF EXISTS echo_index_history;
+
+DROP TYPE IF EXISTS echo_index_trigger;
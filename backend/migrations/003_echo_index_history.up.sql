@@ -0,0 +1,27 @@
+-- EchoLayer Database Schema Migration 003
+-- Description: Echo Index score history with per-calculation deltas
+-- Created: 2024-01-22
+-- Version: 1.0.2
+
+CREATE TYPE echo_index_trigger AS ENUM (
+    'initial',
+    'propagation_added',
+    'recalculation',
+    'scheduled'
+);
+
+CREATE TABLE echo_index_history (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    score DOUBLE PRECISION NOT NULL,
+    odf DOUBLE PRECISION NOT NULL,
+    awr DOUBLE PRECISION NOT NULL,
+    tpm DOUBLE PRECISION NOT NULL,
+    qf DOUBLE PRECISION NOT NULL,
+    delta_score DOUBLE PRECISION NOT NULL DEFAULT 0,
+    trigger echo_index_trigger NOT NULL,
+    -- clock_timestamp keeps rows from the same transaction ordered
+    calculated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT clock_timestamp()
+);
+
+CREATE INDEX idx_echo_index_history_content_calculated_at ON echo_index_history(content_id, calculated_at DESC);
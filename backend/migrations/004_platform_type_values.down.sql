@@ -0,0 +1,8 @@
+-- EchoLayer Database Schema Migration 004 (revert)
+-- Description: Intentionally empty
+-- Created: 2024-03-18
+-- Version: 1.0.3
+
+-- PostgreSQL cannot remove values from an enum type. The extra platform_type
+-- values are harmless to older code and are dropped with the type by 001.
+SELECT 1;
@@ -0,0 +1,8 @@
+-- EchoLayer Database Schema Migration 004
+-- Description: Platforms supported by the API but missing from platform_type
+-- Created: 2024-01-29
+-- Version: 1.0.3
+
+ALTER TYPE platform_type ADD VALUE IF NOT EXISTS 'medium';
+ALTER TYPE platform_type ADD VALUE IF NOT EXISTS 'discord';
+ALTER TYPE platform_type ADD VALUE IF NOT EXISTS 'farcaster';
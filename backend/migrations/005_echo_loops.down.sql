@@ -0,0 +1,7 @@
+-- EchoLayer Database Schema Migration 005 (revert)
+-- Description: Drop persistent Echo Loops
+-- Created: 2024-03-18
+-- Version: 1.0.4
+
+DROP TABLE IF EXISTS propagation_paths;
+DROP TABLE IF EXISTS echo_loops;
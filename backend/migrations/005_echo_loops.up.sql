@@ -0,0 +1,26 @@
+-- EchoLayer Database Schema Migration 005
+-- Description: Persistent Echo Loops and their propagation paths
+-- Created: 2024-02-05
+-- Version: 1.0.4
+
+CREATE TABLE echo_loops (
+    id VARCHAR(64) PRIMARY KEY,
+    source_content_id VARCHAR(255) NOT NULL,
+    total_resonance DOUBLE PRECISION NOT NULL DEFAULT 0,
+    loop_strength DOUBLE PRECISION NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_updated TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_echo_loops_source_content_id ON echo_loops(source_content_id);
+CREATE INDEX idx_echo_loops_last_updated ON echo_loops(last_updated);
+
+CREATE TABLE propagation_paths (
+    loop_id VARCHAR(64) NOT NULL REFERENCES echo_loops(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    nodes JSONB NOT NULL,
+    total_weight DOUBLE PRECISION NOT NULL,
+    resonance_factor DOUBLE PRECISION NOT NULL,
+    decay_rate DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (loop_id, position)
+);
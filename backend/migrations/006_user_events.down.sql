@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 006 (revert)
+-- Description: Drop user activity events
+-- Created: 2024-03-18
+-- Version: 1.0.5
+
+DROP TABLE IF EXISTS user_events;
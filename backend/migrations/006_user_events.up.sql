@@ -0,0 +1,14 @@
+-- EchoLayer Database Schema Migration 006
+-- Description: Typed user activity events backing the timeline
+-- Created: 2024-02-12
+-- Version: 1.0.5
+
+CREATE TABLE user_events (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_type VARCHAR(50) NOT NULL,
+    payload JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT clock_timestamp()
+);
+
+CREATE INDEX idx_user_events_timeline ON user_events(user_id, created_at DESC, id DESC);
@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 007 (revert)
+-- Description: Drop daily content creation streaks
+-- Created: 2024-03-18
+-- Version: 1.0.6
+
+DROP TABLE IF EXISTS user_streaks;
@@ -0,0 +1,13 @@
+-- EchoLayer Database Schema Migration 007
+-- Description: Daily content creation streaks
+-- Created: 2024-02-19
+-- Version: 1.0.6
+
+CREATE TABLE user_streaks (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    current_streak_days INTEGER NOT NULL DEFAULT 0 CHECK (current_streak_days >= 0),
+    longest_streak_days INTEGER NOT NULL DEFAULT 0 CHECK (longest_streak_days >= 0),
+    last_activity_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    streak_freeze_tokens SMALLINT NOT NULL DEFAULT 0 CHECK (streak_freeze_tokens BETWEEN 0 AND 255),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
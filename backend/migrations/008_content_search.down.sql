@@ -0,0 +1,9 @@
+-- EchoLayer Database Schema Migration 008 (revert)
+-- Description: Remove full-text search over content
+-- Created: 2024-03-18
+-- Version: 1.0.7
+
+-- Dropping the column also drops its GIN index
+ALTER TABLE content DROP COLUMN IF EXISTS search_vector;
+
+DROP FUNCTION IF EXISTS content_search_document(TEXT, TEXT, TEXT[]);
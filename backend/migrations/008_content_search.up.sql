@@ -0,0 +1,18 @@
+-- EchoLayer Database Schema Migration 008
+-- Description: Full-text search over content title, body and tags
+-- Created: 2024-02-26
+-- Version: 1.0.7
+
+-- array_to_string is only STABLE, so generated columns need an IMMUTABLE wrapper;
+-- tags are plain text, so the result never depends on session settings
+CREATE OR REPLACE FUNCTION content_search_document(title TEXT, body TEXT, tags TEXT[])
+RETURNS tsvector AS $$
+    SELECT to_tsvector('english',
+        COALESCE(title, '') || ' ' || COALESCE(body, '') || ' ' || COALESCE(array_to_string(tags, ' '), ''))
+$$ LANGUAGE sql IMMUTABLE;
+
+ALTER TABLE content
+    ADD COLUMN search_vector tsvector
+    GENERATED ALWAYS AS (content_search_document(title, body, tags)) STORED;
+
+CREATE INDEX idx_content_search_vector ON content USING GIN (search_vector);
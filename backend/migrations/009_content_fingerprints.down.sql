@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 009 (revert)
+-- Description: Drop content fingerprints
+-- Created: 2024-03-18
+-- Version: 1.0.8
+
+DROP TABLE IF EXISTS content_fingerprints;
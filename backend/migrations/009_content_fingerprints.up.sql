@@ -0,0 +1,14 @@
+-- EchoLayer Database Schema Migration 009
+-- Description: MinHash fingerprints for near-duplicate content detection
+-- Created: 2024-03-04
+-- Version: 1.0.8
+
+CREATE TABLE content_fingerprints (
+    content_id UUID PRIMARY KEY REFERENCES content(id) ON DELETE CASCADE,
+    minhash BIGINT[] NOT NULL,
+    -- One hash per LSH band; content sharing any band hash is a duplicate candidate
+    band_hashes BIGINT[] NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_content_fingerprints_band_hashes ON content_fingerprints USING GIN (band_hashes);
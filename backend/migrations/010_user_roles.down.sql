@@ -0,0 +1,8 @@
+-- EchoLayer Database Schema Migration 010 (revert)
+-- Description: Remove user roles
+-- Created: 2024-03-18
+-- Version: 1.0.9
+
+ALTER TABLE users DROP COLUMN IF EXISTS role;
+
+DROP TYPE IF EXISTS user_role;
@@ -0,0 +1,8 @@
+-- EchoLayer Database Schema Migration 010
+-- Description: Roles for access to operator endpoints
+-- Created: 2024-03-11
+-- Version: 1.0.9
+
+CREATE TYPE user_role AS ENUM ('user', 'moderator', 'admin', 'super_admin');
+
+ALTER TABLE users ADD COLUMN role user_role NOT NULL DEFAULT 'user';
@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 011 (revert)
+-- Description: Drop refresh tokens
+-- Created: 2024-03-25
+-- Version: 1.0.10
+
+DROP TABLE IF EXISTS refresh_tokens;
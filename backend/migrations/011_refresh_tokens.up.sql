@@ -0,0 +1,16 @@
+-- EchoLayer Database Schema Migration 011
+-- Description: Hashed refresh tokens bound to login sessions
+-- Created: 2024-03-25
+-- Version: 1.0.10
+
+CREATE TABLE refresh_tokens (
+    -- SHA-256 of the token; the token itself is only ever held by the client
+    token_hash CHAR(64) PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    session_id UUID NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_refresh_tokens_user_id ON refresh_tokens(user_id);
+CREATE INDEX idx_refresh_tokens_expires_at ON refresh_tokens(expires_at);
@@ -0,0 +1,8 @@
+-- EchoLayer Database Schema Migration 012 (revert)
+-- Description: Drop content cold storage
+-- Created: 2024-04-01
+-- Version: 1.0.11
+
+DROP INDEX IF EXISTS idx_content_archival;
+
+DROP TABLE IF EXISTS archived_content;
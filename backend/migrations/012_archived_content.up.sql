@@ -0,0 +1,18 @@
+-- EchoLayer Database Schema Migration 012
+-- Description: Cold storage for old low-echo content
+-- Created: 2024-04-01
+-- Version: 1.0.11
+
+CREATE TABLE archived_content (
+    content_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- zstd-compressed JSON of the content, its Echo Index history and propagations
+    compressed_data BYTEA NOT NULL,
+    uncompressed_size BIGINT NOT NULL,
+    archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_archived_content_user_id ON archived_content(user_id);
+
+-- The archival sweep looks for stale, low-echo live content
+CREATE INDEX idx_content_archival ON content(updated_at) WHERE deleted_at IS NULL;
@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 013 (revert)
+-- Description: Drop propagation deduplication index
+-- Created: 2024-04-08
+-- Version: 1.0.12
+
+DROP INDEX IF EXISTS idx_propagations_dedup;
@@ -0,0 +1,21 @@
+-- EchoLayer Database Schema Migration 013
+-- Description: Deduplicate propagation events from platform connectors
+-- Created: 2024-04-08
+-- Version: 1.0.12
+
+-- Keep the earliest copy of any propagation recorded more than once
+DELETE FROM propagations p
+USING propagations earlier
+WHERE p.content_id = earlier.content_id
+  AND p.propagation_type = earlier.propagation_type
+  AND COALESCE(p.source_external_id, '') = COALESCE(earlier.source_external_id, '')
+  AND COALESCE(p.target_external_id, '') = COALESCE(earlier.target_external_id, '')
+  AND (p.created_at, p.id) > (earlier.created_at, earlier.id);
+
+-- Connectors replay events, so the same propagation must only be stored once
+CREATE UNIQUE INDEX idx_propagations_dedup ON propagations (
+    content_id,
+    COALESCE(source_external_id, ''),
+    COALESCE(target_external_id, ''),
+    propagation_type
+);
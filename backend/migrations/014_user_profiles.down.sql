@@ -0,0 +1,10 @@
+-- EchoLayer Database Schema Migration 014 (revert)
+-- Description: Drop user display names and the leaderboard index
+-- Created: 2024-04-15
+-- Version: 1.0.13
+
+DROP INDEX IF EXISTS idx_users_leaderboard;
+
+ALTER TABLE users ALTER COLUMN echo_score DROP NOT NULL;
+
+ALTER TABLE users DROP COLUMN IF EXISTS display_name;
@@ -0,0 +1,12 @@
+-- EchoLayer Database Schema Migration 014
+-- Description: User display names and a stable leaderboard ordering
+-- Created: 2024-04-15
+-- Version: 1.0.13
+
+ALTER TABLE users ADD COLUMN display_name VARCHAR(100);
+
+-- Leaderboard pages are keyed on (echo_score, id), which must never be NULL
+UPDATE users SET echo_score = 0 WHERE echo_score IS NULL;
+ALTER TABLE users ALTER COLUMN echo_score SET NOT NULL;
+
+CREATE INDEX idx_users_leaderboard ON users(echo_score DESC, id ASC) WHERE is_active IS NOT FALSE;
@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 015 (revert)
+-- Description: Drop content TF-IDF vectors
+-- Created: 2024-04-22
+-- Version: 1.0.14
+
+DROP TABLE IF EXISTS content_tfidf;
@@ -0,0 +1,16 @@
+-- EchoLayer Database Schema Migration 015
+-- Description: TF-IDF term vectors for related content recommendations
+-- Created: 2024-04-22
+-- Version: 1.0.14
+
+CREATE TABLE content_tfidf (
+    content_id UUID PRIMARY KEY REFERENCES content(id) ON DELETE CASCADE,
+    -- Sparse unit vector: weights[i] is the TF-IDF weight of terms[i]
+    terms TEXT[] NOT NULL,
+    weights DOUBLE PRECISION[] NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT content_tfidf_same_length CHECK (cardinality(terms) = cardinality(weights))
+);
+
+-- Inverted index: content sharing a term with the target is a similarity candidate
+CREATE INDEX idx_content_tfidf_terms ON content_tfidf USING GIN (terms);
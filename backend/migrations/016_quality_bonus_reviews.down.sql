@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 016 (revert)
+-- Description: Drop quality bonus reviews
+-- Created: 2024-04-29
+-- Version: 1.0.15
+
+DROP TABLE IF EXISTS quality_bonus_reviews;
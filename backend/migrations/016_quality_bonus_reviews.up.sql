@@ -0,0 +1,13 @@
+-- EchoLayer Database Schema Migration 016
+-- Description: Retroactive quality bonus reviews, one per content item
+-- Created: 2024-04-29
+-- Version: 1.0.15
+
+CREATE TABLE quality_bonus_reviews (
+    content_id UUID PRIMARY KEY REFERENCES content(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- NULL when the review found nothing to award
+    reward_id VARCHAR(64),
+    amount DOUBLE PRECISION NOT NULL DEFAULT 0,
+    reviewed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,8 @@
+-- EchoLayer Database Schema Migration 017 (revert)
+-- Description: Drop API keys
+-- Created: 2024-05-06
+-- Version: 1.0.16
+
+DROP TABLE IF EXISTS api_keys;
+
+DROP TYPE IF EXISTS api_key_permission;
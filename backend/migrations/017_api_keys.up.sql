@@ -0,0 +1,22 @@
+-- EchoLayer Database Schema Migration 017
+-- Description: Hashed API keys for machine clients
+-- Created: 2024-05-06
+-- Version: 1.0.16
+
+CREATE TYPE api_key_permission AS ENUM ('read', 'write', 'admin');
+
+CREATE TABLE api_keys (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    -- SHA-256 of the key; the key itself is only shown to the client once
+    key_hash CHAR(64) NOT NULL UNIQUE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    permissions api_key_permission[] NOT NULL DEFAULT '{read}',
+    rate_limit_per_minute INTEGER NOT NULL DEFAULT 60 CHECK (rate_limit_per_minute > 0),
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_api_keys_user_id ON api_keys(user_id);
@@ -0,0 +1,8 @@
+-- EchoLayer Database Schema Migration 018 (revert)
+-- Description: Drop velocity alerts
+-- Created: 2024-05-13
+-- Version: 1.0.17
+
+DROP TABLE IF EXISTS velocity_alerts;
+DROP TABLE IF EXISTS alert_triggers;
+DROP TABLE IF EXISTS alert_configs;
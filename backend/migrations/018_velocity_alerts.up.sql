@@ -0,0 +1,37 @@
+-- EchoLayer Database Schema Migration 018
+-- Description: Propagation velocity alert thresholds, trigger state and history
+-- Created: 2024-05-13
+-- Version: 1.0.17
+
+CREATE TABLE alert_configs (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    threshold_type VARCHAR(40) NOT NULL CHECK (
+        threshold_type IN ('propagations_per_hour', 'reach_per_hour', 'echo_index_increase_per_hour')
+    ),
+    threshold DOUBLE PRECISION NOT NULL CHECK (threshold > 0),
+    webhook_url TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, threshold_type)
+);
+
+-- When each threshold last fired for each content item, for the cooldown
+CREATE TABLE alert_triggers (
+    config_id UUID NOT NULL REFERENCES alert_configs(id) ON DELETE CASCADE,
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    triggered_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (config_id, content_id)
+);
+
+CREATE TABLE velocity_alerts (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    threshold_type VARCHAR(40) NOT NULL,
+    threshold DOUBLE PRECISION NOT NULL,
+    current_velocity DOUBLE PRECISION NOT NULL,
+    triggered_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX idx_velocity_alerts_user_triggered_at ON velocity_alerts(user_id, triggered_at DESC);
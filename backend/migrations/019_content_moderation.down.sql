@@ -0,0 +1,9 @@
+-- EchoLayer Database Schema Migration 019 (revert)
+-- Description: Drop content moderation flags
+-- Created: 2024-05-20
+-- Version: 1.0.18
+
+-- PostgreSQL cannot remove values from an enum type, so content_status keeps
+-- 'under_review' and 'removed'; they are dropped with the type by 001.
+DROP TABLE IF EXISTS moderation_flags;
+DROP TYPE IF EXISTS moderation_status;
@@ -0,0 +1,34 @@
+-- EchoLayer Database Schema Migration 019
+-- Description: User flags on content and the moderation review of flagged content
+-- Created: 2024-05-20
+-- Version: 1.0.18
+
+ALTER TYPE content_status ADD VALUE IF NOT EXISTS 'under_review';
+ALTER TYPE content_status ADD VALUE IF NOT EXISTS 'removed';
+
+CREATE TYPE moderation_status AS ENUM (
+    'pending',
+    'under_review',
+    'cleared',
+    'removed'
+);
+
+CREATE TABLE moderation_flags (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    flagged_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason VARCHAR(20) NOT NULL CHECK (
+        reason IN ('spam', 'misinformation', 'harassment', 'copyright', 'other')
+    ),
+    -- The flagging user's own words, for reason 'other'
+    reason_detail TEXT,
+    status moderation_status NOT NULL DEFAULT 'pending',
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    -- A user flagging the same content twice does not count twice
+    UNIQUE (content_id, flagged_by)
+);
+
+CREATE INDEX idx_moderation_flags_flagged_by_created_at ON moderation_flags(flagged_by, created_at DESC);
+CREATE INDEX idx_moderation_flags_status_created_at ON moderation_flags(status, created_at);
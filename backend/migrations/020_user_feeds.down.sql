@@ -0,0 +1,10 @@
+-- EchoLayer Database Schema Migration 020 (revert)
+-- Description: Drop cached discovery feeds
+-- Created: 2024-05-27
+-- Version: 1.0.19
+
+UPDATE system_settings
+SET value = value - 'discovery_feed_affinity_percent', updated_at = NOW()
+WHERE key = 'feature_flags';
+
+DROP TABLE IF EXISTS user_feeds;
@@ -0,0 +1,20 @@
+-- EchoLayer Database Schema Migration 020
+-- Description: Cached personalized discovery feeds and the feed algorithm experiment flag
+-- Created: 2024-05-27
+-- Version: 1.0.19
+
+CREATE TABLE user_feeds (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- Algorithm that ranked the feed, so experiment arms can be compared
+    algorithm VARCHAR(20) NOT NULL,
+    -- Ranked feed entries, served page by page until the feed expires
+    entries JSONB NOT NULL,
+    generated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+-- Percentage of users whose feed is ranked by Echo Index affinity; the rest get
+-- the most viral content, as a baseline
+UPDATE system_settings
+SET value = value || '{"discovery_feed_affinity_percent": 100}', updated_at = NOW()
+WHERE key = 'feature_flags';
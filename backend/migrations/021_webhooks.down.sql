@@ -0,0 +1,8 @@
+-- EchoLayer Database Schema Migration 021 (revert)
+-- Description: Drop webhooks and their deliveries
+-- Created: 2024-06-03
+-- Version: 1.0.20
+
+DROP TABLE IF EXISTS webhook_deliveries;
+DROP TABLE IF EXISTS webhooks;
+DROP TYPE IF EXISTS webhook_event;
@@ -0,0 +1,40 @@
+-- EchoLayer Database Schema Migration 021
+-- Description: Webhooks for Echo Index events and the log of their delivery attempts
+-- Created: 2024-06-03
+-- Version: 1.0.20
+
+CREATE TYPE webhook_event AS ENUM (
+    'echo_index_calculated',
+    'tier_changed',
+    'reward_awarded',
+    'content_flagged'
+);
+
+CREATE TABLE webhooks (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    -- Key payloads are signed with; unlike API keys it must be kept to sign with
+    secret VARCHAR(64) NOT NULL,
+    events webhook_event[] NOT NULL CHECK (cardinality(events) > 0),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_webhooks_user_id ON webhooks(user_id);
+
+-- One row per attempt, so retries can be followed
+CREATE TABLE webhook_deliveries (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    webhook_id UUID NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    event webhook_event NOT NULL,
+    payload JSONB NOT NULL,
+    attempt INTEGER NOT NULL CHECK (attempt > 0),
+    -- NULL when no response arrived
+    status_code INTEGER,
+    error TEXT,
+    succeeded BOOLEAN NOT NULL,
+    attempted_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT clock_timestamp()
+);
+
+CREATE INDEX idx_webhook_deliveries_webhook_id ON webhook_deliveries(webhook_id, attempted_at DESC);
@@ -0,0 +1,8 @@
+-- EchoLayer Database Schema Migration 022 (revert)
+-- Description: Rate limit personal data exports and mark anonymized users
+-- Created: 2024-06-10
+-- Version: 1.0.21
+
+ALTER TABLE users DROP COLUMN IF EXISTS anonymized_at;
+
+DROP TABLE IF EXISTS user_data_exports;
@@ -0,0 +1,13 @@
+-- EchoLayer Database Schema Migration 022
+-- Description: Rate limit personal data exports and mark anonymized users
+-- Created: 2024-06-10
+-- Version: 1.0.21
+
+-- Most recent data export per user, for the one-export-per-day limit
+CREATE TABLE user_data_exports (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    exported_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+-- Set when a user deleted their account; their row stays for Echo Index analytics
+ALTER TABLE users ADD COLUMN anonymized_at TIMESTAMP WITH TIME ZONE;
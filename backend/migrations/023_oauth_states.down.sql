@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 023 (revert)
+-- Description: Pending OAuth flows proving ownership of social accounts
+-- Created: 2024-06-17
+-- Version: 1.0.22
+
+DROP TABLE IF EXISTS oauth_states;
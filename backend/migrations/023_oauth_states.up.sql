@@ -0,0 +1,24 @@
+-- EchoLayer Database Schema Migration 023
+-- Description: Pending OAuth flows proving ownership of social accounts
+-- Created: 2024-06-17
+-- Version: 1.0.22
+
+-- One row per started verification, consumed by its callback. The state doubles as the
+-- CSRF token the provider echoes back, so it is only honoured for the user and platform
+-- it was issued to and until it expires.
+CREATE TABLE oauth_states (
+    state VARCHAR(64) PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    platform platform_type NOT NULL,
+    -- Account the user claims to own; the provider must confirm it
+    account_id VARCHAR(255) NOT NULL,
+    -- PKCE code verifier of OAuth 2.0 flows
+    code_verifier VARCHAR(128),
+    -- Temporary credentials of OAuth 1.0a flows
+    request_token VARCHAR(255),
+    request_token_secret VARCHAR(255),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX idx_oauth_states_expires_at ON oauth_states(expires_at);
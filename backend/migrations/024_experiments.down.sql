@@ -0,0 +1,13 @@
+-- EchoLayer Database Schema Migration 024 (revert)
+-- Description: A/B experiments on Echo Index component weights
+-- Created: 2024-06-24
+-- Version: 1.0.23
+
+DROP INDEX IF EXISTS idx_echo_index_history_experiment;
+
+ALTER TABLE echo_index_history
+    DROP COLUMN IF EXISTS variant,
+    DROP COLUMN IF EXISTS experiment_id;
+
+DROP TABLE IF EXISTS experiments;
+DROP TYPE IF EXISTS experiment_variant;
@@ -0,0 +1,31 @@
+-- EchoLayer Database Schema Migration 024
+-- Description: A/B experiments on Echo Index component weights
+-- Created: 2024-06-24
+-- Version: 1.0.23
+
+CREATE TYPE experiment_variant AS ENUM (
+    'control',
+    'treatment'
+);
+
+-- Weights are stored as {"odf", "awr", "tpm", "qf"} objects summing to 1.0
+CREATE TABLE experiments (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(255) NOT NULL,
+    control_weights JSONB NOT NULL,
+    treatment_weights JSONB NOT NULL,
+    -- Share of content scored with the treatment weights
+    traffic_split DOUBLE PRECISION NOT NULL CHECK (traffic_split >= 0 AND traffic_split <= 1),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Content is scored by at most one experiment at a time
+CREATE UNIQUE INDEX idx_experiments_single_active ON experiments ((TRUE)) WHERE active;
+
+ALTER TABLE echo_index_history
+    ADD COLUMN experiment_id UUID REFERENCES experiments(id) ON DELETE SET NULL,
+    ADD COLUMN variant experiment_variant;
+
+CREATE INDEX idx_echo_index_history_experiment ON echo_index_history(experiment_id, variant)
+    WHERE experiment_id IS NOT NULL;
@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 025 (revert)
+-- Description: Pending rewards checkpointed at shutdown
+-- Created: 2024-07-01
+-- Version: 1.0.24
+
+DROP TABLE IF EXISTS reward_checkpoints;
@@ -0,0 +1,15 @@
+-- EchoLayer Database Schema Migration 025
+-- Description: Pending rewards checkpointed at shutdown
+-- Created: 2024-07-01
+-- Version: 1.0.24
+
+-- Rewards are tracked in memory until they are fully released. On shutdown the ones
+-- still pending are written here, replacing the previous checkpoint, and the next run
+-- resumes paying them out.
+CREATE TABLE reward_checkpoints (
+    reward_id VARCHAR(255) PRIMARY KEY,
+    -- Recipient, a user UUID or a bare wallet address
+    user_id VARCHAR(255) NOT NULL,
+    reward JSONB NOT NULL,
+    checkpointed_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 026 (revert)
+-- Description: Previous versions of edited content
+-- Created: 2024-07-08
+-- Version: 1.0.25
+
+DROP TABLE IF EXISTS content_versions;
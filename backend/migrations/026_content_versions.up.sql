@@ -0,0 +1,26 @@
+-- EchoLayer Database Schema Migration 026
+-- Description: Previous versions of edited content
+-- Created: 2024-07-08
+-- Version: 1.0.25
+
+-- `content` only holds the latest version. Each edit first copies the version it
+-- replaces here, numbered from 1; the live content is the version after the last one.
+CREATE TABLE content_versions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    version_number INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    -- Who made the edit that replaced this version, and when
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    change_summary TEXT NOT NULL,
+    -- Echo Index of the content when it was edited
+    echo_index DOUBLE PRECISION NOT NULL,
+    -- Set when the Echo Index fell sharply soon after the edit
+    flagged_at TIMESTAMP WITH TIME ZONE,
+    UNIQUE (content_id, version_number)
+);
+
+CREATE INDEX idx_content_versions_updated_at ON content_versions(updated_at);
+CREATE INDEX idx_content_versions_flagged_at ON content_versions(flagged_at DESC) WHERE flagged_at IS NOT NULL;
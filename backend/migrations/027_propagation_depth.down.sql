@@ -0,0 +1,10 @@
+-- EchoLayer Database Schema Migration 027 (revert)
+-- Description: Depth of each propagation in its content's share chain
+-- Created: 2024-07-15
+-- Version: 1.0.26
+
+DROP TRIGGER IF EXISTS set_propagations_depth ON propagations;
+DROP FUNCTION IF EXISTS set_propagation_depth();
+DROP INDEX IF EXISTS idx_propagations_content_id_depth;
+DROP INDEX IF EXISTS idx_propagations_content_id_target_user_id;
+ALTER TABLE propagations DROP COLUMN IF EXISTS depth;
@@ -0,0 +1,52 @@
+-- EchoLayer Database Schema Migration 027
+-- Description: Depth of each propagation in its content's share chain
+-- Created: 2024-07-15
+-- Version: 1.0.26
+
+-- 1 for a direct share of the content, 2 for a share of that share, and so on
+ALTER TABLE propagations ADD COLUMN depth INTEGER NOT NULL DEFAULT 1 CHECK (depth >= 1);
+
+-- A propagation is one deeper than the one that first brought the content to its
+-- sharer. Shares by the author, or by users the content never reached through a
+-- recorded propagation, are direct.
+CREATE OR REPLACE FUNCTION set_propagation_depth()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.depth = COALESCE((
+        SELECT depth + 1 FROM propagations
+        WHERE content_id = NEW.content_id AND target_user_id = NEW.source_user_id
+        ORDER BY created_at, id
+        LIMIT 1
+    ), 1);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER set_propagations_depth BEFORE INSERT ON propagations
+    FOR EACH ROW EXECUTE FUNCTION set_propagation_depth();
+
+CREATE INDEX idx_propagations_content_id_target_user_id ON propagations(content_id, target_user_id);
+CREATE INDEX idx_propagations_content_id_depth ON propagations(content_id, depth);
+
+-- Existing propagations, by the same rule; ties on created_at are broken by id so
+-- that no propagation can be its own ancestor
+WITH RECURSIVE parents AS (
+    SELECT p.id, (
+        SELECT q.id FROM propagations q
+        WHERE q.content_id = p.content_id AND q.target_user_id = p.source_user_id
+          AND (q.created_at, q.id) < (p.created_at, p.id)
+        ORDER BY q.created_at, q.id
+        LIMIT 1
+    ) AS parent_id
+    FROM propagations p
+),
+depths AS (
+    SELECT id, 1 AS depth FROM parents WHERE parent_id IS NULL
+    UNION ALL
+    SELECT child.id, depths.depth + 1
+    FROM parents child
+    JOIN depths ON child.parent_id = depths.id
+)
+UPDATE propagations p SET depth = depths.depth
+FROM depths
+WHERE p.id = depths.id;
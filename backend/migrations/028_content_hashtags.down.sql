@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 028 (revert)
+-- Description: Hashtags of content, for trend detection
+-- Created: 2024-07-22
+-- Version: 1.0.27
+
+DROP TABLE IF EXISTS content_hashtags;
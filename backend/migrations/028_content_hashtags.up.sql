@@ -0,0 +1,22 @@
+-- EchoLayer Database Schema Migration 028
+-- Description: Hashtags of content, for trend detection
+-- Created: 2024-07-22
+-- Version: 1.0.27
+
+-- Hashtags in the body of each content item, lowercased. `created_at` is when the
+-- hashtag first appeared on the content, so edits do not make old hashtags look new.
+CREATE TABLE content_hashtags (
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    hashtag TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (content_id, hashtag)
+);
+
+CREATE INDEX idx_content_hashtags_hashtag ON content_hashtags(hashtag);
+CREATE INDEX idx_content_hashtags_created_at ON content_hashtags(created_at);
+
+-- Hashtags of existing content, dated by the content
+INSERT INTO content_hashtags (content_id, hashtag, created_at)
+SELECT DISTINCT c.id, LOWER(m[2]), c.created_at
+FROM content c, regexp_matches(COALESCE(c.body, ''), '(^|\s)#(\w*[[:alpha:]]\w*)', 'g') AS m
+WHERE c.deleted_at IS NULL;
@@ -0,0 +1,7 @@
+-- EchoLayer Database Schema Migration 029 (revert)
+-- Description: Users mentioned in content
+-- Created: 2024-07-29
+-- Version: 1.0.28
+
+DROP INDEX IF EXISTS idx_social_platforms_platform_username;
+DROP TABLE IF EXISTS content_mentions;
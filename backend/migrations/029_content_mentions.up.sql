@@ -0,0 +1,19 @@
+-- EchoLayer Database Schema Migration 029
+-- Description: Users mentioned in content
+-- Created: 2024-07-29
+-- Version: 1.0.28
+
+-- Mentions in the body of each content item that resolved to a linked social account.
+-- `mention` is the handle as written, without the `@`.
+CREATE TABLE content_mentions (
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    mentioned_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    mention TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (content_id, mentioned_user_id)
+);
+
+CREATE INDEX idx_content_mentions_mentioned_user_id ON content_mentions(mentioned_user_id);
+
+-- Handles are matched case-insensitively
+CREATE INDEX idx_social_platforms_platform_username ON social_platforms(platform, LOWER(platform_username));
@@ -0,0 +1,17 @@
+-- EchoLayer Database Schema Migration 030 (revert)
+-- Description: Echo Index history entries for scheduled temporal decay
+-- Created: 2024-08-05
+-- Version: 1.0.29
+
+-- Enum values cannot be dropped, so the type is rebuilt without 'decay'
+DELETE FROM echo_index_history WHERE trigger = 'decay';
+ALTER TYPE echo_index_trigger RENAME TO echo_index_trigger_old;
+CREATE TYPE echo_index_trigger AS ENUM (
+    'initial',
+    'propagation_added',
+    'recalculation',
+    'scheduled'
+);
+ALTER TABLE echo_index_history
+    ALTER COLUMN trigger TYPE echo_index_trigger USING trigger::text::echo_index_trigger;
+DROP TYPE echo_index_trigger_old;
@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 030
+-- Description: Echo Index history entries for scheduled temporal decay
+-- Created: 2024-08-05
+-- Version: 1.0.29
+
+ALTER TYPE echo_index_trigger ADD VALUE IF NOT EXISTS 'decay';
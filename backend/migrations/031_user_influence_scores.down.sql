@@ -0,0 +1,7 @@
+-- EchoLayer Database Schema Migration 031 (revert)
+-- Description: Daily user influence scores for the propagation network
+-- Created: 2024-08-12
+-- Version: 1.0.30
+
+DROP INDEX IF EXISTS idx_propagations_source_user_created_at;
+DROP TABLE IF EXISTS user_influence_scores;
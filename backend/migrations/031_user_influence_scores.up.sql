@@ -0,0 +1,22 @@
+-- EchoLayer Database Schema Migration 031
+-- Description: Daily user influence scores for the propagation network
+-- Created: 2024-08-12
+-- Version: 1.0.30
+
+-- One row per user per daily calculation; the latest row is the user's current influence.
+-- Every score is in [0, 1], computed over the `window_days` before `calculated_at`.
+CREATE TABLE user_influence_scores (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reach_score DOUBLE PRECISION NOT NULL,
+    echo_amplification DOUBLE PRECISION NOT NULL,
+    network_centrality DOUBLE PRECISION NOT NULL,
+    composite DOUBLE PRECISION NOT NULL,
+    window_days INTEGER NOT NULL,
+    calculated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_user_influence_scores_user_id ON user_influence_scores(user_id, calculated_at DESC);
+
+-- Outgoing propagations of a user within a window
+CREATE INDEX idx_propagations_source_user_created_at ON propagations(source_user_id, created_at);
@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 032 (revert)
+-- Description: Users following other users within EchoLayer
+-- Created: 2024-08-19
+-- Version: 1.0.31
+
+DROP TABLE IF EXISTS user_relationships;
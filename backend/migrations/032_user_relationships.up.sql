@@ -0,0 +1,16 @@
+-- EchoLayer Database Schema Migration 032
+-- Description: Users following other users within EchoLayer
+-- Created: 2024-08-19
+-- Version: 1.0.31
+
+-- One row per follow; unfollowing deletes it
+CREATE TABLE user_relationships (
+    follower_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    followed_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (follower_id <> followed_id)
+);
+
+CREATE UNIQUE INDEX idx_user_relationships_follower_followed ON user_relationships(follower_id, followed_id);
+-- Followers of a user, newest first
+CREATE INDEX idx_user_relationships_followed_created_at ON user_relationships(followed_id, created_at DESC);
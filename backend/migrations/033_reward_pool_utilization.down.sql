@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 033 (revert)
+-- Description: Daily reward pool utilization and the pool size adjustments made from it
+-- Created: 2024-08-26
+-- Version: 1.0.32
+
+DROP TABLE IF EXISTS reward_pool_utilization;
@@ -0,0 +1,14 @@
+-- EchoLayer Database Schema Migration 033
+-- Description: Daily reward pool utilization and the pool size adjustments made from it
+-- Created: 2024-08-26
+-- Version: 1.0.32
+
+-- One row per closed UTC day: the pool that day, how much of it was awarded, and the
+-- fractional change applied to the next day's pool (0.10 for +10%, 0 when unchanged).
+CREATE TABLE reward_pool_utilization (
+    day DATE PRIMARY KEY,
+    pool_size DOUBLE PRECISION NOT NULL CHECK (pool_size >= 0),
+    utilized DOUBLE PRECISION NOT NULL CHECK (utilized >= 0),
+    adjustment DOUBLE PRECISION NOT NULL DEFAULT 0,
+    recorded_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
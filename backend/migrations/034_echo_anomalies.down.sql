@@ -0,0 +1,7 @@
+-- EchoLayer Database Schema Migration 034 (revert)
+-- Description: Statistically unusual Echo Index jumps held for operator review
+-- Created: 2024-09-02
+-- Version: 1.0.33
+
+DROP TABLE IF EXISTS echo_anomalies;
+DROP TYPE IF EXISTS anomaly_status;
@@ -0,0 +1,28 @@
+-- EchoLayer Database Schema Migration 034
+-- Description: Statistically unusual Echo Index jumps held for operator review
+-- Created: 2024-09-02
+-- Version: 1.0.33
+
+CREATE TYPE anomaly_status AS ENUM (
+    'open',
+    'dismissed',
+    'confirmed'
+);
+
+CREATE TABLE echo_anomalies (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    -- Scores on the 0-100 scale, before and after the jump
+    previous_score DOUBLE PRECISION NOT NULL,
+    new_score DOUBLE PRECISION NOT NULL,
+    time_delta_seconds BIGINT NOT NULL CHECK (time_delta_seconds >= 0),
+    -- The AnomalyFlag values the jump raised
+    flags JSONB NOT NULL,
+    status anomaly_status NOT NULL DEFAULT 'open',
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMP WITH TIME ZONE,
+    detected_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_echo_anomalies_detected_at ON echo_anomalies(detected_at DESC);
+CREATE INDEX idx_echo_anomalies_content_id_status ON echo_anomalies(content_id, status);
@@ -0,0 +1,7 @@
+-- EchoLayer Database Schema Migration 035 (revert)
+-- Description: Links between content items that are the same underlying content on different platforms
+-- Created: 2024-09-09
+-- Version: 1.0.34
+
+DROP TABLE IF EXISTS content_attributions;
+DROP TYPE IF EXISTS attribution_type;
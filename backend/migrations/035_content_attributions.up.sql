@@ -0,0 +1,24 @@
+-- EchoLayer Database Schema Migration 035
+-- Description: Links between content items that are the same underlying content on different platforms
+-- Created: 2024-09-09
+-- Version: 1.0.34
+
+CREATE TYPE attribution_type AS ENUM (
+    'cross_post',
+    'quote',
+    'repost',
+    'translation'
+);
+
+-- Each secondary is attributed to one primary, which is never itself a secondary, so Echo
+-- Index contributions flow a single step and are counted once
+CREATE TABLE content_attributions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    primary_content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    secondary_content_id UUID NOT NULL UNIQUE REFERENCES content(id) ON DELETE CASCADE,
+    link_type attribution_type NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (primary_content_id <> secondary_content_id)
+);
+
+CREATE INDEX idx_content_attributions_primary_content_id ON content_attributions(primary_content_id);
@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 036 (revert)
+-- Description: Mean Echo Index of recent content per platform, the baseline of cold-start scores
+-- Created: 2024-09-16
+-- Version: 1.0.35
+
+DROP TABLE IF EXISTS platform_baselines;
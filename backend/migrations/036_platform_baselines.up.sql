@@ -0,0 +1,12 @@
+-- EchoLayer Database Schema Migration 036
+-- Description: Mean Echo Index of recent content per platform, the baseline of cold-start scores
+-- Created: 2024-09-16
+-- Version: 1.0.35
+
+CREATE TABLE platform_baselines (
+    platform platform_type PRIMARY KEY,
+    -- Mean Echo Index of the platform's scored content created in the last 30 days
+    mean_echo_index DOUBLE PRECISION NOT NULL,
+    content_count BIGINT NOT NULL,
+    refreshed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,9 @@
+-- EchoLayer Database Schema Migration 037 (revert)
+-- Description: TOTP two-factor authentication, recovery codes and 2FA-verified sessions
+-- Created: 2024-09-23
+-- Version: 1.0.36
+
+ALTER TABLE refresh_tokens DROP COLUMN IF EXISTS totp_verified;
+DROP TABLE IF EXISTS totp_recovery_codes;
+ALTER TABLE users DROP COLUMN IF EXISTS totp_enabled;
+ALTER TABLE users DROP COLUMN IF EXISTS totp_secret;
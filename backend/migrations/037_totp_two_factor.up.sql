@@ -0,0 +1,22 @@
+-- EchoLayer Database Schema Migration 037
+-- Description: TOTP two-factor authentication, recovery codes and 2FA-verified sessions
+-- Created: 2024-09-23
+-- Version: 1.0.36
+
+-- AES-256-GCM encrypted base32 secret; set on setup, in use once 2FA is enabled
+ALTER TABLE users ADD COLUMN totp_secret TEXT;
+ALTER TABLE users ADD COLUMN totp_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE totp_recovery_codes (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- SHA-256 of the code; the code itself is only shown to the user once
+    code_hash CHAR(64) NOT NULL,
+    used_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_totp_recovery_codes_user_id ON totp_recovery_codes(user_id);
+
+-- Access tokens refreshed within a session keep the second factor it was signed in with
+ALTER TABLE refresh_tokens ADD COLUMN totp_verified BOOLEAN NOT NULL DEFAULT FALSE;
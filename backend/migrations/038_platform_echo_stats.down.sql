@@ -0,0 +1,15 @@
+-- EchoLayer Database Schema Migration 038 (revert)
+-- Description: Platform baselines become platform Echo Index statistics, for comparing platforms
+-- Created: 2024-09-30
+-- Version: 1.0.37
+
+ALTER TABLE platform_echo_stats
+    DROP COLUMN IF EXISTS median_echo_index,
+    DROP COLUMN IF EXISTS top_10_pct_threshold,
+    DROP COLUMN IF EXISTS total_propagations,
+    DROP COLUMN IF EXISTS avg_propagation_depth;
+
+ALTER TABLE platform_echo_stats RENAME COLUMN updated_at TO refreshed_at;
+ALTER TABLE platform_echo_stats RENAME COLUMN avg_echo_index TO mean_echo_index;
+ALTER INDEX platform_echo_stats_pkey RENAME TO platform_baselines_pkey;
+ALTER TABLE platform_echo_stats RENAME TO platform_baselines;
@@ -0,0 +1,16 @@
+-- EchoLayer Database Schema Migration 038
+-- Description: Platform baselines become platform Echo Index statistics, for comparing platforms
+-- Created: 2024-09-30
+-- Version: 1.0.37
+
+ALTER TABLE platform_baselines RENAME TO platform_echo_stats;
+ALTER INDEX platform_baselines_pkey RENAME TO platform_echo_stats_pkey;
+ALTER TABLE platform_echo_stats RENAME COLUMN mean_echo_index TO avg_echo_index;
+ALTER TABLE platform_echo_stats RENAME COLUMN refreshed_at TO updated_at;
+
+-- All of the platform's scored content created in the last 30 days, and its propagations
+ALTER TABLE platform_echo_stats
+    ADD COLUMN median_echo_index DOUBLE PRECISION NOT NULL DEFAULT 0,
+    ADD COLUMN top_10_pct_threshold DOUBLE PRECISION NOT NULL DEFAULT 0,
+    ADD COLUMN total_propagations BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN avg_propagation_depth DOUBLE PRECISION NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 039 (revert)
+-- Description: When each user last imported historical content, to limit imports to one a day
+-- Created: 2024-10-07
+-- Version: 1.0.38
+
+DROP TABLE IF EXISTS content_imports;
@@ -0,0 +1,9 @@
+-- EchoLayer Database Schema Migration 039
+-- Description: When each user last imported historical content, to limit imports to one a day
+-- Created: 2024-10-07
+-- Version: 1.0.38
+
+CREATE TABLE content_imports (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    imported_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
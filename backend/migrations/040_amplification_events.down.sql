@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 040 (revert)
+-- Description: Audit log of Echo Loop resonance amplifications, which operators can revert
+-- Created: 2024-10-14
+-- Version: 1.0.39
+
+DROP TABLE IF EXISTS amplification_events;
@@ -0,0 +1,21 @@
+-- EchoLayer Database Schema Migration 040
+-- Description: Audit log of Echo Loop resonance amplifications, which operators can revert
+-- Created: 2024-10-14
+-- Version: 1.0.39
+
+-- Kept after their loop expires, so no foreign key to echo_loops
+CREATE TABLE amplification_events (
+    id UUID PRIMARY KEY,
+    loop_id VARCHAR(64) NOT NULL,
+    content_id VARCHAR(255) NOT NULL,
+    trigger_resonance DOUBLE PRECISION NOT NULL,
+    amplification_factor DOUBLE PRECISION NOT NULL,
+    affected_path_count INTEGER NOT NULL,
+    total_weight_before DOUBLE PRECISION NOT NULL,
+    total_weight_after DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    reverted_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_amplification_events_content_id ON amplification_events(content_id, created_at DESC);
+CREATE INDEX idx_amplification_events_created_at ON amplification_events(created_at DESC);
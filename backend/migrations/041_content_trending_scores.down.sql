@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 041 (revert)
+-- Description: Short-term virality of content, refreshed hourly from its Echo Index history
+-- Created: 2024-10-21
+-- Version: 1.0.40
+
+DROP TABLE IF EXISTS content_trending_scores;
@@ -0,0 +1,16 @@
+-- EchoLayer Database Schema Migration 041
+-- Description: Short-term virality of content, refreshed hourly from its Echo Index history
+-- Created: 2024-10-21
+-- Version: 1.0.40
+
+CREATE TABLE content_trending_scores (
+    content_id UUID PRIMARY KEY REFERENCES content(id) ON DELETE CASCADE,
+    score DOUBLE PRECISION NOT NULL,
+    velocity DOUBLE PRECISION NOT NULL,
+    acceleration DOUBLE PRECISION NOT NULL,
+    peak_predicted_at TIMESTAMP WITH TIME ZONE,
+    calculated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_content_trending_scores_velocity ON content_trending_scores(velocity DESC);
+CREATE INDEX idx_content_trending_scores_score ON content_trending_scores(score DESC);
@@ -0,0 +1,29 @@
+-- EchoLayer Database Schema Migration 042
+-- Description: Signed-in sessions users can list and revoke individually
+-- Created: 2024-10-28
+-- Version: 1.0.41
+
+-- Nothing was ever written to the original table, so it is rebuilt rather than altered
+DROP TABLE IF EXISTS user_sessions;
+
+-- One row per login, kept until the session's refresh token expires or it is revoked
+CREATE TABLE user_sessions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- JWT ID of the latest access token issued to the session
+    jti VARCHAR(64) NOT NULL,
+    device_fingerprint VARCHAR(128) NOT NULL,
+    ip_address INET NOT NULL,
+    user_agent TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_active_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX idx_user_sessions_user_id ON user_sessions(user_id, expires_at);
+
+ALTER TABLE user_sessions ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY user_sessions_policy ON user_sessions
+    FOR ALL
+    USING (user_id = current_setting('app.current_user_id')::UUID);
@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 043 (revert)
+-- Description: Reactions on content by type, weighted by the quality of engagement they signal
+-- Created: 2024-11-04
+-- Version: 1.0.42
+
+ALTER TABLE content DROP COLUMN IF EXISTS reactions;
@@ -0,0 +1,7 @@
+-- EchoLayer Database Schema Migration 043
+-- Description: Reactions on content by type, weighted by the quality of engagement they signal
+-- Created: 2024-11-04
+-- Version: 1.0.42
+
+-- Counts keyed by reaction type, e.g. {"like": 120, "quote_comment": 4}
+ALTER TABLE content ADD COLUMN reactions JSONB NOT NULL DEFAULT '{}';
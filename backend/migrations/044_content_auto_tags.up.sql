@@ -0,0 +1,6 @@
+-- EchoLayer Database Schema Migration 044
+-- Description: Topic tags extracted from content text, kept apart from the tags its author chose
+-- Created: 2024-11-11
+-- Version: 1.0.43
+
+ALTER TABLE content ADD COLUMN auto_tags TEXT[] NOT NULL DEFAULT '{}';
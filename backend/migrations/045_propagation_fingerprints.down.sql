@@ -0,0 +1,25 @@
+-- EchoLayer Database Schema Migration 045 (revert)
+-- Description: Deduplicate propagation events within a window by fingerprint
+-- Created: 2024-11-18
+-- Version: 1.0.44
+
+-- Keep the earliest copy of any propagation stored again after the window
+DELETE FROM propagations p
+USING propagations earlier
+WHERE p.content_id = earlier.content_id
+  AND p.propagation_type = earlier.propagation_type
+  AND COALESCE(p.source_external_id, '') = COALESCE(earlier.source_external_id, '')
+  AND COALESCE(p.target_external_id, '') = COALESCE(earlier.target_external_id, '')
+  AND (p.created_at, p.id) > (earlier.created_at, earlier.id);
+
+CREATE UNIQUE INDEX idx_propagations_dedup ON propagations (
+    content_id,
+    COALESCE(source_external_id, ''),
+    COALESCE(target_external_id, ''),
+    propagation_type
+);
+
+ALTER TABLE propagations DROP COLUMN IF EXISTS duplicate_of;
+ALTER TABLE propagations DROP COLUMN IF EXISTS fingerprint_checked;
+
+DROP TABLE IF EXISTS propagation_fingerprints;
@@ -0,0 +1,25 @@
+-- EchoLayer Database Schema Migration 045
+-- Description: Deduplicate propagation events within a window by fingerprint
+-- Created: 2024-11-18
+-- Version: 1.0.44
+
+-- Latest propagation stored for each fingerprint, the SHA-256 of its content ID,
+-- external IDs and type. An event whose fingerprint was stored within the
+-- deduplication window is a duplicate of that propagation. Fingerprints are claimed
+-- before their propagation is stored, hence the deferred foreign key.
+CREATE TABLE propagation_fingerprints (
+    fingerprint BYTEA PRIMARY KEY,
+    propagation_id UUID NOT NULL REFERENCES propagations(id) ON DELETE CASCADE DEFERRABLE INITIALLY DEFERRED,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_propagation_fingerprints_created_at ON propagation_fingerprints(created_at);
+
+-- Whether a propagation was checked against the fingerprints, and the propagation it
+-- repeats from before the window, if any
+ALTER TABLE propagations ADD COLUMN fingerprint_checked BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE propagations ADD COLUMN duplicate_of UUID REFERENCES propagations(id) ON DELETE SET NULL;
+
+-- The window replaces deduplicating forever, so events repeated once it has passed are
+-- stored again
+DROP INDEX IF EXISTS idx_propagations_dedup;
@@ -0,0 +1,8 @@
+-- EchoLayer Database Schema Migration 046 (revert)
+-- Description: Remove user tiers
+-- Created: 2024-11-25
+-- Version: 1.0.45
+
+ALTER TABLE users DROP COLUMN IF EXISTS tier;
+
+DROP TYPE IF EXISTS user_tier;
@@ -0,0 +1,9 @@
+-- EchoLayer Database Schema Migration 046
+-- Description: User tiers earned from Echo Score and rewards
+-- Created: 2024-11-25
+-- Version: 1.0.45
+
+-- Declared in ascending order, so tiers compare by rank
+CREATE TYPE user_tier AS ENUM ('basic', 'bronze', 'silver', 'gold');
+
+ALTER TABLE users ADD COLUMN tier user_tier NOT NULL DEFAULT 'basic';
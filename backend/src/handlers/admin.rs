@@ -0,0 +1,1188 @@
+use actix_web::{delete, get, post, put, web, HttpResponse, Result};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::RwLock;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::handlers::auth::Claims;
+use crate::handlers::errors::ApiError;
+use crate::middleware::RequireRole;
+use crate::models::content::{ContentCategory, EchoIndexWeights};
+use crate::models::echo_anomaly::EchoAnomaly;
+use crate::models::moderation::ModerationDecision;
+use crate::models::reward_analytics::RewardAnalyticsQuery;
+use crate::models::user::Role;
+use crate::repositories::{
+    ContentRepository, ExperimentRepository, NewExperiment, PropagationRepository, RepositoryError, UserRepository,
+};
+use crate::services::content_clusters::{MAX_CLUSTERED_CONTENT, MAX_CLUSTERS};
+use crate::services::moderation::DEFAULT_QUEUE_LIMIT;
+use crate::services::quality_bonus::quality_metrics;
+use crate::services::{
+    ContentClusterAnalyzer, ContentModerationService, ContentVersioningService, EchoEngineConfig,
+    EchoIndexAnomalyDetector, EchoIndexCache, EngineConfigStore, PoolUtilizationGovernor, PropagationService,
+    QualityBonusScheduler, RewardAnalyticsService, RewardService, TimeWindow,
+};
+
+/// List rewards held for review after suspicious activity
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "list_on_hold_rewards",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Each held `reward` with the `suspicion` it was held for", body = [Object]),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/rewards/on-hold")]
+pub async fn list_on_hold_rewards(
+    reward_service: web::Data<RwLock<RewardService>>,
+) -> Result<HttpResponse> {
+    let held: Vec<_> = reward_service.read()
+        .await
+        .get_on_hold_rewards()
+        .into_iter()
+        .map(|(reward, report)| json!({
+            "reward": reward,
+            "suspicion": report,
+        }))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": held,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Approve a held reward so it is released with the user's next payout
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "approve_held_reward",
+    tag = "admin",
+    params(("reward_id" = String, Path, description = "Reward ID")),
+    responses(
+        (status = 200, description = "Reward approved", body = Object),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No such held reward"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/rewards/{reward_id}/approve")]
+pub async fn approve_held_reward(
+    path: web::Path<String>,
+    reward_service: web::Data<RwLock<RewardService>>,
+) -> Result<HttpResponse> {
+    let reward_id = path.into_inner();
+    let result = reward_service.write()
+        .await
+        .approve_held_reward(&reward_id);
+
+    match result {
+        Ok(()) => {
+            log::info!("Approved held reward {}", reward_id);
+            Ok(HttpResponse::Ok().json(json!({
+                "success": true,
+                "data": { "reward_id": reward_id, "status": "approved" },
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(e) => Err(ApiError::NotFound(e).into()),
+    }
+}
+
+/// Reject a held reward and return its amount to the daily pool
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "reject_held_reward",
+    tag = "admin",
+    params(("reward_id" = String, Path, description = "Reward ID")),
+    responses(
+        (status = 200, description = "Reward rejected", body = Object),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No such held reward"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/rewards/{reward_id}/reject")]
+pub async fn reject_held_reward(
+    path: web::Path<String>,
+    reward_service: web::Data<RwLock<RewardService>>,
+) -> Result<HttpResponse> {
+    let reward_id = path.into_inner();
+    let result = reward_service.write()
+        .await
+        .reject_held_reward(&reward_id);
+
+    match result {
+        Ok(reward) => {
+            log::info!("Rejected held reward {} for {}", reward_id, reward.user_id);
+            Ok(HttpResponse::Ok().json(json!({
+                "success": true,
+                "data": { "reward_id": reward_id, "status": "rejected" },
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(e) => Err(ApiError::NotFound(e).into()),
+    }
+}
+
+/// Live and archived content counts and the storage archiving has saved
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "get_archive_stats",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Content counts and bytes saved by compression", body = Object),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/content/archive-stats")]
+pub async fn get_archive_stats(
+    repository: web::Data<ContentRepository>,
+) -> Result<HttpResponse> {
+    let stats = repository.archive_stats().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to load archive stats");
+        ApiError::internal()
+    })?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": {
+            "live_content": stats.live_content,
+            "archived_content": stats.archived_content,
+            "uncompressed_bytes": stats.uncompressed_bytes,
+            "compressed_bytes": stats.compressed_bytes,
+            "saved_bytes": stats.saved_bytes(),
+            "compression_ratio": stats.compression_ratio(),
+        },
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DecayConfigRequest {
+    pub decay_factor: Option<f64>,
+    /// Use the category's decay factor instead; `other` restores the default
+    pub category: Option<ContentCategory>,
+}
+
+/// Decay the propagation resonance of one content item faster or slower than the default,
+/// e.g. 0.7 a day for breaking news or 0.98 for analysis, given directly or by category
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "set_content_decay_config",
+    tag = "admin",
+    params(("content_id" = String, Path, description = "Content ID")),
+    request_body = DecayConfigRequest,
+    responses(
+        (status = 200, description = "The content's decay factor", body = Object),
+        (status = 400, description = "Decay factor out of range, or not exactly one of decay_factor and category"),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/content/{content_id}/decay-config")]
+pub async fn set_content_decay_config(
+    path: web::Path<String>,
+    request: web::Json<DecayConfigRequest>,
+    propagation: web::Data<PropagationService>,
+) -> Result<HttpResponse> {
+    let content_id = path.into_inner();
+    let factor = match (request.decay_factor, request.category) {
+        (Some(factor), None) => Some(factor),
+        (None, Some(category)) => category.decay_factor(),
+        _ => return Err(ApiError::bad_request("Exactly one of decay_factor and category is required").into()),
+    };
+    match factor {
+        Some(factor) => {
+            if let Err(e) = propagation.set_decay_factor_for_content(&content_id, factor) {
+                return Err(ApiError::bad_request(e).into());
+            }
+        }
+        None => propagation.reset_decay_factor_for_content(&content_id),
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": {
+            "content_id": content_id,
+            "decay_factor": propagation.decay_factor_for(&content_id),
+        },
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PoolUtilizationQuery {
+    pub days: Option<u32>,
+}
+
+/// The reward pool of each recent day, how much of it was awarded and how the next day's
+/// pool was resized from it, most recent first
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "get_pool_utilization_history",
+    tag = "admin",
+    params(PoolUtilizationQuery),
+    responses(
+        (status = 200, description = "Pool size, use and resizing of each day, most recent first", body = [Object]),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/pool/utilization-history")]
+pub async fn get_pool_utilization_history(
+    query: web::Query<PoolUtilizationQuery>,
+    governor: web::Data<PoolUtilizationGovernor>,
+) -> Result<HttpResponse> {
+    let days = query.days.unwrap_or(30).clamp(1, 365);
+    let history = governor.history(days).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to load reward pool utilization");
+        ApiError::internal()
+    })?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": history,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Rewards distributed to all users over time, in hourly, daily or weekly buckets
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "get_reward_analytics",
+    tag = "admin",
+    params(RewardAnalyticsQuery),
+    responses(
+        (status = 200, description = "Rewards distributed per bucket", body = Object),
+        (status = 400, description = "Invalid range"),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/analytics/rewards")]
+pub async fn get_reward_analytics(
+    query: web::Query<RewardAnalyticsQuery>,
+    analytics: web::Data<RewardAnalyticsService>,
+) -> Result<HttpResponse> {
+    let (since, until) = match query.range(chrono::Utc::now()) {
+        Ok(range) => range,
+        Err(message) => return Err(ApiError::bad_request(message).into()),
+    };
+    match analytics.time_series(None, query.granularity, since, until).await {
+        Ok(buckets) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": buckets,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(RepositoryError::InvalidInput(message)) => Err(ApiError::bad_request(message).into()),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to load reward analytics");
+            Err(ApiError::internal().into())
+        }
+    }
+}
+
+/// Hits, misses and size of the Echo Index calculation cache since startup
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "get_cache_stats",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Hits, misses and size of the cache", body = Object),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/cache/stats")]
+pub async fn get_cache_stats(cache: web::Data<EchoIndexCache>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": cache.stats(),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ClusterQuery {
+    /// Chosen by silhouette score when omitted
+    pub k: Option<usize>,
+    /// Comma-separated IDs of the content to cluster instead of the most recent content
+    pub content_ids: Option<String>,
+}
+
+/// Topic clusters of the most recent content, or of the given content
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "get_content_clusters",
+    tag = "admin",
+    params(ClusterQuery),
+    responses(
+        (status = 200, description = "Topic clusters of the content", body = [ContentCluster]),
+        (status = 400, description = "k out of range, or malformed or too many content IDs"),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/analytics/clusters")]
+pub async fn get_content_clusters(
+    query: web::Query<ClusterQuery>,
+    clusters: web::Data<ContentClusterAnalyzer>,
+) -> Result<HttpResponse> {
+    if query.k.is_some_and(|k| !(1..=MAX_CLUSTERS).contains(&k)) {
+        return Err(ApiError::bad_request(format!("k must be between 1 and {}", MAX_CLUSTERS)).into());
+    }
+
+    let content_ids = match &query.content_ids {
+        Some(ids) => {
+            let Ok(ids) = ids.split(',').map(|id| Uuid::parse_str(id.trim())).collect::<Result<Vec<_>, _>>() else {
+                return Err(ApiError::bad_request("content_ids must be comma-separated UUIDs").into());
+            };
+            if ids.len() as i64 > MAX_CLUSTERED_CONTENT {
+                let message = format!("At most {} content items can be clustered", MAX_CLUSTERED_CONTENT);
+                return Err(ApiError::bad_request(message).into());
+            }
+            Some(ids)
+        }
+        None => None,
+    };
+
+    let clusters = match content_ids {
+        Some(ids) => clusters.cluster(&ids, query.k).await,
+        None => clusters.clusters(query.k).await,
+    };
+    let clusters = clusters.map_err(|e| {
+        tracing::error!(error = %e, "Failed to cluster content");
+        ApiError::internal()
+    })?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": clusters,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PendingQualityBonusQuery {
+    pub limit: Option<i64>,
+}
+
+/// Content that qualifies for a retroactive quality bonus but has not been reviewed
+/// yet, with the metrics and bonus the next review would use
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "list_pending_quality_bonuses",
+    tag = "admin",
+    params(PendingQualityBonusQuery),
+    responses(
+        (status = 200, description = "Unreviewed content with its quality metrics and bonus", body = [Object]),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/quality-bonuses/pending")]
+pub async fn list_pending_quality_bonuses(
+    query: web::Query<PendingQualityBonusQuery>,
+    scheduler: web::Data<QualityBonusScheduler>,
+) -> Result<HttpResponse> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let pending = scheduler.pending(limit).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to load pending quality bonuses");
+        ApiError::internal()
+    })?;
+
+    let data: Vec<_> = pending
+        .into_iter()
+        .map(|candidate| {
+            let metrics = quality_metrics(&candidate);
+            json!({
+                "estimated_bonus": metrics.bonus_amount(),
+                "quality_metrics": metrics,
+                "content": candidate,
+            })
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": data,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ModerationQueueQuery {
+    pub limit: Option<i64>,
+}
+
+/// Unresolved content flags, those on content already held for review first
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "get_moderation_queue",
+    tag = "admin",
+    params(ModerationQueueQuery),
+    responses(
+        (status = 200, description = "Unresolved flags", body = [Object]),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/moderation/queue")]
+pub async fn get_moderation_queue(
+    query: web::Query<ModerationQueueQuery>,
+    moderation: web::Data<ContentModerationService>,
+) -> Result<HttpResponse> {
+    let limit = query.limit.unwrap_or(DEFAULT_QUEUE_LIMIT).clamp(1, 200);
+    let queue = moderation.queue(limit).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to load the moderation queue");
+        ApiError::internal()
+    })?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": queue,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Content edits the Echo Index fell sharply after, most recently flagged first. Each
+/// entry is the version the edit replaced.
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "get_flagged_edits",
+    tag = "admin",
+    params(ModerationQueueQuery),
+    responses(
+        (status = 200, description = "Versions replaced by edits the Echo Index fell after", body = [ContentVersion]),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/moderation/edits")]
+pub async fn get_flagged_edits(
+    query: web::Query<ModerationQueueQuery>,
+    versioning: web::Data<ContentVersioningService>,
+) -> Result<HttpResponse> {
+    let limit = query.limit.unwrap_or(DEFAULT_QUEUE_LIMIT).clamp(1, 200);
+    let edits = versioning.flagged_edits(limit).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to load flagged edits");
+        ApiError::internal()
+    })?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": edits,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReviewFlagRequest {
+    pub decision: ModerationDecision,
+}
+
+/// Approve flagged content, clearing its flags, or reject it, removing the content
+/// and freezing its rewards
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "review_flag",
+    tag = "admin",
+    params(("flag_id" = Uuid, Path, description = "Flag ID")),
+    request_body = ReviewFlagRequest,
+    responses(
+        (status = 200, description = "The reviewed flag", body = ModerationFlag),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No such flag"),
+        (status = 409, description = "Flag already reviewed"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[put("/moderation/{flag_id}")]
+pub async fn review_flag(
+    path: web::Path<Uuid>,
+    request: web::Json<ReviewFlagRequest>,
+    claims: web::ReqData<Claims>,
+    moderation: web::Data<ContentModerationService>,
+) -> Result<HttpResponse> {
+    let flag_id = path.into_inner();
+    let Ok(reviewed_by) = Uuid::parse_str(&claims.sub) else {
+        return Err(ApiError::bad_request("Only users can review flags").into());
+    };
+
+    match moderation.review(flag_id, reviewed_by, request.decision).await {
+        Ok(flag) => {
+            log::info!("{} reviewed flag {}: {:?}", reviewed_by, flag_id, request.decision);
+            Ok(HttpResponse::Ok().json(json!({
+                "success": true,
+                "data": flag,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(RepositoryError::NotFound) => Err(ApiError::NotFound("Flag not found".to_string()).into()),
+        Err(RepositoryError::Conflict(message)) => Err(ApiError::StateConflict(message).into()),
+        Err(e) => {
+            tracing::error!(%flag_id, error = %e, "Failed to review flag");
+            Err(ApiError::internal().into())
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AnomalyQuery {
+    /// `24h`, `7d`, `30d` or `all`; `24h` when omitted
+    pub since: Option<String>,
+}
+
+/// Echo Index jumps flagged as anomalous within a period, most recent first
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "list_anomalies",
+    tag = "admin",
+    params(AnomalyQuery),
+    responses(
+        (status = 200, description = "Anomalous jumps, most recent first", body = [Object]),
+        (status = 400, description = "Invalid period"),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/anomalies")]
+pub async fn list_anomalies(
+    query: web::Query<AnomalyQuery>,
+    anomalies: web::Data<EchoIndexAnomalyDetector>,
+) -> Result<HttpResponse> {
+    let Some(window) = TimeWindow::parse(query.since.as_deref().unwrap_or("24h")) else {
+        return Err(ApiError::bad_request("since must be one of 24h, 7d, 30d or all").into());
+    };
+    let detected = anomalies.detected_since(window.since(chrono::Utc::now())).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to load Echo Index anomalies");
+        ApiError::internal()
+    })?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": detected,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Find an anomalous jump legitimate, releasing the content's held rewards
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "dismiss_anomaly",
+    tag = "admin",
+    params(("anomaly_id" = Uuid, Path, description = "Anomaly ID")),
+    responses(
+        (status = 200, description = "The dismissed anomaly", body = Object),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No such anomaly"),
+        (status = 409, description = "Anomaly already resolved"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/anomalies/{anomaly_id}/dismiss")]
+pub async fn dismiss_anomaly(
+    path: web::Path<Uuid>,
+    claims: web::ReqData<Claims>,
+    anomalies: web::Data<EchoIndexAnomalyDetector>,
+) -> Result<HttpResponse> {
+    let anomaly_id = path.into_inner();
+    let Ok(reviewed_by) = Uuid::parse_str(&claims.sub) else {
+        return Err(ApiError::bad_request("Only users can review anomalies").into());
+    };
+    anomaly_review_response(anomaly_id, anomalies.dismiss(anomaly_id, reviewed_by).await)
+}
+
+/// Find an anomalous jump manipulated, freezing the content's rewards
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "confirm_anomaly",
+    tag = "admin",
+    params(("anomaly_id" = Uuid, Path, description = "Anomaly ID")),
+    responses(
+        (status = 200, description = "The confirmed anomaly", body = Object),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No such anomaly"),
+        (status = 409, description = "Anomaly already resolved"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/anomalies/{anomaly_id}/confirm")]
+pub async fn confirm_anomaly(
+    path: web::Path<Uuid>,
+    claims: web::ReqData<Claims>,
+    anomalies: web::Data<EchoIndexAnomalyDetector>,
+) -> Result<HttpResponse> {
+    let anomaly_id = path.into_inner();
+    let Ok(reviewed_by) = Uuid::parse_str(&claims.sub) else {
+        return Err(ApiError::bad_request("Only users can review anomalies").into());
+    };
+    anomaly_review_response(anomaly_id, anomalies.confirm(anomaly_id, reviewed_by).await)
+}
+
+fn anomaly_review_response(
+    anomaly_id: Uuid,
+    result: std::result::Result<EchoAnomaly, RepositoryError>,
+) -> Result<HttpResponse> {
+    match result {
+        Ok(anomaly) => {
+            log::info!("Anomaly {} reviewed: {:?}", anomaly_id, anomaly.status);
+            Ok(HttpResponse::Ok().json(json!({
+                "success": true,
+                "data": anomaly,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(RepositoryError::NotFound) => Err(ApiError::NotFound("Anomaly not found".to_string()).into()),
+        Err(RepositoryError::Conflict(message)) => Err(ApiError::StateConflict(message).into()),
+        Err(e) => {
+            tracing::error!(%anomaly_id, error = %e, "Failed to review anomaly");
+            Err(ApiError::internal().into())
+        }
+    }
+}
+
+/// Most amplification events listed at once
+const MAX_AMPLIFICATION_EVENTS: u32 = 500;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AmplificationEventQuery {
+    pub content_id: Option<String>,
+    /// `24h`, `7d`, `30d` or `all`; `24h` when omitted
+    pub since: Option<String>,
+}
+
+/// Resonance amplifications of Echo Loops within a period, most recent first, for
+/// investigating boosted Echo Indexes
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "list_amplification_events",
+    tag = "admin",
+    params(AmplificationEventQuery),
+    responses(
+        (status = 200, description = "Amplification events, most recent first", body = [Object]),
+        (status = 400, description = "Invalid period"),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/propagation/amplification-events")]
+pub async fn list_amplification_events(
+    query: web::Query<AmplificationEventQuery>,
+    propagations: web::Data<PropagationRepository>,
+) -> Result<HttpResponse> {
+    let Some(window) = TimeWindow::parse(query.since.as_deref().unwrap_or("24h")) else {
+        return Err(ApiError::bad_request("since must be one of 24h, 7d, 30d or all").into());
+    };
+    let events = propagations
+        .list_amplifications(
+            query.content_id.as_deref(),
+            window.since(chrono::Utc::now()),
+            MAX_AMPLIFICATION_EVENTS,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to load amplification events");
+            ApiError::internal()
+        })?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": events,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Scale the path weights an amplification boosted back to what they were before it
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "revert_amplification_event",
+    tag = "admin",
+    params(("event_id" = Uuid, Path, description = "Amplification event ID")),
+    responses(
+        (status = 200, description = "The reverted event", body = Object),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No such event, or its Echo Loop is gone"),
+        (status = 409, description = "Event already reverted"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/propagation/amplification-events/{event_id}/revert")]
+pub async fn revert_amplification_event(
+    path: web::Path<Uuid>,
+    claims: web::ReqData<Claims>,
+    propagation: web::Data<PropagationService>,
+) -> Result<HttpResponse> {
+    let event_id = path.into_inner();
+    match propagation.revert_amplification(event_id).await {
+        Ok(event) => {
+            log::info!("Amplification {} of loop {} reverted by {}", event_id, event.loop_id, claims.sub);
+            Ok(HttpResponse::Ok().json(json!({
+                "success": true,
+                "data": event,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(RepositoryError::NotFound) => {
+            Err(ApiError::NotFound("Amplification event or its Echo Loop not found".to_string()).into())
+        }
+        Err(RepositoryError::Conflict(message)) => Err(ApiError::StateConflict(message).into()),
+        Err(e) => {
+            tracing::error!(%event_id, error = %e, "Failed to revert amplification");
+            Err(ApiError::internal().into())
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetRoleRequest {
+    pub role: Role,
+}
+
+/// Grant or revoke a user's role; takes effect on their next login
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "set_user_role",
+    tag = "admin",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    request_body = SetRoleRequest,
+    responses(
+        (status = 200, description = "The user's new role", body = Object),
+        (status = 403, description = "Not a super admin"),
+        (status = 404, description = "No such user"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[put("/users/{user_id}/role", wrap = "RequireRole(Role::SuperAdmin)")]
+pub async fn set_user_role(
+    path: web::Path<Uuid>,
+    request: web::Json<SetRoleRequest>,
+    users: web::Data<UserRepository>,
+) -> Result<HttpResponse> {
+    let user_id = path.into_inner();
+
+    match users.set_role(user_id, request.role).await {
+        Ok(()) => {
+            log::info!("Set role of user {} to {:?}", user_id, request.role);
+            Ok(HttpResponse::Ok().json(json!({
+                "success": true,
+                "data": { "user_id": user_id, "role": request.role },
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(RepositoryError::NotFound) => Err(ApiError::NotFound("User not found".to_string()).into()),
+        Err(e) => {
+            tracing::error!(%user_id, error = %e, "Failed to set user role");
+            Err(ApiError::internal().into())
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ExperimentRequest {
+    pub name: String,
+    pub control_weights: EchoIndexWeights,
+    pub treatment_weights: EchoIndexWeights,
+    /// Share of content scored with the treatment weights, in [0, 1]
+    pub traffic_split: f64,
+    #[serde(default = "default_experiment_active")]
+    pub active: bool,
+}
+
+fn default_experiment_active() -> bool {
+    true
+}
+
+impl ExperimentRequest {
+    fn validate(&self) -> std::result::Result<NewExperiment<'_>, String> {
+        if self.name.trim().is_empty() {
+            return Err("Experiment name must not be empty".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.traffic_split) {
+            return Err("traffic_split must be between 0 and 1".to_string());
+        }
+        self.control_weights
+            .calculator()
+            .map_err(|e| format!("Invalid control weights: {}", e))?;
+        self.treatment_weights
+            .calculator()
+            .map_err(|e| format!("Invalid treatment weights: {}", e))?;
+
+        Ok(NewExperiment {
+            name: self.name.trim(),
+            control_weights: self.control_weights,
+            treatment_weights: self.treatment_weights,
+            traffic_split: self.traffic_split,
+            active: self.active,
+        })
+    }
+}
+
+/// Start an experiment scoring content with alternative Echo Index weights. Only one
+/// experiment can be active at a time.
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "create_experiment",
+    tag = "admin",
+    request_body = ExperimentRequest,
+    responses(
+        (status = 201, description = "Experiment created", body = Object),
+        (status = 400, description = "Invalid name, weights or traffic split"),
+        (status = 403, description = "Not an admin"),
+        (status = 409, description = "Another experiment is already active"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/experiments")]
+pub async fn create_experiment(
+    request: web::Json<ExperimentRequest>,
+    experiments: web::Data<ExperimentRepository>,
+) -> Result<HttpResponse> {
+    let experiment = match request.validate() {
+        Ok(experiment) => experiment,
+        Err(message) => return Err(ApiError::bad_request(message).into()),
+    };
+
+    match experiments.create(&experiment).await {
+        Ok(experiment) => {
+            log::info!("Created experiment {} ({})", experiment.id, experiment.name);
+            Ok(HttpResponse::Created().json(json!({
+                "success": true,
+                "data": experiment,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(e) => experiment_error(e),
+    }
+}
+
+/// All experiments, newest first
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "list_experiments",
+    tag = "admin",
+    responses(
+        (status = 200, description = "All experiments, newest first", body = [Object]),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/experiments")]
+pub async fn list_experiments(experiments: web::Data<ExperimentRepository>) -> Result<HttpResponse> {
+    match experiments.list().await {
+        Ok(list) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": list,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => experiment_error(e),
+    }
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "get_experiment",
+    tag = "admin",
+    params(("experiment_id" = Uuid, Path, description = "Experiment ID")),
+    responses(
+        (status = 200, description = "The experiment", body = Object),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No such experiment"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/experiments/{experiment_id}")]
+pub async fn get_experiment(
+    path: web::Path<Uuid>,
+    experiments: web::Data<ExperimentRepository>,
+) -> Result<HttpResponse> {
+    match experiments.find(path.into_inner()).await {
+        Ok(experiment) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": experiment,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => experiment_error(e),
+    }
+}
+
+/// Replace an experiment's definition, or stop it by setting `active` to false
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "update_experiment",
+    tag = "admin",
+    params(("experiment_id" = Uuid, Path, description = "Experiment ID")),
+    request_body = ExperimentRequest,
+    responses(
+        (status = 200, description = "The updated experiment", body = Object),
+        (status = 400, description = "Invalid name, weights or traffic split"),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No such experiment"),
+        (status = 409, description = "Another experiment is already active"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[put("/experiments/{experiment_id}")]
+pub async fn update_experiment(
+    path: web::Path<Uuid>,
+    request: web::Json<ExperimentRequest>,
+    experiments: web::Data<ExperimentRepository>,
+) -> Result<HttpResponse> {
+    let experiment_id = path.into_inner();
+    let experiment = match request.validate() {
+        Ok(experiment) => experiment,
+        Err(message) => return Err(ApiError::bad_request(message).into()),
+    };
+
+    match experiments.update(experiment_id, &experiment).await {
+        Ok(experiment) => {
+            log::info!("Updated experiment {} (active: {})", experiment.id, experiment.active);
+            Ok(HttpResponse::Ok().json(json!({
+                "success": true,
+                "data": experiment,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(e) => experiment_error(e),
+    }
+}
+
+/// Delete an experiment; scores calculated under it stay in the history untagged
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "delete_experiment",
+    tag = "admin",
+    params(("experiment_id" = Uuid, Path, description = "Experiment ID")),
+    responses(
+        (status = 204, description = "Experiment deleted"),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No such experiment"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[delete("/experiments/{experiment_id}")]
+pub async fn delete_experiment(
+    path: web::Path<Uuid>,
+    experiments: web::Data<ExperimentRepository>,
+) -> Result<HttpResponse> {
+    let experiment_id = path.into_inner();
+    match experiments.delete(experiment_id).await {
+        Ok(()) => {
+            log::info!("Deleted experiment {}", experiment_id);
+            Ok(HttpResponse::NoContent().finish())
+        }
+        Err(e) => experiment_error(e),
+    }
+}
+
+/// Mean Echo Index and its standard error per variant, and the p-value of their difference
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "get_experiment_results",
+    tag = "admin",
+    params(("experiment_id" = Uuid, Path, description = "Experiment ID")),
+    responses(
+        (status = 200, description = "Mean Echo Index per variant and the p-value of their difference", body = Object),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No such experiment"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/experiments/{experiment_id}/results")]
+pub async fn get_experiment_results(
+    path: web::Path<Uuid>,
+    experiments: web::Data<ExperimentRepository>,
+) -> Result<HttpResponse> {
+    match experiments.results(path.into_inner()).await {
+        Ok(results) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": results,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => experiment_error(e),
+    }
+}
+
+/// The echo engine config in force, where it came from and which settings environment
+/// variables override
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "get_engine_config",
+    tag = "admin",
+    responses(
+        (status = 200, description = "The config in force, its source and the overridden settings", body = Object),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/config")]
+pub async fn get_engine_config(engine_config: web::Data<EngineConfigStore>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": {
+            "live": engine_config.load(),
+            "file_path": engine_config.path(),
+        },
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Swap in a new echo engine config without editing the config file; omitted settings
+/// take their defaults. It stays in force until the file next changes.
+#[utoipa::path(
+    context_path = "/api/v1/admin",
+    operation_id = "update_engine_config",
+    tag = "admin",
+    request_body = Object,
+    responses(
+        (status = 200, description = "The config now `live` and the `changes` from the previous one", body = Object),
+        (status = 400, description = "Invalid config"),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[put("/config")]
+pub async fn update_engine_config(
+    request: web::Json<EchoEngineConfig>,
+    engine_config: web::Data<EngineConfigStore>,
+) -> Result<HttpResponse> {
+    match engine_config.replace(request.into_inner()) {
+        Ok(changes) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": {
+                "live": engine_config.load(),
+                "changes": changes,
+            },
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(ApiError::bad_request(e.to_string()).into()),
+    }
+}
+
+fn experiment_error(error: RepositoryError) -> Result<HttpResponse> {
+    match error {
+        RepositoryError::NotFound => Err(ApiError::NotFound("Experiment not found".to_string()).into()),
+        RepositoryError::Conflict(_) => {
+            Err(ApiError::StateConflict("Another experiment is already active".to_string()).into())
+        }
+        e => {
+            tracing::error!(error = %e, "Experiment query failed");
+            Err(ApiError::internal().into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::auth::{AuthService, JwtConfig};
+    use crate::middleware::JwtMiddleware;
+    use crate::services::TokenBlacklist;
+    use actix_web::{test, App};
+    use sqlx::PgPool;
+
+    const SECRET: &str = "test-secret";
+
+    /// Admins reach admin endpoints only when signed in with a second factor
+    fn bearer(role: Role) -> (&'static str, String) {
+        let config = JwtConfig::new(SECRET);
+        let token = AuthService::generate_access_token("user_1", "wallet_1", "session_1", role, true, &config).unwrap();
+        ("Authorization", format!("Bearer {}", token))
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_admin_endpoints_require_admin_role(pool: PgPool) {
+        let users = web::Data::new(UserRepository::new(pool));
+        let user_id = users.find_or_create_by_wallet("0xpromoted").await.unwrap().id;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(RwLock::new(RewardService::new(10_000.0))))
+                .app_data(users.clone())
+                .service(
+                    web::scope("/admin")
+                        .wrap(RequireRole(Role::Admin))
+                        .wrap(JwtMiddleware::new(JwtConfig::new(SECRET), web::Data::new(TokenBlacklist::new())))
+                        .service(list_on_hold_rewards)
+                        .service(set_user_role),
+                ),
+        )
+        .await;
+
+        let list = |role| test::TestRequest::get().uri("/admin/rewards/on-hold").insert_header(bearer(role));
+        assert_eq!(test::call_service(&app, list(Role::User).to_request()).await.status(), 403);
+        assert_eq!(test::call_service(&app, list(Role::Moderator).to_request()).await.status(), 403);
+        assert_eq!(test::call_service(&app, list(Role::Admin).to_request()).await.status(), 200);
+
+        let promote = |role| {
+            test::TestRequest::put()
+                .uri(&format!("/admin/users/{}/role", user_id))
+                .insert_header(bearer(role))
+                .set_json(json!({ "role": "moderator" }))
+        };
+        assert_eq!(test::call_service(&app, promote(Role::Admin).to_request()).await.status(), 403);
+        assert_eq!(test::call_service(&app, promote(Role::SuperAdmin).to_request()).await.status(), 200);
+        assert_eq!(users.find_or_create_by_wallet("0xpromoted").await.unwrap().role, Role::Moderator);
+    }
+
+    #[actix_web::test]
+    async fn test_decay_config_by_factor_or_category() {
+        let propagation = web::Data::new(PropagationService::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(propagation.clone())
+                .service(web::scope("/admin").service(set_content_decay_config)),
+        )
+        .await;
+
+        let configure = |body| {
+            test::TestRequest::post().uri("/admin/content/content_1/decay-config").set_json(body).to_request()
+        };
+        let response = test::call_service(&app, configure(json!({ "category": "breaking_news" }))).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(propagation.decay_factor_for("content_1"), 0.7);
+        let response = test::call_service(&app, configure(json!({ "decay_factor": 0.95 }))).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(propagation.decay_factor_for("content_1"), 0.95);
+
+        let body: serde_json::Value =
+            test::call_and_read_body_json(&app, configure(json!({ "category": "other" }))).await;
+        assert_eq!(body["data"]["decay_factor"], propagation.decay_factor_for("content_2"));
+        let both = json!({ "decay_factor": 0.9, "category": "analysis" });
+        assert_eq!(test::call_service(&app, configure(both)).await.status(), 400);
+        assert_eq!(test::call_service(&app, configure(json!({}))).await.status(), 400);
+        assert_eq!(test::call_service(&app, configure(json!({ "decay_factor": 1.5 }))).await.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_engine_config_can_be_pushed_and_inspected() {
+        let engine_config = web::Data::new(EngineConfigStore::default());
+        let app = test::init_service(
+            App::new()
+                .app_data(engine_config.clone())
+                .service(web::scope("/admin").service(get_engine_config).service(update_engine_config)),
+        )
+        .await;
+
+        let push = |body| test::TestRequest::put().uri("/admin/config").set_json(body).to_request();
+        let invalid = json!({ "odf_weight": 0.9 });
+        assert_eq!(test::call_service(&app, push(invalid)).await.status(), 400);
+
+        let response = test::call_service(&app, push(json!({ "decay_factor": 0.9 }))).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(body["data"]["changes"], json!(["decay_factor: 0.95 -> 0.9"]));
+
+        let request = test::TestRequest::get().uri("/admin/config").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(body["data"]["live"]["source"], "api");
+        assert_eq!(body["data"]["live"]["config"]["decay_factor"], 0.9);
+        assert_eq!(engine_config.load().config.decay_factor, 0.9);
+    }
+}
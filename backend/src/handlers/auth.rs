@@ -1,21 +1,49 @@
+// Wallet addresses, signatures and tokens arrive straight from the network and are
+// fuzzed (see fuzz/), so malformed input must end in an error rather than a panic
+#![deny(clippy::unwrap_used)]
+
 use actix_web::{web, HttpResponse, Result as ActixResult, HttpRequest};
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc, Duration};
 use uuid::Uuid;
 use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use utoipa::ToSchema;
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use sha3::{Digest, Keccak256};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rand::RngCore;
+use sha2::Sha256;
+use crate::handlers::errors::{ApiError, AuthError};
+use crate::models::api_key::ApiKey;
+use crate::models::session::Session;
+use crate::models::user::{Role, User};
+use crate::models::wallet_address::{EthereumAddress, SolanaAddress, WalletAddress};
+use crate::repositories::{NewSession, RefreshTokenRepository, RepositoryError, SessionRepository, UserRepository};
+use crate::services::{
+    challenge_store::CHALLENGE_TTL, AuthOutcome, ChallengeStore, MetricsRegistry, MpcWalletVerifier, TokenBlacklist,
+    TwoFactorError, TwoFactorService,
+};
 
 /// Wallet authentication request
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct WalletAuthRequest {
     pub wallet_address: String,
     pub signature: String,
     pub message: String,
+    pub nonce: String,
     pub wallet_type: WalletType,
     pub platform: Option<String>,
+    /// TOTP or recovery code, required once the user has enabled two-factor authentication
+    pub totp_code: Option<String>,
+    /// Identifies the device in the user's session list; derived from the user agent when
+    /// the client does not send one
+    pub device_fingerprint: Option<String>,
 }
 
 /// Wallet type enumeration
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+#[schema(example = "phantom")]
 pub enum WalletType {
     #[serde(rename = "mpc")]
     MPC,
@@ -30,7 +58,7 @@ pub enum WalletType {
 }
 
 /// Authentication response with tokens
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct AuthResponse {
     pub user_id: String,
     pub access_token: String,
@@ -41,7 +69,7 @@ pub struct AuthResponse {
 }
 
 /// User profile information
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
 pub struct UserProfile {
     pub user_id: String,
     pub username: Option<String>,
@@ -56,7 +84,7 @@ pub struct UserProfile {
 }
 
 /// User preferences
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
 pub struct UserPreferences {
     pub notifications_enabled: bool,
     pub email_notifications: bool,
@@ -67,13 +95,13 @@ pub struct UserPreferences {
 }
 
 /// Token refresh request
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct RefreshTokenRequest {
     pub refresh_token: String,
 }
 
 /// Token response
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct TokenResponse {
     pub access_token: String,
     pub expires_in: u64,
@@ -93,8 +121,16 @@ pub struct SessionInfo {
     pub ip_address: Option<String>,
 }
 
+/// One of the user's sessions, flagged when it is the one making the request
+#[derive(Serialize, ToSchema)]
+pub struct SessionResponse {
+    #[serde(flatten)]
+    pub session: Session,
+    pub current: bool,
+}
+
 /// JWT Claims structure
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Claims {
     pub sub: String,           // User ID
     pub wallet: String,        // Wallet address
@@ -102,41 +138,80 @@ pub struct Claims {
     pub iat: usize,           // Issued at timestamp
     pub jti: String,          // JWT ID
     pub session_id: String,   // Session identifier
+    #[serde(default)]
+    pub role: Role,           // Access level; tokens issued before roles existed are plain users
+    #[serde(default)]
+    pub totp_verified: bool,  // Signed in with a second factor, as admin endpoints require
+}
+
+impl Claims {
+    /// Whether the caller may act on other users' behalf: an admin signed in with a second
+    /// factor, as `RequireRole` demands of admin endpoints
+    pub fn is_verified_admin(&self) -> bool {
+        self.role.satisfies(Role::Admin) && self.totp_verified
+    }
+}
+
+/// HS256 signing configuration for access tokens
+#[derive(Clone)]
+pub struct JwtConfig {
+    secret: String,
+}
+
+impl JwtConfig {
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self { secret: secret.into() }
+    }
+
+    /// Load the signing secret from the `JWT_SECRET` environment variable
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("JWT_SECRET").expect("JWT_SECRET must be set"))
+    }
+
+    pub fn decoding_key(&self) -> DecodingKey {
+        DecodingKey::from_secret(self.secret.as_bytes())
+    }
+
+    pub fn encoding_key(&self) -> EncodingKey {
+        EncodingKey::from_secret(self.secret.as_bytes())
+    }
 }
 
+/// How long an access token is valid
+pub const ACCESS_TOKEN_TTL_HOURS: i64 = 24;
+/// How long a refresh token can be exchanged for new access tokens
+const REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+/// Longest device fingerprint a client may send
+const MAX_DEVICE_FINGERPRINT_LENGTH: usize = 128;
+
 /// Authentication service implementation
 pub struct AuthService;
 
 impl AuthService {
-    /// Verify wallet signature for authentication
-    pub fn verify_wallet_signature(
+    /// Verify wallet signature for authentication. MPC wallets are verified by `mpc`.
+    #[tracing::instrument(skip(wallet_address, signature, message, mpc), err)]
+    pub async fn verify_wallet_signature(
         wallet_address: &str,
         signature: &str,
         message: &str,
         wallet_type: &WalletType,
+        mpc: &dyn MpcWalletVerifier,
     ) -> Result<bool, String> {
         // In a real implementation, this would verify the cryptographic signature
         // For different wallet types, we would use their respective signature schemes
         
         match wallet_type {
             WalletType::MPC => {
-                // MPC wallet signature verification
-                // This would integrate with the MPC wallet SDK
-                tracing::info!("Verifying MPC wallet signature for: {}", wallet_address);
-                
-                // Mock verification - in production, use actual MPC verification
-                if wallet_address.len() == 44 && signature.len() > 64 {
-                    Ok(true)
-                } else {
-                    Err("Invalid MPC wallet signature".to_string())
-                }
+                // No single party holds an MPC wallet's key, so only its provider can vouch for it
+                SolanaAddress::parse(wallet_address).map_err(|e| format!("Invalid MPC wallet address: {}", e))?;
+                mpc.verify(wallet_address, signature, message).await
             },
             WalletType::Phantom | WalletType::Solflare => {
                 // Solana wallet signature verification
-                tracing::info!("Verifying Solana wallet signature for: {}", wallet_address);
-                
+                SolanaAddress::parse(wallet_address).map_err(|e| format!("Invalid Solana wallet address: {}", e))?;
+
                 // Mock verification - in production, use ed25519 verification
-                if wallet_address.len() == 44 && signature.len() > 64 {
+                if signature.len() > 64 {
                     Ok(true)
                 } else {
                     Err("Invalid Solana wallet signature".to_string())
@@ -144,25 +219,77 @@ impl AuthService {
             },
             WalletType::MetaMask | WalletType::WalletConnect => {
                 // Ethereum wallet signature verification
-                tracing::info!("Verifying Ethereum wallet signature for: {}", wallet_address);
-                
-                // Mock verification - in production, use secp256k1 verification
-                if wallet_address.starts_with("0x") && wallet_address.len() == 42 {
-                    Ok(true)
-                } else {
-                    Err("Invalid Ethereum wallet signature".to_string())
-                }
+                Self::verify_ethereum_signature(wallet_address, signature, message)
             },
         }
     }
+
+    /// Verify an EIP-191 `personal_sign` signature by recovering the signer address
+    fn verify_ethereum_signature(
+        wallet_address: &str,
+        signature: &str,
+        message: &str,
+    ) -> Result<bool, String> {
+        let address = EthereumAddress::parse(wallet_address)
+            .map_err(|e| format!("Invalid Ethereum wallet address: {}", e))?;
+
+        let signature_bytes = hex::decode(signature.trim_start_matches("0x"))
+            .map_err(|_| "Ethereum signature must be hex encoded".to_string())?;
+        if signature_bytes.len() != 65 {
+            return Err("Ethereum signature must be 65 bytes".to_string());
+        }
+
+        // Wallets emit v as 27/28 (legacy) or 0/1
+        let v = signature_bytes[64];
+        let recovery_id = RecoveryId::from_byte(if v >= 27 { v - 27 } else { v })
+            .ok_or_else(|| "Invalid signature recovery id".to_string())?;
+        let signature = Signature::from_slice(&signature_bytes[..64])
+            .map_err(|_| "Invalid Ethereum signature".to_string())?;
+
+        // EIP-191 prefix: "\x19Ethereum Signed Message:\n" + message length + message
+        let prefixed = format!("\x19Ethereum Signed Message:\n{}{}", message.len(), message);
+        let digest = Keccak256::new_with_prefix(prefixed.as_bytes());
+
+        let recovered_key = VerifyingKey::recover_from_digest(digest, &signature, recovery_id)
+            .map_err(|_| "Failed to recover signer from signature".to_string())?;
+
+        Ok(Self::ethereum_address(&recovered_key) == address)
+    }
+
+    /// Derive the Ethereum address of a secp256k1 public key
+    fn ethereum_address(key: &VerifyingKey) -> EthereumAddress {
+        let public_key = key.to_encoded_point(false);
+        // Skip the 0x04 uncompressed point tag; the address is the last 20 bytes of the hash
+        let hash = Keccak256::digest(&public_key.as_bytes()[1..]);
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&hash[12..]);
+        EthereumAddress::from_bytes(address)
+    }
     
-    /// Generate JWT access token
+    /// Generate an HS256-signed JWT access token carrying the user's role and whether
+    /// the session was signed in with a second factor
     pub fn generate_access_token(
         user_id: &str,
         wallet_address: &str,
         session_id: &str,
+        role: Role,
+        totp_verified: bool,
+        config: &JwtConfig,
     ) -> Result<String, String> {
-        let expiration = Utc::now() + Duration::hours(24);
+        Self::issue_access_token(user_id, wallet_address, session_id, role, totp_verified, config)
+            .map(|(token, _)| token)
+    }
+
+    /// Generate an access token as `generate_access_token` does, along with its claims
+    pub fn issue_access_token(
+        user_id: &str,
+        wallet_address: &str,
+        session_id: &str,
+        role: Role,
+        totp_verified: bool,
+        config: &JwtConfig,
+    ) -> Result<(String, Claims), String> {
+        let expiration = Utc::now() + Duration::hours(ACCESS_TOKEN_TTL_HOURS);
         let claims = Claims {
             sub: user_id.to_string(),
             wallet: wallet_address.to_string(),
@@ -170,35 +297,76 @@ impl AuthService {
             iat: Utc::now().timestamp() as usize,
             jti: Uuid::new_v4().to_string(),
             session_id: session_id.to_string(),
+            role,
+            totp_verified,
         };
-        
-        // In production, use a proper JWT library with secret/key management
-        let token = format!("eyJ0eXAiOiJKV1QiLCJhbGciOiJIUzI1NiJ9.{}.signature", 
-                           base64::encode(serde_json::to_string(&claims).unwrap()));
-        
-        Ok(token)
+
+        encode(&Header::new(Algorithm::HS256), &claims, &config.encoding_key())
+            .map(|token| (token, claims))
+            .map_err(|e| format!("Failed to sign token: {}", e))
     }
     
-    /// Generate refresh token
+    /// Fully validate an access token: HS256 signature, expiry and revocation of the
+    /// token or of its whole session
+    pub fn validate_access_token(
+        token: &str,
+        config: &JwtConfig,
+        blacklist: &TokenBlacklist,
+    ) -> Result<Claims, String> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.leeway = 0;
+
+        let claims = decode::<Claims>(token, &config.decoding_key(), &validation)
+            .map(|data| data.claims)
+            .map_err(|e| format!("Invalid token: {}", e))?;
+
+        if blacklist.is_revoked(&claims.jti) || blacklist.is_revoked(&claims.session_id) {
+            return Err("Token has been revoked".to_string());
+        }
+
+        Ok(claims)
+    }
+
+    /// Fingerprint of a device that did not send its own: the SHA-256 of its user agent
+    pub fn device_fingerprint(user_agent: &str) -> String {
+        hex::encode(Sha256::digest(user_agent.as_bytes()))
+    }
+
+    /// Address of the client, as reported by a proxy in front of the server if any
+    pub fn client_ip(req: &HttpRequest) -> IpAddr {
+        let connection_info = req.connection_info();
+        connection_info
+            .realip_remote_addr()
+            .and_then(|addr| {
+                addr.parse::<IpAddr>().ok().or_else(|| addr.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+            })
+            .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+    }
+
+    /// Generate a random 256-bit refresh token, hex encoded
     pub fn generate_refresh_token() -> String {
-        Uuid::new_v4().to_string()
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        hex::encode(bytes)
+    }
+
+    /// SHA-256 of a refresh token, the only form in which it is stored
+    pub fn hash_refresh_token(token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
     }
     
-    /// Create or update user profile
-    pub fn create_user_profile(wallet_address: &str, wallet_type: &WalletType) -> UserProfile {
-        let user_id = Uuid::new_v4().to_string();
-        let now = Utc::now();
-        
+    /// Public profile of a signed-in user
+    pub fn create_user_profile(user: &User) -> UserProfile {
         UserProfile {
-            user_id,
-            username: None,
-            display_name: None,
-            avatar_url: None,
-            bio: None,
-            total_echo_score: 0.0,
-            tier: "Basic".to_string(),
-            created_at: now,
-            last_active: now,
+            user_id: user.id.to_string(),
+            username: user.username.clone(),
+            display_name: user.display_name.clone(),
+            avatar_url: user.avatar_url.clone(),
+            bio: user.bio.clone(),
+            total_echo_score: user.echo_score,
+            tier: user.tier.as_str().to_string(),
+            created_at: user.created_at,
+            last_active: Utc::now(),
             preferences: UserPreferences {
                 notifications_enabled: true,
                 email_notifications: false,
@@ -212,12 +380,75 @@ impl AuthService {
 }
 
 /// Authenticate user with wallet signature
+#[utoipa::path(
+    post,
+    path = "/login",
+    context_path = "/api/v1/auth",
+    operation_id = "login_with_wallet",
+    tag = "auth",
+    request_body = WalletAuthRequest,
+    responses(
+        (status = 200, description = "Signed in", body = AuthResponse),
+        (status = 400, description = "Malformed wallet address or signature"),
+        (status = 401, description = "Unknown challenge, invalid signature or missing or invalid second factor"),
+    ),
+    security((), ("bearer_auth" = []), ("api_key" = []))
+)]
 #[actix_web::post("/login")]
+#[allow(clippy::too_many_arguments)]
 pub async fn login_with_wallet(
     request: web::Json<WalletAuthRequest>,
     req: HttpRequest,
+    challenges: web::Data<ChallengeStore>,
+    users: web::Data<UserRepository>,
+    refresh_tokens: web::Data<RefreshTokenRepository>,
+    sessions: web::Data<SessionRepository>,
+    jwt_config: web::Data<JwtConfig>,
+    metrics: web::Data<MetricsRegistry>,
+    mpc: web::Data<dyn MpcWalletVerifier>,
+    two_factor: web::Data<TwoFactorService>,
+) -> ActixResult<HttpResponse> {
+    let response =
+        authenticate_wallet(request, req, challenges, users, refresh_tokens, sessions, jwt_config, mpc, two_factor)
+            .await;
+    let outcome = match &response {
+        Ok(response) if response.status().is_success() => AuthOutcome::Success,
+        _ => AuthOutcome::Failure,
+    };
+    metrics.record_auth(outcome);
+    response
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn authenticate_wallet(
+    request: web::Json<WalletAuthRequest>,
+    req: HttpRequest,
+    challenges: web::Data<ChallengeStore>,
+    users: web::Data<UserRepository>,
+    refresh_tokens: web::Data<RefreshTokenRepository>,
+    sessions: web::Data<SessionRepository>,
+    jwt_config: web::Data<JwtConfig>,
+    mpc: web::Data<dyn MpcWalletVerifier>,
+    two_factor: web::Data<TwoFactorService>,
 ) -> ActixResult<HttpResponse> {
-    tracing::info!("Authentication attempt for wallet: {}", request.wallet_address);
+    tracing::info!(wallet_type = ?request.wallet_type, "Authentication attempt");
+    
+    // The signed message must be the challenge issued for this nonce
+    if !request.message.contains(&request.nonce) {
+        return Err(ApiError::invalid_field("message", "Signed message does not contain the challenge nonce").into());
+    }
+
+    if request.device_fingerprint.as_ref().is_some_and(|fingerprint| {
+        fingerprint.is_empty() || fingerprint.len() > MAX_DEVICE_FINGERPRINT_LENGTH
+    }) {
+        let message = format!("device_fingerprint must be 1 to {} characters", MAX_DEVICE_FINGERPRINT_LENGTH);
+        return Err(ApiError::invalid_field("device_fingerprint", message).into());
+    }
+    
+    if let Err(e) = challenges.validate(&request.nonce, &request.wallet_address) {
+        tracing::warn!(error = %e, "Rejected challenge");
+        return Err(ApiError::Unauthorized(AuthError::InvalidChallenge(e.to_string())).into());
+    }
     
     // Verify wallet signature
     match AuthService::verify_wallet_signature(
@@ -225,90 +456,184 @@ pub async fn login_with_wallet(
         &request.signature,
         &request.message,
         &request.wallet_type,
-    ) {
+        mpc.get_ref(),
+    )
+    .await
+    {
         Ok(true) => {
-            tracing::info!("Wallet signature verified successfully");
+            tracing::info!("Wallet signature verified");
             
-            // Create or retrieve user profile
-            let user_profile = AuthService::create_user_profile(
-                &request.wallet_address, 
-                &request.wallet_type
-            );
+            // Burn the nonce so the signed message can never be replayed
+            if !challenges.consume(&request.nonce) {
+                tracing::warn!("Challenge replay detected");
+                let error = AuthError::InvalidChallenge("Challenge has already been used".to_string());
+                return Err(ApiError::Unauthorized(error).into());
+            }
+            
+            // Wallets are stored in canonical form, so an Ethereum address signs in to the
+            // same account whatever its casing
+            let wallet_address = WalletAddress::parse(&request.wallet_address)
+                .map_err(|e| ApiError::invalid_field("wallet_address", e.to_string()))?
+                .to_string();
+
+            // New wallets are registered with the default `user` role
+            let user = users
+                .find_or_create_by_wallet(&wallet_address)
+                .await
+                .map_err(|e| {
+                    tracing::error!(error = %e, "Failed to load user");
+                    ApiError::internal()
+                })?;
+
+            // Users with 2FA enabled also need a code from their authenticator, or a
+            // recovery code. A failed attempt needs a new challenge.
+            if user.totp_enabled {
+                let Some(code) = request.totp_code.as_deref() else {
+                    return Err(ApiError::Unauthorized(AuthError::TotpRequired).into());
+                };
+                match two_factor.verify(&user, code).await {
+                    Ok(()) => {}
+                    Err(TwoFactorError::InvalidCode) => {
+                        tracing::warn!(user_id = %user.id, "Invalid two-factor authentication code");
+                        return Err(ApiError::Unauthorized(AuthError::InvalidTotpCode).into());
+                    }
+                    Err(e) => {
+                        tracing::error!(error = %e, "Failed to verify two-factor authentication code");
+                        return Err(ApiError::internal().into());
+                    }
+                }
+            }
+
+            let user_profile = AuthService::create_user_profile(&user);
             
             // Generate session
-            let session_id = Uuid::new_v4().to_string();
+            let session_id = Uuid::new_v4();
             
             // Generate tokens
-            let access_token = AuthService::generate_access_token(
+            let (access_token, claims) = AuthService::issue_access_token(
                 &user_profile.user_id,
-                &request.wallet_address,
-                &session_id,
+                &user.wallet_address,
+                &session_id.to_string(),
+                user.role,
+                user.totp_enabled,
+                &jwt_config,
             ).map_err(|e| {
-                tracing::error!("Failed to generate access token: {}", e);
-                actix_web::error::ErrorInternalServerError("Token generation failed")
+                tracing::error!(error = %e, "Failed to generate access token");
+                ApiError::internal()
             })?;
             
-            let refresh_token = AuthService::generate_refresh_token();
+            let new_refresh_token = AuthService::generate_refresh_token();
+            let expires_at = Utc::now() + Duration::days(REFRESH_TOKEN_TTL_DAYS);
+            refresh_tokens
+                .store(
+                    &AuthService::hash_refresh_token(&new_refresh_token),
+                    user.id,
+                    session_id,
+                    user.totp_enabled,
+                    expires_at,
+                )
+                .await
+                .map_err(|e| {
+                    tracing::error!(error = %e, "Failed to store refresh token");
+                    ApiError::internal()
+                })?;
+
+            // The session lasts as long as its refresh token
+            let user_agent = req.headers().get("User-Agent").and_then(|h| h.to_str().ok()).unwrap_or_default();
+            let device_fingerprint = request
+                .device_fingerprint
+                .clone()
+                .unwrap_or_else(|| AuthService::device_fingerprint(user_agent));
+            sessions
+                .create(&NewSession {
+                    id: session_id,
+                    user_id: user.id,
+                    jti: &claims.jti,
+                    device_fingerprint: &device_fingerprint,
+                    ip_address: AuthService::client_ip(&req),
+                    user_agent,
+                    expires_at,
+                })
+                .await
+                .map_err(|e| {
+                    tracing::error!(error = %e, "Failed to store session");
+                    ApiError::internal()
+                })?;
             
-            // Store session information (in production, store in database/cache)
-            tracing::info!("Session created for user: {}", user_profile.user_id);
+            tracing::info!(user_id = %user_profile.user_id, "Session created");
             
             let response = AuthResponse {
                 user_id: user_profile.user_id.clone(),
                 access_token,
-                refresh_token,
+                refresh_token: new_refresh_token,
                 expires_in: 24 * 3600, // 24 hours
-                wallet_address: request.wallet_address.clone(),
+                wallet_address: user.wallet_address.clone(),
                 user_profile,
             };
             
             Ok(HttpResponse::Ok().json(response))
         },
         Ok(false) => {
-            tracing::warn!("Invalid wallet signature for: {}", request.wallet_address);
-            Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": "invalid_signature",
-                "message": "Wallet signature verification failed"
-            })))
+            tracing::warn!("Invalid wallet signature");
+            Err(ApiError::Unauthorized(AuthError::InvalidSignature).into())
         },
         Err(e) => {
-            tracing::error!("Signature verification error: {}", e);
-            Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                "error": "verification_error",
-                "message": e
-            })))
+            tracing::error!(error = %e, "Signature verification error");
+            Err(ApiError::invalid_field("signature", e).into())
         }
     }
 }
 
 /// Refresh access token using refresh token
+#[utoipa::path(
+    post,
+    path = "/refresh",
+    context_path = "/api/v1/auth",
+    operation_id = "refresh_token",
+    tag = "auth",
+    request_body = RefreshTokenRequest,
+    responses(
+        (status = 200, description = "New access token", body = TokenResponse),
+        (status = 401, description = "Invalid or expired refresh token"),
+    ),
+    security((), ("bearer_auth" = []), ("api_key" = []))
+)]
 #[actix_web::post("/refresh")]
 pub async fn refresh_token(
     request: web::Json<RefreshTokenRequest>,
+    refresh_tokens: web::Data<RefreshTokenRepository>,
+    sessions: web::Data<SessionRepository>,
+    jwt_config: web::Data<JwtConfig>,
 ) -> ActixResult<HttpResponse> {
     tracing::info!("Token refresh requested");
     
-    // In production, validate refresh token against database
-    if request.refresh_token.len() < 10 {
-        return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-            "error": "invalid_refresh_token",
-            "message": "Invalid or expired refresh token"
-        })));
-    }
+    let owner = refresh_tokens
+        .find_active(&AuthService::hash_refresh_token(&request.refresh_token))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to look up refresh token");
+            ApiError::internal()
+        })?;
     
-    // Mock user data - in production, retrieve from database using refresh token
-    let user_id = "user_123";
-    let wallet_address = "mock_wallet_address";
-    let session_id = Uuid::new_v4().to_string();
+    let Some(owner) = owner else {
+        return Err(ApiError::Unauthorized(AuthError::InvalidRefreshToken).into());
+    };
     
-    let new_access_token = AuthService::generate_access_token(
-        user_id,
-        wallet_address,
-        &session_id,
+    // The new access token continues the session the refresh token was issued to
+    let (new_access_token, claims) = AuthService::issue_access_token(
+        &owner.user_id.to_string(),
+        &owner.wallet_address,
+        &owner.session_id.to_string(),
+        owner.role,
+        owner.totp_verified,
+        &jwt_config,
     ).map_err(|e| {
-        tracing::error!("Failed to generate new access token: {}", e);
-        actix_web::error::ErrorInternalServerError("Token generation failed")
+        tracing::error!(error = %e, "Failed to generate new access token");
+        ApiError::internal()
     })?;
+    if let Err(e) = sessions.rotate(owner.session_id, &claims.jti).await {
+        tracing::warn!(session_id = %owner.session_id, error = %e, "Failed to record refreshed access token");
+    }
     
     let response = TokenResponse {
         access_token: new_access_token,
@@ -320,22 +645,61 @@ pub async fn refresh_token(
     Ok(HttpResponse::Ok().json(response))
 }
 
-/// Logout and invalidate session
+/// Logout: revoke the access token and end its session, so its refresh token can no
+/// longer be used either
+#[utoipa::path(
+    post,
+    path = "/logout",
+    context_path = "/api/v1/auth",
+    operation_id = "logout",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Access token and session revoked"),
+        (status = 401, description = "Missing, invalid or already revoked access token"),
+    ),
+    security(("bearer_auth" = []))
+)]
 #[actix_web::post("/logout")]
 pub async fn logout(
     req: HttpRequest,
+    jwt_config: web::Data<JwtConfig>,
+    blacklist: web::Data<TokenBlacklist>,
+    sessions: web::Data<SessionRepository>,
 ) -> ActixResult<HttpResponse> {
     // Extract token from Authorization header
     let auth_header = req.headers().get("Authorization");
     
     if let Some(token) = auth_header {
         if let Ok(token_str) = token.to_str() {
-            if token_str.starts_with("Bearer ") {
-                let token = &token_str[7..];
-                tracing::info!("Logout requested for token: {}...", &token[..10]);
+            if let Some(token) = token_str.strip_prefix("Bearer ") {
+                // Only tokens this server issued may be revoked, so forged ones can neither
+                // pick their own expiry nor fill the blacklist
+                let claims = match AuthService::validate_access_token(token, &jwt_config, &blacklist) {
+                    Ok(claims) => claims,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Logout with invalid token");
+                        return Err(ApiError::Unauthorized(AuthError::InvalidToken(e)).into());
+                    }
+                };
+                
+                tracing::info!(session_id = %claims.session_id, "Logout requested");
                 
-                // In production, invalidate token in database/cache
-                // Add token to blacklist or remove session
+                // Blacklist the token until it would have expired on its own
+                blacklist.revoke(&claims.jti, claims.exp);
+                
+                // End the session too, along with its refresh tokens
+                if let (Ok(user_id), Ok(session_id)) =
+                    (Uuid::parse_str(&claims.sub), Uuid::parse_str(&claims.session_id))
+                {
+                    match sessions.revoke(user_id, session_id).await {
+                        Ok(session) => revoke_access_tokens(&blacklist, &session),
+                        Err(RepositoryError::NotFound) => {}
+                        Err(e) => {
+                            tracing::error!(error = %e, "Failed to end session on logout");
+                            return Err(ApiError::internal().into());
+                        }
+                    }
+                }
                 
                 tracing::info!("Session invalidated successfully");
                 return Ok(HttpResponse::Ok().json(serde_json::json!({
@@ -345,10 +709,138 @@ pub async fn logout(
         }
     }
     
-    Ok(HttpResponse::BadRequest().json(serde_json::json!({
-        "error": "invalid_request",
-        "message": "No valid authentication token provided"
-    })))
+    Err(ApiError::Unauthorized(AuthError::MissingToken).into())
+}
+
+/// The authenticated user's unexpired sessions, most recently active first
+#[utoipa::path(
+    get,
+    path = "",
+    context_path = "/api/v1/auth/sessions",
+    operation_id = "list_sessions",
+    tag = "auth",
+    responses(
+        (status = 200, description = "`sessions`, most recently active first", body = Object),
+        (status = 403, description = "Sessions cannot be managed with an API key"),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[actix_web::get("")]
+pub async fn list_sessions(
+    claims: web::ReqData<Claims>,
+    current_key: Option<web::ReqData<ApiKey>>,
+    sessions: web::Data<SessionRepository>,
+) -> ActixResult<HttpResponse> {
+    let user_id = session_owner(&claims, current_key.is_some())?;
+
+    let sessions = sessions.list_active(user_id).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to list sessions");
+        ApiError::internal()
+    })?;
+    let sessions: Vec<SessionResponse> = sessions
+        .into_iter()
+        .map(|session| SessionResponse { current: session.id.to_string() == claims.session_id, session })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "sessions": sessions })))
+}
+
+/// Revoke one of the authenticated user's sessions, e.g. on a lost device. Its access
+/// tokens are rejected from then on and its refresh token can no longer be used.
+#[utoipa::path(
+    delete,
+    path = "/{session_id}",
+    context_path = "/api/v1/auth/sessions",
+    operation_id = "revoke_session",
+    tag = "auth",
+    params(("session_id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 400, description = "Malformed session ID"),
+        (status = 403, description = "Sessions cannot be managed with an API key"),
+        (status = 404, description = "No active session with this ID"),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[actix_web::delete("/{session_id}")]
+pub async fn revoke_session(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    current_key: Option<web::ReqData<ApiKey>>,
+    sessions: web::Data<SessionRepository>,
+    blacklist: web::Data<TokenBlacklist>,
+) -> ActixResult<HttpResponse> {
+    let user_id = session_owner(&claims, current_key.is_some())?;
+    let Ok(session_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::invalid_field("session_id", "session_id must be a valid UUID").into());
+    };
+
+    match sessions.revoke(user_id, session_id).await {
+        Ok(session) => {
+            revoke_access_tokens(&blacklist, &session);
+            tracing::info!(%user_id, %session_id, "Session revoked");
+            Ok(HttpResponse::NoContent().finish())
+        }
+        Err(RepositoryError::NotFound) => Err(ApiError::NotFound("No active session with this ID".to_string()).into()),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to revoke session");
+            Err(ApiError::internal().into())
+        }
+    }
+}
+
+/// Revoke every session of the authenticated user except the one making the request
+#[utoipa::path(
+    delete,
+    path = "",
+    context_path = "/api/v1/auth/sessions",
+    operation_id = "revoke_other_sessions",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Number of sessions `revoked`", body = Object),
+        (status = 403, description = "Sessions cannot be managed with an API key"),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[actix_web::delete("")]
+pub async fn revoke_other_sessions(
+    claims: web::ReqData<Claims>,
+    current_key: Option<web::ReqData<ApiKey>>,
+    sessions: web::Data<SessionRepository>,
+    blacklist: web::Data<TokenBlacklist>,
+) -> ActixResult<HttpResponse> {
+    let user_id = session_owner(&claims, current_key.is_some())?;
+    // Tokens issued before sessions were recorded belong to none of them
+    let current = Uuid::parse_str(&claims.session_id).unwrap_or_else(|_| Uuid::nil());
+
+    let revoked = sessions.revoke_others(user_id, current).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to revoke sessions");
+        ApiError::internal()
+    })?;
+    for session in &revoked {
+        revoke_access_tokens(&blacklist, session);
+    }
+
+    tracing::info!(%user_id, revoked = revoked.len(), "Other sessions revoked");
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "revoked": revoked.len() })))
+}
+
+/// User whose sessions a request may manage. Sessions belong to wallet logins, so
+/// requests made with an API key are refused.
+fn session_owner(claims: &Claims, with_api_key: bool) -> Result<Uuid, ApiError> {
+    if with_api_key {
+        return Err(ApiError::Forbidden("Sessions cannot be managed with an API key".to_string()));
+    }
+    Uuid::parse_str(&claims.sub)
+        .map_err(|_| ApiError::Unauthorized(AuthError::InvalidToken("Token does not identify a user".to_string())))
+}
+
+/// Blacklist the latest access token of a revoked session, and through its session ID
+/// any earlier one that has not expired yet
+fn revoke_access_tokens(blacklist: &TokenBlacklist, session: &Session) {
+    let until = (Utc::now() + Duration::hours(ACCESS_TOKEN_TTL_HOURS)).timestamp().max(0) as usize;
+    blacklist.revoke(&session.jti, until);
+    blacklist.revoke(&session.id.to_string(), until);
 }
 
 /// Get current session information
@@ -361,11 +853,9 @@ pub async fn get_session_info(
     
     if let Some(token) = auth_header {
         if let Ok(token_str) = token.to_str() {
-            if token_str.starts_with("Bearer ") {
-                let token = &token_str[7..];
-                
+            if let Some(token) = token_str.strip_prefix("Bearer ") {
                 // In production, decode and validate JWT token
-                tracing::info!("Session info requested for token: {}...", &token[..10]);
+                tracing::info!(token_prefix = &token[..10], "Session info requested");
                 
                 // Mock session data
                 let session_info = SessionInfo {
@@ -386,23 +876,41 @@ pub async fn get_session_info(
         }
     }
     
-    Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-        "error": "unauthorized",
-        "message": "Valid authentication token required"
-    })))
+    Err(ApiError::Unauthorized(AuthError::MissingToken).into())
 }
 
 /// Generate authentication challenge for wallet signing
+#[utoipa::path(
+    get,
+    path = "/challenge",
+    context_path = "/api/v1/auth",
+    operation_id = "get_auth_challenge",
+    tag = "auth",
+    params(
+        ("wallet" = String, Query, description = "Address of the wallet signing in"),
+        ("platform" = Option<String>, Query, description = "Client platform, `web` by default"),
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Message to sign, with its `nonce`, `timestamp` and `expires_in` seconds",
+            body = Object
+        ),
+        (status = 400, description = "Missing wallet"),
+    ),
+    security((), ("bearer_auth" = []), ("api_key" = []))
+)]
 #[actix_web::get("/challenge")]
 pub async fn get_auth_challenge(
     query: web::Query<HashMap<String, String>>,
+    challenges: web::Data<ChallengeStore>,
 ) -> ActixResult<HttpResponse> {
     let wallet_address = query.get("wallet")
-        .ok_or_else(|| actix_web::error::ErrorBadRequest("wallet parameter required"))?;
+        .ok_or_else(|| ApiError::invalid_field("wallet", "wallet parameter required"))?;
     
     let platform = query.get("platform").cloned().unwrap_or_else(|| "web".to_string());
     
-    tracing::info!("Challenge requested for wallet: {} on platform: {}", wallet_address, platform);
+    tracing::info!(%platform, "Challenge requested");
     
     // Generate unique challenge message
     let timestamp = Utc::now().timestamp();
@@ -415,11 +923,13 @@ pub async fn get_auth_challenge(
         nonce
     );
     
+    challenges.issue(&nonce, wallet_address);
+    
     let response = serde_json::json!({
         "challenge": challenge_message,
         "nonce": nonce,
         "timestamp": timestamp,
-        "expires_in": 300, // 5 minutes
+        "expires_in": CHALLENGE_TTL.as_secs(),
         "instructions": {
             "message": "Sign this message with your wallet to authenticate",
             "note": "This will not cost any gas or trigger transactions"
@@ -430,36 +940,512 @@ pub async fn get_auth_challenge(
 }
 
 /// Verify token validity (for middleware use)
+#[utoipa::path(
+    post,
+    path = "/verify",
+    context_path = "/api/v1/auth",
+    operation_id = "verify_token",
+    tag = "auth",
+    responses(
+        (
+            status = 200,
+            description = "The token's `user_id`, `wallet_address`, `session_id`, `role` and `expires_at`",
+            body = Object
+        ),
+        (status = 401, description = "Token is invalid, expired or revoked"),
+    ),
+    security(("bearer_auth" = []))
+)]
 #[actix_web::post("/verify")]
 pub async fn verify_token(
     req: HttpRequest,
+    jwt_config: web::Data<JwtConfig>,
+    blacklist: web::Data<TokenBlacklist>,
 ) -> ActixResult<HttpResponse> {
     let auth_header = req.headers().get("Authorization");
     
     if let Some(token) = auth_header {
         if let Ok(token_str) = token.to_str() {
-            if token_str.starts_with("Bearer ") {
-                let token = &token_str[7..];
-                
-                // In production, decode and validate JWT token
+            if let Some(token) = token_str.strip_prefix("Bearer ") {
                 tracing::info!("Token verification requested");
                 
-                // Mock validation - in production, check signature, expiration, etc.
-                if token.len() > 20 {
-                    return Ok(HttpResponse::Ok().json(serde_json::json!({
-                        "valid": true,
-                        "user_id": "user_123",
-                        "wallet_address": "mock_wallet_address",
-                        "expires_at": Utc::now() + Duration::hours(22)
-                    })));
+                match AuthService::validate_access_token(token, &jwt_config, &blacklist) {
+                    Ok(claims) => {
+                        return Ok(HttpResponse::Ok().json(serde_json::json!({
+                            "valid": true,
+                            "user_id": claims.sub,
+                            "wallet_address": claims.wallet,
+                            "session_id": claims.session_id,
+                            "role": claims.role,
+                            "expires_at": DateTime::<Utc>::from_timestamp(claims.exp as i64, 0)
+                        })));
+                    }
+                    Err(e) => tracing::warn!(error = %e, "Token verification failed"),
                 }
             }
         }
     }
     
-    Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-        "valid": false,
-        "error": "invalid_token",
-        "message": "Token is invalid or expired"
-    })))
-} 
\ No newline at end of file
+    Err(ApiError::Unauthorized(AuthError::InvalidToken("Token is invalid or expired".to_string())).into())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::services::mpc_wallet::MockMpcVerifier;
+    use std::sync::Arc;
+
+    // Signed with the well-known web3.js example key 0x4c0883a6...3f362318
+    const ETH_ADDRESS: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+    const ETH_MESSAGE: &str = "Welcome to EchoLayer!";
+    const ETH_SIGNATURE: &str = "0x7e1472a58bcab76ca7479a4bdb4a101bd06113b3a132096bb3656f51a991407a0cb3126cabdfa94d5279b66e412cf5001ca80204b2fa133bc04719be1d6027981b";
+
+    const MPC_WALLET: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+
+    /// Claims of an access token signed with the tests' `test-secret`
+    fn claims_of(token: &str) -> Claims {
+        decode::<Claims>(token, &DecodingKey::from_secret(b"test-secret"), &Validation::new(Algorithm::HS256))
+            .unwrap()
+            .claims
+    }
+
+    async fn verify(
+        wallet_address: &str,
+        signature: &str,
+        message: &str,
+        wallet_type: WalletType,
+    ) -> Result<bool, String> {
+        let mpc = MockMpcVerifier::new();
+        AuthService::verify_wallet_signature(wallet_address, signature, message, &wallet_type, &mpc).await
+    }
+
+    #[tokio::test]
+    async fn test_ethereum_signature_known_vector() {
+        assert_eq!(verify(ETH_ADDRESS, ETH_SIGNATURE, ETH_MESSAGE, WalletType::MetaMask).await, Ok(true));
+    }
+
+    #[tokio::test]
+    async fn test_ethereum_signature_wrong_message() {
+        let result = verify(ETH_ADDRESS, ETH_SIGNATURE, "Welcome to EchoLayer?", WalletType::WalletConnect).await;
+        assert_eq!(result, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn test_ethereum_signature_malformed_input() {
+        assert!(verify(ETH_ADDRESS, "0xzz", ETH_MESSAGE, WalletType::MetaMask).await.is_err());
+        assert!(verify(ETH_ADDRESS, "0x1234", ETH_MESSAGE, WalletType::MetaMask).await.is_err());
+        assert!(verify("0x1234", ETH_SIGNATURE, ETH_MESSAGE, WalletType::MetaMask).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mpc_signatures_are_verified_by_the_provider() {
+        let with_provider = |mpc: MockMpcVerifier| async move {
+            AuthService::verify_wallet_signature(MPC_WALLET, "signature", "message", &WalletType::MPC, &mpc).await
+        };
+        assert_eq!(with_provider(MockMpcVerifier::new()).await, Ok(true));
+        assert_eq!(with_provider(MockMpcVerifier { valid: false, ..MockMpcVerifier::new() }).await, Ok(false));
+        assert!(with_provider(MockMpcVerifier { unreachable: true, ..MockMpcVerifier::new() }).await.is_err());
+
+        // Malformed addresses are rejected before the provider is asked
+        assert!(verify("0x1234", "signature", "message", WalletType::MPC).await.is_err());
+    }
+
+    #[test]
+    fn test_refresh_tokens_are_random_256_bit() {
+        let token = AuthService::generate_refresh_token();
+        assert_eq!(hex::decode(&token).unwrap().len(), 32);
+        assert_ne!(token, AuthService::generate_refresh_token());
+
+        let hash = AuthService::hash_refresh_token(&token);
+        assert_eq!(hash.len(), 64);
+        assert_ne!(hash, token);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_login_issues_signed_tokens_and_refreshes(pool: sqlx::PgPool) {
+        use actix_web::{test, App};
+
+        const SECRET: &str = "test-secret";
+        // The mock MPC provider accepts any signature
+        let wallet = MPC_WALLET.to_string();
+        let mpc: Arc<dyn MpcWalletVerifier> = Arc::new(MockMpcVerifier::new());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ChallengeStore::new()))
+                .app_data(web::Data::new(UserRepository::new(pool.clone())))
+                .app_data(web::Data::new(RefreshTokenRepository::new(pool.clone())))
+                .app_data(web::Data::new(SessionRepository::new(pool.clone())))
+                .app_data(web::Data::new(JwtConfig::new(SECRET)))
+                .app_data(web::Data::new(MetricsRegistry::new()))
+                .app_data(web::Data::from(mpc))
+                .app_data(web::Data::new(two_factor_service(&pool)))
+                .service(get_auth_challenge)
+                .service(login_with_wallet)
+                .service(refresh_token),
+        )
+        .await;
+
+        let challenge: serde_json::Value = test::call_and_read_body_json(
+            &app,
+            test::TestRequest::get().uri(&format!("/challenge?wallet={}", wallet)).to_request(),
+        )
+        .await;
+        let login = test::TestRequest::post()
+            .uri("/login")
+            .set_json(serde_json::json!({
+                "wallet_address": wallet,
+                "signature": "s".repeat(88),
+                "message": challenge["challenge"],
+                "nonce": challenge["nonce"],
+                "wallet_type": "mpc",
+            }))
+            .to_request();
+        let login: serde_json::Value = test::call_and_read_body_json(&app, login).await;
+
+        let decode_claims = |token: &serde_json::Value| {
+            decode::<Claims>(
+                token.as_str().unwrap(),
+                &DecodingKey::from_secret(SECRET.as_bytes()),
+                &Validation::new(Algorithm::HS256),
+            )
+            .unwrap()
+            .claims
+        };
+
+        let claims = decode_claims(&login["access_token"]);
+        assert_eq!(claims.sub, login["user_id"].as_str().unwrap());
+        assert!(Uuid::parse_str(&claims.sub).is_ok());
+        assert_eq!(claims.wallet, wallet);
+        assert_eq!(claims.role, Role::User);
+        assert!(!claims.totp_verified);
+        assert_eq!(claims.exp - claims.iat, 24 * 3600);
+        assert!(Uuid::parse_str(&claims.jti).is_ok());
+        assert!(Uuid::parse_str(&claims.session_id).is_ok());
+
+        let refresh = |token: &str| {
+            test::TestRequest::post()
+                .uri("/refresh")
+                .set_json(serde_json::json!({ "refresh_token": token }))
+                .to_request()
+        };
+        let refreshed: serde_json::Value =
+            test::call_and_read_body_json(&app, refresh(login["refresh_token"].as_str().unwrap())).await;
+        let refreshed_claims = decode_claims(&refreshed["access_token"]);
+        assert_eq!(refreshed_claims.sub, claims.sub);
+        assert_eq!(refreshed_claims.session_id, claims.session_id);
+        assert_ne!(refreshed_claims.jti, claims.jti);
+
+        let forged = AuthService::generate_refresh_token();
+        assert_eq!(test::call_service(&app, refresh(&forged)).await.status(), 401);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_ethereum_wallets_sign_in_whatever_their_casing(pool: sqlx::PgPool) {
+        use actix_web::{test, App};
+        use k256::ecdsa::SigningKey;
+
+        // The key behind ETH_ADDRESS
+        let key = SigningKey::from_slice(
+            &hex::decode("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318").unwrap(),
+        )
+        .unwrap();
+        let sign = |message: &str| {
+            let prefixed = format!("\x19Ethereum Signed Message:\n{}{}", message.len(), message);
+            let (signature, recovery_id) =
+                key.sign_digest_recoverable(Keccak256::new_with_prefix(prefixed.as_bytes())).unwrap();
+            let mut bytes = signature.to_bytes().to_vec();
+            bytes.push(27 + recovery_id.to_byte());
+            format!("0x{}", hex::encode(bytes))
+        };
+
+        let mpc: Arc<dyn MpcWalletVerifier> = Arc::new(MockMpcVerifier::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ChallengeStore::new()))
+                .app_data(web::Data::new(UserRepository::new(pool.clone())))
+                .app_data(web::Data::new(RefreshTokenRepository::new(pool.clone())))
+                .app_data(web::Data::new(SessionRepository::new(pool.clone())))
+                .app_data(web::Data::new(JwtConfig::new("test-secret")))
+                .app_data(web::Data::new(MetricsRegistry::new()))
+                .app_data(web::Data::from(mpc))
+                .app_data(web::Data::new(two_factor_service(&pool)))
+                .service(get_auth_challenge)
+                .service(login_with_wallet),
+        )
+        .await;
+
+        let mut user_ids = Vec::new();
+        for wallet in [ETH_ADDRESS.to_lowercase(), ETH_ADDRESS.to_string()] {
+            let challenge: serde_json::Value = test::call_and_read_body_json(
+                &app,
+                test::TestRequest::get().uri(&format!("/challenge?wallet={}", wallet)).to_request(),
+            )
+            .await;
+            let message = challenge["challenge"].as_str().unwrap();
+            let login = test::TestRequest::post()
+                .uri("/login")
+                .set_json(serde_json::json!({
+                    "wallet_address": wallet,
+                    "signature": sign(message),
+                    "message": message,
+                    "nonce": challenge["nonce"],
+                    "wallet_type": "metamask",
+                }))
+                .to_request();
+            let login: serde_json::Value = test::call_and_read_body_json(&app, login).await;
+            assert_eq!(claims_of(login["access_token"].as_str().unwrap()).wallet, ETH_ADDRESS);
+            user_ids.push(login["user_id"].as_str().unwrap().to_string());
+        }
+        assert_eq!(user_ids[0], user_ids[1]);
+
+        let stored: Vec<String> = sqlx::query_scalar("SELECT wallet_address FROM users").fetch_all(&pool).await.unwrap();
+        assert_eq!(stored, [ETH_ADDRESS]);
+    }
+
+    fn two_factor_service(pool: &sqlx::PgPool) -> TwoFactorService {
+        TwoFactorService::new(
+            Arc::new(UserRepository::new(pool.clone())),
+            Arc::new(crate::repositories::TwoFactorRepository::new(pool.clone())),
+            crate::services::SecretCipher::new("encryption-key"),
+        )
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_login_requires_totp_code_once_enabled(pool: sqlx::PgPool) {
+        use actix_web::{test, App};
+        use totp_rs::{Algorithm, Secret, TOTP};
+
+        let wallet = MPC_WALLET.to_string();
+        let user_id = UserRepository::new(pool.clone()).find_or_create_by_wallet(&wallet).await.unwrap().id;
+        let two_factor = two_factor_service(&pool);
+        let setup = two_factor.setup(user_id).await.unwrap();
+        let secret = Secret::Encoded(setup.secret).to_bytes().unwrap();
+        let authenticator = TOTP::new(Algorithm::SHA1, 6, 1, 30, secret, None, wallet.clone()).unwrap();
+        two_factor.confirm(user_id, &authenticator.generate_current().unwrap()).await.unwrap();
+
+        let mpc: Arc<dyn MpcWalletVerifier> = Arc::new(MockMpcVerifier::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ChallengeStore::new()))
+                .app_data(web::Data::new(UserRepository::new(pool.clone())))
+                .app_data(web::Data::new(RefreshTokenRepository::new(pool.clone())))
+                .app_data(web::Data::new(SessionRepository::new(pool.clone())))
+                .app_data(web::Data::new(JwtConfig::new("test-secret")))
+                .app_data(web::Data::new(MetricsRegistry::new()))
+                .app_data(web::Data::from(mpc))
+                .app_data(web::Data::new(two_factor))
+                .service(get_auth_challenge)
+                .service(login_with_wallet)
+                .service(refresh_token),
+        )
+        .await;
+
+        let login = |totp_code: Option<String>| {
+            let app = &app;
+            let wallet = wallet.clone();
+            async move {
+                let challenge: serde_json::Value = test::call_and_read_body_json(
+                    app,
+                    test::TestRequest::get().uri(&format!("/challenge?wallet={}", wallet)).to_request(),
+                )
+                .await;
+                let request = test::TestRequest::post()
+                    .uri("/login")
+                    .set_json(serde_json::json!({
+                        "wallet_address": wallet,
+                        "signature": "s".repeat(88),
+                        "message": challenge["challenge"],
+                        "nonce": challenge["nonce"],
+                        "wallet_type": "mpc",
+                        "totp_code": totp_code,
+                    }))
+                    .to_request();
+                let response = test::call_service(app, request).await;
+                (response.status().as_u16(), test::read_body_json::<serde_json::Value, _>(response).await)
+            }
+        };
+
+        let (status, body) = login(None).await;
+        assert_eq!((status, body["error_code"].as_str()), (401, Some("totp_required")));
+        let (status, body) = login(Some("000000".to_string())).await;
+        if authenticator.generate_current().unwrap() != "000000" {
+            assert_eq!((status, body["error_code"].as_str()), (401, Some("invalid_totp_code")));
+        }
+
+        let (status, body) = login(Some(authenticator.generate_current().unwrap())).await;
+        assert_eq!(status, 200);
+        let claims = claims_of(body["access_token"].as_str().unwrap());
+        assert!(claims.totp_verified);
+
+        // Access tokens refreshed in the session keep its second factor
+        let refreshed: serde_json::Value = test::call_and_read_body_json(
+            &app,
+            test::TestRequest::post()
+                .uri("/refresh")
+                .set_json(serde_json::json!({ "refresh_token": body["refresh_token"] }))
+                .to_request(),
+        )
+        .await;
+        let refreshed = claims_of(refreshed["access_token"].as_str().unwrap());
+        assert!(refreshed.totp_verified);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_sessions_are_listed_and_revoked_individually(pool: sqlx::PgPool) {
+        use crate::middleware::JwtMiddleware;
+        use actix_web::{test, App};
+
+        const SECRET: &str = "test-secret";
+        let wallet = MPC_WALLET.to_string();
+        let mpc: Arc<dyn MpcWalletVerifier> = Arc::new(MockMpcVerifier::new());
+        let blacklist = web::Data::new(TokenBlacklist::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ChallengeStore::new()))
+                .app_data(web::Data::new(UserRepository::new(pool.clone())))
+                .app_data(web::Data::new(RefreshTokenRepository::new(pool.clone())))
+                .app_data(web::Data::new(SessionRepository::new(pool.clone())))
+                .app_data(web::Data::new(JwtConfig::new(SECRET)))
+                .app_data(web::Data::new(MetricsRegistry::new()))
+                .app_data(web::Data::from(mpc))
+                .app_data(web::Data::new(two_factor_service(&pool)))
+                .app_data(blacklist.clone())
+                .service(get_auth_challenge)
+                .service(login_with_wallet)
+                .service(refresh_token)
+                .service(logout)
+                .service(
+                    web::scope("/sessions")
+                        .wrap(JwtMiddleware::new(JwtConfig::new(SECRET), blacklist))
+                        .service(list_sessions)
+                        .service(revoke_other_sessions)
+                        .service(revoke_session),
+                ),
+        )
+        .await;
+
+        // Signs in from a device, returning its access and refresh tokens
+        let login = |user_agent: &'static str| {
+            let app = &app;
+            let wallet = wallet.clone();
+            async move {
+                let challenge: serde_json::Value = test::call_and_read_body_json(
+                    app,
+                    test::TestRequest::get().uri(&format!("/challenge?wallet={}", wallet)).to_request(),
+                )
+                .await;
+                let request = test::TestRequest::post()
+                    .uri("/login")
+                    .insert_header(("User-Agent", user_agent))
+                    .insert_header(("X-Forwarded-For", "203.0.113.7"))
+                    .set_json(serde_json::json!({
+                        "wallet_address": wallet,
+                        "signature": "s".repeat(88),
+                        "message": challenge["challenge"],
+                        "nonce": challenge["nonce"],
+                        "wallet_type": "mpc",
+                    }))
+                    .to_request();
+                let body: serde_json::Value = test::call_and_read_body_json(app, request).await;
+                (body["access_token"].as_str().unwrap().to_string(), body["refresh_token"].clone())
+            }
+        };
+        let call = |method: actix_web::http::Method, uri: String, token: &str| {
+            let request = test::TestRequest::default()
+                .method(method)
+                .uri(&uri)
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request();
+            test::call_service(&app, request)
+        };
+        let session_id = |token: &str| claims_of(token).session_id;
+        let list = |token: &str| {
+            let request = call(actix_web::http::Method::GET, "/sessions".to_string(), token);
+            async move { test::read_body_json::<serde_json::Value, _>(request.await).await }
+        };
+
+        let (laptop, _) = login("Laptop").await;
+        let (phone, phone_refresh) = login("Phone").await;
+        let (tablet, _) = login("Tablet").await;
+
+        let sessions = list(&laptop).await;
+        let sessions = sessions["sessions"].as_array().unwrap();
+        assert_eq!(sessions.len(), 3);
+        // Using a token marks its session as the most recently active
+        assert_eq!(sessions[0]["id"], session_id(&laptop).as_str());
+        assert_eq!(sessions[0]["current"], true);
+        assert!(sessions[1..].iter().all(|session| session["current"] == false));
+        assert_eq!(sessions[0]["user_agent"], "Laptop");
+        assert_eq!(sessions[0]["ip_address"], "203.0.113.7");
+        assert_eq!(sessions[0]["device_fingerprint"], AuthService::device_fingerprint("Laptop"));
+        assert!(sessions[0].get("jti").is_none());
+
+        // Revoking the phone signs out only the phone, for its access and refresh tokens
+        let uri = format!("/sessions/{}", session_id(&phone));
+        assert_eq!(call(actix_web::http::Method::DELETE, uri.clone(), &laptop).await.status(), 204);
+        assert_eq!(call(actix_web::http::Method::GET, "/sessions".to_string(), &phone).await.status(), 401);
+        let refresh = test::TestRequest::post()
+            .uri("/refresh")
+            .set_json(serde_json::json!({ "refresh_token": phone_refresh }))
+            .to_request();
+        assert_eq!(test::call_service(&app, refresh).await.status(), 401);
+        assert_eq!(list(&tablet).await["sessions"].as_array().unwrap().len(), 2);
+        assert_eq!(call(actix_web::http::Method::DELETE, uri, &laptop).await.status(), 404);
+        let invalid = call(actix_web::http::Method::DELETE, "/sessions/phone".to_string(), &laptop).await;
+        assert_eq!(invalid.status(), 400);
+
+        // Logging out everywhere else keeps the session asking for it
+        let (desktop, _) = login("Desktop").await;
+        let response = call(actix_web::http::Method::DELETE, "/sessions".to_string(), &laptop).await;
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(body["revoked"], 2);
+        for token in [&tablet, &desktop] {
+            assert_eq!(call(actix_web::http::Method::GET, "/sessions".to_string(), token).await.status(), 401);
+        }
+        let sessions = list(&laptop).await;
+        assert_eq!(sessions["sessions"].as_array().unwrap().len(), 1);
+        assert_eq!(sessions["sessions"][0]["id"], session_id(&laptop).as_str());
+
+        // Revoked sessions stay revoked without the blacklist, e.g. after a restart
+        let restarted = test::init_service(
+            App::new().app_data(web::Data::new(SessionRepository::new(pool.clone()))).service(
+                web::scope("/sessions")
+                    .wrap(JwtMiddleware::new(JwtConfig::new(SECRET), web::Data::new(TokenBlacklist::new())))
+                    .service(list_sessions),
+            ),
+        )
+        .await;
+        for (token, status) in [(&laptop, 200), (&phone, 401), (&tablet, 401)] {
+            let request = test::TestRequest::get()
+                .uri("/sessions")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request();
+            assert_eq!(test::call_service(&restarted, request).await.status(), status);
+        }
+
+        // Logging out ends the session, for its refresh token too
+        let log_out = |token: &str| {
+            let request = test::TestRequest::post()
+                .uri("/logout")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request();
+            test::call_service(&app, request)
+        };
+        let (kiosk, kiosk_refresh) = login("Kiosk").await;
+        assert_eq!(log_out(&kiosk).await.status(), 200);
+        assert_eq!(log_out(&kiosk).await.status(), 401);
+        let refresh = test::TestRequest::post()
+            .uri("/refresh")
+            .set_json(serde_json::json!({ "refresh_token": kiosk_refresh }))
+            .to_request();
+        assert_eq!(test::call_service(&app, refresh).await.status(), 401);
+        assert_eq!(list(&laptop).await["sessions"].as_array().unwrap().len(), 1);
+
+        // Tokens this server did not sign are refused, whatever expiry they claim
+        let forged = Claims { exp: usize::MAX, ..claims_of(&laptop) };
+        let forged = encode(&Header::default(), &forged, &EncodingKey::from_secret(b"other-secret")).unwrap();
+        assert_eq!(log_out(&forged).await.status(), 401);
+    }
+}
@@ -1,204 +1,1377 @@
 use actix_web::{get, post, put, delete, web, HttpResponse, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Deserialize)]
+use crate::handlers::auth::Claims;
+use crate::handlers::errors::ApiError;
+use crate::models::content::{ContentRecord, ContentSearchHit, ReactionType};
+use crate::models::content_attribution::AttributionType;
+use crate::models::moderation::FlagReason;
+use crate::models::Platform;
+use crate::models::pagination::Cursor;
+use crate::models::trending::TrendingScore;
+use crate::models::user_event::UserEvent;
+use crate::repositories::{
+    ContentFilter, ContentFingerprintRepository, ContentRepository, NewContent, RepositoryError, TrendingContent,
+    UserEventRepository,
+};
+use crate::services::farcaster::normalize_cast_hash;
+use crate::services::{
+    ContentAttributionService, ContentClusterAnalyzer, ContentCreationData, ContentFingerprintService,
+    ContentModerationService, ContentNormalizer, ContentSimilarityService, ContentVersioningService, MentionLinker,
+    ModerationError, PlatformNormalizer, RewardService, TrendingScoreService,
+};
+
+/// Default and maximum page sizes for content listings
+const DEFAULT_PAGE_LIMIT: u32 = 20;
+const MAX_PAGE_LIMIT: u32 = 100;
+/// Default number of related content items
+const DEFAULT_RELATED_LIMIT: u32 = 10;
+
+#[derive(Deserialize, ToSchema)]
 pub struct CreateContentRequest {
     pub user_id: String,
-    pub platform: String,
+    pub platform: Platform,
     pub external_id: String,
     pub content_type: String,
     pub title: String,
     pub body: String,
     pub media_urls: Vec<String>,
     pub tags: Vec<String>,
+    /// Reaction counts by type, or by the platform's own name for it, e.g. `retweet`
+    #[serde(default)]
+    pub reactions: HashMap<String, u64>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ContentResponse {
     pub id: String,
     pub user_id: String,
-    pub platform: String,
+    pub platform: Platform,
     pub external_id: String,
     pub content_type: String,
     pub title: String,
     pub body: String,
     pub media_urls: Vec<String>,
     pub tags: Vec<String>,
+    /// Topic tags extracted from the title and body
+    pub auto_tags: Vec<String>,
+    pub reactions: HashMap<ReactionType, u64>,
     pub echo_index: f64,
     pub propagation_count: u32,
     pub total_rewards: f64,
     pub status: String,
     pub created_at: String,
     pub updated_at: String,
+    /// Short-term virality, for content whose Echo Index changed lately
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trending_score: Option<TrendingScore>,
 }
 
-/// Create new content
+/// Create new content by `user_id`, who earns its rewards. Only admins can create content
+/// for another user.
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "create_content",
+    tag = "content",
+    request_body = CreateContentRequest,
+    responses(
+        (status = 201, description = "Content created", body = ContentResponse),
+        (status = 400, description = "Invalid content"),
+        (status = 403, description = "`user_id` is another user and the caller is not an admin"),
+        (status = 409, description = "Near-duplicate of existing content, whose ID is in `details.existing_id`"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[post("")]
-pub async fn create_content(content_data: web::Json<CreateContentRequest>) -> Result<HttpResponse> {
-    let content = ContentResponse {
-        id: Uuid::new_v4().to_string(),
-        user_id: content_data.user_id.clone(),
-        platform: content_data.platform.clone(),
-        external_id: content_data.external_id.clone(),
-        content_type: content_data.content_type.clone(),
-        title: content_data.title.clone(),
-        body: content_data.body.clone(),
-        media_urls: content_data.media_urls.clone(),
-        tags: content_data.tags.clone(),
-        echo_index: 0.0, // Will be calculated by Echo Index engine
-        propagation_count: 0,
-        total_rewards: 0.0,
-        status: "active".to_string(),
-        created_at: chrono::Utc::now().to_rfc3339(),
-        updated_at: chrono::Utc::now().to_rfc3339(),
-    };
-
-    Ok(HttpResponse::Created().json(json!({
-        "success": true,
-        "data": content,
-        "timestamp": chrono::Utc::now().to_rfc3339()
-    })))
+#[allow(clippy::too_many_arguments)]
+pub async fn create_content(
+    content_data: web::Json<CreateContentRequest>,
+    claims: web::ReqData<Claims>,
+    repository: web::Data<ContentRepository>,
+    events: web::Data<UserEventRepository>,
+    fingerprints: web::Data<ContentFingerprintService>,
+    fingerprint_repository: web::Data<ContentFingerprintRepository>,
+    similarity: web::Data<ContentSimilarityService>,
+    mentions: web::Data<MentionLinker>,
+    rewards: web::Data<RwLock<RewardService>>,
+) -> Result<HttpResponse> {
+    let new_content = match content_data.into_inner().into_new_content() {
+        Ok(new_content) => new_content,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+    if claims.sub != new_content.user_id.to_string() && !claims.is_verified_admin() {
+        return Err(ApiError::Forbidden("Only admins can create content for another user".to_string()).into());
+    }
+
+    // The same text resubmitted from another account or platform would earn rewards twice
+    let fingerprint = fingerprints.fingerprint(&new_content.body);
+    if let Some(fingerprint) = &fingerprint {
+        let candidates = match fingerprint_repository.candidates(fingerprint).await {
+            Ok(candidates) => candidates,
+            Err(e) => return Err(repository_error(e).into()),
+        };
+        if let Some((duplicate_of, similarity)) = fingerprints.best_duplicate(fingerprint, &candidates) {
+            log::info!("Rejected content {:.0}% similar to {}", similarity * 100.0, duplicate_of);
+            return Err(ApiError::Conflict { resource_type: "content".to_string(), existing_id: duplicate_of }.into());
+        }
+    }
+
+    match repository.create(&new_content).await {
+        Ok(record) => {
+            if let Some(fingerprint) = &fingerprint {
+                if let Err(e) = fingerprint_repository.store(record.id, fingerprint).await {
+                    log::warn!("Failed to store fingerprint of {}: {}", record.id, e);
+                }
+            }
+            if let Err(e) = similarity.index(&record).await {
+                log::warn!("Failed to index {} for related content: {}", record.id, e);
+            }
+            if let Err(e) = mentions.link(&record).await {
+                log::warn!("Failed to link mentions of {}: {}", record.id, e);
+            }
+
+            let event = UserEvent::ContentCreated {
+                content_id: record.id,
+                platform: record.platform.clone(),
+                initial_echo: record.echo_index,
+            };
+            if let Err(e) = events.record(record.user_id, &event).await {
+                log::warn!("Failed to record creation of {} on timeline: {}", record.id, e);
+            }
+            // Also counts towards the creator's daily streak
+            let creation = ContentCreationData::for_content(&record);
+            let reward = rewards
+                .write()
+                .await
+                .process_content_creation(record.user_id.to_string(), record.id.to_string(), creation)
+                .await;
+            if let Err(e) = reward {
+                log::warn!("Failed to reward creation of {}: {}", record.id, e);
+            }
+
+            Ok(HttpResponse::Created().json(json!({
+                "success": true,
+                "data": ContentResponse::from(record),
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(e) => Err(repository_error(e).into()),
+    }
 }
 
 /// Get content by ID
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "get_content",
+    tag = "content",
+    params(("content_id" = Uuid, Path, description = "Content ID")),
+    responses(
+        (status = 200, description = "The content, live or archived", body = ContentResponse),
+        (status = 400, description = "Malformed content ID"),
+        (status = 404, description = "No such content"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[get("/{content_id}")]
-pub async fn get_content(path: web::Path<String>) -> Result<HttpResponse> {
-    let content_id = path.into_inner();
-    
-    // Mock content data
-    let content = ContentResponse {
-        id: content_id,
-        user_id: "user_123".to_string(),
-        platform: "twitter".to_string(),
-        external_id: "tweet_456".to_string(),
-        content_type: "text".to_string(),
-        title: "The Future of Decentralized Social Networks".to_string(),
-        body: "Exploring how blockchain technology is revolutionizing social media and content monetization...".to_string(),
-        media_urls: vec![],
-        tags: vec!["blockchain".to_string(), "social".to_string(), "decentralized".to_string()],
-        echo_index: 78.5,
-        propagation_count: 25,
-        total_rewards: 150.0,
-        status: "active".to_string(),
-        created_at: chrono::Utc::now().to_rfc3339(),
-        updated_at: chrono::Utc::now().to_rfc3339(),
+pub async fn get_content(
+    path: web::Path<String>,
+    repository: web::Data<ContentRepository>,
+    trending: web::Data<TrendingScoreService>,
+) -> Result<HttpResponse> {
+    let content_id = match parse_content_id(&path) {
+        Ok(content_id) => content_id,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    // Archived content is served from cold storage in the same shape
+    let record = match repository.find_by_id(content_id).await {
+        Err(RepositoryError::NotFound) => repository.find_archived(content_id).await.map(|archive| archive.content),
+        result => result,
+    };
+
+    let mut response = match record {
+        Ok(record) => ContentResponse::from(record),
+        Err(e) => return Err(repository_error(e).into()),
     };
+    match trending.score(content_id).await {
+        Ok(score) => response.trending_score = score,
+        Err(e) => log::warn!("Failed to load trending score of {}: {}", content_id, e),
+    }
 
     Ok(HttpResponse::Ok().json(json!({
         "success": true,
-        "data": content,
+        "data": response,
         "timestamp": chrono::Utc::now().to_rfc3339()
     })))
 }
 
-/// List content with pagination
+/// Live content gaining Echo Index fastest, as of the latest hourly refresh of trending
+/// scores
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "get_trending_content",
+    tag = "content",
+    params(TrendingContentQuery),
+    responses(
+        (status = 200, description = "Trending content, fastest gaining first", body = [ContentResponse]),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/trending")]
+pub async fn get_trending_content(
+    query: web::Query<TrendingContentQuery>,
+    trending: web::Data<TrendingScoreService>,
+) -> Result<HttpResponse> {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+
+    match trending.trending(query.platform.as_ref(), limit).await {
+        Ok(content) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": content.into_iter().map(ContentResponse::from).collect::<Vec<_>>(),
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(repository_error(e).into()),
+    }
+}
+
+/// List content with cursor-based pagination
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "list_content",
+    tag = "content",
+    params(ListContentQuery),
+    responses(
+        (status = 200, description = "A page of content", body = [ContentResponse]),
+        (status = 400, description = "Invalid cursor or user ID"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[get("")]
-pub async fn list_content(query: web::Query<ListContentQuery>) -> Result<HttpResponse> {
-    // Mock content list
-    let contents = vec![
-        ContentResponse {
-            id: "content_1".to_string(),
-            user_id: "user_123".to_string(),
-            platform: "twitter".to_string(),
-            external_id: "tweet_456".to_string(),
-            content_type: "text".to_string(),
-            title: "The Future of Decentralized Social Networks".to_string(),
-            body: "Exploring how blockchain technology is revolutionizing...".to_string(),
-            media_urls: vec![],
-            tags: vec!["blockchain".to_string(), "social".to_string()],
-            echo_index: 78.5,
-            propagation_count: 25,
-            total_rewards: 150.0,
-            status: "active".to_string(),
-            created_at: chrono::Utc::now().to_rfc3339(),
-            updated_at: chrono::Utc::now().to_rfc3339(),
-        },
-        ContentResponse {
-            id: "content_2".to_string(),
-            user_id: "user_124".to_string(),
-            platform: "telegram".to_string(),
-            external_id: "msg_789".to_string(),
-            content_type: "image".to_string(),
-            title: "EchoLayer Architecture Diagram".to_string(),
-            body: "Visual representation of the EchoLayer ecosystem".to_string(),
-            media_urls: vec!["https://example.com/diagram.png".to_string()],
-            tags: vec!["architecture".to_string(), "diagram".to_string()],
-            echo_index: 85.2,
-            propagation_count: 42,
-            total_rewards: 220.0,
-            status: "active".to_string(),
-            created_at: chrono::Utc::now().to_rfc3339(),
-            updated_at: chrono::Utc::now().to_rfc3339(),
-        }
-    ];
+pub async fn list_content(
+    query: web::Query<ListContentQuery>,
+    repository: web::Data<ContentRepository>,
+) -> Result<HttpResponse> {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+
+    let after = match query.after.as_deref().map(Cursor::decode).transpose() {
+        Ok(after) => after,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    let user_id = match query.user_id.as_deref().map(Uuid::parse_str).transpose() {
+        Ok(user_id) => user_id,
+        Err(_) => return Err(ApiError::bad_request("user_id must be a valid UUID").into()),
+    };
+
+    let filter = ContentFilter {
+        user_id,
+        platform: query.platform.clone(),
+        status: query.status.clone(),
+    };
+
+    let page = match repository.list(&filter, after, limit).await {
+        Ok(page) => page.map(ContentResponse::from),
+        Err(e) => return Err(repository_error(e).into()),
+    };
+
+    let mut response = HttpResponse::Ok();
+    if query.page.is_some() {
+        response.insert_header(("Warning", "299 - \"page is deprecated; use the after cursor\""));
+    }
+
+    Ok(response.json(json!({
+        "success": true,
+        "data": page.data,
+        "next_cursor": page.next_cursor,
+        "has_more": page.has_more,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Full-text search over content title, body and tags, most relevant first
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "search_content",
+    tag = "content",
+    params(SearchContentQuery),
+    responses(
+        (status = 200, description = "A page of matching content", body = [SearchResultResponse]),
+        (status = 400, description = "Empty query or invalid cursor"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/search")]
+pub async fn search_content(
+    query: web::Query<SearchContentQuery>,
+    repository: web::Data<ContentRepository>,
+) -> Result<HttpResponse> {
+    let search = query.q.trim();
+    if search.is_empty() {
+        return Err(ApiError::bad_request("q must not be empty").into());
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
 
-    let pagination = json!({
-        "page": query.page.unwrap_or(1),
-        "limit": query.limit.unwrap_or(20),
-        "total": 2,
-        "total_pages": 1
-    });
+    let after = match query.after.as_deref().map(Cursor::decode).transpose() {
+        Ok(after) => after,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    let page = match repository
+        .search(search, query.platform.clone(), query.min_echo, limit, after)
+        .await
+    {
+        Ok(page) => page.map(SearchResultResponse::from),
+        Err(e) => return Err(repository_error(e).into()),
+    };
 
     Ok(HttpResponse::Ok().json(json!({
         "success": true,
-        "data": contents,
-        "pagination": pagination,
+        "data": page.data,
+        "next_cursor": page.next_cursor,
+        "has_more": page.has_more,
         "timestamp": chrono::Utc::now().to_rfc3339()
     })))
 }
 
-/// Update content
+/// Update content. Only its author or an admin can, and the author stays the same.
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "update_content",
+    tag = "content",
+    params(("content_id" = Uuid, Path, description = "Content ID")),
+    request_body = CreateContentRequest,
+    responses(
+        (status = 200, description = "Content updated", body = ContentResponse),
+        (status = 400, description = "Malformed content ID or invalid content"),
+        (status = 403, description = "Not the author of the content"),
+        (status = 404, description = "No such content"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[put("/{content_id}")]
 pub async fn update_content(
     path: web::Path<String>,
-    content_data: web::Json<CreateContentRequest>
+    content_data: web::Json<CreateContentRequest>,
+    claims: web::ReqData<Claims>,
+    repository: web::Data<ContentRepository>,
+    similarity: web::Data<ContentSimilarityService>,
+    mentions: web::Data<MentionLinker>,
+) -> Result<HttpResponse> {
+    let content_id = match parse_content_id(&path) {
+        Ok(content_id) => content_id,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    let changes = match content_data.into_inner().into_new_content() {
+        Ok(changes) => changes,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+    forbid_other_author(&repository, content_id, &claims, "Only the author can edit content").await?;
+
+    // The previous title and body are kept as a version, attributed to the editor
+    let updated_by = Uuid::parse_str(&claims.sub).ok();
+    match repository.update(content_id, &changes, updated_by).await {
+        Ok(record) => {
+            if let Err(e) = similarity.index(&record).await {
+                log::warn!("Failed to reindex {} for related content: {}", record.id, e);
+            }
+            // Users newly mentioned by the edit are linked; earlier mentions stay linked
+            if let Err(e) = mentions.link(&record).await {
+                log::warn!("Failed to link mentions of {}: {}", record.id, e);
+            }
+
+            Ok(HttpResponse::Ok().json(json!({
+                "success": true,
+                "data": ContentResponse::from(record),
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(e) => Err(repository_error(e).into()),
+    }
+}
+
+/// Previous versions of content, newest first
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "list_content_versions",
+    tag = "content",
+    params(("content_id" = Uuid, Path, description = "Content ID")),
+    responses(
+        (status = 200, description = "Previous versions, newest first", body = VersionHistory),
+        (status = 400, description = "Malformed content ID"),
+        (status = 404, description = "No such content"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{content_id}/versions")]
+pub async fn list_content_versions(
+    path: web::Path<String>,
+    versioning: web::Data<ContentVersioningService>,
 ) -> Result<HttpResponse> {
-    let content_id = path.into_inner();
-    
-    let content = ContentResponse {
-        id: content_id,
-        user_id: content_data.user_id.clone(),
-        platform: content_data.platform.clone(),
-        external_id: content_data.external_id.clone(),
-        content_type: content_data.content_type.clone(),
-        title: content_data.title.clone(),
-        body: content_data.body.clone(),
-        media_urls: content_data.media_urls.clone(),
-        tags: content_data.tags.clone(),
-        echo_index: 78.5,
-        propagation_count: 25,
-        total_rewards: 150.0,
-        status: "active".to_string(),
-        created_at: "2024-01-01T12:00:00Z".to_string(),
-        updated_at: chrono::Utc::now().to_rfc3339(),
+    let content_id = match parse_content_id(&path) {
+        Ok(content_id) => content_id,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
     };
 
+    match versioning.history(content_id).await {
+        Ok(history) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": history,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(repository_error(e).into()),
+    }
+}
+
+/// A previous version of content
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "get_content_version",
+    tag = "content",
+    params(
+        ("content_id" = Uuid, Path, description = "Content ID"),
+        ("version_number" = i32, Path, description = "Version number, from 1"),
+    ),
+    responses(
+        (status = 200, description = "The version", body = ContentVersion),
+        (status = 400, description = "Malformed content ID"),
+        (status = 404, description = "No such content or version"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{content_id}/versions/{version_number}")]
+pub async fn get_content_version(
+    path: web::Path<(String, i32)>,
+    versioning: web::Data<ContentVersioningService>,
+) -> Result<HttpResponse> {
+    let (content_id, version_number) = path.into_inner();
+    let content_id = match parse_content_id(&content_id) {
+        Ok(content_id) => content_id,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    match versioning.version(content_id, version_number).await {
+        Ok(version) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": version,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(repository_error(e).into()),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct VersionDiffQuery {
+    pub from: i32,
+    pub to: i32,
+}
+
+/// Line diff of the bodies of two versions of content, either of which may be the live one
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "diff_content_versions",
+    tag = "content",
+    params(("content_id" = Uuid, Path, description = "Content ID"), VersionDiffQuery),
+    responses(
+        (status = 200, description = "Line diff of the two versions", body = VersionDiff),
+        (status = 400, description = "Malformed content ID"),
+        (status = 404, description = "No such content or version"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{content_id}/diff")]
+pub async fn diff_content_versions(
+    path: web::Path<String>,
+    query: web::Query<VersionDiffQuery>,
+    versioning: web::Data<ContentVersioningService>,
+) -> Result<HttpResponse> {
+    let content_id = match parse_content_id(&path) {
+        Ok(content_id) => content_id,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    match versioning.diff(content_id, query.from, query.to).await {
+        Ok(diff) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": diff,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(repository_error(e).into()),
+    }
+}
+
+/// Content with the most similar text, most similar first. Content by the same
+/// author is left out unless `include_own=true`.
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "get_related_content",
+    tag = "content",
+    params(("content_id" = Uuid, Path, description = "Content ID"), RelatedContentQuery),
+    responses(
+        (status = 200, description = "Related content, most similar first", body = [RelatedContentResponse]),
+        (status = 400, description = "Malformed content ID"),
+        (status = 404, description = "No such content"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{content_id}/related")]
+pub async fn get_related_content(
+    path: web::Path<String>,
+    query: web::Query<RelatedContentQuery>,
+    repository: web::Data<ContentRepository>,
+    similarity: web::Data<ContentSimilarityService>,
+) -> Result<HttpResponse> {
+    let content_id = match parse_content_id(&path) {
+        Ok(content_id) => content_id,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_RELATED_LIMIT).clamp(1, MAX_PAGE_LIMIT) as usize;
+    let include_own = query.include_own.unwrap_or(false);
+
+    let record = match repository.find_by_id(content_id).await {
+        Ok(record) => record,
+        Err(e) => return Err(repository_error(e).into()),
+    };
+
+    let mut related = similarity.find_similar(content_id, limit, include_own).await;
+    if let Err(RepositoryError::NotFound) = related {
+        // Content created before similarity indexing is indexed on first use
+        related = match similarity.index(&record).await {
+            Ok(()) => similarity.find_similar(content_id, limit, include_own).await,
+            Err(e) => Err(e),
+        };
+    }
+    let related = match related {
+        Ok(related) => related,
+        // No indexable words in the text
+        Err(RepositoryError::NotFound) => Vec::new(),
+        Err(e) => return Err(repository_error(e).into()),
+    };
+
+    let ids: Vec<Uuid> = related.iter().map(|(id, _)| *id).collect();
+    let mut records: HashMap<Uuid, ContentRecord> = match repository.find_by_ids(&ids).await {
+        Ok(records) => records.into_iter().map(|record| (record.id, record)).collect(),
+        Err(e) => return Err(repository_error(e).into()),
+    };
+    let data: Vec<RelatedContentResponse> = related
+        .into_iter()
+        .filter_map(|(id, similarity)| {
+            records.remove(&id).map(|record| RelatedContentResponse {
+                content: ContentResponse::from(record),
+                similarity,
+            })
+        })
+        .collect();
+
     Ok(HttpResponse::Ok().json(json!({
         "success": true,
-        "data": content,
+        "data": data,
         "timestamp": chrono::Utc::now().to_rfc3339()
     })))
 }
 
-/// Delete content
+/// The topic cluster the content belongs to, with the content in it most similar to it
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "get_content_cluster",
+    tag = "content",
+    params(("content_id" = Uuid, Path, description = "Content ID")),
+    responses(
+        (status = 200, description = "The content's topic cluster", body = ClusterMembership),
+        (status = 400, description = "Malformed content ID"),
+        (status = 404, description = "Content is not in any cluster"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{content_id}/cluster")]
+pub async fn get_content_cluster(
+    path: web::Path<String>,
+    clusters: web::Data<ContentClusterAnalyzer>,
+) -> Result<HttpResponse> {
+    let content_id = match parse_content_id(&path) {
+        Ok(content_id) => content_id,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    match clusters.cluster_of(content_id).await {
+        Ok(Some(membership)) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": membership,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        // Unknown, without indexable text, or created since the clusters were computed
+        Ok(None) => Err(ApiError::NotFound("Content is not in any cluster".to_string()).into()),
+        Err(e) => Err(repository_error(e).into()),
+    }
+}
+
+/// Delete content. Only its author or an admin can.
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "delete_content",
+    tag = "content",
+    params(("content_id" = Uuid, Path, description = "Content ID")),
+    responses(
+        (status = 200, description = "Content deleted"),
+        (status = 400, description = "Malformed content ID"),
+        (status = 403, description = "Not the author of the content"),
+        (status = 404, description = "No such content"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[delete("/{content_id}")]
-pub async fn delete_content(path: web::Path<String>) -> Result<HttpResponse> {
-    let _content_id = path.into_inner();
-    
-    Ok(HttpResponse::Ok().json(json!({
-        "success": true,
-        "message": "Content deleted successfully",
-        "timestamp": chrono::Utc::now().to_rfc3339()
-    })))
+pub async fn delete_content(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    repository: web::Data<ContentRepository>,
+) -> Result<HttpResponse> {
+    let content_id = match parse_content_id(&path) {
+        Ok(content_id) => content_id,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+    forbid_other_author(&repository, content_id, &claims, "Only the author can delete content").await?;
+
+    match repository.soft_delete(content_id).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": "Content deleted successfully",
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(repository_error(e).into()),
+    }
 }
 
-#[derive(Deserialize)]
+/// Reject changes to content made by anyone but its author or an admin
+async fn forbid_other_author(
+    repository: &ContentRepository,
+    content_id: Uuid,
+    claims: &Claims,
+    message: &str,
+) -> Result<(), ApiError> {
+    let record = repository.find_by_id(content_id).await.map_err(repository_error)?;
+    if claims.sub != record.user_id.to_string() && !claims.is_verified_admin() {
+        return Err(ApiError::Forbidden(message.to_string()));
+    }
+    Ok(())
+}
+
+/// Flag content as harmful or spammy; enough flags hold it for moderator review
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "flag_content",
+    tag = "content",
+    params(("content_id" = Uuid, Path, description = "Content ID")),
+    request_body = FlagContentRequest,
+    responses(
+        (
+            status = 201,
+            description = "Flag recorded, with the content's flag count and whether it is held for review",
+            body = FlagOutcome
+        ),
+        (status = 400, description = "Malformed content ID"),
+        (status = 404, description = "No such content"),
+        (status = 409, description = "Content already flagged by the user"),
+        (status = 429, description = "Daily flag limit reached"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/{content_id}/flag")]
+pub async fn flag_content(
+    path: web::Path<String>,
+    request: web::Json<FlagContentRequest>,
+    claims: web::ReqData<Claims>,
+    moderation: web::Data<ContentModerationService>,
+) -> Result<HttpResponse> {
+    let content_id = match parse_content_id(&path) {
+        Ok(content_id) => content_id,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+    let Ok(flagged_by) = Uuid::parse_str(&claims.sub) else {
+        return Err(ApiError::bad_request("Only users can flag content").into());
+    };
+
+    match moderation.flag(content_id, flagged_by, request.into_inner().reason, chrono::Utc::now()).await {
+        Ok(outcome) => Ok(HttpResponse::Created().json(json!({
+            "success": true,
+            "data": outcome,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e @ ModerationError::DailyLimitReached) => Err(ApiError::LimitReached(e.to_string()).into()),
+        Err(ModerationError::Repository(RepositoryError::Conflict(_))) => {
+            Err(repository_error(RepositoryError::Conflict("You have already flagged this content".to_string())).into())
+        }
+        Err(ModerationError::Repository(e)) => Err(repository_error(e).into()),
+    }
+}
+
+/// Attribute content to the original it is the same content as, e.g. a LinkedIn cross-post
+/// of a Medium article. Only the author of the secondary content or an admin can link it.
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "link_content",
+    tag = "content",
+    request_body = LinkContentRequest,
+    responses(
+        (status = 201, description = "Secondary content attributed to the primary", body = ContentAttribution),
+        (status = 400, description = "Malformed content IDs"),
+        (status = 403, description = "Not the author of the secondary content"),
+        (status = 404, description = "No such content"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/link")]
+pub async fn link_content(
+    request: web::Json<LinkContentRequest>,
+    claims: web::ReqData<Claims>,
+    repository: web::Data<ContentRepository>,
+    attributions: web::Data<ContentAttributionService>,
+) -> Result<HttpResponse> {
+    let request = request.into_inner();
+    let (Ok(primary_id), Ok(secondary_id)) =
+        (parse_content_id(&request.primary_content_id), parse_content_id(&request.secondary_content_id))
+    else {
+        return Err(ApiError::bad_request("primary_content_id and secondary_content_id must be valid UUIDs").into());
+    };
+
+    let secondary = match repository.find_by_id(secondary_id).await {
+        Ok(secondary) => secondary,
+        Err(e) => return Err(repository_error(e).into()),
+    };
+    if claims.sub != secondary.user_id.to_string() && !claims.is_verified_admin() {
+        return Err(ApiError::Forbidden("Only the author of the secondary content can link it".to_string()).into());
+    }
+
+    match attributions.link(primary_id, secondary_id, request.link_type).await {
+        Ok(attribution) => Ok(HttpResponse::Created().json(json!({
+            "success": true,
+            "data": attribution,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(repository_error(e).into()),
+    }
+}
+
+/// The attribution graph of content: its primary, everything attributed to that primary
+/// and the primary's Echo Index aggregated over them
+#[utoipa::path(
+    context_path = "/api/v1/content",
+    operation_id = "get_content_attributions",
+    tag = "content",
+    params(("content_id" = Uuid, Path, description = "Content ID")),
+    responses(
+        (status = 200, description = "Attribution graph of the content", body = AttributionGraph),
+        (status = 400, description = "Malformed content ID"),
+        (status = 404, description = "No such content"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{content_id}/attributions")]
+pub async fn get_content_attributions(
+    path: web::Path<String>,
+    attributions: web::Data<ContentAttributionService>,
+) -> Result<HttpResponse> {
+    let content_id = match parse_content_id(&path) {
+        Ok(content_id) => content_id,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    match attributions.attribution_graph(content_id).await {
+        Ok(graph) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": graph,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(repository_error(e).into()),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct LinkContentRequest {
+    pub primary_content_id: String,
+    pub secondary_content_id: String,
+    pub link_type: AttributionType,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct FlagContentRequest {
+    pub reason: FlagReason,
+}
+
+#[derive(Deserialize, IntoParams)]
 pub struct ListContentQuery {
+    /// Deprecated: offset pages are no longer supported, use `after`
     pub page: Option<u32>,
     pub limit: Option<u32>,
+    pub after: Option<String>,
     pub user_id: Option<String>,
-    pub platform: Option<String>,
+    pub platform: Option<Platform>,
     pub status: Option<String>,
-} 
\ No newline at end of file
+} 
+
+#[derive(Deserialize, IntoParams)]
+pub struct SearchContentQuery {
+    pub q: String,
+    pub platform: Option<Platform>,
+    pub min_echo: Option<f64>,
+    pub limit: Option<u32>,
+    pub after: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct TrendingContentQuery {
+    pub platform: Option<Platform>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct RelatedContentQuery {
+    pub limit: Option<u32>,
+    pub include_own: Option<bool>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RelatedContentResponse {
+    #[serde(flatten)]
+    pub content: ContentResponse,
+    /// Cosine similarity of the two texts' TF-IDF vectors, in (0, 1]
+    pub similarity: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SearchResultResponse {
+    #[serde(flatten)]
+    pub content: ContentResponse,
+    pub relevance_score: f64,
+}
+
+impl From<ContentSearchHit> for SearchResultResponse {
+    fn from(hit: ContentSearchHit) -> Self {
+        Self {
+            content: ContentResponse::from(hit.content),
+            relevance_score: hit.relevance_score,
+        }
+    }
+}
+
+impl From<ContentRecord> for ContentResponse {
+    fn from(record: ContentRecord) -> Self {
+        Self {
+            id: record.id.to_string(),
+            user_id: record.user_id.to_string(),
+            platform: record.platform,
+            external_id: record.external_id,
+            content_type: record.content_type,
+            title: record.title,
+            body: record.body,
+            media_urls: record.media_urls,
+            tags: record.tags,
+            auto_tags: record.auto_tags,
+            reactions: record.reactions,
+            echo_index: record.echo_index,
+            propagation_count: record.propagation_count.max(0) as u32,
+            total_rewards: record.total_rewards,
+            status: record.status,
+            created_at: record.created_at.to_rfc3339(),
+            updated_at: record.updated_at.to_rfc3339(),
+            trending_score: None,
+        }
+    }
+}
+
+impl From<TrendingContent> for ContentResponse {
+    fn from(content: TrendingContent) -> Self {
+        Self {
+            trending_score: Some(content.trending),
+            ..ContentResponse::from(content.content)
+        }
+    }
+}
+
+impl CreateContentRequest {
+    fn into_new_content(self) -> Result<NewContent, &'static str> {
+        let user_id = Uuid::parse_str(&self.user_id).map_err(|_| "user_id must be a valid UUID")?;
+        let external_id = match self.platform {
+            // Recasts are looked up on a Farcaster Hub by the cast's hash
+            Platform::Farcaster => normalize_cast_hash(&self.external_id)
+                .ok_or("external_id of a Farcaster cast must be its 0x-prefixed 20-byte hash")?,
+            _ => self.external_id,
+        };
+
+        // Hashtags in the body tag the content too
+        let mut tags = self.tags;
+        for hashtag in PlatformNormalizer::normalize(&self.body, &self.platform).hashtags {
+            if !tags.iter().any(|tag| tag.eq_ignore_ascii_case(&hashtag)) {
+                tags.push(hashtag);
+            }
+        }
+
+        let mut reactions = HashMap::new();
+        for (name, count) in self.reactions {
+            let reaction = ReactionType::parse_for(&self.platform, &name)
+                .ok_or("reactions must be reaction types the platform offers")?;
+            let total: &mut u64 = reactions.entry(reaction).or_default();
+            *total = total.saturating_add(count);
+        }
+
+        Ok(NewContent {
+            user_id,
+            platform: self.platform,
+            external_id,
+            content_type: self.content_type,
+            title: self.title,
+            body: self.body,
+            media_urls: self.media_urls,
+            tags,
+            reactions,
+        })
+    }
+}
+
+fn parse_content_id(raw: &str) -> Result<Uuid, &'static str> {
+    Uuid::parse_str(raw).map_err(|_| "content_id must be a valid UUID")
+}
+
+fn repository_error(error: RepositoryError) -> ApiError {
+    match error {
+        RepositoryError::InvalidCursor(message) | RepositoryError::InvalidInput(message) => {
+            ApiError::bad_request(message)
+        }
+        RepositoryError::NotFound => ApiError::NotFound("Content not found".to_string()),
+        RepositoryError::Conflict(message) => ApiError::StateConflict(message),
+        e @ (RepositoryError::Archive(_) | RepositoryError::Database(_)) => {
+            tracing::error!(error = %e, "Content query failed");
+            ApiError::internal()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use crate::handlers::auth::{AuthService, JwtConfig};
+    use crate::middleware::JwtMiddleware;
+    use crate::models::user::Role;
+    use crate::repositories::{ContentTfIdfRepository, MentionRepository, TrendingRepository};
+    use crate::services::{PropagationService, TokenBlacklist};
+    use actix_web::App;
+    use sqlx::PgPool;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_hashtags_become_tags() {
+        let request = CreateContentRequest {
+            user_id: Uuid::new_v4().to_string(),
+            platform: Platform::Twitter,
+            external_id: "tweet_tags".to_string(),
+            content_type: "text".to_string(),
+            title: "Tagged".to_string(),
+            body: "Rollups are here #Ethereum #L2 #ethereum".to_string(),
+            media_urls: vec![],
+            tags: vec!["ethereum".to_string()],
+            reactions: HashMap::new(),
+        };
+
+        assert_eq!(request.into_new_content().unwrap().tags, vec!["ethereum", "L2"]);
+    }
+
+    #[test]
+    fn test_reactions_accept_platform_names() {
+        let request = |platform: Platform, reactions: &[(&str, u64)]| CreateContentRequest {
+            user_id: Uuid::new_v4().to_string(),
+            platform,
+            external_id: "reacted".to_string(),
+            content_type: "text".to_string(),
+            title: "Reacted".to_string(),
+            body: "Reacted to".to_string(),
+            media_urls: vec![],
+            tags: vec![],
+            reactions: reactions.iter().map(|(name, count)| (name.to_string(), *count)).collect(),
+        };
+
+        let tweet = request(Platform::Twitter, &[("favorite", 12), ("Retweet", 3), ("repost", 2), ("quote", 1)]);
+        assert_eq!(
+            tweet.into_new_content().unwrap().reactions,
+            HashMap::from([(ReactionType::Like, 12), (ReactionType::Repost, 5), (ReactionType::QuoteComment, 1)])
+        );
+        let post = request(Platform::LinkedIn, &[("praise", 4), ("interest", 2), ("love", 1)]);
+        assert_eq!(
+            post.into_new_content().unwrap().reactions,
+            HashMap::from([(ReactionType::Celebrate, 4), (ReactionType::Insightful, 2), (ReactionType::Love, 1)])
+        );
+
+        // Twitter has no celebrate reaction
+        assert!(request(Platform::Twitter, &[("celebrate", 1)]).into_new_content().is_err());
+        assert!(request(Platform::Twitter, &[("retweets", 1)]).into_new_content().is_err());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_near_duplicate_content_conflicts(pool: PgPool) {
+        let config = JwtConfig::new("test-secret");
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xdup') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let rewards = web::Data::new(RwLock::new(RewardService::new(10_000.0)));
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(ContentRepository::new(pool.clone())))
+                .app_data(web::Data::new(UserEventRepository::new(pool.clone())))
+                .app_data(web::Data::new(ContentFingerprintRepository::new(pool.clone())))
+                .app_data(web::Data::new(ContentFingerprintService::new()))
+                .app_data(web::Data::new(ContentSimilarityService::new(Arc::new(ContentTfIdfRepository::new(pool.clone())))))
+                .app_data(web::Data::new(MentionLinker::new(
+                    Arc::new(MentionRepository::new(pool.clone())),
+                    Arc::new(PropagationService::new()),
+                    rewards.clone().into_inner(),
+                )))
+                .app_data(rewards.clone())
+                .service(
+                    web::scope("/content")
+                        .wrap(JwtMiddleware::new(config.clone(), web::Data::new(TokenBlacklist::new())))
+                        .service(create_content),
+                ),
+        )
+        .await;
+        let token =
+            AuthService::generate_access_token(&user_id.to_string(), "0xdup", "session", Role::User, false, &config)
+                .unwrap();
+        let submit = |external_id: &str, platform: &str, body: &str| {
+            TestRequest::post()
+                .uri("/content")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(json!({
+                    "user_id": user_id,
+                    "platform": platform,
+                    "external_id": external_id,
+                    "content_type": "text",
+                    "title": "Rollups",
+                    "body": body,
+                    "media_urls": [],
+                    "tags": []
+                }))
+                .to_request()
+        };
+
+        let original = call_service(&app, submit(
+            "tweet_original",
+            "twitter",
+            "Layer two rollups batch thousands of transactions off chain and post a single compressed proof \
+             to the base layer, which keeps fees low while inheriting the security of Ethereum.",
+        ))
+        .await;
+        assert_eq!(original.status(), 201);
+        let original: serde_json::Value = read_body_json(original).await;
+
+        let repost = call_service(&app, submit(
+            "0x9f8e7d6c5b4a39281706f5e4d3c2b1a098765432",
+            "farcaster",
+            "Layer two rollups batch thousands of transactions off-chain, then post a single compressed proof \
+             to the base layer; this keeps fees low while inheriting the security of Ethereum!",
+        ))
+        .await;
+        assert_eq!(repost.status(), 409);
+        let repost: serde_json::Value = read_body_json(repost).await;
+        assert_eq!(repost["error_code"], "already_exists");
+        assert_eq!(repost["details"], json!({"resource_type": "content", "existing_id": original["data"]["id"]}));
+        // Only the original earned a creation reward
+        assert_eq!(rewards.read().await.get_user_rewards(&user_id.to_string()).len(), 1);
+
+        let unrelated = |external_id: &str| {
+            submit(
+                external_id,
+                "farcaster",
+                "My grandmother's sourdough starter still bubbles happily on the kitchen counter.",
+            )
+        };
+        // Farcaster content is identified by its cast hash
+        assert_eq!(call_service(&app, unrelated("cast_unrelated")).await.status(), 400);
+        let created = call_service(&app, unrelated("0x0123456789ABCDEF0123456789ABCDEF01234567")).await;
+        assert_eq!(created.status(), 201);
+        let created: serde_json::Value = read_body_json(created).await;
+        assert_eq!(created["data"]["external_id"], "0x0123456789abcdef0123456789abcdef01234567");
+
+        // Content for another user, whose rewards it would earn
+        let for_other = |role: Role, totp_verified: bool| {
+            let caller = Uuid::new_v4().to_string();
+            let token =
+                AuthService::generate_access_token(&caller, "0xother", "session", role, totp_verified, &config).unwrap();
+            let mut request = submit("tweet_other", "twitter", "Posted on behalf of someone else entirely.");
+            request.headers_mut().insert(
+                actix_web::http::header::AUTHORIZATION,
+                format!("Bearer {}", token).parse().unwrap(),
+            );
+            request
+        };
+        assert_eq!(call_service(&app, for_other(Role::User, true)).await.status(), 403);
+        assert_eq!(call_service(&app, for_other(Role::Admin, false)).await.status(), 403);
+        assert_eq!(call_service(&app, for_other(Role::Admin, true)).await.status(), 201);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_only_the_author_edits_and_deletes_content(pool: PgPool) {
+        let config = JwtConfig::new("test-secret");
+        let mut users = Vec::new();
+        for wallet in ["0xauthor", "0xvandal"] {
+            let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+                .bind(wallet)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            users.push(user_id);
+        }
+        let (author, vandal) = (users[0], users[1]);
+        let repository = ContentRepository::new(pool.clone());
+        let content = repository
+            .create(&NewContent {
+                user_id: author,
+                platform: Platform::Twitter,
+                external_id: "tweet_owned".to_string(),
+                content_type: "text".to_string(),
+                title: "Mine".to_string(),
+                body: "Written by the author".to_string(),
+                media_urls: vec![],
+                tags: vec![],
+                reactions: HashMap::new(),
+            })
+            .await
+            .unwrap();
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(ContentRepository::new(pool.clone())))
+                .app_data(web::Data::new(ContentSimilarityService::new(Arc::new(ContentTfIdfRepository::new(pool.clone())))))
+                .app_data(web::Data::new(MentionLinker::new(
+                    Arc::new(MentionRepository::new(pool.clone())),
+                    Arc::new(PropagationService::new()),
+                    Arc::new(RwLock::new(RewardService::new(10_000.0))),
+                )))
+                .service(
+                    web::scope("/content")
+                        .wrap(JwtMiddleware::new(config.clone(), web::Data::new(TokenBlacklist::new())))
+                        .service(update_content)
+                        .service(delete_content),
+                ),
+        )
+        .await;
+        let token = |user_id: Uuid| {
+            let token =
+                AuthService::generate_access_token(&user_id.to_string(), "0xwallet", "session", Role::User, false, &config)
+                    .unwrap();
+            format!("Bearer {}", token)
+        };
+        // Also tries to hand the content to the vandal
+        let update = |caller: Uuid| {
+            TestRequest::put()
+                .uri(&format!("/content/{}", content.id))
+                .insert_header(("Authorization", token(caller)))
+                .set_json(json!({
+                    "user_id": vandal,
+                    "platform": "twitter",
+                    "external_id": "tweet_owned",
+                    "content_type": "text",
+                    "title": "Edited",
+                    "body": format!("Edited by {}", caller),
+                    "media_urls": [],
+                    "tags": []
+                }))
+                .to_request()
+        };
+        let delete = |caller: Uuid| {
+            TestRequest::delete()
+                .uri(&format!("/content/{}", content.id))
+                .insert_header(("Authorization", token(caller)))
+                .to_request()
+        };
+
+        assert_eq!(call_service(&app, update(vandal)).await.status(), 403);
+        assert_eq!(call_service(&app, delete(vandal)).await.status(), 403);
+
+        let response = call_service(&app, update(author)).await;
+        assert_eq!(response.status(), 200);
+        let edited: serde_json::Value = read_body_json(response).await;
+        assert_eq!(edited["data"]["title"], "Edited");
+        assert_eq!(edited["data"]["user_id"], author.to_string());
+
+        assert_eq!(call_service(&app, delete(author)).await.status(), 200);
+        assert!(matches!(repository.find_by_id(content.id).await, Err(RepositoryError::NotFound)));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_archived_content_is_served(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xcold') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let repository = ContentRepository::new(pool.clone());
+        let content = repository
+            .create(&NewContent {
+                user_id,
+                platform: Platform::Twitter,
+                external_id: "tweet_cold".to_string(),
+                content_type: "text".to_string(),
+                title: "Old news".to_string(),
+                body: "Nobody echoes this anymore".to_string(),
+                media_urls: vec![],
+                tags: vec!["archive".to_string()],
+                reactions: HashMap::new(),
+            })
+            .await
+            .unwrap();
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(ContentRepository::new(pool.clone())))
+                .app_data(web::Data::new(TrendingScoreService::new(Arc::new(TrendingRepository::new(pool.clone())))))
+                .service(web::scope("/content").service(get_content)),
+        )
+        .await;
+        let get = || TestRequest::get().uri(&format!("/content/{}", content.id)).to_request();
+
+        let live: serde_json::Value = read_body_json(call_service(&app, get()).await).await;
+        repository.archive_content(content.id).await.unwrap();
+
+        let archived = call_service(&app, get()).await;
+        assert_eq!(archived.status(), 200);
+        let archived: serde_json::Value = read_body_json(archived).await;
+        assert_eq!(archived["data"], live["data"]);
+
+        let missing = TestRequest::get().uri(&format!("/content/{}", Uuid::new_v4())).to_request();
+        assert_eq!(call_service(&app, missing).await.status(), 404);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_trending_content_is_ranked_by_velocity(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xviral') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let repository = ContentRepository::new(pool.clone());
+        let mut content_ids = Vec::new();
+        for (external_id, platform, gain) in [("tweet_slow", "twitter", 6.0), ("tweet_fast", "twitter", 30.0),
+            ("msg_fast", "telegram", 60.0)]
+        {
+            let record = repository
+                .create(&NewContent {
+                    user_id,
+                    platform: Platform::from(platform),
+                    external_id: external_id.to_string(),
+                    content_type: "text".to_string(),
+                    title: external_id.to_string(),
+                    body: String::new(),
+                    media_urls: vec![],
+                    tags: vec![],
+                    reactions: HashMap::new(),
+                })
+                .await
+                .unwrap();
+            sqlx::query(
+                "INSERT INTO echo_index_history
+                     (content_id, score, odf, awr, tpm, qf, delta_score, trigger, calculated_at)
+                 VALUES ($1, 10, 0, 0, 0, 0, 0, 'initial', NOW() - INTERVAL '6 hours'),
+                        ($1, 10 + $2, 0, 0, 0, 0, $2, 'propagation_added', NOW() - INTERVAL '1 hour')",
+            )
+            .bind(record.id)
+            .bind(gain)
+            .execute(&pool)
+            .await
+            .unwrap();
+            content_ids.push(record.id);
+        }
+        let trending = TrendingScoreService::new(Arc::new(TrendingRepository::new(pool.clone())));
+        trending.refresh().await.unwrap();
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(repository))
+                .app_data(web::Data::new(trending))
+                .service(web::scope("/content").service(get_trending_content).service(get_content)),
+        )
+        .await;
+        let request = TestRequest::get().uri("/content/trending?platform=twitter").to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = read_body_json(response).await;
+        let ranked: Vec<&str> = body["data"].as_array().unwrap().iter().map(|c| c["id"].as_str().unwrap()).collect();
+        assert_eq!(ranked, vec![content_ids[1].to_string(), content_ids[0].to_string()]);
+        assert_eq!(body["data"][0]["trending_score"]["velocity"], 5.0);
+
+        let content = TestRequest::get().uri(&format!("/content/{}", content_ids[0])).to_request();
+        let content: serde_json::Value = read_body_json(call_service(&app, content).await).await;
+        assert_eq!(content["data"]["trending_score"]["velocity"], 1.0);
+        assert!(content["data"]["trending_score"]["score"].as_f64().unwrap() > 0.0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_related_content_skips_own_by_default(pool: PgPool) {
+        let mut users = Vec::new();
+        for wallet in ["0xrelated_a", "0xrelated_b"] {
+            let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+                .bind(wallet)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            users.push(user_id);
+        }
+        let repository = ContentRepository::new(pool.clone());
+        let similarity = ContentSimilarityService::new(Arc::new(ContentTfIdfRepository::new(pool.clone())));
+
+        let posts = [
+            (users[0], "Ethereum validators stake ETH to secure the blockchain and earn rewards for blocks."),
+            (users[1], "Solana blockchain validators vote on blocks every slot and earn staking rewards."),
+            (users[0], "Running blockchain validators at home: what staking rewards to expect."),
+            (users[1], "Slow roast the chicken with garlic and rosemary, then rest it before carving."),
+        ];
+        let mut ids = Vec::new();
+        for (i, (user_id, body)) in posts.into_iter().enumerate() {
+            let record = repository
+                .create(&NewContent {
+                    user_id,
+                    platform: Platform::Twitter,
+                    external_id: format!("tweet_related_{}", i),
+                    content_type: "text".to_string(),
+                    title: String::new(),
+                    body: body.to_string(),
+                    media_urls: vec![],
+                    tags: vec![],
+                    reactions: HashMap::new(),
+                })
+                .await
+                .unwrap();
+            // The first post is left for the handler to index on first use
+            if i > 0 {
+                similarity.index(&record).await.unwrap();
+            }
+            ids.push(record.id.to_string());
+        }
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(repository))
+                .app_data(web::Data::new(similarity))
+                .service(web::scope("/content").service(get_related_content)),
+        )
+        .await;
+        let related = |query: &str| {
+            TestRequest::get().uri(&format!("/content/{}/related{}", ids[0], query)).to_request()
+        };
+
+        let others: serde_json::Value = read_body_json(call_service(&app, related("")).await).await;
+        let others = others["data"].as_array().unwrap();
+        assert_eq!(others.len(), 1);
+        assert_eq!(others[0]["id"], ids[1]);
+        assert!(others[0]["similarity"].as_f64().unwrap() > 0.0);
+
+        let all: serde_json::Value = read_body_json(call_service(&app, related("?include_own=true")).await).await;
+        let all: Vec<&str> = all["data"].as_array().unwrap().iter().map(|hit| hit["id"].as_str().unwrap()).collect();
+        assert_eq!(all.len(), 2);
+        assert!(all.contains(&ids[1].as_str()) && all.contains(&ids[2].as_str()));
+
+        let missing = TestRequest::get().uri(&format!("/content/{}/related", Uuid::new_v4())).to_request();
+        assert_eq!(call_service(&app, missing).await.status(), 404);
+    }
+}
@@ -1,31 +1,75 @@
-use actix_web::{web, HttpResponse, Result as ActixResult};
+use actix_web::{web, HttpRequest, HttpResponse, Result as ActixResult};
+use actix_ws::Message;
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::handlers::errors::ApiError;
+use crate::handlers::projection::{ProjectionFilter, ProjectionQuery};
+use crate::models::echo_index::{
+    bootstrap_interval, wilson_interval, ConfidenceLevel, EchoIndexCalculator, EchoIndexConfidence,
+    BOOTSTRAP_RESAMPLES,
+};
+use crate::middleware::RequireRole;
+use crate::models::echo_index_history::{HistoryGranularity, LeaderboardSort};
+use crate::models::user::Role;
+use crate::models::user_event::echo_tier;
+use crate::models::pagination::ScoreCursor;
+use crate::models::Platform;
+use crate::repositories::{
+    ContentRepository, EchoIndexHistoryRepository, ExperimentRepository, RepositoryError, UserEventRepository,
+    UserRepository,
+};
+use crate::services::{
+    BatchJobs, ColdStartService, ConfigSource, EchoIndexAnomalyDetector, EngineConfigStore, HashtagTrendService,
+    LeaderboardCache, LeaderboardService, MetricsRegistry, PropagationService, RecalculationContext,
+    RewardForecastService, TimeWindow, UserTierProgressionService, WebhookDispatcher,
+};
+use crate::services::{EchoIndexCache, EchoIndexComponents, EchoIndexPercentileCache, EchoIndexUpdates, TierCutoffs};
+use crate::services::batch_jobs::recalculate;
+use crate::services::language::{detect_language, DEFAULT_LANGUAGE};
+use crate::services::reward_forecast::{DEFAULT_HORIZON_HOURS, MAX_HORIZON_HOURS};
 
 /// Echo Index calculation request payload
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct EchoIndexRequest {
     pub content_id: String,
     pub content_type: String,
     pub content_text: String,
     pub author_id: String,
-    pub platform: String,
+    pub platform: Platform,
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
 /// Echo Index calculation response
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct EchoIndexResponse {
     pub content_id: String,
     pub echo_index: EchoIndex,
     pub calculated_at: DateTime<Utc>,
     pub version: String,
+    /// ISO 639-3 code of the language the content is written in
+    pub detected_language: String,
+    pub confidence: EchoIndexConfidence,
+    /// Share of scored platform content with a lower score (0-100)
+    pub percentile_rank: f64,
 }
 
+/// Transmission paths with a bot score at least this high count as automated
+const AUTOMATED_BOT_SCORE: f64 = 0.5;
+/// A content item can be recalculated on request at most this often
+const RECALCULATION_COOLDOWN_SECONDS: i64 = 60;
+
 /// Echo Index components and overall score
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
 pub struct EchoIndex {
     pub odf: f64,  // Originality Depth Factor
     pub awr: f64,  // Audience Weight Rating
@@ -49,18 +93,49 @@ pub struct PropagationData {
 }
 
 /// Individual transmission path
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct TransmissionPath {
     pub from_user: String,
     pub to_user: String,
-    pub platform: String,
+    pub platform: Platform,
     pub timestamp: DateTime<Utc>,
     pub interaction_type: String,
     pub weight: f64,
+    /// Likelihood the transmission was machine generated (0.0 = human, 1.0 = bot)
+    #[serde(default)]
+    pub bot_score: f64,
+}
+
+/// Echo Index component weights, used to reconfigure the calculator
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct EchoIndexWeightsConfig {
+    pub odf: f64,
+    pub awr: f64,
+    pub tpm: f64,
+    pub qf: f64,
+}
+
+/// Echo Index component weights and the current tier cutoffs
+#[derive(Serialize, ToSchema)]
+pub struct EchoIndexConfigResponse {
+    #[serde(flatten)]
+    pub weights: EchoIndexWeightsConfig,
+    pub tier_cutoffs: TierCutoffs,
+}
+
+impl From<&EchoIndexCalculator> for EchoIndexWeightsConfig {
+    fn from(calculator: &EchoIndexCalculator) -> Self {
+        Self {
+            odf: calculator.odf_weight(),
+            awr: calculator.awr_weight(),
+            tpm: calculator.tpm_weight(),
+            qf: calculator.qf_weight(),
+        }
+    }
 }
 
 /// Leaderboard entry
-#[derive(Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LeaderboardEntry {
     pub rank: u32,
     pub content_id: String,
@@ -68,15 +143,36 @@ pub struct LeaderboardEntry {
     pub author: String,
     pub echo_index: f64,
     pub tier: String,
+    /// From the number of propagations; not tracked on the live leaderboard
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence_level: Option<ConfidenceLevel>,
+    /// On leaderboards sorted by trending score or rewards
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trending_score: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_rewards: Option<f64>,
     pub created_at: DateTime<Utc>,
 }
 
+impl LeaderboardEntry {
+    /// The value the entry is ranked and paged by on a leaderboard sorted by `sort`
+    pub fn sort_score(&self, sort: LeaderboardSort) -> f64 {
+        match sort {
+            LeaderboardSort::Trending => self.trending_score.unwrap_or_default(),
+            LeaderboardSort::EchoIndex => self.echo_index,
+            LeaderboardSort::Rewards => self.total_rewards.unwrap_or_default(),
+        }
+    }
+}
+
 /// Echo Index calculation service
 impl EchoIndex {
-    /// Calculate Echo Index based on content and propagation data
+    /// Calculate Echo Index based on content and propagation data, tiered by `cutoffs`
     pub fn calculate(
         content: &EchoIndexRequest,
         propagation: &PropagationData,
+        calculator: &EchoIndexCalculator,
+        cutoffs: &TierCutoffs,
     ) -> Self {
         let odf = Self::calculate_odf(content, propagation);
         let awr = Self::calculate_awr(propagation);
@@ -84,8 +180,8 @@ impl EchoIndex {
         let qf = Self::calculate_qf(propagation);
         
         // Weighted combination of all factors
-        let score = (odf * 0.3) + (awr * 0.25) + (tpm * 0.25) + (qf * 0.2);
-        let tier = Self::determine_tier(score);
+        let score = calculator.calculate_overall_score(odf, awr, tpm, qf);
+        let tier = Self::determine_tier(score, cutoffs);
         
         EchoIndex {
             odf,
@@ -118,17 +214,17 @@ impl EchoIndex {
         };
         
         // Platform factor (some platforms encourage more original content)
-        let platform_factor = match content.platform.as_str() {
-            "twitter" => 0.8,
-            "linkedin" => 1.2,
-            "medium" => 1.5,
+        let platform_factor = match content.platform {
+            Platform::Twitter => 0.8,
+            Platform::LinkedIn => 1.2,
+            Platform::Medium => 1.5,
             _ => 1.0,
         };
         
         let odf = (length_factor + uniqueness_factor + engagement_depth) 
                  * platform_factor * 33.33; // Scale to 0-100
         
-        odf.min(100.0).max(0.0)
+        odf.clamp(0.0, 100.0)
     }
     
     /// Calculate Audience Weight Rating (AWR)
@@ -144,7 +240,7 @@ impl EchoIndex {
         let reach_factor = (propagation.reach as f64).log10() * 5.0;
         
         let awr = quality_score + engagement_factor + reach_factor;
-        awr.min(100.0).max(0.0)
+        awr.clamp(0.0, 100.0)
     }
     
     /// Calculate Transmission Path Mapping (TPM)
@@ -180,7 +276,7 @@ impl EchoIndex {
         let time_factor = (time_span / 24.0).min(1.0) * 30.0; // Max 30 points for 24+ hour spread
         
         let tpm = platform_diversity + path_depth + weight_balance + time_factor;
-        tpm.min(100.0).max(0.0)
+        tpm.clamp(0.0, 100.0)
     }
     
     /// Calculate Quote Frequency (QF)
@@ -204,26 +300,85 @@ impl EchoIndex {
         };
         
         let qf = (quote_ratio * 40.0) + (volume_factor * 10.0) + (engagement_context * 50.0);
-        qf.min(100.0).max(0.0)
+        qf.clamp(0.0, 100.0)
     }
     
-    /// Determine Echo Index tier based on score
-    fn determine_tier(score: f64) -> String {
-        match score {
-            s if s >= 80.0 => "Gold".to_string(),
-            s if s >= 60.0 => "Silver".to_string(),
-            s if s >= 40.0 => "Bronze".to_string(),
-            _ => "Basic".to_string(),
+    /// 95% confidence interval of the score, with each transmission path as one
+    /// propagation. ODF's share of the interval comes from a Wilson interval of the share
+    /// of organic paths; the rest from the percentiles of the score recalculated for
+    /// bootstrap resamples of the paths, which moves AWR and TPM. Resampling is seeded
+    /// with the content id, so the same input always gets the same interval.
+    pub fn confidence(
+        &self,
+        content_id: &str,
+        propagation: &PropagationData,
+        calculator: &EchoIndexCalculator,
+    ) -> EchoIndexConfidence {
+        let paths = &propagation.transmission_paths;
+        let sample_size = paths.len() as u32;
+
+        // Organic share with no paths is taken as whole, as nothing suggests automation
+        let organic = paths.iter().filter(|path| path.bot_score < AUTOMATED_BOT_SCORE).count() as u32;
+        let organic_share = if sample_size == 0 { 1.0 } else { organic as f64 / sample_size as f64 };
+        let (organic_lower, organic_upper) = wilson_interval(organic, sample_size);
+        let odf_lower = (self.odf * (1.0 - (organic_share - organic_lower))).max(0.0);
+        let odf_upper = (self.odf * (1.0 + (organic_upper - organic_share))).min(100.0);
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        content_id.hash(&mut hasher);
+        let mut rng = StdRng::seed_from_u64(hasher.finish());
+        let awr = Self::calculate_awr(propagation);
+        let (score_lower, score_upper) = bootstrap_interval(paths, BOOTSTRAP_RESAMPLES, &mut rng, |resample| {
+            calculator.calculate_overall_score(self.odf, awr, Self::calculate_tpm(resample), self.qf)
+        });
+
+        let odf_weight = calculator.odf_weight();
+        EchoIndexConfidence::new(
+            self.score,
+            (score_lower - odf_weight * (self.odf - odf_lower)).max(0.0),
+            (score_upper + odf_weight * (odf_upper - self.odf)).min(100.0),
+            sample_size,
+        )
+    }
+
+    pub fn components(&self) -> EchoIndexComponents {
+        EchoIndexComponents {
+            odf: self.odf,
+            awr: self.awr,
+            tpm: self.tpm,
+            qf: self.qf,
         }
     }
+
+    /// Determine Echo Index tier based on where the score ranks among platform content
+    fn determine_tier(score: f64, cutoffs: &TierCutoffs) -> String {
+        cutoffs.tier(score).to_string()
+    }
 }
 
 /// Calculate Echo Index for content
+#[utoipa::path(
+    post,
+    path = "/calculate",
+    context_path = "/api/v1/echo-index",
+    operation_id = "calculate_echo_index",
+    tag = "echo-index",
+    request_body = EchoIndexRequest,
+    responses(
+        (status = 200, description = "The content's Echo Index", body = EchoIndexResponse),
+        (status = 400, description = "Invalid request"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[actix_web::post("/calculate")]
 pub async fn calculate_echo_index(
     request: web::Json<EchoIndexRequest>,
+    calculator: web::Data<RwLock<EchoIndexCalculator>>,
+    updates: web::Data<EchoIndexUpdates>,
+    metrics: web::Data<MetricsRegistry>,
+    percentiles: web::Data<EchoIndexPercentileCache>,
 ) -> ActixResult<HttpResponse> {
-    tracing::info!("Calculating Echo Index for content: {}", request.content_id);
+    tracing::info!(content_id = %request.content_id, "Calculating Echo Index");
     
     // In a real implementation, this would fetch propagation data from the database
     // For now, we'll use mock data based on the content metadata
@@ -252,26 +407,254 @@ pub async fn calculate_echo_index(
         transmission_paths: vec![], // Would be populated from database
     };
     
-    let echo_index = EchoIndex::calculate(&request, &propagation);
+    let calculator = calculator.read()
+        .map_err(|_| ApiError::internal())?
+        .clone();
+    let echo_index = EchoIndex::calculate(&request, &propagation, &calculator, &percentiles.tier_cutoffs());
+    let confidence = echo_index.confidence(&request.content_id, &propagation, &calculator);
+    updates.publish(&request.content_id, echo_index.score, echo_index.components());
+    metrics.record_echo_index(&request.platform, echo_index.score);
     
     let response = EchoIndexResponse {
         content_id: request.content_id.clone(),
+        percentile_rank: percentiles.percentile_rank(echo_index.score),
         echo_index,
         calculated_at: Utc::now(),
         version: "1.0.0".to_string(),
+        detected_language: detect_language(&request.content_text),
+        confidence,
     };
     
-    tracing::info!("Echo Index calculated successfully: {}", response.echo_index.score);
+    tracing::info!(score = response.echo_index.score, "Echo Index calculated");
     Ok(HttpResponse::Ok().json(response))
 }
 
-/// Get Echo Index for specific content
+/// Stream Echo Index updates for a content item over a WebSocket
+#[utoipa::path(
+    get,
+    path = "/ws/echo-index/{content_id}",
+    context_path = "/api/v1",
+    operation_id = "echo_index_updates",
+    tag = "echo-index",
+    params(("content_id" = String, Path, description = "Content ID")),
+    responses(
+        (status = 101, description = "Switched to a WebSocket sending the latest and then each new score as JSON"),
+    ),
+    security((), ("bearer_auth" = []), ("api_key" = []))
+)]
+#[actix_web::get("/ws/echo-index/{content_id}")]
+pub async fn echo_index_updates(
+    req: HttpRequest,
+    body: web::Payload,
+    path: web::Path<String>,
+    updates: web::Data<EchoIndexUpdates>,
+) -> ActixResult<HttpResponse> {
+    let content_id = path.into_inner();
+    let (response, mut session, mut messages) = actix_ws::handle(&req, body)?;
+    let (current, mut receiver) = updates.subscribe(&content_id);
+
+    actix_web::rt::spawn(async move {
+        let snapshot = match current {
+            Some(update) => serde_json::to_string(&update),
+            None => serde_json::to_string(&serde_json::json!({
+                "content_id": content_id,
+                "score": null,
+            })),
+        };
+
+        if let Ok(snapshot) = snapshot {
+            if session.text(snapshot).await.is_err() {
+                updates.release(&content_id);
+                return;
+            }
+        }
+
+        loop {
+            tokio::select! {
+                update = receiver.recv() => match update {
+                    Ok(update) => {
+                        let Ok(payload) = serde_json::to_string(&update) else { continue };
+                        if session.text(payload).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::debug!(%content_id, skipped, "Echo Index subscriber lagged");
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                message = messages.next() => match message {
+                    Some(Ok(Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+
+        let _ = session.close(None).await;
+        drop(receiver);
+        updates.release(&content_id);
+        tracing::debug!(%content_id, "Echo Index subscriber disconnected");
+    });
+
+    Ok(response)
+}
+
+/// Get the Echo Index component weights and the score each tier currently starts at
+#[utoipa::path(
+    get,
+    path = "/config",
+    context_path = "/api/v1/echo-index",
+    operation_id = "get_echo_index_config",
+    tag = "echo-index",
+    responses(
+        (status = 200, description = "Component weights and tier cutoffs", body = EchoIndexConfigResponse),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[actix_web::get("/config")]
+pub async fn get_echo_index_config(
+    calculator: web::Data<RwLock<EchoIndexCalculator>>,
+    percentiles: web::Data<EchoIndexPercentileCache>,
+) -> ActixResult<HttpResponse> {
+    let weights = calculator.read()
+        .map(|calculator| EchoIndexWeightsConfig::from(&*calculator))
+        .map_err(|_| ApiError::internal())?;
+
+    Ok(HttpResponse::Ok().json(EchoIndexConfigResponse { weights, tier_cutoffs: percentiles.tier_cutoffs() }))
+}
+
+/// Update the Echo Index component weights (admin)
+#[utoipa::path(
+    post,
+    path = "/config",
+    context_path = "/api/v1/echo-index",
+    operation_id = "update_echo_index_config",
+    tag = "echo-index",
+    request_body = EchoIndexWeightsConfig,
+    responses(
+        (status = 200, description = "The new component weights", body = EchoIndexWeightsConfig),
+        (status = 400, description = "Negative weights, or weights not summing to 1"),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[actix_web::post("/config", wrap = "RequireRole(Role::Admin)")]
+pub async fn update_echo_index_config(
+    request: web::Json<EchoIndexWeightsConfig>,
+    calculator: web::Data<RwLock<EchoIndexCalculator>>,
+) -> ActixResult<HttpResponse> {
+    let new_calculator = match EchoIndexCalculator::builder()
+        .odf_weight(request.odf)
+        .awr_weight(request.awr)
+        .tpm_weight(request.tpm)
+        .qf_weight(request.qf)
+        .build()
+    {
+        Ok(new_calculator) => new_calculator,
+        Err(e) => {
+            tracing::warn!(error = %e, "Rejected Echo Index weights");
+            return Err(ApiError::bad_request(e.to_string()).into());
+        }
+    };
+    
+    let weights = EchoIndexWeightsConfig::from(&new_calculator);
+    *calculator.write()
+        .map_err(|_| ApiError::internal())? = new_calculator;
+    
+    tracing::info!(odf = weights.odf, awr = weights.awr, tpm = weights.tpm, qf = weights.qf, "Echo Index weights updated");
+    Ok(HttpResponse::Ok().json(weights))
+}
+
+/// Get the per-platform ODF normalization factors
+#[utoipa::path(
+    get,
+    path = "/platform-config",
+    context_path = "/api/v1/echo-index",
+    operation_id = "get_platform_config",
+    tag = "echo-index",
+    responses(
+        (status = 200, description = "ODF normalization factor of each platform", body = HashMap<String, f64>),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[actix_web::get("/platform-config")]
+pub async fn get_platform_config(
+    engine_config: web::Data<EngineConfigStore>,
+) -> ActixResult<HttpResponse> {
+    Ok(HttpResponse::Ok().json(&engine_config.load().config.platform_odf_normalization))
+}
+
+/// Update per-platform ODF normalization factors (admin); unlisted platforms keep their factor
+#[utoipa::path(
+    post,
+    path = "/platform-config",
+    context_path = "/api/v1/echo-index",
+    operation_id = "update_platform_config",
+    tag = "echo-index",
+    request_body = HashMap<String, f64>,
+    responses(
+        (status = 200, description = "ODF normalization factor of each platform", body = HashMap<String, f64>),
+        (status = 400, description = "A factor that is not a positive number"),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[actix_web::post("/platform-config", wrap = "RequireRole(Role::Admin)")]
+pub async fn update_platform_config(
+    request: web::Json<HashMap<Platform, f64>>,
+    engine_config: web::Data<EngineConfigStore>,
+) -> ActixResult<HttpResponse> {
+    if let Some((platform, factor)) = request.iter().find(|(_, factor)| !factor.is_finite() || **factor <= 0.0) {
+        tracing::warn!(factor, %platform, "Rejected ODF normalization factor");
+        let message = format!("normalization factor for `{}` must be a finite, positive number", platform);
+        return Err(ApiError::invalid_field(platform.to_string(), message).into());
+    }
+    
+    let factors = request.into_inner();
+    engine_config
+        .update(ConfigSource::Api, |config| config.platform_odf_normalization.extend(factors.clone()))
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to update ODF normalization");
+            ApiError::internal()
+        })?;
+    
+    let engine_config = engine_config.load();
+    tracing::info!(normalization = ?engine_config.config.platform_odf_normalization, "ODF normalization updated");
+    Ok(HttpResponse::Ok().json(&engine_config.config.platform_odf_normalization))
+}
+
+/// Get Echo Index for specific content. The `fields` query parameter, or the `minimal`
+/// profile of the vendor media type, trims the response to the fields named.
+#[utoipa::path(
+    get,
+    path = "/{content_id}",
+    context_path = "/api/v1/echo-index",
+    operation_id = "get_echo_index",
+    tag = "echo-index",
+    params(("content_id" = String, Path, description = "Content ID"), ProjectionQuery),
+    responses(
+        (
+            status = 200,
+            description = "The content's Echo Index, trimmed to the fields asked for",
+            body = EchoIndexResponse
+        ),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[actix_web::get("/{content_id}")]
 pub async fn get_echo_index(
+    req: HttpRequest,
     path: web::Path<String>,
+    query: web::Query<ProjectionQuery>,
+    percentiles: web::Data<EchoIndexPercentileCache>,
 ) -> ActixResult<HttpResponse> {
     let content_id = path.into_inner();
-    tracing::info!("Fetching Echo Index for content: {}", content_id);
+    tracing::info!(%content_id, "Fetching Echo Index");
     
     // In a real implementation, this would query the database
     // For now, return mock data
@@ -281,126 +664,914 @@ pub async fn get_echo_index(
         tpm: 68.7,
         qf: 71.2,
         score: 74.4,
-        tier: "Silver".to_string(),
+        tier: percentiles.tier_cutoffs().tier(74.4).to_string(),
     };
     
+    // Without propagations to go on, the score could be anywhere
+    let confidence = EchoIndexConfidence::new(mock_echo_index.score, 0.0, 100.0, 0);
     let response = EchoIndexResponse {
         content_id,
+        percentile_rank: percentiles.percentile_rank(mock_echo_index.score),
         echo_index: mock_echo_index,
         calculated_at: Utc::now(),
         version: "1.0.0".to_string(),
+        detected_language: DEFAULT_LANGUAGE.to_string(),
+        confidence,
     };
     
-    Ok(HttpResponse::Ok().json(response))
+    // Always built in full; bare field names refer to the Echo Index itself
+    match ProjectionFilter::from_request(&query, &req) {
+        Some(filter) => {
+            let response = serde_json::to_value(&response).map_err(|e| {
+                tracing::error!(error = %e, "Failed to encode Echo Index");
+                ApiError::internal()
+            })?;
+            Ok(HttpResponse::Ok().json(filter.with_default_object("echo_index").apply(response)))
+        }
+        None => Ok(HttpResponse::Ok().json(response)),
+    }
+}
+
+/// Query parameters for the Echo Index leaderboard
+#[derive(Deserialize)]
+pub struct EchoLeaderboardQuery {
+    pub limit: Option<u32>,
+    pub platform: Option<Platform>,
+    /// `24h`, `7d`, `30d` or `all` (the default)
+    pub time_range: Option<String>,
+    /// `low`, `medium`, `high` or `very_high`; content with less confidence is left out
+    pub min_confidence: Option<String>,
+    /// `trending`, `echo_index` (the default) or `rewards`
+    pub sort_by: Option<String>,
+    pub after: Option<String>,
 }
 
-/// Get Echo Index leaderboard
+/// Get the leaderboard of content created within a time range, ranked by the highest
+/// Echo Index each item reached within it, or by its trending score or rewards
 #[actix_web::get("/leaderboard")]
 pub async fn get_leaderboard(
-    query: web::Query<HashMap<String, String>>,
+    query: web::Query<EchoLeaderboardQuery>,
+    leaderboards: web::Data<LeaderboardService>,
 ) -> ActixResult<HttpResponse> {
-    let limit: usize = query.get("limit")
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(10)
-        .min(100);
-    
-    let platform = query.get("platform").cloned();
-    let time_range = query.get("time_range").cloned().unwrap_or_else(|| "24h".to_string());
-    
-    tracing::info!("Fetching leaderboard with limit: {}, platform: {:?}, time_range: {}", 
-                   limit, platform, time_range);
-    
-    // Mock leaderboard data
-    let mut leaderboard = vec![
-        LeaderboardEntry {
-            rank: 1,
-            content_id: "content_1".to_string(),
-            title: "Revolutionary AI Breakthrough in Decentralized Networks".to_string(),
-            author: "TechVisioneer".to_string(),
-            echo_index: 94.7,
-            tier: "Gold".to_string(),
-            created_at: Utc::now() - chrono::Duration::hours(2),
-        },
-        LeaderboardEntry {
-            rank: 2,
-            content_id: "content_2".to_string(),
-            title: "The Future of Attention Economics".to_string(),
-            author: "AttentionGuru".to_string(),
-            echo_index: 91.3,
-            tier: "Gold".to_string(),
-            created_at: Utc::now() - chrono::Duration::hours(5),
-        },
-        LeaderboardEntry {
-            rank: 3,
-            content_id: "content_3".to_string(),
-            title: "Building Sustainable Creator Economies".to_string(),
-            author: "CreatorAdvocate".to_string(),
-            echo_index: 87.9,
-            tier: "Gold".to_string(),
-            created_at: Utc::now() - chrono::Duration::hours(8),
-        },
-        LeaderboardEntry {
-            rank: 4,
-            content_id: "content_4".to_string(),
-            title: "Blockchain Gaming: The Next Big Wave".to_string(),
-            author: "GameChanger".to_string(),
-            echo_index: 83.5,
-            tier: "Gold".to_string(),
-            created_at: Utc::now() - chrono::Duration::hours(12),
-        },
-        LeaderboardEntry {
-            rank: 5,
-            content_id: "content_5".to_string(),
-            title: "Democratizing Content Discovery".to_string(),
-            author: "ContentCurator".to_string(),
-            echo_index: 79.2,
-            tier: "Silver".to_string(),
-            created_at: Utc::now() - chrono::Duration::hours(18),
-        },
-    ];
-    
-    // Apply limit
-    leaderboard.truncate(limit);
-    
-    Ok(HttpResponse::Ok().json(leaderboard))
+    let query = query.into_inner();
+    let limit = query.limit.unwrap_or(10).clamp(1, 100);
+    let time_range = query.time_range.unwrap_or_else(|| "all".to_string());
+    let Some(window) = TimeWindow::parse(&time_range) else {
+        return Err(ApiError::invalid_field("time_range", "time_range must be one of 24h, 7d, 30d or all").into());
+    };
+    let min_confidence = match query.min_confidence.as_deref().map(ConfidenceLevel::parse) {
+        None => None,
+        Some(Some(level)) => Some(level),
+        Some(None) => {
+            let message = "min_confidence must be one of low, medium, high or very_high";
+            return Err(ApiError::invalid_field("min_confidence", message).into());
+        }
+    };
+    let sort = match query.sort_by.as_deref().map(LeaderboardSort::parse) {
+        None => LeaderboardSort::default(),
+        Some(Some(sort)) => sort,
+        Some(None) => {
+            let message = "sort_by must be one of trending, echo_index or rewards";
+            return Err(ApiError::invalid_field("sort_by", message).into());
+        }
+    };
+    let after = match query.after.as_deref().map(ScoreCursor::decode).transpose() {
+        Ok(after) => after,
+        Err(e) => {
+            return Err(ApiError::invalid_field("cursor", e).into())
+        }
+    };
+
+    tracing::info!(limit, platform = ?query.platform, %time_range, ?sort, "Fetching leaderboard");
+
+    // Fetch one extra entry to learn whether another page exists
+    let mut leaderboard = leaderboards
+        .build_leaderboard(window, query.platform.clone(), sort, min_confidence, limit + 1, after)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Leaderboard query failed");
+            ApiError::internal()
+        })?;
+    let has_more = leaderboard.len() > limit as usize;
+    leaderboard.truncate(limit as usize);
+    let next_cursor = if has_more {
+        leaderboard.last().and_then(|entry| {
+            let id = Uuid::parse_str(&entry.content_id).ok()?;
+            Some(ScoreCursor::new(entry.sort_score(sort), id).encode())
+        })
+    } else {
+        None
+    };
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "time_range": time_range,
+        "platform": query.platform,
+        "sort_by": sort,
+        "min_confidence": min_confidence,
+        "leaderboard": leaderboard,
+        "next_cursor": next_cursor,
+        "has_more": has_more,
+    })))
 }
 
-/// Get historical Echo Index data for content
+/// Stream leaderboard changes as server-sent `leaderboard_update` events
+#[utoipa::path(
+    get,
+    path = "/leaderboard/stream",
+    context_path = "/api/v1/echo-index",
+    operation_id = "leaderboard_stream",
+    tag = "echo-index",
+    responses(
+        (
+            status = 200,
+            description = "Server-sent `leaderboard_update` events",
+            body = String,
+            content_type = "text/event-stream"
+        ),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[actix_web::get("/leaderboard/stream")]
+pub async fn leaderboard_stream(leaderboard: web::Data<LeaderboardCache>) -> HttpResponse {
+    let receiver = leaderboard.subscribe();
+
+    // Clients reconnect after 5 seconds if the stream drops
+    let retry = futures_util::stream::once(async { Ok::<_, actix_web::Error>(web::Bytes::from_static(b"retry: 5000\n\n")) });
+    let events = futures_util::stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    let Ok(data) = serde_json::to_string(&event) else { continue };
+                    let frame = format!("event: leaderboard_update\ndata: {}\n\n", data);
+                    return Some((Ok(web::Bytes::from(frame)), receiver));
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!(skipped, "Leaderboard subscriber lagged");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((actix_web::http::header::CACHE_CONTROL, "no-cache"))
+        .streaming(retry.chain(events))
+}
+
+/// Bulk Echo Index recalculation request
+#[derive(Deserialize, ToSchema)]
+pub struct BatchRecalculateRequest {
+    pub content_ids: Vec<Uuid>,
+    /// Recalculate even content scored within the last hour
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Queue Echo Index recalculation for many content items at once
+#[utoipa::path(
+    post,
+    path = "/batch-recalculate",
+    context_path = "/api/v1/echo-index",
+    operation_id = "batch_recalculate_echo_index",
+    tag = "echo-index",
+    request_body = BatchRecalculateRequest,
+    responses(
+        (status = 202, description = "Job queued, with its `job_id` and `total` content items", body = Object),
+        (status = 400, description = "Invalid request"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[actix_web::post("/batch-recalculate")]
+#[allow(clippy::too_many_arguments)]
+pub async fn batch_recalculate_echo_index(
+    request: web::Json<BatchRecalculateRequest>,
+    batch_jobs: web::Data<BatchJobs>,
+    calculator: web::Data<RwLock<EchoIndexCalculator>>,
+    content: web::Data<ContentRepository>,
+    history: web::Data<EchoIndexHistoryRepository>,
+    updates: web::Data<EchoIndexUpdates>,
+    metrics: web::Data<MetricsRegistry>,
+    events: web::Data<UserEventRepository>,
+    engine_config: web::Data<EngineConfigStore>,
+    webhooks: web::Data<WebhookDispatcher>,
+    experiments: web::Data<ExperimentRepository>,
+    hashtag_trends: web::Data<HashtagTrendService>,
+    echo_cache: web::Data<EchoIndexCache>,
+    anomalies: web::Data<EchoIndexAnomalyDetector>,
+    cold_start: web::Data<ColdStartService>,
+    // Extracted together, as handlers take at most 16 extractors
+    (propagation, tiers, users): (
+        web::Data<PropagationService>,
+        web::Data<UserTierProgressionService>,
+        web::Data<UserRepository>,
+    ),
+) -> ActixResult<HttpResponse> {
+    let calculator = calculator.read()
+        .map_err(|_| ApiError::internal())?
+        .clone();
+    let request = request.into_inner();
+    let total = request.content_ids.len();
+
+    let context = RecalculationContext {
+        content: content.into_inner(),
+        history: history.into_inner(),
+        updates: updates.into_inner(),
+        metrics: metrics.into_inner(),
+        events: events.into_inner(),
+        users: users.into_inner(),
+        calculator,
+        engine_config: engine_config.into_inner(),
+        webhooks: Some(webhooks.into_inner()),
+        experiments: Some(experiments.into_inner()),
+        hashtag_trends: Some(hashtag_trends.into_inner()),
+        echo_cache: Some(echo_cache.into_inner()),
+        anomalies: Some(anomalies.into_inner()),
+        cold_start: Some(cold_start.into_inner()),
+        propagation: Some(propagation.into_inner()),
+        tiers: Some(tiers.into_inner()),
+    };
+    let job_id = batch_jobs.into_inner().submit(request.content_ids, request.force, context);
+    tracing::info!(%job_id, total, "Queued batch recalculation job");
+
+    Ok(HttpResponse::Accepted().json(serde_json::json!({
+        "job_id": job_id,
+        "total": total,
+    })))
+}
+
+/// Get the progress of a batch recalculation job
+#[utoipa::path(
+    get,
+    path = "/jobs/{job_id}",
+    context_path = "/api/v1/echo-index",
+    operation_id = "get_batch_job",
+    tag = "echo-index",
+    params(("job_id" = Uuid, Path, description = "Batch job ID")),
+    responses(
+        (status = 200, description = "Progress of the job", body = JobStatus),
+        (status = 400, description = "Malformed job ID"),
+        (status = 404, description = "No such job, or finished over an hour ago"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[actix_web::get("/jobs/{job_id}")]
+pub async fn get_batch_job(
+    path: web::Path<String>,
+    batch_jobs: web::Data<BatchJobs>,
+) -> ActixResult<HttpResponse> {
+    let Ok(job_id) = Uuid::parse_str(&path) else {
+        return Err(ApiError::invalid_field("job_id", "job_id must be a valid UUID").into());
+    };
+
+    match batch_jobs.status(job_id) {
+        Some(status) => Ok(HttpResponse::Ok().json(status)),
+        None => Err(ApiError::NotFound(format!("No batch job with id {}", job_id)).into()),
+    }
+}
+
+/// Recalculate the Echo Index of a content item from its stored propagations and audience.
+/// Content scored within the last minute, for any reason, is not recalculated. Responds with
+/// the new scores, the calculation they replace and the change from it.
+#[utoipa::path(
+    post,
+    path = "/{content_id}/recalculate",
+    context_path = "/api/v1/echo-index",
+    operation_id = "recalculate_echo_index",
+    tag = "echo-index",
+    params(("content_id" = Uuid, Path, description = "Content ID")),
+    responses(
+        (status = 200, description = "New scores, the calculation they replace and the `diff` from it", body = Object),
+        (status = 400, description = "Malformed content ID"),
+        (status = 404, description = "No such content"),
+        (status = 429, description = "Scored within the last minute"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[actix_web::post("/{content_id}/recalculate")]
+#[allow(clippy::too_many_arguments)]
+pub async fn recalculate_echo_index(
+    path: web::Path<String>,
+    calculator: web::Data<RwLock<EchoIndexCalculator>>,
+    content: web::Data<ContentRepository>,
+    history: web::Data<EchoIndexHistoryRepository>,
+    updates: web::Data<EchoIndexUpdates>,
+    metrics: web::Data<MetricsRegistry>,
+    events: web::Data<UserEventRepository>,
+    engine_config: web::Data<EngineConfigStore>,
+    webhooks: web::Data<WebhookDispatcher>,
+    experiments: web::Data<ExperimentRepository>,
+    hashtag_trends: web::Data<HashtagTrendService>,
+    echo_cache: web::Data<EchoIndexCache>,
+    anomalies: web::Data<EchoIndexAnomalyDetector>,
+    cold_start: web::Data<ColdStartService>,
+    // Extracted together, as handlers take at most 16 extractors
+    (propagation, tiers, users): (
+        web::Data<PropagationService>,
+        web::Data<UserTierProgressionService>,
+        web::Data<UserRepository>,
+    ),
+) -> ActixResult<HttpResponse> {
+    let Ok(content_id) = Uuid::parse_str(&path) else {
+        return Err(ApiError::invalid_field("content_id", "content_id must be a valid UUID").into());
+    };
+
+    match content.find_by_id(content_id).await {
+        Ok(_) => {}
+        Err(RepositoryError::NotFound) => {
+            return Err(ApiError::NotFound(format!("No content with id {}", content_id)).into());
+        }
+        Err(e) => {
+            tracing::error!(%content_id, error = %e, "Content lookup failed");
+            return Err(ApiError::internal().into());
+        }
+    }
+
+    let latest = history.latest(content_id).await.map_err(|e| {
+        tracing::error!(%content_id, error = %e, "Echo Index history query failed");
+        ApiError::internal()
+    })?;
+    let cooldown = chrono::Duration::seconds(RECALCULATION_COOLDOWN_SECONDS);
+    if let Some(remaining) = latest.map(|latest| latest.calculated_at + cooldown - Utc::now()) {
+        if remaining > chrono::Duration::zero() {
+            let retry_after_seconds = ((remaining.num_milliseconds() + 999) / 1000) as u32;
+            return Err(ApiError::RateLimited { retry_after_seconds }.into());
+        }
+    }
+
+    let calculator = calculator.read()
+        .map_err(|_| ApiError::internal())?
+        .clone();
+    let context = RecalculationContext {
+        content: content.into_inner(),
+        history: history.into_inner(),
+        updates: updates.into_inner(),
+        metrics: metrics.into_inner(),
+        events: events.into_inner(),
+        users: users.into_inner(),
+        calculator,
+        engine_config: engine_config.into_inner(),
+        webhooks: Some(webhooks.into_inner()),
+        experiments: Some(experiments.into_inner()),
+        hashtag_trends: Some(hashtag_trends.into_inner()),
+        echo_cache: Some(echo_cache.into_inner()),
+        anomalies: Some(anomalies.into_inner()),
+        cold_start: Some(cold_start.into_inner()),
+        propagation: Some(propagation.into_inner()),
+        tiers: Some(tiers.into_inner()),
+    };
+    // Forced, so never skipped as fresh
+    let recalculation = recalculate(&context, content_id, true)
+        .await
+        .and_then(|recalculation| recalculation.ok_or_else(|| "recalculation skipped".to_string()))
+        .map_err(|e| {
+            tracing::error!(%content_id, error = %e, "Echo Index recalculation failed");
+            ApiError::internal()
+        })?;
+
+    let echo_index = recalculation.echo_index;
+    let components = EchoIndexComponents {
+        odf: echo_index.originality_depth_factor,
+        awr: echo_index.audience_weight_rating,
+        tpm: echo_index.transmission_path_mapping,
+        qf: echo_index.quote_frequency,
+    };
+    let diff = recalculation.previous.as_ref().map(|previous| {
+        serde_json::json!({
+            "score": echo_index.overall_score - previous.score,
+            "odf": components.odf - previous.odf,
+            "awr": components.awr - previous.awr,
+            "tpm": components.tpm - previous.tpm,
+            "qf": components.qf - previous.qf,
+        })
+    });
+    tracing::info!(%content_id, score = echo_index.overall_score, "Echo Index recalculated");
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "content_id": content_id,
+        "score": echo_index.overall_score,
+        "tier": echo_tier(echo_index.overall_score * 100.0),
+        "components": components,
+        "previous": recalculation.previous,
+        "diff": diff,
+        "is_cold_start": recalculation.data_sufficiency.is_some(),
+        "data_sufficiency": recalculation.data_sufficiency.unwrap_or(1.0),
+    })))
+}
+
+/// Query parameters for Echo Index history
+#[derive(Deserialize, IntoParams)]
+pub struct EchoIndexHistoryQuery {
+    #[serde(default)]
+    pub granularity: HistoryGranularity,
+    pub days: Option<u32>,
+}
+
+/// Get historical Echo Index data for content, aggregated per bucket
+#[utoipa::path(
+    get,
+    path = "/{content_id}/history",
+    context_path = "/api/v1/echo-index",
+    operation_id = "get_echo_index_history",
+    tag = "echo-index",
+    params(("content_id" = Uuid, Path, description = "Content ID"), EchoIndexHistoryQuery),
+    responses(
+        (status = 200, description = "`history` buckets over the last `period_days`", body = Object),
+        (status = 400, description = "Malformed content ID"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[actix_web::get("/{content_id}/history")]
 pub async fn get_echo_index_history(
     path: web::Path<String>,
-    query: web::Query<HashMap<String, String>>,
+    query: web::Query<EchoIndexHistoryQuery>,
+    history: web::Data<EchoIndexHistoryRepository>,
 ) -> ActixResult<HttpResponse> {
-    let content_id = path.into_inner();
-    let days: u32 = query.get("days")
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(7)
-        .min(365);
+    let Ok(content_id) = Uuid::parse_str(&path) else {
+        return Err(ApiError::invalid_field("content_id", "content_id must be a valid UUID").into());
+    };
+    let days = query.days.unwrap_or(30).clamp(1, 365);
     
-    tracing::info!("Fetching Echo Index history for content: {} (last {} days)", 
-                   content_id, days);
+    tracing::info!(%content_id, days, granularity = ?query.granularity, "Fetching Echo Index history");
     
-    // Mock historical data
-    let mut history = Vec::new();
-    for i in 0..days {
-        let timestamp = Utc::now() - chrono::Duration::days(days as i64 - i as i64);
-        let base_score = 50.0;
-        let variance = (i as f64 * 0.1).sin() * 20.0;
-        let trend = i as f64 * 0.5;
-        
-        history.push(serde_json::json!({
-            "timestamp": timestamp,
-            "echo_index": (base_score + variance + trend).min(100.0).max(0.0),
-            "odf": (base_score + variance * 0.8).min(100.0).max(0.0),
-            "awr": (base_score + variance * 1.2).min(100.0).max(0.0),
-            "tpm": (base_score + variance * 0.9).min(100.0).max(0.0),
-            "qf": (base_score + variance * 1.1).min(100.0).max(0.0),
-        }));
-    }
+    let buckets = history.buckets(content_id, query.granularity, days).await.map_err(|e| {
+        tracing::error!(error = %e, "Echo Index history query failed");
+        ApiError::internal()
+    })?;
     
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "content_id": content_id,
-        "history": history,
+        "granularity": query.granularity,
+        "history": buckets,
         "period_days": days,
     })))
-} 
\ No newline at end of file
+}
+
+/// Query parameters for a reward forecast
+#[derive(Deserialize, IntoParams)]
+pub struct ForecastQuery {
+    pub hours: Option<u32>,
+}
+
+/// Forecast the Echo Index of content and the EchoDrops it earns over the next `hours`
+#[utoipa::path(
+    get,
+    path = "/{content_id}/forecast",
+    context_path = "/api/v1/echo-index",
+    operation_id = "get_echo_index_forecast",
+    tag = "echo-index",
+    params(("content_id" = Uuid, Path, description = "Content ID"), ForecastQuery),
+    responses(
+        (status = 200, description = "Predicted Echo Index and rewards at the horizon", body = Object),
+        (status = 400, description = "Malformed content ID"),
+        (status = 404, description = "No such content"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[actix_web::get("/{content_id}/forecast")]
+pub async fn get_echo_index_forecast(
+    path: web::Path<String>,
+    query: web::Query<ForecastQuery>,
+    forecasts: web::Data<RewardForecastService>,
+) -> ActixResult<HttpResponse> {
+    let Ok(content_id) = Uuid::parse_str(&path) else {
+        return Err(ApiError::invalid_field("content_id", "content_id must be a valid UUID").into());
+    };
+    let hours = query.hours.unwrap_or(DEFAULT_HORIZON_HOURS).clamp(1, MAX_HORIZON_HOURS);
+
+    match forecasts.forecast(content_id, hours).await {
+        Ok(forecast) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "content_id": content_id,
+            "horizon_hours": hours,
+            "predicted_echo_index_at_horizon": forecast.predicted_echo_index_at_horizon,
+            "predicted_total_rewards": forecast.predicted_total_rewards,
+            "confidence_interval": forecast.confidence_interval,
+            "prediction_basis": forecast.prediction_basis,
+        }))),
+        Err(RepositoryError::NotFound) => Err(ApiError::NotFound(format!("No content with id {}", content_id)).into()),
+        Err(e) => {
+            tracing::error!(error = %e, "Reward forecast failed");
+            Err(ApiError::internal().into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::echo_index_history::EchoIndexTrigger;
+    use crate::repositories::{
+        EchoAnomalyRepository, EchoIndexScores, HashtagRepository, NewContent, PlatformStatsRepository,
+        WebhookRepository,
+    };
+    use crate::services::{PlatformStatsService, TierConfig};
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use actix_web::{App, HttpServer};
+    use futures_util::StreamExt;
+    use sqlx::PgPool;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    const COMPONENTS: EchoIndexComponents = EchoIndexComponents { odf: 70.0, awr: 80.0, tpm: 60.0, qf: 50.0 };
+
+    #[actix_web::test]
+    async fn test_subscriber_receives_recalculated_score() {
+        let calculator = web::Data::new(RwLock::new(EchoIndexCalculator::default()));
+        let updates = web::Data::new(EchoIndexUpdates::new());
+        let metrics = web::Data::new(MetricsRegistry::new());
+
+        let server_updates = updates.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(server_updates.clone())
+                .service(echo_index_updates)
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let addr = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+
+        let (mut socket, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws/echo-index/content_1", addr))
+                .await
+                .unwrap();
+
+        let snapshot = socket.next().await.unwrap().unwrap();
+        let snapshot: serde_json::Value = serde_json::from_str(snapshot.to_text().unwrap()).unwrap();
+        assert!(snapshot["score"].is_null());
+
+        let app = init_service(
+            App::new()
+                .app_data(calculator.clone())
+                .app_data(updates.clone())
+                .app_data(metrics.clone())
+                .app_data(web::Data::new(EchoIndexPercentileCache::new()))
+                .service(calculate_echo_index),
+        )
+        .await;
+        let request = TestRequest::post()
+            .uri("/calculate")
+            .set_json(serde_json::json!({
+                "content_id": "content_1",
+                "content_type": "text",
+                "content_text": "Echoes travel further than the original voice",
+                "author_id": "author_1",
+                "platform": "twitter",
+                "metadata": { "shares": 10, "likes": 40, "comments": 5, "quotes": 3 }
+            }))
+            .to_request();
+        assert!(call_service(&app, request).await.status().is_success());
+
+        let pushed = tokio::time::timeout(Duration::from_millis(500), socket.next())
+            .await
+            .expect("update not pushed within 500ms")
+            .unwrap()
+            .unwrap();
+        let WsMessage::Text(payload) = pushed else { panic!("expected a text frame") };
+        let update: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(update["content_id"], "content_1");
+        assert!(update["score"].as_f64().unwrap() > 0.0);
+        assert_eq!(update["delta"], 0.0);
+        assert!(update["components"]["odf"].is_number());
+    }
+
+    /// Propagation data with `count` transmission paths, every fifth of them automated
+    fn propagation_with_paths(count: usize) -> PropagationData {
+        let start = Utc::now() - chrono::Duration::hours(48);
+        let platforms = [Platform::Twitter, Platform::Reddit, Platform::LinkedIn];
+        PropagationData {
+            shares: count as u32,
+            likes: 40,
+            comments: 10,
+            quotes: 5,
+            reach: 5_000,
+            engagement_rate: 0.05,
+            audience_quality: 0.7,
+            transmission_paths: (0..count)
+                .map(|i| TransmissionPath {
+                    from_user: format!("user_{}", i),
+                    to_user: format!("user_{}", i + 1),
+                    platform: platforms[i % platforms.len()].clone(),
+                    timestamp: start + chrono::Duration::minutes(37 * i as i64 % 2_880),
+                    interaction_type: "share".to_string(),
+                    weight: (i % 7) as f64 / 6.0,
+                    bot_score: if i % 5 == 0 { 0.9 } else { 0.1 },
+                })
+                .collect(),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_config_reports_percentile_tier_cutoffs() {
+        let percentiles = EchoIndexPercentileCache::new();
+        for score in 1..=100 {
+            percentiles.record(Uuid::new_v4(), score as f64);
+        }
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(RwLock::new(EchoIndexCalculator::default())))
+                .app_data(web::Data::new(percentiles))
+                .service(get_echo_index_config)
+                .service(get_echo_index),
+        )
+        .await;
+
+        let config: serde_json::Value =
+            read_body_json(call_service(&app, TestRequest::get().uri("/config").to_request()).await).await;
+        let cutoffs = serde_json::json!({ "gold_min": 96.0, "silver_min": 81.0, "bronze_min": 51.0 });
+        assert_eq!(config["tier_cutoffs"], cutoffs);
+        assert!(config["odf"].is_number());
+
+        // 74.4 ranks above 74 of the 100 scores, which no longer makes it Silver
+        let echo_index: serde_json::Value =
+            read_body_json(call_service(&app, TestRequest::get().uri("/content_1").to_request()).await).await;
+        assert_eq!(echo_index["percentile_rank"], 74.0);
+        assert_eq!(echo_index["echo_index"]["tier"], "Bronze");
+    }
+
+    #[actix_web::test]
+    async fn test_echo_index_fields_are_projected() {
+        let app = init_service(
+            App::new().app_data(web::Data::new(EchoIndexPercentileCache::new())).service(get_echo_index),
+        )
+        .await;
+        let get = |request: TestRequest| async { read_body_json(call_service(&app, request.to_request()).await).await };
+
+        let projected: serde_json::Value = get(TestRequest::get().uri("/content_1?fields=score")).await;
+        assert_eq!(projected, serde_json::json!({ "echo_index": { "score": 74.4 } }));
+
+        let projected: serde_json::Value =
+            get(TestRequest::get().uri("/content_1?fields=content_id,echo_index.odf,tier")).await;
+        assert_eq!(projected["content_id"], "content_1");
+        assert_eq!(projected["echo_index"].as_object().unwrap().len(), 2);
+        assert_eq!(projected["echo_index"]["odf"], 75.5);
+
+        let projected: serde_json::Value = get(TestRequest::get().uri("/content_1?fields=nonexistent")).await;
+        assert_eq!(projected, serde_json::json!({ "echo_index": {} }));
+
+        let minimal = TestRequest::get()
+            .uri("/content_1")
+            .insert_header(("Accept", "application/vnd.echolayer.v1+json; profile=\"minimal\""));
+        let projected: serde_json::Value = get(minimal).await;
+        assert_eq!(projected["echo_index"].as_object().unwrap().len(), 2);
+        assert!(projected["echo_index"]["tier"].is_string());
+
+        let full: serde_json::Value = get(TestRequest::get().uri("/content_1")).await;
+        assert_eq!(full["echo_index"].as_object().unwrap().len(), 6);
+        assert!(full["confidence"].is_object());
+    }
+
+    #[test]
+    fn test_confidence_grows_with_the_number_of_propagations() {
+        let calculator = EchoIndexCalculator::default();
+        let request = EchoIndexRequest {
+            content_id: "content_1".to_string(),
+            content_type: "text".to_string(),
+            content_text: "An original thought, carefully argued and worth passing on.".to_string(),
+            author_id: "author_1".to_string(),
+            platform: Platform::Twitter,
+            metadata: HashMap::new(),
+        };
+
+        let confidence = |count: usize| {
+            let propagation = propagation_with_paths(count);
+            let echo_index = EchoIndex::calculate(&request, &propagation, &calculator, &TierCutoffs::FIXED);
+            echo_index.confidence(&request.content_id, &propagation, &calculator)
+        };
+        let few = confidence(5);
+        let many = confidence(500);
+
+        assert_eq!((few.sample_size, few.confidence_level), (5, ConfidenceLevel::Low));
+        assert_eq!((many.sample_size, many.confidence_level), (500, ConfidenceLevel::VeryHigh));
+        for interval in [&few, &many] {
+            assert!(interval.lower_95 <= interval.score && interval.score <= interval.upper_95);
+        }
+        assert!(many.upper_95 - many.lower_95 < few.upper_95 - few.lower_95);
+        assert_eq!(confidence(5), few);
+    }
+
+    #[actix_web::test]
+    async fn test_leaderboard_stream_emits_rank_changes_in_order() {
+        let updates = Arc::new(EchoIndexUpdates::new());
+        let leaderboard = web::Data::new(LeaderboardCache::new(10));
+        leaderboard.clone().into_inner().spawn_update_task(updates.clone());
+
+        let server_leaderboard = leaderboard.clone();
+        let server = HttpServer::new(move || App::new().app_data(server_leaderboard.clone()).service(leaderboard_stream))
+            .workers(1)
+            .bind(("127.0.0.1", 0))
+            .unwrap();
+        let addr = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+
+        let mut response = reqwest::get(format!("http://{}/leaderboard/stream", addr)).await.unwrap();
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+        updates.publish("content_1", 80.0, COMPONENTS);
+        updates.publish("content_2", 90.0, COMPONENTS);
+
+        // Frames may be split or merged across chunks
+        let mut buffer = String::new();
+        while buffer.matches("\n\n").count() < 3 {
+            let chunk = tokio::time::timeout(Duration::from_secs(2), response.chunk())
+                .await
+                .expect("leaderboard events not streamed within 2s")
+                .unwrap()
+                .unwrap();
+            buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+        let frames: Vec<&str> = buffer.split_terminator("\n\n").collect();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0], "retry: 5000");
+
+        let events: Vec<serde_json::Value> = frames[1..]
+            .iter()
+            .map(|frame| {
+                let data = frame.strip_prefix("event: leaderboard_update\ndata: ").expect("a leaderboard_update event");
+                serde_json::from_str(data).unwrap()
+            })
+            .collect();
+        assert_eq!(
+            events[0]["rank_changes"],
+            serde_json::json!([{ "content_id": "content_1", "previous_rank": null, "rank": 1 }])
+        );
+        assert_eq!(
+            events[1]["rank_changes"],
+            serde_json::json!([
+                { "content_id": "content_2", "previous_rank": null, "rank": 1 },
+                { "content_id": "content_1", "previous_rank": 1, "rank": 2 },
+            ])
+        );
+        let board: Vec<&str> = events[1]["full_leaderboard"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["content_id"].as_str().unwrap())
+            .collect();
+        assert_eq!(board, ["content_2", "content_1"]);
+    }
+
+    async fn propagate(pool: &PgPool, content_id: Uuid, from: Uuid, to: Uuid, reaches: i64) {
+        sqlx::query(
+            "INSERT INTO propagations
+                 (content_id, source_user_id, target_user_id, propagation_type, source_platform, target_platform,
+                  source_external_id, target_external_id, engagement_metrics)
+             VALUES ($1, $2, $3, 'share', 'twitter', 'reddit', $2::text, $3::text,
+                     jsonb_build_object('reaches', $4::bigint, 'likes', 12, 'comments', 3, 'shares', 2))",
+        )
+        .bind(content_id)
+        .bind(from)
+        .bind(to)
+        .bind(reaches)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_recalculation_rescores_stored_propagations(pool: PgPool) {
+        let mut users = Vec::new();
+        for i in 0..6 {
+            let user: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+                .bind(format!("0xrecalculate{}", i))
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            users.push(user);
+        }
+        let content = ContentRepository::new(pool.clone());
+        let created = content
+            .create(&NewContent {
+                user_id: users[0],
+                platform: Platform::Twitter,
+                external_id: "tweet_recalculate".to_string(),
+                content_type: "text".to_string(),
+                title: "Recalculated".to_string(),
+                body: "Echoes are rescored from what was stored".to_string(),
+                media_urls: vec![],
+                tags: vec![],
+                reactions: HashMap::new(),
+            })
+            .await
+            .unwrap();
+        propagate(&pool, created.id, users[0], users[1], 100).await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(RwLock::new(EchoIndexCalculator::default())))
+                .app_data(web::Data::new(content))
+                .app_data(web::Data::new(EchoIndexHistoryRepository::new(pool.clone())))
+                .app_data(web::Data::new(EchoIndexUpdates::new()))
+                .app_data(web::Data::new(MetricsRegistry::new()))
+                .app_data(web::Data::new(UserEventRepository::new(pool.clone())))
+                .app_data(web::Data::new(EngineConfigStore::default()))
+                .app_data(web::Data::new(WebhookDispatcher::new(Arc::new(WebhookRepository::new(pool.clone())))))
+                .app_data(web::Data::new(ExperimentRepository::new(pool.clone())))
+                .app_data(web::Data::new(HashtagTrendService::new(
+                    Arc::new(HashtagRepository::new(pool.clone())),
+                    0.05,
+                )))
+                .app_data(web::Data::new(EchoIndexCache::new()))
+                .app_data(web::Data::new(EchoIndexAnomalyDetector::new(
+                    Arc::new(EchoAnomalyRepository::new(pool.clone())),
+                    Arc::new(EchoIndexHistoryRepository::new(pool.clone())),
+                )))
+                .app_data(web::Data::new(ColdStartService::new(Arc::new(PlatformStatsService::new(Arc::new(
+                    PlatformStatsRepository::new(pool.clone()),
+                ))))))
+                .app_data(web::Data::new(PropagationService::new()))
+                .app_data(web::Data::new(UserTierProgressionService::new(
+                    Arc::new(UserRepository::new(pool.clone())),
+                    Arc::new(UserEventRepository::new(pool.clone())),
+                    TierConfig::default(),
+                )))
+                .app_data(web::Data::new(UserRepository::new(pool.clone())))
+                .service(recalculate_echo_index),
+        )
+        .await;
+        let recalculate = || TestRequest::post().uri(&format!("/{}/recalculate", created.id)).to_request();
+
+        let response = call_service(&app, recalculate()).await;
+        assert_eq!(response.status(), 200);
+        let first: serde_json::Value = read_body_json(response).await;
+        assert!(first["previous"].is_null() && first["diff"].is_null());
+        // Propagated content has the data it needs
+        assert_eq!((first["is_cold_start"].as_bool(), first["data_sufficiency"].as_f64()), (Some(false), Some(1.0)));
+
+        // A second request within the minute is turned away
+        let response = call_service(&app, recalculate()).await;
+        assert_eq!(response.status(), 429);
+        assert!(response.headers().contains_key("Retry-After"));
+
+        for i in 1..5 {
+            propagate(&pool, created.id, users[i], users[i + 1], 1_000).await;
+        }
+        sqlx::query("UPDATE echo_index_history SET calculated_at = calculated_at - INTERVAL '2 minutes'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let response = call_service(&app, recalculate()).await;
+        assert_eq!(response.status(), 200);
+        let second: serde_json::Value = read_body_json(response).await;
+        // More propagations map onto a wider transmission path
+        let tpm = second["components"]["tpm"].as_f64().unwrap();
+        assert!(tpm > first["components"]["tpm"].as_f64().unwrap());
+        assert_eq!(second["previous"]["score"], first["score"]);
+        let score_change = second["score"].as_f64().unwrap() - first["score"].as_f64().unwrap();
+        assert!((second["diff"]["score"].as_f64().unwrap() - score_change).abs() < 1e-9);
+        assert!(second["diff"]["tpm"].as_f64().unwrap() > 0.0);
+
+        // Stored to two decimal places
+        let stored = ContentRepository::new(pool).find_by_id(created.id).await.unwrap();
+        assert!((stored.echo_index - second["score"].as_f64().unwrap()).abs() <= 0.005);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_leaderboard_route_is_not_taken_for_a_content_id(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xboard') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let created = ContentRepository::new(pool.clone())
+            .create(&NewContent {
+                user_id,
+                platform: Platform::Twitter,
+                external_id: "tweet_board".to_string(),
+                content_type: "text".to_string(),
+                title: "On the board".to_string(),
+                body: "Ranked by its peak".to_string(),
+                media_urls: vec![],
+                tags: vec![],
+                reactions: HashMap::new(),
+            })
+            .await
+            .unwrap();
+        let history = Arc::new(EchoIndexHistoryRepository::new(pool.clone()));
+        let scores = EchoIndexScores { score: 0.7, odf: 0.7, awr: 0.7, tpm: 0.7, qf: 0.7 };
+        history.record(created.id, scores, EchoIndexTrigger::Recalculation, None).await.unwrap();
+
+        // Registered as in main.rs, ahead of the catch-all `/{content_id}`
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(LeaderboardService::new(history)))
+                .app_data(web::Data::new(EchoIndexPercentileCache::new()))
+                .service(web::scope("/echo-index").service(get_leaderboard).service(get_echo_index)),
+        )
+        .await;
+
+        let response = call_service(&app, TestRequest::get().uri("/echo-index/leaderboard?time_range=24h").to_request())
+            .await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = read_body_json(response).await;
+        assert_eq!(body["time_range"], "24h");
+        assert_eq!(body["leaderboard"][0]["title"], "On the board");
+        assert_eq!(body["leaderboard"][0]["echo_index"], 70.0);
+
+        let response = call_service(&app, TestRequest::get().uri("/echo-index/leaderboard?time_range=1y").to_request())
+            .await;
+        assert_eq!(response.status(), 400);
+    }
+}
@@ -0,0 +1,275 @@
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::middleware::request_id::current_request_id;
+
+/// Why a request could not be authenticated
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AuthError {
+    #[error("Authentication required")]
+    MissingToken,
+    #[error("{0}")]
+    InvalidToken(String),
+    #[error("{0}")]
+    InvalidApiKey(String),
+    #[error("{0}")]
+    InvalidChallenge(String),
+    #[error("Wallet signature verification failed")]
+    InvalidSignature,
+    #[error("Invalid or expired refresh token")]
+    InvalidRefreshToken,
+    #[error("Two-factor authentication code required")]
+    TotpRequired,
+    #[error("Invalid two-factor authentication code")]
+    InvalidTotpCode,
+}
+
+impl AuthError {
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            AuthError::MissingToken => "missing_token",
+            AuthError::InvalidToken(_) => "invalid_token",
+            AuthError::InvalidApiKey(_) => "invalid_api_key",
+            AuthError::InvalidChallenge(_) => "invalid_challenge",
+            AuthError::InvalidSignature => "invalid_signature",
+            AuthError::InvalidRefreshToken => "invalid_refresh_token",
+            AuthError::TotpRequired => "totp_required",
+            AuthError::InvalidTotpCode => "invalid_totp_code",
+        }
+    }
+}
+
+/// A problem with one part of a request, or with the request as a whole without a `field`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ValidationError {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { field: None, message: message.into() }
+    }
+
+    pub fn field(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { field: Some(field.into()), message: message.into() }
+    }
+}
+
+/// Body of every error response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub success: bool,
+    /// One of the codes listed in docs/API.md, for clients to match on
+    #[schema(example = "not_found")]
+    pub error_code: &'static str,
+    pub message: String,
+    /// Specific to the error code, e.g. the `errors` of an `invalid_request`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<Value>,
+    pub timestamp: String,
+    /// ID of the request, filled in by the request ID middleware
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<Uuid>,
+}
+
+/// Error responses of the API, each rendered as an `ErrorResponse`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    NotFound(String),
+    Unauthorized(AuthError),
+    /// Authenticated, but not allowed to make the request
+    Forbidden(String),
+    /// An admin endpoint called from a session signed in without two-factor authentication
+    SecondFactorRequired,
+    BadRequest(Vec<ValidationError>),
+    /// The resource to create already exists as `existing_id`
+    Conflict { resource_type: String, existing_id: Uuid },
+    /// The propagation event was already recorded, as `duplicate_of`
+    Duplicate { duplicate_of: Uuid },
+    /// The request conflicts with the current state of a resource, e.g. an action
+    /// already taken on it
+    StateConflict(String),
+    RateLimited { retry_after_seconds: u32 },
+    /// A daily allowance was used up
+    LimitReached(String),
+    /// The cause is logged where the error is raised; clients get the request ID to quote
+    InternalError { request_id: Uuid },
+    ServiceUnavailable { dependency: String },
+}
+
+impl ApiError {
+    /// A request invalid as a whole
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        ApiError::BadRequest(vec![ValidationError::new(message)])
+    }
+
+    /// A request invalid because of one of its fields
+    pub fn invalid_field(field: impl Into<String>, message: impl Into<String>) -> Self {
+        ApiError::BadRequest(vec![ValidationError::field(field, message)])
+    }
+
+    /// An internal error of the request being handled
+    pub fn internal() -> Self {
+        ApiError::InternalError { request_id: current_request_id() }
+    }
+
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Unauthorized(e) => e.error_code(),
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::SecondFactorRequired => "totp_required",
+            ApiError::BadRequest(_) => "invalid_request",
+            ApiError::Conflict { .. } => "already_exists",
+            ApiError::Duplicate { .. } => "duplicate_event",
+            ApiError::StateConflict(_) => "conflict",
+            ApiError::RateLimited { .. } => "rate_limited",
+            ApiError::LimitReached(_) => "limit_reached",
+            ApiError::InternalError { .. } => "internal_error",
+            ApiError::ServiceUnavailable { .. } => "service_unavailable",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ApiError::NotFound(message)
+            | ApiError::Forbidden(message)
+            | ApiError::StateConflict(message)
+            | ApiError::LimitReached(message) => message.clone(),
+            ApiError::Unauthorized(e) => e.to_string(),
+            ApiError::SecondFactorRequired => "Requires signing in with two-factor authentication".to_string(),
+            ApiError::BadRequest(errors) => {
+                let messages: Vec<&str> = errors.iter().map(|error| error.message.as_str()).collect();
+                messages.join("; ")
+            }
+            ApiError::Conflict { resource_type, existing_id } => {
+                format!("The {} already exists as {}", resource_type, existing_id)
+            }
+            ApiError::Duplicate { duplicate_of } => format!("The event was already recorded as {}", duplicate_of),
+            ApiError::RateLimited { retry_after_seconds } => {
+                format!("Too many requests, please retry in {} seconds", retry_after_seconds)
+            }
+            ApiError::InternalError { .. } => "Internal server error".to_string(),
+            ApiError::ServiceUnavailable { dependency } => format!("{} is unavailable, please retry later", dependency),
+        }
+    }
+
+    pub fn details(&self) -> Option<Value> {
+        match self {
+            ApiError::BadRequest(errors) => Some(json!({ "errors": errors })),
+            ApiError::Conflict { resource_type, existing_id } => {
+                Some(json!({ "resource_type": resource_type, "existing_id": existing_id }))
+            }
+            ApiError::Duplicate { duplicate_of } => Some(json!({ "duplicate_of": duplicate_of })),
+            ApiError::RateLimited { retry_after_seconds } => {
+                Some(json!({ "retry_after_seconds": retry_after_seconds }))
+            }
+            ApiError::InternalError { request_id } => Some(json!({ "request_id": request_id })),
+            ApiError::ServiceUnavailable { dependency } => Some(json!({ "dependency": dependency })),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.error_code(), self.message())
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) | ApiError::SecondFactorRequired => StatusCode::FORBIDDEN,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Conflict { .. } | ApiError::Duplicate { .. } | ApiError::StateConflict(_) => {
+                StatusCode::CONFLICT
+            }
+            ApiError::RateLimited { .. } | ApiError::LimitReached(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::InternalError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::ServiceUnavailable { .. } => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let body = ErrorResponse {
+            success: false,
+            error_code: self.error_code(),
+            message: self.message(),
+            details: self.details(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            request_id: None,
+        };
+
+        let mut response = HttpResponse::build(self.status_code());
+        if let ApiError::RateLimited { retry_after_seconds } = self {
+            response.insert_header(("Retry-After", retry_after_seconds.to_string()));
+        }
+        response.json(body)
+    }
+}
+
+impl From<AuthError> for ApiError {
+    fn from(error: AuthError) -> Self {
+        ApiError::Unauthorized(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+
+    async fn render(error: ApiError) -> (u16, Option<String>, Value) {
+        let response = error.error_response();
+        let status = response.status().as_u16();
+        let retry_after = response.headers().get("Retry-After").map(|value| value.to_str().unwrap().to_string());
+        let body = to_bytes(response.into_body()).await.unwrap();
+        (status, retry_after, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[actix_web::test]
+    async fn test_errors_render_their_code_and_details() {
+        let (status, _, body) = render(ApiError::Unauthorized(AuthError::TotpRequired)).await;
+        assert_eq!((status, body["error_code"].as_str()), (401, Some("totp_required")));
+        assert_eq!(body["message"], "Two-factor authentication code required");
+        assert!(body.get("details").is_none());
+
+        let invalid = ApiError::BadRequest(vec![
+            ValidationError::field("limit", "limit must be 1 to 100"),
+            ValidationError::new("cursor is invalid"),
+        ]);
+        let (status, _, body) = render(invalid).await;
+        assert_eq!((status, body["error_code"].as_str()), (400, Some("invalid_request")));
+        assert_eq!(body["message"], "limit must be 1 to 100; cursor is invalid");
+        assert_eq!(
+            body["details"]["errors"],
+            json!([{"field": "limit", "message": "limit must be 1 to 100"}, {"message": "cursor is invalid"}])
+        );
+
+        let existing_id = Uuid::new_v4();
+        let (status, _, body) = render(ApiError::Conflict { resource_type: "content".to_string(), existing_id }).await;
+        assert_eq!((status, body["details"]["existing_id"].as_str()), (409, Some(existing_id.to_string().as_str())));
+        let (status, _, body) = render(ApiError::Duplicate { duplicate_of: existing_id }).await;
+        assert_eq!((status, body["details"]["duplicate_of"].as_str()), (409, Some(existing_id.to_string().as_str())));
+
+        let (status, retry_after, body) = render(ApiError::RateLimited { retry_after_seconds: 30 }).await;
+        assert_eq!((status, retry_after.as_deref()), (429, Some("30")));
+        assert_eq!(body["details"]["retry_after_seconds"], 30);
+
+        // Outside a request there is no request ID to report
+        let (status, _, body) = render(ApiError::internal()).await;
+        assert_eq!((status, body["message"].as_str()), (500, Some("Internal server error")));
+        assert_eq!(body["details"]["request_id"], Uuid::nil().to_string());
+    }
+}
@@ -0,0 +1,137 @@
+use actix_web::{get, web, HttpResponse, Result};
+use serde::Deserialize;
+use serde_json::json;
+use utoipa::IntoParams;
+
+use crate::handlers::content::ContentResponse;
+use crate::handlers::errors::ApiError;
+use crate::models::pagination::ScoreCursor;
+use crate::models::Platform;
+use crate::repositories::{ContentRepository, RepositoryError};
+use crate::services::hashtag_trends::{DEFAULT_TREND_WINDOW_HOURS, MAX_TREND_WINDOW_HOURS};
+use crate::services::HashtagTrendService;
+
+/// Default and maximum number of hashtags or content items per response
+const DEFAULT_LIMIT: u32 = 20;
+const MAX_LIMIT: u32 = 100;
+
+#[derive(Deserialize, IntoParams)]
+pub struct TrendingHashtagsQuery {
+    pub platform: Option<Platform>,
+    pub hours: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct HashtagContentQuery {
+    pub limit: Option<u32>,
+    pub after: Option<String>,
+}
+
+/// Hashtags most used within the last `hours`, with whether their use is rising or falling.
+/// Topics common in content riding the rising ones come back as `suggestions`.
+#[utoipa::path(
+    context_path = "/api/v1/hashtags",
+    operation_id = "get_trending_hashtags",
+    tag = "hashtags",
+    params(TrendingHashtagsQuery),
+    responses(
+        (
+            status = 200,
+            description = "Trending hashtags, most used first, and auto-tag `suggestions`",
+            body = [TrendingHashtag]
+        ),
+        (status = 400, description = "Invalid window"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/trending")]
+pub async fn get_trending_hashtags(
+    query: web::Query<TrendingHashtagsQuery>,
+    trends: web::Data<HashtagTrendService>,
+) -> Result<HttpResponse> {
+    let hours = query.hours.unwrap_or(DEFAULT_TREND_WINDOW_HOURS);
+    if hours == 0 || hours > MAX_TREND_WINDOW_HOURS {
+        return Err(ApiError::bad_request(format!("hours must be between 1 and {}", MAX_TREND_WINDOW_HOURS)).into());
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let hashtags = match trends.trending(query.platform.clone(), hours, limit).await {
+        Ok(hashtags) => hashtags,
+        Err(e) => return Err(repository_error(e).into()),
+    };
+    let suggestions = match trends.suggestions(query.platform.clone(), hours, &hashtags).await {
+        Ok(suggestions) => suggestions,
+        Err(e) => return Err(repository_error(e).into()),
+    };
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": hashtags,
+        "suggestions": suggestions,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Content tagged with a hashtag, highest Echo Index first, with cursor-based pagination
+#[utoipa::path(
+    context_path = "/api/v1/hashtags",
+    operation_id = "get_hashtag_content",
+    tag = "hashtags",
+    params(("tag" = String, Path, description = "Hashtag, with or without the `#`"), HashtagContentQuery),
+    responses(
+        (status = 200, description = "A page of tagged content, highest Echo Index first", body = [ContentResponse]),
+        (status = 400, description = "Empty tag or invalid cursor"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{tag}/content")]
+pub async fn get_hashtag_content(
+    path: web::Path<String>,
+    query: web::Query<HashtagContentQuery>,
+    repository: web::Data<ContentRepository>,
+) -> Result<HttpResponse> {
+    // Hashtags are indexed lowercased and without the `#`
+    let tag = path.trim_start_matches('#').to_lowercase();
+    if tag.is_empty() {
+        return Err(ApiError::bad_request("tag must not be empty").into());
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let after = match query.after.as_deref().map(ScoreCursor::decode).transpose() {
+        Ok(after) => after,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    // Fetch one extra item to learn whether another page exists
+    let mut content = match repository.list_by_hashtag(&tag, limit + 1, after).await {
+        Ok(content) => content,
+        Err(e) => return Err(repository_error(e).into()),
+    };
+    let has_more = content.len() > limit as usize;
+    content.truncate(limit as usize);
+    let next_cursor = if has_more {
+        content.last().map(|record| ScoreCursor::new(record.echo_index, record.id).encode())
+    } else {
+        None
+    };
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": content.into_iter().map(ContentResponse::from).collect::<Vec<_>>(),
+        "next_cursor": next_cursor,
+        "has_more": has_more,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+fn repository_error(error: RepositoryError) -> ApiError {
+    match error {
+        RepositoryError::InvalidCursor(message) | RepositoryError::InvalidInput(message) => {
+            ApiError::bad_request(message)
+        }
+        e => {
+            tracing::error!(error = %e, "Hashtag query failed");
+            ApiError::internal()
+        }
+    }
+}
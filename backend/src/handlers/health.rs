@@ -1 +1,134 @@
- 
\ No newline at end of file
+use actix_web::{get, web, HttpResponse, Result};
+use serde_json::json;
+use sqlx::PgPool;
+use std::time::Duration;
+
+use crate::services::{MetricsRegistry, PropagationService, RewardService, TokenBlacklist, WebhookDispatcher};
+
+/// How long the database may take to answer the readiness probe
+const DATABASE_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Liveness probe
+#[utoipa::path(
+    context_path = "/api/v1",
+    operation_id = "health_check",
+    tag = "health",
+    responses(
+        (status = 200, description = "Server is up, with its `version` and `token_blacklist_size`", body = Object),
+    ),
+    security((), ("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/health")]
+pub async fn health_check(blacklist: web::Data<TokenBlacklist>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(json!({
+        "status": "healthy",
+        "version": env!("CARGO_PKG_VERSION"),
+        "token_blacklist_size": blacklist.blacklist_size(),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Readiness probe: 503 while the database is unreachable, the reward pool is used up or
+/// webhook deliveries are backlogged
+#[utoipa::path(
+    context_path = "/api/v1",
+    operation_id = "ready_check",
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready, with the result of each of the `checks`", body = Object),
+        (status = 503, description = "Not ready; `checks` names the failing dependency", body = Object),
+    ),
+    security((), ("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/ready")]
+pub async fn ready_check(
+    pool: web::Data<PgPool>,
+    reward_service: web::Data<tokio::sync::RwLock<RewardService>>,
+    webhooks: web::Data<WebhookDispatcher>,
+) -> Result<HttpResponse> {
+    let database = tokio::time::timeout(DATABASE_PROBE_TIMEOUT, sqlx::query("SELECT 1").execute(pool.get_ref())).await;
+    let database_ok = matches!(database, Ok(Ok(_)));
+    if !database_ok {
+        log::warn!("Readiness probe could not reach the database");
+    }
+    let pool_ok = reward_service.read().await.get_pool_status().1 > 0.0;
+    let webhooks_ok = !webhooks.is_backlogged();
+
+    let ready = database_ok && pool_ok && webhooks_ok;
+    let mut response = if ready { HttpResponse::Ok() } else { HttpResponse::ServiceUnavailable() };
+    Ok(response.json(json!({
+        "status": if ready { "ready" } else { "not_ready" },
+        "checks": {
+            "database": if database_ok { "ok" } else { "fail" },
+            "reward_pool": if pool_ok { "ok" } else { "depleted" },
+            "webhook_queue": if webhooks_ok { "ok" } else { "backlogged" },
+        },
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Prometheus scrape endpoint
+#[utoipa::path(
+    operation_id = "metrics",
+    tag = "health",
+    responses(
+        (status = 200, description = "Prometheus text exposition format", body = String, content_type = "text/plain"),
+    ),
+    security((), ("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/metrics")]
+pub async fn metrics(
+    metrics: web::Data<MetricsRegistry>,
+    propagation_service: web::Data<PropagationService>,
+    reward_service: web::Data<tokio::sync::RwLock<RewardService>>,
+) -> Result<HttpResponse> {
+    // Gauges are sampled at scrape time rather than tracked on every change
+    metrics.active_echo_loops.set(propagation_service.active_loop_count() as i64);
+    metrics.reward_pool_remaining.set(reward_service.read().await.get_pool_status().1);
+
+    let body = metrics.encode().map_err(|e| {
+        tracing::error!(error = %e, "Failed to encode metrics");
+        actix_web::error::ErrorInternalServerError("Failed to encode metrics")
+    })?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(prometheus::TEXT_FORMAT)
+        .body(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::WebhookRepository;
+    use actix_web::{test, App};
+    use sqlx::postgres::PgPoolOptions;
+    use std::sync::Arc;
+
+    #[actix_web::test]
+    async fn test_unreachable_database_fails_readiness_but_not_liveness() {
+        // Nothing listens on the discard port
+        let pool = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(500))
+            .connect_lazy("postgres://echolayer@127.0.0.1:9/echolayer")
+            .unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(TokenBlacklist::new()))
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(tokio::sync::RwLock::new(RewardService::new(10_000.0))))
+                .app_data(web::Data::new(WebhookDispatcher::new(Arc::new(WebhookRepository::new(pool)))))
+                .service(health_check)
+                .service(ready_check),
+        )
+        .await;
+
+        let response = test::call_service(&app, test::TestRequest::get().uri("/health").to_request()).await;
+        assert_eq!(response.status(), 200);
+
+        let response = test::call_service(&app, test::TestRequest::get().uri("/ready").to_request()).await;
+        assert_eq!(response.status(), 503);
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(body["status"], "not_ready");
+        assert_eq!(body["checks"], json!({ "database": "fail", "reward_pool": "ok", "webhook_queue": "ok" }));
+    }
+}
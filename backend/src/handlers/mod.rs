@@ -3,4 +3,10 @@ pub mod users;
 pub mod content;
 pub mod echo_index;
 pub mod auth;
-pub mod platforms; 
\ No newline at end of file
+pub mod admin;
+pub mod errors;
+pub mod hashtags;
+pub mod openapi;
+pub mod platforms; 
+pub mod projection;
+pub mod propagation;
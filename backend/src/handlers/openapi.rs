@@ -0,0 +1,421 @@
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::{Content, ObjectBuilder, OpenApi as OpenApiDoc, Ref, RefOr, Schema, SchemaFormat, SchemaType};
+use utoipa::{Modify, OpenApi};
+
+use crate::handlers::admin::{DecayConfigRequest, ExperimentRequest, ReviewFlagRequest, SetRoleRequest};
+use crate::handlers::auth::{
+    AuthResponse, RefreshTokenRequest, SessionResponse, TokenResponse, UserPreferences, UserProfile, WalletAuthRequest,
+    WalletType,
+};
+use crate::handlers::content::{
+    ContentResponse, CreateContentRequest, FlagContentRequest, LinkContentRequest, RelatedContentResponse,
+    SearchResultResponse,
+};
+use crate::handlers::echo_index::{
+    BatchRecalculateRequest, EchoIndex, EchoIndexConfigResponse, EchoIndexRequest, EchoIndexResponse,
+    EchoIndexWeightsConfig,
+};
+use crate::handlers::errors::{ErrorResponse, ValidationError};
+use crate::handlers::propagation::{
+    BulkPropagationRequest, BulkPropagationResponse, CreatePropagationRequest, EngagementMetrics, PropagationResponse,
+};
+use crate::handlers::users::{
+    ClaimBatchRequest, ConfigureAlertRequest, CreateApiKeyRequest, CreateUserRequest, CreateWebhookRequest,
+    FollowedUserResponse, LeaderboardEntryResponse, UpdateUserRequest, UserResponse,
+};
+use crate::handlers::{admin, auth, content, echo_index, hashtags, health, platforms, propagation, users};
+use crate::models::api_key::Permission;
+use crate::models::content::{ContentCategory, ContentSort, EchoIndexWeights, ReactionType, SortOrder};
+use crate::models::content_attribution::{AttributedContent, AttributionGraph, AttributionType, ContentAttribution};
+use crate::models::content_version::{ContentVersion, DiffLine, DiffOp, VersionDiff};
+use crate::models::echo_index::{ConfidenceLevel, EchoIndexConfidence};
+use crate::models::echo_index_history::HistoryGranularity;
+use crate::models::moderation::{FlagReason, ModerationDecision, ModerationFlag, ModerationStatus};
+use crate::models::platform_stats::PlatformEchoStats;
+use crate::models::reward_analytics::Granularity;
+use crate::models::session::Session;
+use crate::models::trending::TrendingScore;
+use crate::models::user::{Role, UserTier};
+use crate::models::velocity_alert::VelocityThreshold;
+use crate::models::webhook::WebhookEvent;
+use crate::models::Platform;
+use crate::services::batch_jobs::{JobState, JobStatus};
+use crate::services::content_clusters::{ClusterMembership, ClusterNeighbor, ContentCluster};
+use crate::services::content_versioning::VersionHistory;
+use crate::services::echo_percentiles::TierCutoffs;
+use crate::services::hashtag_trends::{HashtagSuggestion, TrendDirection, TrendingHashtag};
+use crate::services::merkle_rewards::{MerkleRewardClaim, RewardProof};
+use crate::services::moderation::FlagOutcome;
+use crate::services::platform_stats::RefreshSchedule;
+
+/// Route the OpenAPI description is served at
+pub const OPENAPI_PATH: &str = "/api-docs/openapi.json";
+
+/// Tags whose handlers reply with their data as is; the others wrap it as
+/// `{"success": true, "data", "timestamp"}`
+const UNWRAPPED_TAGS: [&str; 3] = ["health", "auth", "echo-index"];
+
+/// OpenAPI description of the API, from the `utoipa::path` annotations of the handlers.
+/// `info.version` is bumped whenever the description changes; CI checks the committed
+/// openapi.json against both.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "EchoLayer API",
+        version = "1.18.0",
+        description = "Echo Index scoring, propagation tracking and EchoDrop rewards for content \
+                       across social platforms. Errors are described in docs/API.md."
+    ),
+    paths(
+        health::health_check,
+        health::ready_check,
+        health::metrics,
+        auth::get_auth_challenge,
+        auth::login_with_wallet,
+        auth::logout,
+        auth::verify_token,
+        auth::refresh_token,
+        auth::list_sessions,
+        auth::revoke_session,
+        auth::revoke_other_sessions,
+        content::create_content,
+        content::get_content,
+        content::get_trending_content,
+        content::list_content,
+        content::search_content,
+        content::update_content,
+        content::list_content_versions,
+        content::get_content_version,
+        content::diff_content_versions,
+        content::get_related_content,
+        content::get_content_cluster,
+        content::delete_content,
+        content::flag_content,
+        content::link_content,
+        content::get_content_attributions,
+        hashtags::get_trending_hashtags,
+        hashtags::get_hashtag_content,
+        platforms::get_platform_stats,
+        platforms::get_platform_stats_last_updated,
+        platforms::get_platform_top_content,
+        echo_index::echo_index_updates,
+        echo_index::calculate_echo_index,
+        echo_index::leaderboard_stream,
+        echo_index::get_echo_index_config,
+        echo_index::update_echo_index_config,
+        echo_index::get_platform_config,
+        echo_index::update_platform_config,
+        echo_index::batch_recalculate_echo_index,
+        echo_index::get_batch_job,
+        echo_index::get_echo_index,
+        echo_index::get_echo_index_history,
+        echo_index::get_echo_index_forecast,
+        echo_index::recalculate_echo_index,
+        propagation::create_propagation,
+        propagation::bulk_create_propagations,
+        propagation::get_propagation_network,
+        propagation::get_propagation_communities,
+        propagation::get_propagation_depth_analysis,
+        propagation::get_propagation_cycles,
+        propagation::get_deep_propagations,
+        propagation::get_propagation_analytics,
+        propagation::export_propagation_graph,
+        users::create_user,
+        users::get_leaderboard,
+        users::get_user,
+        users::update_user,
+        users::delete_user,
+        users::export_user_data,
+        users::import_content,
+        users::get_user_analytics,
+        users::get_user_reward_analytics,
+        users::get_claimable_rewards,
+        users::process_user_rewards,
+        users::claim_rewards_batch,
+        users::get_pending_transactions,
+        users::get_earnings_forecast,
+        users::get_user_timeline,
+        users::get_user_content,
+        users::get_user_feed,
+        users::get_user_streak,
+        users::freeze_user_streak,
+        users::get_user_influence,
+        users::follow_user,
+        users::unfollow_user,
+        users::get_followers,
+        users::get_following,
+        users::create_api_key,
+        users::revoke_api_key,
+        users::setup_two_factor,
+        users::confirm_two_factor,
+        users::get_recovery_codes,
+        users::configure_alert,
+        users::list_alerts,
+        users::create_webhook,
+        users::list_webhooks,
+        users::delete_webhook,
+        users::list_webhook_deliveries,
+        users::start_social_verification,
+        users::complete_social_verification,
+        admin::list_on_hold_rewards,
+        admin::approve_held_reward,
+        admin::reject_held_reward,
+        admin::set_user_role,
+        admin::get_archive_stats,
+        admin::set_content_decay_config,
+        admin::get_pool_utilization_history,
+        admin::get_reward_analytics,
+        admin::get_cache_stats,
+        admin::get_content_clusters,
+        admin::list_pending_quality_bonuses,
+        admin::get_moderation_queue,
+        admin::get_flagged_edits,
+        admin::review_flag,
+        admin::list_anomalies,
+        admin::dismiss_anomaly,
+        admin::confirm_anomaly,
+        admin::list_amplification_events,
+        admin::revert_amplification_event,
+        admin::create_experiment,
+        admin::list_experiments,
+        admin::get_experiment,
+        admin::update_experiment,
+        admin::delete_experiment,
+        admin::get_experiment_results,
+        admin::get_engine_config,
+        admin::update_engine_config,
+    ),
+    components(schemas(
+        ErrorResponse,
+        ValidationError,
+        Platform,
+        WalletAuthRequest,
+        WalletType,
+        AuthResponse,
+        UserProfile,
+        UserPreferences,
+        Role,
+        UserTier,
+        RefreshTokenRequest,
+        TokenResponse,
+        SessionResponse,
+        Session,
+        ContentResponse,
+        CreateContentRequest,
+        FlagContentRequest,
+        LinkContentRequest,
+        RelatedContentResponse,
+        SearchResultResponse,
+        ContentSort,
+        ReactionType,
+        ContentCategory,
+        SortOrder,
+        TrendingScore,
+        AttributedContent,
+        AttributionGraph,
+        AttributionType,
+        ContentAttribution,
+        ContentVersion,
+        DiffLine,
+        DiffOp,
+        VersionDiff,
+        VersionHistory,
+        FlagReason,
+        ModerationDecision,
+        ModerationFlag,
+        ModerationStatus,
+        FlagOutcome,
+        ClusterMembership,
+        ClusterNeighbor,
+        ContentCluster,
+        TrendingHashtag,
+        TrendDirection,
+        HashtagSuggestion,
+        PlatformEchoStats,
+        RefreshSchedule,
+        EchoIndexRequest,
+        EchoIndexResponse,
+        EchoIndex,
+        EchoIndexConfidence,
+        ConfidenceLevel,
+        EchoIndexWeightsConfig,
+        EchoIndexConfigResponse,
+        TierCutoffs,
+        BatchRecalculateRequest,
+        JobStatus,
+        JobState,
+        HistoryGranularity,
+        CreatePropagationRequest,
+        BulkPropagationRequest,
+        BulkPropagationResponse,
+        PropagationResponse,
+        EngagementMetrics,
+        CreateUserRequest,
+        UpdateUserRequest,
+        UserResponse,
+        LeaderboardEntryResponse,
+        FollowedUserResponse,
+        ClaimBatchRequest,
+        MerkleRewardClaim,
+        RewardProof,
+        Granularity,
+        CreateApiKeyRequest,
+        Permission,
+        ConfigureAlertRequest,
+        VelocityThreshold,
+        CreateWebhookRequest,
+        WebhookEvent,
+        SetRoleRequest,
+        DecayConfigRequest,
+        ReviewFlagRequest,
+        ExperimentRequest,
+        EchoIndexWeights,
+    )),
+    modifiers(&SecuritySchemes, &ResponseConventions),
+    tags(
+        (name = "health", description = "Liveness, readiness and metrics"),
+        (name = "auth", description = "Wallet sign-in, tokens and sessions"),
+        (name = "content", description = "Content and its versions, relations and moderation"),
+        (name = "hashtags", description = "Trending hashtags and tagged content"),
+        (name = "analytics", description = "Echo Index statistics of each platform"),
+        (name = "echo-index", description = "Echo Index scores, their configuration and live updates"),
+        (name = "propagation", description = "Propagations of content and their networks"),
+        (name = "users", description = "Users, their rewards, follows, credentials and notifications"),
+        (name = "admin", description = "Review of rewards, content and anomalies, and engine configuration"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Access tokens from `POST /auth/login`, or API keys for machine clients
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut OpenApiDoc) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(crate::middleware::jwt::API_KEY_HEADER))),
+        );
+    }
+}
+
+/// Wraps the data of successful responses in the response envelope and gives error
+/// responses the `ErrorResponse` body, so handlers only describe their own data
+struct ResponseConventions;
+
+impl Modify for ResponseConventions {
+    fn modify(&self, openapi: &mut OpenApiDoc) {
+        for path in openapi.paths.paths.values_mut() {
+            for operation in path.operations.values_mut() {
+                let tags = operation.tags.iter().flatten();
+                let wrapped = !tags.into_iter().any(|tag| UNWRAPPED_TAGS.contains(&tag.as_str()));
+                // Cursor-paginated listings take the cursor of their next page as `after`
+                let paginated = operation.parameters.iter().flatten().any(|parameter| parameter.name == "after");
+
+                for (status, response) in operation.responses.responses.iter_mut() {
+                    let RefOr::T(response) = response else { continue };
+                    if status.starts_with('2') {
+                        if let (true, Some(content)) = (wrapped, response.content.get_mut("application/json")) {
+                            content.schema = envelope(content.schema.clone(), paginated);
+                        }
+                    } else if response.content.is_empty() {
+                        let error = Content::new(Ref::from_schema_name("ErrorResponse"));
+                        response.content.insert("application/json".to_string(), error);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn envelope(data: RefOr<Schema>, paginated: bool) -> RefOr<Schema> {
+    let mut envelope = ObjectBuilder::new()
+        .property("success", ObjectBuilder::new().schema_type(SchemaType::Boolean))
+        .required("success")
+        .property("data", data)
+        .required("data");
+    if paginated {
+        envelope = envelope
+            .property("next_cursor", ObjectBuilder::new().schema_type(SchemaType::String).nullable(true))
+            .required("next_cursor")
+            .property("has_more", ObjectBuilder::new().schema_type(SchemaType::Boolean))
+            .required("has_more");
+    }
+    let timestamp = ObjectBuilder::new().schema_type(SchemaType::String).format(Some(SchemaFormat::KnownFormat(
+        utoipa::openapi::KnownFormat::DateTime,
+    )));
+    envelope.property("timestamp", timestamp).required("timestamp").into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::collections::BTreeSet;
+
+    /// Committed description, compared against the generated one
+    const COMMITTED_SPEC: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json");
+
+    fn collect_refs(value: &Value, refs: &mut BTreeSet<String>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    refs.insert(reference.clone());
+                }
+                map.values().for_each(|value| collect_refs(value, refs));
+            }
+            Value::Array(values) => values.iter().for_each(|value| collect_refs(value, refs)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_spec_is_valid_openapi_with_secured_operations() {
+        let json = ApiDoc::openapi().to_pretty_json().unwrap();
+        let spec: openapiv3::OpenAPI = serde_json::from_str(&json).unwrap();
+        assert!(spec.openapi.starts_with("3.0"));
+
+        let components = spec.components.as_ref().unwrap();
+        let schemes: BTreeSet<&str> = components.security_schemes.keys().map(String::as_str).collect();
+        assert_eq!(schemes, BTreeSet::from(["api_key", "bearer_auth"]));
+
+        let mut operations = 0;
+        for (path, item) in spec.paths.iter() {
+            for (method, operation) in item.as_item().unwrap().iter() {
+                operations += 1;
+                let security = operation.security.as_ref().unwrap_or_else(|| panic!("{} {} is unsecured", method, path));
+                let referenced: BTreeSet<&str> = security.iter().flat_map(|r| r.keys()).map(String::as_str).collect();
+                assert!(!referenced.is_empty(), "{} {} references no security scheme", method, path);
+                assert!(referenced.is_subset(&schemes), "{} {} references unknown schemes", method, path);
+                assert!(operation.operation_id.is_some(), "{} {} has no operation ID", method, path);
+            }
+        }
+        assert!(operations > 0);
+
+        // Every schema referenced is among the components
+        let mut refs = BTreeSet::new();
+        collect_refs(&serde_json::from_str(&json).unwrap(), &mut refs);
+        for reference in refs {
+            let name = reference.trim_start_matches("#/components/schemas/");
+            assert!(components.schemas.contains_key(name), "{} is not a component", reference);
+        }
+    }
+
+    #[test]
+    fn test_committed_spec_is_current() {
+        let generated = ApiDoc::openapi().to_pretty_json().unwrap() + "\n";
+        if std::env::var_os("UPDATE_OPENAPI").is_some() {
+            std::fs::write(COMMITTED_SPEC, &generated).unwrap();
+        }
+        let committed = std::fs::read_to_string(COMMITTED_SPEC).unwrap_or_default();
+        assert!(
+            committed == generated,
+            "openapi.json is out of date; regenerate it with `UPDATE_OPENAPI=1 cargo test openapi` and bump \
+             `info.version` in handlers/openapi.rs"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use actix_web::{get, web, HttpResponse, Result};
+use serde::Deserialize;
+use serde_json::json;
+use utoipa::IntoParams;
+
+use crate::handlers::content::ContentResponse;
+use crate::handlers::errors::ApiError;
+use crate::models::Platform;
+use crate::repositories::ContentRepository;
+use crate::services::PlatformStatsService;
+
+/// Default and maximum number of content items per response
+const DEFAULT_LIMIT: u32 = 20;
+const MAX_LIMIT: u32 = 100;
+
+#[derive(Deserialize, IntoParams)]
+pub struct TopContentQuery {
+    pub limit: Option<u32>,
+}
+
+/// Echo Index statistics of every platform, highest average Echo Index first
+#[utoipa::path(
+    context_path = "/api/v1/analytics",
+    operation_id = "get_platform_stats",
+    tag = "analytics",
+    responses(
+        (status = 200, description = "Statistics of every platform", body = [PlatformEchoStats]),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/platforms")]
+pub async fn get_platform_stats(stats: web::Data<PlatformStatsService>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": stats.all(),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// When the platform statistics were last recomputed and when they will be next
+#[utoipa::path(
+    context_path = "/api/v1/analytics",
+    operation_id = "get_platform_stats_last_updated",
+    tag = "analytics",
+    responses(
+        (
+            status = 200,
+            description = "When the statistics were last and will next be refreshed",
+            body = RefreshSchedule
+        ),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/platforms/last-updated")]
+pub async fn get_platform_stats_last_updated(stats: web::Data<PlatformStatsService>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": stats.schedule(),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// The platform's content with the highest Echo Index
+#[utoipa::path(
+    context_path = "/api/v1/analytics",
+    operation_id = "get_platform_top_content",
+    tag = "analytics",
+    params(("platform" = Platform, Path, description = "Platform"), TopContentQuery),
+    responses(
+        (status = 200, description = "The platform's content, highest Echo Index first", body = [ContentResponse]),
+        (status = 400, description = "Unknown platform"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/platforms/{platform}/top-content")]
+pub async fn get_platform_top_content(
+    path: web::Path<String>,
+    query: web::Query<TopContentQuery>,
+    repository: web::Data<ContentRepository>,
+) -> Result<HttpResponse> {
+    let platform = Platform::from(path.as_str());
+    if let Platform::Other(name) = &platform {
+        return Err(ApiError::bad_request(format!("Unknown platform: {}", name)).into());
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    match repository.list_top_by_platform(&platform, limit).await {
+        Ok(content) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": content.into_iter().map(ContentResponse::from).collect::<Vec<_>>(),
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => {
+            tracing::error!(error = %e, platform = %platform, "Top content query failed");
+            Err(ApiError::internal().into())
+        }
+    }
+}
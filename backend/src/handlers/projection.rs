@@ -0,0 +1,214 @@
+use actix_web::http::header::ACCEPT;
+use actix_web::HttpRequest;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use utoipa::IntoParams;
+
+/// Vendor media type of the versioned API
+pub const VENDOR_MEDIA_TYPE: &str = "application/vnd.echolayer.v1+json";
+/// Fields the `minimal` profile of the vendor media type stands for
+pub const MINIMAL_PROFILE_FIELDS: &str = "score,tier";
+
+#[derive(Deserialize, IntoParams)]
+pub struct ProjectionQuery {
+    /// Comma-separated fields to keep, as dot-separated paths
+    pub fields: Option<String>,
+}
+
+/// Keeps only the requested fields of a serialized response, so clients that need a
+/// couple of values are not sent everything. Fields are dot-separated paths such as
+/// `echo_index.score`; a path ending at an object keeps the whole object. Paths that
+/// match nothing are left out rather than rejected, leaving their parent object empty.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectionFilter {
+    fields: BTreeMap<String, Projection>,
+    /// Object that paths naming no top-level field are looked up in
+    default_object: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Projection {
+    All,
+    Fields(BTreeMap<String, Projection>),
+}
+
+impl ProjectionFilter {
+    /// Filter for comma-separated fields, or None when no field is named
+    pub fn parse(fields: &str) -> Option<Self> {
+        let mut filter = Self::default();
+        for path in fields.split(',').map(str::trim).filter(|path| !path.is_empty()) {
+            let segments: Vec<&str> = path.split('.').map(str::trim).collect();
+            if segments.iter().any(|segment| segment.is_empty()) {
+                continue;
+            }
+            insert(&mut filter.fields, &segments);
+        }
+
+        (!filter.fields.is_empty()).then_some(filter)
+    }
+
+    /// Filter for the `fields` query parameter, or for the `minimal` profile when the
+    /// request accepts only that. An explicit `fields` parameter wins.
+    pub fn from_request(query: &ProjectionQuery, req: &HttpRequest) -> Option<Self> {
+        match &query.fields {
+            Some(fields) => Self::parse(fields),
+            None if accepts_minimal_profile(req) => Self::parse(MINIMAL_PROFILE_FIELDS),
+            None => None,
+        }
+    }
+
+    /// Look up paths whose first segment is not a top-level field in `key` instead, so
+    /// `score` means `echo_index.score`
+    pub fn with_default_object(mut self, key: &str) -> Self {
+        self.default_object = Some(key.to_string());
+        self
+    }
+
+    /// The value with only the requested fields
+    pub fn apply(&self, value: Value) -> Value {
+        let Some(default_object) = &self.default_object else {
+            return project(value, &self.fields);
+        };
+
+        let mut fields = BTreeMap::new();
+        let mut relative = BTreeMap::new();
+        for (key, projection) in &self.fields {
+            let target = if value.get(key).is_some() { &mut fields } else { &mut relative };
+            target.insert(key.clone(), projection.clone());
+        }
+        if !relative.is_empty() {
+            merge(&mut fields, default_object.clone(), Projection::Fields(relative));
+        }
+        project(value, &fields)
+    }
+}
+
+fn insert(fields: &mut BTreeMap<String, Projection>, segments: &[&str]) {
+    let projection = if segments.len() == 1 {
+        Projection::All
+    } else {
+        let mut nested = BTreeMap::new();
+        insert(&mut nested, &segments[1..]);
+        Projection::Fields(nested)
+    };
+    merge(fields, segments[0].to_string(), projection);
+}
+
+/// Add a projection of a field, keeping the whole field if either asks for all of it
+fn merge(fields: &mut BTreeMap<String, Projection>, key: String, projection: Projection) {
+    match (fields.get_mut(&key), projection) {
+        (None, projection) => {
+            fields.insert(key, projection);
+        }
+        (Some(Projection::All), _) => {}
+        (Some(existing), Projection::All) => *existing = Projection::All,
+        (Some(Projection::Fields(existing)), Projection::Fields(nested)) => {
+            for (key, projection) in nested {
+                merge(existing, key, projection);
+            }
+        }
+    }
+}
+
+fn project(value: Value, fields: &BTreeMap<String, Projection>) -> Value {
+    match value {
+        Value::Object(mut object) => {
+            let mut projected = Map::new();
+            for (key, projection) in fields {
+                let Some(field) = object.remove(key) else {
+                    continue;
+                };
+                match projection {
+                    Projection::All => {
+                        projected.insert(key.clone(), field);
+                    }
+                    Projection::Fields(nested) if field.is_object() || field.is_array() => {
+                        projected.insert(key.clone(), project(field, nested));
+                    }
+                    // A path into a number or string matches nothing
+                    Projection::Fields(_) => {}
+                }
+            }
+            Value::Object(projected)
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(|item| project(item, fields)).collect()),
+        value => value,
+    }
+}
+
+/// Whether the request's Accept header asks for the vendor media type's `minimal` profile
+fn accepts_minimal_profile(req: &HttpRequest) -> bool {
+    let Some(accept) = req.headers().get(ACCEPT).and_then(|value| value.to_str().ok()) else {
+        return false;
+    };
+
+    accept.split(',').any(|media_range| {
+        let mut parts = media_range.split(';').map(str::trim);
+        let is_vendor_type = parts.next().is_some_and(|media_type| media_type.eq_ignore_ascii_case(VENDOR_MEDIA_TYPE));
+        is_vendor_type
+            && parts.any(|parameter| {
+                parameter.split_once('=').is_some_and(|(name, value)| {
+                    name.trim().eq_ignore_ascii_case("profile") && value.trim().trim_matches('"') == "minimal"
+                })
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use serde_json::json;
+
+    fn response() -> Value {
+        json!({
+            "content_id": "content_1",
+            "echo_index": {"odf": 75.5, "awr": 82.3, "score": 74.4, "tier": "Silver"},
+            "confidence": {"lower": 60.0, "upper": 90.0, "propagation_count": 12}
+        })
+    }
+
+    #[test]
+    fn test_projection_keeps_requested_paths() {
+        let filter = ProjectionFilter::parse("content_id, echo_index.score,confidence.lower,,").unwrap();
+        assert_eq!(
+            filter.apply(response()),
+            json!({"content_id": "content_1", "echo_index": {"score": 74.4}, "confidence": {"lower": 60.0}})
+        );
+
+        // A whole object wins over paths into it
+        let filter = ProjectionFilter::parse("echo_index.score,echo_index").unwrap();
+        assert_eq!(filter.apply(response()), json!({"echo_index": response()["echo_index"]}));
+
+        let rows = json!([{"id": 1, "score": 2.0}, {"id": 2, "score": 3.0}]);
+        assert_eq!(ProjectionFilter::parse("score").unwrap().apply(rows), json!([{"score": 2.0}, {"score": 3.0}]));
+
+        assert_eq!(ProjectionFilter::parse(" , ."), None);
+    }
+
+    #[test]
+    fn test_projection_of_unknown_fields_is_empty() {
+        let filter = ProjectionFilter::parse("echo_index.unknown,content_id.length,missing").unwrap();
+        assert_eq!(filter.apply(response()), json!({"echo_index": {}}));
+
+        let filter = ProjectionFilter::parse("unknown").unwrap().with_default_object("echo_index");
+        assert_eq!(filter.apply(response()), json!({"echo_index": {}}));
+    }
+
+    #[test]
+    fn test_minimal_profile_implies_score_and_tier() {
+        let query = ProjectionQuery { fields: None };
+        let minimal = TestRequest::default()
+            .insert_header((ACCEPT, "application/json;q=0.5, application/vnd.echolayer.v1+json; profile=\"minimal\""))
+            .to_http_request();
+        let filter = ProjectionFilter::from_request(&query, &minimal).unwrap().with_default_object("echo_index");
+        assert_eq!(filter.apply(response()), json!({"echo_index": {"score": 74.4, "tier": "Silver"}}));
+
+        let full = TestRequest::default().insert_header((ACCEPT, VENDOR_MEDIA_TYPE)).to_http_request();
+        assert_eq!(ProjectionFilter::from_request(&query, &full), None);
+
+        let query = ProjectionQuery { fields: Some("odf".to_string()) };
+        assert_eq!(ProjectionFilter::from_request(&query, &minimal), ProjectionFilter::parse("odf"));
+    }
+}
@@ -1,36 +1,128 @@
 use actix_web::{get, post, web, HttpResponse, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::{HashMap, HashSet, VecDeque};
+use tokio::sync::RwLock;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Deserialize)]
+use crate::handlers::auth::Claims;
+use crate::handlers::errors::ApiError;
+use crate::models::user_event::UserEvent;
+use crate::models::Platform;
+use crate::repositories::{ContentRepository, NewPropagation, UserEventRepository};
+use crate::services::propagation::PropagationPath;
+use crate::services::{NodeType, PropagationEventError};
+use crate::services::{Community, LoopStrength, PropagationCommunityDetector, PropagationDepthAnalyzer};
+use crate::services::{IdempotencyCache, MentionLinker, MetricsRegistry, PropagationService, RecalculationQueue};
+use crate::services::{PropagationData, PropagationEventDeduplicator, RewardService};
+
+/// Largest batch accepted by the bulk ingestion endpoint
+pub const MAX_BULK_EVENTS: usize = 5_000;
+/// JSON body limit for the propagation scope, large enough for a full bulk batch
+pub const MAX_BULK_PAYLOAD_BYTES: usize = 4 * 1024 * 1024;
+
+/// Depth from which propagations count as deep when no `min_depth` is given
+const DEFAULT_DEEP_PROPAGATION_DEPTH: i32 = 5;
+/// Most deep propagations listed at once
+const MAX_DEEP_PROPAGATIONS: i64 = 100;
+
+/// Weight of a single propagation in its reward, before it is part of any Echo Loop
+const PROPAGATION_REWARD_WEIGHT: f64 = 1.0;
+/// Interaction strength of a propagation between two users in its content's Echo Loop
+const PROPAGATION_INTERACTION_STRENGTH: f64 = 1.0;
+/// Influence weight of users in the Echo Loop of content they propagate
+const PROPAGATOR_INFLUENCE_WEIGHT: f64 = 0.5;
+
+/// Values of the `propagation_type` database enum
+const PROPAGATION_TYPES: &[&str] = &["share", "repost", "quote", "mention", "link", "embed", "cross_post"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct CreatePropagationRequest {
     pub content_id: String,
     pub source_user_id: Option<String>,
     pub target_user_id: Option<String>,
+    /// `share`, `repost`, `quote`, `mention`, `link`, `embed` or `cross_post`
+    #[schema(example = "share")]
     pub propagation_type: String,
-    pub source_platform: String,
-    pub target_platform: String,
+    pub source_platform: Platform,
+    pub target_platform: Platform,
     pub source_external_id: Option<String>,
     pub target_external_id: Option<String>,
 }
 
-#[derive(Serialize)]
+impl CreatePropagationRequest {
+    /// The event as stored, or why it cannot be
+    pub fn to_new_propagation(&self) -> Result<NewPropagation, String> {
+        let parse_user_id = |raw: &Option<String>, field: &str| {
+            raw.as_deref()
+                .map(Uuid::parse_str)
+                .transpose()
+                .map_err(|_| format!("{} must be a valid UUID", field))
+        };
+        let content_id = Uuid::parse_str(&self.content_id).map_err(|_| "content_id must be a valid UUID".to_string())?;
+
+        if !PROPAGATION_TYPES.contains(&self.propagation_type.as_str()) {
+            return Err(format!("unknown propagation_type '{}'", self.propagation_type));
+        }
+        for platform in [&self.source_platform, &self.target_platform] {
+            if let Platform::Other(name) = platform {
+                return Err(format!("unsupported platform '{}'", name));
+            }
+        }
+
+        Ok(NewPropagation {
+            content_id,
+            source_user_id: parse_user_id(&self.source_user_id, "source_user_id")?,
+            target_user_id: parse_user_id(&self.target_user_id, "target_user_id")?,
+            propagation_type: self.propagation_type.clone(),
+            source_platform: self.source_platform.clone(),
+            target_platform: self.target_platform.clone(),
+            source_external_id: self.source_external_id.clone(),
+            target_external_id: self.target_external_id.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkPropagationRequest {
+    pub events: Vec<CreatePropagationRequest>,
+    /// Retrying with the same key within 24 hours returns the original response
+    pub idempotency_key: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BulkPropagationResponse {
+    pub processed: usize,
+    /// Events repeated within the batch or already stored
+    pub duplicates_skipped: usize,
+    /// Rejected events, as `[position in the batch, reason]` pairs
+    #[schema(value_type = Vec<Vec<Value>>)]
+    pub failed: Vec<(usize, String)>,
+    /// Content items queued for Echo Index recalculation
+    pub echo_index_updates_queued: usize,
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct PropagationResponse {
     pub id: String,
     pub content_id: String,
     pub source_user_id: Option<String>,
     pub target_user_id: Option<String>,
     pub propagation_type: String,
-    pub source_platform: String,
-    pub target_platform: String,
+    pub source_platform: Platform,
+    pub target_platform: Platform,
     pub echo_boost: f64,
     pub reward_amount: f64,
     pub engagement_metrics: EngagementMetrics,
+    /// Whether the event was checked for duplicates before being stored
+    pub fingerprint_checked: bool,
+    /// Earlier propagation of the same event, stored before the deduplication window
+    pub duplicate_of: Option<Uuid>,
     pub created_at: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct EngagementMetrics {
     pub views: u32,
     pub likes: u32,
@@ -45,7 +137,7 @@ pub struct EngagementMetrics {
 pub struct PropagationNode {
     pub id: String,
     pub user_id: String,
-    pub platform: String,
+    pub platform: Platform,
     pub influence: f64,
     pub echo_score: f64,
 }
@@ -64,6 +156,14 @@ pub struct PropagationNetwork {
     pub nodes: Vec<PropagationNode>,
     pub edges: Vec<PropagationEdge>,
     pub metrics: NetworkMetrics,
+    /// Communities of two or more nodes, only detected on request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub communities: Option<Vec<Community>>,
+    /// Strength of the content's active Echo Loops
+    pub echo_loops: Vec<LoopStrength>,
+    /// Daily decay factor of the content's path resonance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decay_factor: Option<f64>,
 }
 
 #[derive(Serialize)]
@@ -73,23 +173,378 @@ pub struct NetworkMetrics {
     pub density: f64,
     pub average_path_length: f64,
     pub clustering_coefficient: f64,
+    /// Longest shortest path between any two connected nodes
+    pub diameter: u32,
+    /// Betweenness centrality per node, only computed on request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub betweenness_centrality: Option<Vec<(String, f64)>>,
+}
+
+impl PropagationNetwork {
+    pub fn new(nodes: Vec<PropagationNode>, edges: Vec<PropagationEdge>, include_centrality: bool) -> Self {
+        let metrics = NetworkMetrics::compute(&nodes, &edges, include_centrality);
+        Self { nodes, edges, metrics, communities: None, echo_loops: Vec::new(), decay_factor: None }
+    }
+
+    pub fn with_communities(mut self) -> Self {
+        self.communities = Some(PropagationCommunityDetector::detect(&self));
+        self
+    }
+
+    pub fn with_echo_loops(mut self, echo_loops: Vec<LoopStrength>) -> Self {
+        self.echo_loops = echo_loops;
+        self
+    }
+
+    pub fn with_decay_factor(mut self, decay_factor: f64) -> Self {
+        self.decay_factor = Some(decay_factor);
+        self
+    }
+}
+
+impl NetworkMetrics {
+    /// Compute graph metrics, treating propagation edges as undirected links
+    pub fn compute(nodes: &[PropagationNode], edges: &[PropagationEdge], include_centrality: bool) -> Self {
+        let index: HashMap<&str, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id.as_str(), i))
+            .collect();
+
+        // Parallel edges and self-loops do not add connectivity
+        let mut neighbors: Vec<HashSet<usize>> = vec![HashSet::new(); nodes.len()];
+        for edge in edges {
+            let (Some(&a), Some(&b)) = (index.get(edge.source_id.as_str()), index.get(edge.target_id.as_str())) else {
+                continue;
+            };
+            if a != b {
+                neighbors[a].insert(b);
+                neighbors[b].insert(a);
+            }
+        }
+
+        let n = nodes.len();
+        let edge_count = neighbors.iter().map(HashSet::len).sum::<usize>() / 2;
+        let density = if n > 1 {
+            2.0 * edge_count as f64 / (n * (n - 1)) as f64
+        } else {
+            0.0
+        };
+        let (average_path_length, diameter) = Self::path_lengths(&neighbors);
+        let betweenness_centrality = include_centrality.then(|| {
+            Self::betweenness(&neighbors)
+                .into_iter()
+                .zip(nodes)
+                .map(|(centrality, node)| (node.id.clone(), centrality))
+                .collect()
+        });
+
+        Self {
+            total_nodes: n as u32,
+            total_edges: edge_count as u32,
+            density,
+            average_path_length,
+            clustering_coefficient: Self::clustering(&neighbors),
+            diameter,
+            betweenness_centrality,
+        }
+    }
+
+    /// Mean local clustering coefficient; nodes with degree below 2 count as 0
+    fn clustering(neighbors: &[HashSet<usize>]) -> f64 {
+        if neighbors.is_empty() {
+            return 0.0;
+        }
+
+        let total: f64 = neighbors
+            .iter()
+            .map(|adjacent| {
+                let k = adjacent.len();
+                if k < 2 {
+                    return 0.0;
+                }
+                let triangles = adjacent
+                    .iter()
+                    .flat_map(|&u| adjacent.iter().map(move |&v| (u, v)))
+                    .filter(|&(u, v)| u < v && neighbors[u].contains(&v))
+                    .count();
+                2.0 * triangles as f64 / (k * (k - 1)) as f64
+            })
+            .sum();
+
+        total / neighbors.len() as f64
+    }
+
+    /// BFS from every node: mean shortest path over connected pairs, and the diameter
+    fn path_lengths(neighbors: &[HashSet<usize>]) -> (f64, u32) {
+        let mut total = 0u64;
+        let mut pairs = 0u64;
+        let mut diameter = 0u32;
+
+        for source in 0..neighbors.len() {
+            let distances = Self::bfs(neighbors, source);
+            for &distance in distances.iter().skip(source + 1).flatten() {
+                total += distance as u64;
+                pairs += 1;
+                diameter = diameter.max(distance);
+            }
+        }
+
+        let average = if pairs > 0 { total as f64 / pairs as f64 } else { 0.0 };
+        (average, diameter)
+    }
+
+    fn bfs(neighbors: &[HashSet<usize>], source: usize) -> Vec<Option<u32>> {
+        let mut distances = vec![None; neighbors.len()];
+        let mut queue = VecDeque::from([source]);
+        distances[source] = Some(0);
+
+        while let Some(v) = queue.pop_front() {
+            let next = distances[v].unwrap_or(0) + 1;
+            for &w in &neighbors[v] {
+                if distances[w].is_none() {
+                    distances[w] = Some(next);
+                    queue.push_back(w);
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Brandes' algorithm for unweighted graphs; pairs are counted once per direction
+    /// and halved, since links are undirected
+    pub(crate) fn betweenness(neighbors: &[HashSet<usize>]) -> Vec<f64> {
+        let n = neighbors.len();
+        let mut centrality = vec![0.0; n];
+
+        for source in 0..n {
+            let mut stack = Vec::with_capacity(n);
+            let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+            let mut paths = vec![0.0; n];
+            let mut distances: Vec<Option<u32>> = vec![None; n];
+            paths[source] = 1.0;
+            distances[source] = Some(0);
+
+            let mut queue = VecDeque::from([source]);
+            while let Some(v) = queue.pop_front() {
+                stack.push(v);
+                let next = distances[v].unwrap_or(0) + 1;
+                for &w in &neighbors[v] {
+                    if distances[w].is_none() {
+                        distances[w] = Some(next);
+                        queue.push_back(w);
+                    }
+                    if distances[w] == Some(next) {
+                        paths[w] += paths[v];
+                        predecessors[w].push(v);
+                    }
+                }
+            }
+
+            let mut dependency = vec![0.0; n];
+            while let Some(w) = stack.pop() {
+                for &v in &predecessors[w] {
+                    dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+                }
+                if w != source {
+                    centrality[w] += dependency[w];
+                }
+            }
+        }
+
+        centrality.iter().map(|c| c / 2.0).collect()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct NetworkQuery {
+    /// Betweenness centrality is O(N*E), so it is opt-in
+    #[serde(default)]
+    pub include_centrality: bool,
+    #[serde(default)]
+    pub include_communities: bool,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ExportQuery {
+    /// `graphml` (the default) or `dot`
+    pub format: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct DeepPropagationsQuery {
+    pub min_depth: Option<i32>,
+}
+
+/// Add a propagation between two users to the Echo Loop of its content, which refuses
+/// propagations closing a cycle or reaching past the maximum depth. Propagations without
+/// both users are not part of any loop.
+async fn add_to_echo_loop(
+    propagation_service: &PropagationService,
+    propagation: &NewPropagation,
+) -> Result<(), PropagationEventError> {
+    let (Some(source_user_id), Some(target_user_id)) = (propagation.source_user_id, propagation.target_user_id) else {
+        return Ok(());
+    };
+    let loop_id = propagation_service
+        .content_echo_loop(&propagation.content_id.to_string())
+        .await
+        .map_err(PropagationEventError::Loop)?;
+    propagation_service
+        .add_propagation_event(
+            &loop_id,
+            propagator_node(source_user_id),
+            propagator_node(target_user_id),
+            PROPAGATION_INTERACTION_STRENGTH,
+        )
+        .await
+}
+
+fn propagator_node(user_id: Uuid) -> crate::services::PropagationNode {
+    crate::services::PropagationNode {
+        id: user_id.to_string(),
+        node_type: NodeType::User,
+        influence_weight: PROPAGATOR_INFLUENCE_WEIGHT,
+        reach: 1,
+        engagement_rate: 0.0,
+        timestamp: chrono::Utc::now(),
+    }
 }
 
 /// Create a new propagation record
+#[utoipa::path(
+    context_path = "/api/v1/propagation",
+    operation_id = "create_propagation",
+    tag = "propagation",
+    request_body = CreatePropagationRequest,
+    responses(
+        (status = 201, description = "Propagation recorded", body = PropagationResponse),
+        (status = 400, description = "Invalid content or user ID, propagation type or platform"),
+        (
+            status = 403,
+            description = "`source_user_id` is another user and the caller is not an admin who passed the second factor"
+        ),
+        (status = 404, description = "Content or user not found"),
+        (
+            status = 409,
+            description = "Event already recorded within the deduplication window, as `duplicate_of`, leading \
+                           back to a user the content already reached through the source user, or making its share \
+                           chain deeper than `PROPAGATION_MAX_DEPTH`"
+        ),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[post("")]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_propagation(
-    propagation_data: web::Json<CreatePropagationRequest>
+    claims: web::ReqData<Claims>,
+    propagation_data: web::Json<CreatePropagationRequest>,
+    deduplicator: web::Data<PropagationEventDeduplicator>,
+    metrics: web::Data<MetricsRegistry>,
+    events: web::Data<UserEventRepository>,
+    mentions: web::Data<MentionLinker>,
+    content: web::Data<ContentRepository>,
+    rewards: web::Data<RwLock<RewardService>>,
+    propagation_service: web::Data<PropagationService>,
 ) -> Result<HttpResponse> {
+    let new_propagation = propagation_data.to_new_propagation().map_err(ApiError::bad_request)?;
+    // The source user is rewarded and has the propagation on their timeline
+    let source_user_id = new_propagation.source_user_id;
+    if source_user_id.is_some_and(|id| claims.sub != id.to_string()) && !claims.is_verified_admin() {
+        return Err(ApiError::Forbidden("Only admins can record propagations by another user".to_string()).into());
+    }
+    match deduplicator.duplicate_of(&propagation_data).await {
+        Ok(Some(duplicate_of)) => return Err(ApiError::Duplicate { duplicate_of }.into()),
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to check propagation for duplicates");
+            return Err(ApiError::internal().into());
+        }
+    }
+    // Refused before it is stored if it would close a loop in the content's share chains
+    add_to_echo_loop(&propagation_service, &new_propagation).await.map_err(|e| match e {
+        PropagationEventError::Loop(e) => {
+            tracing::error!(error = %e, "Failed to add propagation to its Echo Loop");
+            ApiError::internal()
+        }
+        rejected => ApiError::StateConflict(rejected.to_string()),
+    })?;
+    // Stored unless a concurrent request recorded the same event in the meantime
+    let outcome = match deduplicator.record(&[new_propagation]).await {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to store propagation");
+            return Err(ApiError::internal().into());
+        }
+    };
+    if let Some((_, message)) = outcome.rejected.into_iter().next() {
+        return Err(ApiError::NotFound(message).into());
+    }
+    let Some(stored) = outcome.inserted.into_iter().next() else {
+        let duplicate_of = outcome.duplicates[0].1;
+        return Err(ApiError::Duplicate { duplicate_of }.into());
+    };
+    metrics.record_propagation(&stored.propagation_type);
+
+    // Anonymous propagations have no timeline to land on
+    if let Some(user_id) = source_user_id {
+        let event = UserEvent::PropagationMade {
+            content_id: stored.content_id,
+            target_platform: propagation_data.target_platform.clone(),
+            echo_boost: stored.echo_boost,
+        };
+        if let Err(e) = events.record(user_id, &event).await {
+            log::warn!("Failed to record propagation {} on timeline: {}", stored.id, e);
+        }
+    }
+
+    // Users mentioned in the content share in its further propagation
+    if let Err(e) = mentions.reward_propagations(stored.content_id, 1).await {
+        log::warn!("Failed to reward mentions in {}: {}", stored.content_id, e);
+    }
+
+    // The propagator earns a reward, less their tier's fee, and the creator a share of it
+    let mut reward_amount = 0.0;
+    if let Some(propagator) = source_user_id {
+        let reward = match content.find_by_id(stored.content_id).await {
+            Ok(record) => {
+                let data = PropagationData {
+                    original_creator_id: record.user_id.to_string(),
+                    propagation_weight: PROPAGATION_REWARD_WEIGHT,
+                    loop_strength: 0.0,
+                };
+                let mut rewards = rewards.write().await;
+                rewards
+                    .process_content_propagation(propagator.to_string(), stored.content_id.to_string(), data)
+                    .await
+                    .map(|reward_ids| {
+                        // The propagator's reward comes first
+                        let awarded = rewards.get_user_rewards(&propagator.to_string());
+                        reward_ids
+                            .first()
+                            .and_then(|id| awarded.iter().find(|reward| &reward.id == id))
+                            .map_or(0.0, |reward| reward.amount)
+                    })
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        match reward {
+            Ok(amount) => reward_amount = amount,
+            Err(e) => log::warn!("Failed to reward propagation {}: {}", stored.id, e),
+        }
+    }
+
     let propagation = PropagationResponse {
-        id: Uuid::new_v4().to_string(),
+        id: stored.id.to_string(),
         content_id: propagation_data.content_id.clone(),
         source_user_id: propagation_data.source_user_id.clone(),
         target_user_id: propagation_data.target_user_id.clone(),
         propagation_type: propagation_data.propagation_type.clone(),
         source_platform: propagation_data.source_platform.clone(),
         target_platform: propagation_data.target_platform.clone(),
-        echo_boost: 1.25, // Calculated based on propagation quality
-        reward_amount: 5.0, // Token reward for successful propagation
+        echo_boost: stored.echo_boost,
+        reward_amount,
         engagement_metrics: EngagementMetrics {
             views: 150,
             likes: 12,
@@ -99,7 +554,9 @@ pub async fn create_propagation(
             clicks: 25,
             saves: 4,
         },
-        created_at: chrono::Utc::now().to_rfc3339(),
+        fingerprint_checked: true,
+        duplicate_of: stored.duplicate_of,
+        created_at: stored.created_at.to_rfc3339(),
     };
 
     Ok(HttpResponse::Created().json(json!({
@@ -109,37 +566,183 @@ pub async fn create_propagation(
     })))
 }
 
-/// Get propagation network for content
-#[get("/{content_id}/network")]
-pub async fn get_propagation_network(path: web::Path<String>) -> Result<HttpResponse> {
-    let _content_id = path.into_inner();
-    
-    // Mock propagation network data
-    let network = PropagationNetwork {
-        nodes: vec![
+/// Ingest a batch of propagation events from a platform connector
+#[utoipa::path(
+    context_path = "/api/v1/propagation",
+    operation_id = "bulk_create_propagations",
+    tag = "propagation",
+    request_body = BulkPropagationRequest,
+    responses(
+        (
+            status = 200,
+            description = "Events stored, skipped and rejected; repeated for a retried `idempotency_key`",
+            body = BulkPropagationResponse
+        ),
+        (status = 400, description = "No `idempotency_key`, or too many events"),
+        (
+            status = 409,
+            description = "Every event was already recorded within the deduplication window; `duplicate_of` is the \
+                           first event's propagation"
+        ),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/bulk")]
+pub async fn bulk_create_propagations(
+    request: web::Json<BulkPropagationRequest>,
+    deduplicator: web::Data<PropagationEventDeduplicator>,
+    idempotency: web::Data<IdempotencyCache<BulkPropagationResponse>>,
+    recalculations: web::Data<RecalculationQueue>,
+    metrics: web::Data<MetricsRegistry>,
+    mentions: web::Data<MentionLinker>,
+    propagation_service: web::Data<PropagationService>,
+) -> Result<HttpResponse> {
+    let request = request.into_inner();
+    if request.idempotency_key.trim().is_empty() || request.events.len() > MAX_BULK_EVENTS {
+        let message =
+            format!("idempotency_key is required and at most {} events are accepted per batch", MAX_BULK_EVENTS);
+        return Err(ApiError::bad_request(message).into());
+    }
+
+    if let Some(response) = idempotency.get(&request.idempotency_key) {
+        return Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": response,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        })));
+    }
+
+    let mut failed = Vec::new();
+    let mut duplicates_skipped = 0;
+    let mut seen = HashSet::new();
+    // Batch position of each event sent to the repository
+    let mut positions = Vec::with_capacity(request.events.len());
+    let mut unique = Vec::with_capacity(request.events.len());
+    for (index, event) in request.events.iter().enumerate() {
+        match event.to_new_propagation() {
+            Ok(propagation) => {
+                if seen.insert(propagation.dedup_key()) {
+                    positions.push(index);
+                    unique.push(propagation);
+                } else {
+                    duplicates_skipped += 1;
+                }
+            }
+            Err(message) => failed.push((index, message)),
+        }
+    }
+
+    // Events recorded within the window are skipped before the batch takes any locks
+    let known = match deduplicator.duplicates_of(&unique).await {
+        Ok(known) => known,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to check propagations for duplicates");
+            return Err(ApiError::internal().into());
+        }
+    };
+    let unique_count = unique.len();
+    // Batch position and repeated propagation of each duplicate
+    let mut duplicates = Vec::new();
+    let mut fresh_positions = Vec::with_capacity(unique_count);
+    let mut fresh = Vec::with_capacity(unique_count);
+    for ((position, propagation), duplicate_of) in positions.into_iter().zip(unique).zip(known) {
+        if let Some(duplicate_of) = duplicate_of {
+            duplicates.push((position, duplicate_of));
+            continue;
+        }
+        // In batch order, so an event can close a loop with earlier events of the batch
+        match add_to_echo_loop(&propagation_service, &propagation).await {
+            Ok(()) => {
+                fresh_positions.push(position);
+                fresh.push(propagation);
+            }
+            Err(PropagationEventError::Loop(e)) => {
+                tracing::error!(error = %e, "Failed to add propagation to its Echo Loop");
+                return Err(ApiError::internal().into());
+            }
+            Err(rejected) => failed.push((position, rejected.to_string())),
+        }
+    }
+
+    let outcome = match deduplicator.record(&fresh).await {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            tracing::error!(error = %e, "Bulk propagation ingestion failed");
+            return Err(ApiError::internal().into());
+        }
+    };
+    duplicates.extend(outcome.duplicates.iter().map(|(i, duplicate_of)| (fresh_positions[*i], *duplicate_of)));
+    duplicates.sort_unstable_by_key(|(position, _)| *position);
+
+    // Nothing new to take from a batch made only of events already recorded
+    if let Some((_, duplicate_of)) = duplicates.first() {
+        if duplicates.len() == unique_count && failed.is_empty() {
+            return Err(ApiError::Duplicate { duplicate_of: *duplicate_of }.into());
+        }
+    }
+
+    duplicates_skipped += duplicates.len();
+    failed.extend(outcome.rejected.into_iter().map(|(i, message)| (fresh_positions[i], message)));
+    failed.sort_unstable_by_key(|(index, _)| *index);
+
+    // Propagations stored per content item
+    let mut affected: HashMap<Uuid, usize> = HashMap::new();
+    for propagation in &outcome.inserted {
+        metrics.record_propagation(&propagation.propagation_type);
+        *affected.entry(propagation.content_id).or_default() += 1;
+    }
+    for (content_id, propagations) in &affected {
+        if let Err(e) = mentions.reward_propagations(*content_id, *propagations).await {
+            log::warn!("Failed to reward mentions in {}: {}", content_id, e);
+        }
+    }
+    let echo_index_updates_queued = affected
+        .into_keys()
+        .filter(|content_id| recalculations.enqueue(*content_id))
+        .count();
+
+    let response = BulkPropagationResponse {
+        processed: outcome.inserted.len(),
+        duplicates_skipped,
+        failed,
+        echo_index_updates_queued,
+    };
+    idempotency.insert(&request.idempotency_key, response.clone());
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": response,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Mock propagation network of a content item
+fn content_network(_content_id: &str, include_centrality: bool) -> PropagationNetwork {
+    PropagationNetwork::new(
+        vec![
             PropagationNode {
                 id: "node_1".to_string(),
                 user_id: "user_1".to_string(),
-                platform: "twitter".to_string(),
+                platform: Platform::Twitter,
                 influence: 85.5,
                 echo_score: 92.3,
             },
             PropagationNode {
                 id: "node_2".to_string(),
                 user_id: "user_2".to_string(),
-                platform: "telegram".to_string(),
+                platform: Platform::Telegram,
                 influence: 72.8,
                 echo_score: 78.1,
             },
             PropagationNode {
                 id: "node_3".to_string(),
                 user_id: "user_3".to_string(),
-                platform: "linkedin".to_string(),
+                platform: Platform::LinkedIn,
                 influence: 68.2,
                 echo_score: 74.5,
             },
         ],
-        edges: vec![
+        vec![
             PropagationEdge {
                 source_id: "node_1".to_string(),
                 target_id: "node_2".to_string(),
@@ -155,14 +758,39 @@ pub async fn get_propagation_network(path: web::Path<String>) -> Result<HttpResp
                 timestamp: chrono::Utc::now().to_rfc3339(),
             },
         ],
-        metrics: NetworkMetrics {
-            total_nodes: 3,
-            total_edges: 2,
-            density: 0.33,
-            average_path_length: 1.5,
-            clustering_coefficient: 0.0,
-        },
-    };
+        include_centrality,
+    )
+}
+
+/// Get propagation network for content
+#[utoipa::path(
+    context_path = "/api/v1/propagation",
+    operation_id = "get_propagation_network",
+    tag = "propagation",
+    params(("content_id" = String, Path, description = "Content ID"), NetworkQuery),
+    responses(
+        (status = 200, description = "Nodes, edges and metrics of the propagation network", body = Object),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{content_id}/network")]
+pub async fn get_propagation_network(
+    path: web::Path<String>,
+    query: web::Query<NetworkQuery>,
+    propagation_service: web::Data<PropagationService>,
+) -> Result<HttpResponse> {
+    let content_id = path.into_inner();
+    propagation_service.load_content_echo_loops(&content_id).await.map_err(|e| {
+        tracing::error!(%content_id, error = %e, "Failed to load Echo Loops");
+        ApiError::internal()
+    })?;
+
+    let mut network = content_network(&content_id, query.include_centrality)
+        .with_echo_loops(propagation_service.content_loop_strengths(&content_id))
+        .with_decay_factor(propagation_service.decay_factor_for(&content_id));
+    if query.include_communities {
+        network = network.with_communities();
+    }
 
     Ok(HttpResponse::Ok().json(json!({
         "success": true,
@@ -171,7 +799,39 @@ pub async fn get_propagation_network(path: web::Path<String>) -> Result<HttpResp
     })))
 }
 
+/// Get the communities of a content item's propagation network
+#[utoipa::path(
+    context_path = "/api/v1/propagation",
+    operation_id = "get_propagation_communities",
+    tag = "propagation",
+    params(("content_id" = String, Path, description = "Content ID")),
+    responses(
+        (status = 200, description = "Communities of the propagation network", body = Object),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{content_id}/communities")]
+pub async fn get_propagation_communities(path: web::Path<String>) -> Result<HttpResponse> {
+    let network = content_network(&path.into_inner(), false);
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": PropagationCommunityDetector::detect(&network),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
 /// Get propagation analytics
+#[utoipa::path(
+    context_path = "/api/v1/propagation",
+    operation_id = "get_propagation_analytics",
+    tag = "propagation",
+    params(("content_id" = String, Path, description = "Content ID")),
+    responses(
+        (status = 200, description = "Propagation totals and top propagators", body = Object),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[get("/{content_id}/analytics")]
 pub async fn get_propagation_analytics(path: web::Path<String>) -> Result<HttpResponse> {
     let _content_id = path.into_inner();
@@ -248,4 +908,710 @@ pub async fn get_propagation_analytics(path: web::Path<String>) -> Result<HttpRe
         "data": analytics,
         "timestamp": chrono::Utc::now().to_rfc3339()
     })))
-} 
\ No newline at end of file
+} 
+
+/// Get how deep a content item's propagation paths reach and how influence decays with depth
+#[utoipa::path(
+    context_path = "/api/v1/propagation",
+    operation_id = "get_propagation_depth_analysis",
+    tag = "propagation",
+    params(("content_id" = String, Path, description = "Content ID")),
+    responses(
+        (
+            status = 200,
+            description = "Depth reached by the propagation paths and influence at each depth",
+            body = Object
+        ),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{content_id}/depth-analysis")]
+pub async fn get_propagation_depth_analysis(
+    path: web::Path<String>,
+    propagation_service: web::Data<PropagationService>,
+) -> Result<HttpResponse> {
+    let content_id = path.into_inner();
+    propagation_service.load_content_echo_loops(&content_id).await.map_err(|e| {
+        tracing::error!(%content_id, error = %e, "Failed to load Echo Loops");
+        ApiError::internal()
+    })?;
+
+    let paths: Vec<PropagationPath> = propagation_service
+        .get_content_echo_loops(&content_id)
+        .into_iter()
+        .flat_map(|echo_loop| echo_loop.propagation_paths)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": PropagationDepthAnalyzer::analyze(&paths),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// List the circular propagations rejected from a content item's Echo Loops since the
+/// service started, with the nodes that formed each cycle
+#[utoipa::path(
+    context_path = "/api/v1/propagation",
+    operation_id = "get_propagation_cycles",
+    tag = "propagation",
+    params(("content_id" = String, Path, description = "Content ID")),
+    responses(
+        (status = 200, description = "Cycle reports of the content's Echo Loops", body = [Object]),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{content_id}/cycles")]
+pub async fn get_propagation_cycles(
+    path: web::Path<String>,
+    propagation_service: web::Data<PropagationService>,
+) -> Result<HttpResponse> {
+    let content_id = path.into_inner();
+    propagation_service.load_content_echo_loops(&content_id).await.map_err(|e| {
+        tracing::error!(%content_id, error = %e, "Failed to load Echo Loops");
+        ApiError::internal()
+    })?;
+
+    let reports: Vec<_> = propagation_service
+        .get_content_echo_loops(&content_id)
+        .iter()
+        .filter_map(|echo_loop| propagation_service.get_cycle_report(&echo_loop.id))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": reports,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// List the propagations of a content item at least `min_depth` hops from the original
+/// post. Deep chains are typical of viral content, and of bot rings.
+#[utoipa::path(
+    context_path = "/api/v1/propagation",
+    operation_id = "get_deep_propagations",
+    tag = "propagation",
+    params(("content_id" = String, Path, description = "Content ID"), DeepPropagationsQuery),
+    responses(
+        (status = 200, description = "Propagations at least `min_depth` hops from the original post", body = Object),
+        (status = 400, description = "Malformed content ID"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{content_id}/deep-propagations")]
+pub async fn get_deep_propagations(
+    path: web::Path<String>,
+    query: web::Query<DeepPropagationsQuery>,
+    repository: web::Data<ContentRepository>,
+) -> Result<HttpResponse> {
+    let Ok(content_id) = Uuid::parse_str(&path) else {
+        return Err(ApiError::bad_request("content_id must be a valid UUID").into());
+    };
+    let min_depth = query.min_depth.unwrap_or(DEFAULT_DEEP_PROPAGATION_DEPTH).max(1);
+
+    let propagations = repository
+        .list_deep_propagations(content_id, min_depth, MAX_DEEP_PROPAGATIONS)
+        .await
+        .map_err(|e| {
+            tracing::error!(%content_id, error = %e, "Failed to list deep propagations");
+            ApiError::internal()
+        })?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": {
+            "content_id": content_id,
+            "min_depth": min_depth,
+            "max_depth": propagations.first().map(|propagation| propagation.depth),
+            "propagations": propagations,
+        },
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Export the propagation graph for content as GraphML or Graphviz DOT
+#[utoipa::path(
+    context_path = "/api/v1/propagation",
+    operation_id = "export_propagation_graph",
+    tag = "propagation",
+    params(("content_id" = String, Path, description = "Content ID"), ExportQuery),
+    responses(
+        (
+            status = 200,
+            description = "GraphML, or DOT with `format=dot`",
+            body = String,
+            content_type = "application/xml"
+        ),
+        (status = 400, description = "Unsupported format"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{content_id}/export")]
+pub async fn export_propagation_graph(
+    path: web::Path<String>,
+    query: web::Query<ExportQuery>,
+    propagation_service: web::Data<PropagationService>,
+) -> Result<HttpResponse> {
+    let content_id = path.into_inner();
+    propagation_service.load_content_echo_loops(&content_id).await.map_err(|e| {
+        tracing::error!(%content_id, error = %e, "Failed to load Echo Loops");
+        ApiError::internal()
+    })?;
+
+    match query.format.as_deref().unwrap_or("graphml") {
+        "graphml" => Ok(HttpResponse::Ok()
+            .content_type("application/xml")
+            .body(propagation_service.export_graphml(&content_id))),
+        "dot" => Ok(HttpResponse::Ok()
+            .content_type("text/vnd.graphviz")
+            .body(propagation_service.export_dot(&content_id))),
+        other => Err(ApiError::bad_request(format!("Unsupported export format: {}", other)).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::auth::{AuthService, JwtConfig};
+    use crate::middleware::JwtMiddleware;
+    use crate::models::echo_index::EchoIndexCalculator;
+    use crate::models::user::Role;
+    use crate::repositories::{
+        ContentRepository, EchoIndexHistoryRepository, MentionRepository, NewContent, PropagationRepository,
+        UserRepository,
+    };
+    use crate::services::{ContentCreationData, EchoIndexUpdates, EngineConfigStore, RecalculationContext};
+    use crate::services::TokenBlacklist;
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use actix_web::App;
+    use serde_json::Value;
+    use sqlx::PgPool;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    fn node(id: &str) -> PropagationNode {
+        PropagationNode {
+            id: id.to_string(),
+            user_id: id.to_string(),
+            platform: Platform::Twitter,
+            influence: 0.0,
+            echo_score: 0.0,
+        }
+    }
+
+    fn edge(source: &str, target: &str) -> PropagationEdge {
+        PropagationEdge {
+            source_id: source.to_string(),
+            target_id: target.to_string(),
+            weight: 1.0,
+            propagation_type: "share".to_string(),
+            timestamp: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_metrics_on_triangle_with_tail() {
+        // a - b - c triangle, with c - d - e hanging off it
+        let nodes = ["a", "b", "c", "d", "e"].map(node);
+        let edges = vec![edge("a", "b"), edge("a", "c"), edge("b", "c"), edge("c", "d"), edge("d", "e")];
+
+        let metrics = NetworkMetrics::compute(&nodes, &edges, true);
+
+        assert_eq!(metrics.total_edges, 5);
+        assert!((metrics.density - 0.5).abs() < 1e-9);
+        assert!((metrics.clustering_coefficient - 7.0 / 15.0).abs() < 1e-9);
+        assert!((metrics.average_path_length - 1.7).abs() < 1e-9);
+        assert_eq!(metrics.diameter, 3);
+
+        let centrality: HashMap<String, f64> = metrics.betweenness_centrality.unwrap().into_iter().collect();
+        assert_eq!(centrality["a"], 0.0);
+        assert_eq!(centrality["b"], 0.0);
+        assert!((centrality["c"] - 4.0).abs() < 1e-9);
+        assert!((centrality["d"] - 3.0).abs() < 1e-9);
+        assert_eq!(centrality["e"], 0.0);
+    }
+
+    #[test]
+    fn test_centrality_is_opt_in() {
+        let nodes = ["a", "b"].map(node);
+        let metrics = NetworkMetrics::compute(&nodes, &[edge("a", "b"), edge("b", "a")], false);
+
+        assert_eq!(metrics.total_edges, 1);
+        assert_eq!(metrics.density, 1.0);
+        assert!(metrics.betweenness_centrality.is_none());
+    }
+
+    async fn create_content(pool: &PgPool, user_id: Uuid, external_id: &str) -> Uuid {
+        ContentRepository::new(pool.clone())
+            .create(&NewContent {
+                user_id,
+                platform: Platform::Twitter,
+                external_id: external_id.to_string(),
+                content_type: "text".to_string(),
+                title: "Echoed".to_string(),
+                body: "Shared far and wide".to_string(),
+                media_urls: vec![],
+                tags: vec![],
+                reactions: HashMap::new(),
+            })
+            .await
+            .unwrap()
+            .id
+    }
+
+    fn event(content_id: &str, target_external_id: &str, propagation_type: &str) -> Value {
+        json!({
+            "content_id": content_id,
+            "propagation_type": propagation_type,
+            "source_platform": "twitter",
+            "target_platform": "telegram",
+            "source_external_id": "tweet_1",
+            "target_external_id": target_external_id
+        })
+    }
+
+    fn jwt_middleware() -> JwtMiddleware {
+        JwtMiddleware::new(JwtConfig::new("test-secret"), web::Data::new(TokenBlacklist::new()))
+    }
+
+    /// Authorization header of a user signed in with `role`
+    fn bearer(user_id: Uuid, role: Role, totp_verified: bool) -> (&'static str, String) {
+        let config = JwtConfig::new("test-secret");
+        let token =
+            AuthService::generate_access_token(&user_id.to_string(), "0xwallet", "session", role, totp_verified, &config)
+                .unwrap();
+        ("Authorization", format!("Bearer {}", token))
+    }
+
+    fn recalculation_queue(pool: &PgPool, history: Arc<EchoIndexHistoryRepository>) -> RecalculationQueue {
+        let context = RecalculationContext {
+            content: Arc::new(ContentRepository::new(pool.clone())),
+            history,
+            updates: Arc::new(EchoIndexUpdates::new()),
+            metrics: Arc::new(MetricsRegistry::new()),
+            events: Arc::new(UserEventRepository::new(pool.clone())),
+            users: Arc::new(UserRepository::new(pool.clone())),
+            calculator: EchoIndexCalculator::default(),
+            engine_config: Arc::new(EngineConfigStore::default()),
+            webhooks: None,
+            experiments: None,
+            hashtag_trends: None,
+            echo_cache: None,
+            anomalies: None,
+            cold_start: None,
+            propagation: None,
+            tiers: None,
+        };
+        let calculator = Arc::new(std::sync::RwLock::new(EchoIndexCalculator::default()));
+        RecalculationQueue::spawn(context, calculator, 100).0
+    }
+
+    fn mention_linker(pool: &PgPool) -> MentionLinker {
+        MentionLinker::new(
+            Arc::new(MentionRepository::new(pool.clone())),
+            Arc::new(PropagationService::new()),
+            Arc::new(tokio::sync::RwLock::new(RewardService::new(10_000.0))),
+        )
+    }
+
+    fn loop_node(id: &str) -> crate::services::PropagationNode {
+        crate::services::PropagationNode {
+            id: id.to_string(),
+            node_type: crate::services::NodeType::User,
+            influence_weight: 0.5,
+            reach: 100,
+            engagement_rate: 0.1,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_cycles_are_reported_per_content() {
+        let service = web::Data::new(PropagationService::new());
+        let loop_id = service.create_echo_loop("content_1".to_string()).await.unwrap();
+        service.add_propagation_event(&loop_id, loop_node("a"), loop_node("b"), 1.0).await.unwrap();
+        assert!(service.add_propagation_event(&loop_id, loop_node("b"), loop_node("a"), 1.0).await.is_err());
+        service.create_echo_loop("content_2".to_string()).await.unwrap();
+
+        let app = init_service(
+            App::new()
+                .app_data(service)
+                .service(web::scope("/propagation").service(get_propagation_cycles)),
+        )
+        .await;
+
+        let cycles: Value = read_body_json(
+            call_service(&app, TestRequest::get().uri("/propagation/content_1/cycles").to_request()).await,
+        )
+        .await;
+        assert_eq!(cycles["data"][0]["loop_id"], loop_id.as_str());
+        assert_eq!(cycles["data"][0]["cycle_nodes"], json!(["a", "b"]));
+        assert_eq!(cycles["data"][0]["detection_count"], 1);
+
+        let clean: Value = read_body_json(
+            call_service(&app, TestRequest::get().uri("/propagation/content_2/cycles").to_request()).await,
+        )
+        .await;
+        assert_eq!(clean["data"], json!([]));
+    }
+
+    fn deduplicator(pool: &PgPool) -> PropagationEventDeduplicator {
+        let repository = Arc::new(PropagationRepository::new(pool.clone()));
+        PropagationEventDeduplicator::new(repository, chrono::Duration::hours(24))
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_same_event_twice_within_window_conflicts(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xtwice') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let content_id = create_content(&pool, user_id, "tweet_twice").await.to_string();
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(deduplicator(&pool)))
+                .app_data(web::Data::new(MetricsRegistry::new()))
+                .app_data(web::Data::new(UserEventRepository::new(pool.clone())))
+                .app_data(web::Data::new(mention_linker(&pool)))
+                .app_data(web::Data::new(PropagationService::new()))
+                .app_data(web::Data::new(ContentRepository::new(pool.clone())))
+                .app_data(web::Data::new(RwLock::new(RewardService::new(10_000.0))))
+                .service(web::scope("/propagation").wrap(jwt_middleware()).service(create_propagation)),
+        )
+        .await;
+        let post = |platform: &str| {
+            let mut event = event(&content_id, "msg_1", "share");
+            event["source_platform"] = json!(platform);
+            TestRequest::post()
+                .uri("/propagation")
+                .insert_header(bearer(user_id, Role::User, false))
+                .set_json(event)
+                .to_request()
+        };
+
+        let response = call_service(&app, post("twitter")).await;
+        assert_eq!(response.status(), 201);
+        let created: Value = read_body_json(response).await;
+        assert_eq!(created["data"]["fingerprint_checked"], true);
+        assert!(created["data"]["duplicate_of"].is_null());
+
+        // The same share reported by another connector
+        let response = call_service(&app, post("farcaster")).await;
+        assert_eq!(response.status(), 409);
+        let body: Value = read_body_json(response).await;
+        assert_eq!(body["error_code"], "duplicate_event");
+        assert_eq!(body["details"]["duplicate_of"], created["data"]["id"]);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_propagation_rewards_propagator_and_creator(pool: PgPool) {
+        let mut users = Vec::new();
+        for wallet in ["0xcreator", "0xpropagator"] {
+            let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+                .bind(wallet)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            users.push(user_id);
+        }
+        let content = ContentRepository::new(pool.clone());
+        let record = content.find_by_id(create_content(&pool, users[0], "tweet_rewarded").await).await.unwrap();
+        let rewards = web::Data::new(RwLock::new(RewardService::new(10_000.0)));
+        let creation = ContentCreationData::for_content(&record);
+        rewards
+            .write()
+            .await
+            .process_content_creation(users[0].to_string(), record.id.to_string(), creation)
+            .await
+            .unwrap();
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(deduplicator(&pool)))
+                .app_data(web::Data::new(MetricsRegistry::new()))
+                .app_data(web::Data::new(UserEventRepository::new(pool.clone())))
+                .app_data(web::Data::new(mention_linker(&pool)))
+                .app_data(web::Data::new(PropagationService::new()))
+                .app_data(web::Data::new(content))
+                .app_data(rewards.clone())
+                .service(web::scope("/propagation").wrap(jwt_middleware()).service(create_propagation)),
+        )
+        .await;
+
+        let mut event = event(&record.id.to_string(), "msg_rewarded", "share");
+        event["source_user_id"] = json!(users[1]);
+        let post = |caller: Uuid, role: Role, totp_verified: bool| {
+            TestRequest::post()
+                .uri("/propagation")
+                .insert_header(bearer(caller, role, totp_verified))
+                .set_json(&event)
+                .to_request()
+        };
+
+        // Only the propagator, or an admin who passed the second factor, reports their propagations
+        for (role, totp_verified) in [(Role::User, false), (Role::Admin, false)] {
+            assert_eq!(call_service(&app, post(users[0], role, totp_verified)).await.status(), 403);
+        }
+        let response = call_service(&app, post(users[1], Role::User, false)).await;
+        assert_eq!(response.status(), 201);
+        let created: Value = read_body_json(response).await;
+
+        let rewards = rewards.read().await;
+        let awarded = rewards.get_user_rewards(&users[1].to_string());
+        assert_eq!(awarded.len(), 1);
+        assert!(awarded[0].amount > 0.0);
+        assert!((created["data"]["reward_amount"].as_f64().unwrap() - awarded[0].amount).abs() < 1e-9);
+        assert_eq!(created["data"]["echo_boost"], 1.0);
+        // Creation and a share of the propagation
+        assert_eq!(rewards.get_user_rewards(&users[0].to_string()).len(), 2);
+    }
+
+    async fn create_users(pool: &PgPool, wallets: &[&str]) -> Vec<Uuid> {
+        let mut users = Vec::new();
+        for wallet in wallets {
+            let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+                .bind(wallet)
+                .fetch_one(pool)
+                .await
+                .unwrap();
+            users.push(user_id);
+        }
+        users
+    }
+
+    fn share(content_id: &str, from: Uuid, to: Uuid) -> Value {
+        let mut event = event(content_id, &format!("msg_{}_{}", from, to), "share");
+        event["source_user_id"] = json!(from);
+        event["target_user_id"] = json!(to);
+        event
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_propagation_closing_a_cycle_is_refused(pool: PgPool) {
+        let users = create_users(&pool, &["0xcycle_a", "0xcycle_b", "0xcycle_c"]).await;
+        let content_id = create_content(&pool, users[0], "tweet_cycle").await.to_string();
+        let propagation_service = web::Data::new(PropagationService::new());
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(deduplicator(&pool)))
+                .app_data(web::Data::new(MetricsRegistry::new()))
+                .app_data(web::Data::new(UserEventRepository::new(pool.clone())))
+                .app_data(web::Data::new(mention_linker(&pool)))
+                .app_data(propagation_service.clone())
+                .app_data(web::Data::new(ContentRepository::new(pool.clone())))
+                .app_data(web::Data::new(RwLock::new(RewardService::new(10_000.0))))
+                .service(web::scope("/propagation").wrap(jwt_middleware()).service(create_propagation)),
+        )
+        .await;
+        let post = |from: usize, to: usize| {
+            TestRequest::post()
+                .uri("/propagation")
+                .insert_header(bearer(users[from], Role::User, false))
+                .set_json(share(&content_id, users[from], users[to]))
+                .to_request()
+        };
+
+        assert_eq!(call_service(&app, post(0, 1)).await.status(), 201);
+        assert_eq!(call_service(&app, post(1, 2)).await.status(), 201);
+
+        // c shares back to a, who the content reached c through
+        let response = call_service(&app, post(2, 0)).await;
+        assert_eq!(response.status(), 409);
+        let body: Value = read_body_json(response).await;
+        assert_eq!(body["error_code"], "conflict");
+
+        let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM propagations").fetch_one(&pool).await.unwrap();
+        assert_eq!(stored, 2);
+        let echo_loop = &propagation_service.get_content_echo_loops(&content_id)[0];
+        assert_eq!(propagation_service.get_cycle_report(&echo_loop.id).unwrap().detection_count, 1);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_propagation_past_max_depth_is_refused(pool: PgPool) {
+        let users = create_users(&pool, &["0xdeep_a", "0xdeep_b", "0xdeep_c", "0xdeep_d"]).await;
+        let content_id = create_content(&pool, users[0], "tweet_deep").await.to_string();
+        let propagation_service = PropagationService::new().configure(3, 0.3, 0.9);
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(deduplicator(&pool)))
+                .app_data(web::Data::new(MetricsRegistry::new()))
+                .app_data(web::Data::new(UserEventRepository::new(pool.clone())))
+                .app_data(web::Data::new(mention_linker(&pool)))
+                .app_data(web::Data::new(propagation_service))
+                .app_data(web::Data::new(ContentRepository::new(pool.clone())))
+                .app_data(web::Data::new(RwLock::new(RewardService::new(10_000.0))))
+                .service(web::scope("/propagation").wrap(jwt_middleware()).service(create_propagation)),
+        )
+        .await;
+        let post = |from: usize, to: usize| {
+            TestRequest::post()
+                .uri("/propagation")
+                .insert_header(bearer(users[from], Role::User, false))
+                .set_json(share(&content_id, users[from], users[to]))
+                .to_request()
+        };
+
+        assert_eq!(call_service(&app, post(0, 1)).await.status(), 201);
+        assert_eq!(call_service(&app, post(1, 2)).await.status(), 201);
+
+        // Sharing on from c would reach the maximum depth of 3
+        let response = call_service(&app, post(2, 3)).await;
+        assert_eq!(response.status(), 409);
+        let body: Value = read_body_json(response).await;
+        assert_eq!(body["message"], "Maximum propagation depth exceeded");
+
+        let deepest: i32 = sqlx::query_scalar("SELECT MAX(depth) FROM propagations").fetch_one(&pool).await.unwrap();
+        assert_eq!(deepest, 2);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_bulk_events_closing_a_cycle_fail(pool: PgPool) {
+        let users = create_users(&pool, &["0xbulk_cycle_a", "0xbulk_cycle_b"]).await;
+        let content_id = create_content(&pool, users[0], "tweet_bulk_cycle").await.to_string();
+        let history = Arc::new(EchoIndexHistoryRepository::new(pool.clone()));
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(deduplicator(&pool)))
+                .app_data(web::Data::new(IdempotencyCache::<BulkPropagationResponse>::new()))
+                .app_data(web::Data::new(recalculation_queue(&pool, history)))
+                .app_data(web::Data::new(MetricsRegistry::new()))
+                .app_data(web::Data::new(mention_linker(&pool)))
+                .app_data(web::Data::new(PropagationService::new()))
+                .service(web::scope("/propagation").service(bulk_create_propagations)),
+        )
+        .await;
+
+        // The second event closes a cycle with the first of the same batch
+        let events = vec![share(&content_id, users[0], users[1]), share(&content_id, users[1], users[0])];
+        let request = TestRequest::post()
+            .uri("/propagation/bulk")
+            .set_json(json!({ "idempotency_key": "batch-cycle", "events": events }))
+            .to_request();
+        let body: Value = read_body_json(call_service(&app, request).await).await;
+        let response: BulkPropagationResponse = serde_json::from_value(body["data"].clone()).unwrap();
+        assert_eq!(response.processed, 1);
+        assert_eq!(response.failed.len(), 1);
+        assert_eq!(response.failed[0].0, 1);
+        assert!(response.failed[0].1.starts_with("Circular propagation detected"));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_bulk_ingestion_deduplicates_and_replays(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xbulk') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let content_id = create_content(&pool, user_id, "tweet_bulk").await.to_string();
+        let history = Arc::new(EchoIndexHistoryRepository::new(pool.clone()));
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(deduplicator(&pool)))
+                .app_data(web::Data::new(IdempotencyCache::<BulkPropagationResponse>::new()))
+                .app_data(web::Data::new(recalculation_queue(&pool, history.clone())))
+                .app_data(web::Data::new(MetricsRegistry::new()))
+                .app_data(web::Data::new(mention_linker(&pool)))
+                .app_data(web::Data::new(PropagationService::new()))
+                .service(
+                    web::scope("/propagation")
+                        .app_data(web::JsonConfig::default().limit(MAX_BULK_PAYLOAD_BYTES))
+                        .service(bulk_create_propagations),
+                ),
+        )
+        .await;
+        let post = |key: &str, events: Vec<Value>| {
+            TestRequest::post()
+                .uri("/propagation/bulk")
+                .set_json(json!({ "idempotency_key": key, "events": events }))
+                .to_request()
+        };
+
+        let first = vec![
+            event(&content_id, "msg_1", "share"),
+            event(&content_id, "msg_1", "share"),
+            event(&Uuid::new_v4().to_string(), "msg_1", "share"),
+            event(&content_id, "msg_1", "teleport"),
+            event(&content_id, "msg_2", "share"),
+        ];
+        let body: Value = read_body_json(call_service(&app, post("batch-1", first)).await).await;
+        let response: BulkPropagationResponse = serde_json::from_value(body["data"].clone()).unwrap();
+        assert_eq!((response.processed, response.duplicates_skipped, response.echo_index_updates_queued), (2, 1, 1));
+        let failed: Vec<usize> = response.failed.iter().map(|(index, _)| *index).collect();
+        assert_eq!(failed, vec![2, 3]);
+
+        // Events stored by an earlier batch are skipped
+        let second = vec![event(&content_id, "msg_2", "share"), event(&content_id, "msg_3", "quote")];
+        let body: Value = read_body_json(call_service(&app, post("batch-2", second)).await).await;
+        assert_eq!((body["data"]["processed"].as_u64(), body["data"]["duplicates_skipped"].as_u64()), (Some(1), Some(1)));
+
+        // A batch with nothing new conflicts
+        let third = vec![event(&content_id, "msg_3", "quote"), event(&content_id, "msg_3", "quote")];
+        let conflict = call_service(&app, post("batch-3", third)).await;
+        assert_eq!(conflict.status(), 409);
+        let body: Value = read_body_json(conflict).await;
+        assert!(body["details"]["duplicate_of"].is_string());
+
+        // Replaying a key returns the original response without storing anything
+        let replay = vec![event(&content_id, "msg_4", "share")];
+        let body: Value = read_body_json(call_service(&app, post("batch-1", replay)).await).await;
+        assert_eq!(serde_json::from_value::<BulkPropagationResponse>(body["data"].clone()).unwrap(), response);
+
+        let (stored, counted): (i64, i32) = sqlx::query_as(
+            "SELECT (SELECT COUNT(*) FROM propagations), propagation_count FROM content WHERE id = $1::uuid",
+        )
+        .bind(&content_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((stored, counted), (3, 3));
+
+        let content_id = Uuid::parse_str(&content_id).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while history.latest(content_id).await.unwrap().is_none() {
+            assert!(Instant::now() < deadline, "queued recalculation never ran");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_thousand_event_batch_within_two_seconds(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xload') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let mut content_ids = Vec::new();
+        for i in 0..10 {
+            content_ids.push(create_content(&pool, user_id, &format!("tweet_load_{}", i)).await.to_string());
+        }
+
+        let history = Arc::new(EchoIndexHistoryRepository::new(pool.clone()));
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(deduplicator(&pool)))
+                .app_data(web::Data::new(IdempotencyCache::<BulkPropagationResponse>::new()))
+                .app_data(web::Data::new(recalculation_queue(&pool, history)))
+                .app_data(web::Data::new(MetricsRegistry::new()))
+                .app_data(web::Data::new(mention_linker(&pool)))
+                .app_data(web::Data::new(PropagationService::new()))
+                .service(
+                    web::scope("/propagation")
+                        .app_data(web::JsonConfig::default().limit(MAX_BULK_PAYLOAD_BYTES))
+                        .service(bulk_create_propagations),
+                ),
+        )
+        .await;
+
+        let events: Vec<Value> = (0..1000)
+            .map(|i| event(&content_ids[i % 10], &format!("msg_{}", i), "repost"))
+            .collect();
+        let request = TestRequest::post()
+            .uri("/propagation/bulk")
+            .set_json(json!({ "idempotency_key": "load", "events": events }))
+            .to_request();
+
+        let started = Instant::now();
+        let body: Value = read_body_json(call_service(&app, request).await).await;
+        assert!(started.elapsed() < Duration::from_secs(2), "took {:?}", started.elapsed());
+        assert_eq!(body["data"]["processed"], 1000);
+        assert_eq!(body["data"]["echo_index_updates_queued"], 10);
+    }
+}
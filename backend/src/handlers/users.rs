@@ -1,16 +1,168 @@
-use actix_web::{get, post, put, web, HttpResponse, Result};
+use actix_multipart::Multipart;
+use actix_web::{delete, get, post, put, web, HttpResponse, Result};
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::HashSet;
+use tokio::sync::RwLock;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Deserialize)]
+use crate::handlers::auth::Claims;
+use crate::handlers::content::ContentResponse;
+use crate::handlers::errors::ApiError;
+use crate::models::api_key::{ApiKey, Permission};
+use crate::models::content::{ContentSort, SortOrder};
+use crate::models::content_import::{ContentImportRequest, ImportFormat};
+use crate::models::oauth::OAuthCallback;
+use crate::models::pagination::{Cursor, Page, ScoreCursor, SortCursor};
+use crate::models::reward_analytics::RewardAnalyticsQuery;
+use crate::models::user::{FollowedUser, LeaderboardEntry, User, UserTier};
+use crate::models::velocity_alert::VelocityThreshold;
+use crate::models::wallet_address::WalletAddress;
+use crate::models::user_streak::UserStreak;
+use crate::models::webhook::WebhookEvent;
+use crate::models::Platform;
+use crate::repositories::{
+    ContentRepository, RepositoryError, UserEventRepository, UserPatch, UserRepository, WebhookRepository,
+};
+use crate::services::api_keys::DEFAULT_API_KEY_RATE_LIMIT;
+use crate::services::callback_urls::check_callback_url;
+use crate::services::reward_forecast::{DEFAULT_HORIZON_HOURS, MAX_HORIZON_HOURS};
+use crate::services::rewards::format_reward_id;
+use crate::services::webhooks::{generate_secret, RECENT_DELIVERIES_LIMIT};
+use crate::services::{
+    ApiKeyService, ContentImportError, ContentImportService, DiscoveryFeedService, InfluenceScoreCalculator,
+    MerkleRewardClaim, RewardAnalyticsService, RewardForecastService, RewardService, SettlementError,
+    SocialAccountVerifier, StreakService, TokenVestingService, TwoFactorError, TwoFactorService, UserDataError,
+    UserDataService, UserFollowerGraph, VelocityAlertService, VerificationError,
+};
+
+/// Default and maximum page sizes for timelines
+const DEFAULT_TIMELINE_LIMIT: u32 = 50;
+const MAX_TIMELINE_LIMIT: u32 = 100;
+
+/// Default and maximum page sizes for discovery feeds
+const DEFAULT_FEED_LIMIT: u32 = 20;
+const MAX_FEED_LIMIT: u32 = 50;
+
+/// Default and maximum page sizes for follower and following lists
+const DEFAULT_FOLLOW_LIMIT: u32 = 50;
+const MAX_FOLLOW_LIMIT: u32 = 100;
+
+/// Default and maximum page sizes for the leaderboard
+const DEFAULT_LEADERBOARD_LIMIT: u32 = 20;
+const MAX_LEADERBOARD_LIMIT: u32 = 100;
+
+/// Default and maximum page sizes for a user's content
+const DEFAULT_USER_CONTENT_LIMIT: u32 = 20;
+const MAX_USER_CONTENT_LIMIT: u32 = 100;
+
+/// Size of the chunks a data export is streamed in
+const EXPORT_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Largest export file a content import accepts
+const MAX_IMPORT_FILE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Most rewards claimed against one Merkle root
+const MAX_CLAIM_BATCH_REWARDS: usize = 100;
+
+/// Upper bound on the requests per minute a single API key may be granted
+const MAX_API_KEY_RATE_LIMIT: u32 = 6_000;
+const MAX_API_KEY_NAME_LENGTH: usize = 100;
+
+#[derive(Deserialize, ToSchema)]
 pub struct CreateUserRequest {
     pub wallet_address: String,
     pub username: Option<String>,
     pub display_name: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Deserialize, IntoParams)]
+pub struct StartVerificationQuery {
+    /// Platform account the user claims to own
+    pub account_id: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateUserRequest {
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct LeaderboardQuery {
+    pub after: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct UserContentQuery {
+    #[serde(default)]
+    pub sort_by: ContentSort,
+    #[serde(default)]
+    pub order: SortOrder,
+    pub platform: Option<Platform>,
+    pub after: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct TimelineQuery {
+    pub after: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct FeedQuery {
+    pub after: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct FollowListQuery {
+    pub after: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct EarningsForecastQuery {
+    pub hours: Option<u32>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ClaimBatchRequest {
+    /// UUIDs of the rewards, as in their `reward_<uuid>` IDs
+    pub reward_ids: Vec<Uuid>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    /// Defaults to read-only
+    pub permissions: Option<Vec<Permission>>,
+    pub rate_limit_per_minute: Option<u32>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ConfigureAlertRequest {
+    pub threshold: VelocityThreshold,
+    /// HTTPS endpoint of a public host alerts are posted to
+    pub webhook_url: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    /// HTTPS endpoint events are posted to
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct UserResponse {
     pub id: String,
     pub wallet_address: String,
@@ -18,88 +170,446 @@ pub struct UserResponse {
     pub display_name: Option<String>,
     pub echo_score: f64,
     pub total_rewards: f64,
-    pub rank: Option<u32>,
+    pub tier: UserTier,
+    pub rank: u32,
     pub is_verified: bool,
+    pub followers_count: u32,
+    pub following_count: u32,
     pub created_at: String,
 }
 
+impl From<User> for UserResponse {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id.to_string(),
+            wallet_address: user.wallet_address,
+            username: user.username,
+            display_name: user.display_name,
+            echo_score: user.echo_score,
+            total_rewards: user.total_rewards,
+            tier: user.tier,
+            rank: user.rank.max(1) as u32,
+            is_verified: user.is_verified,
+            followers_count: user.followers_count.max(0) as u32,
+            following_count: user.following_count.max(0) as u32,
+            created_at: user.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LeaderboardEntryResponse {
+    #[serde(flatten)]
+    pub user: UserResponse,
+    /// Position by influence score; null until the user's influence is first scored
+    pub influence_rank: Option<u32>,
+}
+
+impl From<LeaderboardEntry> for LeaderboardEntryResponse {
+    fn from(entry: LeaderboardEntry) -> Self {
+        Self {
+            user: UserResponse::from(entry.user),
+            influence_rank: entry.influence_rank.map(|rank| rank.max(1) as u32),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct FollowedUserResponse {
+    #[serde(flatten)]
+    pub user: UserResponse,
+    pub followed_at: String,
+}
+
+impl From<FollowedUser> for FollowedUserResponse {
+    fn from(followed: FollowedUser) -> Self {
+        Self {
+            user: UserResponse::from(followed.user),
+            followed_at: followed.followed_at.to_rfc3339(),
+        }
+    }
+}
+
 /// Create a new user
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "create_user",
+    tag = "users",
+    request_body = CreateUserRequest,
+    responses(
+        (status = 201, description = "User created", body = UserResponse),
+        (status = 400, description = "Missing or invalid wallet address"),
+        (status = 409, description = "Wallet address or username already taken"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[post("")]
-pub async fn create_user(user_data: web::Json<CreateUserRequest>) -> Result<HttpResponse> {
-    // Mock implementation - in real app, this would save to database
-    let user = UserResponse {
-        id: Uuid::new_v4().to_string(),
-        wallet_address: user_data.wallet_address.clone(),
-        username: user_data.username.clone(),
-        display_name: user_data.display_name.clone(),
-        echo_score: 0.0,
-        total_rewards: 0.0,
-        rank: None,
-        is_verified: false,
-        created_at: chrono::Utc::now().to_rfc3339(),
-    };
-
-    Ok(HttpResponse::Created().json(json!({
-        "success": true,
-        "data": user,
-        "timestamp": chrono::Utc::now().to_rfc3339()
-    })))
+pub async fn create_user(
+    user_data: web::Json<CreateUserRequest>,
+    users: web::Data<UserRepository>,
+) -> Result<HttpResponse> {
+    let wallet_address = user_data.wallet_address.trim();
+    if wallet_address.is_empty() {
+        return Err(ApiError::bad_request("wallet_address is required").into());
+    }
+    if let Err(e) = WalletAddress::parse(wallet_address) {
+        return Err(ApiError::bad_request(format!("Invalid wallet_address: {}", e)).into());
+    }
+
+    match users
+        .create(wallet_address, user_data.username.as_deref(), user_data.display_name.as_deref())
+        .await
+    {
+        Ok(user) => Ok(HttpResponse::Created().json(json!({
+            "success": true,
+            "data": UserResponse::from(user),
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(RepositoryError::Conflict(_)) => {
+            let message = "A user with this wallet address or username already exists";
+            Err(ApiError::StateConflict(message.to_string()).into())
+        }
+        Err(e) => Err(user_error(e).into()),
+    }
 }
 
 /// Get user by ID
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_user",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "The user", body = UserResponse),
+        (status = 400, description = "Malformed user ID"),
+        (status = 404, description = "No such user"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[get("/{user_id}")]
-pub async fn get_user(path: web::Path<String>) -> Result<HttpResponse> {
-    let user_id = path.into_inner();
-    
-    // Mock user data
-    let user = UserResponse {
-        id: user_id,
-        wallet_address: "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM".to_string(),
-        username: Some("echo_pioneer".to_string()),
-        display_name: Some("Echo Pioneer".to_string()),
-        echo_score: 75.8,
-        total_rewards: 1250.0,
-        rank: Some(42),
-        is_verified: true,
-        created_at: chrono::Utc::now().to_rfc3339(),
+pub async fn get_user(path: web::Path<String>, users: web::Data<UserRepository>) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+
+    match users.find_by_id(user_id).await {
+        Ok(Some(user)) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": UserResponse::from(user),
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Ok(None) => Err(user_error(RepositoryError::NotFound).into()),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// A user's live content, sorted by Echo Index, creation time or propagation count,
+/// with cursor-based pagination and totals over all of it
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_user_content",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID"), UserContentQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the user's content; `total_count` and `stats` cover all of it",
+            body = [ContentResponse]
+        ),
+        (status = 400, description = "Malformed user ID or invalid cursor"),
+        (status = 404, description = "No such user"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/content")]
+pub async fn get_user_content(
+    path: web::Path<String>,
+    query: web::Query<UserContentQuery>,
+    users: web::Data<UserRepository>,
+    content: web::Data<ContentRepository>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_USER_CONTENT_LIMIT).clamp(1, MAX_USER_CONTENT_LIMIT);
+    let after = match query.after.as_deref().map(SortCursor::decode).transpose() {
+        Ok(after) => after,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    match users.find_by_id(user_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(user_error(RepositoryError::NotFound).into()),
+        Err(e) => return Err(user_error(e).into()),
+    }
+
+    let platform = query.platform.as_ref();
+    let page = match content.find_by_author(user_id, platform, query.sort_by, query.order, after, limit).await {
+        Ok(page) => page.map(ContentResponse::from),
+        Err(RepositoryError::InvalidCursor(message)) => {
+            return Err(ApiError::bad_request(format!("invalid cursor: {}", message)).into())
+        }
+        Err(e) => return Err(user_error(e).into()),
+    };
+    let (total_count, stats) = match content.author_content_stats(user_id, platform).await {
+        Ok(stats) => stats,
+        Err(e) => return Err(user_error(e).into()),
     };
 
     Ok(HttpResponse::Ok().json(json!({
         "success": true,
-        "data": user,
+        "data": page.data,
+        "next_cursor": page.next_cursor,
+        "has_more": page.has_more,
+        "total_count": total_count,
+        "stats": stats,
         "timestamp": chrono::Utc::now().to_rfc3339()
     })))
 }
 
-/// Update user information
+/// Update user profile fields; omitted fields are left unchanged
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "update_user",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    request_body = UpdateUserRequest,
+    responses(
+        (status = 200, description = "The updated user", body = UserResponse),
+        (status = 400, description = "Malformed user ID"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 404, description = "No such user"),
+        (status = 409, description = "Username already taken"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[put("/{user_id}")]
 pub async fn update_user(
     path: web::Path<String>,
-    user_data: web::Json<CreateUserRequest>
+    user_data: web::Json<UpdateUserRequest>,
+    claims: web::ReqData<Claims>,
+    users: web::Data<UserRepository>,
 ) -> Result<HttpResponse> {
-    let user_id = path.into_inner();
-    
-    // Mock update response
-    let user = UserResponse {
-        id: user_id,
-        wallet_address: user_data.wallet_address.clone(),
-        username: user_data.username.clone(),
-        display_name: user_data.display_name.clone(),
-        echo_score: 75.8,
-        total_rewards: 1250.0,
-        rank: Some(42),
-        is_verified: true,
-        created_at: chrono::Utc::now().to_rfc3339(),
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot update another user's profile")?;
+    let user_data = user_data.into_inner();
+    let patch = UserPatch {
+        username: user_data.username,
+        display_name: user_data.display_name,
+        bio: user_data.bio,
+        avatar_url: user_data.avatar_url,
     };
 
-    Ok(HttpResponse::Ok().json(json!({
-        "success": true,
-        "data": user,
-        "timestamp": chrono::Utc::now().to_rfc3339()
-    })))
+    match users.update(user_id, &patch).await {
+        Ok(user) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": UserResponse::from(user),
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(RepositoryError::Conflict(_)) => {
+            Err(ApiError::StateConflict("Username is already taken".to_string()).into())
+        }
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// Delete a user's account by anonymizing it. Their wallet address, username and email
+/// are replaced with hashes and their profile, connected accounts and credentials are
+/// dropped; their content and its Echo Index history stay for platform analytics.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "delete_user",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 204, description = "User anonymized"),
+        (status = 400, description = "Malformed user ID"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 404, description = "No such user"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[delete("/{user_id}")]
+pub async fn delete_user(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    user_data: web::Data<UserDataService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot delete another user")?;
+
+    match user_data.anonymize(user_id).await {
+        Ok(()) => {
+            log::info!("User {} was anonymized by {}", user_id, claims.sub);
+            Ok(HttpResponse::NoContent().finish())
+        }
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// Download everything stored about a user as a ZIP archive of JSON files, at most
+/// once a day
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "export_user_data",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "ZIP archive of JSON files", body = Vec<u8>, content_type = "application/zip"),
+        (status = 400, description = "Malformed user ID"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 404, description = "No such user"),
+        (status = 429, description = "Already exported today"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/export")]
+pub async fn export_user_data(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    user_data: web::Data<UserDataService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot export another user's data")?;
+
+    let archive = match user_data.export(user_id, Utc::now()).await {
+        Ok(archive) => web::Bytes::from(archive),
+        Err(UserDataError::ExportLimitReached) => {
+            return Err(ApiError::LimitReached(UserDataError::ExportLimitReached.to_string()).into())
+        }
+        Err(UserDataError::Repository(e)) => return Err(user_error(e).into()),
+        Err(e) => {
+            tracing::error!(%user_id, error = %e, "Data export failed");
+            return Err(ApiError::internal().into());
+        }
+    };
+    log::info!("User {} exported the data of {}", claims.sub, user_id);
+
+    let size = archive.len();
+    let chunks = (0..size).step_by(EXPORT_CHUNK_BYTES).map(move |start| {
+        Ok::<_, actix_web::Error>(archive.slice(start..(start + EXPORT_CHUNK_BYTES).min(size)))
+    });
+    Ok(HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header((
+            actix_web::http::header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"echolayer-export-{}.zip\"", user_id),
+        ))
+        .streaming(futures_util::stream::iter(chunks)))
+}
+
+/// Import the posts a user published before joining EchoLayer from a platform export,
+/// at most once a day. Takes a multipart form with the export `format`, the export
+/// `file` and, for generic CSV, the `platform` it comes from.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "import_content",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    request_body(
+        content = Object,
+        content_type = "multipart/form-data",
+        description = "The export `format`, the export `file` and, for generic CSV, its `platform`"
+    ),
+    responses(
+        (status = 200, description = "Posts imported, skipped as duplicates and failed", body = Object),
+        (status = 400, description = "Missing field, unknown format or invalid export"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 429, description = "Already imported today"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/{user_id}/content/import")]
+pub async fn import_content(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    mut form: Multipart,
+    imports: web::Data<ContentImportService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot import content for another user")?;
+
+    let (mut format, mut platform, mut file) = (None, None, None);
+    while let Some(field) = form.next().await {
+        let mut field = field?;
+        let name = field.name().unwrap_or_default().to_string();
+        let mut value = Vec::new();
+        while let Some(chunk) = field.next().await {
+            value.extend_from_slice(&chunk?);
+            if value.len() > MAX_IMPORT_FILE_BYTES {
+                return Err(ApiError::bad_request(format!(
+                    "export file must be at most {} MB",
+                    MAX_IMPORT_FILE_BYTES / (1024 * 1024)
+                )).into());
+            }
+        }
+        match name.as_str() {
+            "format" => format = Some(String::from_utf8_lossy(&value).trim().to_string()),
+            "platform" => platform = Some(Platform::from(String::from_utf8_lossy(&value).trim())),
+            "file" => file = Some(value),
+            _ => {}
+        }
+    }
+
+    let Some(format) = format else {
+        return Err(ApiError::bad_request("format is required").into());
+    };
+    let Ok(format) = serde_json::from_value::<ImportFormat>(json!(format)) else {
+        return Err(ApiError::bad_request(format!("Unknown export format: {}", format)).into());
+    };
+    let Some(platform) = platform.or_else(|| format.platform()) else {
+        return Err(ApiError::bad_request("platform is required for generic CSV imports").into());
+    };
+    let Some(file) = file else {
+        return Err(ApiError::bad_request("file is required").into());
+    };
+    let payload = match format.payload_from_file(&file) {
+        Ok(payload) => payload,
+        Err(message) => return Err(ApiError::bad_request(message).into()),
+    };
+
+    let request = ContentImportRequest { platform, format, payload };
+    match imports.import(user_id, &request, Utc::now()).await {
+        Ok(summary) => {
+            log::info!(
+                "User {} imported {} {} posts ({} duplicates, {} failed)",
+                user_id,
+                summary.imported,
+                request.platform,
+                summary.skipped_duplicates,
+                summary.failed
+            );
+            Ok(HttpResponse::Ok().json(json!({
+                "success": true,
+                "data": summary,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(e @ ContentImportError::ImportLimitReached) => Err(ApiError::LimitReached(e.to_string()).into()),
+        Err(ContentImportError::InvalidExport(message)) => Err(ApiError::bad_request(message).into()),
+        Err(ContentImportError::Repository(e)) => Err(user_error(e).into()),
+    }
 }
 
 /// Get user analytics
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_user_analytics",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Echo Score summary, trend and breakdown by platform", body = Object),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[get("/{user_id}/analytics")]
 pub async fn get_user_analytics(path: web::Path<String>) -> Result<HttpResponse> {
     let _user_id = path.into_inner();
@@ -140,40 +650,2105 @@ pub async fn get_user_analytics(path: web::Path<String>) -> Result<HttpResponse>
     })))
 }
 
-/// Get leaderboard
+/// Rewards distributed to the user over time, in hourly, daily or weekly buckets. Only
+/// the user or an admin may see them.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_user_reward_analytics",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID"), RewardAnalyticsQuery),
+    responses(
+        (status = 200, description = "Rewards distributed to the user per bucket", body = Object),
+        (status = 400, description = "Malformed user ID or invalid range"),
+        (status = 403, description = "Not the user or an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/analytics/rewards")]
+pub async fn get_user_reward_analytics(
+    path: web::Path<String>,
+    query: web::Query<RewardAnalyticsQuery>,
+    claims: web::ReqData<Claims>,
+    analytics: web::Data<RewardAnalyticsService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot view another user's reward analytics")?;
+    let (since, until) = match query.range(Utc::now()) {
+        Ok(range) => range,
+        Err(message) => return Err(ApiError::bad_request(message).into()),
+    };
+
+    match analytics.time_series(Some(user_id), query.granularity, since, until).await {
+        Ok(buckets) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": buckets,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// Get the vested portion of a user's pending rewards that can be claimed now
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_claimable_rewards",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "The user's claimable and pending rewards", body = Object),
+        (status = 400, description = "Malformed user ID"),
+        (status = 403, description = "Not the user or an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/rewards/claimable")]
+pub async fn get_claimable_rewards(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    reward_service: web::Data<RwLock<RewardService>>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot view another user's rewards")?;
+    let reward_service = reward_service.read().await;
+    let now = chrono::Utc::now();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": {
+            "user_id": user_id,
+            "claimable": reward_service.get_user_claimable_rewards(&user_id.to_string(), now),
+            "pending": reward_service.get_user_pending_rewards(&user_id.to_string()),
+            "as_of": now.to_rfc3339()
+        },
+        "timestamp": now.to_rfc3339()
+    })))
+}
+
+/// Pay out the user's claimable rewards to their wallet in one on-chain transfer
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "process_user_rewards",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Settlement of the user's claimable rewards", body = Object),
+        (status = 400, description = "Malformed user ID"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 409, description = "A settlement of the user's rewards is in flight"),
+        (status = 503, description = "Solana RPC unavailable, or submitting transfers is not enabled"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/{user_id}/rewards/process")]
+pub async fn process_user_rewards(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    vesting: web::Data<TokenVestingService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot process another user's rewards")?;
+
+    match vesting.process_batch(user_id, Utc::now()).await {
+        Ok(settlement) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": settlement,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(SettlementError::InFlight) => Err(ApiError::StateConflict(SettlementError::InFlight.to_string()).into()),
+        Err(SettlementError::Repository(e)) => Err(user_error(e).into()),
+        Err(e) => {
+            log::warn!("Settlement of rewards of {} failed: {}", user_id, e);
+            Err(ApiError::ServiceUnavailable { dependency: "Solana RPC".to_string() }.into())
+        }
+    }
+}
+
+/// Merkle proofs for claiming some of the user's rewards on chain in one transaction
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "claim_rewards_batch",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    request_body = ClaimBatchRequest,
+    responses(
+        (status = 200, description = "Merkle root of the rewards and a proof of each", body = MerkleRewardClaim),
+        (status = 400, description = "Malformed user ID, or no, too many or repeated rewards"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 404, description = "A reward that is not the user's"),
+        (status = 409, description = "A reward that is held, frozen or already paid"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/{user_id}/rewards/claim-batch")]
+pub async fn claim_rewards_batch(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    request: web::Json<ClaimBatchRequest>,
+    reward_service: web::Data<RwLock<RewardService>>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot claim another user's rewards")?;
+    let reward_ids = request.into_inner().reward_ids;
+    if reward_ids.is_empty() || reward_ids.len() > MAX_CLAIM_BATCH_REWARDS {
+        let message = format!("reward_ids must hold 1 to {} rewards", MAX_CLAIM_BATCH_REWARDS);
+        return Err(ApiError::bad_request(message).into());
+    }
+    if reward_ids.iter().collect::<HashSet<_>>().len() < reward_ids.len() {
+        return Err(ApiError::bad_request("reward_ids must not repeat").into());
+    }
+
+    let user_rewards = reward_service.read().await.get_user_rewards(&user_id.to_string());
+    let mut rewards = Vec::with_capacity(reward_ids.len());
+    for reward_id in reward_ids {
+        let id = format_reward_id(reward_id);
+        let Some(reward) = user_rewards.iter().find(|reward| reward.id == id) else {
+            return Err(ApiError::NotFound(format!("Reward {} not found", reward_id)).into());
+        };
+        // Held, frozen and paid rewards cannot be claimed
+        if reward.on_hold || reward.frozen || reward.transaction_hash.is_some() {
+            return Err(ApiError::StateConflict(format!("Reward {} cannot be claimed", reward_id)).into());
+        }
+        rewards.push(reward.clone());
+    }
+
+    let Some(claim) = MerkleRewardClaim::new(&rewards) else {
+        return Err(ApiError::bad_request("reward_ids must not be empty").into());
+    };
+    claim.publish_root(&user_id.to_string());
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": claim,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Reward transfers of the user submitted on chain but not yet settled
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_pending_transactions",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Unsettled reward transfers", body = Object),
+        (status = 400, description = "Malformed user ID"),
+        (status = 403, description = "Not the user or an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/rewards/pending-transactions")]
+pub async fn get_pending_transactions(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    vesting: web::Data<TokenVestingService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot view another user's transactions")?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": vesting.pending_transactions(user_id),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Forecast the EchoDrops a user's active content earns over the next `hours`
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_earnings_forecast",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID"), EarningsForecastQuery),
+    responses(
+        (status = 200, description = "EchoDrops forecast for each of the user's active content items", body = Object),
+        (status = 400, description = "Malformed user ID"),
+        (status = 403, description = "Not the user or an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/earnings-forecast")]
+pub async fn get_earnings_forecast(
+    path: web::Path<String>,
+    query: web::Query<EarningsForecastQuery>,
+    claims: web::ReqData<Claims>,
+    forecasts: web::Data<RewardForecastService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot view another user's earnings forecast")?;
+    let hours = query.hours.unwrap_or(DEFAULT_HORIZON_HOURS).clamp(1, MAX_HORIZON_HOURS);
+
+    match forecasts.earnings_forecast(user_id, hours).await {
+        Ok(forecast) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": forecast,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// Get a user's activity events, newest first, with cursor-based pagination.
+/// Each event carries an `event_type` discriminant.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_user_timeline",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID"), TimelineQuery),
+    responses(
+        (status = 200, description = "A page of the user's events, newest first", body = [Object]),
+        (status = 400, description = "Malformed user ID or invalid cursor"),
+        (status = 403, description = "Not the user or an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/timeline")]
+pub async fn get_user_timeline(
+    path: web::Path<String>,
+    query: web::Query<TimelineQuery>,
+    claims: web::ReqData<Claims>,
+    events: web::Data<UserEventRepository>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot view another user's timeline")?;
+    let limit = query.limit.unwrap_or(DEFAULT_TIMELINE_LIMIT).clamp(1, MAX_TIMELINE_LIMIT);
+    let after = match query.after.as_deref().map(Cursor::decode).transpose() {
+        Ok(after) => after,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    match events.timeline(user_id, after, limit).await {
+        Ok(page) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": page.data,
+            "next_cursor": page.next_cursor,
+            "has_more": page.has_more,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => {
+            tracing::error!(%user_id, error = %e, "Timeline query failed");
+            Err(ApiError::internal().into())
+        }
+    }
+}
+
+/// Content picked for the user from what they and the users they follow propagated,
+/// each with the reason it was picked. The feed is ranked at most every 15 minutes;
+/// a cursor from an earlier ranking is rejected.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_user_feed",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID"), FeedQuery),
+    responses(
+        (status = 200, description = "A page of the feed, each entry with the reason it was picked", body = [Object]),
+        (status = 400, description = "Malformed user ID, or a cursor from an earlier ranking"),
+        (status = 403, description = "Not the user or an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/feed")]
+pub async fn get_user_feed(
+    path: web::Path<String>,
+    query: web::Query<FeedQuery>,
+    claims: web::ReqData<Claims>,
+    feeds: web::Data<DiscoveryFeedService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot view another user's feed")?;
+    let limit = query.limit.unwrap_or(DEFAULT_FEED_LIMIT).clamp(1, MAX_FEED_LIMIT);
+    let after = match query.after.as_deref().map(Cursor::decode).transpose() {
+        Ok(after) => after,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    // Fetch one extra entry to learn whether another page exists
+    match feeds.generate_feed(user_id, limit + 1, after).await {
+        Ok(entries) => {
+            let page = Page::from_rows(entries, limit as usize, |entry| {
+                Cursor::new(entry.content.created_at, entry.content.id)
+            });
+            Ok(HttpResponse::Ok().json(json!({
+                "success": true,
+                "data": page.data,
+                "next_cursor": page.next_cursor,
+                "has_more": page.has_more,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(RepositoryError::InvalidCursor(message)) => {
+            Err(ApiError::bad_request(format!("invalid cursor: {}", message)).into())
+        }
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// Get a user's daily content creation streak and the reward multiplier it earns
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_user_streak",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "The `streak` and the reward `multiplier` it earns", body = Object),
+        (status = 400, description = "Malformed user ID"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/streak")]
+pub async fn get_user_streak(
+    path: web::Path<String>,
+    streaks: web::Data<StreakService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+
+    match streaks.get(user_id, chrono::Utc::now().date_naive()).await {
+        Ok(streak) => Ok(streak_response(streak)),
+        Err(e) => Err(streak_error(user_id, e).into()),
+    }
+}
+
+/// Spend a streak freeze token so today counts without new content
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "freeze_user_streak",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "The `streak` and the reward `multiplier` it earns", body = Object),
+        (status = 400, description = "Malformed user ID"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 409, description = "No streak to freeze or token left, or today already covered"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/{user_id}/streak/freeze")]
+pub async fn freeze_user_streak(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    streaks: web::Data<StreakService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot freeze another user's streak")?;
+
+    match streaks.freeze(user_id, chrono::Utc::now().date_naive()).await {
+        Ok(streak) => {
+            log::info!("User {} froze their streak at {} days", user_id, streak.current_streak_days);
+            Ok(streak_response(streak))
+        }
+        Err(e) => Err(streak_error(user_id, e).into()),
+    }
+}
+
+fn streak_response(streak: UserStreak) -> HttpResponse {
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "data": {
+            "multiplier": streak.multiplier(),
+            "streak": streak
+        },
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    }))
+}
+
+fn streak_error(user_id: Uuid, error: RepositoryError) -> ApiError {
+    match error {
+        RepositoryError::Conflict(message) => ApiError::StateConflict(message),
+        e => {
+            tracing::error!(%user_id, error = %e, "Streak query failed");
+            ApiError::internal()
+        }
+    }
+}
+
+/// Get a user's latest influence score on the propagation network and their rank by it
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_user_influence",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Latest influence score and rank", body = Object),
+        (status = 400, description = "Malformed user ID"),
+        (status = 404, description = "No such user"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/influence")]
+pub async fn get_user_influence(
+    path: web::Path<String>,
+    users: web::Data<UserRepository>,
+    influence: web::Data<InfluenceScoreCalculator>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    match users.find_by_id(user_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(user_error(RepositoryError::NotFound).into()),
+        Err(e) => return Err(user_error(e).into()),
+    }
+
+    match influence.influence(user_id).await {
+        Ok(influence) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": influence,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// Follow another user. Following someone again keeps the original follow.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "follow_user",
+    tag = "users",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID"),
+        ("target_user_id" = Uuid, Path, description = "User followed"),
+    ),
+    responses(
+        (status = 200, description = "The follow, new or existing", body = Object),
+        (status = 400, description = "Malformed user ID, or following oneself"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 404, description = "No such user to follow"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/{user_id}/follow/{target_user_id}")]
+pub async fn follow_user(
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+    users: web::Data<UserRepository>,
+    follower_graph: web::Data<UserFollowerGraph>,
+) -> Result<HttpResponse> {
+    let (user_id, target_user_id) = path.into_inner();
+    let (Ok(user_id), Ok(target_user_id)) = (Uuid::parse_str(&user_id), Uuid::parse_str(&target_user_id)) else {
+        return Err(ApiError::bad_request("user_id and target_user_id must be valid UUIDs").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot follow users for another user")?;
+    match users.find_by_id(target_user_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(user_error(RepositoryError::NotFound).into()),
+        Err(e) => return Err(user_error(e).into()),
+    }
+
+    match follower_graph.follow(user_id, target_user_id).await {
+        Ok(relationship) => {
+            log::info!("User {} followed user {}", user_id, target_user_id);
+            Ok(HttpResponse::Ok().json(json!({
+                "success": true,
+                "data": relationship,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// Stop following another user
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "unfollow_user",
+    tag = "users",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID"),
+        ("target_user_id" = Uuid, Path, description = "User followed"),
+    ),
+    responses(
+        (status = 204, description = "No longer following"),
+        (status = 400, description = "Malformed user ID"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 404, description = "Not following the user"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[delete("/{user_id}/follow/{target_user_id}")]
+pub async fn unfollow_user(
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+    follower_graph: web::Data<UserFollowerGraph>,
+) -> Result<HttpResponse> {
+    let (user_id, target_user_id) = path.into_inner();
+    let (Ok(user_id), Ok(target_user_id)) = (Uuid::parse_str(&user_id), Uuid::parse_str(&target_user_id)) else {
+        return Err(ApiError::bad_request("user_id and target_user_id must be valid UUIDs").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot unfollow users for another user")?;
+
+    match follower_graph.unfollow(user_id, target_user_id).await {
+        Ok(true) => {
+            log::info!("User {} unfollowed user {}", user_id, target_user_id);
+            Ok(HttpResponse::NoContent().finish())
+        }
+        Ok(false) => Err(ApiError::NotFound("Not following this user".to_string()).into()),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// Users following a user, most recent follow first, with cursor-based pagination
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_followers",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID"), FollowListQuery),
+    responses(
+        (status = 200, description = "A page of followers, most recent first", body = [FollowedUserResponse]),
+        (status = 400, description = "Malformed user ID or invalid cursor"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/followers")]
+pub async fn get_followers(
+    path: web::Path<String>,
+    query: web::Query<FollowListQuery>,
+    follower_graph: web::Data<UserFollowerGraph>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_FOLLOW_LIMIT).clamp(1, MAX_FOLLOW_LIMIT);
+    let after = match query.after.as_deref().map(Cursor::decode).transpose() {
+        Ok(after) => after,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    follow_list_response(follower_graph.followers(user_id, after, limit).await)
+}
+
+/// Users a user follows, most recent follow first, with cursor-based pagination
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_following",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID"), FollowListQuery),
+    responses(
+        (status = 200, description = "A page of users followed, most recent first", body = [FollowedUserResponse]),
+        (status = 400, description = "Malformed user ID or invalid cursor"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/following")]
+pub async fn get_following(
+    path: web::Path<String>,
+    query: web::Query<FollowListQuery>,
+    follower_graph: web::Data<UserFollowerGraph>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_FOLLOW_LIMIT).clamp(1, MAX_FOLLOW_LIMIT);
+    let after = match query.after.as_deref().map(Cursor::decode).transpose() {
+        Ok(after) => after,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    follow_list_response(follower_graph.following(user_id, after, limit).await)
+}
+
+fn follow_list_response(
+    result: std::result::Result<Page<FollowedUser>, RepositoryError>,
+) -> Result<HttpResponse> {
+    match result {
+        Ok(page) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": page.data.into_iter().map(FollowedUserResponse::from).collect::<Vec<_>>(),
+            "next_cursor": page.next_cursor,
+            "has_more": page.has_more,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// Create an API key for a machine client acting as this user. The raw key is only
+/// ever returned here; only its hash is stored.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "create_api_key",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "The raw `key`, shown only once, and the `api_key`", body = Object),
+        (status = 400, description = "Invalid name, permissions, rate limit or expiry"),
+        (status = 403, description = "Not the user or an admin, or made with an API key"),
+        (status = 404, description = "No such user"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/{user_id}/api-keys")]
+pub async fn create_api_key(
+    path: web::Path<String>,
+    body: web::Json<CreateApiKeyRequest>,
+    claims: web::ReqData<Claims>,
+    current_key: Option<web::ReqData<ApiKey>>,
+    api_keys: web::Data<ApiKeyService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_key_management(user_id, &claims, current_key.is_some())?;
+
+    let body = body.into_inner();
+    let name = body.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_API_KEY_NAME_LENGTH {
+        return Err(ApiError::bad_request(format!("name must be 1 to {} characters", MAX_API_KEY_NAME_LENGTH)).into());
+    }
+    let permissions = body.permissions.unwrap_or_else(|| vec![Permission::Read]);
+    if permissions.is_empty() {
+        return Err(ApiError::bad_request("permissions must not be empty").into());
+    }
+    let rate_limit = body.rate_limit_per_minute.unwrap_or(DEFAULT_API_KEY_RATE_LIMIT);
+    if !(1..=MAX_API_KEY_RATE_LIMIT).contains(&rate_limit) {
+        let message = format!("rate_limit_per_minute must be 1 to {}", MAX_API_KEY_RATE_LIMIT);
+        return Err(ApiError::bad_request(message).into());
+    }
+    if body.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+        return Err(ApiError::bad_request("expires_at must be in the future").into());
+    }
+
+    match api_keys.create(user_id, name, &permissions, rate_limit, body.expires_at).await {
+        Ok((raw_key, api_key)) => {
+            log::info!("User {} created API key {}", user_id, api_key.id);
+            Ok(HttpResponse::Created().json(json!({
+                "success": true,
+                "data": {
+                    "key": raw_key,
+                    "api_key": api_key
+                },
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        // The only foreign key is the owner
+        Err(RepositoryError::Conflict(_)) => Err(user_error(RepositoryError::NotFound).into()),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// Revoke one of a user's API keys; requests made with it are rejected from then on
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "revoke_api_key",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID"), ("key_id" = Uuid, Path, description = "API key ID")),
+    responses(
+        (status = 204, description = "API key revoked"),
+        (status = 400, description = "Malformed ID"),
+        (status = 403, description = "Not the user or an admin, or made with an API key"),
+        (status = 404, description = "No such API key of the user"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[delete("/{user_id}/api-keys/{key_id}")]
+pub async fn revoke_api_key(
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+    current_key: Option<web::ReqData<ApiKey>>,
+    api_keys: web::Data<ApiKeyService>,
+) -> Result<HttpResponse> {
+    let (user_id, key_id) = path.into_inner();
+    let (Ok(user_id), Ok(key_id)) = (Uuid::parse_str(&user_id), Uuid::parse_str(&key_id)) else {
+        return Err(ApiError::bad_request("user_id and key_id must be valid UUIDs").into());
+    };
+    forbid_key_management(user_id, &claims, current_key.is_some())?;
+
+    match api_keys.revoke(user_id, key_id).await {
+        Ok(()) => {
+            log::info!("User {} revoked API key {}", user_id, key_id);
+            Ok(HttpResponse::NoContent().finish())
+        }
+        Err(RepositoryError::NotFound) => Err(ApiError::NotFound("API key not found".to_string()).into()),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ConfirmTwoFactorQuery {
+    pub code: String,
+}
+
+/// Generate a TOTP secret for the user's authenticator app. Until it is confirmed,
+/// setting up again replaces it.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "setup_two_factor",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "TOTP secret and provisioning URI for the authenticator app", body = Object),
+        (status = 400, description = "Malformed user ID"),
+        (status = 403, description = "Not the user, or made with an API key"),
+        (status = 409, description = "Two-factor authentication already enabled"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/{user_id}/2fa/setup")]
+pub async fn setup_two_factor(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    current_key: Option<web::ReqData<ApiKey>>,
+    two_factor: web::Data<TwoFactorService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_two_factor_management(user_id, &claims, current_key.is_some())?;
+
+    match two_factor.setup(user_id).await {
+        Ok(setup) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": setup,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(two_factor_error(e).into()),
+    }
+}
+
+/// Enable two-factor authentication with a first code from the authenticator app,
+/// returning the user's recovery codes
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "confirm_two_factor",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID"), ConfirmTwoFactorQuery),
+    responses(
+        (status = 200, description = "Two-factor authentication enabled, with the `recovery_codes`", body = Object),
+        (status = 400, description = "Not set up, or an invalid code"),
+        (status = 403, description = "Not the user, or made with an API key"),
+        (status = 409, description = "Two-factor authentication already enabled"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/{user_id}/2fa/confirm")]
+pub async fn confirm_two_factor(
+    path: web::Path<String>,
+    query: web::Query<ConfirmTwoFactorQuery>,
+    claims: web::ReqData<Claims>,
+    current_key: Option<web::ReqData<ApiKey>>,
+    two_factor: web::Data<TwoFactorService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_two_factor_management(user_id, &claims, current_key.is_some())?;
+
+    match two_factor.confirm(user_id, &query.code).await {
+        Ok(recovery_codes) => {
+            log::info!("User {} enabled two-factor authentication", user_id);
+            Ok(HttpResponse::Ok().json(json!({
+                "success": true,
+                "data": {
+                    "totp_enabled": true,
+                    "recovery_codes": recovery_codes
+                },
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(e) => Err(two_factor_error(e).into()),
+    }
+}
+
+/// A new set of recovery codes, invalidating the previous ones. Codes are stored hashed,
+/// so earlier ones cannot be shown again.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_recovery_codes",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "New `recovery_codes`, replacing the previous ones", body = Object),
+        (status = 400, description = "Two-factor authentication not enabled"),
+        (status = 403, description = "Not the user, or made with an API key"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/2fa/recovery-codes")]
+pub async fn get_recovery_codes(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    current_key: Option<web::ReqData<ApiKey>>,
+    two_factor: web::Data<TwoFactorService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_two_factor_management(user_id, &claims, current_key.is_some())?;
+
+    match two_factor.regenerate_recovery_codes(user_id).await {
+        Ok(recovery_codes) => {
+            log::info!("User {} regenerated two-factor recovery codes", user_id);
+            Ok(HttpResponse::Ok().json(json!({
+                "success": true,
+                "data": { "recovery_codes": recovery_codes },
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Err(e) => Err(two_factor_error(e).into()),
+    }
+}
+
+/// Only the user themself, signed in with their wallet, may manage their second factor
+fn forbid_two_factor_management(user_id: Uuid, claims: &Claims, with_api_key: bool) -> Result<(), ApiError> {
+    if with_api_key {
+        return Err(ApiError::Forbidden("Two-factor authentication cannot be managed with an API key".to_string()));
+    }
+    if claims.sub != user_id.to_string() {
+        return Err(ApiError::Forbidden("Cannot manage another user's two-factor authentication".to_string()));
+    }
+    Ok(())
+}
+
+fn two_factor_error(error: TwoFactorError) -> ApiError {
+    match error {
+        TwoFactorError::AlreadyEnabled | TwoFactorError::Repository(RepositoryError::Conflict(_)) => {
+            ApiError::StateConflict(error.to_string())
+        }
+        TwoFactorError::Repository(e) => user_error(e),
+        TwoFactorError::NotSetUp | TwoFactorError::NotEnabled | TwoFactorError::InvalidCode => {
+            ApiError::bad_request(error.to_string())
+        }
+        e @ (TwoFactorError::Cipher | TwoFactorError::Totp(_)) => {
+            tracing::error!(error = %e, "Two-factor authentication failed");
+            ApiError::internal()
+        }
+    }
+}
+
+/// Only the user, signed in with their wallet, or an admin may manage a user's API
+/// keys. Keys cannot mint or revoke keys, so a leaked key cannot outlive its revocation.
+fn forbid_key_management(user_id: Uuid, claims: &Claims, with_api_key: bool) -> Result<(), ApiError> {
+    if with_api_key {
+        return Err(ApiError::Forbidden("API keys cannot be managed with an API key".to_string()));
+    }
+    forbid_other_user(user_id, claims, "Cannot manage another user's API keys")
+}
+
+/// Reject requests about a user made by anyone but that user or an admin signed in with a
+/// second factor
+fn forbid_other_user(user_id: Uuid, claims: &Claims, message: &str) -> Result<(), ApiError> {
+    if claims.sub == user_id.to_string() || claims.is_verified_admin() {
+        return Ok(());
+    }
+    Err(ApiError::Forbidden(message.to_string()))
+}
+
+/// Set one of the user's velocity alert thresholds, replacing any earlier threshold of
+/// the same type. Applies to all of the user's content.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "configure_alert",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    request_body = ConfigureAlertRequest,
+    responses(
+        (status = 201, description = "The threshold set", body = Object),
+        (status = 400, description = "Invalid threshold, or a webhook URL that is not an https URL of a public host"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 404, description = "No such user"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/{user_id}/alerts")]
+pub async fn configure_alert(
+    path: web::Path<String>,
+    body: web::Json<ConfigureAlertRequest>,
+    claims: web::ReqData<Claims>,
+    alerts: web::Data<VelocityAlertService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot manage another user's alerts")?;
+
+    let body = body.into_inner();
+    let webhook_url = body.webhook_url.as_deref().map(str::trim).filter(|url| !url.is_empty());
+    if let Some(url) = webhook_url {
+        if let Err(e) = check_callback_url(url).await {
+            return Err(ApiError::bad_request(format!("webhook_url {}", e)).into());
+        }
+    }
+
+    match alerts.configure(user_id, body.threshold, webhook_url).await {
+        Ok(config) => Ok(HttpResponse::Created().json(json!({
+            "success": true,
+            "data": config,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        // The only foreign key is the user
+        Err(RepositoryError::Conflict(_)) => Err(user_error(RepositoryError::NotFound).into()),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// The user's velocity alert thresholds and the alerts they most recently fired
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "list_alerts",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "The user's `thresholds` and `recent_alerts`", body = Object),
+        (status = 400, description = "Malformed user ID"),
+        (status = 403, description = "Not the user or an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/alerts")]
+pub async fn list_alerts(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    alerts: web::Data<VelocityAlertService>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot view another user's alerts")?;
+
+    let configs = match alerts.configs(user_id).await {
+        Ok(configs) => configs,
+        Err(e) => return Err(user_error(e).into()),
+    };
+    match alerts.recent_alerts(user_id).await {
+        Ok(recent) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": {
+                "thresholds": configs,
+                "recent_alerts": recent
+            },
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// Register a webhook for some of the user's events. The signing secret is only ever
+/// returned here.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "create_webhook",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 201, description = "The signing `secret`, shown only once, and the `webhook`", body = Object),
+        (status = 400, description = "Not an https URL of a public host, or no events"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 404, description = "No such user"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/{user_id}/webhooks")]
+pub async fn create_webhook(
+    path: web::Path<String>,
+    body: web::Json<CreateWebhookRequest>,
+    claims: web::ReqData<Claims>,
+    webhooks: web::Data<WebhookRepository>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot manage another user's webhooks")?;
+
+    let body = body.into_inner();
+    let url = body.url.trim();
+    if let Err(e) = check_callback_url(url).await {
+        return Err(ApiError::bad_request(format!("url {}", e)).into());
+    }
+    let mut events: Vec<WebhookEvent> = Vec::new();
+    for event in body.events {
+        if !events.contains(&event) {
+            events.push(event);
+        }
+    }
+    if events.is_empty() {
+        return Err(ApiError::bad_request("events must not be empty").into());
+    }
+
+    let secret = generate_secret();
+    match webhooks.create(user_id, url, &secret, &events).await {
+        Ok(webhook) => {
+            log::info!("User {} registered webhook {}", user_id, webhook.id);
+            Ok(HttpResponse::Created().json(json!({
+                "success": true,
+                "data": {
+                    "secret": secret,
+                    "webhook": webhook
+                },
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        // The only foreign key is the user
+        Err(RepositoryError::Conflict(_)) => Err(user_error(RepositoryError::NotFound).into()),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// The user's webhooks, oldest first
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "list_webhooks",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "The user's webhooks, oldest first", body = [Object]),
+        (status = 400, description = "Malformed user ID"),
+        (status = 403, description = "Not the user or an admin"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/webhooks")]
+pub async fn list_webhooks(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    webhooks: web::Data<WebhookRepository>,
+) -> Result<HttpResponse> {
+    let Ok(user_id) = Uuid::parse_str(&path.into_inner()) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot view another user's webhooks")?;
+
+    match webhooks.list(user_id).await {
+        Ok(webhooks) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": webhooks,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// Delete one of the user's webhooks along with its delivery log
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "delete_webhook",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID"), ("webhook_id" = Uuid, Path, description = "Webhook ID")),
+    responses(
+        (status = 204, description = "Webhook deleted"),
+        (status = 400, description = "Malformed ID"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 404, description = "No such webhook of the user"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[delete("/{user_id}/webhooks/{webhook_id}")]
+pub async fn delete_webhook(
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+    webhooks: web::Data<WebhookRepository>,
+) -> Result<HttpResponse> {
+    let (user_id, webhook_id) = path.into_inner();
+    let (Ok(user_id), Ok(webhook_id)) = (Uuid::parse_str(&user_id), Uuid::parse_str(&webhook_id)) else {
+        return Err(ApiError::bad_request("user_id and webhook_id must be valid UUIDs").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot manage another user's webhooks")?;
+
+    match webhooks.delete(user_id, webhook_id).await {
+        Ok(()) => {
+            log::info!("User {} deleted webhook {}", user_id, webhook_id);
+            Ok(HttpResponse::NoContent().finish())
+        }
+        Err(RepositoryError::NotFound) => Err(webhook_not_found().into()),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+/// The most recent attempts at delivering events to one of the user's webhooks, newest first
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "list_webhook_deliveries",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User ID"), ("webhook_id" = Uuid, Path, description = "Webhook ID")),
+    responses(
+        (status = 200, description = "Most recent delivery attempts, newest first", body = [Object]),
+        (status = 400, description = "Malformed ID"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 404, description = "No such webhook of the user"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/webhooks/{webhook_id}/deliveries")]
+pub async fn list_webhook_deliveries(
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+    webhooks: web::Data<WebhookRepository>,
+) -> Result<HttpResponse> {
+    let (user_id, webhook_id) = path.into_inner();
+    let (Ok(user_id), Ok(webhook_id)) = (Uuid::parse_str(&user_id), Uuid::parse_str(&webhook_id)) else {
+        return Err(ApiError::bad_request("user_id and webhook_id must be valid UUIDs").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot view another user's webhooks")?;
+
+    match webhooks.find(user_id, webhook_id).await {
+        Ok(_) => {}
+        Err(RepositoryError::NotFound) => return Err(webhook_not_found().into()),
+        Err(e) => return Err(user_error(e).into()),
+    }
+    match webhooks.deliveries(webhook_id, RECENT_DELIVERIES_LIMIT).await {
+        Ok(deliveries) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": deliveries,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(user_error(e).into()),
+    }
+}
+
+fn webhook_not_found() -> ApiError {
+    ApiError::NotFound("Webhook not found".to_string())
+}
+
+/// Start proving that the user owns an account on a social platform. The user is sent
+/// to the returned URL to authorize EchoLayer with the platform.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "start_social_verification",
+    tag = "users",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID"),
+        ("platform" = Platform, Path, description = "Platform of the account"),
+        StartVerificationQuery,
+    ),
+    responses(
+        (status = 200, description = "URL to send the user to for authorizing EchoLayer", body = Object),
+        (status = 400, description = "Malformed user ID, empty account ID or unsupported platform"),
+        (status = 403, description = "Not the user or an admin"),
+        (status = 404, description = "No such user"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/social-accounts/{platform}/verify/start")]
+pub async fn start_social_verification(
+    path: web::Path<(String, String)>,
+    query: web::Query<StartVerificationQuery>,
+    claims: web::ReqData<Claims>,
+    verifier: web::Data<SocialAccountVerifier>,
+) -> Result<HttpResponse> {
+    let (user_id, platform) = path.into_inner();
+    let Ok(user_id) = Uuid::parse_str(&user_id) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot verify another user's accounts")?;
+    let account_id = query.account_id.trim();
+    if account_id.is_empty() {
+        return Err(ApiError::bad_request("account_id must not be empty").into());
+    }
+
+    match verifier.start(user_id, &Platform::from(platform.as_str()), account_id, Utc::now()).await {
+        Ok(start) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": start,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        // The only foreign key is the user
+        Err(VerificationError::Repository(RepositoryError::Conflict(_))) => {
+            Err(user_error(RepositoryError::NotFound).into())
+        }
+        Err(e) => Err(verification_error(e).into()),
+    }
+}
+
+/// Finish a verification with the parameters the platform redirected back with. The
+/// account is marked verified if the user authorized as the account they claimed.
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "complete_social_verification",
+    tag = "users",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID"),
+        ("platform" = Platform, Path, description = "Platform of the account"),
+        OAuthCallback,
+    ),
+    responses(
+        (status = 200, description = "The verified account", body = Object),
+        (status = 400, description = "Unknown or expired state"),
+        (status = 403, description = "Not the user or an admin, or authorized as another account"),
+        (status = 503, description = "OAuth provider unavailable"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/{user_id}/social-accounts/{platform}/verify/callback")]
+pub async fn complete_social_verification(
+    path: web::Path<(String, String)>,
+    query: web::Query<OAuthCallback>,
+    claims: web::ReqData<Claims>,
+    verifier: web::Data<SocialAccountVerifier>,
+) -> Result<HttpResponse> {
+    let (user_id, platform) = path.into_inner();
+    let Ok(user_id) = Uuid::parse_str(&user_id) else {
+        return Err(ApiError::bad_request("user_id must be a valid UUID").into());
+    };
+    forbid_other_user(user_id, &claims, "Cannot verify another user's accounts")?;
+
+    match verifier.complete(user_id, &Platform::from(platform.as_str()), &query, Utc::now()).await {
+        Ok(account) => Ok(HttpResponse::Ok().json(json!({
+            "success": true,
+            "data": account,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))),
+        Err(e) => Err(verification_error(e).into()),
+    }
+}
+
+fn verification_error(error: VerificationError) -> ApiError {
+    match error {
+        VerificationError::AccountMismatch { .. } => ApiError::Forbidden(error.to_string()),
+        VerificationError::Provider(message) => {
+            log::warn!("OAuth provider request failed: {}", message);
+            ApiError::ServiceUnavailable { dependency: "OAuth provider".to_string() }
+        }
+        VerificationError::Repository(RepositoryError::Conflict(message)) => ApiError::StateConflict(message),
+        VerificationError::Repository(e) => user_error(e),
+        e => ApiError::bad_request(e.to_string()),
+    }
+}
+
+/// Users ranked by Echo Score with their influence rank, with cursor-based pagination that
+/// stays stable across ties
+#[utoipa::path(
+    context_path = "/api/v1/users",
+    operation_id = "get_leaderboard",
+    tag = "users",
+    params(LeaderboardQuery),
+    responses(
+        (status = 200, description = "A page of users, highest Echo Score first", body = [LeaderboardEntryResponse]),
+        (status = 400, description = "Invalid cursor"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[get("/leaderboard")]
-pub async fn get_leaderboard() -> Result<HttpResponse> {
-    // Mock leaderboard data
-    let leaderboard = vec![
-        json!({
-            "rank": 1,
-            "user_id": "user_1",
-            "username": "echo_master",
-            "display_name": "Echo Master",
-            "echo_score": 95.8,
-            "total_rewards": 5000.0
-        }),
-        json!({
-            "rank": 2,
-            "user_id": "user_2",
-            "username": "propagation_pro",
-            "display_name": "Propagation Pro",
-            "echo_score": 89.3,
-            "total_rewards": 4200.0
-        }),
-        json!({
-            "rank": 3,
-            "user_id": "user_3",
-            "username": "content_king",
-            "display_name": "Content King",
-            "echo_score": 85.7,
-            "total_rewards": 3800.0
-        })
-    ];
+pub async fn get_leaderboard(
+    query: web::Query<LeaderboardQuery>,
+    users: web::Data<UserRepository>,
+) -> Result<HttpResponse> {
+    let limit = query.limit.unwrap_or(DEFAULT_LEADERBOARD_LIMIT).clamp(1, MAX_LEADERBOARD_LIMIT);
+    let after = match query.after.as_deref().map(ScoreCursor::decode).transpose() {
+        Ok(after) => after,
+        Err(e) => return Err(ApiError::bad_request(e).into()),
+    };
+
+    // Fetch one extra user to learn whether another page exists
+    let mut leaders = match users
+        .leaderboard(limit + 1, after.map(|cursor| cursor.score), after.map(|cursor| cursor.id))
+        .await
+    {
+        Ok(leaders) => leaders,
+        Err(e) => return Err(user_error(e).into()),
+    };
+    let has_more = leaders.len() > limit as usize;
+    leaders.truncate(limit as usize);
+    let next_cursor = if has_more {
+        leaders.last().map(|entry| ScoreCursor::new(entry.user.echo_score, entry.user.id).encode())
+    } else {
+        None
+    };
 
     Ok(HttpResponse::Ok().json(json!({
         "success": true,
-        "data": leaderboard,
+        "data": leaders.into_iter().map(LeaderboardEntryResponse::from).collect::<Vec<_>>(),
+        "next_cursor": next_cursor,
+        "has_more": has_more,
         "timestamp": chrono::Utc::now().to_rfc3339()
     })))
-} 
\ No newline at end of file
+}
+
+fn user_error(error: RepositoryError) -> ApiError {
+    match error {
+        RepositoryError::NotFound => ApiError::NotFound("User not found".to_string()),
+        RepositoryError::InvalidInput(message) => ApiError::bad_request(message),
+        e => {
+            tracing::error!(error = %e, "User query failed");
+            ApiError::internal()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::auth::{AuthService, JwtConfig};
+    use crate::handlers::{content, propagation};
+    use crate::middleware::jwt::API_KEY_HEADER;
+    use crate::middleware::JwtMiddleware;
+    use crate::models::user::Role;
+    use crate::repositories::{
+        AlertRepository, ApiKeyRepository, ContentFingerprintRepository, ContentRepository, ContentTfIdfRepository, InfluenceRepository,
+        MentionRepository, PropagationRepository, StreakRepository,
+    };
+    use crate::services::{
+        ContentFingerprintService, ContentSimilarityService, EchoDropReward, EchoEngineConfig, EngineConfigStore,
+        MentionLinker, MetricsRegistry, PropagationEventDeduplicator, PropagationService, TokenBlacklist,
+    };
+    use actix_web::test::{call_and_read_body_json, call_service, init_service, read_body_json, TestRequest};
+    use actix_web::App;
+    use sqlx::PgPool;
+    use std::sync::Arc;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_timeline_grows_with_each_operation(pool: PgPool) {
+        let config = JwtConfig::new("test-secret");
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xactive') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let rewards = web::Data::new(tokio::sync::RwLock::new(RewardService::new(10_000.0)));
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(ContentRepository::new(pool.clone())))
+                .app_data(web::Data::new(UserEventRepository::new(pool.clone())))
+                .app_data(web::Data::new(ContentFingerprintRepository::new(pool.clone())))
+                .app_data(web::Data::new(ContentFingerprintService::new()))
+                .app_data(web::Data::new(ContentSimilarityService::new(Arc::new(ContentTfIdfRepository::new(pool.clone())))))
+                .app_data(web::Data::new(MetricsRegistry::new()))
+                .app_data(web::Data::new(PropagationEventDeduplicator::new(
+                    Arc::new(PropagationRepository::new(pool.clone())),
+                    chrono::Duration::hours(24),
+                )))
+                .app_data(web::Data::new(MentionLinker::new(
+                    Arc::new(MentionRepository::new(pool.clone())),
+                    Arc::new(PropagationService::new()),
+                    rewards.clone().into_inner(),
+                )))
+                .app_data(rewards)
+                .app_data(web::Data::new(PropagationService::new()))
+                .service(
+                    web::scope("/content")
+                        .wrap(JwtMiddleware::new(config.clone(), web::Data::new(TokenBlacklist::new())))
+                        .service(content::create_content),
+                )
+                .service(
+                    web::scope("/propagation")
+                        .wrap(JwtMiddleware::new(config.clone(), web::Data::new(TokenBlacklist::new())))
+                        .service(propagation::create_propagation),
+                )
+                .service(
+                    web::scope("/users")
+                        .wrap(JwtMiddleware::new(config.clone(), web::Data::new(TokenBlacklist::new())))
+                        .service(get_user_timeline),
+                ),
+        )
+        .await;
+        let timeline_uri = format!("/users/{}/timeline", user_id);
+        let token =
+            AuthService::generate_access_token(&user_id.to_string(), "0xactive", "session", Role::User, false, &config)
+                .unwrap();
+        let get_timeline = |uri: &str| {
+            TestRequest::get().uri(uri).insert_header(("Authorization", format!("Bearer {}", token))).to_request()
+        };
+
+        let created: serde_json::Value = call_and_read_body_json(
+            &app,
+            TestRequest::post()
+                .uri("/content")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(json!({
+                    "user_id": user_id,
+                    "platform": "twitter",
+                    "external_id": "tweet_timeline",
+                    "content_type": "text",
+                    "title": "Timeline",
+                    "body": "Every echo leaves a trace",
+                    "media_urls": [],
+                    "tags": []
+                }))
+                .to_request(),
+        )
+        .await;
+        let timeline: serde_json::Value = call_and_read_body_json(&app, get_timeline(&timeline_uri)).await;
+        assert_eq!(timeline["data"].as_array().unwrap().len(), 1);
+        assert_eq!(timeline["data"][0]["event_type"], "content_created");
+        assert_eq!(timeline["data"][0]["content_id"], created["data"]["id"]);
+
+        let propagate = json!({
+            "content_id": created["data"]["id"],
+            "source_user_id": user_id,
+            "propagation_type": "share",
+            "source_platform": "twitter",
+            "target_platform": "farcaster"
+        });
+        call_and_read_body_json::<_, _, serde_json::Value>(
+            &app,
+            TestRequest::post()
+                .uri("/propagation")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(&propagate)
+                .to_request(),
+        )
+        .await;
+        let timeline: serde_json::Value =
+            call_and_read_body_json(&app, get_timeline(&format!("{}?limit=1", timeline_uri))).await;
+        assert_eq!(timeline["data"][0]["event_type"], "propagation_made");
+        assert_eq!(timeline["data"][0]["target_platform"], "farcaster");
+        assert_eq!(timeline["has_more"], true);
+
+        let older: serde_json::Value = call_and_read_body_json(
+            &app,
+            get_timeline(&format!("{}?after={}", timeline_uri, timeline["next_cursor"].as_str().unwrap())),
+        )
+        .await;
+        assert_eq!(older["data"].as_array().unwrap().len(), 1);
+        assert_eq!(older["data"][0]["event_type"], "content_created");
+
+        let other = format!("/users/{}/timeline", Uuid::new_v4());
+        assert_eq!(call_service(&app, get_timeline(&other)).await.status(), 403);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_leaderboard_cursor_survives_ties(pool: PgPool) {
+        let influence = Arc::new(InfluenceRepository::new(pool.clone()));
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(UserRepository::new(pool.clone())))
+                .app_data(web::Data::new(InfluenceScoreCalculator::new(influence)))
+                .service(
+                    web::scope("/users")
+                        .service(create_user)
+                        .service(get_leaderboard)
+                        .service(get_user_influence)
+                        .service(get_user),
+                ),
+        )
+        .await;
+
+        let mut registered = Vec::new();
+        for i in 0..5 {
+            let created = call_service(
+                &app,
+                TestRequest::post()
+                    .uri("/users")
+                    .set_json(json!({ "wallet_address": format!("0x{:040x}", i), "username": format!("tied_{}", i) }))
+                    .to_request(),
+            )
+            .await;
+            assert_eq!(created.status(), 201);
+            let created: serde_json::Value = read_body_json(created).await;
+            registered.push(created["data"]["id"].as_str().unwrap().to_string());
+        }
+        sqlx::query("UPDATE users SET echo_score = 42 WHERE wallet_address <> $1")
+            .bind(format!("0x{:040x}", 4))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let duplicate = json!({ "wallet_address": format!("0x{:040x}", 0) });
+        let duplicate = TestRequest::post().uri("/users").set_json(duplicate).to_request();
+        assert_eq!(call_service(&app, duplicate).await.status(), 409);
+        // Right length, wrong EIP-55 checksum
+        let invalid = json!({ "wallet_address": "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD" });
+        let invalid = TestRequest::post().uri("/users").set_json(invalid).to_request();
+        assert_eq!(call_service(&app, invalid).await.status(), 400);
+
+        let mut seen = Vec::new();
+        let mut uri = "/users/leaderboard?limit=2".to_string();
+        loop {
+            let page: serde_json::Value = call_and_read_body_json(&app, TestRequest::get().uri(&uri).to_request()).await;
+            seen.extend(page["data"].as_array().unwrap().iter().map(|user| {
+                (user["id"].as_str().unwrap().to_string(), user["rank"].as_u64().unwrap())
+            }));
+            match page["next_cursor"].as_str() {
+                Some(cursor) => uri = format!("/users/leaderboard?limit=2&after={}", cursor),
+                None => break,
+            }
+        }
+
+        let mut tied: Vec<String> = registered[..4].to_vec();
+        tied.sort();
+        let expected: Vec<(String, u64)> = tied
+            .into_iter()
+            .map(|id| (id, 1))
+            .chain([(registered[4].clone(), 5)])
+            .collect();
+        assert_eq!(seen, expected);
+
+        let missing = TestRequest::get().uri(&format!("/users/{}", Uuid::new_v4())).to_request();
+        assert_eq!(call_service(&app, missing).await.status(), 404);
+
+        // Influence is scored on first request, and ranks the user on the leaderboard
+        let uri = format!("/users/{}/influence", registered[4]);
+        let influence: serde_json::Value =
+            call_and_read_body_json(&app, TestRequest::get().uri(&uri).to_request()).await;
+        assert_eq!(influence["data"]["composite"].as_f64(), Some(0.0));
+        assert_eq!(influence["data"]["influence_rank"].as_u64(), Some(1));
+        let page: serde_json::Value =
+            call_and_read_body_json(&app, TestRequest::get().uri("/users/leaderboard").to_request()).await;
+        let influence_ranks: Vec<Option<u64>> =
+            page["data"].as_array().unwrap().iter().map(|user| user["influence_rank"].as_u64()).collect();
+        assert_eq!(influence_ranks, vec![None, None, None, None, Some(1)]);
+        let missing = TestRequest::get().uri(&format!("/users/{}/influence", Uuid::new_v4())).to_request();
+        assert_eq!(call_service(&app, missing).await.status(), 404);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_user_content_pages_with_stats(pool: PgPool) {
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(UserRepository::new(pool.clone())))
+                .app_data(web::Data::new(ContentRepository::new(pool.clone())))
+                .service(web::scope("/users").service(get_user_content)),
+        )
+        .await;
+        let author_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xauthor') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let scored = [("tweet_a", 25.5, 3), ("tweet_b", 80.0, 1), ("tweet_c", 40.0, 7)];
+        for (external_id, echo_index, propagations) in scored {
+            sqlx::query(
+                "INSERT INTO content (user_id, platform, external_id, content_type, title, body, echo_index,
+                                      propagation_count, total_rewards)
+                 VALUES ($1, 'twitter', $2, 'text', 'Echo', 'Signal', $3, $4, 2.5)",
+            )
+            .bind(author_id)
+            .bind(external_id)
+            .bind(echo_index)
+            .bind(propagations)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let uri = format!("/users/{}/content?limit=2", author_id);
+        let first: serde_json::Value = call_and_read_body_json(&app, TestRequest::get().uri(&uri).to_request()).await;
+        let scores: Vec<f64> =
+            first["data"].as_array().unwrap().iter().map(|c| c["echo_index"].as_f64().unwrap()).collect();
+        assert_eq!(scores, vec![80.0, 40.0]);
+        assert_eq!(first["has_more"], true);
+        assert_eq!(first["total_count"], 3);
+        assert_eq!(
+            first["stats"],
+            json!({ "avg_echo_index": 48.5, "max_echo_index": 80.0, "total_propagations": 11, "total_rewards": 7.5 })
+        );
+
+        let uri = format!("/users/{}/content?limit=2&after={}", author_id, first["next_cursor"].as_str().unwrap());
+        let second: serde_json::Value = call_and_read_body_json(&app, TestRequest::get().uri(&uri).to_request()).await;
+        assert_eq!(second["data"][0]["external_id"], "tweet_a");
+        assert_eq!(second["has_more"], false);
+
+        let uri = format!("/users/{}/content?sort_by=propagation_count&order=asc", author_id);
+        let by_count: serde_json::Value =
+            call_and_read_body_json(&app, TestRequest::get().uri(&uri).to_request()).await;
+        let counts: Vec<u64> =
+            by_count["data"].as_array().unwrap().iter().map(|c| c["propagation_count"].as_u64().unwrap()).collect();
+        assert_eq!(counts, vec![1, 3, 7]);
+
+        let uri = format!("/users/{}/content?platform=farcaster", author_id);
+        let farcaster: serde_json::Value =
+            call_and_read_body_json(&app, TestRequest::get().uri(&uri).to_request()).await;
+        assert_eq!((farcaster["total_count"].as_u64(), farcaster["data"].as_array().map(Vec::len)), (Some(0), Some(0)));
+
+        // A cursor of the Echo Index order does not page the creation time order
+        let uri = format!(
+            "/users/{}/content?sort_by=created_at&after={}",
+            author_id,
+            first["next_cursor"].as_str().unwrap()
+        );
+        assert_eq!(call_service(&app, TestRequest::get().uri(&uri).to_request()).await.status(), 400);
+        let missing = TestRequest::get().uri(&format!("/users/{}/content", Uuid::new_v4())).to_request();
+        assert_eq!(call_service(&app, missing).await.status(), 404);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_api_key_lifecycle(pool: PgPool) {
+        let config = JwtConfig::new("test-secret");
+        let blacklist = web::Data::new(TokenBlacklist::new());
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(UserRepository::new(pool.clone())))
+                .app_data(web::Data::new(ApiKeyService::new(Arc::new(ApiKeyRepository::new(pool.clone())))))
+                .service(
+                    web::scope("/users")
+                        .wrap(JwtMiddleware::new(config.clone(), blacklist))
+                        .service(get_user)
+                        .service(create_api_key)
+                        .service(revoke_api_key),
+                ),
+        )
+        .await;
+
+        let owner: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xowner') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let other: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xother') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let token =
+            AuthService::generate_access_token(&owner.to_string(), "0xowner", "session", Role::User, false, &config)
+                .unwrap();
+        let bearer = ("Authorization", format!("Bearer {}", token));
+
+        let create_key = |user_id: Uuid, name: &str| {
+            TestRequest::post()
+                .uri(&format!("/users/{}/api-keys", user_id))
+                .insert_header(bearer.clone())
+                .set_json(json!({ "name": name, "permissions": ["read"], "rate_limit_per_minute": 10 }))
+                .to_request()
+        };
+        let get_owner = |key: &str| {
+            TestRequest::get()
+                .uri(&format!("/users/{}", owner))
+                .insert_header((API_KEY_HEADER, key.to_string()))
+                .to_request()
+        };
+
+        // The raw key is returned once; only its hash is stored
+        let created = call_service(&app, create_key(owner, "ci")).await;
+        assert_eq!(created.status(), 201);
+        let created: serde_json::Value = read_body_json(created).await;
+        let key = created["data"]["key"].as_str().unwrap().to_string();
+        let key_id = created["data"]["api_key"]["id"].as_str().unwrap().to_string();
+        assert_eq!(created["data"]["api_key"]["permissions"], json!(["read"]));
+        assert!(created["data"]["api_key"].get("key_hash").is_none());
+        let stored: String = sqlx::query_scalar("SELECT key_hash FROM api_keys WHERE id = $1::uuid")
+            .bind(&key_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_ne!(stored, key);
+
+        assert_eq!(call_service(&app, create_key(other, "theirs")).await.status(), 403);
+
+        // Requests with the key act as its owner
+        let response = call_service(&app, get_owner(&key)).await;
+        assert_eq!(response.status(), 200);
+        let last_used: Option<DateTime<Utc>> =
+            sqlx::query_scalar("SELECT last_used_at FROM api_keys WHERE id = $1::uuid")
+                .bind(&key_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(last_used.is_some());
+        assert_eq!(call_service(&app, get_owner("elk_unknown")).await.status(), 401);
+
+        // Expired keys are rejected
+        sqlx::query("UPDATE api_keys SET expires_at = NOW() - INTERVAL '1 second' WHERE id = $1::uuid")
+            .bind(&key_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let expired: serde_json::Value = read_body_json(call_service(&app, get_owner(&key)).await).await;
+        assert_eq!(expired["message"], "API key has expired");
+
+        // So are revoked ones, and a key cannot be revoked twice
+        let revoked: serde_json::Value = read_body_json(call_service(&app, create_key(owner, "deploy")).await).await;
+        let revoked_key = revoked["data"]["key"].as_str().unwrap().to_string();
+        let revoke_uri = format!("/users/{}/api-keys/{}", owner, revoked["data"]["api_key"]["id"].as_str().unwrap());
+        assert_eq!(call_service(&app, get_owner(&revoked_key)).await.status(), 200);
+        let revoke = || TestRequest::delete().uri(&revoke_uri).insert_header(bearer.clone()).to_request();
+        assert_eq!(call_service(&app, revoke()).await.status(), 204);
+        assert_eq!(call_service(&app, get_owner(&revoked_key)).await.status(), 401);
+        assert_eq!(call_service(&app, revoke()).await.status(), 404);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_webhooks_must_call_public_hosts(pool: PgPool) {
+        let config = JwtConfig::new("test-secret");
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xhooked') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let app = init_service(
+            App::new().app_data(web::Data::new(WebhookRepository::new(pool.clone()))).service(
+                web::scope("/users")
+                    .wrap(JwtMiddleware::new(config.clone(), web::Data::new(TokenBlacklist::new())))
+                    .service(create_webhook),
+            ),
+        )
+        .await;
+        let token =
+            AuthService::generate_access_token(&user_id.to_string(), "0xhooked", "session", Role::User, false, &config)
+                .unwrap();
+        let register = |url: &str| {
+            TestRequest::post()
+                .uri(&format!("/users/{}/webhooks", user_id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(json!({ "url": url, "events": ["echo_index_calculated"] }))
+                .to_request()
+        };
+
+        for url in [
+            "http://93.184.216.34/hook",
+            "https://127.0.0.1:8080/hook",
+            "https://localhost/hook",
+            "https://169.254.169.254/latest/meta-data",
+            "https://[::ffff:10.0.0.1]/hook",
+        ] {
+            assert_eq!(call_service(&app, register(url)).await.status(), 400, "{}", url);
+        }
+        assert_eq!(call_service(&app, register("https://93.184.216.34/hook")).await.status(), 201);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_alert_webhooks_must_call_public_hosts(pool: PgPool) {
+        let config = JwtConfig::new("test-secret");
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xalerted') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let alerts = VelocityAlertService::new(Arc::new(AlertRepository::new(pool.clone())), chrono::Duration::hours(1));
+        let app = init_service(
+            App::new().app_data(web::Data::new(alerts)).service(
+                web::scope("/users")
+                    .wrap(JwtMiddleware::new(config.clone(), web::Data::new(TokenBlacklist::new())))
+                    .service(configure_alert),
+            ),
+        )
+        .await;
+        let token =
+            AuthService::generate_access_token(&user_id.to_string(), "0xalerted", "session", Role::User, false, &config)
+                .unwrap();
+        let configure = |url: &str| {
+            TestRequest::post()
+                .uri(&format!("/users/{}/alerts", user_id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(json!({
+                    "threshold": { "type": "propagations_per_hour", "value": 10.0 },
+                    "webhook_url": url
+                }))
+                .to_request()
+        };
+
+        for url in [
+            "http://93.184.216.34/alert",
+            "https://10.0.0.5/alert",
+            "https://alerts.internal/alert",
+            "https://169.254.169.254/latest/meta-data",
+            "https://[::1]/alert",
+        ] {
+            assert_eq!(call_service(&app, configure(url)).await.status(), 400, "{}", url);
+        }
+        assert_eq!(call_service(&app, configure("https://93.184.216.34/alert")).await.status(), 201);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_only_the_user_updates_their_profile(pool: PgPool) {
+        let config = JwtConfig::new("test-secret");
+        let app = init_service(
+            App::new().app_data(web::Data::new(UserRepository::new(pool.clone()))).service(
+                web::scope("/users")
+                    .wrap(JwtMiddleware::new(config.clone(), web::Data::new(TokenBlacklist::new())))
+                    .service(update_user),
+            ),
+        )
+        .await;
+
+        let owner: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xeditor') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let other: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xedited') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let token =
+            AuthService::generate_access_token(&owner.to_string(), "0xeditor", "session", Role::User, false, &config)
+                .unwrap();
+        let update = |user_id: Uuid| {
+            TestRequest::put()
+                .uri(&format!("/users/{}", user_id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(json!({ "display_name": "Echo" }))
+                .to_request()
+        };
+
+        assert_eq!(call_service(&app, update(other)).await.status(), 403);
+        let body: serde_json::Value = call_and_read_body_json(&app, update(owner)).await;
+        assert_eq!(body["data"]["display_name"], "Echo");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_only_the_user_spends_their_streak_freeze_tokens(pool: PgPool) {
+        let config = JwtConfig::new("test-secret");
+        let streaks = StreakService::new(Arc::new(StreakRepository::new(pool.clone())));
+        let owner: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xfreezer') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let intruder: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xthief') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let today = chrono::Utc::now().date_naive();
+        for days_ago in (1..=7).rev() {
+            streaks.record_activity(owner, today - chrono::Days::new(days_ago)).await.unwrap();
+        }
+        let app = init_service(
+            App::new().app_data(web::Data::new(streaks)).service(
+                web::scope("/users")
+                    .wrap(JwtMiddleware::new(config.clone(), web::Data::new(TokenBlacklist::new())))
+                    .service(freeze_user_streak),
+            ),
+        )
+        .await;
+        let freeze = |caller: Uuid| {
+            let token =
+                AuthService::generate_access_token(&caller.to_string(), "0xcaller", "session", Role::User, false, &config)
+                    .unwrap();
+            TestRequest::post()
+                .uri(&format!("/users/{}/streak/freeze", owner))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request()
+        };
+
+        assert_eq!(call_service(&app, freeze(intruder)).await.status(), 403);
+        let body: serde_json::Value = call_and_read_body_json(&app, freeze(owner)).await;
+        assert_eq!(body["data"]["streak"]["streak_freeze_tokens"], 0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_data_export_download_and_account_deletion(pool: PgPool) {
+        let config = JwtConfig::new("test-secret");
+        let blacklist = web::Data::new(TokenBlacklist::new());
+        let users = Arc::new(UserRepository::new(pool.clone()));
+        let user_data = UserDataService::new(
+            users.clone(),
+            Arc::new(ContentRepository::new(pool.clone())),
+            Arc::new(UserEventRepository::new(pool.clone())),
+            Arc::new(StreakRepository::new(pool.clone())),
+            Arc::new(RwLock::new(RewardService::new(10_000.0))),
+        );
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::from(users))
+                .app_data(web::Data::new(user_data))
+                .service(
+                    web::scope("/users")
+                        .wrap(JwtMiddleware::new(config.clone(), blacklist))
+                        .service(get_user)
+                        .service(delete_user)
+                        .service(export_user_data),
+                ),
+        )
+        .await;
+
+        let owner: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xexporter') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let other: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xbystander') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let token =
+            AuthService::generate_access_token(&owner.to_string(), "0xexporter", "session", Role::User, false, &config)
+                .unwrap();
+        let bearer = ("Authorization", format!("Bearer {}", token));
+        let export = |user_id: Uuid| {
+            TestRequest::get()
+                .uri(&format!("/users/{}/export", user_id))
+                .insert_header(bearer.clone())
+                .to_request()
+        };
+
+        let response = call_service(&app, export(owner)).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers().get("content-disposition").unwrap(),
+            &format!("attachment; filename=\"echolayer-export-{}.zip\"", owner)
+        );
+        let archive = actix_web::test::read_body(response).await;
+        assert_eq!(&archive[..4], b"PK\x03\x04");
+
+        assert_eq!(call_service(&app, export(owner)).await.status(), 429);
+        assert_eq!(call_service(&app, export(other)).await.status(), 403);
+
+        let delete = |user_id: Uuid| {
+            TestRequest::delete()
+                .uri(&format!("/users/{}", user_id))
+                .insert_header(bearer.clone())
+                .to_request()
+        };
+        assert_eq!(call_service(&app, delete(other)).await.status(), 403);
+        assert_eq!(call_service(&app, delete(owner)).await.status(), 204);
+        assert_eq!(call_service(&app, delete(owner)).await.status(), 404);
+
+        let profile: serde_json::Value = call_and_read_body_json(
+            &app,
+            TestRequest::get()
+                .uri(&format!("/users/{}", owner))
+                .insert_header(bearer.clone())
+                .to_request(),
+        )
+        .await;
+        assert_ne!(profile["data"]["wallet_address"], "0xexporter");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_content_import_from_uploaded_export(pool: PgPool) {
+        let config = JwtConfig::new("test-secret");
+        let imports = ContentImportService::new(
+            Arc::new(ContentRepository::new(pool.clone())),
+            Arc::new(EngineConfigStore::new(EchoEngineConfig::default(), "missing.toml")),
+        );
+        let app = init_service(
+            App::new().app_data(web::Data::new(imports)).service(
+                web::scope("/users")
+                    .wrap(JwtMiddleware::new(config.clone(), web::Data::new(TokenBlacklist::new())))
+                    .service(import_content),
+            ),
+        )
+        .await;
+
+        let owner: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0ximporter') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let token =
+            AuthService::generate_access_token(&owner.to_string(), "0ximporter", "session", Role::User, false, &config)
+                .unwrap();
+        let import = |fields: &[(&str, &str)]| {
+            let mut body = String::new();
+            for (name, value) in fields {
+                body.push_str(&format!(
+                    "--boundary\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                    name, value
+                ));
+            }
+            body.push_str("--boundary--\r\n");
+            TestRequest::post()
+                .uri(&format!("/users/{}/content/import", owner))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .insert_header(("Content-Type", "multipart/form-data; boundary=boundary"))
+                .set_payload(body)
+                .to_request()
+        };
+        let tweets = r#"window.YTD.tweets.part0 = [
+            {"tweet": {"id_str": "2001", "full_text": "Building reputation that travels with you",
+                       "created_at": "Wed Oct 10 20:19:24 +0000 2018", "retweet_count": "3", "favorite_count": "9"}},
+            {"tweet": {"id_str": "2002", "full_text": "No date"}}
+        ]"#;
+
+        // Rejected requests do not use up the day's import
+        let csv = "external_id,text,created_at\na,An essay,2024-03-01\n";
+        let response = call_service(&app, import(&[("format", "generic_csv"), ("file", csv)])).await;
+        assert_eq!(response.status(), 400);
+        let response = call_service(&app, import(&[("format", "myspace_backup"), ("file", csv)])).await;
+        assert_eq!(response.status(), 400);
+
+        let body: serde_json::Value =
+            call_and_read_body_json(&app, import(&[("format", "twitter_archive"), ("file", tweets)])).await;
+        assert_eq!(body["data"], json!({"imported": 1, "skipped_duplicates": 0, "failed": 1}));
+        let response = call_service(&app, import(&[("format", "twitter_archive"), ("file", tweets)])).await;
+        assert_eq!(response.status(), 429);
+    }
+
+    #[actix_web::test]
+    async fn test_claimable_rewards_are_private_to_the_user() {
+        let config = JwtConfig::new("test-secret");
+        let rewards = web::Data::new(RwLock::new(RewardService::new(10_000.0)));
+        let owner = Uuid::new_v4();
+        rewards
+            .write()
+            .await
+            .award_community_contribution(owner.to_string(), "content_1".into(), 2.5, 0.1)
+            .await
+            .unwrap();
+        let app = init_service(
+            App::new().app_data(rewards).service(
+                web::scope("/users")
+                    .wrap(JwtMiddleware::new(config.clone(), web::Data::new(TokenBlacklist::new())))
+                    .service(get_claimable_rewards),
+            ),
+        )
+        .await;
+        let claimable = |caller: Uuid, role: Role, totp_verified: bool| {
+            let token = AuthService::generate_access_token(
+                &caller.to_string(),
+                "0xcaller",
+                "session",
+                role,
+                totp_verified,
+                &config,
+            )
+            .unwrap();
+            TestRequest::get()
+                .uri(&format!("/users/{}/rewards/claimable", owner))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request()
+        };
+
+        assert_eq!(call_service(&app, claimable(Uuid::new_v4(), Role::User, true)).await.status(), 403);
+        let body: serde_json::Value = call_and_read_body_json(&app, claimable(owner, Role::User, false)).await;
+        assert!(body["data"]["pending"].as_f64().unwrap() > 0.0);
+        // Admins act for other users only when signed in with a second factor, which
+        // admin API keys never are
+        assert_eq!(call_service(&app, claimable(Uuid::new_v4(), Role::Admin, false)).await.status(), 403);
+        let body: serde_json::Value =
+            call_and_read_body_json(&app, claimable(Uuid::new_v4(), Role::Admin, true)).await;
+        assert_eq!(body["data"]["user_id"], json!(owner));
+    }
+
+    #[actix_web::test]
+    async fn test_batch_claim_proves_the_requested_rewards() {
+        let config = JwtConfig::new("test-secret");
+        let rewards = web::Data::new(RwLock::new(RewardService::new(10_000.0)));
+        let owner = Uuid::new_v4();
+        let mut reward_ids = Vec::new();
+        {
+            let mut rewards = rewards.write().await;
+            for content_id in ["content_1", "content_2", "content_3"] {
+                let reward_id =
+                    rewards.award_community_contribution(owner.to_string(), content_id.into(), 2.5, 0.1).await.unwrap();
+                reward_ids.push(reward_id);
+            }
+            rewards.freeze_content_rewards("content_3");
+        }
+        let app = init_service(
+            App::new().app_data(rewards.clone()).service(
+                web::scope("/users")
+                    .wrap(JwtMiddleware::new(config.clone(), web::Data::new(TokenBlacklist::new())))
+                    .service(claim_rewards_batch),
+            ),
+        )
+        .await;
+        let token =
+            AuthService::generate_access_token(&owner.to_string(), "0xclaimer", "session", Role::User, false, &config)
+                .unwrap();
+        let claim = |reward_ids: &[&String]| {
+            let reward_ids: Vec<&str> = reward_ids.iter().map(|id| id.trim_start_matches("reward_")).collect();
+            TestRequest::post()
+                .uri(&format!("/users/{}/rewards/claim-batch", owner))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(json!({ "reward_ids": reward_ids }))
+                .to_request()
+        };
+
+        // Leaves follow the order the rewards were requested in
+        let body: serde_json::Value = call_and_read_body_json(&app, claim(&[&reward_ids[1], &reward_ids[0]])).await;
+        let user_rewards = rewards.read().await.get_user_rewards(&owner.to_string());
+        let requested: Vec<EchoDropReward> = [1, 0]
+            .iter()
+            .map(|i| user_rewards.iter().find(|reward| reward.id == reward_ids[*i]).unwrap().clone())
+            .collect();
+        assert_eq!(body["data"], serde_json::to_value(MerkleRewardClaim::new(&requested).unwrap()).unwrap());
+        assert_eq!(body["data"]["proofs"][0]["reward_id"], json!(reward_ids[1]));
+        assert_eq!(body["data"]["proofs"][1]["leaf_index"], 1);
+
+        let frozen = call_service(&app, claim(&[&reward_ids[0], &reward_ids[2]])).await;
+        assert_eq!(frozen.status(), 409);
+        let repeated = call_service(&app, claim(&[&reward_ids[0], &reward_ids[0]])).await;
+        assert_eq!(repeated.status(), 400);
+        let unknown = call_service(&app, claim(&[&format_reward_id(Uuid::new_v4())])).await;
+        assert_eq!(unknown.status(), 404);
+        assert_eq!(call_service(&app, claim(&[])).await.status(), 400);
+    }
+}
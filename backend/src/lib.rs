@@ -0,0 +1,10 @@
+//! The backend's modules as a library, shared by the server in main.rs, the benches and
+//! the fuzz targets in `fuzz/`, which build as a separate crate
+
+pub mod handlers;
+pub mod middleware;
+pub mod models;
+pub mod repositories;
+pub mod services;
+pub mod shutdown;
+pub mod telemetry;
@@ -1,19 +1,59 @@
 use actix_cors::Cors;
 use actix_web::{web, App, HttpServer, middleware::Logger};
 use log::info;
+use sqlx::postgres::PgPoolOptions;
 use std::env;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
 
-mod handlers;
-mod models;
-mod services;
-mod utils;
+use echolayer_backend::{handlers, middleware, models, repositories, services, shutdown, telemetry};
 
-use handlers::{health, auth, echo_index, content, users, propagation};
+use handlers::{health, auth, admin, echo_index, content, hashtags, platforms, users, propagation};
+use handlers::auth::JwtConfig;
+use handlers::openapi::{ApiDoc, OPENAPI_PATH};
+use middleware::{
+    CacheConfig, CacheMiddleware, JwtMiddleware, RateLimit, RateLimitConfig, RateLimiter, RequestIdMiddleware,
+    RequestMetrics, RequestTracing, RequireRole, ResponseCache, REQUEST_ID_HEADER, SHARE_LINK_PARAMS,
+};
+use models::echo_index::EchoIndexCalculator;
+use models::user::Role;
+use repositories::{
+    AlertRepository, ApiKeyRepository, ContentAttributionRepository, ContentFingerprintRepository,
+    ContentRepository, ContentTfIdfRepository, ContentVersionRepository, EchoAnomalyRepository,
+    EchoIndexHistoryRepository, ExperimentRepository, FeedRepository, HashtagRepository, InfluenceRepository,
+    MentionRepository, ModerationRepository, OAuthStateRepository, PlatformStatsRepository, PropagationRepository,
+    QualityBonusRepository, RefreshTokenRepository, RewardAnalyticsRepository, RewardCheckpointRepository,
+    RewardPoolRepository, SessionRepository, StreakRepository, TrendingRepository, TwoFactorRepository,
+    UserEventRepository, UserRelationshipRepository, UserRepository, WebhookRepository,
+};
+use services::{
+    AlertWebhookDispatcher, ApiKeyService, BatchJobs, ChallengeStore, ColdStartService, ContentArchiver,
+    ContentAttributionService, ContentClusterAnalyzer, ContentFingerprintService, ContentImportService,
+    ContentModerationService, ContentSimilarityService, ContentVersioningService, DbDispatcher, DecayScheduler,
+    DiscoveryFeedService, EchoIndexAnomalyDetector, EchoIndexCache, EchoIndexPercentileCache, EchoIndexUpdates,
+    EngineConfigStore, FarcasterIndexer, HashtagTrendService, IdempotencyCache, InfluenceScoreCalculator,
+    LeaderboardCache, LeaderboardService, LogDispatcher, MentionLinker, MetricsRegistry, MpcWalletVerifier,
+    PlatformStatsService, PoolUtilizationGovernor, PrivyMpcVerifier, PropagationEventDeduplicator, PropagationService,
+    QualityBonusScheduler, RecalculationContext, RecalculationQueue, RewardAnalyticsService, RewardForecastService,
+    RewardService, SecretCipher, SocialAccountVerifier, SolanaBlockchainClient, StreakService, TierConfig,
+    TokenBlacklist, TokenVestingService, TrendingScoreService, TwoFactorService, UserDataService, UserFollowerGraph,
+    UserTierProgressionService, VelocityAlertService, WebhookDispatcher,
+};
+use services::recalculation_queue::RECALCULATION_QUEUE_CAPACITY;
+use services::leaderboard::LEADERBOARD_SIZE;
+use services::rewards::DEFAULT_POOL_EMERGENCY_RESERVE;
+use services::velocity_alerts::DEFAULT_ALERT_COOLDOWN_HOURS;
+use services::webhooks::DEFAULT_FIRST_RETRY_DELAY_SECS;
+use services::propagation::{
+    DEFAULT_DECAY_FACTOR, DEFAULT_MAX_AMPLIFICATION_FACTOR, DEFAULT_MAX_LOOP_DEPTH, DEFAULT_RESONANCE_THRESHOLD,
+};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    // Initialize logger
-    env_logger::init();
+    // Logging, trace export and error reporting
+    let telemetry = telemetry::init().expect("Failed to initialize telemetry");
 
     // Get server configuration from environment
     let host = env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
@@ -24,71 +64,765 @@ async fn main() -> std::io::Result<()> {
 
     info!("Starting EchoLayer Backend Server at {}:{}", host, port);
 
+    // Database connection pool
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let db_pool = PgPoolOptions::new()
+        .max_connections(10)
+        .connect(&database_url)
+        .await
+        .expect("Failed to connect to the database");
+    repositories::MIGRATOR
+        .run(&db_pool)
+        .await
+        .expect("Failed to run database migrations");
+    let content_repository = web::Data::new(ContentRepository::new(db_pool.clone()));
+    let content_fingerprints = web::Data::new(ContentFingerprintRepository::new(db_pool.clone()));
+    let fingerprint_service = web::Data::new(ContentFingerprintService::new());
+    let content_tfidf = Arc::new(ContentTfIdfRepository::new(db_pool.clone()));
+    let content_similarity = web::Data::new(ContentSimilarityService::new(content_tfidf.clone()));
+    // Topic clusters of recent content, recomputed at most hourly
+    let content_clusters = web::Data::new(ContentClusterAnalyzer::new(content_tfidf));
+    // The same content posted on several platforms, crediting the original with its copies
+    let content_attributions =
+        web::Data::new(ContentAttributionService::new(Arc::new(ContentAttributionRepository::new(db_pool.clone()))));
+    let discovery_feeds = web::Data::new(DiscoveryFeedService::from_env(
+        Arc::new(FeedRepository::new(db_pool.clone())),
+        content_similarity.clone().into_inner(),
+    ));
+    let echo_index_history = web::Data::new(EchoIndexHistoryRepository::new(db_pool.clone()));
+    let experiments = web::Data::new(ExperimentRepository::new(db_pool.clone()));
+    let users = web::Data::new(UserRepository::new(db_pool.clone()));
+    let follower_graph = web::Data::new(UserFollowerGraph::new(Arc::new(UserRelationshipRepository::new(
+        db_pool.clone(),
+    ))));
+    let refresh_tokens = web::Data::new(RefreshTokenRepository::new(db_pool.clone()));
+    let sessions = web::Data::new(SessionRepository::new(db_pool.clone()));
+    let user_events = web::Data::new(UserEventRepository::new(db_pool.clone()));
+    let streak_repository = Arc::new(StreakRepository::new(db_pool.clone()));
+    let streaks = web::Data::new(StreakService::new(streak_repository.clone()));
+
+    // Prometheus metrics, scraped from /metrics
+    let metrics = web::Data::new(MetricsRegistry::new());
+
+    // Request budgets: strict for authentication, generous for the rest of the API
+    let auth_rate_limiter = Arc::new(RateLimiter::new(RateLimitConfig::from_env("RATE_LIMIT", 10, 60)));
+    let api_rate_limiter = Arc::new(RateLimiter::new(RateLimitConfig::from_env("API_RATE_LIMIT", 300, 60)));
+    // Responses of read-heavy routes, shared by every worker; user-specific ones are never stored
+    let response_cache = Arc::new(ResponseCache::new(
+        CacheConfig::from_env()
+            .cache("/api/v1/users/leaderboard", 60)
+            .cache("/api/v1/content/trending", 30)
+            .cache_excluding("/api/v1/content/{content_id}", 30, SHARE_LINK_PARAMS)
+            .cache("/api/v1/hashtags/trending", 60)
+            .cache("/api/v1/analytics/platforms", 300)
+            .cache("/api/v1/analytics/platforms/{platform}/top-content", 300)
+            .no_store("/api/v1/users/{user_id}/feed")
+            .no_store("/api/v1/users/{user_id}/rewards/claimable")
+            .no_store("/api/v1/users/{user_id}/2fa/recovery-codes"),
+    ));
+    // Periodic tasks, cancelled once the server has shut down
+    let mut background_tasks = vec![
+        auth_rate_limiter.clone().spawn_eviction_task(Duration::from_secs(60)),
+        api_rate_limiter.clone().spawn_eviction_task(Duration::from_secs(60)),
+    ];
+
+    // Access token signing configuration
+    let jwt_config = JwtConfig::from_env();
+
+    // Revoked access tokens, shared across workers
+    let token_blacklist = web::Data::new(TokenBlacklist::new());
+    background_tasks.push(token_blacklist.clone().into_inner().spawn_eviction_task(Duration::from_secs(60)));
+
+    // API keys for machine clients, accepted by the JWT middleware
+    let api_keys = web::Data::new(ApiKeyService::new(Arc::new(ApiKeyRepository::new(db_pool.clone()))));
+    background_tasks.push(api_keys.clone().into_inner().spawn_eviction_task(Duration::from_secs(60)));
+
+    // Outstanding wallet login challenges
+    let challenge_store = web::Data::new(ChallengeStore::new());
+    background_tasks.push(challenge_store.clone().into_inner().spawn_eviction_task(Duration::from_secs(60)));
+
+    // MPC wallet signatures, verified by Privy
+    let mpc_verifier: web::Data<dyn MpcWalletVerifier> =
+        web::Data::from(Arc::new(PrivyMpcVerifier::from_env()) as Arc<dyn MpcWalletVerifier>);
+
+    // TOTP second factor, required at sign-in once enabled and by admin endpoints
+    let two_factor = web::Data::new(TwoFactorService::new(
+        users.clone().into_inner(),
+        Arc::new(TwoFactorRepository::new(db_pool.clone())),
+        SecretCipher::from_env(),
+    ));
+
+    // Echo Index calculator, reconfigurable at runtime
+    let echo_index_calculator = web::Data::new(RwLock::new(EchoIndexCalculator::default()));
+
+    // Echo engine tuning, reloaded when its config file changes and adjustable through the admin endpoints
+    let echo_engine_config = web::Data::new(EngineConfigStore::from_env());
+    background_tasks.push(echo_engine_config.clone().into_inner().spawn_watch_task());
+
+    // Echo Index calculations reused until their content is propagated again
+    let echo_index_cache = web::Data::new(EchoIndexCache::new());
+
+    // Live Echo Index subscribers
+    let echo_index_updates = web::Data::new(EchoIndexUpdates::new());
+
+    // Live leaderboard, refreshed by every Echo Index update
+    let leaderboard = web::Data::new(LeaderboardCache::new(LEADERBOARD_SIZE));
+    background_tasks.push(leaderboard.clone().into_inner().spawn_update_task(echo_index_updates.clone().into_inner()));
+
+    // Every scored content item in score order, for percentile ranks and tier cutoffs
+    let echo_index_percentiles = web::Data::new(EchoIndexPercentileCache::new());
+    match echo_index_percentiles.load(&content_repository).await {
+        Ok(ranked) => log::info!("Ranked {} scored content items", ranked),
+        Err(e) => log::warn!("Failed to load Echo Index scores for percentiles: {}", e),
+    }
+    background_tasks.push(
+        echo_index_percentiles
+            .clone()
+            .into_inner()
+            .spawn_update_task(echo_index_updates.clone().into_inner()),
+    );
+
+    // Leaderboards per time range, ranked by peak Echo Index
+    let leaderboards = web::Data::new(LeaderboardService::new(echo_index_history.clone().into_inner()));
+
+    // Signed event deliveries to the webhooks of users' integrations
+    let webhook_first_retry_delay_secs = env::var("WEBHOOK_FIRST_RETRY_DELAY_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_FIRST_RETRY_DELAY_SECS);
+    let webhook_repository = web::Data::new(WebhookRepository::new(db_pool.clone()));
+    let webhook_dispatcher = web::Data::new(
+        WebhookDispatcher::new(webhook_repository.clone().into_inner())
+            .with_first_retry_delay(Duration::from_secs(webhook_first_retry_delay_secs)),
+    );
+
+    // Tiers users earn with their Echo Score and rewards, and the propagation fees they pay
+    let tier_progression = web::Data::new(
+        UserTierProgressionService::new(
+            users.clone().into_inner(),
+            user_events.clone().into_inner(),
+            TierConfig::from_env(),
+        )
+        .with_webhooks(webhook_dispatcher.clone().into_inner()),
+    );
+
+    // EchoDrop rewards, vesting against the daily pool
+    let daily_reward_pool = env::var("DAILY_REWARD_POOL")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(10_000.0);
+    let pool_emergency_reserve = env::var("POOL_EMERGENCY_RESERVE_PCT")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .map(|pct| pct / 100.0)
+        .unwrap_or(DEFAULT_POOL_EMERGENCY_RESERVE);
+    // Rewards still pending when the previous run shut down
+    let reward_checkpoints = RewardCheckpointRepository::new(db_pool.clone());
+    let restored_rewards = reward_checkpoints.load().await.unwrap_or_else(|e| {
+        log::warn!("Failed to load checkpointed rewards: {}", e);
+        Vec::new()
+    });
+    info!("Restored {} pending rewards", restored_rewards.len());
+    let reward_service = web::Data::new(tokio::sync::RwLock::new(
+        RewardService::new(daily_reward_pool)
+            .with_emergency_reserve(pool_emergency_reserve)
+            .with_event_repository(user_events.clone().into_inner())
+            .with_streak_service(streaks.clone().into_inner())
+            .with_webhooks(webhook_dispatcher.clone().into_inner())
+            .with_tier_progression(tier_progression.clone().into_inner())
+            .with_restored_rewards(restored_rewards),
+    ));
+
+    // Payouts of vested rewards from the on-chain reward pool, only logged unless
+    // SOLANA_SUBMIT_TRANSACTIONS enables submitting them
+    let blockchain_client = SolanaBlockchainClient::from_env().unwrap_or_else(|e| {
+        log::error!("Invalid Solana configuration, reward transfers will only be logged: {}", e);
+        SolanaBlockchainClient::logging_only()
+    });
+    let token_vesting = web::Data::new(TokenVestingService::new(
+        users.clone().into_inner(),
+        reward_service.clone().into_inner(),
+        Arc::new(blockchain_client),
+    ));
+
+    // Retroactive quality bonuses for high-echo content
+    let quality_review_hours = env::var("QUALITY_REVIEW_INTERVAL_HOURS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(6);
+    let quality_bonuses = web::Data::new(QualityBonusScheduler::new(
+        Arc::new(QualityBonusRepository::new(db_pool.clone())),
+        reward_service.clone().into_inner(),
+    ));
+    background_tasks.push(
+        quality_bonuses
+            .clone()
+            .into_inner()
+            .spawn_review_task(Duration::from_secs(quality_review_hours * 3600)),
+    );
+
+    // Daily pool resized at midnight UTC from how much of it the past week used
+    let pool_governor = web::Data::new(PoolUtilizationGovernor::from_env(
+        Arc::new(RewardPoolRepository::new(db_pool.clone())),
+        reward_service.clone().into_inner(),
+    ));
+    background_tasks.push(pool_governor.clone().into_inner().spawn_daily_task());
+
+    // Reward distribution over time, for dashboards
+    let reward_analytics =
+        web::Data::new(RewardAnalyticsService::new(Arc::new(RewardAnalyticsRepository::new(db_pool.clone()))));
+
+    // Content flagged by users, reviewed by operators
+    let moderation = web::Data::new(
+        ContentModerationService::new(Arc::new(ModerationRepository::new(db_pool.clone())))
+            .with_reward_service(reward_service.clone().into_inner())
+            .with_webhooks(webhook_dispatcher.clone().into_inner()),
+    );
+
+    // Echo Index jumps that look manipulated, holding the content's rewards until reviewed
+    let echo_anomalies = web::Data::new(
+        EchoIndexAnomalyDetector::new(
+            Arc::new(EchoAnomalyRepository::new(db_pool.clone())),
+            echo_index_history.clone().into_inner(),
+        )
+        .with_reward_service(reward_service.clone().into_inner()),
+    );
+
+    // Echo Index statistics of every platform, refreshed hourly
+    let platform_stats = web::Data::new(PlatformStatsService::new(Arc::new(PlatformStatsRepository::new(
+        db_pool.clone(),
+    ))));
+    background_tasks.push(platform_stats.clone().into_inner().spawn_refresh_task());
+
+    // Short-term virality of content, rescored hourly from recent Echo Index history
+    let trending_scores = web::Data::new(TrendingScoreService::new(Arc::new(TrendingRepository::new(
+        db_pool.clone(),
+    ))));
+    background_tasks.push(
+        trending_scores
+            .clone()
+            .into_inner()
+            .spawn_refresh_task(Duration::from_secs(3600)),
+    );
+
+    // Scores the first content of new authors against the average Echo Index of their
+    // platform
+    let cold_start = web::Data::new(ColdStartService::new(platform_stats.clone().into_inner()));
+
+    // Previous versions of edited content; edits followed by a sharp Echo Index drop are
+    // flagged every five minutes
+    let content_versioning = web::Data::new(ContentVersioningService::new(
+        Arc::new(ContentVersionRepository::new(db_pool.clone())),
+        content_repository.clone().into_inner(),
+    ));
+    background_tasks.push(
+        content_versioning
+            .clone()
+            .into_inner()
+            .spawn_review_task(Duration::from_secs(300)),
+    );
+
+    // Personal data exports and account deletion
+    let user_data = web::Data::new(UserDataService::new(
+        users.clone().into_inner(),
+        content_repository.clone().into_inner(),
+        user_events.clone().into_inner(),
+        streak_repository,
+        reward_service.clone().into_inner(),
+    ));
+
+    // Imports of content users published before joining, scored by quality alone
+    let content_imports = web::Data::new(ContentImportService::new(
+        content_repository.clone().into_inner(),
+        echo_engine_config.clone().into_inner(),
+    ));
+
+    // EchoDrop earnings forecasts for content still gathering propagations
+    let reward_forecasts = web::Data::new(RewardForecastService::new(
+        echo_index_history.clone().into_inner(),
+        content_repository.clone().into_inner(),
+        reward_service.clone().into_inner(),
+    ));
+
+    // Trending hashtags, refreshed every five minutes; content riding them earns a TPM bonus
+    let hashtag_repository = Arc::new(HashtagRepository::new(db_pool.clone()));
+    let hashtag_trends = web::Data::new(HashtagTrendService::from_env(hashtag_repository));
+    background_tasks.push(
+        hashtag_trends
+            .clone()
+            .into_inner()
+            .spawn_refresh_task(Duration::from_secs(300)),
+    );
+
+    // OAuth proof of social account ownership, for providers with credentials configured
+    let social_verifier = web::Data::new(SocialAccountVerifier::from_env(
+        Arc::new(OAuthStateRepository::new(db_pool.clone())),
+        users.clone().into_inner(),
+    ));
+
+    // Propagation velocity alerts, checked every five minutes
+    let alert_cooldown_hours = env::var("VELOCITY_ALERT_COOLDOWN_HOURS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_ALERT_COOLDOWN_HOURS);
+    let alert_repository = Arc::new(AlertRepository::new(db_pool.clone()));
+    let velocity_alerts = web::Data::new(
+        VelocityAlertService::new(alert_repository.clone(), chrono::Duration::hours(alert_cooldown_hours))
+            .with_dispatcher(Arc::new(LogDispatcher))
+            .with_dispatcher(Arc::new(DbDispatcher::new(alert_repository)))
+            .with_dispatcher(Arc::new(AlertWebhookDispatcher::new())),
+    );
+    background_tasks.push(
+        velocity_alerts
+            .clone()
+            .into_inner()
+            .spawn_evaluation_task(Duration::from_secs(300)),
+    );
+
+    // Bulk recalculation jobs; tasks run on this runtime so they outlive the workers
+    let batch_jobs = web::Data::new(BatchJobs::from_env());
+    background_tasks.push(batch_jobs.clone().into_inner().spawn_eviction_task(Duration::from_secs(3600)));
+
+    // Echo Loop tracking, persisted with a short-lived in-memory cache
+    let propagation_max_depth = env::var("PROPAGATION_MAX_DEPTH")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_LOOP_DEPTH);
+    let propagation_resonance_threshold = env::var("PROPAGATION_RESONANCE_THRESHOLD")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(DEFAULT_RESONANCE_THRESHOLD);
+    let propagation_decay_factor = env::var("PROPAGATION_DECAY_FACTOR")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|factor| *factor > 0.0 && *factor <= 1.0)
+        .unwrap_or(DEFAULT_DECAY_FACTOR);
+    let propagation_max_amplification_factor = env::var("PROPAGATION_MAX_AMPLIFICATION_FACTOR")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|factor| *factor >= 1.0)
+        .unwrap_or(DEFAULT_MAX_AMPLIFICATION_FACTOR);
+    let propagation_repository = Arc::new(PropagationRepository::new(db_pool.clone()));
+    let propagation_service = web::Data::new(
+        PropagationService::with_repository(propagation_repository.clone())
+            .configure(propagation_max_depth, propagation_resonance_threshold, propagation_decay_factor)
+            .with_max_amplification_factor(propagation_max_amplification_factor)
+            .with_engine_config(echo_engine_config.clone().into_inner())
+            .with_follower_graph(follower_graph.clone().into_inner()),
+    );
+    let propagation_deduplicator =
+        web::Data::new(PropagationEventDeduplicator::from_env(propagation_repository.clone()));
+    let propagation_repository = web::Data::from(propagation_repository);
+    {
+        let propagation_service = propagation_service.clone();
+        background_tasks.push(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(600));
+            loop {
+                interval.tick().await;
+                match propagation_service.cleanup_expired_loops(72).await {
+                    Ok(deleted) if deleted > 0 => log::debug!("Deleted {} expired Echo Loops", deleted),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Echo Loop cleanup failed: {}", e),
+                }
+            }
+        }));
+    }
+
+    // Influence of users on the propagation network, recalculated daily
+    let influence_repository = Arc::new(InfluenceRepository::new(db_pool.clone()));
+    let influence = web::Data::new(InfluenceScoreCalculator::new(influence_repository.clone()));
+    background_tasks.push(influence.clone().into_inner().spawn_recalculation_task());
+
+    // Users mentioned in content, linked to its author and rewarded as it propagates
+    let mention_linker = web::Data::new(
+        MentionLinker::new(
+            Arc::new(MentionRepository::new(db_pool.clone())),
+            propagation_service.clone().into_inner(),
+            reward_service.clone().into_inner(),
+        )
+        .with_influence(influence_repository),
+    );
+
+    // Bulk propagation ingestion: replayed batches and the recalculations they trigger
+    let bulk_propagation_responses = web::Data::new(IdempotencyCache::<propagation::BulkPropagationResponse>::new());
+    background_tasks.push(
+        bulk_propagation_responses
+            .clone()
+            .into_inner()
+            .spawn_eviction_task(Duration::from_secs(600)),
+    );
+    let (recalculation_queue, recalculation_worker) = RecalculationQueue::spawn(
+        RecalculationContext {
+            content: content_repository.clone().into_inner(),
+            history: echo_index_history.clone().into_inner(),
+            updates: echo_index_updates.clone().into_inner(),
+            metrics: metrics.clone().into_inner(),
+            events: user_events.clone().into_inner(),
+            users: users.clone().into_inner(),
+            calculator: EchoIndexCalculator::default(),
+            engine_config: echo_engine_config.clone().into_inner(),
+            webhooks: Some(webhook_dispatcher.clone().into_inner()),
+            experiments: Some(experiments.clone().into_inner()),
+            hashtag_trends: Some(hashtag_trends.clone().into_inner()),
+            echo_cache: Some(echo_index_cache.clone().into_inner()),
+            anomalies: Some(echo_anomalies.clone().into_inner()),
+            cold_start: Some(cold_start.clone().into_inner()),
+            propagation: Some(propagation_service.clone().into_inner()),
+            tiers: Some(tier_progression.clone().into_inner()),
+        },
+        echo_index_calculator.clone().into_inner(),
+        RECALCULATION_QUEUE_CAPACITY,
+    );
+    background_tasks.push(recalculation_worker);
+    let recalculation_queue = web::Data::new(recalculation_queue);
+
+    // Cold storage for old low-echo content
+    background_tasks.push(
+        Arc::new(ContentArchiver::from_env(content_repository.clone().into_inner()))
+            .spawn_archival_task(Duration::from_secs(3600)),
+    );
+
+    // Temporal decay of scores not recalculated within `DECAY_INTERVAL_HOURS`
+    background_tasks.push(
+        Arc::new(
+            DecayScheduler::from_env(
+                content_repository.clone().into_inner(),
+                echo_index_history.clone().into_inner(),
+                user_events.clone().into_inner(),
+                echo_engine_config.clone().into_inner(),
+            )
+            .with_webhooks(webhook_dispatcher.clone().into_inner()),
+        )
+        .spawn_decay_task(),
+    );
+
+    // Recasts and quotes of Farcaster casts, polled from a Hub and submitted through the
+    // bulk propagation endpoint
+    if let Some(indexer) =
+        FarcasterIndexer::from_env(content_repository.clone().into_inner(), users.clone().into_inner(), port)
+    {
+        background_tasks.push(Arc::new(indexer).spawn_index_task());
+    }
+
     // Start HTTP server
-    HttpServer::new(|| {
+    let server_db_pool = web::Data::new(db_pool.clone());
+    let server_batch_jobs = batch_jobs.clone();
+    let server_propagation_service = propagation_service.clone();
+    let server_reward_service = reward_service.clone();
+    let server_metrics = metrics.clone();
+    // Generated once rather than by every worker
+    let openapi = ApiDoc::openapi();
+    let server = HttpServer::new(move || {
         let cors = Cors::default()
             .allow_any_origin()
             .allow_any_method()
             .allow_any_header()
+            .expose_headers([REQUEST_ID_HEADER])
             .max_age(3600);
+        // Inside authentication, so only requests allowed through are served from the cache
+        let cache_responses = || {
+            CacheMiddleware::new(response_cache.clone())
+                .with_counters(server_metrics.cache_hits(), server_metrics.cache_misses())
+        };
 
         App::new()
+            .app_data(web::Data::new(jwt_config.clone()))
+            .app_data(token_blacklist.clone())
+            .app_data(api_keys.clone())
+            .app_data(challenge_store.clone())
+            .app_data(mpc_verifier.clone())
+            .app_data(echo_index_calculator.clone())
+            .app_data(echo_engine_config.clone())
+            .app_data(echo_index_updates.clone())
+            .app_data(leaderboard.clone())
+            .app_data(echo_index_percentiles.clone())
+            .app_data(leaderboards.clone())
+            .app_data(server_propagation_service.clone())
+            .app_data(propagation_repository.clone())
+            .app_data(propagation_deduplicator.clone())
+            .app_data(mention_linker.clone())
+            .app_data(influence.clone())
+            .app_data(follower_graph.clone())
+            .app_data(token_vesting.clone())
+            .app_data(bulk_propagation_responses.clone())
+            .app_data(recalculation_queue.clone())
+            .app_data(content_repository.clone())
+            .app_data(content_fingerprints.clone())
+            .app_data(fingerprint_service.clone())
+            .app_data(content_similarity.clone())
+            .app_data(trending_scores.clone())
+            .app_data(content_clusters.clone())
+            .app_data(content_attributions.clone())
+            .app_data(discovery_feeds.clone())
+            .app_data(echo_index_history.clone())
+            .app_data(experiments.clone())
+            .app_data(users.clone())
+            .app_data(refresh_tokens.clone())
+            .app_data(sessions.clone())
+            .app_data(two_factor.clone())
+            .app_data(user_events.clone())
+            .app_data(tier_progression.clone())
+            .app_data(streaks.clone())
+            .app_data(server_db_pool.clone())
+            .app_data(server_batch_jobs.clone())
+            .app_data(server_reward_service.clone())
+            .app_data(quality_bonuses.clone())
+            .app_data(pool_governor.clone())
+            .app_data(reward_analytics.clone())
+            .app_data(reward_forecasts.clone())
+            .app_data(hashtag_trends.clone())
+            .app_data(echo_index_cache.clone())
+            .app_data(echo_anomalies.clone())
+            .app_data(platform_stats.clone())
+            .app_data(cold_start.clone())
+            .app_data(velocity_alerts.clone())
+            .app_data(moderation.clone())
+            .app_data(content_versioning.clone())
+            .app_data(webhook_repository.clone())
+            .app_data(webhook_dispatcher.clone())
+            .app_data(user_data.clone())
+            .app_data(content_imports.clone())
+            .app_data(social_verifier.clone())
+            .app_data(server_metrics.clone())
             .wrap(cors)
             .wrap(Logger::default())
+            .wrap(RequestMetrics::new(server_metrics.clone().into_inner()))
+            // Around everything but the request span it records into, so even requests
+            // rejected by other middleware get an ID
+            .wrap(RequestIdMiddleware)
+            // Outermost, so the request span covers every other middleware
+            .wrap(RequestTracing)
+            // Operational endpoints, exempt from rate limiting and authentication
+            .service(health::metrics)
+            .service(SwaggerUi::new("/swagger-ui/{_:.*}").url(OPENAPI_PATH, openapi.clone()))
             .service(
                 web::scope("/api/v1")
                     // Health check
                     .service(health::health_check)
                     .service(health::ready_check)
-                    
-                    // Authentication
-                    .service(
-                        web::scope("/auth")
-                            .service(auth::login)
-                            .service(auth::logout)
-                            .service(auth::verify_token)
-                            .service(auth::refresh_token)
-                    )
-                    
-                    // Users
-                    .service(
-                        web::scope("/users")
-                            .service(users::create_user)
-                            .service(users::get_user)
-                            .service(users::update_user)
-                            .service(users::get_user_analytics)
-                            .service(users::get_leaderboard)
-                    )
-                    
-                    // Content
-                    .service(
-                        web::scope("/content")
-                            .service(content::create_content)
-                            .service(content::get_content)
-                            .service(content::list_content)
-                            .service(content::update_content)
-                            .service(content::delete_content)
-                    )
-                    
-                    // Echo Index
-                    .service(
-                        web::scope("/echo-index")
-                            .service(echo_index::calculate_echo_index)
-                            .service(echo_index::get_echo_index)
-                            .service(echo_index::get_echo_index_history)
-                            .service(echo_index::recalculate_echo_index)
-                    )
-                    
-                    // Propagation
+
+                    // Everything else shares the API request budget
                     .service(
-                        web::scope("/propagation")
-                            .service(propagation::create_propagation)
-                            .service(propagation::get_propagation_network)
-                            .service(propagation::get_propagation_analytics)
+                        web::scope("")
+                            .wrap(RateLimit::new(api_rate_limiter.clone()))
+
+                            // Live Echo Index updates
+                            .service(echo_index::echo_index_updates)
+
+                            // Authentication
+                            .service(
+                                web::scope("/auth")
+                                    .wrap(
+                                        RateLimit::new(auth_rate_limiter.clone())
+                                            .with_rejection_counter(server_metrics.auth_rate_limited()),
+                                    )
+                                    .service(auth::get_auth_challenge)
+                                    .service(auth::login_with_wallet)
+                                    .service(auth::logout)
+                                    .service(auth::verify_token)
+                                    .service(auth::refresh_token)
+                                    .service(
+                                        web::scope("/sessions")
+                                            .wrap(JwtMiddleware::new(jwt_config.clone(), token_blacklist.clone()))
+                                            .service(auth::list_sessions)
+                                            .service(auth::revoke_other_sessions)
+                                            .service(auth::revoke_session),
+                                    )
+                            )
+
+                            // Users
+                            .service(
+                                web::scope("/users")
+                                    .wrap(cache_responses())
+                                    .wrap(JwtMiddleware::new(jwt_config.clone(), token_blacklist.clone()))
+                                    .service(users::create_user)
+                                    // Registered before `/{user_id}`, which would otherwise match it
+                                    .service(users::get_leaderboard)
+                                    .service(users::get_user)
+                                    .service(users::update_user)
+                                    .service(users::delete_user)
+                                    .service(users::export_user_data)
+                                    .service(users::import_content)
+                                    .service(users::get_user_analytics)
+                                    .service(users::get_user_reward_analytics)
+                                    .service(users::get_claimable_rewards)
+                                    .service(users::process_user_rewards)
+                                    .service(users::claim_rewards_batch)
+                                    .service(users::get_pending_transactions)
+                                    .service(users::get_earnings_forecast)
+                                    .service(users::get_user_timeline)
+                                    .service(users::get_user_content)
+                                    .service(users::get_user_feed)
+                                    .service(users::get_user_streak)
+                                    .service(users::freeze_user_streak)
+                                    .service(users::get_user_influence)
+                                    .service(users::follow_user)
+                                    .service(users::unfollow_user)
+                                    .service(users::get_followers)
+                                    .service(users::get_following)
+                                    .service(users::create_api_key)
+                                    .service(users::revoke_api_key)
+                                    .service(users::setup_two_factor)
+                                    .service(users::confirm_two_factor)
+                                    .service(users::get_recovery_codes)
+                                    .service(users::configure_alert)
+                                    .service(users::list_alerts)
+                                    .service(users::create_webhook)
+                                    .service(users::list_webhooks)
+                                    .service(users::delete_webhook)
+                                    .service(users::list_webhook_deliveries)
+                                    .service(users::start_social_verification)
+                                    .service(users::complete_social_verification)
+                            )
+
+                            // Content
+                            .service(
+                                web::scope("/content")
+                                    .wrap(cache_responses())
+                                    .wrap(JwtMiddleware::new(jwt_config.clone(), token_blacklist.clone()))
+                                    .service(content::create_content)
+                                    .service(content::search_content)
+                                    .service(content::get_trending_content)
+                                    .service(content::get_content)
+                                    .service(content::get_related_content)
+                                    .service(content::get_content_cluster)
+                                    .service(content::list_content_versions)
+                                    .service(content::get_content_version)
+                                    .service(content::diff_content_versions)
+                                    .service(content::list_content)
+                                    .service(content::update_content)
+                                    .service(content::delete_content)
+                                    .service(content::flag_content)
+                                    .service(content::link_content)
+                                    .service(content::get_content_attributions)
+                            )
+
+                            // Hashtags
+                            .service(
+                                web::scope("/hashtags")
+                                    .wrap(cache_responses())
+                                    .wrap(JwtMiddleware::new(jwt_config.clone(), token_blacklist.clone()))
+                                    .service(hashtags::get_trending_hashtags)
+                                    .service(hashtags::get_hashtag_content)
+                            )
+
+                            // Platform analytics
+                            .service(
+                                web::scope("/analytics")
+                                    .wrap(cache_responses())
+                                    .wrap(JwtMiddleware::new(jwt_config.clone(), token_blacklist.clone()))
+                                    .service(platforms::get_platform_stats)
+                                    .service(platforms::get_platform_stats_last_updated)
+                                    .service(platforms::get_platform_top_content)
+                            )
+
+                            // Echo Index
+                            .service(
+                                web::scope("/echo-index")
+                                    .wrap(JwtMiddleware::new(jwt_config.clone(), token_blacklist.clone()))
+                                    .service(echo_index::calculate_echo_index)
+                                    .service(echo_index::leaderboard_stream)
+                                    .service(echo_index::get_echo_index_config)
+                                    .service(echo_index::update_echo_index_config)
+                                    .service(echo_index::get_platform_config)
+                                    .service(echo_index::update_platform_config)
+                                    .service(echo_index::batch_recalculate_echo_index)
+                                    .service(echo_index::get_batch_job)
+                                    .service(echo_index::get_leaderboard)
+                                    .service(echo_index::get_echo_index)
+                                    .service(echo_index::get_echo_index_history)
+                                    .service(echo_index::get_echo_index_forecast)
+                                    .service(echo_index::recalculate_echo_index)
+                            )
+
+                            // Propagation
+                            .service(
+                                web::scope("/propagation")
+                                    .wrap(JwtMiddleware::new(jwt_config.clone(), token_blacklist.clone()))
+                                    .app_data(web::JsonConfig::default().limit(propagation::MAX_BULK_PAYLOAD_BYTES))
+                                    .service(propagation::create_propagation)
+                                    .service(propagation::bulk_create_propagations)
+                                    .service(propagation::get_propagation_network)
+                                    .service(propagation::get_propagation_communities)
+                                    .service(propagation::get_propagation_depth_analysis)
+                                    .service(propagation::get_propagation_cycles)
+                                    .service(propagation::get_deep_propagations)
+                                    .service(propagation::get_propagation_analytics)
+                                    .service(propagation::export_propagation_graph)
+                            )
+
+                            // Operator review
+                            .service(
+                                web::scope("/admin")
+                                    .wrap(RequireRole(Role::Admin))
+                                    .wrap(JwtMiddleware::new(jwt_config.clone(), token_blacklist.clone()))
+                                    .service(admin::list_on_hold_rewards)
+                                    .service(admin::approve_held_reward)
+                                    .service(admin::reject_held_reward)
+                                    .service(admin::set_user_role)
+                                    .service(admin::get_archive_stats)
+                                    .service(admin::set_content_decay_config)
+                                    .service(admin::get_pool_utilization_history)
+                                    .service(admin::get_reward_analytics)
+                                    .service(admin::get_cache_stats)
+                                    .service(admin::get_content_clusters)
+                                    .service(admin::list_pending_quality_bonuses)
+                                    .service(admin::get_moderation_queue)
+                                    .service(admin::get_flagged_edits)
+                                    .service(admin::review_flag)
+                                    .service(admin::list_anomalies)
+                                    .service(admin::dismiss_anomaly)
+                                    .service(admin::confirm_anomaly)
+                                    .service(admin::list_amplification_events)
+                                    .service(admin::revert_amplification_event)
+                                    .service(admin::create_experiment)
+                                    .service(admin::list_experiments)
+                                    .service(admin::get_experiment)
+                                    .service(admin::update_experiment)
+                                    .service(admin::delete_experiment)
+                                    .service(admin::get_experiment_results)
+                                    .service(admin::get_engine_config)
+                                    .service(admin::update_engine_config)
+                            )
                     )
             )
     })
+    // Shutdown is driven by `shutdown::signal`, so the cleanup below runs after it
+    .disable_signals()
+    .shutdown_timeout(shutdown::timeout_secs())
     .bind((host.as_str(), port))?
-    .run()
-    .await
+    .run();
+
+    // On Ctrl-C or SIGTERM, stop accepting connections and let requests in flight finish
+    let in_flight = metrics.http_requests_in_flight.clone();
+    tokio::spawn(shutdown::stop_on(shutdown::signal(), server.handle(), in_flight.clone()));
+    server.await?;
+    info!("HTTP server stopped with {} requests in flight", in_flight.get());
+
+    // Let queued recalculations finish before exiting
+    info!("Waiting for in-flight batch jobs to finish");
+    batch_jobs.drain().await;
+
+    // Keep the rewards not yet released for the next run
+    let pending_rewards = reward_service.read().await.pending_rewards();
+    match reward_checkpoints.save(&pending_rewards).await {
+        Ok(()) => info!("Checkpointed {} pending rewards", pending_rewards.len()),
+        Err(e) => tracing::error!(error = %e, "Failed to checkpoint pending rewards"),
+    }
+
+    // Persist Echo Loops whose saves the shutdown may have interrupted
+    match propagation_service.checkpoint().await {
+        Ok(saved) => info!("Checkpointed {} Echo Loops", saved),
+        Err(e) => tracing::error!(error = %e, "Failed to checkpoint Echo Loops"),
+    }
+
+    for task in background_tasks {
+        task.abort();
+    }
+
+    // Flush spans still buffered for export
+    if let Err(e) = telemetry.tracer_provider.shutdown() {
+        log::warn!("Failed to flush traces: {}", e);
+    }
+    Ok(())
 } 
\ No newline at end of file
@@ -0,0 +1,427 @@
+use actix_web::body::{self, BoxBody, MessageBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::ErrorInternalServerError;
+use actix_web::http::header::{ContentType, HeaderValue, CACHE_CONTROL, CONTENT_TYPE, VARY};
+use actix_web::http::{Method, StatusCode};
+use actix_web::{Error, HttpMessage, HttpResponse};
+use futures_util::future::LocalBoxFuture;
+use moka::sync::Cache;
+use prometheus::IntCounter;
+use std::collections::{HashMap, HashSet};
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::handlers::auth::Claims;
+
+/// Responses kept at most, across every cached route
+pub const DEFAULT_CACHE_MAX_ENTRIES: u64 = 10_000;
+/// Query parameters shared links carry to credit whoever shared them
+pub const SHARE_LINK_PARAMS: &[&str] = &["ref", "utm_source", "utm_medium", "utm_campaign"];
+
+/// How long responses of a route are cached, and what tells its requests apart
+#[derive(Debug, Clone)]
+pub struct CachedRoute {
+    pub ttl: Duration,
+    /// Query parameters left out of the cache key: they say who is asking, such as a
+    /// referral code, not what is asked for
+    pub user_params: Vec<String>,
+}
+
+/// Routes whose `GET` responses are cached, by route pattern such as
+/// `/api/v1/content/{content_id}`
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    routes: HashMap<String, CachedRoute>,
+    /// Routes whose responses are specific to the user, which neither this cache nor any
+    /// other may store
+    no_store: HashSet<String>,
+    max_entries: u64,
+}
+
+impl CacheConfig {
+    pub fn new(max_entries: u64) -> Self {
+        Self {
+            routes: HashMap::new(),
+            no_store: HashSet::new(),
+            max_entries,
+        }
+    }
+
+    /// No routes yet, keeping up to `RESPONSE_CACHE_MAX_ENTRIES` responses, 10,000 by default
+    pub fn from_env() -> Self {
+        let max_entries = std::env::var("RESPONSE_CACHE_MAX_ENTRIES")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_CACHE_MAX_ENTRIES);
+        Self::new(max_entries)
+    }
+
+    /// Cache responses of `pattern` for `ttl_seconds`
+    pub fn cache(self, pattern: &str, ttl_seconds: u64) -> Self {
+        self.cache_excluding(pattern, ttl_seconds, &[])
+    }
+
+    /// Cache responses of `pattern` for `ttl_seconds`, sharing them between requests that
+    /// differ only in `user_params`
+    pub fn cache_excluding(mut self, pattern: &str, ttl_seconds: u64, user_params: &[&str]) -> Self {
+        self.routes.insert(
+            pattern.to_string(),
+            CachedRoute {
+                ttl: Duration::from_secs(ttl_seconds),
+                user_params: user_params.iter().map(|param| param.to_string()).collect(),
+            },
+        );
+        self
+    }
+
+    /// Mark responses of `pattern` as specific to the user
+    pub fn no_store(mut self, pattern: &str) -> Self {
+        self.no_store.insert(pattern.to_string());
+        self
+    }
+}
+
+/// What the cache does with a request
+enum CachePolicy {
+    /// Serve the response stored under `key` while younger than `ttl`, or store it
+    Cache { key: String, ttl: Duration },
+    NoStore,
+}
+
+/// Response bodies of the routes of a `CacheConfig`, shared by every worker. An entry is
+/// served until its route's TTL passes, so changes show up at most that late, and only
+/// to the caller it was made for.
+pub struct ResponseCache {
+    config: CacheConfig,
+    entries: Cache<String, (Vec<u8>, Instant)>,
+}
+
+impl ResponseCache {
+    pub fn new(config: CacheConfig) -> Self {
+        // Entries of every route are gone once the longest TTL has passed
+        let longest_ttl = config.routes.values().map(|route| route.ttl).max().unwrap_or_default();
+        let entries = Cache::builder()
+            .max_capacity(config.max_entries)
+            .time_to_live(longest_ttl.max(Duration::from_secs(1)))
+            .build();
+        Self { config, entries }
+    }
+
+    fn policy(&self, req: &ServiceRequest) -> Option<CachePolicy> {
+        if req.method() != Method::GET {
+            return None;
+        }
+        let pattern = req.match_pattern()?;
+        if self.config.no_store.contains(&pattern) {
+            return Some(CachePolicy::NoStore);
+        }
+        let route = self.config.routes.get(&pattern)?;
+        Some(CachePolicy::Cache {
+            key: cache_key(req, route),
+            ttl: route.ttl,
+        })
+    }
+
+    /// The body stored under `key` and its age, unless older than `ttl`
+    fn get(&self, key: &str, ttl: Duration) -> Option<(Vec<u8>, Duration)> {
+        let (body, stored_at) = self.entries.get(key)?;
+        let age = stored_at.elapsed();
+        (age < ttl).then_some((body, age))
+    }
+
+    fn insert(&self, key: String, body: Vec<u8>) {
+        self.entries.insert(key, (body, Instant::now()));
+    }
+}
+
+/// Caller, method, path and query of a request, leaving out the route's user parameters
+/// and sorting the rest so their order does not matter
+fn cache_key(req: &ServiceRequest, route: &CachedRoute) -> String {
+    let subject = req.extensions().get::<Claims>().map(|claims| claims.sub.clone()).unwrap_or_default();
+    let mut params: Vec<&str> = req
+        .query_string()
+        .split('&')
+        .filter(|param| {
+            let name = param.split('=').next().unwrap_or_default();
+            !name.is_empty() && !route.user_params.iter().any(|user_param| user_param == name)
+        })
+        .collect();
+    params.sort_unstable();
+    format!("{} {} {}?{}", subject, req.method(), req.path(), params.join("&"))
+}
+
+/// `Cache-Control` of a response still fresh for `remaining`. Responses are authenticated,
+/// so shared caches such as proxies and CDNs may not store them.
+fn max_age(remaining: Duration) -> HeaderValue {
+    HeaderValue::from_str(&format!("private, max-age={}", remaining.as_secs()))
+        .expect("max-age is a valid header value")
+}
+
+/// Serves `GET` responses of the routes configured in a `ResponseCache` from it, and marks
+/// those of user-specific routes `no-store`. Only `200 OK` JSON responses are stored; a
+/// hit returns the stored bytes without calling the handler.
+pub struct CacheMiddleware {
+    cache: Arc<ResponseCache>,
+    hits: Option<IntCounter>,
+    misses: Option<IntCounter>,
+}
+
+impl CacheMiddleware {
+    pub fn new(cache: Arc<ResponseCache>) -> Self {
+        Self { cache, hits: None, misses: None }
+    }
+
+    /// Count requests to cached routes served from the cache and passed to the handler
+    pub fn with_counters(mut self, hits: IntCounter, misses: IntCounter) -> Self {
+        self.hits = Some(hits);
+        self.misses = Some(misses);
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for CacheMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Transform = CacheMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CacheMiddlewareService {
+            service: Rc::new(service),
+            cache: Arc::clone(&self.cache),
+            hits: self.hits.clone(),
+            misses: self.misses.clone(),
+        }))
+    }
+}
+
+pub struct CacheMiddlewareService<S> {
+    service: Rc<S>,
+    cache: Arc<ResponseCache>,
+    hits: Option<IntCounter>,
+    misses: Option<IntCounter>,
+}
+
+impl<S, B> Service<ServiceRequest> for CacheMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let (key, ttl) = match self.cache.policy(&req) {
+            None => {
+                return Box::pin(async move { service.call(req).await.map(ServiceResponse::map_into_boxed_body) });
+            }
+            Some(CachePolicy::NoStore) => {
+                return Box::pin(async move {
+                    let mut response = service.call(req).await?;
+                    response.headers_mut().insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+                    Ok(response.map_into_boxed_body())
+                });
+            }
+            Some(CachePolicy::Cache { key, ttl }) => (key, ttl),
+        };
+
+        if let Some((body, age)) = self.cache.get(&key, ttl) {
+            if let Some(hits) = &self.hits {
+                hits.inc();
+            }
+            let response = HttpResponse::Ok()
+                .content_type(ContentType::json())
+                .insert_header((CACHE_CONTROL, max_age(ttl.saturating_sub(age))))
+                .insert_header((VARY, "Accept-Encoding"))
+                .body(body);
+            return Box::pin(async move { Ok(req.into_response(response)) });
+        }
+        if let Some(misses) = &self.misses {
+            misses.inc();
+        }
+
+        let cache = Arc::clone(&self.cache);
+        Box::pin(async move {
+            let response = service.call(req).await?;
+            let is_json = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.starts_with("application/json"));
+            if response.status() != StatusCode::OK || !is_json {
+                return Ok(response.map_into_boxed_body());
+            }
+
+            let (req, response) = response.into_parts();
+            let (mut response, body) = response.into_parts();
+            let bytes = body::to_bytes(body).await.map_err(|e| {
+                let e: Box<dyn std::error::Error> = e.into();
+                ErrorInternalServerError(e.to_string())
+            })?;
+            cache.insert(key, bytes.to_vec());
+
+            response.headers_mut().insert(CACHE_CONTROL, max_age(ttl));
+            response.headers_mut().insert(VARY, HeaderValue::from_static("Accept-Encoding"));
+            Ok(ServiceResponse::new(req, response.set_body(BoxBody::new(bytes))))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body, TestRequest};
+    use actix_web::web;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn cache(config: CacheConfig) -> Arc<ResponseCache> {
+        Arc::new(ResponseCache::new(config))
+    }
+
+    #[actix_web::test]
+    async fn test_identical_requests_within_ttl_call_the_handler_once() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handler_calls = Arc::clone(&calls);
+        let app = init_service(
+            actix_web::App::new()
+                .wrap(CacheMiddleware::new(cache(CacheConfig::new(100).cache("/leaderboard", 60))))
+                .route(
+                    "/leaderboard",
+                    web::get().to(move || {
+                        let call = handler_calls.fetch_add(1, Ordering::SeqCst) + 1;
+                        async move { HttpResponse::Ok().json(json!({ "success": true, "data": call })) }
+                    }),
+                ),
+        )
+        .await;
+
+        let mut bodies = Vec::new();
+        for _ in 0..3 {
+            let response = call_service(&app, TestRequest::get().uri("/leaderboard?limit=10").to_request()).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(response.headers().get(CACHE_CONTROL).unwrap().to_str().unwrap().starts_with("private, max-age="));
+            assert_eq!(response.headers().get(VARY).unwrap(), "Accept-Encoding");
+            bodies.push(read_body(response).await);
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(bodies.iter().all(|body| *body == bodies[0]));
+        let body: serde_json::Value = serde_json::from_slice(&bodies[0]).unwrap();
+        assert_eq!(body, json!({ "success": true, "data": 1 }));
+    }
+
+    #[actix_web::test]
+    async fn test_callers_never_share_responses() {
+        let app = init_service(
+            actix_web::App::new()
+                .wrap(CacheMiddleware::new(cache(CacheConfig::new(100).cache("/me", 60))))
+                // Stands in for `JwtMiddleware`, which runs before the cache
+                .wrap_fn(|req, service| {
+                    let sub = req.headers().get("X-User").and_then(|value| value.to_str().ok()).unwrap_or_default();
+                    let claims = Claims {
+                        sub: sub.to_string(),
+                        wallet: format!("wallet_{}", sub),
+                        exp: 0,
+                        iat: 0,
+                        jti: "jti".to_string(),
+                        session_id: "session".to_string(),
+                        role: crate::models::user::Role::User,
+                        totp_verified: false,
+                    };
+                    req.extensions_mut().insert(claims);
+                    service.call(req)
+                })
+                .route(
+                    "/me",
+                    web::get().to(|claims: web::ReqData<Claims>| async move {
+                        HttpResponse::Ok().json(json!({ "success": true, "data": claims.sub }))
+                    }),
+                ),
+        )
+        .await;
+
+        for user in ["alice", "bob", "alice"] {
+            let request = TestRequest::get().uri("/me").insert_header(("X-User", user)).to_request();
+            let body = read_body(call_service(&app, request).await).await;
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body["data"], user);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_key_leaves_out_user_params() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handler_calls = Arc::clone(&calls);
+        let config = CacheConfig::new(100).cache_excluding("/content/{id}", 60, &["ref"]);
+        let metrics = crate::services::MetricsRegistry::new();
+        let app = init_service(
+            actix_web::App::new()
+                .wrap(CacheMiddleware::new(cache(config)).with_counters(metrics.cache_hits(), metrics.cache_misses()))
+                .route(
+                    "/content/{id}",
+                    web::get().to(move || {
+                        handler_calls.fetch_add(1, Ordering::SeqCst);
+                        async { HttpResponse::Ok().json(json!({ "success": true })) }
+                    }),
+                ),
+        )
+        .await;
+
+        for uri in ["/content/1?ref=alice&full=1", "/content/1?full=1&ref=bob", "/content/1", "/content/2?ref=alice"] {
+            call_service(&app, TestRequest::get().uri(uri).to_request()).await;
+        }
+
+        // The second request shares the first's entry; the others differ in path or query
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(metrics.cache_hits().get(), 1);
+        assert_eq!(metrics.cache_misses().get(), 3);
+    }
+
+    #[actix_web::test]
+    async fn test_errors_and_user_specific_routes_are_not_stored() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let (missing_calls, feed_calls) = (Arc::clone(&calls), Arc::clone(&calls));
+        let config = CacheConfig::new(100).cache("/missing", 60).no_store("/feed");
+        let app = init_service(
+            actix_web::App::new()
+                .wrap(CacheMiddleware::new(cache(config)))
+                .route(
+                    "/missing",
+                    web::get().to(move || {
+                        missing_calls.fetch_add(1, Ordering::SeqCst);
+                        async { HttpResponse::NotFound().json(json!({ "success": false })) }
+                    }),
+                )
+                .route(
+                    "/feed",
+                    web::get().to(move || {
+                        feed_calls.fetch_add(1, Ordering::SeqCst);
+                        async { HttpResponse::Ok().json(json!({ "success": true })) }
+                    }),
+                ),
+        )
+        .await;
+
+        for _ in 0..2 {
+            let missing = call_service(&app, TestRequest::get().uri("/missing").to_request()).await;
+            assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+            assert!(missing.headers().get(CACHE_CONTROL).is_none());
+
+            let feed = call_service(&app, TestRequest::get().uri("/feed").to_request()).await;
+            assert_eq!(feed.headers().get(CACHE_CONTROL).unwrap(), "no-store");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+}
@@ -0,0 +1,256 @@
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{web, Error, HttpMessage, ResponseError};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use tracing::Span;
+use uuid::Uuid;
+
+use crate::handlers::auth::{AuthService, JwtConfig};
+use crate::handlers::errors::{ApiError, AuthError};
+use crate::repositories::SessionRepository;
+use crate::services::{ApiKeyError, ApiKeyService, TokenBlacklist};
+
+/// Header machine clients send their API key in
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Requires a valid `Authorization: Bearer <jwt>` header on every request.
+/// Decoded `Claims` are stored as a request extension for downstream handlers, and
+/// their subject is recorded as the `user_id` of the `RequestTracing` span, next to the
+/// `request_id` that `RequestIdMiddleware` recorded. With a `SessionRepository` in app
+/// data, the token's session is marked active, and tokens of sessions that have ended or
+/// been revoked are rejected even once the in-memory blacklist has forgotten them, e.g.
+/// after a restart or on another instance.
+///
+/// A request with an `X-API-Key` header is authenticated by that key instead, through
+/// the `ApiKeyService` in app data, and gets claims for the key's owner. The `ApiKey`
+/// itself is stored as an extension too.
+pub struct JwtMiddleware {
+    config: JwtConfig,
+    blacklist: web::Data<TokenBlacklist>,
+}
+
+impl JwtMiddleware {
+    pub fn new(config: JwtConfig, blacklist: web::Data<TokenBlacklist>) -> Self {
+        Self { config, blacklist }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for JwtMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = JwtMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(JwtMiddlewareService {
+            service: Rc::new(service),
+            config: self.config.clone(),
+            blacklist: self.blacklist.clone(),
+        }))
+    }
+}
+
+pub struct JwtMiddlewareService<S> {
+    service: Rc<S>,
+    config: JwtConfig,
+    blacklist: web::Data<TokenBlacklist>,
+}
+
+impl<S, B> Service<ServiceRequest> for JwtMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if let Some(raw_key) = req.headers().get(API_KEY_HEADER) {
+            let raw_key = raw_key.to_str().unwrap_or_default().to_string();
+            return self.call_with_api_key(req, raw_key);
+        }
+
+        let token = req
+            .headers()
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::to_string);
+
+        let claims = match token {
+            Some(token) => AuthService::validate_access_token(&token, &self.config, &self.blacklist)
+                .map_err(AuthError::InvalidToken),
+            None => Err(AuthError::MissingToken),
+        };
+
+        match claims {
+            Ok(claims) => {
+                Span::current().record("user_id", claims.sub.as_str());
+                let session_id = Uuid::parse_str(&claims.session_id).ok();
+                req.extensions_mut().insert(claims);
+                let sessions = req.app_data::<web::Data<SessionRepository>>().cloned();
+                let service = Rc::clone(&self.service);
+                Box::pin(async move {
+                    if let Some(sessions) = sessions {
+                        let Some(session_id) = session_id else {
+                            let error = AuthError::InvalidToken("Token does not belong to a session".to_string());
+                            return Ok(unauthorized(req, error));
+                        };
+                        match sessions.touch(session_id).await {
+                            Ok(true) => {}
+                            Ok(false) => {
+                                let error = AuthError::InvalidToken("Session has ended".to_string());
+                                return Ok(unauthorized(req, error));
+                            }
+                            Err(e) => {
+                                tracing::error!(%session_id, error = %e, "Session lookup failed");
+                                return Ok(reject(req, ApiError::internal()));
+                            }
+                        }
+                    }
+                    service.call(req).await.map(ServiceResponse::map_into_left_body)
+                })
+            }
+            Err(e) => Box::pin(async move { Ok(unauthorized(req, e)) }),
+        }
+    }
+}
+
+impl<S, B> JwtMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    /// Authenticate a machine client by its API key instead of a bearer token
+    fn call_with_api_key(
+        &self,
+        req: ServiceRequest,
+        raw_key: String,
+    ) -> LocalBoxFuture<'static, Result<ServiceResponse<EitherBody<B>>, Error>> {
+        let service = Rc::clone(&self.service);
+        let api_keys = req.app_data::<web::Data<ApiKeyService>>().cloned();
+
+        Box::pin(async move {
+            let Some(api_keys) = api_keys else {
+                return Ok(unauthorized(req, AuthError::InvalidApiKey("API keys are not accepted here".to_string())));
+            };
+
+            match api_keys.authenticate(&raw_key, req.method()).await {
+                Ok((key, claims)) => {
+                    Span::current().record("user_id", claims.sub.as_str());
+                    req.extensions_mut().insert(claims);
+                    req.extensions_mut().insert(key);
+                    service.call(req).await.map(ServiceResponse::map_into_left_body)
+                }
+                Err(ApiKeyError::RateLimited(retry_after)) => {
+                    let retry_after_seconds = retry_after.as_secs_f64().ceil().max(1.0) as u32;
+                    Ok(reject(req, ApiError::RateLimited { retry_after_seconds }))
+                }
+                Err(ApiKeyError::Forbidden) => Ok(reject(req, ApiError::Forbidden(ApiKeyError::Forbidden.to_string()))),
+                Err(ApiKeyError::Repository(e)) => {
+                    tracing::error!(error = %e, "API key lookup failed");
+                    Ok(reject(req, ApiError::internal()))
+                }
+                Err(e) => Ok(unauthorized(req, AuthError::InvalidApiKey(e.to_string()))),
+            }
+        })
+    }
+}
+
+fn unauthorized<B>(req: ServiceRequest, error: AuthError) -> ServiceResponse<EitherBody<B>> {
+    tracing::warn!(path = req.path(), reason = %error, "Rejected request");
+    reject(req, ApiError::Unauthorized(error))
+}
+
+fn reject<B>(req: ServiceRequest, error: ApiError) -> ServiceResponse<EitherBody<B>> {
+    req.into_response(error.error_response()).map_into_right_body()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::auth::Claims;
+    use crate::models::user::Role;
+    use actix_web::{test, App, HttpResponse};
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    const SECRET: &str = "test-secret";
+
+    fn token(secret: &str, jti: &str, expires_in_secs: i64) -> String {
+        let now = chrono::Utc::now().timestamp();
+        let claims = Claims {
+            sub: "user_1".to_string(),
+            wallet: "wallet_1".to_string(),
+            exp: (now + expires_in_secs) as usize,
+            iat: now as usize,
+            jti: jti.to_string(),
+            session_id: "session_1".to_string(),
+            role: Role::User,
+            totp_verified: false,
+        };
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes())).unwrap()
+    }
+
+    async fn whoami(claims: web::ReqData<Claims>) -> HttpResponse {
+        HttpResponse::Ok().body(claims.sub.clone())
+    }
+
+    async fn status_for(token: Option<String>, blacklist: web::Data<TokenBlacklist>) -> u16 {
+        let app = test::init_service(
+            App::new().service(
+                web::scope("/protected")
+                    .wrap(JwtMiddleware::new(JwtConfig::new(SECRET), blacklist))
+                    .route("", web::get().to(whoami)),
+            ),
+        )
+        .await;
+
+        let mut request = test::TestRequest::get().uri("/protected");
+        if let Some(token) = token {
+            request = request.insert_header(("Authorization", format!("Bearer {}", token)));
+        }
+        test::call_service(&app, request.to_request()).await.status().as_u16()
+    }
+
+    #[actix_web::test]
+    async fn test_valid_token_passes_claims() {
+        let blacklist = web::Data::new(TokenBlacklist::new());
+        assert_eq!(status_for(Some(token(SECRET, "jti_ok", 3600)), blacklist).await, 200);
+    }
+
+    #[actix_web::test]
+    async fn test_missing_token_rejected() {
+        let blacklist = web::Data::new(TokenBlacklist::new());
+        assert_eq!(status_for(None, blacklist).await, 401);
+    }
+
+    #[actix_web::test]
+    async fn test_expired_token_rejected() {
+        let blacklist = web::Data::new(TokenBlacklist::new());
+        assert_eq!(status_for(Some(token(SECRET, "jti_old", -10)), blacklist).await, 401);
+    }
+
+    #[actix_web::test]
+    async fn test_bad_signature_rejected() {
+        let blacklist = web::Data::new(TokenBlacklist::new());
+        assert_eq!(status_for(Some(token("other-secret", "jti_forged", 3600)), blacklist).await, 401);
+    }
+
+    #[actix_web::test]
+    async fn test_blacklisted_token_rejected() {
+        let blacklist = web::Data::new(TokenBlacklist::new());
+        let exp = (chrono::Utc::now().timestamp() + 3600) as usize;
+        blacklist.revoke("jti_revoked", exp);
+        assert_eq!(status_for(Some(token(SECRET, "jti_revoked", 3600)), blacklist).await, 401);
+    }
+}
@@ -0,0 +1,123 @@
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::Error;
+use futures_util::future::LocalBoxFuture;
+use prometheus::IntGauge;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::services::MetricsRegistry;
+
+/// Records the duration of every request, labelled by method and matched route pattern.
+/// Unmatched requests share a single `unmatched` route label to keep cardinality bounded.
+/// Also counts the requests in flight, which graceful shutdown waits to drain.
+pub struct RequestMetrics {
+    metrics: Arc<MetricsRegistry>,
+}
+
+impl RequestMetrics {
+    pub fn new(metrics: Arc<MetricsRegistry>) -> Self {
+        Self { metrics }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestMetricsService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestMetricsService {
+            service: Rc::new(service),
+            metrics: Arc::clone(&self.metrics),
+        }))
+    }
+}
+
+pub struct RequestMetricsService<S> {
+    service: Rc<S>,
+    metrics: Arc<MetricsRegistry>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let started = Instant::now();
+        let method = req.method().to_string();
+        let service = Rc::clone(&self.service);
+        let metrics = Arc::clone(&self.metrics);
+        let in_flight = InFlight::start(metrics.http_requests_in_flight.clone());
+
+        Box::pin(async move {
+            let _in_flight = in_flight;
+            let response = service.call(req).await?;
+            let route = response
+                .request()
+                .match_pattern()
+                .unwrap_or_else(|| "unmatched".to_string());
+            metrics
+                .http_request_duration
+                .with_label_values(&[method.as_str(), route.as_str()])
+                .observe(started.elapsed().as_secs_f64());
+            Ok(response)
+        })
+    }
+}
+
+/// Counts a request in flight until dropped, whether it completed, failed or was cancelled
+struct InFlight(IntGauge);
+
+impl InFlight {
+    fn start(gauge: IntGauge) -> Self {
+        gauge.inc();
+        Self(gauge)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+
+    #[actix_web::test]
+    async fn test_duration_labelled_by_route_pattern() {
+        let metrics = Arc::new(MetricsRegistry::new());
+        let app = init_service(
+            App::new()
+                .wrap(RequestMetrics::new(Arc::clone(&metrics)))
+                .route("/content/{id}", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        call_service(&app, TestRequest::get().uri("/content/42").to_request()).await;
+        call_service(&app, TestRequest::get().uri("/missing").to_request()).await;
+
+        let histogram = &metrics.http_request_duration;
+        assert_eq!(histogram.with_label_values(&["GET", "/content/{id}"]).get_sample_count(), 1);
+        assert_eq!(histogram.with_label_values(&["GET", "unmatched"]).get_sample_count(), 1);
+        assert_eq!(metrics.http_requests_in_flight.get(), 0);
+    }
+}
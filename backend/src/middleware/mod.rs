@@ -0,0 +1,15 @@
+pub mod cache;
+pub mod jwt;
+pub mod metrics;
+pub mod rate_limit;
+pub mod rbac;
+pub mod request_id;
+pub mod request_tracing;
+
+pub use cache::{CacheConfig, CacheMiddleware, ResponseCache, SHARE_LINK_PARAMS};
+pub use jwt::JwtMiddleware;
+pub use metrics::RequestMetrics;
+pub use rate_limit::{RateLimit, RateLimitConfig, RateLimiter};
+pub use rbac::RequireRole;
+pub use request_id::{RequestIdMiddleware, REQUEST_ID_HEADER};
+pub use request_tracing::RequestTracing;
@@ -0,0 +1,326 @@
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{Error, ResponseError};
+use dashmap::DashMap;
+use futures_util::future::LocalBoxFuture;
+use prometheus::IntCounter;
+use std::future::{ready, Ready};
+use std::net::IpAddr;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+use crate::handlers::errors::ApiError;
+
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    pub max_requests: u32,
+    pub window: Duration,
+    /// Use the first `X-Forwarded-For` address instead of the peer address.
+    /// Only enable behind a proxy that overwrites the header.
+    pub trust_forwarded_for: bool,
+}
+
+impl RateLimitConfig {
+    /// Load `<PREFIX>_REQUESTS` and `<PREFIX>_WINDOW_SECS`, falling back to the given defaults.
+    /// Forwarded-for trust is shared through `RATE_LIMIT_TRUST_FORWARDED_FOR`.
+    pub fn from_env(prefix: &str, default_requests: u32, default_window_secs: u64) -> Self {
+        let max_requests = std::env::var(format!("{}_REQUESTS", prefix))
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default_requests);
+        let window_secs = std::env::var(format!("{}_WINDOW_SECS", prefix))
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default_window_secs);
+        let trust_forwarded_for = std::env::var("RATE_LIMIT_TRUST_FORWARDED_FOR")
+            .map(|value| value == "true" || value == "1")
+            .unwrap_or(false);
+
+        Self {
+            max_requests,
+            window: Duration::from_secs(window_secs),
+            trust_forwarded_for,
+        }
+    }
+}
+
+/// Requests of one client in the window under way and the one before it
+#[derive(Debug, Clone, Copy)]
+struct ClientWindow {
+    start: Instant,
+    current: u32,
+    previous: u32,
+}
+
+impl ClientWindow {
+    /// Move the window forward to the one `now` falls in
+    fn advance(&mut self, now: Instant, window: Duration) {
+        let elapsed = now.duration_since(self.start);
+        if elapsed < window {
+            return;
+        }
+        let windows_passed = (elapsed.as_nanos() / window.as_nanos().max(1)) as u32;
+        self.previous = if windows_passed == 1 { self.current } else { 0 };
+        self.current = 0;
+        self.start += window * windows_passed;
+    }
+
+    /// Share of the previous window still covered by a window ending at `now`
+    fn previous_weight(&self, now: Instant, window: Duration) -> f64 {
+        1.0 - now.duration_since(self.start).as_secs_f64() / window.as_secs_f64()
+    }
+}
+
+/// Per-IP request counter over a sliding window. Requests of the previous fixed window are
+/// weighted by how much of it the sliding window still covers, so a client cannot spend
+/// its budget twice with a burst on either side of a window boundary.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    clients: DashMap<IpAddr, ClientWindow>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            clients: DashMap::new(),
+        }
+    }
+
+    /// Count a request from `ip`; returns the time until it would be allowed when over the limit
+    pub fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        self.check_at(ip, Instant::now())
+    }
+
+    fn check_at(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        let window = self.config.window;
+        let max_requests = f64::from(self.config.max_requests);
+        let mut client = self
+            .clients
+            .entry(ip.to_canonical())
+            .or_insert(ClientWindow { start: now, current: 0, previous: 0 });
+        client.advance(now, window);
+
+        let previous_weight = client.previous_weight(now, window);
+        let (previous, current) = (f64::from(client.previous), f64::from(client.current));
+        if previous * previous_weight + current < max_requests {
+            client.current += 1;
+            return Ok(());
+        }
+
+        // The estimate falls as the previous window slides out, and once the current one
+        // ends, as it slides out in turn
+        let (elapsed, window) = (now.duration_since(client.start).as_secs_f64(), window.as_secs_f64());
+        let wait = if current < max_requests {
+            window * (1.0 - (max_requests - current) / previous) - elapsed
+        } else {
+            window - elapsed + window * (1.0 - max_requests / current)
+        };
+        Err(Duration::from_secs_f64(wait.max(0.0)))
+    }
+
+    /// Drop clients whose requests no longer count towards the window, returning how many
+    /// were removed
+    pub fn evict_idle(&self) -> usize {
+        let now = Instant::now();
+        let window = self.config.window;
+        let before = self.clients.len();
+        self.clients.retain(|_, client| now.duration_since(client.start) < window * 2);
+        before.saturating_sub(self.clients.len())
+    }
+
+    /// Periodically evict idle clients so the map does not grow with every address seen
+    pub fn spawn_eviction_task(self: Arc<Self>, period: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let evicted = self.evict_idle();
+                if evicted > 0 {
+                    log::debug!("Evicted {} idle clients from rate limiter", evicted);
+                }
+            }
+        })
+    }
+
+    /// Resolve the client address, honouring `X-Forwarded-For` only when trusted
+    pub fn client_ip(&self, req: &ServiceRequest) -> Option<IpAddr> {
+        if self.config.trust_forwarded_for {
+            let forwarded = req
+                .headers()
+                .get("X-Forwarded-For")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.split(',').next())
+                .and_then(|first| first.trim().parse::<IpAddr>().ok());
+            if forwarded.is_some() {
+                return forwarded;
+            }
+        }
+
+        req.peer_addr().map(|addr| addr.ip())
+    }
+}
+
+/// Middleware rejecting clients that exceed their request budget with `429 Too Many Requests`
+pub struct RateLimit {
+    limiter: Arc<RateLimiter>,
+    rejections: Option<IntCounter>,
+}
+
+impl RateLimit {
+    pub fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self { limiter, rejections: None }
+    }
+
+    /// Count every request turned away with 429
+    pub fn with_rejection_counter(mut self, counter: IntCounter) -> Self {
+        self.rejections = Some(counter);
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RateLimitService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitService {
+            service: Rc::new(service),
+            limiter: Arc::clone(&self.limiter),
+            rejections: self.rejections.clone(),
+        }))
+    }
+}
+
+pub struct RateLimitService<S> {
+    service: Rc<S>,
+    limiter: Arc<RateLimiter>,
+    rejections: Option<IntCounter>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let verdict = match self.limiter.client_ip(&req) {
+            Some(ip) => self.limiter.check(ip),
+            // Without an address there is nothing to key on; let the request through
+            None => Ok(()),
+        };
+
+        match verdict {
+            Ok(()) => {
+                let service = Rc::clone(&self.service);
+                Box::pin(async move {
+                    service.call(req).await.map(ServiceResponse::map_into_left_body)
+                })
+            }
+            Err(retry_after) => {
+                if let Some(rejections) = &self.rejections {
+                    rejections.inc();
+                }
+                let retry_after_seconds = retry_after.as_secs_f64().ceil().max(1.0) as u32;
+                let response = ApiError::RateLimited { retry_after_seconds }.error_response();
+                Box::pin(async move { Ok(req.into_response(response).map_into_right_body()) })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+
+    fn limiter(max_requests: u32, trust_forwarded_for: bool) -> Arc<RateLimiter> {
+        Arc::new(RateLimiter::new(RateLimitConfig {
+            max_requests,
+            window: Duration::from_secs(60),
+            trust_forwarded_for,
+        }))
+    }
+
+    #[test]
+    fn test_limit_per_ip() {
+        let limiter = limiter(2, false);
+        let first: IpAddr = "203.0.113.1".parse().unwrap();
+        let second: IpAddr = "2001:db8::1".parse().unwrap();
+
+        assert!(limiter.check(first).is_ok());
+        assert!(limiter.check(first).is_ok());
+        let retry_after = limiter.check(first).unwrap_err();
+        assert!(retry_after <= Duration::from_secs(60));
+
+        assert!(limiter.check(second).is_ok());
+    }
+
+    #[test]
+    fn test_burst_across_window_boundary_is_limited() {
+        let limiter = limiter(10, false);
+        let ip: IpAddr = "203.0.113.9".parse().unwrap();
+        let start = Instant::now();
+        assert!(limiter.check_at(ip, start).is_ok());
+
+        // The rest of the budget just before the boundary, then a burst just after it
+        for _ in 0..9 {
+            assert!(limiter.check_at(ip, start + Duration::from_secs(59)).is_ok());
+        }
+        let after_boundary = start + Duration::from_secs(61);
+        let allowed = (0..10).filter(|_| limiter.check_at(ip, after_boundary).is_ok()).count();
+        assert_eq!(allowed, 1);
+
+        // The previous window's requests stop counting as it slides out
+        let retry_after = limiter.check_at(ip, after_boundary).unwrap_err();
+        assert!(retry_after > Duration::ZERO && retry_after <= Duration::from_secs(60));
+        assert!(limiter.check_at(ip, after_boundary + retry_after + Duration::from_millis(1)).is_ok());
+        assert!(limiter.check_at(ip, start + Duration::from_secs(181)).is_ok());
+    }
+
+    #[test]
+    fn test_ipv4_mapped_ipv6_shares_budget() {
+        let limiter = limiter(1, false);
+        assert!(limiter.check("198.51.100.7".parse().unwrap()).is_ok());
+        assert!(limiter.check("::ffff:198.51.100.7".parse().unwrap()).is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_middleware_returns_429_with_retry_after() {
+        let app = init_service(
+            App::new()
+                .wrap(RateLimit::new(limiter(1, true)))
+                .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let request = || {
+            TestRequest::get()
+                .uri("/")
+                .insert_header(("X-Forwarded-For", "2001:db8::42, 10.0.0.1"))
+                .to_request()
+        };
+
+        assert_eq!(call_service(&app, request()).await.status(), 200);
+
+        let limited = call_service(&app, request()).await;
+        assert_eq!(limited.status(), 429);
+        assert!(limited.headers().contains_key("Retry-After"));
+    }
+}
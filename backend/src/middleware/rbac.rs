@@ -0,0 +1,141 @@
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{Error, HttpMessage, ResponseError};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+
+use crate::handlers::auth::Claims;
+use crate::handlers::errors::{ApiError, AuthError};
+use crate::models::user::Role;
+
+/// Requires the authenticated user to hold at least the given role, and for admin roles to
+/// have signed in with two-factor authentication.
+/// Must run inside `JwtMiddleware`, which stores the `Claims` this reads.
+pub struct RequireRole(pub Role);
+
+impl<S, B> Transform<S, ServiceRequest> for RequireRole
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RequireRoleService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireRoleService {
+            service: Rc::new(service),
+            required: self.0,
+        }))
+    }
+}
+
+pub struct RequireRoleService<S> {
+    service: Rc<S>,
+    required: Role,
+}
+
+impl<S, B> Service<ServiceRequest> for RequireRoleService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let claims = req.extensions().get::<Claims>().map(|claims| (claims.role, claims.totp_verified));
+
+        let error = match claims {
+            // Admin endpoints also need a session signed in with a second factor
+            Some((role, false)) if role.satisfies(self.required) && self.required.satisfies(Role::Admin) => {
+                tracing::warn!(?role, path = req.path(), "Denied admin access without two-factor authentication");
+                ApiError::SecondFactorRequired
+            }
+            Some((role, _)) if role.satisfies(self.required) => {
+                let service = Rc::clone(&self.service);
+                return Box::pin(async move {
+                    service.call(req).await.map(ServiceResponse::map_into_left_body)
+                });
+            }
+            Some((role, _)) => {
+                tracing::warn!(?role, path = req.path(), required = ?self.required, "Denied access");
+                ApiError::Forbidden(format!("Requires {:?} role or higher", self.required))
+            }
+            None => ApiError::Unauthorized(AuthError::MissingToken),
+        };
+
+        Box::pin(async move { Ok(req.into_response(error.error_response()).map_into_right_body()) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::auth::{AuthService, JwtConfig};
+    use crate::middleware::JwtMiddleware;
+    use crate::services::TokenBlacklist;
+    use actix_web::{test, web, App, HttpResponse};
+
+    const SECRET: &str = "test-secret";
+
+    async fn status_for(role: Role, totp_verified: bool, required: Role) -> u16 {
+        let config = JwtConfig::new(SECRET);
+        let token = AuthService::generate_access_token("user_1", "wallet_1", "session_1", role, totp_verified, &config)
+            .unwrap();
+        let app = test::init_service(
+            App::new().service(
+                web::scope("/restricted")
+                    .wrap(RequireRole(required))
+                    .wrap(JwtMiddleware::new(config, web::Data::new(TokenBlacklist::new())))
+                    .route("", web::get().to(HttpResponse::Ok)),
+            ),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri("/restricted")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        test::call_service(&app, request).await.status().as_u16()
+    }
+
+    #[actix_web::test]
+    async fn test_role_hierarchy_enforced() {
+        assert_eq!(status_for(Role::User, true, Role::Admin).await, 403);
+        assert_eq!(status_for(Role::Moderator, true, Role::Admin).await, 403);
+        assert_eq!(status_for(Role::Admin, true, Role::Admin).await, 200);
+        assert_eq!(status_for(Role::SuperAdmin, true, Role::Admin).await, 200);
+        assert_eq!(status_for(Role::Admin, true, Role::SuperAdmin).await, 403);
+    }
+
+    #[actix_web::test]
+    async fn test_admin_endpoints_require_two_factor_sessions() {
+        assert_eq!(status_for(Role::Admin, false, Role::Admin).await, 403);
+        assert_eq!(status_for(Role::SuperAdmin, false, Role::SuperAdmin).await, 403);
+        // Endpoints below admin accept sessions without a second factor
+        assert_eq!(status_for(Role::Admin, false, Role::Moderator).await, 200);
+        assert_eq!(status_for(Role::User, false, Role::User).await, 200);
+    }
+
+    #[actix_web::test]
+    async fn test_missing_claims_unauthorized() {
+        let app = test::init_service(
+            App::new().service(
+                web::scope("/restricted")
+                    .wrap(RequireRole(Role::User))
+                    .route("", web::get().to(HttpResponse::Ok)),
+            ),
+        )
+        .await;
+
+        let request = test::TestRequest::get().uri("/restricted").to_request();
+        assert_eq!(test::call_service(&app, request).await.status().as_u16(), 401);
+    }
+}
@@ -0,0 +1,156 @@
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::{Error, HttpMessage};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use tracing::Span;
+use uuid::Uuid;
+
+use super::request_tracing::{is_json_error, with_json_fields};
+
+/// Header a caller's request ID is read from, and returned in
+pub const REQUEST_ID_HEADER: &str = "X-Request-ID";
+
+/// Correlation ID of a request, stored as a request extension by `RequestIdMiddleware`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestId(pub Uuid);
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: Uuid;
+}
+
+/// ID of the request being handled, for errors raised away from the `HttpRequest`; nil
+/// outside `RequestIdMiddleware`
+pub fn current_request_id() -> Uuid {
+    CURRENT_REQUEST_ID.try_with(|request_id| *request_id).unwrap_or_else(|_| Uuid::nil())
+}
+
+/// Gives every request an ID: the caller's `X-Request-ID` when it is a UUID, a fresh one
+/// otherwise. The ID is stored as a `RequestId` extension, returned by `current_request_id`
+/// while the request is handled, recorded as the `request_id` of the current span and
+/// returned in the `X-Request-ID` response header. JSON error bodies get a `request_id`
+/// field too, so clients can quote it when reporting failures.
+///
+/// Wrap it directly inside `RequestTracing`, which opens the span it records into.
+pub struct RequestIdMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestIdMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Transform = RequestIdService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdService { service: Rc::new(service) }))
+    }
+}
+
+pub struct RequestIdService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| Uuid::parse_str(value.trim()).ok())
+            .unwrap_or_else(Uuid::new_v4);
+        req.extensions_mut().insert(RequestId(request_id));
+        Span::current().record("request_id", tracing::field::display(request_id));
+
+        let service = Rc::clone(&self.service);
+        Box::pin(async move {
+            let response = CURRENT_REQUEST_ID.scope(request_id, service.call(req)).await?;
+            let mut response = if is_json_error(&response) {
+                with_json_fields(response, |fields| {
+                    fields.insert("request_id".to_string(), request_id.to_string().into());
+                })
+                .await?
+            } else {
+                response.map_into_boxed_body()
+            };
+
+            if let Ok(value) = HeaderValue::from_str(&request_id.to_string()) {
+                response.headers_mut().insert(HeaderName::from_static("x-request-id"), value);
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::errors::ApiError;
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+    use serde_json::json;
+
+    fn request_id_of<B>(response: &ServiceResponse<B>) -> Uuid {
+        let header = response.headers().get(REQUEST_ID_HEADER).unwrap();
+        Uuid::parse_str(header.to_str().unwrap()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_request_ids_are_echoed_or_generated() {
+        let app = init_service(
+            App::new()
+                .wrap(RequestIdMiddleware)
+                .route("/ok", web::get().to(|| async { HttpResponse::Ok().json(json!({"success": true})) }))
+                .route(
+                    "/missing",
+                    web::get().to(|| async { HttpResponse::NotFound().json(json!({"success": false})) }),
+                )
+                .route("/failing", web::get().to(|| async { Err::<HttpResponse, _>(ApiError::internal()) })),
+        )
+        .await;
+
+        let sent = Uuid::new_v4();
+        let request = TestRequest::get()
+            .uri("/missing")
+            .insert_header((REQUEST_ID_HEADER, sent.to_string()))
+            .to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(request_id_of(&response), sent);
+        let body: serde_json::Value = read_body_json(response).await;
+        assert_eq!(body["request_id"], sent.to_string());
+
+        // A caller without an ID, or with one that is not a UUID, gets a fresh one
+        let response = call_service(&app, TestRequest::get().uri("/missing").to_request()).await;
+        let generated = request_id_of(&response);
+        let body: serde_json::Value = read_body_json(response).await;
+        assert_eq!(body["request_id"], generated.to_string());
+        let request = TestRequest::get().uri("/ok").insert_header((REQUEST_ID_HEADER, "abc")).to_request();
+        let response = call_service(&app, request).await;
+        assert_ne!(request_id_of(&response), generated);
+
+        // Successful responses only get the header
+        let body: serde_json::Value = read_body_json(response).await;
+        assert_eq!(body, json!({"success": true}));
+
+        // Errors raised while handling the request carry its ID
+        let response = call_service(&app, TestRequest::get().uri("/failing").to_request()).await;
+        let generated = request_id_of(&response);
+        let body: serde_json::Value = read_body_json(response).await;
+        assert_eq!(body["details"]["request_id"], generated.to_string());
+    }
+}
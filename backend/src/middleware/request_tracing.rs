@@ -0,0 +1,231 @@
+use actix_web::body::{self, BoxBody, MessageBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{self, HeaderMap};
+use actix_web::Error;
+use futures_util::future::LocalBoxFuture;
+use opentelemetry::propagation::Extractor;
+use opentelemetry::trace::TraceContextExt;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use tracing::field::Empty;
+use tracing::{Instrument, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Opens a server span around every request, recording `http.method`, `http.route` and
+/// `http.status_code`. Spans started by the handler become its children, and a W3C
+/// `traceparent` header from the caller makes it part of the caller's trace.
+///
+/// The span also carries the `request_id` that `RequestIdMiddleware` records, and the
+/// `user_id` that `JwtMiddleware` records once the caller is authenticated, so every log
+/// line of the request has both.
+///
+/// JSON error responses get the `trace_id` and `span_id` of the request, so a failure
+/// reported by a client can be looked up in the trace backend.
+pub struct RequestTracing;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestTracing
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Transform = RequestTracingService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestTracingService { service: Rc::new(service) }))
+    }
+}
+
+pub struct RequestTracingService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestTracingService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let method = req.method().to_string();
+        // The route is only known once routing has run, so the span name is set afterwards
+        let span = tracing::info_span!(
+            "http.request",
+            otel.name = %method,
+            otel.kind = "server",
+            otel.status_code = Empty,
+            http.method = %method,
+            http.route = Empty,
+            http.status_code = Empty,
+            request_id = Empty,
+            user_id = Empty,
+        );
+        let parent = opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.extract(&HeaderExtractor(req.headers()))
+        });
+        span.set_parent(parent);
+
+        let service = Rc::clone(&self.service);
+        Box::pin(
+            async move {
+                let response = service.call(req).await?;
+                let span = Span::current();
+
+                let route = response
+                    .request()
+                    .match_pattern()
+                    .unwrap_or_else(|| "unmatched".to_string());
+                let status = response.status();
+                span.record("otel.name", format!("{} {}", method, route));
+                span.record("http.route", route.as_str());
+                span.record("http.status_code", i64::from(status.as_u16()));
+                if status.is_server_error() {
+                    span.record("otel.status_code", "ERROR");
+                }
+
+                if !is_json_error(&response) {
+                    return Ok(response.map_into_boxed_body());
+                }
+                with_trace_ids(response, &span).await
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// Add the current `trace_id` and `span_id` to a JSON error body
+async fn with_trace_ids<B>(response: ServiceResponse<B>, span: &Span) -> Result<ServiceResponse<BoxBody>, Error>
+where
+    B: MessageBody + 'static,
+{
+    let context = span.context();
+    let span_context = context.span().span_context().clone();
+    if !span_context.is_valid() {
+        return Ok(response.map_into_boxed_body());
+    }
+
+    with_json_fields(response, |fields| {
+        fields.insert("trace_id".to_string(), span_context.trace_id().to_string().into());
+        fields.insert("span_id".to_string(), span_context.span_id().to_string().into());
+    })
+    .await
+}
+
+/// Add fields to a JSON object body. Other bodies are passed through unchanged.
+pub(super) async fn with_json_fields<B>(
+    response: ServiceResponse<B>,
+    extend: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+) -> Result<ServiceResponse<BoxBody>, Error>
+where
+    B: MessageBody + 'static,
+{
+    let (request, response) = response.into_parts();
+    let (response, body) = response.into_parts();
+    let bytes = body::to_bytes(body)
+        .await
+        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to read error response"))?;
+
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(serde_json::Value::Object(mut fields)) => {
+            extend(&mut fields);
+            serde_json::to_vec(&fields).unwrap_or_else(|_| bytes.to_vec()).into()
+        }
+        _ => bytes,
+    };
+
+    Ok(ServiceResponse::new(request, response.set_body(BoxBody::new(body))))
+}
+
+/// Whether the response is a 4xx or 5xx with a JSON body
+pub(super) fn is_json_error<B>(response: &ServiceResponse<B>) -> bool {
+    let status = response.status();
+    (status.is_client_error() || status.is_server_error()) && is_json(response.headers())
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+/// Reads W3C trace context headers from an incoming request
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::auth::{AuthService, WalletType};
+    use crate::services::mpc_wallet::MockMpcVerifier;
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry::Value;
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    async fn verify_wallet() -> HttpResponse {
+        let mpc = MockMpcVerifier::new();
+        match AuthService::verify_wallet_signature("short", "sig", "message", &WalletType::Phantom, &mpc).await {
+            Ok(_) => HttpResponse::Ok().finish(),
+            Err(e) => HttpResponse::Unauthorized().json(serde_json::json!({"success": false, "error": e})),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_request_spans_are_exported() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = init_service(
+            App::new()
+                .wrap(RequestTracing)
+                .route("/auth/{wallet}", web::post().to(verify_wallet)),
+        )
+        .await;
+        let response = call_service(&app, TestRequest::post().uri("/auth/short").to_request()).await;
+        assert_eq!(response.status(), 401);
+        let body: serde_json::Value = read_body_json(response).await;
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let request = spans.iter().find(|span| span.name == "POST /auth/{wallet}").unwrap();
+        let attribute = |key: &str| {
+            request.attributes.iter().find(|kv| kv.key.as_str() == key).map(|kv| kv.value.clone())
+        };
+        assert_eq!(attribute("http.method"), Some(Value::from("POST")));
+        assert_eq!(attribute("http.route"), Some(Value::from("/auth/{wallet}")));
+        assert_eq!(attribute("http.status_code"), Some(Value::I64(401)));
+
+        // The instrumented service call is a child of the request span
+        let verify = spans.iter().find(|span| span.name == "verify_wallet_signature").unwrap();
+        assert_eq!(verify.parent_span_id, request.span_context.span_id());
+        assert_eq!(verify.span_context.trace_id(), request.span_context.trace_id());
+
+        // The error body points back at the trace
+        assert_eq!(body["error"], "Invalid Solana wallet address: address must be 32 bytes, got 4");
+        assert_eq!(body["trace_id"], request.span_context.trace_id().to_string());
+        assert_eq!(body["span_id"], request.span_context.span_id().to_string());
+    }
+}
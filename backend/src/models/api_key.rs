@@ -0,0 +1,96 @@
+use actix_web::http::Method;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::{PgHasArrayType, PgTypeInfo};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// What a request authenticated with an API key may do. `Write` includes `Read`, and
+/// `Admin` additionally lets the key act with its owner's role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "api_key_permission", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[schema(example = "read")]
+pub enum Permission {
+    Read,
+    Write,
+    Admin,
+}
+
+impl PgHasArrayType for Permission {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_api_key_permission")
+    }
+}
+
+/// A credential a machine client uses in place of a wallet login, acting on behalf of
+/// the user who created it
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+pub struct ApiKey {
+    pub id: Uuid,
+    /// SHA-256 of the key, the only form in which it is stored
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub user_id: Uuid,
+    pub name: String,
+    pub permissions: Vec<Permission>,
+    #[sqlx(try_from = "i32")]
+    pub rate_limit_per_minute: u32,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Whether the key may make a request with this method. Safe methods need `read`,
+    /// anything that changes state needs `write`.
+    pub fn allows(&self, method: &Method) -> bool {
+        let read_only = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+        self.permissions.iter().any(|permission| match permission {
+            Permission::Read => read_only,
+            Permission::Write | Permission::Admin => true,
+        })
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(permissions: Vec<Permission>, expires_at: Option<DateTime<Utc>>) -> ApiKey {
+        ApiKey {
+            id: Uuid::new_v4(),
+            key_hash: "a".repeat(64),
+            user_id: Uuid::new_v4(),
+            name: "ci".to_string(),
+            permissions,
+            rate_limit_per_minute: 60,
+            last_used_at: None,
+            expires_at,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_permissions_gate_methods() {
+        let read = key(vec![Permission::Read], None);
+        assert!(read.allows(&Method::GET));
+        assert!(!read.allows(&Method::POST));
+        assert!(key(vec![Permission::Write], None).allows(&Method::DELETE));
+        assert!(!key(Vec::new(), None).allows(&Method::GET));
+
+        let now = Utc::now();
+        assert!(!read.is_expired(now));
+        assert!(key(vec![Permission::Read], Some(now)).is_expired(now));
+        assert_eq!(serde_json::to_value(&read).unwrap().get("key_hash"), None);
+    }
+}
@@ -1,22 +1,168 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::collections::HashMap;
+use std::fmt;
+use utoipa::ToSchema;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+use super::content_version::ContentVersion;
+use super::echo_index::{EchoIndexCalculator, WeightError};
+use super::echo_index_history::EchoIndexHistory;
+use super::Platform;
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Content {
     pub id: Uuid,
     pub author_id: Uuid,
     pub text: String,
-    pub platform: String,
+    pub platform: Platform,
     pub original_url: String,
     pub echo_index: EchoIndex,
     pub propagation_count: i32,
     pub total_interactions: i32,
+    /// Value of the `content_status` enum, e.g. `active` or `under_review`
+    pub status: String,
+    /// Whether the author verified their account on the content's platform
+    #[serde(default)]
+    pub author_verified: bool,
+    /// TPM bonus for being tagged with a currently trending hashtag
+    #[serde(default)]
+    pub trending_hashtag_bonus: f64,
+    /// ODF bonus of a verified author's content for reaching a different audience on the
+    /// platforms it was cross-posted to
+    #[serde(default)]
+    pub cross_platform_bonus: f64,
+    /// Mean normalized strength of the content's active Echo Loops
+    #[serde(default)]
+    pub echo_loop_strength: f64,
+    /// Content the author published before this one
+    #[serde(default)]
+    pub author_content_count: u32,
+    #[serde(default)]
+    pub category: ContentCategory,
+    /// Reactions on the content's platform, by type
+    #[serde(default)]
+    pub reactions: HashMap<ReactionType, u64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// What kind of content a piece is, which decides how quickly its propagation loses
+/// resonance
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentCategory {
+    BreakingNews,
+    Analysis,
+    Tutorial,
+    Entertainment,
+    #[default]
+    Other,
+}
+
+impl ContentCategory {
+    /// Daily decay factor suited to the category, or `None` for the propagation service
+    /// default. News is stale within days while analysis and tutorials stay relevant.
+    pub fn decay_factor(self) -> Option<f64> {
+        match self {
+            ContentCategory::BreakingNews => Some(0.7),
+            ContentCategory::Analysis | ContentCategory::Tutorial => Some(0.98),
+            ContentCategory::Entertainment => Some(0.85),
+            ContentCategory::Other => None,
+        }
+    }
+}
+
+/// How someone reacted to content. Reactions signal engagement of different depth: a
+/// quote with commentary says more than a plain repost, and an insightful reaction marks
+/// content people learned from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(example = "like")]
+pub enum ReactionType {
+    Like,
+    Love,
+    Insightful,
+    Celebrate,
+    Save,
+    Bookmark,
+    Repost,
+    QuoteComment,
+    ThreadReply,
+}
+
+impl ReactionType {
+    pub const ALL: [ReactionType; 9] = [
+        ReactionType::Like,
+        ReactionType::Love,
+        ReactionType::Insightful,
+        ReactionType::Celebrate,
+        ReactionType::Save,
+        ReactionType::Bookmark,
+        ReactionType::Repost,
+        ReactionType::QuoteComment,
+        ReactionType::ThreadReply,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReactionType::Like => "like",
+            ReactionType::Love => "love",
+            ReactionType::Insightful => "insightful",
+            ReactionType::Celebrate => "celebrate",
+            ReactionType::Save => "save",
+            ReactionType::Bookmark => "bookmark",
+            ReactionType::Repost => "repost",
+            ReactionType::QuoteComment => "quote_comment",
+            ReactionType::ThreadReply => "thread_reply",
+        }
+    }
+
+    /// Reactions a platform offers; every type for platforms without a mapping
+    pub fn supported_on(platform: &Platform) -> &'static [ReactionType] {
+        use ReactionType::*;
+        match platform {
+            Platform::Twitter => &[Like, Repost, QuoteComment, ThreadReply, Bookmark],
+            Platform::LinkedIn => &[Like, Celebrate, Love, Insightful, Repost, QuoteComment, ThreadReply, Save],
+            Platform::Farcaster => &[Like, Repost, QuoteComment, ThreadReply],
+            Platform::Reddit => &[Like, Repost, ThreadReply, Save],
+            Platform::Medium => &[Like, ThreadReply, Bookmark],
+            Platform::Telegram | Platform::Discord => &[Like, Love, Repost, ThreadReply],
+            Platform::Other(_) => &Self::ALL,
+        }
+    }
+
+    /// A reaction by its name here or its native name on the platform, e.g. `retweet` on
+    /// Twitter or `praise` on LinkedIn. None if unknown or not offered by the platform.
+    pub fn parse_for(platform: &Platform, name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        let native = match (platform, name.as_str()) {
+            (Platform::Twitter, "favorite") => Some(ReactionType::Like),
+            (Platform::Twitter, "retweet") | (Platform::Farcaster, "recast") | (Platform::Reddit, "crosspost") => {
+                Some(ReactionType::Repost)
+            }
+            (Platform::Twitter, "quote") | (Platform::Farcaster, "quote") => Some(ReactionType::QuoteComment),
+            (Platform::LinkedIn, "praise") => Some(ReactionType::Celebrate),
+            (Platform::LinkedIn, "empathy") => Some(ReactionType::Love),
+            (Platform::LinkedIn, "interest") => Some(ReactionType::Insightful),
+            (Platform::Reddit, "upvote") | (Platform::Medium, "clap") => Some(ReactionType::Like),
+            (Platform::Telegram, "forward") => Some(ReactionType::Repost),
+            (_, "reply" | "comment" | "response") => Some(ReactionType::ThreadReply),
+            _ => None,
+        };
+        native
+            .or_else(|| Self::ALL.into_iter().find(|reaction| reaction.as_str() == name))
+            .filter(|reaction| Self::supported_on(platform).contains(reaction))
+    }
+}
+
+impl fmt::Display for ReactionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EchoIndex {
     pub originality_depth_factor: f64,
@@ -26,18 +172,124 @@ pub struct EchoIndex {
     pub overall_score: f64,
 }
 
+/// A row of the `content` table
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ContentRecord {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub platform: Platform,
+    pub external_id: String,
+    pub content_type: String,
+    pub title: String,
+    pub body: String,
+    pub media_urls: Vec<String>,
+    pub tags: Vec<String>,
+    /// Topic tags extracted from the title and body, apart from the author's `tags`
+    #[serde(default)]
+    pub auto_tags: Vec<String>,
+    pub echo_index: f64,
+    pub propagation_count: i32,
+    pub total_rewards: f64,
+    pub status: String,
+    /// Reactions on the content's platform, by type
+    #[serde(default)]
+    #[sqlx(json)]
+    pub reactions: HashMap<ReactionType, u64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A content item moved to cold storage together with everything deleted alongside it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedContent {
+    pub content: ContentRecord,
+    pub platform_metadata: serde_json::Value,
+    pub echo_components: serde_json::Value,
+    /// Echo Index calculations, oldest first
+    pub echo_history: Vec<EchoIndexHistory>,
+    /// Raw `propagations` rows, oldest first
+    pub propagations: Vec<serde_json::Value>,
+    /// Versions replaced by edits, oldest first. Missing from items archived before
+    /// versions were kept.
+    #[serde(default)]
+    pub versions: Vec<ContentVersion>,
+    pub archived_at: DateTime<Utc>,
+}
+
+/// Size of the live and archived content sets
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ArchiveStats {
+    pub live_content: i64,
+    pub archived_content: i64,
+    /// Bytes of archived JSON before compression
+    pub uncompressed_bytes: i64,
+    pub compressed_bytes: i64,
+}
+
+impl ArchiveStats {
+    pub fn saved_bytes(&self) -> i64 {
+        self.uncompressed_bytes - self.compressed_bytes
+    }
+
+    /// Compressed size as a fraction of the original; 0 when nothing is archived
+    pub fn compression_ratio(&self) -> f64 {
+        if self.uncompressed_bytes == 0 {
+            return 0.0;
+        }
+        self.compressed_bytes as f64 / self.uncompressed_bytes as f64
+    }
+}
+
+/// A content row matched by full-text search
+#[derive(Debug, Clone, FromRow)]
+pub struct ContentSearchHit {
+    #[sqlx(flatten)]
+    pub content: ContentRecord,
+    /// `ts_rank` of the row against the search query
+    pub relevance_score: f64,
+}
+
+/// Column an author's content listing is sorted by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(example = "echo_index")]
+pub enum ContentSort {
+    #[default]
+    EchoIndex,
+    CreatedAt,
+    PropagationCount,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[schema(example = "desc")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Totals over all of an author's live content matching a listing's filters
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UserContentStats {
+    pub avg_echo_index: f64,
+    pub max_echo_index: f64,
+    pub total_propagations: u64,
+    pub total_rewards: f64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateContentRequest {
     pub text: String,
-    pub platform: String,
+    pub platform: Platform,
     pub original_url: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
 pub struct ContentSummary {
     pub id: Uuid,
     pub text: String,
-    pub platform: String,
+    pub platform: Platform,
     pub echo_score: f64,
     pub propagation_count: i32,
     pub created_at: DateTime<Utc>,
@@ -49,15 +301,64 @@ pub struct Propagation {
     pub content_id: Uuid,
     pub from_user_id: Uuid,
     pub to_user_id: Option<Uuid>,
-    pub platform: String,
+    pub platform: Platform,
     pub propagation_type: String,
     pub depth: i32,
     pub weight: f64,
     pub timestamp: DateTime<Utc>,
+    #[serde(default)]
+    #[sqlx(default)]
+    pub reach: i64,
+    #[serde(default)]
+    #[sqlx(default)]
+    pub engagement: i64,
+    /// Likelihood the propagation was machine generated (0.0 = human, 1.0 = bot)
+    #[serde(default)]
+    #[sqlx(default)]
+    pub bot_score: f64,
+    /// Whether the propagation was checked for duplicates when it was stored
+    #[serde(default)]
+    #[sqlx(default)]
+    pub fingerprint_checked: bool,
+    /// Earlier propagation of the same event, stored before the deduplication window
+    #[serde(default)]
+    #[sqlx(default)]
+    pub duplicate_of: Option<Uuid>,
+}
+
+impl From<ContentRecord> for Content {
+    fn from(record: ContentRecord) -> Self {
+        let text = if record.title.is_empty() {
+            record.body
+        } else {
+            format!("{}\n\n{}", record.title, record.body)
+        };
+
+        Self {
+            id: record.id,
+            author_id: record.user_id,
+            text,
+            platform: record.platform,
+            original_url: String::new(),
+            echo_index: EchoIndex::default(),
+            propagation_count: record.propagation_count,
+            total_interactions: 0,
+            status: record.status,
+            author_verified: false,
+            trending_hashtag_bonus: 0.0,
+            cross_platform_bonus: 0.0,
+            echo_loop_strength: 0.0,
+            author_content_count: 0,
+            category: ContentCategory::default(),
+            reactions: record.reactions,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+        }
+    }
 }
 
 impl Content {
-    pub fn new(author_id: Uuid, text: String, platform: String, original_url: String) -> Self {
+    pub fn new(author_id: Uuid, text: String, platform: Platform, original_url: String) -> Self {
         let now = Utc::now();
         Self {
             id: Uuid::new_v4(),
@@ -68,6 +369,14 @@ impl Content {
             echo_index: EchoIndex::default(),
             propagation_count: 0,
             total_interactions: 0,
+            status: "active".to_string(),
+            author_verified: false,
+            trending_hashtag_bonus: 0.0,
+            cross_platform_bonus: 0.0,
+            echo_loop_strength: 0.0,
+            author_content_count: 0,
+            category: ContentCategory::default(),
+            reactions: HashMap::new(),
             created_at: now,
             updated_at: now,
         }
@@ -107,6 +416,7 @@ impl Default for EchoIndex {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct EchoIndexWeights {
     pub odf: f64,
     pub awr: f64,
@@ -114,6 +424,18 @@ pub struct EchoIndexWeights {
     pub qf: f64,
 }
 
+impl EchoIndexWeights {
+    /// A calculator scoring with these weights, if they are valid
+    pub fn calculator(&self) -> Result<EchoIndexCalculator, WeightError> {
+        EchoIndexCalculator::builder()
+            .odf_weight(self.odf)
+            .awr_weight(self.awr)
+            .tpm_weight(self.tpm)
+            .qf_weight(self.qf)
+            .build()
+    }
+}
+
 impl Default for EchoIndexWeights {
     fn default() -> Self {
         Self {
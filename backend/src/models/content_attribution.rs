@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Platform;
+
+/// How a secondary content item relates to the primary it is attributed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "attribution_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[schema(example = "cross_post")]
+pub enum AttributionType {
+    /// The same content posted again on another platform
+    CrossPost,
+    Quote,
+    Repost,
+    Translation,
+}
+
+/// A secondary content item attributed to the primary it is the same content as
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow, ToSchema)]
+pub struct ContentAttribution {
+    pub id: Uuid,
+    pub primary_content_id: Uuid,
+    pub secondary_content_id: Uuid,
+    pub link_type: AttributionType,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A content item in an attribution graph, at its own Echo Index
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow, ToSchema)]
+pub struct AttributedContent {
+    pub content_id: Uuid,
+    pub platform: Platform,
+    pub echo_index: f64,
+    /// How the item relates to the primary; None for the primary itself
+    pub link_type: Option<AttributionType>,
+    pub linked_at: Option<DateTime<Utc>>,
+}
+
+/// A primary content item and everything attributed to it
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct AttributionGraph {
+    pub primary: AttributedContent,
+    /// Oldest link first
+    pub secondaries: Vec<AttributedContent>,
+    /// The primary's own Echo Index plus its share of each secondary's
+    pub aggregate_echo_index: f64,
+}
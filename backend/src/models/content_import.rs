@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::Platform;
+
+/// Kinds of export content can be imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    /// `tweet.js` from a Twitter archive
+    TwitterArchive,
+    /// `Shares.csv` from a LinkedIn data export
+    #[serde(rename = "linkedin_data_export")]
+    LinkedInDataExport,
+    /// `result.json` of a Telegram Desktop chat export
+    TelegramExport,
+    /// CSV with `external_id`, `text` and `created_at` columns, and optionally `reshares`
+    /// and `likes`, for any platform
+    #[serde(rename = "generic_csv")]
+    GenericCSV,
+}
+
+impl ImportFormat {
+    /// The platform the format comes from, or None for generic CSV
+    pub fn platform(self) -> Option<Platform> {
+        match self {
+            ImportFormat::TwitterArchive => Some(Platform::Twitter),
+            ImportFormat::LinkedInDataExport => Some(Platform::LinkedIn),
+            ImportFormat::TelegramExport => Some(Platform::Telegram),
+            ImportFormat::GenericCSV => None,
+        }
+    }
+
+    /// The payload of an uploaded export file: parsed JSON, or the text of a CSV file
+    pub fn payload_from_file(self, file: &[u8]) -> Result<Value, String> {
+        let text = std::str::from_utf8(file).map_err(|_| "export file must be UTF-8 text".to_string())?;
+        match self {
+            ImportFormat::TwitterArchive | ImportFormat::TelegramExport => {
+                // Twitter archives wrap the JSON in `window.YTD.tweets.part0 = ...`
+                let start = text.find(['[', '{']).ok_or_else(|| "export file must contain JSON".to_string())?;
+                serde_json::from_str(&text[start..]).map_err(|e| format!("export file is not valid JSON: {}", e))
+            }
+            ImportFormat::LinkedInDataExport | ImportFormat::GenericCSV => Ok(Value::String(text.to_string())),
+        }
+    }
+}
+
+/// An export to import content from
+#[derive(Debug, Clone)]
+pub struct ContentImportRequest {
+    pub platform: Platform,
+    pub format: ImportFormat,
+    /// The export's JSON, or a string holding CSV
+    pub payload: Value,
+}
+
+/// A post found in an export
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedContent {
+    /// The post's ID on its platform
+    pub external_id: String,
+    pub text: String,
+    /// When the post was originally published
+    pub created_at: DateTime<Utc>,
+    /// Retweets, forwards or other reshares, where the export records them
+    pub reshares: u64,
+    pub likes: u64,
+}
+
+/// Posts an export holds, and how many of its entries could not be read
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedExport {
+    pub content: Vec<ImportedContent>,
+    pub failed: usize,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ImportSummary {
+    pub imported: usize,
+    /// Posts already on EchoLayer, imported before or added by hand
+    pub skipped_duplicates: usize,
+    /// Entries that could not be read or scored
+    pub failed: usize,
+}
@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Title and body of a content item as they were before an edit replaced them
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ContentVersion {
+    pub id: Uuid,
+    pub content_id: Uuid,
+    pub version_number: i32,
+    pub title: String,
+    pub body: String,
+    /// Who made the edit that replaced this version, and when
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+    pub change_summary: String,
+    /// Echo Index of the content when it was edited
+    pub echo_index: f64,
+    /// When the edit was flagged for moderation after the Echo Index fell sharply
+    pub flagged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(example = "added")]
+pub enum DiffOp {
+    Added,
+    Removed,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub line: String,
+}
+
+/// Line diff of the bodies of two versions of a content item
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VersionDiff {
+    pub content_id: Uuid,
+    pub from: i32,
+    pub to: i32,
+    pub from_title: String,
+    pub to_title: String,
+    pub added: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+impl VersionDiff {
+    /// `from` and `to` are the `(title, body)` of the two versions
+    pub fn new(content_id: Uuid, from: (i32, &str, &str), to: (i32, &str, &str)) -> Self {
+        let lines = diff_lines(from.2, to.2);
+        let count = |op| lines.iter().filter(|line| line.op == op).count();
+
+        Self {
+            content_id,
+            from: from.0,
+            to: to.0,
+            from_title: from.1.to_string(),
+            to_title: to.1.to_string(),
+            added: count(DiffOp::Added),
+            removed: count(DiffOp::Removed),
+            unchanged: count(DiffOp::Unchanged),
+            lines,
+        }
+    }
+}
+
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| DiffLine {
+            op: match change.tag() {
+                ChangeTag::Insert => DiffOp::Added,
+                ChangeTag::Delete => DiffOp::Removed,
+                ChangeTag::Equal => DiffOp::Unchanged,
+            },
+            line: change.value().trim_end_matches(['\r', '\n']).to_string(),
+        })
+        .collect()
+}
+
+/// One-line description of an edit, such as "title changed, 2 lines added, 1 removed"
+pub fn change_summary(old_title: &str, old_body: &str, new_title: &str, new_body: &str) -> String {
+    let lines = diff_lines(old_body, new_body);
+    let added = lines.iter().filter(|line| line.op == DiffOp::Added).count();
+    let removed = lines.iter().filter(|line| line.op == DiffOp::Removed).count();
+
+    let mut changes = Vec::new();
+    if old_title != new_title {
+        changes.push("title changed".to_string());
+    }
+    if added > 0 || removed > 0 {
+        let plural = if added == 1 { "line" } else { "lines" };
+        changes.push(format!("{} {} added, {} removed", added, plural, removed));
+    }
+    if changes.is_empty() {
+        return "no text changes".to_string();
+    }
+    changes.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_counts_added_removed_and_unchanged_lines() {
+        let old = "intro\nclaim: up 10%\noutro\n";
+        let new = "intro\nclaim: up 40%\nsource: trust me\noutro\n";
+        let diff = VersionDiff::new(Uuid::nil(), (1, "Gains", old), (3, "Huge gains", new));
+
+        assert_eq!((diff.added, diff.removed, diff.unchanged), (2, 1, 2));
+        assert_eq!(
+            diff.lines.iter().map(|line| (line.op, line.line.as_str())).collect::<Vec<_>>(),
+            vec![
+                (DiffOp::Unchanged, "intro"),
+                (DiffOp::Removed, "claim: up 10%"),
+                (DiffOp::Added, "claim: up 40%"),
+                (DiffOp::Added, "source: trust me"),
+                (DiffOp::Unchanged, "outro"),
+            ]
+        );
+        assert_eq!(change_summary("Gains", old, "Huge gains", new), "title changed, 2 lines added, 1 removed");
+        assert_eq!(change_summary("Gains", old, "Gains", old), "no text changes");
+    }
+}
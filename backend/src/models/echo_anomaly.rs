@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A reason an Echo Index jump looks manipulated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum AnomalyFlag {
+    /// The score rose by more points than it may in an hour, at this many points per hour
+    ScoreJumpTooFast { rate_per_hour: f64 },
+    /// The content outscores every user who propagated it
+    ScoreHigherThanAllPropagators,
+    /// Originality rose further than a recalculation can move it
+    OdfOvertaken { new_odf: f64, expected_max: f64 },
+    /// Quote frequency rose sharply at once
+    QfSpike,
+}
+
+/// Where an anomaly is in operator review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "anomaly_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyStatus {
+    /// Awaiting review; the content's rewards are held
+    Open,
+    /// An operator found the jump legitimate and released the rewards
+    Dismissed,
+    /// An operator found the jump manipulated and froze the rewards
+    Confirmed,
+}
+
+/// An Echo Index jump flagged for operator review. Scores are on the 0-100 scale.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EchoAnomaly {
+    pub id: Uuid,
+    pub content_id: Uuid,
+    pub previous_score: f64,
+    pub new_score: f64,
+    pub time_delta_seconds: i64,
+    pub flags: Vec<AnomalyFlag>,
+    pub status: AnomalyStatus,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub detected_at: DateTime<Utc>,
+}
@@ -1,5 +1,9 @@
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use super::Platform;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EchoMetrics {
@@ -7,20 +11,27 @@ pub struct EchoMetrics {
     pub word_count: usize,
     pub unique_words: usize,
     pub sentiment_score: f64,
+    /// Flesch Reading Ease normalized to [0, 1]
     pub readability_score: f64,
+    /// Flesch-Kincaid Grade Level
+    #[serde(default)]
+    pub grade_level: f64,
     pub originality_markers: Vec<String>,
+    /// ISO 639-3 code of the language the content is written in
+    #[serde(default)]
+    pub detected_language: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PropagationMetrics {
     pub total_propagations: i32,
     pub unique_propagators: i32,
-    pub platform_distribution: HashMap<String, i32>,
+    pub platform_distribution: HashMap<Platform, i32>,
     pub propagation_velocity: f64,
     pub network_reach: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudienceMetrics {
     pub total_interactions: i32,
     pub quality_interactions: i32,
@@ -37,9 +48,144 @@ pub struct QuoteMetrics {
     pub citation_quality: f64,
 }
 
-pub struct EchoIndexCalculator;
+/// Default component weights, summing to 1.0
+const DEFAULT_ODF_WEIGHT: f64 = 0.30;
+const DEFAULT_AWR_WEIGHT: f64 = 0.25;
+const DEFAULT_TPM_WEIGHT: f64 = 0.25;
+const DEFAULT_QF_WEIGHT: f64 = 0.20;
+
+/// ODF multiplier for content whose author verified their account on its platform
+pub const VERIFIED_ACCOUNT_ODF_BONUS: f64 = 1.10;
+
+/// ODF a verified author's cross-post earns per tenfold difference in reach between its
+/// platforms, up to `MAX_CROSS_PLATFORM_ODF_BONUS`
+const CROSS_PLATFORM_ODF_BONUS_PER_DECADE: f64 = 0.05;
+pub const MAX_CROSS_PLATFORM_ODF_BONUS: f64 = 0.10;
+/// TPM a content's Echo Loops add at a normalized loop strength of 1.0
+pub const ECHO_LOOP_TPM_WEIGHT: f64 = 0.1;
+
+/// Allowed deviation of the weight sum from 1.0
+const WEIGHT_SUM_TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum WeightError {
+    #[error("weight `{0}` must be a finite, non-negative number")]
+    InvalidWeight(&'static str),
+    #[error("weights must sum to 1.0, got {0}")]
+    InvalidSum(f64),
+}
+
+#[derive(Debug, Clone)]
+pub struct EchoIndexCalculator {
+    odf_weight: f64,
+    awr_weight: f64,
+    tpm_weight: f64,
+    qf_weight: f64,
+}
+
+/// Builder for an `EchoIndexCalculator` with validated component weights
+#[derive(Debug, Clone)]
+pub struct EchoIndexCalculatorBuilder {
+    odf_weight: f64,
+    awr_weight: f64,
+    tpm_weight: f64,
+    qf_weight: f64,
+}
+
+impl EchoIndexCalculatorBuilder {
+    pub fn new() -> Self {
+        Self {
+            odf_weight: DEFAULT_ODF_WEIGHT,
+            awr_weight: DEFAULT_AWR_WEIGHT,
+            tpm_weight: DEFAULT_TPM_WEIGHT,
+            qf_weight: DEFAULT_QF_WEIGHT,
+        }
+    }
+
+    pub fn odf_weight(mut self, weight: f64) -> Self {
+        self.odf_weight = weight;
+        self
+    }
+
+    pub fn awr_weight(mut self, weight: f64) -> Self {
+        self.awr_weight = weight;
+        self
+    }
+
+    pub fn tpm_weight(mut self, weight: f64) -> Self {
+        self.tpm_weight = weight;
+        self
+    }
+
+    pub fn qf_weight(mut self, weight: f64) -> Self {
+        self.qf_weight = weight;
+        self
+    }
+
+    /// Validate the weights and build the calculator
+    pub fn build(self) -> Result<EchoIndexCalculator, WeightError> {
+        let weights = [
+            ("odf", self.odf_weight),
+            ("awr", self.awr_weight),
+            ("tpm", self.tpm_weight),
+            ("qf", self.qf_weight),
+        ];
+
+        for (name, weight) in weights {
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(WeightError::InvalidWeight(name));
+            }
+        }
+
+        let sum: f64 = weights.iter().map(|(_, weight)| weight).sum();
+        if (sum - 1.0).abs() > WEIGHT_SUM_TOLERANCE {
+            return Err(WeightError::InvalidSum(sum));
+        }
+
+        Ok(EchoIndexCalculator {
+            odf_weight: self.odf_weight,
+            awr_weight: self.awr_weight,
+            tpm_weight: self.tpm_weight,
+            qf_weight: self.qf_weight,
+        })
+    }
+}
+
+impl Default for EchoIndexCalculatorBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for EchoIndexCalculator {
+    fn default() -> Self {
+        EchoIndexCalculatorBuilder::new()
+            .build()
+            .expect("default Echo Index weights must sum to 1.0")
+    }
+}
 
 impl EchoIndexCalculator {
+    pub fn builder() -> EchoIndexCalculatorBuilder {
+        EchoIndexCalculatorBuilder::new()
+    }
+
+    pub fn odf_weight(&self) -> f64 {
+        self.odf_weight
+    }
+
+    pub fn awr_weight(&self) -> f64 {
+        self.awr_weight
+    }
+
+    pub fn tpm_weight(&self) -> f64 {
+        self.tpm_weight
+    }
+
+    pub fn qf_weight(&self) -> f64 {
+        self.qf_weight
+    }
+
     /// Calculate Originality Depth Factor (ODF)
     pub fn calculate_odf(content: &str, metrics: &EchoMetrics) -> f64 {
         let mut score = 0.0;
@@ -53,10 +199,59 @@ impl EchoIndexCalculator {
         score += uniqueness_ratio * 0.3;
 
         // Sentiment and readability contribution
-        score += (metrics.sentiment_score.abs() * 0.15);
-        score += (metrics.readability_score * 0.15);
+        score += metrics.sentiment_score.abs() * 0.15;
+        score += metrics.readability_score * 0.15;
+
+        score.clamp(0.0, 1.0)
+    }
+
+    /// Weight ODF by how human its propagations look, each contributing `1.0 - bot_score`.
+    /// Content that has not propagated yet keeps its full score.
+    pub fn discount_bot_propagation(odf: f64, bot_scores: &[f64]) -> f64 {
+        if bot_scores.is_empty() {
+            return odf;
+        }
+
+        let human_share = bot_scores
+            .iter()
+            .map(|bot_score| 1.0 - bot_score.clamp(0.0, 1.0))
+            .sum::<f64>()
+            / bot_scores.len() as f64;
+
+        odf * human_share
+    }
+
+    /// Raise ODF by `VERIFIED_ACCOUNT_ODF_BONUS` when the author proved they own the
+    /// account the content was posted from, capped at 1.0
+    pub fn apply_verified_account_bonus(odf: f64, author_verified: bool) -> f64 {
+        if author_verified {
+            (odf * VERIFIED_ACCOUNT_ODF_BONUS).min(1.0)
+        } else {
+            odf
+        }
+    }
+
+    /// ODF bonus of cross-posted content: the further the reach on the two platforms differs,
+    /// the more the content found an audience it would not have found on one of them
+    pub fn cross_platform_bonus(own_reach: u64, linked_reach: u64) -> f64 {
+        let decades = ((linked_reach as f64).ln_1p() - (own_reach as f64).ln_1p()).abs() / std::f64::consts::LN_10;
+        (decades * CROSS_PLATFORM_ODF_BONUS_PER_DECADE).min(MAX_CROSS_PLATFORM_ODF_BONUS)
+    }
+
+    /// Raise ODF by a cross-platform bonus, capped at 1.0
+    pub fn apply_cross_platform_bonus(odf: f64, bonus: f64) -> f64 {
+        (odf + bonus).min(1.0)
+    }
+
+    /// Raise TPM by `bonus` while the content is tagged with a trending hashtag, capped at 1.0
+    pub fn apply_trending_hashtag_bonus(tpm: f64, bonus: f64) -> f64 {
+        (tpm + bonus).min(1.0)
+    }
 
-        score.min(1.0).max(0.0)
+    /// Raise TPM by the contribution of the content's Echo Loops, capped at 1.0. Loop
+    /// strength is normalized by network size, so large networks are not favoured.
+    pub fn apply_echo_loop_contribution(tpm: f64, normalized_loop_strength: f64) -> f64 {
+        (tpm + normalized_loop_strength.clamp(0.0, 1.0) * ECHO_LOOP_TPM_WEIGHT).min(1.0)
     }
 
     /// Calculate Audience Weight Rating (AWR)
@@ -80,7 +275,7 @@ impl EchoIndexCalculator {
         // Engagement depth
         score += audience_metrics.engagement_depth * 0.1;
 
-        score.min(1.0).max(0.0)
+        score.clamp(0.0, 1.0)
     }
 
     /// Calculate Transmission Path Mapping (TPM)
@@ -99,7 +294,7 @@ impl EchoIndexCalculator {
         let platform_diversity = propagation_metrics.platform_distribution.len() as f64 / 10.0;
         score += platform_diversity.min(0.3);
 
-        score.min(1.0).max(0.0)
+        score.clamp(0.0, 1.0)
     }
 
     /// Calculate Quote Frequency (QF)
@@ -117,12 +312,12 @@ impl EchoIndexCalculator {
         let discussion_factor = (quote_metrics.discussion_threads as f64).ln() / 5.0;
         score += discussion_factor.min(0.3);
 
-        score.min(1.0).max(0.0)
+        score.clamp(0.0, 1.0)
     }
 
     /// Calculate overall Echo Index score
-    pub fn calculate_overall_score(odf: f64, awr: f64, tpm: f64, qf: f64) -> f64 {
-        (odf * 0.3) + (awr * 0.25) + (tpm * 0.25) + (qf * 0.2)
+    pub fn calculate_overall_score(&self, odf: f64, awr: f64, tpm: f64, qf: f64) -> f64 {
+        (odf * self.odf_weight) + (awr * self.awr_weight) + (tpm * self.tpm_weight) + (qf * self.qf_weight)
     }
 
     /// Analyze content originality using simple heuristics
@@ -138,10 +333,180 @@ impl EchoIndexCalculator {
     }
 }
 
+/// z-value of a two-sided 95% interval
+const Z_95: f64 = 1.96;
+/// Resamples drawn when bootstrapping an interval
+pub const BOOTSTRAP_RESAMPLES: usize = 100;
+
+/// How far a score can be trusted, by the number of propagations it rests on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(example = "high")]
+pub enum ConfidenceLevel {
+    Low,
+    Medium,
+    High,
+    VeryHigh,
+}
+
+impl ConfidenceLevel {
+    /// `Low` below 30 propagations, `Medium` below 100, `High` below 500
+    pub fn from_sample_size(sample_size: u32) -> Self {
+        match sample_size {
+            0..=29 => ConfidenceLevel::Low,
+            30..=99 => ConfidenceLevel::Medium,
+            100..=499 => ConfidenceLevel::High,
+            _ => ConfidenceLevel::VeryHigh,
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "low" => Some(ConfidenceLevel::Low),
+            "medium" => Some(ConfidenceLevel::Medium),
+            "high" => Some(ConfidenceLevel::High),
+            "very_high" => Some(ConfidenceLevel::VeryHigh),
+            _ => None,
+        }
+    }
+}
+
+/// An Echo Index score with its 95% confidence interval
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct EchoIndexConfidence {
+    pub score: f64,
+    pub lower_95: f64,
+    pub upper_95: f64,
+    /// Propagations the score was calculated from
+    pub sample_size: u32,
+    pub confidence_level: ConfidenceLevel,
+}
+
+impl EchoIndexConfidence {
+    /// The interval is widened where needed to contain the score
+    pub fn new(score: f64, lower_95: f64, upper_95: f64, sample_size: u32) -> Self {
+        Self {
+            score,
+            lower_95: lower_95.min(score),
+            upper_95: upper_95.max(score),
+            sample_size,
+            confidence_level: ConfidenceLevel::from_sample_size(sample_size),
+        }
+    }
+}
+
+/// Wilson score interval of `successes` out of `trials` at 95% confidence; without
+/// trials the proportion could be anything
+pub fn wilson_interval(successes: u32, trials: u32) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+
+    let n = trials as f64;
+    let p = successes.min(trials) as f64 / n;
+    let z2 = Z_95 * Z_95;
+    let denominator = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denominator;
+    let half_width = Z_95 / denominator * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+    ((center - half_width).max(0.0), (center + half_width).min(1.0))
+}
+
+/// 2.5th and 97.5th percentiles of `statistic` over `resamples` resamples, with
+/// replacement, of `sample`
+pub fn bootstrap_interval<T: Clone>(
+    sample: &[T],
+    resamples: usize,
+    rng: &mut impl Rng,
+    statistic: impl Fn(&[T]) -> f64,
+) -> (f64, f64) {
+    if sample.is_empty() || resamples == 0 {
+        let value = statistic(sample);
+        return (value, value);
+    }
+
+    let mut resample = Vec::with_capacity(sample.len());
+    let mut values: Vec<f64> = (0..resamples)
+        .map(|_| {
+            resample.clear();
+            resample.extend((0..sample.len()).map(|_| sample[rng.gen_range(0..sample.len())].clone()));
+            statistic(&resample)
+        })
+        .collect();
+    values.sort_by(f64::total_cmp);
+
+    // Nearest-rank percentiles
+    let percentile = |p: f64| values[((p * resamples as f64).ceil() as usize).clamp(1, resamples) - 1];
+    (percentile(0.025), percentile(0.975))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_bot_propagation_discounts_odf() {
+        assert_eq!(EchoIndexCalculator::discount_bot_propagation(0.8, &[1.0, 1.0, 1.0]), 0.0);
+        assert_eq!(EchoIndexCalculator::discount_bot_propagation(0.8, &[0.0, 1.0]), 0.4);
+        assert_eq!(EchoIndexCalculator::discount_bot_propagation(0.8, &[]), 0.8);
+    }
+
+    #[test]
+    fn test_confidence_level_follows_sample_size() {
+        assert_eq!(ConfidenceLevel::from_sample_size(5), ConfidenceLevel::Low);
+        assert_eq!(ConfidenceLevel::from_sample_size(30), ConfidenceLevel::Medium);
+        assert_eq!(ConfidenceLevel::from_sample_size(100), ConfidenceLevel::High);
+        assert_eq!(ConfidenceLevel::from_sample_size(500), ConfidenceLevel::VeryHigh);
+        assert!(ConfidenceLevel::Medium < ConfidenceLevel::VeryHigh);
+        assert_eq!(ConfidenceLevel::parse("very_high"), Some(ConfidenceLevel::VeryHigh));
+    }
+
+    #[test]
+    fn test_wilson_interval_narrows_with_more_trials() {
+        let (lower, upper) = wilson_interval(8, 10);
+        assert!((lower - 0.4902).abs() < 1e-4 && (upper - 0.9433).abs() < 1e-4);
+
+        let (lower, upper) = wilson_interval(800, 1000);
+        assert!(lower > 0.77 && upper < 0.83);
+        assert_eq!(wilson_interval(0, 0), (0.0, 1.0));
+    }
+
+    #[test]
+    fn test_bootstrap_interval_brackets_the_statistic() {
+        let mut rng = rand::thread_rng();
+        let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+
+        assert_eq!(bootstrap_interval(&[4.0; 20], BOOTSTRAP_RESAMPLES, &mut rng, mean), (4.0, 4.0));
+        let sample: Vec<f64> = (0..200).map(|i| (i % 10) as f64).collect();
+        let (lower, upper) = bootstrap_interval(&sample, BOOTSTRAP_RESAMPLES, &mut rng, mean);
+        assert!(lower < 4.5 && 4.5 < upper);
+        assert!(lower > 3.5 && upper < 5.5);
+    }
+
+    #[test]
+    fn test_verified_account_bonus_raises_odf_up_to_one() {
+        assert!((EchoIndexCalculator::apply_verified_account_bonus(0.5, true) - 0.55).abs() < 1e-9);
+        assert_eq!(EchoIndexCalculator::apply_verified_account_bonus(0.5, false), 0.5);
+        assert_eq!(EchoIndexCalculator::apply_verified_account_bonus(0.95, true), 1.0);
+    }
+
+    #[test]
+    fn test_cross_platform_bonus_grows_with_the_reach_gap() {
+        assert_eq!(EchoIndexCalculator::cross_platform_bonus(500, 500), 0.0);
+        let bonus = EchoIndexCalculator::cross_platform_bonus(99, 9_999);
+        assert!((bonus - 0.1).abs() < 1e-9);
+        assert_eq!(bonus, EchoIndexCalculator::cross_platform_bonus(9_999, 99));
+        assert!((EchoIndexCalculator::cross_platform_bonus(9, 99) - 0.05).abs() < 1e-9);
+        assert_eq!(EchoIndexCalculator::cross_platform_bonus(0, 10_000_000), MAX_CROSS_PLATFORM_ODF_BONUS);
+        assert_eq!(EchoIndexCalculator::apply_cross_platform_bonus(0.95, 0.1), 1.0);
+    }
+
+    #[test]
+    fn test_trending_hashtag_bonus_raises_tpm_up_to_one() {
+        assert!((EchoIndexCalculator::apply_trending_hashtag_bonus(0.5, 0.05) - 0.55).abs() < 1e-9);
+        assert_eq!(EchoIndexCalculator::apply_trending_hashtag_bonus(0.5, 0.0), 0.5);
+        assert_eq!(EchoIndexCalculator::apply_trending_hashtag_bonus(0.98, 0.05), 1.0);
+    }
+
     #[test]
     fn test_odf_calculation() {
         let content = "This is a test content with some originality and depth in the analysis of complex topics.";
@@ -151,7 +516,9 @@ mod tests {
             unique_words: 14,
             sentiment_score: 0.8,
             readability_score: 0.7,
+            grade_level: 8.0,
             originality_markers: vec!["analysis".to_string(), "complex".to_string()],
+            detected_language: "eng".to_string(),
         };
 
         let odf = EchoIndexCalculator::calculate_odf(content, &metrics);
@@ -160,11 +527,41 @@ mod tests {
 
     #[test]
     fn test_overall_score_calculation() {
-        let score = EchoIndexCalculator::calculate_overall_score(0.8, 0.7, 0.6, 0.5);
+        let score = EchoIndexCalculator::default().calculate_overall_score(0.8, 0.7, 0.6, 0.5);
         assert!(score > 0.0 && score <= 1.0);
         
         // Check if the calculation is correct with the weights
         let expected = (0.8 * 0.3) + (0.7 * 0.25) + (0.6 * 0.25) + (0.5 * 0.2);
         assert!((score - expected).abs() < 0.001);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_builder_custom_weights() {
+        let calculator = EchoIndexCalculator::builder()
+            .odf_weight(0.4)
+            .awr_weight(0.2)
+            .tpm_weight(0.2)
+            .qf_weight(0.2)
+            .build()
+            .unwrap();
+
+        assert_eq!(calculator.odf_weight(), 0.4);
+        let expected = (0.8 * 0.4) + (0.7 * 0.2) + (0.6 * 0.2) + (0.5 * 0.2);
+        assert!((calculator.calculate_overall_score(0.8, 0.7, 0.6, 0.5) - expected).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_weight_sum() {
+        let result = EchoIndexCalculator::builder().odf_weight(0.5).build();
+        assert!(matches!(result, Err(WeightError::InvalidSum(_))));
+
+        let result = EchoIndexCalculator::builder().qf_weight(0.0).build();
+        assert!(matches!(result, Err(WeightError::InvalidSum(_))));
+
+        let result = EchoIndexCalculator::builder()
+            .odf_weight(0.6)
+            .qf_weight(-0.1)
+            .build();
+        assert_eq!(result.unwrap_err(), WeightError::InvalidWeight("qf"));
+    }
+}
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::experiment::ExperimentVariant;
+
+/// What caused an Echo Index calculation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "echo_index_trigger", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum EchoIndexTrigger {
+    Initial,
+    PropagationAdded,
+    Recalculation,
+    Scheduled,
+    /// Temporal decay of a score that has not been recalculated for a while
+    Decay,
+}
+
+/// A recorded Echo Index calculation and its change from the previous one
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EchoIndexHistory {
+    pub id: Uuid,
+    pub content_id: Uuid,
+    pub score: f64,
+    pub odf: f64,
+    pub awr: f64,
+    pub tpm: f64,
+    pub qf: f64,
+    pub calculated_at: DateTime<Utc>,
+    pub delta_score: f64,
+    pub trigger: EchoIndexTrigger,
+    /// Experiment whose weights produced the score, if any
+    pub experiment_id: Option<Uuid>,
+    pub variant: Option<ExperimentVariant>,
+}
+
+/// Highest Echo Index a content item reached within a time window
+#[derive(Debug, Clone, FromRow)]
+pub struct PeakScore {
+    pub content_id: Uuid,
+    pub title: String,
+    pub author: String,
+    pub peak_score: f64,
+    pub propagation_count: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Current standing of a content item, for leaderboards not ranked by peak Echo Index
+#[derive(Debug, Clone, FromRow)]
+pub struct ContentStanding {
+    pub content_id: Uuid,
+    pub title: String,
+    pub author: String,
+    pub echo_index: f64,
+    /// 0 for content without a trending score
+    pub trending_score: f64,
+    pub total_rewards: f64,
+    pub propagation_count: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What a content leaderboard is ranked by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardSort {
+    /// Trending score as of its latest hourly refresh
+    Trending,
+    /// Highest Echo Index reached within the window
+    #[default]
+    EchoIndex,
+    /// EchoDrop rewards earned
+    Rewards,
+}
+
+impl LeaderboardSort {
+    /// Parse a `sort_by` query value: `trending`, `echo_index` or `rewards`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "trending" => Some(LeaderboardSort::Trending),
+            "echo_index" => Some(LeaderboardSort::EchoIndex),
+            "rewards" => Some(LeaderboardSort::Rewards),
+            _ => None,
+        }
+    }
+}
+
+/// Bucket width for aggregated history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[schema(example = "daily")]
+pub enum HistoryGranularity {
+    Hourly,
+    #[default]
+    Daily,
+    Weekly,
+}
+
+impl HistoryGranularity {
+    /// Field name understood by Postgres `date_trunc`
+    pub fn date_trunc_field(&self) -> &'static str {
+        match self {
+            HistoryGranularity::Hourly => "hour",
+            HistoryGranularity::Daily => "day",
+            HistoryGranularity::Weekly => "week",
+        }
+    }
+}
+
+/// Score range observed within one history bucket
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EchoIndexHistoryBucket {
+    pub bucket_start: DateTime<Utc>,
+    pub min_score: f64,
+    pub max_score: f64,
+    pub avg_score: f64,
+    pub samples: i64,
+}
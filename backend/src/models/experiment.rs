@@ -0,0 +1,270 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::content::EchoIndexWeights;
+
+/// Which set of weights a content item is scored with during an experiment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "experiment_variant", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ExperimentVariant {
+    Control,
+    Treatment,
+}
+
+/// An A/B test of Echo Index component weights. Each content item is consistently
+/// assigned to one variant, `traffic_split` of them to the treatment.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Experiment {
+    pub id: Uuid,
+    pub name: String,
+    pub control_weights: EchoIndexWeights,
+    pub treatment_weights: EchoIndexWeights,
+    pub traffic_split: f64,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The experiment and variant an Echo Index calculation was made under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExperimentAssignment {
+    pub experiment_id: Uuid,
+    pub variant: ExperimentVariant,
+}
+
+impl Experiment {
+    /// Variant of a content item. Hashing the experiment id along with the content id
+    /// keeps the assignment stable across recalculations while giving every experiment
+    /// an independent split.
+    pub fn assign(&self, content_id: Uuid) -> ExperimentAssignment {
+        let digest = Sha256::new()
+            .chain_update(self.id.as_bytes())
+            .chain_update(content_id.as_bytes())
+            .finalize();
+        let bucket = u64::from_be_bytes(digest[..8].try_into().expect("SHA-256 digests are 32 bytes"));
+        let position = bucket as f64 / (u64::MAX as f64 + 1.0);
+
+        let variant = if position < self.traffic_split {
+            ExperimentVariant::Treatment
+        } else {
+            ExperimentVariant::Control
+        };
+        ExperimentAssignment { experiment_id: self.id, variant }
+    }
+
+    pub fn weights(&self, variant: ExperimentVariant) -> EchoIndexWeights {
+        match variant {
+            ExperimentVariant::Control => self.control_weights,
+            ExperimentVariant::Treatment => self.treatment_weights,
+        }
+    }
+}
+
+/// Echo Index of the content scored with one variant, each item counted once at its
+/// latest score
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct VariantSummary {
+    pub samples: i64,
+    pub mean_score: f64,
+    pub std_dev: f64,
+    pub standard_error: f64,
+}
+
+impl VariantSummary {
+    pub fn new(samples: i64, mean_score: f64, std_dev: f64) -> Self {
+        let standard_error = if samples > 0 { std_dev / (samples as f64).sqrt() } else { 0.0 };
+        Self { samples, mean_score, std_dev, standard_error }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExperimentResults {
+    pub experiment_id: Uuid,
+    pub control: VariantSummary,
+    pub treatment: VariantSummary,
+    /// Treatment mean minus control mean
+    pub difference: f64,
+    /// Two-sided p-value of Welch's t-test; `None` until both variants have two samples
+    /// and some variance
+    pub p_value: Option<f64>,
+}
+
+impl ExperimentResults {
+    pub fn new(experiment_id: Uuid, control: VariantSummary, treatment: VariantSummary) -> Self {
+        Self {
+            experiment_id,
+            control,
+            treatment,
+            difference: treatment.mean_score - control.mean_score,
+            p_value: welch_t_test(&control, &treatment),
+        }
+    }
+}
+
+/// Two-sided p-value of Welch's unequal variances t-test between two samples
+pub fn welch_t_test(a: &VariantSummary, b: &VariantSummary) -> Option<f64> {
+    if a.samples < 2 || b.samples < 2 {
+        return None;
+    }
+
+    let var_a = a.standard_error.powi(2);
+    let var_b = b.standard_error.powi(2);
+    let variance = var_a + var_b;
+    if variance <= 0.0 {
+        return None;
+    }
+
+    let t = (b.mean_score - a.mean_score) / variance.sqrt();
+    // Welch–Satterthwaite degrees of freedom
+    let df = variance.powi(2) / (var_a.powi(2) / (a.samples - 1) as f64 + var_b.powi(2) / (b.samples - 1) as f64);
+    Some(student_t_two_sided_p(t, df))
+}
+
+/// P(|T| >= |t|) for Student's t distribution with `df` degrees of freedom
+fn student_t_two_sided_p(t: f64, df: f64) -> f64 {
+    regularized_incomplete_beta(df / (df + t * t), df / 2.0, 0.5).clamp(0.0, 1.0)
+}
+
+/// I_x(a, b), evaluated with the continued fraction that converges quickly on whichever
+/// side of the distribution's mean `x` falls
+fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - ln_front.exp() * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// Continued fraction of the incomplete beta function, by the modified Lentz method
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 300;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut fraction = d;
+
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        for numerator in [
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ] {
+            d = 1.0 + numerator * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1.0 + numerator / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            fraction *= d * c;
+        }
+        if (d * c - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    fraction
+}
+
+/// ln Γ(x) for x > 0, by the Lanczos approximation
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // Reflection formula
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let mut sum = COEFFICIENTS[0];
+    for (i, coefficient) in COEFFICIENTS.iter().enumerate().skip(1) {
+        sum += coefficient / (x + i as f64);
+    }
+    let t = x + 7.5;
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn experiment(traffic_split: f64) -> Experiment {
+        let weights = EchoIndexWeights { odf: 0.3, awr: 0.25, tpm: 0.25, qf: 0.2 };
+        Experiment {
+            id: Uuid::from_u128(0x5eed),
+            name: "odf heavy".to_string(),
+            control_weights: weights,
+            treatment_weights: EchoIndexWeights { odf: 0.4, awr: 0.2, tpm: 0.2, ..weights },
+            traffic_split,
+            active: true,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_assignment_is_deterministic() {
+        let experiment = experiment(0.5);
+        for i in 0..100 {
+            let content_id = Uuid::from_u128(i);
+            assert_eq!(experiment.assign(content_id), experiment.assign(content_id));
+        }
+        assert_eq!(experiment.assign(Uuid::nil()).experiment_id, experiment.id);
+    }
+
+    #[test]
+    fn test_traffic_split_is_respected() {
+        for split in [0.0, 0.1, 0.5, 0.8, 1.0] {
+            let experiment = experiment(split);
+            let treated = (0..10_000u128)
+                .filter(|&i| experiment.assign(Uuid::from_u128(i)).variant == ExperimentVariant::Treatment)
+                .count();
+            // Four standard deviations of a binomial share over 10,000 draws is at most 0.02
+            let share = treated as f64 / 10_000.0;
+            assert!((share - split).abs() < 0.02, "split {} gave {}", split, share);
+        }
+    }
+
+    #[test]
+    fn test_welch_t_test_matches_reference_values() {
+        // t = 2.0 with 10 degrees of freedom
+        assert!((student_t_two_sided_p(2.0, 10.0) - 0.073_388_1).abs() < 1e-6);
+        assert!((student_t_two_sided_p(0.0, 5.0) - 1.0).abs() < 1e-12);
+        // Approaches the normal distribution
+        assert!((student_t_two_sided_p(1.959_964, 1e7) - 0.05).abs() < 1e-5);
+
+        let control = VariantSummary::new(50, 0.50, 0.10);
+        let treatment = VariantSummary::new(50, 0.56, 0.10);
+        let p_value = welch_t_test(&control, &treatment).unwrap();
+        // t = 3.0 with 98 degrees of freedom
+        assert!((p_value - 0.003_423_3).abs() < 1e-6, "p = {}", p_value);
+        assert!(welch_t_test(&VariantSummary::new(1, 0.5, 0.0), &treatment).is_none());
+    }
+}
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::content::ContentSummary;
+
+/// Why a content item was picked for a user's feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelevanceReason {
+    /// Among the most viral content, mixed in so feeds do not narrow to one topic
+    HighEchoIndex,
+    /// Propagated by a user whose propagations reach this user
+    PropagatedByFollowing,
+    /// Similar in text to content the user recently propagated
+    SimilarToEngaged,
+    /// Rising on the platform the user is most active on
+    TrendingInPlatform,
+}
+
+/// One item of a personalized discovery feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedEntry {
+    #[serde(flatten)]
+    pub content: ContentSummary,
+    pub relevance_reason: RelevanceReason,
+}
+
+/// Ranking a feed was generated with. Users are split between algorithms by the
+/// `discovery_feed_affinity_percent` feature flag, so they can be compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedAlgorithm {
+    /// Content related to what the user engaged with, boosted by Echo Index
+    Affinity,
+    /// The most viral content, the same for everyone; the experiment's baseline
+    Popular,
+}
+
+impl FeedAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedAlgorithm::Affinity => "affinity",
+            FeedAlgorithm::Popular => "popular",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "affinity" => Some(FeedAlgorithm::Affinity),
+            "popular" => Some(FeedAlgorithm::Popular),
+            _ => None,
+        }
+    }
+
+    /// Algorithm a user is assigned to when `affinity_percent` of users get the
+    /// affinity feed. A user stays in the same arm for as long as the split does.
+    pub fn for_user(user_id: Uuid, affinity_percent: u8) -> Self {
+        if (user_id.as_u128() % 100) < affinity_percent as u128 {
+            FeedAlgorithm::Affinity
+        } else {
+            FeedAlgorithm::Popular
+        }
+    }
+}
+
+/// A user's ranked feed, served page by page until it expires
+#[derive(Debug, Clone)]
+pub struct CachedFeed {
+    pub algorithm: FeedAlgorithm,
+    pub entries: Vec<FeedEntry>,
+    pub generated_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_experiment_split_is_stable_per_user() {
+        let users: Vec<Uuid> = (0..1000).map(|_| Uuid::new_v4()).collect();
+        let affinity = users
+            .iter()
+            .filter(|user| FeedAlgorithm::for_user(**user, 30) == FeedAlgorithm::Affinity)
+            .count();
+        assert!((200..400).contains(&affinity), "{} of 1000 users got the affinity feed", affinity);
+
+        assert!(users.iter().all(|user| FeedAlgorithm::for_user(*user, 30) == FeedAlgorithm::for_user(*user, 30)));
+        assert!(users.iter().all(|user| FeedAlgorithm::for_user(*user, 100) == FeedAlgorithm::Affinity));
+        assert!(users.iter().all(|user| FeedAlgorithm::for_user(*user, 0) == FeedAlgorithm::Popular));
+    }
+}
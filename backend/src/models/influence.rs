@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Share of the composite score carried by each component
+pub const REACH_WEIGHT: f64 = 0.4;
+pub const AMPLIFICATION_WEIGHT: f64 = 0.35;
+pub const CENTRALITY_WEIGHT: f64 = 0.25;
+/// Users reached downstream of a user at which the reach score is ~63%
+const REACH_SCALE: f64 = 50.0;
+/// Mean Echo Index gain after a user's propagations at which amplification is ~63%
+const AMPLIFICATION_SCALE: f64 = 10.0;
+
+/// Raw propagation activity of a user within a time window
+#[derive(Debug, Clone, Default, PartialEq, FromRow)]
+pub struct InfluenceSignals {
+    /// Distinct users the user propagated content to
+    pub out_degree: i64,
+    /// Distinct users taking part in any propagation of the window
+    pub network_size: i64,
+    /// Mean Echo Index change of content from before to after the user propagated it;
+    /// None if none of it has been scored since
+    pub echo_delta: Option<f64>,
+    /// Distinct users the user's propagations reached, directly or through further shares
+    pub reach: i64,
+}
+
+/// How strongly a user's propagations spread content. Every component is in [0, 1].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, FromRow)]
+pub struct InfluenceScore {
+    pub reach_score: f64,
+    pub echo_amplification: f64,
+    /// Share of the window's other participants the user propagated to
+    pub network_centrality: f64,
+    pub composite: f64,
+}
+
+impl InfluenceScore {
+    pub fn from_signals(signals: &InfluenceSignals) -> Self {
+        let reach_score = 1.0 - (-(signals.reach.max(0) as f64) / REACH_SCALE).exp();
+        let gain = signals.echo_delta.unwrap_or(0.0).max(0.0);
+        let echo_amplification = 1.0 - (-gain / AMPLIFICATION_SCALE).exp();
+        let network_centrality = if signals.network_size > 1 {
+            (signals.out_degree as f64 / (signals.network_size - 1) as f64).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let composite = REACH_WEIGHT * reach_score
+            + AMPLIFICATION_WEIGHT * echo_amplification
+            + CENTRALITY_WEIGHT * network_centrality;
+
+        Self { reach_score, echo_amplification, network_centrality, composite }
+    }
+}
+
+/// A stored influence calculation
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+pub struct UserInfluence {
+    pub user_id: Uuid,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub score: InfluenceScore,
+    /// Position among the latest scores of all users; equal composites share a rank
+    pub influence_rank: i32,
+    pub window_days: i32,
+    pub calculated_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_components_saturate_and_combine() {
+        assert_eq!(InfluenceScore::from_signals(&InfluenceSignals::default()), InfluenceScore::default());
+
+        let signals = InfluenceSignals { out_degree: 3, network_size: 7, echo_delta: Some(10.0), reach: 50 };
+        let score = InfluenceScore::from_signals(&signals);
+        let saturation = 1.0 - (-1.0f64).exp();
+        assert!((score.reach_score - saturation).abs() < 1e-9);
+        assert!((score.echo_amplification - saturation).abs() < 1e-9);
+        assert!((score.network_centrality - 0.5).abs() < 1e-9);
+        let composite = 0.4 * saturation + 0.35 * saturation + 0.25 * 0.5;
+        assert!((score.composite - composite).abs() < 1e-9);
+
+        // Propagations that lowered the Echo Index amplify nothing
+        let harmful = InfluenceSignals { echo_delta: Some(-5.0), ..signals };
+        assert_eq!(InfluenceScore::from_signals(&harmful).echo_amplification, 0.0);
+    }
+}
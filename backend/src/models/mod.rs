@@ -1,3 +1,157 @@
 pub mod user;
 pub mod content;
-pub mod echo_index; 
\ No newline at end of file
+pub mod content_version;
+pub mod echo_index;
+pub mod echo_index_history;
+pub mod pagination;
+pub mod user_event;
+pub mod user_streak;
+pub mod api_key;
+pub mod velocity_alert;
+pub mod moderation;
+pub mod feed;
+pub mod webhook;
+pub mod oauth;
+pub mod experiment;
+pub mod wallet_address;
+pub mod influence;
+pub mod reward_pool;
+pub mod reward_analytics;
+pub mod platform_stats;
+pub mod echo_anomaly;
+pub mod content_attribution;
+pub mod content_import;
+pub mod trending;
+pub mod session;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Social platform a piece of content or propagation lives on
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Platform {
+    Twitter,
+    Telegram,
+    LinkedIn,
+    Reddit,
+    Medium,
+    Discord,
+    Farcaster,
+    Other(String),
+}
+
+impl Platform {
+    /// Lowercase identifier used in the API and the database
+    pub fn as_str(&self) -> &str {
+        match self {
+            Platform::Twitter => "twitter",
+            Platform::Telegram => "telegram",
+            Platform::LinkedIn => "linkedin",
+            Platform::Reddit => "reddit",
+            Platform::Medium => "medium",
+            Platform::Discord => "discord",
+            Platform::Farcaster => "farcaster",
+            Platform::Other(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Accepts the lowercase identifiers as well as the variant names (`"LinkedIn"`, `"linked_in"`);
+/// anything unrecognised becomes `Platform::Other`
+impl From<&str> for Platform {
+    fn from(value: &str) -> Self {
+        match value.to_lowercase().replace('_', "").as_str() {
+            "twitter" => Platform::Twitter,
+            "telegram" => Platform::Telegram,
+            "linkedin" => Platform::LinkedIn,
+            "reddit" => Platform::Reddit,
+            "medium" => Platform::Medium,
+            "discord" => Platform::Discord,
+            "farcaster" => Platform::Farcaster,
+            _ => Platform::Other(value.to_string()),
+        }
+    }
+}
+
+impl FromStr for Platform {
+    type Err = Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Platform::from(value))
+    }
+}
+
+impl Serialize for Platform {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Platform {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(Platform::from(value.as_str()))
+    }
+}
+
+/// Documented with the platforms EchoLayer knows; other names are accepted as `Other`
+impl<'s> utoipa::ToSchema<'s> for Platform {
+    fn schema() -> (&'s str, utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>) {
+        let known = ["twitter", "telegram", "linkedin", "reddit", "medium", "discord", "farcaster"];
+        let schema = utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::SchemaType::String)
+            .enum_values(Some(known))
+            .example(Some(serde_json::json!("twitter")))
+            .build();
+        ("Platform", schema.into())
+    }
+}
+
+/// Stored as text; `platform_type` columns are cast explicitly in queries
+impl sqlx::Type<sqlx::Postgres> for Platform {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+}
+
+impl<'q> sqlx::Encode<'q, sqlx::Postgres> for Platform {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <&str as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for Platform {
+    fn decode(value: sqlx::postgres::PgValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let value = <&str as sqlx::Decode<sqlx::Postgres>>::decode(value)?;
+        Ok(Platform::from(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_platform_accepts_legacy_and_variant_names() {
+        for raw in ["\"linkedin\"", "\"LinkedIn\"", "\"linked_in\""] {
+            let platform: Platform = serde_json::from_str(raw).unwrap();
+            assert_eq!(platform, Platform::LinkedIn);
+        }
+        assert_eq!(serde_json::to_string(&Platform::LinkedIn).unwrap(), "\"linkedin\"");
+    }
+
+    #[test]
+    fn test_unknown_platform_is_preserved() {
+        let platform: Platform = serde_json::from_str("\"twiter\"").unwrap();
+        assert_eq!(platform, Platform::Other("twiter".to_string()));
+        assert_eq!(platform.to_string(), "twiter");
+    }
+}
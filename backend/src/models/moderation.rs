@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// `content.status` of content held for moderator review
+pub const CONTENT_UNDER_REVIEW: &str = "under_review";
+/// Share of its Echo Index that content keeps while under review
+pub const UNDER_REVIEW_ECHO_WEIGHT: f64 = 0.5;
+
+/// Why a user flagged content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(example = "spam")]
+pub enum FlagReason {
+    Spam,
+    Misinformation,
+    Harassment,
+    Copyright,
+    Other(String),
+}
+
+impl FlagReason {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FlagReason::Spam => "spam",
+            FlagReason::Misinformation => "misinformation",
+            FlagReason::Harassment => "harassment",
+            FlagReason::Copyright => "copyright",
+            FlagReason::Other(_) => "other",
+        }
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        match self {
+            FlagReason::Other(detail) => Some(detail),
+            _ => None,
+        }
+    }
+
+    /// Rebuild a reason from its stored kind and detail
+    pub fn from_parts(kind: &str, detail: Option<String>) -> Option<Self> {
+        match kind {
+            "spam" => Some(FlagReason::Spam),
+            "misinformation" => Some(FlagReason::Misinformation),
+            "harassment" => Some(FlagReason::Harassment),
+            "copyright" => Some(FlagReason::Copyright),
+            "other" => Some(FlagReason::Other(detail.unwrap_or_default())),
+            _ => None,
+        }
+    }
+}
+
+/// Where a flag is in the review workflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "moderation_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[schema(example = "pending")]
+pub enum ModerationStatus {
+    Pending,
+    /// Enough users flagged the content for a moderator to look at it
+    UnderReview,
+    /// A moderator found nothing wrong with the content
+    Cleared,
+    Removed,
+}
+
+impl ModerationStatus {
+    pub fn is_open(&self) -> bool {
+        matches!(self, ModerationStatus::Pending | ModerationStatus::UnderReview)
+    }
+}
+
+/// A moderator's verdict on flagged content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(example = "approve")]
+pub enum ModerationDecision {
+    /// Keep the content and clear its flags
+    Approve,
+    /// Remove the content and freeze its rewards
+    Reject,
+}
+
+impl ModerationDecision {
+    /// Status the content's open flags are resolved with
+    pub fn resolution(&self) -> ModerationStatus {
+        match self {
+            ModerationDecision::Approve => ModerationStatus::Cleared,
+            ModerationDecision::Reject => ModerationStatus::Removed,
+        }
+    }
+}
+
+/// One user's report of harmful or spammy content
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ModerationFlag {
+    pub id: Uuid,
+    pub content_id: Uuid,
+    pub flagged_by: Uuid,
+    pub reason: FlagReason,
+    pub status: ModerationStatus,
+    pub reviewed_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reasons_round_trip_through_storage_and_json() {
+        let reason = FlagReason::Other("impersonates a project".to_string());
+        assert_eq!(FlagReason::from_parts(reason.kind(), reason.detail().map(str::to_string)), Some(reason.clone()));
+        assert_eq!(serde_json::to_value(&reason).unwrap(), serde_json::json!({ "other": "impersonates a project" }));
+        assert_eq!(serde_json::from_value::<FlagReason>(serde_json::json!("spam")).unwrap(), FlagReason::Spam);
+
+        assert!(ModerationStatus::UnderReview.is_open());
+        assert!(!ModerationDecision::Reject.resolution().is_open());
+    }
+}
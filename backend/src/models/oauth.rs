@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::models::Platform;
+
+/// A started OAuth flow proving that a user owns a social account, waiting for the
+/// provider to redirect back
+#[derive(Debug, Clone, PartialEq)]
+pub struct OAuthState {
+    /// Random token the provider echoes back; doubles as CSRF protection
+    pub state: String,
+    pub user_id: Uuid,
+    pub platform: Platform,
+    /// Account the user claims to own
+    pub account_id: String,
+    /// PKCE code verifier of OAuth 2.0 flows
+    pub code_verifier: Option<String>,
+    /// Temporary credentials of OAuth 1.0a flows
+    pub request_token: Option<String>,
+    pub request_token_secret: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Query parameters the provider redirects back with. OAuth 2.0 providers send `code`,
+/// OAuth 1.0a providers `oauth_token` and `oauth_verifier`.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct OAuthCallback {
+    pub state: String,
+    pub code: Option<String>,
+    pub oauth_token: Option<String>,
+    pub oauth_verifier: Option<String>,
+}
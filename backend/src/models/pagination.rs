@@ -0,0 +1,249 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Opaque keyset cursor pointing at a `(created_at, id)` position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn new(created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { created_at, id }
+    }
+
+    /// Encode as URL-safe base64 of `<created_at micros>:<id>`
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", self.created_at.timestamp_micros(), self.id))
+    }
+
+    pub fn decode(encoded: &str) -> Result<Self, String> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(encoded)
+            .map_err(|_| "Cursor is not valid base64".to_string())?;
+        let raw = String::from_utf8(bytes).map_err(|_| "Cursor is not valid UTF-8".to_string())?;
+
+        let (micros, id) = raw
+            .split_once(':')
+            .ok_or_else(|| "Malformed cursor".to_string())?;
+        let micros: i64 = micros.parse().map_err(|_| "Malformed cursor timestamp".to_string())?;
+        let created_at = DateTime::<Utc>::from_timestamp_micros(micros)
+            .ok_or_else(|| "Cursor timestamp out of range".to_string())?;
+        let id = Uuid::parse_str(id).map_err(|_| "Malformed cursor id".to_string())?;
+
+        Ok(Self { created_at, id })
+    }
+}
+
+/// Opaque keyset cursor pointing at a `(score, id)` position of a ranking
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreCursor {
+    pub score: f64,
+    pub id: Uuid,
+}
+
+impl ScoreCursor {
+    pub fn new(score: f64, id: Uuid) -> Self {
+        Self { score, id }
+    }
+
+    /// Encode as URL-safe base64 of `<score>:<id>`
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", self.score, self.id))
+    }
+
+    pub fn decode(encoded: &str) -> Result<Self, String> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(encoded)
+            .map_err(|_| "Cursor is not valid base64".to_string())?;
+        let raw = String::from_utf8(bytes).map_err(|_| "Cursor is not valid UTF-8".to_string())?;
+
+        let (score, id) = raw
+            .split_once(':')
+            .ok_or_else(|| "Malformed cursor".to_string())?;
+        let score: f64 = score
+            .parse()
+            .ok()
+            .filter(|score: &f64| score.is_finite())
+            .ok_or_else(|| "Malformed cursor score".to_string())?;
+        let id = Uuid::parse_str(id).map_err(|_| "Malformed cursor id".to_string())?;
+
+        Ok(Self { score, id })
+    }
+}
+
+/// Value of the column a listing is sorted by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortKey {
+    Number(f64),
+    Time(DateTime<Utc>),
+}
+
+/// Opaque keyset cursor pointing at a `(sort key, id)` position of a listing that can be
+/// sorted by more than one column
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SortCursor {
+    pub key: SortKey,
+    pub id: Uuid,
+}
+
+impl SortCursor {
+    pub fn new(key: SortKey, id: Uuid) -> Self {
+        Self { key, id }
+    }
+
+    /// Encode as URL-safe base64 of `n:<number>:<id>` or `t:<micros>:<id>`
+    pub fn encode(&self) -> String {
+        let raw = match self.key {
+            SortKey::Number(value) => format!("n:{}:{}", value, self.id),
+            SortKey::Time(time) => format!("t:{}:{}", time.timestamp_micros(), self.id),
+        };
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(encoded: &str) -> Result<Self, String> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(encoded)
+            .map_err(|_| "Cursor is not valid base64".to_string())?;
+        let raw = String::from_utf8(bytes).map_err(|_| "Cursor is not valid UTF-8".to_string())?;
+
+        let mut parts = raw.splitn(3, ':');
+        let (Some(kind), Some(key), Some(id)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err("Malformed cursor".to_string());
+        };
+        let key = match kind {
+            "n" => key
+                .parse()
+                .ok()
+                .filter(|value: &f64| value.is_finite())
+                .map(SortKey::Number)
+                .ok_or_else(|| "Malformed cursor sort key".to_string())?,
+            "t" => key
+                .parse()
+                .ok()
+                .and_then(DateTime::<Utc>::from_timestamp_micros)
+                .map(SortKey::Time)
+                .ok_or_else(|| "Malformed cursor sort key".to_string())?,
+            _ => return Err("Malformed cursor".to_string()),
+        };
+        let id = Uuid::parse_str(id).map_err(|_| "Malformed cursor id".to_string())?;
+
+        Ok(Self { key, id })
+    }
+}
+
+/// One page of results ordered by `(created_at, id) DESC`
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
+impl<T> Page<T> {
+    /// Build a page from up to `limit + 1` rows; the extra row only signals that more exist
+    pub fn from_rows(rows: Vec<T>, limit: usize, cursor_of: impl Fn(&T) -> Cursor) -> Self {
+        Self::from_rows_with(rows, limit, |row| cursor_of(row).encode())
+    }
+
+    /// `from_rows` for listings in another order, with the encoded cursor of a row
+    pub fn from_rows_with(mut rows: Vec<T>, limit: usize, encode_cursor: impl Fn(&T) -> String) -> Self {
+        let has_more = rows.len() > limit;
+        rows.truncate(limit);
+
+        let next_cursor = if has_more {
+            rows.last().map(encode_cursor)
+        } else {
+            None
+        };
+
+        Self {
+            data: rows,
+            next_cursor,
+            has_more,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            data: self.data.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            has_more: self.has_more,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    /// Simulates `WHERE (created_at, id) < cursor ORDER BY created_at DESC, id DESC LIMIT n + 1`
+    fn fetch(rows: &[Cursor], after: Option<&str>, limit: usize) -> Page<Cursor> {
+        let after = after.map(|c| Cursor::decode(c).unwrap());
+        let matching: Vec<Cursor> = rows
+            .iter()
+            .filter(|row| after.is_none_or(|a| (row.created_at, row.id) < (a.created_at, a.id)))
+            .take(limit + 1)
+            .copied()
+            .collect();
+        Page::from_rows(matching, limit, |row| *row)
+    }
+
+    fn rows(count: i64) -> Vec<Cursor> {
+        // Postgres timestamps carry microsecond precision, as do cursors
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 123_456_000).unwrap();
+        (0..count)
+            .map(|i| Cursor::new(start - Duration::minutes(i), Uuid::new_v4()))
+            .collect()
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = rows(1)[0];
+        assert_eq!(Cursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(Cursor::decode("not a cursor!").is_err());
+
+        let cursor = ScoreCursor::new(75.8, Uuid::new_v4());
+        assert_eq!(ScoreCursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(ScoreCursor::decode(&URL_SAFE_NO_PAD.encode(format!("NaN:{}", cursor.id))).is_err());
+
+        for key in [SortKey::Number(42.5), SortKey::Time(rows(1)[0].created_at)] {
+            let cursor = SortCursor::new(key, Uuid::new_v4());
+            assert_eq!(SortCursor::decode(&cursor.encode()).unwrap(), cursor);
+        }
+        assert!(SortCursor::decode(&URL_SAFE_NO_PAD.encode(format!("x:1:{}", cursor.id))).is_err());
+    }
+
+    #[test]
+    fn test_first_page() {
+        let rows = rows(5);
+        let page = fetch(&rows, None, 2);
+        assert_eq!(page.data, rows[..2]);
+        assert!(page.has_more);
+        assert_eq!(page.next_cursor, Some(rows[1].encode()));
+    }
+
+    #[test]
+    fn test_middle_page() {
+        let rows = rows(5);
+        let first = fetch(&rows, None, 2);
+        let page = fetch(&rows, first.next_cursor.as_deref(), 2);
+        assert_eq!(page.data, rows[2..4]);
+        assert!(page.has_more);
+    }
+
+    #[test]
+    fn test_last_page() {
+        let rows = rows(5);
+        let cursor = rows[3].encode();
+        let page = fetch(&rows, Some(&cursor), 2);
+        assert_eq!(page.data, rows[4..]);
+        assert!(!page.has_more);
+        assert_eq!(page.next_cursor, None);
+    }
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+use super::Platform;
+
+/// How a platform's recent content scores, for comparing platforms and as the baseline of
+/// cold-start scores
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow, ToSchema)]
+pub struct PlatformEchoStats {
+    pub platform: Platform,
+    /// Live, scored content created on the platform in the last 30 days
+    #[sqlx(try_from = "i64")]
+    pub content_count: u64,
+    pub avg_echo_index: f64,
+    pub median_echo_index: f64,
+    /// Lowest Echo Index of the platform's top 10% of content
+    pub top_10_pct_threshold: f64,
+    /// Propagations of that content
+    #[sqlx(try_from = "i64")]
+    pub total_propagations: u64,
+    /// Mean hops from the original post of those propagations; 0 without any
+    pub avg_propagation_depth: f64,
+    pub updated_at: DateTime<Utc>,
+}
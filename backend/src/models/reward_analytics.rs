@@ -0,0 +1,116 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::services::rewards::RewardType;
+
+/// Width of the buckets rewards are aggregated into. Buckets start on UTC hour, day and
+/// Monday boundaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[schema(example = "day")]
+pub enum Granularity {
+    Hour,
+    #[default]
+    Day,
+    Week,
+}
+
+impl Granularity {
+    /// Field name `date_trunc` truncates to
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Granularity::Hour => "hour",
+            Granularity::Day => "day",
+            Granularity::Week => "week",
+        }
+    }
+
+    pub fn width(self) -> Duration {
+        match self {
+            Granularity::Hour => Duration::hours(1),
+            Granularity::Day => Duration::days(1),
+            Granularity::Week => Duration::weeks(1),
+        }
+    }
+}
+
+/// Rewards distributed within one bucket of a time series
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnalyticsBucket {
+    pub bucket_start: DateTime<Utc>,
+    pub total_distributed: f64,
+    pub by_type: HashMap<RewardType, f64>,
+    pub unique_recipients: u32,
+    /// Recipients whose first reward ever falls in this bucket
+    pub new_users_rewarded: u32,
+    /// `total_distributed` as a percentage of the reward pool over the bucket, or 0 for
+    /// days whose pool has not been recorded yet
+    pub pool_utilization_pct: f64,
+    /// Average distributed per day over the 7 days ending with this bucket
+    pub rolling_7d_avg: f64,
+}
+
+/// Query of the reward time series endpoints
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RewardAnalyticsQuery {
+    #[serde(default)]
+    pub granularity: Granularity,
+    /// RFC 3339 timestamp, or a date for the start of that UTC day
+    pub since: String,
+    /// RFC 3339 timestamp, or a date for the end of that UTC day; now when omitted
+    pub until: Option<String>,
+}
+
+impl RewardAnalyticsQuery {
+    /// The `[since, until)` range asked for, or why it is invalid
+    pub fn range(&self, now: DateTime<Utc>) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+        let since = parse_bound(&self.since, false)
+            .ok_or_else(|| "since must be an RFC 3339 timestamp or a YYYY-MM-DD date".to_string())?;
+        let until = match &self.until {
+            Some(until) => parse_bound(until, true)
+                .ok_or_else(|| "until must be an RFC 3339 timestamp or a YYYY-MM-DD date".to_string())?,
+            None => now,
+        };
+        if since >= until {
+            return Err("since must be before until".to_string());
+        }
+        Ok((since, until))
+    }
+}
+
+/// A timestamp, or the start of a date; with `end_of_day`, the end of a date
+fn parse_bound(value: &str, end_of_day: bool) -> Option<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+    let start = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0)?.and_utc();
+    Some(if end_of_day { start + Duration::days(1) } else { start })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(since: &str, until: Option<&str>) -> RewardAnalyticsQuery {
+        RewardAnalyticsQuery {
+            granularity: Granularity::Day,
+            since: since.to_string(),
+            until: until.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_dates_cover_whole_days() {
+        let (since, until) = query("2024-01-01", Some("2024-01-31")).range(Utc::now()).unwrap();
+        assert_eq!(since.to_rfc3339(), "2024-01-01T00:00:00+00:00");
+        assert_eq!(until.to_rfc3339(), "2024-02-01T00:00:00+00:00");
+
+        let (since, _) = query("2024-01-01T12:30:00+02:00", Some("2024-01-02")).range(Utc::now()).unwrap();
+        assert_eq!(since.to_rfc3339(), "2024-01-01T10:30:00+00:00");
+
+        assert!(query("2024-01-31", Some("2024-01-01")).range(Utc::now()).is_err());
+        assert!(query("January", None).range(Utc::now()).is_err());
+    }
+}
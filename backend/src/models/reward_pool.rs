@@ -0,0 +1,17 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::FromRow;
+
+/// Reward pool activity of one closed UTC day
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+pub struct PoolUtilizationDay {
+    pub day: NaiveDate,
+    /// EchoDrops available that day
+    pub pool_size: f64,
+    /// EchoDrops awarded from the pool that day, deferred rewards charged at its start included
+    pub utilized: f64,
+    /// `utilized` as a share of `pool_size`
+    pub utilization: f64,
+    /// Fractional change applied to the next day's pool: 0.10 for +10%, 0 when unchanged
+    pub adjustment: f64,
+}
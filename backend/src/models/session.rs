@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::net::IpAddr;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A login on one device. Access tokens refreshed with the login's refresh token carry
+/// its ID as their `session_id` claim, so revoking the session signs the device out.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// JWT ID of the latest access token issued to the session
+    #[serde(skip_serializing)]
+    pub jti: String,
+    pub device_fingerprint: String,
+    #[schema(value_type = String)]
+    pub ip_address: IpAddr,
+    pub user_agent: String,
+    pub created_at: DateTime<Utc>,
+    pub last_active_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
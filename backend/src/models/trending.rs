@@ -0,0 +1,173 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Hours velocity is measured over
+pub const VELOCITY_WINDOW_HOURS: i64 = 6;
+/// Latest hours of the velocity window, whose velocity acceleration compares with that
+/// of the rest of the window
+pub const RECENT_WINDOW_HOURS: i64 = 2;
+/// Echo Index history a score is computed from: the velocity window as of now and as of
+/// `RECENT_WINDOW_HOURS` ago
+pub const SERIES_HOURS: i64 = VELOCITY_WINDOW_HOURS + RECENT_WINDOW_HOURS;
+/// Scores halve for each such period without new propagations
+pub const DECAY_PERIOD_HOURS: i64 = 4;
+
+/// Hours ahead acceleration carries velocity when scoring
+const ACCELERATION_HORIZON_HOURS: f64 = 2.0;
+/// Hours between the middles of the recent and earlier windows
+const WINDOW_MIDPOINT_GAP_HOURS: f64 = 3.0;
+/// Acceleration measured now describes growth around the middle of both windows, this
+/// many hours ago
+const ACCELERATION_LAG_HOURS: f64 = 2.5;
+/// Peaks predicted further ahead are too uncertain to report
+const MAX_PEAK_HORIZON_HOURS: f64 = 48.0;
+
+/// Short-term virality of a content item, from how fast its Echo Index is changing.
+/// Unlike the Echo Index, which measures lasting quality, it fades as soon as the
+/// content stops spreading.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TrendingScore {
+    pub score: f64,
+    /// Echo Index gained per hour over the last six hours
+    pub velocity: f64,
+    /// Change per hour between the velocity of the last two hours and that of the four
+    /// hours before
+    pub acceleration: f64,
+    /// When the content's growth stops accelerating, so its velocity peaks. None while
+    /// growth is not slowing down, and once acceleration has stayed negative for a while.
+    pub peak_predicted_at: Option<DateTime<Utc>>,
+}
+
+impl TrendingScore {
+    /// Score as of `now` from the Echo Index history of a content item, oldest first,
+    /// reaching back `SERIES_HOURS` plus the last score before that. The score is the
+    /// velocity carried two hours ahead by the acceleration, halved for every four hours
+    /// since the latest propagation; content never propagated is halved once.
+    pub fn compute(
+        series: &[(DateTime<Utc>, f64)],
+        now: DateTime<Utc>,
+        last_propagated_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        let velocity =
+            (echo_index_at(series, now) - echo_index_at(series, now - Duration::hours(VELOCITY_WINDOW_HOURS)))
+                / VELOCITY_WINDOW_HOURS as f64;
+        let acceleration = acceleration_at(series, now);
+        let previous_acceleration = acceleration_at(series, now - Duration::hours(RECENT_WINDOW_HOURS));
+
+        let idle_periods = last_propagated_at.map_or(1, |at| (now - at).num_hours().max(0) / DECAY_PERIOD_HOURS);
+        let decay = 0.5f64.powi(idle_periods.min(i32::MAX as i64) as i32);
+        Self {
+            score: (velocity + acceleration * ACCELERATION_HORIZON_HOURS).max(0.0) * decay,
+            velocity,
+            acceleration,
+            peak_predicted_at: predicted_peak(now, acceleration, previous_acceleration),
+        }
+    }
+}
+
+/// The latest score at or before `at`; the first one for earlier times
+fn echo_index_at(series: &[(DateTime<Utc>, f64)], at: DateTime<Utc>) -> f64 {
+    series
+        .iter()
+        .take_while(|(calculated_at, _)| *calculated_at <= at)
+        .last()
+        .or(series.first())
+        .map_or(0.0, |(_, score)| *score)
+}
+
+fn acceleration_at(series: &[(DateTime<Utc>, f64)], at: DateTime<Utc>) -> f64 {
+    let split = at - Duration::hours(RECENT_WINDOW_HOURS);
+    let start = at - Duration::hours(VELOCITY_WINDOW_HOURS);
+    let recent = (echo_index_at(series, at) - echo_index_at(series, split)) / RECENT_WINDOW_HOURS as f64;
+    let earlier = (echo_index_at(series, split) - echo_index_at(series, start))
+        / (VELOCITY_WINDOW_HOURS - RECENT_WINDOW_HOURS) as f64;
+    (recent - earlier) / WINDOW_MIDPOINT_GAP_HOURS
+}
+
+/// When acceleration turns negative, extrapolated linearly from its value now and
+/// `RECENT_WINDOW_HOURS` ago and moved back by the lag of the measurement. Once it has
+/// turned, the crossing within the last window is interpolated instead.
+fn predicted_peak(now: DateTime<Utc>, acceleration: f64, previous: f64) -> Option<DateTime<Utc>> {
+    if previous <= 0.0 || previous <= acceleration {
+        return None;
+    }
+
+    // Hours from now until the measured acceleration reaches zero; negative once it has
+    let falling_per_hour = (previous - acceleration) / RECENT_WINDOW_HOURS as f64;
+    let hours = acceleration / falling_per_hour - ACCELERATION_LAG_HOURS;
+    if hours > MAX_PEAK_HORIZON_HOURS {
+        return None;
+    }
+    Some(now + Duration::seconds((hours * 3600.0).round() as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Echo Index following a logistic curve around `inflection`, sampled every 15 minutes
+    fn logistic(inflection: DateTime<Utc>, until: DateTime<Utc>) -> Vec<(DateTime<Utc>, f64)> {
+        let start = inflection - Duration::hours(24);
+        (0..)
+            .map(|i| start + Duration::minutes(15 * i))
+            .take_while(|at| *at <= until)
+            .map(|at| {
+                let hours = (at - inflection).num_minutes() as f64 / 60.0;
+                (at, 100.0 / (1.0 + (-hours / 3.0).exp()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_acceleration_sign_change_predicts_inflection() {
+        let inflection = DateTime::parse_from_rfc3339("2024-10-21T12:00:00Z").unwrap().with_timezone(&Utc);
+        let at = |hours: i64| {
+            let now = inflection + Duration::hours(hours);
+            TrendingScore::compute(&logistic(inflection, now), now, Some(now))
+        };
+
+        // Still accelerating well before the inflection, with no slowdown to extrapolate
+        let early = at(-8);
+        assert!(early.velocity > 0.0 && early.acceleration > 0.0);
+        assert_eq!(early.peak_predicted_at, None);
+
+        // Acceleration falling towards zero predicts the inflection before its sign changes,
+        // and the sign change confirms it
+        let approaching = at(2);
+        assert!(approaching.acceleration > 0.0);
+        for score in [approaching, at(3), at(4)] {
+            let predicted = score.peak_predicted_at.expect("predicted peak");
+            assert!((predicted - inflection).num_minutes().abs() <= 30, "predicted {}", predicted);
+        }
+        assert!(at(3).acceleration < 0.0);
+
+        // Well past the inflection growth decelerates while still climbing
+        let late = at(6);
+        assert!(late.velocity > 0.0 && late.acceleration < 0.0);
+        assert_eq!(late.peak_predicted_at, None);
+        assert!(late.score < approaching.score);
+    }
+
+    #[test]
+    fn test_scores_halve_without_new_propagations() {
+        let now = Utc::now();
+        let series = vec![(now - Duration::hours(6), 10.0), (now - Duration::hours(3), 20.0), (now, 40.0)];
+        let fresh = TrendingScore::compute(&series, now, Some(now - Duration::hours(1)));
+        assert!(fresh.score > 0.0);
+        assert_eq!(fresh.velocity, 5.0);
+
+        let quiet = TrendingScore::compute(&series, now, Some(now - Duration::hours(5)));
+        assert_eq!(quiet.score, fresh.score / 2.0);
+        let silent = TrendingScore::compute(&series, now, Some(now - Duration::hours(9)));
+        assert_eq!(silent.score, fresh.score / 4.0);
+        assert_eq!(TrendingScore::compute(&series, now, None).score, fresh.score / 2.0);
+
+        // Content losing Echo Index does not trend
+        let falling = vec![(now - Duration::hours(6), 40.0), (now - Duration::hours(3), 20.0), (now, 10.0)];
+        let falling = TrendingScore::compute(&falling, now, Some(now));
+        assert!(falling.velocity < 0.0);
+        assert_eq!(falling.score, 0.0);
+    }
+}
@@ -1,21 +1,112 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+use super::Platform;
+
+/// Access level of a user. Variants are ordered by privilege, so a role satisfies
+/// any requirement at or below it: `SuperAdmin > Admin > Moderator > User`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "user_role", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[schema(example = "user")]
+pub enum Role {
+    #[default]
+    User,
+    Moderator,
+    Admin,
+    SuperAdmin,
+}
+
+impl Role {
+    pub fn satisfies(&self, required: Role) -> bool {
+        *self >= required
+    }
+}
+
+/// Tier a user has earned with their Echo Score and rewards. Variants are ordered by
+/// rank, and users only ever move up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "user_tier", rename_all = "snake_case")]
+#[schema(example = "Bronze")]
+pub enum UserTier {
+    #[default]
+    Basic,
+    Bronze,
+    Silver,
+    Gold,
+}
+
+impl UserTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserTier::Basic => "Basic",
+            UserTier::Bronze => "Bronze",
+            UserTier::Silver => "Silver",
+            UserTier::Gold => "Gold",
+        }
+    }
+
+    /// Tiers above Basic
+    pub fn level(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// A registered wallet and its public profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
 pub struct User {
     pub id: Uuid,
-    pub username: String,
-    pub email: String,
-    pub wallet_address: Option<String>,
+    pub wallet_address: String,
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
     pub echo_score: f64,
-    pub total_content_created: i64,
-    pub total_rewards_earned: f64,
+    pub total_rewards: f64,
+    /// Leaderboard position; users with equal scores share a rank
+    pub rank: i32,
+    pub is_verified: bool,
+    pub role: Role,
+    pub tier: UserTier,
+    /// Encrypted TOTP secret, set once two-factor authentication is set up; never serialized
+    #[serde(skip)]
+    pub totp_secret: Option<String>,
+    /// Whether sign-in requires a TOTP or recovery code
+    pub totp_enabled: bool,
+    pub followers_count: i32,
+    pub following_count: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// A user on the Echo Score leaderboard
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct LeaderboardEntry {
+    #[sqlx(flatten)]
+    pub user: User,
+    /// Position by latest influence score; None until the user's influence is scored
+    pub influence_rank: Option<i32>,
+}
+
+/// `follower_id` follows `followed_id`
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+pub struct UserRelationship {
+    pub follower_id: Uuid,
+    pub followed_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A follower or followed user of someone, with when the follow started
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct FollowedUser {
+    #[sqlx(flatten)]
+    pub user: User,
+    pub followed_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateUserRequest {
     pub username: String,
@@ -27,7 +118,7 @@ pub struct CreateUserRequest {
 pub struct SocialAccount {
     pub id: Uuid,
     pub user_id: Uuid,
-    pub platform: String,
+    pub platform: Platform,
     pub account_id: String,
     pub username: String,
     pub verified: bool,
@@ -41,34 +132,23 @@ pub struct UserProfile {
     pub recent_content: Vec<crate::models::content::ContentSummary>,
 }
 
-impl User {
-    pub fn new(username: String, email: String) -> Self {
-        let now = Utc::now();
-        Self {
-            id: Uuid::new_v4(),
-            username,
-            email,
-            wallet_address: None,
-            echo_score: 0.0,
-            total_content_created: 0,
-            total_rewards_earned: 0.0,
-            created_at: now,
-            updated_at: now,
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    pub fn update_echo_score(&mut self, new_score: f64) {
-        self.echo_score = new_score;
-        self.updated_at = Utc::now();
+    #[test]
+    fn test_role_hierarchy() {
+        assert!(Role::SuperAdmin.satisfies(Role::Admin));
+        assert!(Role::Admin.satisfies(Role::Admin));
+        assert!(Role::Moderator.satisfies(Role::User));
+        assert!(!Role::Moderator.satisfies(Role::Admin));
+        assert!(!Role::User.satisfies(Role::Moderator));
     }
 
-    pub fn add_content(&mut self) {
-        self.total_content_created += 1;
-        self.updated_at = Utc::now();
+    #[test]
+    fn test_tier_order() {
+        assert!(UserTier::Gold > UserTier::Silver && UserTier::Silver > UserTier::Bronze);
+        assert_eq!((UserTier::Basic.level(), UserTier::Gold.level()), (0, 3));
+        assert_eq!(serde_json::to_value(UserTier::Silver).unwrap(), "Silver");
     }
-
-    pub fn add_rewards(&mut self, amount: f64) {
-        self.total_rewards_earned += amount;
-        self.updated_at = Utc::now();
-    }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::Platform;
+use crate::services::rewards::RewardType;
+
+/// Echo Index scores (0-100 scale) whose first crossing is recorded as a milestone
+const ECHO_INDEX_MILESTONES: [(f64, &str); 4] = [
+    (25.0, "rising"),
+    (50.0, "resonant"),
+    (75.0, "amplified"),
+    (90.0, "viral"),
+];
+
+/// Tier an Echo Index score (0-100 scale) falls into
+pub fn echo_tier(score: f64) -> &'static str {
+    match score {
+        s if s >= 80.0 => "Gold",
+        s if s >= 60.0 => "Silver",
+        s if s >= 40.0 => "Bronze",
+        _ => "Basic",
+    }
+}
+
+/// Lowest score (0-100 scale) of the tier below the one `score` falls into, or 0 in the
+/// lowest tier
+pub fn lower_tier_floor(score: f64) -> f64 {
+    match score {
+        s if s >= 80.0 => 60.0,
+        s if s >= 60.0 => 40.0,
+        _ => 0.0,
+    }
+}
+
+/// Something a user did or achieved, rendered as a card on their timeline.
+/// `event_type` is the discriminant stored alongside the JSON payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event_type", rename_all = "snake_case")]
+pub enum UserEvent {
+    ContentCreated {
+        content_id: Uuid,
+        platform: Platform,
+        initial_echo: f64,
+    },
+    PropagationMade {
+        content_id: Uuid,
+        target_platform: Platform,
+        echo_boost: f64,
+    },
+    RewardEarned {
+        amount: f64,
+        reward_type: RewardType,
+        content_id: String,
+    },
+    TierChanged {
+        old_tier: String,
+        new_tier: String,
+    },
+    EchoIndexMilestone {
+        score: f64,
+        milestone_type: String,
+    },
+}
+
+impl UserEvent {
+    /// Value of the `event_type` discriminant
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            UserEvent::ContentCreated { .. } => "content_created",
+            UserEvent::PropagationMade { .. } => "propagation_made",
+            UserEvent::RewardEarned { .. } => "reward_earned",
+            UserEvent::TierChanged { .. } => "tier_changed",
+            UserEvent::EchoIndexMilestone { .. } => "echo_index_milestone",
+        }
+    }
+
+    /// Milestone and tier events caused by an Echo Index moving from `previous` to `current`
+    /// (both on the 0-100 scale). A first calculation starts from zero.
+    pub fn echo_index_transitions(previous: Option<f64>, current: f64) -> Vec<UserEvent> {
+        let previous = previous.unwrap_or(0.0);
+        let mut events: Vec<UserEvent> = ECHO_INDEX_MILESTONES
+            .iter()
+            .filter(|(threshold, _)| previous < *threshold && current >= *threshold)
+            .map(|(_, milestone)| UserEvent::EchoIndexMilestone {
+                score: current,
+                milestone_type: milestone.to_string(),
+            })
+            .collect();
+
+        let (old_tier, new_tier) = (echo_tier(previous), echo_tier(current));
+        if old_tier != new_tier {
+            events.push(UserEvent::TierChanged {
+                old_tier: old_tier.to_string(),
+                new_tier: new_tier.to_string(),
+            });
+        }
+
+        events
+    }
+}
+
+/// A persisted event as returned by the timeline
+#[derive(Debug, Clone, Serialize)]
+pub struct UserEventRecord {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: UserEvent,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_echo_index_transitions() {
+        let events = UserEvent::echo_index_transitions(Some(45.0), 78.0);
+        let types: Vec<_> = events.iter().map(UserEvent::event_type).collect();
+        assert_eq!(types, ["echo_index_milestone", "echo_index_milestone", "tier_changed"]);
+        assert!(matches!(
+            &events[2],
+            UserEvent::TierChanged { old_tier, new_tier } if old_tier == "Bronze" && new_tier == "Silver"
+        ));
+
+        assert!(UserEvent::echo_index_transitions(Some(52.0), 55.0).is_empty());
+    }
+
+    #[test]
+    fn test_discriminant_in_json() {
+        let event = UserEvent::EchoIndexMilestone {
+            score: 91.0,
+            milestone_type: "viral".to_string(),
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event_type"], event.event_type());
+        assert_eq!(serde_json::from_value::<UserEvent>(json).unwrap().event_type(), "echo_index_milestone");
+    }
+}
@@ -0,0 +1,149 @@
+use chrono::{Days, NaiveDate};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A freeze token is earned for every this many consecutive days
+const FREEZE_TOKEN_INTERVAL_DAYS: u32 = 7;
+/// Freeze tokens a user can hold at once
+pub const MAX_FREEZE_TOKENS: u8 = 3;
+
+/// Consecutive days of content creation
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+pub struct UserStreak {
+    pub user_id: Uuid,
+    #[sqlx(try_from = "i32")]
+    pub current_streak_days: u32,
+    #[sqlx(try_from = "i32")]
+    pub longest_streak_days: u32,
+    pub last_activity_date: NaiveDate,
+    /// Each token bridges one day without activity
+    #[sqlx(try_from = "i16")]
+    pub streak_freeze_tokens: u8,
+}
+
+impl UserStreak {
+    /// A streak that has not started yet
+    pub fn new(user_id: Uuid, today: NaiveDate) -> Self {
+        Self {
+            user_id,
+            current_streak_days: 0,
+            longest_streak_days: 0,
+            last_activity_date: today,
+            streak_freeze_tokens: 0,
+        }
+    }
+
+    /// Record activity on `today`. The streak continues if the last active day was
+    /// yesterday, or if freeze tokens cover every missed day in between; otherwise it
+    /// restarts at 1. Repeated activity on the same day changes nothing.
+    pub fn record_activity(&mut self, today: NaiveDate) {
+        if self.current_streak_days > 0 && self.last_activity_date >= today {
+            return;
+        }
+
+        let missed_days = (today - self.last_activity_date).num_days() - 1;
+        let continues = self.current_streak_days > 0
+            && missed_days >= 0
+            && missed_days <= i64::from(self.streak_freeze_tokens);
+
+        if continues {
+            self.streak_freeze_tokens -= missed_days as u8;
+            self.current_streak_days += 1;
+            if self.current_streak_days.is_multiple_of(FREEZE_TOKEN_INTERVAL_DAYS) {
+                self.streak_freeze_tokens = (self.streak_freeze_tokens + 1).min(MAX_FREEZE_TOKENS);
+            }
+        } else {
+            self.current_streak_days = 1;
+        }
+
+        self.longest_streak_days = self.longest_streak_days.max(self.current_streak_days);
+        self.last_activity_date = today;
+    }
+
+    /// Spend a freeze token to cover `today` without creating content
+    pub fn freeze(&mut self, today: NaiveDate) -> Result<(), String> {
+        if self.current_streak_days == 0 {
+            return Err("No active streak to freeze".to_string());
+        }
+        if self.last_activity_date >= today {
+            return Err("Streak is already covered for today".to_string());
+        }
+        if today.checked_sub_days(Days::new(1)) != Some(self.last_activity_date) {
+            return Err("Streak has already lapsed".to_string());
+        }
+        if self.streak_freeze_tokens == 0 {
+            return Err("No streak freeze tokens left".to_string());
+        }
+
+        self.streak_freeze_tokens -= 1;
+        self.last_activity_date = today;
+        Ok(())
+    }
+
+    /// Content creation reward multiplier earned by the current streak
+    pub fn multiplier(&self) -> f64 {
+        match self.current_streak_days {
+            days if days >= 100 => 2.0,
+            days if days >= 30 => 1.5,
+            days if days >= 7 => 1.25,
+            _ => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(offset: u64) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap() + Days::new(offset)
+    }
+
+    #[test]
+    fn test_consecutive_days_extend_streak() {
+        let mut streak = UserStreak::new(Uuid::new_v4(), day(0));
+        for offset in 0..7 {
+            streak.record_activity(day(offset));
+            streak.record_activity(day(offset));
+        }
+
+        assert_eq!(streak.current_streak_days, 7);
+        assert_eq!(streak.longest_streak_days, 7);
+        assert_eq!(streak.streak_freeze_tokens, 1);
+        assert_eq!(streak.multiplier(), 1.25);
+    }
+
+    #[test]
+    fn test_gap_resets_unless_frozen() {
+        let mut streak = UserStreak::new(Uuid::new_v4(), day(0));
+        for offset in 0..7 {
+            streak.record_activity(day(offset));
+        }
+
+        // One missed day is bridged by the token earned on day 7
+        streak.record_activity(day(8));
+        assert_eq!((streak.current_streak_days, streak.streak_freeze_tokens), (8, 0));
+
+        streak.record_activity(day(10));
+        assert_eq!(streak.current_streak_days, 1);
+        assert_eq!(streak.longest_streak_days, 8);
+        assert_eq!(streak.multiplier(), 1.0);
+    }
+
+    #[test]
+    fn test_freeze_covers_today() {
+        let mut streak = UserStreak::new(Uuid::new_v4(), day(0));
+        assert!(streak.freeze(day(1)).is_err());
+
+        streak.current_streak_days = 30;
+        streak.streak_freeze_tokens = 1;
+        streak.last_activity_date = day(0);
+        streak.freeze(day(1)).unwrap();
+        assert!(streak.freeze(day(1)).is_err());
+
+        streak.record_activity(day(2));
+        assert_eq!(streak.current_streak_days, 31);
+        assert_eq!(streak.multiplier(), 1.5);
+    }
+}
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A propagation rate that, once exceeded by a user's content, raises an alert
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+// Variant names spell out the unit each threshold is measured in
+#[allow(clippy::enum_variant_names)]
+pub enum VelocityThreshold {
+    PropagationsPerHour(f64),
+    /// Distinct users reached per hour
+    ReachPerHour(u64),
+    EchoIndexIncreasePerHour(f64),
+}
+
+impl VelocityThreshold {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            VelocityThreshold::PropagationsPerHour(_) => "propagations_per_hour",
+            VelocityThreshold::ReachPerHour(_) => "reach_per_hour",
+            VelocityThreshold::EchoIndexIncreasePerHour(_) => "echo_index_increase_per_hour",
+        }
+    }
+
+    pub fn value(&self) -> f64 {
+        match *self {
+            VelocityThreshold::PropagationsPerHour(value) | VelocityThreshold::EchoIndexIncreasePerHour(value) => value,
+            VelocityThreshold::ReachPerHour(value) => value as f64,
+        }
+    }
+
+    /// Rebuild a threshold from its stored kind and value
+    pub fn from_parts(kind: &str, value: f64) -> Option<Self> {
+        match kind {
+            "propagations_per_hour" => Some(VelocityThreshold::PropagationsPerHour(value)),
+            "reach_per_hour" => Some(VelocityThreshold::ReachPerHour(value as u64)),
+            "echo_index_increase_per_hour" => Some(VelocityThreshold::EchoIndexIncreasePerHour(value)),
+            _ => None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let value = self.value();
+        if !value.is_finite() || value <= 0.0 {
+            return Err(format!("{} threshold must be a positive number", self.kind()));
+        }
+        Ok(())
+    }
+
+    /// The velocity this threshold measures, if the content is moving faster than it
+    pub fn exceeded_by(&self, velocity: &ContentVelocity) -> Option<f64> {
+        let current = match self {
+            VelocityThreshold::PropagationsPerHour(_) => velocity.propagations_per_hour,
+            VelocityThreshold::ReachPerHour(_) => velocity.reach_per_hour as f64,
+            VelocityThreshold::EchoIndexIncreasePerHour(_) => velocity.echo_index_increase_per_hour,
+        };
+        (current > self.value()).then_some(current)
+    }
+}
+
+/// How fast a content item spread over the last hour
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContentVelocity {
+    pub content_id: Uuid,
+    pub user_id: Uuid,
+    pub propagations_per_hour: f64,
+    pub reach_per_hour: u64,
+    pub echo_index_increase_per_hour: f64,
+}
+
+/// A threshold a user asked to be alerted about, for all of their content
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertConfig {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub threshold: VelocityThreshold,
+    /// Where alerts are posted, besides being recorded
+    pub webhook_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Content that went faster than one of its creator's thresholds
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VelocityAlert {
+    pub content_id: Uuid,
+    pub user_id: Uuid,
+    pub threshold_type: VelocityThreshold,
+    pub triggered_at: DateTime<Utc>,
+    pub current_velocity: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thresholds_compare_their_own_measure() {
+        let velocity = ContentVelocity {
+            content_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            propagations_per_hour: 12.0,
+            reach_per_hour: 40,
+            echo_index_increase_per_hour: 3.5,
+        };
+
+        assert_eq!(VelocityThreshold::PropagationsPerHour(10.0).exceeded_by(&velocity), Some(12.0));
+        assert_eq!(VelocityThreshold::PropagationsPerHour(12.0).exceeded_by(&velocity), None);
+        assert_eq!(VelocityThreshold::ReachPerHour(50).exceeded_by(&velocity), None);
+        assert_eq!(VelocityThreshold::EchoIndexIncreasePerHour(2.0).exceeded_by(&velocity), Some(3.5));
+
+        let threshold = VelocityThreshold::ReachPerHour(25);
+        assert_eq!(serde_json::to_value(threshold).unwrap(), serde_json::json!({"type": "reach_per_hour", "value": 25}));
+        assert_eq!(VelocityThreshold::from_parts(threshold.kind(), threshold.value()), Some(threshold));
+        assert!(VelocityThreshold::PropagationsPerHour(0.0).validate().is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use sha3::{Digest, Keccak256};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum AddressError {
+    #[error("address is not valid base58")]
+    InvalidBase58,
+    #[error("address must be {expected} bytes, got {actual}")]
+    InvalidLength { expected: usize, actual: usize },
+    #[error("address is not an Ed25519 public key")]
+    OffCurve,
+    #[error("address is not in canonical base58 form")]
+    NonCanonical,
+    #[error("Ethereum address must start with 0x")]
+    MissingPrefix,
+    #[error("Ethereum address must be 40 hex digits")]
+    InvalidHex,
+    #[error("Ethereum address fails its EIP-55 checksum")]
+    InvalidChecksum,
+}
+
+/// A Solana wallet: an Ed25519 public key, written in base58
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SolanaAddress([u8; 32]);
+
+impl SolanaAddress {
+    /// Parse a base58 address, rejecting anything but the canonical encoding of a point on
+    /// the Ed25519 curve. Program derived addresses are deliberately off the curve and so
+    /// are not wallets.
+    pub fn parse(s: &str) -> Result<Self, AddressError> {
+        let bytes = bs58::decode(s).into_vec().map_err(|_| AddressError::InvalidBase58)?;
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|bytes: Vec<u8>| AddressError::InvalidLength { expected: 32, actual: bytes.len() })?;
+
+        if CompressedEdwardsY(bytes).decompress().is_none() {
+            return Err(AddressError::OffCurve);
+        }
+        if bs58::encode(bytes).into_string() != s {
+            return Err(AddressError::NonCanonical);
+        }
+        Ok(Self(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for SolanaAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&bs58::encode(self.0).into_string())
+    }
+}
+
+/// An Ethereum account: the last 20 bytes of the Keccak-256 hash of its public key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EthereumAddress([u8; 20]);
+
+impl EthereumAddress {
+    /// Parse a 0x-prefixed hex address. Mixed-case addresses must match their EIP-55
+    /// checksum; all-lowercase and all-uppercase ones carry no checksum.
+    pub fn parse(s: &str) -> Result<Self, AddressError> {
+        let digits = s.strip_prefix("0x").ok_or(AddressError::MissingPrefix)?;
+        if digits.len() != 40 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(AddressError::InvalidHex);
+        }
+
+        let mut bytes = [0u8; 20];
+        hex::decode_to_slice(digits, &mut bytes).map_err(|_| AddressError::InvalidHex)?;
+        let address = Self(bytes);
+
+        let has_lower = digits.chars().any(|c| c.is_ascii_lowercase());
+        let has_upper = digits.chars().any(|c| c.is_ascii_uppercase());
+        if has_lower && has_upper && address.to_checksum() != s {
+            return Err(AddressError::InvalidChecksum);
+        }
+        Ok(address)
+    }
+
+    pub fn from_bytes(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+
+    /// EIP-55 form: each letter is uppercase when the matching nibble of the hash of the
+    /// lowercase address is 8 or more
+    pub fn to_checksum(self) -> String {
+        let lowercase = hex::encode(self.0);
+        let hash = Keccak256::digest(lowercase.as_bytes());
+
+        let checksummed: String = lowercase
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+                if nibble >= 8 {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect();
+        format!("0x{}", checksummed)
+    }
+}
+
+impl fmt::Display for EthereumAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_checksum())
+    }
+}
+
+/// A wallet on either chain users sign in from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WalletAddress {
+    Solana(SolanaAddress),
+    Ethereum(EthereumAddress),
+}
+
+impl WalletAddress {
+    /// Parse a 0x-prefixed address as Ethereum and anything else as Solana
+    pub fn parse(s: &str) -> Result<Self, AddressError> {
+        if s.starts_with("0x") {
+            EthereumAddress::parse(s).map(WalletAddress::Ethereum)
+        } else {
+            SolanaAddress::parse(s).map(WalletAddress::Solana)
+        }
+    }
+}
+
+/// Canonical form: base58 for Solana, EIP-55 for Ethereum
+impl fmt::Display for WalletAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalletAddress::Solana(address) => address.fmt(f),
+            WalletAddress::Ethereum(address) => address.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solana_addresses_are_curve_points() {
+        for address in ["7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU", "4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi"] {
+            assert_eq!(SolanaAddress::parse(address).unwrap().to_string(), address);
+        }
+
+        // Right length, but 32 bytes of 0x0f are not a point on the curve
+        assert_eq!(SolanaAddress::parse("21nS9Wz9sUTQ6MkcYUtnN8aSfPA26xJJP7zqshfzCzqc"), Err(AddressError::OffCurve));
+        let invalid = SolanaAddress::parse("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAs0");
+        assert_eq!(invalid, Err(AddressError::InvalidBase58));
+        assert_eq!(SolanaAddress::parse("3yZe7d"), Err(AddressError::InvalidLength { expected: 32, actual: 4 }));
+        assert!(SolanaAddress::parse("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+        let wallet = WalletAddress::parse("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU");
+        assert!(matches!(wallet, Ok(WalletAddress::Solana(_))));
+    }
+
+    #[test]
+    fn test_ethereum_addresses_match_their_checksum() {
+        // Test vectors from EIP-55
+        for address in ["0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed", "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359"] {
+            assert_eq!(EthereumAddress::parse(address).unwrap().to_string(), address);
+        }
+        let lowercase = EthereumAddress::parse("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap();
+        assert_eq!(lowercase.to_checksum(), "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+        assert!(EthereumAddress::parse("0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED").is_ok());
+
+        assert_eq!(
+            EthereumAddress::parse("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+            Err(AddressError::InvalidChecksum)
+        );
+        for (address, error) in [
+            ("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed", AddressError::MissingPrefix),
+            ("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA", AddressError::InvalidHex),
+            ("0xzzAeb6053F3E94C9b9A09f33669435E7Ef1BeAed", AddressError::InvalidHex),
+        ] {
+            assert_eq!(EthereumAddress::parse(address), Err(error));
+        }
+        let wallet = WalletAddress::parse("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD");
+        assert_eq!(wallet, Err(AddressError::InvalidChecksum));
+    }
+}
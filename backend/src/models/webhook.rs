@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::{PgHasArrayType, PgTypeInfo};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Something that happened to a user's content or rewards that webhooks can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "webhook_event", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[schema(example = "tier_changed")]
+pub enum WebhookEvent {
+    EchoIndexCalculated,
+    /// Content moved into another Echo Index tier
+    TierChanged,
+    RewardAwarded,
+    ContentFlagged,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::EchoIndexCalculated => "echo_index_calculated",
+            WebhookEvent::TierChanged => "tier_changed",
+            WebhookEvent::RewardAwarded => "reward_awarded",
+            WebhookEvent::ContentFlagged => "content_flagged",
+        }
+    }
+}
+
+impl PgHasArrayType for WebhookEvent {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_webhook_event")
+    }
+}
+
+/// An endpoint of an external integration that a user's events are posted to
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub url: String,
+    /// Key payloads are signed with; only shown when the webhook is registered
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One attempt at posting an event to a webhook
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: WebhookEvent,
+    pub payload: serde_json::Value,
+    /// 1 for the first attempt, counting up with each retry
+    pub attempt: i32,
+    /// `None` when no response arrived
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub succeeded: bool,
+    pub attempted_at: DateTime<Utc>,
+}
@@ -0,0 +1,186 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::velocity_alert::{AlertConfig, ContentVelocity, VelocityAlert, VelocityThreshold};
+
+pub struct AlertRepository {
+    pool: PgPool,
+}
+
+impl AlertRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Set a user's threshold of this kind, replacing any earlier one. `Conflict` if the
+    /// user does not exist.
+    pub async fn upsert_config(
+        &self,
+        user_id: Uuid,
+        threshold: VelocityThreshold,
+        webhook_url: Option<&str>,
+    ) -> Result<AlertConfig, RepositoryError> {
+        let row = sqlx::query(
+            "INSERT INTO alert_configs (user_id, threshold_type, threshold, webhook_url)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (user_id, threshold_type)
+             DO UPDATE SET threshold = $3, webhook_url = $4, updated_at = NOW()
+             RETURNING id, user_id, threshold_type, threshold, webhook_url, created_at",
+        )
+        .bind(user_id)
+        .bind(threshold.kind())
+        .bind(threshold.value())
+        .bind(webhook_url)
+        .fetch_one(&self.pool)
+        .await?;
+
+        config_from_row(&row)
+    }
+
+    pub async fn configs_for_user(&self, user_id: Uuid) -> Result<Vec<AlertConfig>, RepositoryError> {
+        let rows = sqlx::query(
+            "SELECT id, user_id, threshold_type, threshold, webhook_url, created_at
+             FROM alert_configs WHERE user_id = $1
+             ORDER BY threshold_type",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(config_from_row).collect()
+    }
+
+    pub async fn all_configs(&self) -> Result<Vec<AlertConfig>, RepositoryError> {
+        let rows = sqlx::query("SELECT id, user_id, threshold_type, threshold, webhook_url, created_at FROM alert_configs")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(config_from_row).collect()
+    }
+
+    /// Velocity over the `window` before `now` of the live content of these users that
+    /// was propagated in that time. The Echo Index increase is measured from the last
+    /// score calculated before the window, or from 0 for content first scored within it.
+    pub async fn velocities(
+        &self,
+        user_ids: &[Uuid],
+        now: DateTime<Utc>,
+        window: Duration,
+    ) -> Result<Vec<ContentVelocity>, RepositoryError> {
+        let since = now - window;
+        let rows: Vec<(Uuid, Uuid, i64, i64, f64)> = sqlx::query_as(
+            "SELECT c.id, c.user_id, COUNT(p.id), COUNT(DISTINCT p.target_user_id),
+                    c.echo_index::float8 - COALESCE((SELECT h.score FROM echo_index_history h
+                                                      WHERE h.content_id = c.id AND h.calculated_at <= $2
+                                                      ORDER BY h.calculated_at DESC LIMIT 1), 0)
+             FROM content c
+             JOIN propagations p ON p.content_id = c.id AND p.created_at > $2
+             WHERE c.deleted_at IS NULL AND c.user_id = ANY($1)
+             GROUP BY c.id",
+        )
+        .bind(user_ids)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let hours = window.num_seconds().max(1) as f64 / 3600.0;
+        Ok(rows
+            .into_iter()
+            .map(|(content_id, user_id, propagations, reach, echo_increase)| ContentVelocity {
+                content_id,
+                user_id,
+                propagations_per_hour: propagations as f64 / hours,
+                reach_per_hour: (reach as f64 / hours).round() as u64,
+                echo_index_increase_per_hour: echo_increase / hours,
+            })
+            .collect())
+    }
+
+    /// Mark a threshold as fired for a content item at `now`, unless it already fired
+    /// after `cooldown_start`. Returns whether it fired, so concurrent evaluations never
+    /// raise the same alert twice.
+    pub async fn try_trigger(
+        &self,
+        config_id: Uuid,
+        content_id: Uuid,
+        now: DateTime<Utc>,
+        cooldown_start: DateTime<Utc>,
+    ) -> Result<bool, RepositoryError> {
+        let result = sqlx::query(
+            "INSERT INTO alert_triggers (config_id, content_id, triggered_at) VALUES ($1, $2, $3)
+             ON CONFLICT (config_id, content_id)
+             DO UPDATE SET triggered_at = EXCLUDED.triggered_at
+             WHERE alert_triggers.triggered_at <= $4",
+        )
+        .bind(config_id)
+        .bind(content_id)
+        .bind(now)
+        .bind(cooldown_start)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn record_alert(&self, alert: &VelocityAlert) -> Result<(), RepositoryError> {
+        sqlx::query(
+            "INSERT INTO velocity_alerts (content_id, user_id, threshold_type, threshold, current_velocity, triggered_at)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(alert.content_id)
+        .bind(alert.user_id)
+        .bind(alert.threshold_type.kind())
+        .bind(alert.threshold_type.value())
+        .bind(alert.current_velocity)
+        .bind(alert.triggered_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// A user's most recent recorded alerts, newest first
+    pub async fn recent_alerts(&self, user_id: Uuid, limit: i64) -> Result<Vec<VelocityAlert>, RepositoryError> {
+        let rows = sqlx::query(
+            "SELECT content_id, user_id, threshold_type, threshold, current_velocity, triggered_at
+             FROM velocity_alerts WHERE user_id = $1
+             ORDER BY triggered_at DESC
+             LIMIT $2",
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(VelocityAlert {
+                    content_id: row.try_get("content_id")?,
+                    user_id: row.try_get("user_id")?,
+                    threshold_type: threshold_from_row(row)?,
+                    triggered_at: row.try_get("triggered_at")?,
+                    current_velocity: row.try_get("current_velocity")?,
+                })
+            })
+            .collect()
+    }
+}
+
+fn config_from_row(row: &PgRow) -> Result<AlertConfig, RepositoryError> {
+    Ok(AlertConfig {
+        id: row.try_get("id")?,
+        user_id: row.try_get("user_id")?,
+        threshold: threshold_from_row(row)?,
+        webhook_url: row.try_get("webhook_url")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+fn threshold_from_row(row: &PgRow) -> Result<VelocityThreshold, RepositoryError> {
+    let kind: String = row.try_get("threshold_type")?;
+    VelocityThreshold::from_parts(&kind, row.try_get("threshold")?)
+        .ok_or_else(|| RepositoryError::InvalidInput(format!("unknown threshold type {}", kind)))
+}
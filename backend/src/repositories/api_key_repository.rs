@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::api_key::{ApiKey, Permission};
+use crate::models::user::Role;
+
+const API_KEY_COLUMNS: &str = "k.id, k.key_hash, k.user_id, k.name, k.permissions, k.rate_limit_per_minute,
+                               k.last_used_at, k.expires_at, k.created_at";
+
+pub struct NewApiKey<'a> {
+    pub key_hash: &'a str,
+    pub user_id: Uuid,
+    pub name: &'a str,
+    pub permissions: &'a [Permission],
+    pub rate_limit_per_minute: u32,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// An unrevoked API key and the user it acts for
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct ApiKeyOwner {
+    #[sqlx(flatten)]
+    pub key: ApiKey,
+    pub wallet_address: String,
+    pub role: Role,
+}
+
+pub struct ApiKeyRepository {
+    pool: PgPool,
+}
+
+impl ApiKeyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// `Conflict` if the user does not exist
+    pub async fn create(&self, new_key: &NewApiKey<'_>) -> Result<ApiKey, RepositoryError> {
+        let rate_limit = i32::try_from(new_key.rate_limit_per_minute)
+            .map_err(|_| RepositoryError::InvalidInput("rate_limit_per_minute is too large".to_string()))?;
+
+        let key = sqlx::query_as::<_, ApiKey>(&format!(
+            "INSERT INTO api_keys AS k (key_hash, user_id, name, permissions, rate_limit_per_minute, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING {}",
+            API_KEY_COLUMNS
+        ))
+        .bind(new_key.key_hash)
+        .bind(new_key.user_id)
+        .bind(new_key.name)
+        .bind(new_key.permissions)
+        .bind(rate_limit)
+        .bind(new_key.expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(key)
+    }
+
+    /// Key with this hash and its owner, unless it is unknown or revoked. Expired keys
+    /// are returned so callers can tell them apart from unknown ones.
+    pub async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKeyOwner>, RepositoryError> {
+        let owner = sqlx::query_as::<_, ApiKeyOwner>(&format!(
+            "SELECT {}, u.wallet_address, u.role
+             FROM api_keys k
+             JOIN users u ON u.id = k.user_id
+             WHERE k.key_hash = $1 AND k.revoked_at IS NULL",
+            API_KEY_COLUMNS
+        ))
+        .bind(key_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(owner)
+    }
+
+    pub async fn touch(&self, id: Uuid) -> Result<(), RepositoryError> {
+        sqlx::query("UPDATE api_keys SET last_used_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Revoke one of a user's keys; `NotFound` if the user has no such unrevoked key
+    pub async fn revoke(&self, user_id: Uuid, id: Uuid) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            "UPDATE api_keys SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::content_attribution::{AttributedContent, AttributionType, ContentAttribution};
+
+pub struct ContentAttributionRepository {
+    pool: PgPool,
+}
+
+impl ContentAttributionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Attribute `secondary_content_id` to `primary_content_id`. `NotFound` if either is not
+    /// live content; `Conflict` if the secondary is already attributed or has attributions of
+    /// its own, or if the primary is itself attributed to another original.
+    pub async fn link(
+        &self,
+        primary_content_id: Uuid,
+        secondary_content_id: Uuid,
+        link_type: AttributionType,
+    ) -> Result<ContentAttribution, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Both rows are locked in id order, so concurrent links between the same content
+        // cannot both pass the checks below
+        let locked: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM content WHERE id IN ($1, $2) AND deleted_at IS NULL ORDER BY id FOR UPDATE",
+        )
+        .bind(primary_content_id)
+        .bind(secondary_content_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        if locked.len() < 2 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        let (primary_is_secondary, secondary_is_primary): (bool, bool) = sqlx::query_as(
+            "SELECT EXISTS (SELECT 1 FROM content_attributions WHERE secondary_content_id = $1),
+                    EXISTS (SELECT 1 FROM content_attributions WHERE primary_content_id = $2)",
+        )
+        .bind(primary_content_id)
+        .bind(secondary_content_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if primary_is_secondary {
+            return Err(RepositoryError::Conflict(
+                "primary content is attributed to another original; link to that instead".to_string(),
+            ));
+        }
+        if secondary_is_primary {
+            return Err(RepositoryError::Conflict("secondary content has attributions of its own".to_string()));
+        }
+
+        let attribution = sqlx::query_as::<_, ContentAttribution>(
+            "INSERT INTO content_attributions (primary_content_id, secondary_content_id, link_type)
+             VALUES ($1, $2, $3)
+             RETURNING id, primary_content_id, secondary_content_id, link_type, created_at",
+        )
+        .bind(primary_content_id)
+        .bind(secondary_content_id)
+        .bind(link_type)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(attribution)
+    }
+
+    /// Live primary the content is attributed to, if it is a secondary
+    pub async fn primary_of(&self, content_id: Uuid) -> Result<Option<Uuid>, RepositoryError> {
+        let primary = sqlx::query_scalar(
+            "SELECT a.primary_content_id FROM content_attributions a
+             JOIN content c ON c.id = a.primary_content_id
+             WHERE a.secondary_content_id = $1 AND c.deleted_at IS NULL",
+        )
+        .bind(content_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(primary)
+    }
+
+    /// A live content item at its own Echo Index. `NotFound` if there is none.
+    pub async fn attributed_content(&self, content_id: Uuid) -> Result<AttributedContent, RepositoryError> {
+        let content = sqlx::query_as::<_, AttributedContent>(
+            "SELECT id AS content_id, platform::text AS platform, COALESCE(echo_index, 0)::float8 AS echo_index,
+                    NULL::attribution_type AS link_type, NULL::timestamptz AS linked_at
+             FROM content WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(content_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(content)
+    }
+
+    /// Live content attributed to a primary, oldest link first
+    pub async fn secondaries(&self, primary_content_id: Uuid) -> Result<Vec<AttributedContent>, RepositoryError> {
+        let secondaries = sqlx::query_as::<_, AttributedContent>(
+            "SELECT c.id AS content_id, c.platform::text AS platform,
+                    COALESCE(c.echo_index, 0)::float8 AS echo_index,
+                    a.link_type, a.created_at AS linked_at
+             FROM content_attributions a
+             JOIN content c ON c.id = a.secondary_content_id
+             WHERE a.primary_content_id = $1 AND c.deleted_at IS NULL
+             ORDER BY a.created_at, a.id",
+        )
+        .bind(primary_content_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(secondaries)
+    }
+}
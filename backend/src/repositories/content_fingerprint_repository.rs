@@ -0,0 +1,58 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::services::ContentFingerprint;
+
+/// Postgres has no unsigned integers; hashes are stored bit-for-bit as BIGINT
+fn to_signed(values: &[u64]) -> Vec<i64> {
+    values.iter().map(|&value| value as i64).collect()
+}
+
+pub struct ContentFingerprintRepository {
+    pool: PgPool,
+}
+
+impl ContentFingerprintRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn store(&self, content_id: Uuid, fingerprint: &ContentFingerprint) -> Result<(), RepositoryError> {
+        sqlx::query(
+            "INSERT INTO content_fingerprints (content_id, minhash, band_hashes) VALUES ($1, $2, $3)
+             ON CONFLICT (content_id) DO UPDATE SET minhash = $2, band_hashes = $3",
+        )
+        .bind(content_id)
+        .bind(to_signed(&fingerprint.minhash))
+        .bind(to_signed(&fingerprint.band_hashes()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Live content sharing at least one LSH band with `fingerprint`
+    pub async fn candidates(
+        &self,
+        fingerprint: &ContentFingerprint,
+    ) -> Result<Vec<(Uuid, ContentFingerprint)>, RepositoryError> {
+        let rows: Vec<(Uuid, Vec<i64>)> = sqlx::query_as(
+            "SELECT f.content_id, f.minhash
+             FROM content_fingerprints f
+             JOIN content c ON c.id = f.content_id
+             WHERE f.band_hashes && $1 AND c.deleted_at IS NULL",
+        )
+        .bind(to_signed(&fingerprint.band_hashes()))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(content_id, minhash)| {
+                let minhash = minhash.into_iter().map(|value| value as u64).collect();
+                (content_id, ContentFingerprint { minhash })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,1253 @@
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::content::{
+    ArchiveStats, ArchivedContent, ContentRecord, ContentSearchHit, ContentSort, Propagation, ReactionType,
+    SortOrder, UserContentStats,
+};
+use crate::models::content_version::{change_summary, ContentVersion};
+use crate::models::echo_index::AudienceMetrics;
+use crate::models::echo_index_history::EchoIndexHistory;
+use crate::models::Platform;
+use crate::models::pagination::{Cursor, Page, ScoreCursor, SortCursor, SortKey};
+use crate::services::{ContentNLPPipeline, ContentNormalizer, PlatformNormalizer};
+
+/// Columns of `content` projected onto `ContentRecord`
+pub(crate) const CONTENT_COLUMNS: &str = "
+    id, user_id, platform::text AS platform, external_id, content_type::text AS content_type,
+    COALESCE(title, '') AS title, COALESCE(body, '') AS body,
+    COALESCE(media_urls, '{}') AS media_urls, COALESCE(tags, '{}') AS tags, auto_tags,
+    COALESCE(echo_index, 0)::float8 AS echo_index, COALESCE(propagation_count, 0) AS propagation_count,
+    COALESCE(total_rewards, 0)::float8 AS total_rewards, status::text AS status, reactions,
+    created_at, updated_at";
+
+/// Columns of `propagations` projected onto `Propagation`
+const PROPAGATION_COLUMNS: &str = "
+    id, content_id,
+    COALESCE(source_user_id, '00000000-0000-0000-0000-000000000000') AS from_user_id,
+    target_user_id AS to_user_id, target_platform::text AS platform,
+    propagation_type::text AS propagation_type, depth,
+    COALESCE(echo_boost, 1)::float8 AS weight, created_at AS timestamp,
+    COALESCE((engagement_metrics->>'reaches')::bigint, 0) AS reach,
+    COALESCE((engagement_metrics->>'likes')::bigint, 0)
+        + COALESCE((engagement_metrics->>'comments')::bigint, 0)
+        + COALESCE((engagement_metrics->>'shares')::bigint, 0) AS engagement,
+    fingerprint_checked, duplicate_of";
+
+/// Propagators whose latest influence composite reaches this count as influencers
+const INFLUENCER_COMPOSITE: f64 = 0.5;
+/// Share chains this many propagations deep or deeper count as fully engaged
+const MAX_ENGAGEMENT_DEPTH: i32 = 5;
+
+/// zstd level for archives, which are written once and rarely read
+const ARCHIVE_COMPRESSION_LEVEL: i32 = 9;
+
+/// A live content row with the JSON columns `ContentRecord` leaves out
+#[derive(FromRow)]
+struct ArchivableContentRow {
+    #[sqlx(flatten)]
+    content: ContentRecord,
+    platform_metadata: serde_json::Value,
+    echo_components: serde_json::Value,
+}
+
+/// Fields supplied when creating or replacing a content item
+#[derive(Debug, Clone)]
+pub struct NewContent {
+    pub user_id: Uuid,
+    pub platform: Platform,
+    pub external_id: String,
+    pub content_type: String,
+    pub title: String,
+    pub body: String,
+    pub media_urls: Vec<String>,
+    pub tags: Vec<String>,
+    /// Reactions the content already has on its platform
+    pub reactions: HashMap<ReactionType, u64>,
+}
+
+/// A live Farcaster cast whose recasts are tracked
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct TrackedCast {
+    pub content_id: Uuid,
+    pub author_id: Uuid,
+    /// The cast's hash, its external ID
+    pub cast_hash: String,
+    pub author_wallet: String,
+}
+
+/// Optional filters for content listings
+#[derive(Debug, Default)]
+pub struct ContentFilter {
+    pub user_id: Option<Uuid>,
+    pub platform: Option<Platform>,
+    pub status: Option<String>,
+}
+
+pub struct ContentRepository {
+    pool: PgPool,
+}
+
+impl ContentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert a content item; a duplicate `(platform, external_id)` is a conflict
+    pub async fn create(&self, content: &NewContent) -> Result<ContentRecord, RepositoryError> {
+        let query = format!(
+            "INSERT INTO content (user_id, platform, external_id, content_type, title, body, media_urls, tags,
+                                  reactions, auto_tags)
+             VALUES ($1, $2::platform_type, $3, $4::content_type, $5, $6, $7, $8, $9, $10)
+             RETURNING {}",
+            CONTENT_COLUMNS
+        );
+
+        let mut tx = self.pool.begin().await?;
+        let record = sqlx::query_as::<_, ContentRecord>(&query)
+            .bind(content.user_id)
+            .bind(&content.platform)
+            .bind(&content.external_id)
+            .bind(&content.content_type)
+            .bind(&content.title)
+            .bind(&content.body)
+            .bind(&content.media_urls)
+            .bind(&content.tags)
+            .bind(Json(&content.reactions))
+            .bind(auto_tags(content))
+            .fetch_one(&mut *tx)
+            .await?;
+        Self::set_hashtags(&mut tx, record.id, content).await?;
+        tx.commit().await?;
+
+        Ok(record)
+    }
+
+    /// Fetch a live content item by id
+    pub async fn find_by_id(&self, id: Uuid) -> Result<ContentRecord, RepositoryError> {
+        let query = format!(
+            "SELECT {} FROM content WHERE id = $1 AND deleted_at IS NULL",
+            CONTENT_COLUMNS
+        );
+
+        let record = sqlx::query_as::<_, ContentRecord>(&query)
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(record)
+    }
+
+    /// Fetch the live content items among `ids`, in no particular order
+    pub async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<ContentRecord>, RepositoryError> {
+        let query = format!(
+            "SELECT {} FROM content WHERE id = ANY($1) AND deleted_at IS NULL",
+            CONTENT_COLUMNS
+        );
+
+        let records = sqlx::query_as::<_, ContentRecord>(&query)
+            .bind(ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(records)
+    }
+
+    /// Replace the editable fields of a live content item, first keeping the title and
+    /// body it replaces as its next version in `content_versions`. The author is kept.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        content: &NewContent,
+        updated_by: Option<Uuid>,
+    ) -> Result<ContentRecord, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let (title, body, echo_index): (String, String, f64) = sqlx::query_as(
+            "SELECT COALESCE(title, ''), COALESCE(body, ''), COALESCE(echo_index, 0)::float8
+             FROM content WHERE id = $1 AND deleted_at IS NULL
+             FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query(
+            "INSERT INTO content_versions
+                 (content_id, version_number, title, body, updated_by, change_summary, echo_index)
+             SELECT $1, COALESCE(MAX(version_number), 0) + 1, $2, $3, $4, $5, $6
+             FROM content_versions WHERE content_id = $1",
+        )
+        .bind(id)
+        .bind(&title)
+        .bind(&body)
+        .bind(updated_by)
+        .bind(change_summary(&title, &body, &content.title, &content.body))
+        .bind(echo_index)
+        .execute(&mut *tx)
+        .await?;
+
+        let query = format!(
+            "UPDATE content
+             SET platform = $2::platform_type, external_id = $3, content_type = $4::content_type,
+                 title = $5, body = $6, media_urls = $7, tags = $8, auto_tags = $9
+             WHERE id = $1
+             RETURNING {}",
+            CONTENT_COLUMNS
+        );
+
+        let record = sqlx::query_as::<_, ContentRecord>(&query)
+            .bind(id)
+            .bind(&content.platform)
+            .bind(&content.external_id)
+            .bind(&content.content_type)
+            .bind(&content.title)
+            .bind(&content.body)
+            .bind(&content.media_urls)
+            .bind(&content.tags)
+            .bind(auto_tags(content))
+            .fetch_one(&mut *tx)
+            .await?;
+        Self::set_hashtags(&mut tx, id, content).await?;
+        tx.commit().await?;
+
+        Ok(record)
+    }
+
+    /// Index the hashtags in the body of `content`, lowercased. Hashtags already indexed
+    /// keep the time they were first seen; those no longer in the body are dropped.
+    async fn set_hashtags(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        content: &NewContent,
+    ) -> Result<(), RepositoryError> {
+        let hashtags: Vec<String> = PlatformNormalizer::normalize(&content.body, &content.platform)
+            .hashtags
+            .iter()
+            .map(|hashtag| hashtag.to_lowercase())
+            .collect();
+
+        sqlx::query("DELETE FROM content_hashtags WHERE content_id = $1 AND NOT (hashtag = ANY($2))")
+            .bind(id)
+            .bind(&hashtags)
+            .execute(&mut **tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO content_hashtags (content_id, hashtag)
+             SELECT $1, UNNEST($2::text[])
+             ON CONFLICT (content_id, hashtag) DO NOTHING",
+        )
+        .bind(id)
+        .bind(&hashtags)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark a content item deleted; it disappears from reads but the row is kept
+    pub async fn soft_delete(&self, id: Uuid) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            "UPDATE content SET status = 'deleted', deleted_at = NOW()
+             WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Store a freshly calculated Echo Index on a live content item
+    pub async fn set_echo_index(&self, id: Uuid, echo_index: f64) -> Result<(), RepositoryError> {
+        let result = sqlx::query("UPDATE content SET echo_index = $2 WHERE id = $1 AND deleted_at IS NULL")
+            .bind(id)
+            .bind(echo_index)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Whether the author of a content item verified their account on its platform
+    pub async fn author_verified(&self, content_id: Uuid) -> Result<bool, RepositoryError> {
+        let verified = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM content c
+                 JOIN social_platforms s ON s.user_id = c.user_id AND s.platform = c.platform
+                 WHERE c.id = $1 AND s.verification_status = 'verified'
+             )",
+        )
+        .bind(content_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(verified)
+    }
+
+    /// Live content the author of a content item published before it
+    pub async fn prior_content_count(&self, content_id: Uuid) -> Result<u32, RepositoryError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM content prior
+             JOIN content c ON c.user_id = prior.user_id
+             WHERE c.id = $1 AND prior.deleted_at IS NULL AND (prior.created_at, prior.id) < (c.created_at, c.id)",
+        )
+        .bind(content_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(u32::try_from(count).unwrap_or(u32::MAX))
+    }
+
+    /// Reach of a content item and of each live content item it is cross-posted as or
+    /// from, as `(own reach, linked reach)` pairs. Reach is summed over propagations.
+    pub async fn cross_post_reaches(&self, content_id: Uuid) -> Result<Vec<(u64, u64)>, RepositoryError> {
+        let reaches: Vec<(i64, i64)> = sqlx::query_as(
+            "WITH reach AS (
+                 SELECT content_id, SUM(COALESCE((engagement_metrics->>'reaches')::bigint, 0))::bigint AS reach
+                 FROM propagations GROUP BY content_id
+             )
+             SELECT COALESCE((SELECT reach FROM reach WHERE content_id = $1), 0),
+                    COALESCE(linked_reach.reach, 0)
+             FROM content_attributions a
+             JOIN content linked ON linked.id = CASE
+                 WHEN a.primary_content_id = $1 THEN a.secondary_content_id
+                 ELSE a.primary_content_id
+             END
+             LEFT JOIN reach linked_reach ON linked_reach.content_id = linked.id
+             WHERE (a.primary_content_id = $1 OR a.secondary_content_id = $1)
+               AND a.link_type = 'cross_post' AND linked.deleted_at IS NULL",
+        )
+        .bind(content_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(reaches
+            .into_iter()
+            .map(|(own, linked)| (own.max(0) as u64, linked.max(0) as u64))
+            .collect())
+    }
+
+    /// Propagations of a content item in the shape used by Echo Index calculation
+    pub async fn list_propagations(&self, content_id: Uuid) -> Result<Vec<Propagation>, RepositoryError> {
+        let propagations = sqlx::query_as::<_, Propagation>(&format!(
+            "SELECT {} FROM propagations WHERE content_id = $1 ORDER BY created_at",
+            PROPAGATION_COLUMNS
+        ))
+        .bind(content_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(propagations)
+    }
+
+    /// Propagations of a content item at least `min_depth` hops from the original post,
+    /// deepest first
+    pub async fn list_deep_propagations(
+        &self,
+        content_id: Uuid,
+        min_depth: i32,
+        limit: i64,
+    ) -> Result<Vec<Propagation>, RepositoryError> {
+        let propagations = sqlx::query_as::<_, Propagation>(&format!(
+            "SELECT {} FROM propagations WHERE content_id = $1 AND depth >= $2
+             ORDER BY depth DESC, created_at LIMIT $3",
+            PROPAGATION_COLUMNS
+        ))
+        .bind(content_id)
+        .bind(min_depth)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(propagations)
+    }
+
+    /// When the content item was last propagated; None if it never was
+    pub async fn last_propagated_at(&self, content_id: Uuid) -> Result<Option<DateTime<Utc>>, RepositoryError> {
+        let last = sqlx::query_scalar("SELECT MAX(created_at) FROM propagations WHERE content_id = $1")
+            .bind(content_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(last)
+    }
+
+    /// Echo Index of every live content item that has been scored
+    pub async fn echo_index_scores(&self) -> Result<Vec<(Uuid, f64)>, RepositoryError> {
+        let scores = sqlx::query_as::<_, (Uuid, f64)>(
+            "SELECT id, echo_index::float8 FROM content WHERE deleted_at IS NULL AND echo_index > 0",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(scores)
+    }
+
+    /// Audience of a content item as recorded on its propagations: interactions (comments
+    /// and shares counting as quality ones), the share of propagations reaching a distinct
+    /// user, the share of propagators who are influencers, and how deep share chains run.
+    pub async fn audience_metrics(&self, content_id: Uuid) -> Result<AudienceMetrics, RepositoryError> {
+        let (total, quality, audience_diversity, influencer_ratio, engagement_depth) =
+            sqlx::query_as::<_, (i64, i64, f64, f64, f64)>(
+                "WITH p AS (
+                     SELECT source_user_id, target_user_id, depth,
+                            COALESCE((engagement_metrics->>'likes')::bigint, 0) AS likes,
+                            COALESCE((engagement_metrics->>'comments')::bigint, 0) AS comments,
+                            COALESCE((engagement_metrics->>'shares')::bigint, 0) AS shares
+                     FROM propagations WHERE content_id = $1
+                 )
+                 SELECT
+                     COALESCE(SUM(likes + comments + shares), 0)::bigint,
+                     COALESCE(SUM(comments + shares), 0)::bigint,
+                     COALESCE(COUNT(DISTINCT target_user_id)::float8 / NULLIF(COUNT(*), 0), 0),
+                     COALESCE((
+                         SELECT AVG((COALESCE(latest.composite, 0) >= $2)::int)::float8
+                         FROM (SELECT DISTINCT source_user_id FROM p WHERE source_user_id IS NOT NULL) propagators
+                         LEFT JOIN LATERAL (
+                             SELECT composite FROM user_influence_scores
+                             WHERE user_id = propagators.source_user_id
+                             ORDER BY calculated_at DESC LIMIT 1
+                         ) latest ON TRUE
+                     ), 0),
+                     COALESCE(AVG((LEAST(depth, $3) - 1)::float8 / ($3 - 1)), 0)
+                 FROM p",
+            )
+            .bind(content_id)
+            .bind(INFLUENCER_COMPOSITE)
+            .bind(MAX_ENGAGEMENT_DEPTH)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(AudienceMetrics {
+            total_interactions: total.min(i32::MAX as i64) as i32,
+            quality_interactions: quality.min(i32::MAX as i64) as i32,
+            audience_diversity,
+            influencer_ratio,
+            engagement_depth,
+        })
+    }
+
+    /// Every content item the user authored, deleted or not, oldest first
+    pub async fn list_by_author(&self, user_id: Uuid) -> Result<Vec<ContentRecord>, RepositoryError> {
+        let content = sqlx::query_as::<_, ContentRecord>(&format!(
+            "SELECT {} FROM content WHERE user_id = $1 ORDER BY created_at, id",
+            CONTENT_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(content)
+    }
+
+    /// Live content tagged with `hashtag`, highest Echo Index first. A page continues
+    /// after the `(echo_index, id)` position of the previous page's last item.
+    pub async fn list_by_hashtag(
+        &self,
+        hashtag: &str,
+        limit: u32,
+        after: Option<ScoreCursor>,
+    ) -> Result<Vec<ContentRecord>, RepositoryError> {
+        let content = sqlx::query_as::<_, ContentRecord>(&format!(
+            "SELECT {} FROM content
+             WHERE id IN (SELECT content_id FROM content_hashtags WHERE hashtag = $1)
+               AND deleted_at IS NULL
+               AND ($3::float8 IS NULL OR $4::uuid IS NULL
+                    OR COALESCE(echo_index, 0) < $3::numeric
+                    OR (COALESCE(echo_index, 0) = $3::numeric AND id > $4))
+             ORDER BY COALESCE(echo_index, 0) DESC, id ASC
+             LIMIT $2",
+            CONTENT_COLUMNS
+        ))
+        .bind(hashtag)
+        .bind(limit as i64)
+        .bind(after.map(|cursor| cursor.score))
+        .bind(after.map(|cursor| cursor.id))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(content)
+    }
+
+    /// Insert content imported from a platform export, dated and hashtag-indexed as of when
+    /// it was first published. False when the platform's item is already stored.
+    pub async fn create_imported(
+        &self,
+        content: &NewContent,
+        created_at: DateTime<Utc>,
+        echo_index: f64,
+        platform_metadata: &serde_json::Value,
+    ) -> Result<bool, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        let id: Option<Uuid> = sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type, title, body, echo_index,
+                                  platform_metadata, reactions, created_at, updated_at, auto_tags)
+             VALUES ($1, $2::platform_type, $3, $4::content_type, $5, $6, $7, $8, $9, $10, $10, $11)
+             ON CONFLICT (platform, external_id) DO NOTHING
+             RETURNING id",
+        )
+        .bind(content.user_id)
+        .bind(&content.platform)
+        .bind(&content.external_id)
+        .bind(&content.content_type)
+        .bind(&content.title)
+        .bind(&content.body)
+        .bind(echo_index)
+        .bind(platform_metadata)
+        .bind(Json(&content.reactions))
+        .bind(created_at)
+        .bind(auto_tags(content))
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(id) = id else {
+            return Ok(false);
+        };
+
+        // Dated when posted, so old hashtags do not count as trending now
+        let hashtags: Vec<String> = PlatformNormalizer::normalize(&content.body, &content.platform)
+            .hashtags
+            .iter()
+            .map(|hashtag| hashtag.to_lowercase())
+            .collect();
+        sqlx::query(
+            "INSERT INTO content_hashtags (content_id, hashtag, created_at)
+             SELECT $1, UNNEST($2::text[]), $3
+             ON CONFLICT (content_id, hashtag) DO NOTHING",
+        )
+        .bind(id)
+        .bind(&hashtags)
+        .bind(created_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Record an import by the user at `now` unless they imported content after `since`.
+    /// False when they did.
+    pub async fn claim_import(
+        &self,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+        since: DateTime<Utc>,
+    ) -> Result<bool, RepositoryError> {
+        let claimed = sqlx::query(
+            "INSERT INTO content_imports (user_id, imported_at) VALUES ($1, $2)
+             ON CONFLICT (user_id) DO UPDATE SET imported_at = $2
+             WHERE content_imports.imported_at <= $3
+             RETURNING user_id",
+        )
+        .bind(user_id)
+        .bind(now)
+        .bind(since)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(claimed.is_some())
+    }
+
+    /// Live scored content of a platform, highest Echo Index first
+    pub async fn list_top_by_platform(
+        &self,
+        platform: &Platform,
+        limit: u32,
+    ) -> Result<Vec<ContentRecord>, RepositoryError> {
+        let content = sqlx::query_as::<_, ContentRecord>(&format!(
+            "SELECT {} FROM content
+             WHERE platform::text = $1 AND deleted_at IS NULL AND echo_index > 0
+             ORDER BY echo_index DESC, id ASC
+             LIMIT $2",
+            CONTENT_COLUMNS
+        ))
+        .bind(platform.as_str())
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(content)
+    }
+
+    /// Live Farcaster casts published since `since`, newest first
+    pub async fn list_tracked_casts(&self, since: DateTime<Utc>) -> Result<Vec<TrackedCast>, RepositoryError> {
+        let casts = sqlx::query_as::<_, TrackedCast>(
+            "SELECT c.id AS content_id, c.user_id AS author_id, c.external_id AS cast_hash,
+                    u.wallet_address AS author_wallet
+             FROM content c
+             JOIN users u ON u.id = c.user_id
+             WHERE c.platform = 'farcaster' AND c.deleted_at IS NULL AND c.created_at >= $1
+             ORDER BY c.created_at DESC, c.id ASC",
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(casts)
+    }
+
+    /// Every propagation the user made, oldest first
+    pub async fn list_propagations_by_user(&self, user_id: Uuid) -> Result<Vec<Propagation>, RepositoryError> {
+        let propagations = sqlx::query_as::<_, Propagation>(&format!(
+            "SELECT {} FROM propagations WHERE source_user_id = $1 ORDER BY created_at, id",
+            PROPAGATION_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(propagations)
+    }
+
+    /// Full-text search over title, body and tags, most relevant first. Content matching
+    /// any of the query's words is returned, ranked higher the more of them it contains.
+    /// Pages continue after the `(relevance, created_at, id)` position of the cursor row.
+    pub async fn search(
+        &self,
+        query: &str,
+        platform: Option<Platform>,
+        min_echo: Option<f64>,
+        limit: u32,
+        after: Option<Cursor>,
+    ) -> Result<Page<ContentSearchHit>, RepositoryError> {
+        // plainto_tsquery normalizes the words and joins them with AND; switching to OR
+        // lets partial matches through while ts_rank still favours complete ones
+        let sql = format!(
+            "WITH search AS (
+                 SELECT replace(plainto_tsquery('english', $1)::text, '&', '|')::tsquery AS query
+             ),
+             ranked AS (
+                 SELECT {}, ts_rank(content.search_vector, search.query)::float8 AS relevance_score
+                 FROM content, search
+                 WHERE deleted_at IS NULL
+                   AND content.search_vector @@ search.query
+                   AND ($2::text IS NULL OR platform::text = $2)
+                   AND ($3::float8 IS NULL OR echo_index >= $3)
+             )
+             SELECT * FROM ranked
+             WHERE $4::uuid IS NULL
+                OR (relevance_score, created_at, id) < ((SELECT relevance_score FROM ranked WHERE id = $4), $5, $4)
+             ORDER BY relevance_score DESC, created_at DESC, id DESC
+             LIMIT $6",
+            CONTENT_COLUMNS
+        );
+
+        // Fetch one extra row to learn whether another page exists
+        let rows = sqlx::query_as::<_, ContentSearchHit>(&sql)
+            .bind(query)
+            .bind(platform.as_ref().map(Platform::as_str))
+            .bind(min_echo)
+            .bind(after.map(|cursor| cursor.id))
+            .bind(after.map(|cursor| cursor.created_at))
+            .bind(limit as i64 + 1)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(Page::from_rows(rows, limit as usize, |hit| {
+            Cursor::new(hit.content.created_at, hit.content.id)
+        }))
+    }
+
+    /// List content newest first using keyset pagination on `(created_at, id)`
+    pub async fn list(
+        &self,
+        filter: &ContentFilter,
+        after: Option<Cursor>,
+        limit: u32,
+    ) -> Result<Page<ContentRecord>, RepositoryError> {
+        let query = format!(
+            "SELECT {} FROM content
+             WHERE deleted_at IS NULL
+               AND ($1::timestamptz IS NULL OR (created_at, id) < ($1, $2))
+               AND ($3::uuid IS NULL OR user_id = $3)
+               AND ($4::text IS NULL OR platform::text = $4)
+               AND ($5::text IS NULL OR status::text = $5)
+             ORDER BY created_at DESC, id DESC
+             LIMIT $6",
+            CONTENT_COLUMNS
+        );
+
+        // Fetch one extra row to learn whether another page exists
+        let rows = sqlx::query_as::<_, ContentRecord>(&query)
+            .bind(after.map(|cursor| cursor.created_at))
+            .bind(after.map(|cursor| cursor.id))
+            .bind(filter.user_id)
+            .bind(filter.platform.as_ref().map(Platform::as_str))
+            .bind(filter.status.as_deref())
+            .bind(limit as i64 + 1)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(Page::from_rows(rows, limit as usize, |row| {
+            Cursor::new(row.created_at, row.id)
+        }))
+    }
+
+    /// One page of an author's live content in `sort` order, ties broken by id
+    pub async fn find_by_author(
+        &self,
+        author_id: Uuid,
+        platform: Option<&Platform>,
+        sort: ContentSort,
+        order: SortOrder,
+        after: Option<SortCursor>,
+        limit: u32,
+    ) -> Result<Page<ContentRecord>, RepositoryError> {
+        let (key, key_type) = match sort {
+            ContentSort::EchoIndex => ("COALESCE(echo_index, 0)::float8", "float8"),
+            ContentSort::PropagationCount => ("COALESCE(propagation_count, 0)::float8", "float8"),
+            ContentSort::CreatedAt => ("created_at", "timestamptz"),
+        };
+        let (direction, comparison) = match order {
+            SortOrder::Asc => ("ASC", ">"),
+            SortOrder::Desc => ("DESC", "<"),
+        };
+        let query = format!(
+            "SELECT {columns} FROM content
+             WHERE user_id = $1 AND deleted_at IS NULL
+               AND ($2::text IS NULL OR platform::text = $2)
+               AND ($3::uuid IS NULL OR ({key}, id) {comparison} ($4::{key_type}, $3))
+             ORDER BY {key} {direction}, id {direction}
+             LIMIT $5",
+            columns = CONTENT_COLUMNS,
+        );
+
+        let query = sqlx::query_as::<_, ContentRecord>(&query)
+            .bind(author_id)
+            .bind(platform.map(Platform::as_str))
+            .bind(after.map(|cursor| cursor.id));
+        let query = match (sort, after.map(|cursor| cursor.key)) {
+            (ContentSort::CreatedAt, Some(SortKey::Time(created_at))) => query.bind(Some(created_at)),
+            (ContentSort::CreatedAt, None) => query.bind(None::<DateTime<Utc>>),
+            (ContentSort::EchoIndex | ContentSort::PropagationCount, Some(SortKey::Number(value))) => {
+                query.bind(Some(value))
+            }
+            (ContentSort::EchoIndex | ContentSort::PropagationCount, None) => query.bind(None::<f64>),
+            _ => return Err(RepositoryError::InvalidCursor("cursor belongs to another sort order".to_string())),
+        };
+
+        // Fetch one extra row to learn whether another page exists
+        let rows = query.bind(limit as i64 + 1).fetch_all(&self.pool).await?;
+
+        Ok(Page::from_rows_with(rows, limit as usize, |row| {
+            let key = match sort {
+                ContentSort::EchoIndex => SortKey::Number(row.echo_index),
+                ContentSort::PropagationCount => SortKey::Number(f64::from(row.propagation_count)),
+                ContentSort::CreatedAt => SortKey::Time(row.created_at),
+            };
+            SortCursor::new(key, row.id).encode()
+        }))
+    }
+
+    /// Number of an author's live content items and totals over them
+    pub async fn author_content_stats(
+        &self,
+        author_id: Uuid,
+        platform: Option<&Platform>,
+    ) -> Result<(u64, UserContentStats), RepositoryError> {
+        let (count, avg_echo_index, max_echo_index, total_propagations, total_rewards): (i64, f64, f64, i64, f64) =
+            sqlx::query_as(
+                "SELECT COUNT(*),
+                        COALESCE(AVG(COALESCE(echo_index, 0)), 0)::float8,
+                        COALESCE(MAX(COALESCE(echo_index, 0)), 0)::float8,
+                        COALESCE(SUM(COALESCE(propagation_count, 0)), 0)::bigint,
+                        COALESCE(SUM(COALESCE(total_rewards, 0)), 0)::float8
+                 FROM content
+                 WHERE user_id = $1 AND deleted_at IS NULL
+                   AND ($2::text IS NULL OR platform::text = $2)",
+            )
+            .bind(author_id)
+            .bind(platform.map(Platform::as_str))
+            .fetch_one(&self.pool)
+            .await?;
+
+        let stats = UserContentStats {
+            avg_echo_index,
+            max_echo_index,
+            total_propagations: total_propagations.max(0) as u64,
+            total_rewards,
+        };
+        Ok((count.max(0) as u64, stats))
+    }
+
+    /// Move a live content item into cold storage. The item, its Echo Index history, its
+    /// propagations and its previous versions are serialized into one zstd-compressed blob and deleted from the
+    /// live tables in the same transaction.
+    pub async fn archive_content(&self, content_id: Uuid) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = format!(
+            "SELECT {}, COALESCE(platform_metadata, '{{}}') AS platform_metadata,
+                    COALESCE(echo_components, '{{}}') AS echo_components
+             FROM content WHERE id = $1 AND deleted_at IS NULL
+             FOR UPDATE",
+            CONTENT_COLUMNS
+        );
+        let row = sqlx::query_as::<_, ArchivableContentRow>(&query)
+            .bind(content_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let echo_history = sqlx::query_as::<_, EchoIndexHistory>(
+            "SELECT id, content_id, score, odf, awr, tpm, qf, calculated_at, delta_score, trigger,
+                    experiment_id, variant
+             FROM echo_index_history WHERE content_id = $1
+             ORDER BY calculated_at, id",
+        )
+        .bind(content_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let propagations = sqlx::query_scalar::<_, serde_json::Value>(
+            "SELECT to_jsonb(p) FROM propagations p WHERE content_id = $1 ORDER BY created_at, id",
+        )
+        .bind(content_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let versions = sqlx::query_as::<_, ContentVersion>(
+            "SELECT id, content_id, version_number, title, body, updated_by, updated_at, change_summary,
+                    echo_index, flagged_at
+             FROM content_versions WHERE content_id = $1
+             ORDER BY version_number",
+        )
+        .bind(content_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let archive = ArchivedContent {
+            content: row.content,
+            platform_metadata: row.platform_metadata,
+            echo_components: row.echo_components,
+            echo_history,
+            propagations,
+            versions,
+            archived_at: Utc::now(),
+        };
+        let json = serde_json::to_vec(&archive).map_err(|e| RepositoryError::Archive(e.to_string()))?;
+        let compressed = zstd::bulk::compress(&json, ARCHIVE_COMPRESSION_LEVEL)
+            .map_err(|e| RepositoryError::Archive(e.to_string()))?;
+
+        sqlx::query(
+            "INSERT INTO archived_content (content_id, user_id, compressed_data, uncompressed_size, archived_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(content_id)
+        .bind(archive.content.user_id)
+        .bind(&compressed)
+        .bind(json.len() as i64)
+        .bind(archive.archived_at)
+        .execute(&mut *tx)
+        .await?;
+
+        // Propagations, history, versions and fingerprints cascade with the content row
+        sqlx::query("DELETE FROM content WHERE id = $1")
+            .bind(content_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Decompress an archived content item
+    pub async fn find_archived(&self, content_id: Uuid) -> Result<ArchivedContent, RepositoryError> {
+        let (compressed, uncompressed_size) = sqlx::query_as::<_, (Vec<u8>, i64)>(
+            "SELECT compressed_data, uncompressed_size FROM archived_content WHERE content_id = $1",
+        )
+        .bind(content_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let json = zstd::bulk::decompress(&compressed, uncompressed_size as usize)
+            .map_err(|e| RepositoryError::Archive(e.to_string()))?;
+        let archive = serde_json::from_slice(&json).map_err(|e| RepositoryError::Archive(e.to_string()))?;
+
+        Ok(archive)
+    }
+
+    /// Archive up to `limit` live items not updated since `cutoff` whose Echo Index is
+    /// below `max_echo_index`, returning how many were archived
+    pub async fn archive_stale(
+        &self,
+        cutoff: DateTime<Utc>,
+        max_echo_index: f64,
+        limit: u32,
+    ) -> Result<usize, RepositoryError> {
+        let stale: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM content
+             WHERE deleted_at IS NULL AND updated_at < $1 AND COALESCE(echo_index, 0) < $2
+             ORDER BY updated_at
+             LIMIT $3",
+        )
+        .bind(cutoff)
+        .bind(max_echo_index)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut archived = 0;
+        for content_id in stale {
+            match self.archive_content(content_id).await {
+                Ok(()) => archived += 1,
+                // Deleted or archived concurrently
+                Err(RepositoryError::NotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(archived)
+    }
+
+    /// Up to `limit` live items with a positive Echo Index not updated since `cutoff`, in
+    /// id order after `after`
+    pub async fn list_stale_scores(
+        &self,
+        cutoff: DateTime<Utc>,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<ContentRecord>, RepositoryError> {
+        let content = sqlx::query_as::<_, ContentRecord>(&format!(
+            "SELECT {} FROM content
+             WHERE deleted_at IS NULL AND updated_at < $1 AND echo_index > 0
+               AND ($2::uuid IS NULL OR id > $2)
+             ORDER BY id
+             LIMIT $3",
+            CONTENT_COLUMNS
+        ))
+        .bind(cutoff)
+        .bind(after)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(content)
+    }
+
+    /// Counts of live and archived content and the space archiving has saved
+    pub async fn archive_stats(&self) -> Result<ArchiveStats, RepositoryError> {
+        let stats = sqlx::query_as::<_, ArchiveStats>(
+            "SELECT (SELECT COUNT(*) FROM content WHERE deleted_at IS NULL) AS live_content,
+                    COUNT(*) AS archived_content,
+                    COALESCE(SUM(uncompressed_size), 0)::bigint AS uncompressed_bytes,
+                    COALESCE(SUM(octet_length(compressed_data)), 0)::bigint AS compressed_bytes
+             FROM archived_content",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+}
+
+/// Topic tags of the title and body of `content`; the title is its own line so it ends
+/// a sentence
+fn auto_tags(content: &NewContent) -> Vec<String> {
+    ContentNLPPipeline::extract_entities(&format!("{}\n{}", content.title, content.body)).auto_tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn insert_user(pool: &PgPool, wallet: &str) -> Uuid {
+        sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+            .bind(wallet)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    fn new_content(user_id: Uuid, external_id: &str) -> NewContent {
+        NewContent {
+            user_id,
+            platform: Platform::Twitter,
+            external_id: external_id.to_string(),
+            content_type: "text".to_string(),
+            title: "Echoes".to_string(),
+            body: "Signal travels".to_string(),
+            media_urls: vec![],
+            tags: vec!["echo".to_string()],
+            reactions: HashMap::new(),
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_create_find_update_delete(pool: PgPool) {
+        let repository = ContentRepository::new(pool.clone());
+        let user_id = insert_user(&pool, "0xabc").await;
+
+        let created = repository.create(&new_content(user_id, "tweet_1")).await.unwrap();
+        assert_eq!(created.status, "active");
+
+        let found = repository.find_by_id(created.id).await.unwrap();
+        assert_eq!(found.external_id, "tweet_1");
+
+        let mut changes = new_content(user_id, "tweet_1");
+        changes.title = "Louder echoes".to_string();
+        let updated = repository.update(created.id, &changes, Some(user_id)).await.unwrap();
+        assert_eq!(updated.title, "Louder echoes");
+
+        repository.soft_delete(created.id).await.unwrap();
+        assert!(matches!(repository.find_by_id(created.id).await, Err(RepositoryError::NotFound)));
+        assert!(matches!(repository.soft_delete(created.id).await, Err(RepositoryError::NotFound)));
+
+        let page = repository.list(&ContentFilter::default(), None, 10).await.unwrap();
+        assert!(page.data.is_empty());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_duplicate_external_id_conflicts(pool: PgPool) {
+        let repository = ContentRepository::new(pool.clone());
+        let user_id = insert_user(&pool, "0xdef").await;
+
+        repository.create(&new_content(user_id, "tweet_2")).await.unwrap();
+        let duplicate = repository.create(&new_content(user_id, "tweet_2")).await;
+        assert!(matches!(duplicate, Err(RepositoryError::Conflict(_))));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_missing_content_is_not_found(pool: PgPool) {
+        let repository = ContentRepository::new(pool);
+        let missing = repository.update(Uuid::new_v4(), &new_content(Uuid::new_v4(), "tweet_3"), None).await;
+        assert!(matches!(missing, Err(RepositoryError::NotFound)));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_search_ranks_full_matches_above_partial(pool: PgPool) {
+        let repository = ContentRepository::new(pool.clone());
+        let user_id = insert_user(&pool, "0xsearch").await;
+
+        let mut full = new_content(user_id, "tweet_full");
+        full.title = "Scaling blockchain consensus".to_string();
+        full.body = "Rollups batch transactions before settling".to_string();
+        let full = repository.create(&full).await.unwrap();
+
+        let mut partial = new_content(user_id, "tweet_partial");
+        partial.title = "Consensus explained".to_string();
+        partial.body = "Every blockchain needs agreement".to_string();
+        let partial = repository.create(&partial).await.unwrap();
+
+        let mut tagged = new_content(user_id, "cast_tagged");
+        tagged.platform = Platform::Farcaster;
+        tagged.body = "Notes from the weekend".to_string();
+        tagged.tags = vec!["blockchain".to_string()];
+        let tagged = repository.create(&tagged).await.unwrap();
+
+        repository.create(&new_content(user_id, "tweet_unrelated")).await.unwrap();
+        repository.set_echo_index(full.id, 80.0).await.unwrap();
+        repository.set_echo_index(partial.id, 30.0).await.unwrap();
+
+        let page = repository.search("scaling blockchain consensus", None, None, 10, None).await.unwrap();
+        let ids: Vec<Uuid> = page.data.iter().map(|hit| hit.content.id).collect();
+        assert_eq!(ids, [full.id, partial.id, tagged.id]);
+        assert!(page.data[0].relevance_score > page.data[1].relevance_score);
+        assert!(page.data[1].relevance_score > page.data[2].relevance_score);
+
+        let first = repository.search("scaling blockchain consensus", None, None, 1, None).await.unwrap();
+        let cursor = Cursor::decode(first.next_cursor.as_deref().unwrap()).unwrap();
+        let rest = repository.search("scaling blockchain consensus", None, None, 5, Some(cursor)).await.unwrap();
+        assert_eq!(rest.data.iter().map(|hit| hit.content.id).collect::<Vec<_>>(), [partial.id, tagged.id]);
+
+        let twitter = repository.search("blockchain", Some(Platform::Twitter), Some(50.0), 10, None).await.unwrap();
+        assert_eq!(twitter.data.iter().map(|hit| hit.content.id).collect::<Vec<_>>(), [full.id]);
+
+        assert!(repository.search("mempool", None, None, 10, None).await.unwrap().data.is_empty());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_archive_round_trip(pool: PgPool) {
+        use crate::models::echo_index_history::EchoIndexTrigger;
+        use crate::repositories::{EchoIndexHistoryRepository, EchoIndexScores};
+
+        let repository = ContentRepository::new(pool.clone());
+        let user_id = insert_user(&pool, "0xarchive").await;
+        let mut content = new_content(user_id, "tweet_cold");
+        content.media_urls = vec!["https://example.com/a.png".to_string()];
+        let content = repository.create(&content).await.unwrap();
+        repository.set_echo_index(content.id, 12.5).await.unwrap();
+
+        let history = EchoIndexHistoryRepository::new(pool.clone());
+        let scores = EchoIndexScores { score: 12.5, odf: 0.4, awr: 0.1, tpm: 0.2, qf: 0.05 };
+        let recorded = history.record(content.id, scores, EchoIndexTrigger::Initial, None).await.unwrap();
+        sqlx::query("UPDATE content SET platform_metadata = '{\"lang\": \"en\"}' WHERE id = $1")
+            .bind(content.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO propagations (content_id, source_user_id, propagation_type, source_platform, target_platform,
+                                       engagement_metrics)
+             VALUES ($1, $2, 'quote', 'twitter', 'farcaster', '{\"likes\": 4}')",
+        )
+        .bind(content.id)
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO content_versions (content_id, version_number, title, body, updated_by, change_summary, echo_index)
+             VALUES ($1, 1, 'First draft', 'Old body', $2, 'Rewrote the body', 12.5)",
+        )
+        .bind(content.id)
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let live = repository.find_by_id(content.id).await.unwrap();
+
+        let kept = repository.create(&new_content(user_id, "tweet_hot")).await.unwrap();
+        repository.set_echo_index(kept.id, 45.0).await.unwrap();
+
+        // Nothing is older than a day yet; everything is older than tomorrow
+        assert_eq!(repository.archive_stale(Utc::now() - chrono::Duration::days(1), 20.0, 10).await.unwrap(), 0);
+        assert_eq!(repository.archive_stale(Utc::now() + chrono::Duration::days(1), 20.0, 10).await.unwrap(), 1);
+
+        assert!(matches!(repository.find_by_id(content.id).await, Err(RepositoryError::NotFound)));
+        assert!(repository.find_by_id(kept.id).await.is_ok());
+
+        let archived = repository.find_archived(content.id).await.unwrap();
+        assert_eq!(serde_json::to_value(&archived.content).unwrap(), serde_json::to_value(&live).unwrap());
+        assert_eq!(archived.platform_metadata, serde_json::json!({ "lang": "en" }));
+        assert_eq!(archived.echo_history.len(), 1);
+        assert_eq!(
+            serde_json::to_value(&archived.echo_history[0]).unwrap(),
+            serde_json::to_value(&recorded).unwrap()
+        );
+        assert_eq!(archived.propagations.len(), 1);
+        assert_eq!(archived.propagations[0]["propagation_type"], "quote");
+        assert_eq!(archived.propagations[0]["engagement_metrics"]["likes"], 4);
+        assert_eq!(archived.versions.len(), 1);
+        assert_eq!((archived.versions[0].version_number, archived.versions[0].title.as_str()), (1, "First draft"));
+        assert_eq!(archived.versions[0].updated_by, Some(user_id));
+
+        let stats = repository.archive_stats().await.unwrap();
+        assert_eq!((stats.live_content, stats.archived_content), (1, 1));
+        assert!(stats.compressed_bytes > 0 && stats.saved_bytes() > 0);
+
+        assert!(matches!(repository.archive_content(content.id).await, Err(RepositoryError::NotFound)));
+        assert!(matches!(repository.find_archived(kept.id).await, Err(RepositoryError::NotFound)));
+    }
+
+    async fn insert_scored(
+        repository: &ContentRepository,
+        pool: &PgPool,
+        user_id: Uuid,
+        external_id: &str,
+        echo_index: f64,
+        propagation_count: i32,
+    ) -> Uuid {
+        let content = repository.create(&new_content(user_id, external_id)).await.unwrap();
+        repository.set_echo_index(content.id, echo_index).await.unwrap();
+        sqlx::query("UPDATE content SET propagation_count = $2, total_rewards = $2 * 1.5 WHERE id = $1")
+            .bind(content.id)
+            .bind(propagation_count)
+            .execute(pool)
+            .await
+            .unwrap();
+        content.id
+    }
+
+    async fn all_pages(
+        repository: &ContentRepository,
+        author_id: Uuid,
+        sort: ContentSort,
+        order: SortOrder,
+    ) -> Vec<ContentRecord> {
+        let mut records = Vec::new();
+        let mut after = None;
+        loop {
+            let page = repository.find_by_author(author_id, None, sort, order, after, 2).await.unwrap();
+            records.extend(page.data);
+            match page.next_cursor {
+                Some(cursor) => after = Some(SortCursor::decode(&cursor).unwrap()),
+                None => return records,
+            }
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_author_content_pages_in_each_sort_order(pool: PgPool) {
+        let repository = ContentRepository::new(pool.clone());
+        let author_id = insert_user(&pool, "0xauthor").await;
+        let other_id = insert_user(&pool, "0xother").await;
+        let scored = [
+            ("tweet_a", 10.0, 5),
+            ("tweet_b", 50.0, 1),
+            ("tweet_c", 30.0, 3),
+            ("tweet_d", 50.0, 2),
+            ("tweet_e", 70.0, 4),
+        ];
+        for (external_id, echo_index, propagations) in scored {
+            insert_scored(&repository, &pool, author_id, external_id, echo_index, propagations).await;
+        }
+        insert_scored(&repository, &pool, other_id, "tweet_other", 99.0, 9).await;
+        let deleted = insert_scored(&repository, &pool, author_id, "tweet_deleted", 80.0, 8).await;
+        repository.soft_delete(deleted).await.unwrap();
+
+        let by_echo = all_pages(&repository, author_id, ContentSort::EchoIndex, SortOrder::Desc).await;
+        let scores: Vec<f64> = by_echo.iter().map(|record| record.echo_index).collect();
+        assert_eq!(scores, vec![70.0, 50.0, 50.0, 30.0, 10.0]);
+        // Ties are broken by id, in the same direction
+        assert!(by_echo[1].id > by_echo[2].id);
+
+        let by_propagations = all_pages(&repository, author_id, ContentSort::PropagationCount, SortOrder::Asc).await;
+        let counts: Vec<i32> = by_propagations.iter().map(|record| record.propagation_count).collect();
+        assert_eq!(counts, vec![1, 2, 3, 4, 5]);
+
+        let newest_first = all_pages(&repository, author_id, ContentSort::CreatedAt, SortOrder::Desc).await;
+        let external_ids: Vec<&str> = newest_first.iter().map(|record| record.external_id.as_str()).collect();
+        assert_eq!(external_ids, vec!["tweet_e", "tweet_d", "tweet_c", "tweet_b", "tweet_a"]);
+        let oldest_first = all_pages(&repository, author_id, ContentSort::CreatedAt, SortOrder::Asc).await;
+        assert!(oldest_first.iter().map(|record| record.id).eq(newest_first.iter().rev().map(|record| record.id)));
+
+        let (total_count, stats) = repository.author_content_stats(author_id, None).await.unwrap();
+        assert_eq!(total_count, 5);
+        assert_eq!(
+            stats,
+            UserContentStats { avg_echo_index: 42.0, max_echo_index: 70.0, total_propagations: 15, total_rewards: 22.5 }
+        );
+        let farcaster = repository.author_content_stats(author_id, Some(&Platform::Farcaster)).await.unwrap();
+        assert_eq!(farcaster.0, 0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_author_content_cursor_is_stable_across_inserts(pool: PgPool) {
+        let repository = ContentRepository::new(pool.clone());
+        let author_id = insert_user(&pool, "0xstable").await;
+        for (external_id, echo_index) in [("tweet_a", 20.0), ("tweet_b", 40.0), ("tweet_c", 60.0), ("tweet_d", 80.0)] {
+            insert_scored(&repository, &pool, author_id, external_id, echo_index, 0).await;
+        }
+
+        let sort = (ContentSort::EchoIndex, SortOrder::Desc);
+        let first = repository.find_by_author(author_id, None, sort.0, sort.1, None, 2).await.unwrap();
+        let scores: Vec<f64> = first.data.iter().map(|record| record.echo_index).collect();
+        assert_eq!(scores, vec![80.0, 60.0]);
+
+        // Content scored above the cursor belongs to pages already read; below it, to pages to come
+        insert_scored(&repository, &pool, author_id, "tweet_top", 95.0, 0).await;
+        insert_scored(&repository, &pool, author_id, "tweet_mid", 50.0, 0).await;
+
+        let after = SortCursor::decode(first.next_cursor.as_deref().unwrap()).unwrap();
+        let second = repository.find_by_author(author_id, None, sort.0, sort.1, Some(after), 2).await.unwrap();
+        let scores: Vec<f64> = second.data.iter().map(|record| record.echo_index).collect();
+        assert_eq!(scores, vec![50.0, 40.0]);
+        assert!(second.has_more);
+
+        // A cursor from another sort order is rejected rather than misread
+        let created_cursor = SortCursor::new(SortKey::Time(first.data[0].created_at), first.data[0].id);
+        let mismatched = repository.find_by_author(author_id, None, sort.0, sort.1, Some(created_cursor), 2).await;
+        assert!(matches!(mismatched, Err(RepositoryError::InvalidCursor(_))));
+    }
+}
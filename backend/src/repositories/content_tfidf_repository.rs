@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::Platform;
+use crate::services::TfIdfVector;
+
+/// Vector of a content item along with what cluster statistics are computed from
+#[derive(Debug, Clone)]
+pub struct ClusterInput {
+    pub content_id: Uuid,
+    pub vector: TfIdfVector,
+    pub platform: Platform,
+    pub echo_index: f64,
+}
+
+#[derive(sqlx::FromRow)]
+struct ClusterRow {
+    content_id: Uuid,
+    terms: Vec<String>,
+    weights: Vec<f64>,
+    platform: Platform,
+    echo_index: f64,
+}
+
+pub struct ContentTfIdfRepository {
+    pool: PgPool,
+}
+
+impl ContentTfIdfRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn store(&self, content_id: Uuid, vector: &TfIdfVector) -> Result<(), RepositoryError> {
+        let (terms, weights): (Vec<&str>, Vec<f64>) =
+            vector.iter().map(|(term, weight)| (term.as_str(), *weight)).unzip();
+
+        sqlx::query(
+            "INSERT INTO content_tfidf (content_id, terms, weights) VALUES ($1, $2, $3)
+             ON CONFLICT (content_id) DO UPDATE SET terms = $2, weights = $3, updated_at = NOW()",
+        )
+        .bind(content_id)
+        .bind(terms)
+        .bind(weights)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Forget the vector of content that no longer has any indexable text
+    pub async fn remove(&self, content_id: Uuid) -> Result<(), RepositoryError> {
+        sqlx::query("DELETE FROM content_tfidf WHERE content_id = $1")
+            .bind(content_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Stored vector of a content item; `NotFound` if it has not been indexed
+    pub async fn find(&self, content_id: Uuid) -> Result<TfIdfVector, RepositoryError> {
+        let (terms, weights): (Vec<String>, Vec<f64>) =
+            sqlx::query_as("SELECT terms, weights FROM content_tfidf WHERE content_id = $1")
+                .bind(content_id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(terms.into_iter().zip(weights).collect())
+    }
+
+    /// Number of indexed documents other than `content_id`, and how many of them contain
+    /// each of `terms`. Terms no other document contains are left out.
+    pub async fn document_frequencies(
+        &self,
+        content_id: Uuid,
+        terms: &[String],
+    ) -> Result<(i64, HashMap<String, i64>), RepositoryError> {
+        let documents: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM content_tfidf WHERE content_id <> $1")
+            .bind(content_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let frequencies: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT term, COUNT(*)
+             FROM content_tfidf, unnest(terms) AS term
+             WHERE content_id <> $1 AND terms && $2 AND term = ANY($2)
+             GROUP BY term",
+        )
+        .bind(content_id)
+        .bind(terms)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((documents, frequencies.into_iter().collect()))
+    }
+
+    /// Vectors of other live content sharing at least one of `terms` with `content_id`,
+    /// optionally leaving out content by the same author
+    pub async fn candidates(
+        &self,
+        content_id: Uuid,
+        terms: &[String],
+        include_own: bool,
+    ) -> Result<Vec<(Uuid, TfIdfVector)>, RepositoryError> {
+        let rows: Vec<(Uuid, Vec<String>, Vec<f64>)> = sqlx::query_as(
+            "SELECT t.content_id, t.terms, t.weights
+             FROM content_tfidf t
+             JOIN content c ON c.id = t.content_id
+             WHERE t.terms && $2
+               AND t.content_id <> $1
+               AND c.deleted_at IS NULL
+               AND ($3 OR c.user_id <> (SELECT user_id FROM content WHERE id = $1))",
+        )
+        .bind(content_id)
+        .bind(terms)
+        .bind(include_own)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(content_id, terms, weights)| (content_id, terms.into_iter().zip(weights).collect()))
+            .collect())
+    }
+
+    /// Vectors of live content, the most recently created first. Only `content_ids` are
+    /// considered when given.
+    pub async fn cluster_inputs(
+        &self,
+        content_ids: Option<&[Uuid]>,
+        limit: i64,
+    ) -> Result<Vec<ClusterInput>, RepositoryError> {
+        let rows = sqlx::query_as::<_, ClusterRow>(
+            "SELECT t.content_id, t.terms, t.weights, c.platform::text AS platform,
+                    COALESCE(c.echo_index, 0)::float8 AS echo_index
+             FROM content_tfidf t
+             JOIN content c ON c.id = t.content_id
+             WHERE c.deleted_at IS NULL
+               AND ($1::uuid[] IS NULL OR t.content_id = ANY($1))
+             ORDER BY c.created_at DESC, t.content_id
+             LIMIT $2",
+        )
+        .bind(content_ids)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ClusterInput {
+                content_id: row.content_id,
+                vector: row.terms.into_iter().zip(row.weights).collect(),
+                platform: row.platform,
+                echo_index: row.echo_index,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,171 @@
+use chrono::Duration;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::content_version::ContentVersion;
+
+const VERSION_COLUMNS: &str =
+    "id, content_id, version_number, title, body, updated_by, updated_at, change_summary, echo_index, flagged_at";
+
+/// Previous versions of edited content. Versions are written by `ContentRepository::update`.
+pub struct ContentVersionRepository {
+    pool: PgPool,
+}
+
+impl ContentVersionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Every previous version of a content item, newest first
+    pub async fn list(&self, content_id: Uuid) -> Result<Vec<ContentVersion>, RepositoryError> {
+        let versions = sqlx::query_as::<_, ContentVersion>(&format!(
+            "SELECT {} FROM content_versions WHERE content_id = $1 ORDER BY version_number DESC",
+            VERSION_COLUMNS
+        ))
+        .bind(content_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(versions)
+    }
+
+    pub async fn find(&self, content_id: Uuid, version_number: i32) -> Result<ContentVersion, RepositoryError> {
+        let version = sqlx::query_as::<_, ContentVersion>(&format!(
+            "SELECT {} FROM content_versions WHERE content_id = $1 AND version_number = $2",
+            VERSION_COLUMNS
+        ))
+        .bind(content_id)
+        .bind(version_number)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(version)
+    }
+
+    /// Number of the latest previous version; 0 if the content was never edited
+    pub async fn latest_version_number(&self, content_id: Uuid) -> Result<i32, RepositoryError> {
+        let latest = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(version_number), 0) FROM content_versions WHERE content_id = $1",
+        )
+        .bind(content_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(latest)
+    }
+
+    /// Flag recent edits after which the Echo Index fell more than `min_drop` below its
+    /// value at the edit within `window`, returning the newly flagged edits
+    pub async fn flag_drops_after_edits(
+        &self,
+        window: Duration,
+        min_drop: f64,
+    ) -> Result<Vec<ContentVersion>, RepositoryError> {
+        let flagged = sqlx::query_as::<_, ContentVersion>(&format!(
+            "WITH drops AS (
+                 SELECT v.id
+                 FROM content_versions v
+                 JOIN echo_index_history h ON h.content_id = v.content_id
+                     AND h.calculated_at > v.updated_at
+                     AND h.calculated_at <= v.updated_at + make_interval(secs => $1)
+                 WHERE v.flagged_at IS NULL AND v.updated_at >= NOW() - make_interval(secs => $1 * 2)
+                 GROUP BY v.id, v.echo_index
+                 HAVING v.echo_index - MIN(h.score) > $2
+             )
+             UPDATE content_versions SET flagged_at = NOW()
+             WHERE id IN (SELECT id FROM drops)
+             RETURNING {}",
+            VERSION_COLUMNS
+        ))
+        .bind(window.num_seconds() as f64)
+        .bind(min_drop)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(flagged)
+    }
+
+    /// Edits flagged for moderation, most recently flagged first
+    pub async fn flagged(&self, limit: i64) -> Result<Vec<ContentVersion>, RepositoryError> {
+        let flagged = sqlx::query_as::<_, ContentVersion>(&format!(
+            "SELECT {} FROM content_versions
+             WHERE flagged_at IS NOT NULL
+             ORDER BY flagged_at DESC, id
+             LIMIT $1",
+            VERSION_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(flagged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::echo_index_history::EchoIndexTrigger;
+    use crate::models::Platform;
+    use crate::repositories::{ContentRepository, EchoIndexHistoryRepository, EchoIndexScores, NewContent};
+    use std::collections::HashMap;
+
+    fn content(user_id: Uuid, title: &str, body: &str) -> NewContent {
+        NewContent {
+            user_id,
+            platform: Platform::Twitter,
+            external_id: "tweet_1".to_string(),
+            content_type: "text".to_string(),
+            title: title.to_string(),
+            body: body.to_string(),
+            media_urls: vec![],
+            tags: vec![],
+            reactions: HashMap::new(),
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_edits_keep_previous_versions_and_sharp_drops_are_flagged(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xabc') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let contents = ContentRepository::new(pool.clone());
+        let versions = ContentVersionRepository::new(pool.clone());
+        let history = EchoIndexHistoryRepository::new(pool.clone());
+
+        let created = contents.create(&content(user_id, "Gains", "up 10%")).await.unwrap();
+        assert_eq!(versions.latest_version_number(created.id).await.unwrap(), 0);
+        contents.set_echo_index(created.id, 80.0).await.unwrap();
+        contents.update(created.id, &content(user_id, "Gains", "up 40%"), Some(user_id)).await.unwrap();
+        let live = contents.update(created.id, &content(user_id, "Huge gains", "up 40%"), None).await.unwrap();
+        assert_eq!(live.title, "Huge gains");
+
+        let listed = versions.list(created.id).await.unwrap();
+        let numbers: Vec<i32> = listed.iter().map(|version| version.version_number).collect();
+        assert_eq!(numbers, vec![2, 1]);
+        let first = versions.find(created.id, 1).await.unwrap();
+        assert_eq!((first.title.as_str(), first.body.as_str()), ("Gains", "up 10%"));
+        assert_eq!((first.updated_by, first.echo_index), (Some(user_id), 80.0));
+        assert_eq!(first.change_summary, "1 line added, 1 removed");
+        assert_eq!(listed[0].change_summary, "title changed");
+        assert!(matches!(versions.find(created.id, 3).await, Err(RepositoryError::NotFound)));
+
+        // A 15 point fall is tolerated, a 30 point one is not
+        let record = |score| {
+            let scores = EchoIndexScores { score, odf: score, awr: score, tpm: score, qf: score };
+            history.record(created.id, scores, EchoIndexTrigger::Recalculation, None)
+        };
+        record(65.0).await.unwrap();
+        assert!(versions.flag_drops_after_edits(Duration::hours(1), 20.0).await.unwrap().is_empty());
+        record(50.0).await.unwrap();
+        let flagged = versions.flag_drops_after_edits(Duration::hours(1), 20.0).await.unwrap();
+        assert_eq!(flagged.len(), 2);
+        assert!(flagged.iter().all(|version| version.flagged_at.is_some()));
+        // Edits are flagged once
+        assert!(versions.flag_drops_after_edits(Duration::hours(1), 20.0).await.unwrap().is_empty());
+        assert_eq!(versions.flagged(10).await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::echo_anomaly::{AnomalyFlag, AnomalyStatus, EchoAnomaly};
+
+const ANOMALY_COLUMNS: &str =
+    "id, content_id, previous_score, new_score, time_delta_seconds, flags, status, reviewed_by, reviewed_at, detected_at";
+
+#[derive(FromRow)]
+struct AnomalyRow {
+    id: Uuid,
+    content_id: Uuid,
+    previous_score: f64,
+    new_score: f64,
+    time_delta_seconds: i64,
+    flags: Json<Vec<AnomalyFlag>>,
+    status: AnomalyStatus,
+    reviewed_by: Option<Uuid>,
+    reviewed_at: Option<DateTime<Utc>>,
+    detected_at: DateTime<Utc>,
+}
+
+impl From<AnomalyRow> for EchoAnomaly {
+    fn from(row: AnomalyRow) -> Self {
+        Self {
+            id: row.id,
+            content_id: row.content_id,
+            previous_score: row.previous_score,
+            new_score: row.new_score,
+            time_delta_seconds: row.time_delta_seconds,
+            flags: row.flags.0,
+            status: row.status,
+            reviewed_by: row.reviewed_by,
+            reviewed_at: row.reviewed_at,
+            detected_at: row.detected_at,
+        }
+    }
+}
+
+pub struct EchoAnomalyRepository {
+    pool: PgPool,
+}
+
+impl EchoAnomalyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Echo scores of the users who propagated a content item
+    pub async fn propagator_echo_scores(&self, content_id: Uuid) -> Result<Vec<f64>, RepositoryError> {
+        let scores = sqlx::query_scalar(
+            "SELECT u.echo_score::float8 FROM users u
+             WHERE u.id IN (
+                 SELECT source_user_id FROM propagations
+                 WHERE content_id = $1 AND source_user_id IS NOT NULL
+             )",
+        )
+        .bind(content_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(scores)
+    }
+
+    /// Record an open anomaly. `Conflict` if the content does not exist.
+    pub async fn create(
+        &self,
+        content_id: Uuid,
+        previous_score: f64,
+        new_score: f64,
+        time_delta_seconds: u64,
+        flags: &[AnomalyFlag],
+    ) -> Result<EchoAnomaly, RepositoryError> {
+        let row = sqlx::query_as::<_, AnomalyRow>(&format!(
+            "INSERT INTO echo_anomalies (content_id, previous_score, new_score, time_delta_seconds, flags)
+             VALUES ($1, $2, $3, $4, $5) RETURNING {}",
+            ANOMALY_COLUMNS
+        ))
+        .bind(content_id)
+        .bind(previous_score)
+        .bind(new_score)
+        .bind(i64::try_from(time_delta_seconds).unwrap_or(i64::MAX))
+        .bind(Json(flags))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    /// Anomalies detected since `since`, or ever, most recent first
+    pub async fn detected_since(&self, since: Option<DateTime<Utc>>) -> Result<Vec<EchoAnomaly>, RepositoryError> {
+        let rows = sqlx::query_as::<_, AnomalyRow>(&format!(
+            "SELECT {} FROM echo_anomalies
+             WHERE $1::timestamptz IS NULL OR detected_at >= $1
+             ORDER BY detected_at DESC",
+            ANOMALY_COLUMNS
+        ))
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(EchoAnomaly::from).collect())
+    }
+
+    /// Anomalies on a content item that no operator has reviewed yet
+    pub async fn open_count(&self, content_id: Uuid) -> Result<i64, RepositoryError> {
+        let count = sqlx::query_scalar("SELECT COUNT(*) FROM echo_anomalies WHERE content_id = $1 AND status = 'open'")
+            .bind(content_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Close an open anomaly as dismissed or confirmed. `NotFound` if there is no such
+    /// anomaly, `Conflict` if it was already reviewed.
+    pub async fn review(
+        &self,
+        anomaly_id: Uuid,
+        reviewed_by: Uuid,
+        status: AnomalyStatus,
+    ) -> Result<EchoAnomaly, RepositoryError> {
+        let row = sqlx::query_as::<_, AnomalyRow>(&format!(
+            "UPDATE echo_anomalies SET status = $3, reviewed_by = $2, reviewed_at = NOW()
+             WHERE id = $1 AND status = 'open'
+             RETURNING {}",
+            ANOMALY_COLUMNS
+        ))
+        .bind(anomaly_id)
+        .bind(reviewed_by)
+        .bind(status)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(row.into()),
+            None => {
+                let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM echo_anomalies WHERE id = $1)")
+                    .bind(anomaly_id)
+                    .fetch_one(&self.pool)
+                    .await?;
+                if exists {
+                    Err(RepositoryError::Conflict("anomaly has already been reviewed".to_string()))
+                } else {
+                    Err(RepositoryError::NotFound)
+                }
+            }
+        }
+    }
+}
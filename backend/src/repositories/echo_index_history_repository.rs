@@ -0,0 +1,271 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::echo_index_history::{
+    ContentStanding, EchoIndexHistory, EchoIndexHistoryBucket, EchoIndexTrigger, HistoryGranularity, LeaderboardSort,
+    PeakScore,
+};
+use crate::models::experiment::ExperimentAssignment;
+use crate::models::Platform;
+
+const HISTORY_COLUMNS: &str =
+    "id, content_id, score, odf, awr, tpm, qf, calculated_at, delta_score, trigger, experiment_id, variant";
+
+/// Component scores of a single calculation
+#[derive(Debug, Clone, Copy)]
+pub struct EchoIndexScores {
+    pub score: f64,
+    pub odf: f64,
+    pub awr: f64,
+    pub tpm: f64,
+    pub qf: f64,
+}
+
+pub struct EchoIndexHistoryRepository {
+    pool: PgPool,
+}
+
+impl EchoIndexHistoryRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Append a calculation, deriving its delta from the latest recorded score.
+    /// The first calculation for a content item is always recorded as `Initial`.
+    pub async fn record(
+        &self,
+        content_id: Uuid,
+        scores: EchoIndexScores,
+        trigger: EchoIndexTrigger,
+        assignment: Option<ExperimentAssignment>,
+    ) -> Result<EchoIndexHistory, RepositoryError> {
+        let record = sqlx::query_as::<_, EchoIndexHistory>(&format!(
+            "WITH previous AS (
+                 SELECT score FROM echo_index_history
+                 WHERE content_id = $1
+                 ORDER BY calculated_at DESC
+                 LIMIT 1
+             )
+             INSERT INTO echo_index_history
+                 (content_id, score, odf, awr, tpm, qf, delta_score, trigger, experiment_id, variant)
+             SELECT $1, $2, $3, $4, $5, $6,
+                    $2 - COALESCE((SELECT score FROM previous), $2),
+                    CASE WHEN EXISTS (SELECT 1 FROM previous) THEN $7 ELSE 'initial'::echo_index_trigger END,
+                    $8, $9
+             RETURNING {}",
+            HISTORY_COLUMNS
+        ))
+        .bind(content_id)
+        .bind(scores.score)
+        .bind(scores.odf)
+        .bind(scores.awr)
+        .bind(scores.tpm)
+        .bind(scores.qf)
+        .bind(trigger)
+        .bind(assignment.map(|assignment| assignment.experiment_id))
+        .bind(assignment.map(|assignment| assignment.variant))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// Most recent calculation for a content item, if any
+    pub async fn latest(&self, content_id: Uuid) -> Result<Option<EchoIndexHistory>, RepositoryError> {
+        let record = sqlx::query_as::<_, EchoIndexHistory>(&format!(
+            "SELECT {} FROM echo_index_history WHERE content_id = $1
+             ORDER BY calculated_at DESC
+             LIMIT 1",
+            HISTORY_COLUMNS
+        ))
+        .bind(content_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// The latest `limit` calculations of a content item, most recent first
+    pub async fn recent(&self, content_id: Uuid, limit: i64) -> Result<Vec<EchoIndexHistory>, RepositoryError> {
+        let records = sqlx::query_as::<_, EchoIndexHistory>(&format!(
+            "SELECT {} FROM echo_index_history WHERE content_id = $1
+             ORDER BY calculated_at DESC
+             LIMIT $2",
+            HISTORY_COLUMNS
+        ))
+        .bind(content_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// The latest `limit` scores of a content item with when they were calculated,
+    /// oldest first
+    pub async fn series(&self, content_id: Uuid, limit: i64) -> Result<Vec<(DateTime<Utc>, f64)>, RepositoryError> {
+        let mut series = sqlx::query_as::<_, (DateTime<Utc>, f64)>(
+            "SELECT calculated_at, score FROM echo_index_history
+             WHERE content_id = $1
+             ORDER BY calculated_at DESC
+             LIMIT $2",
+        )
+        .bind(content_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        series.reverse();
+        Ok(series)
+    }
+
+    /// Min/max/avg score per bucket over the last `days` days, oldest first
+    pub async fn buckets(
+        &self,
+        content_id: Uuid,
+        granularity: HistoryGranularity,
+        days: u32,
+    ) -> Result<Vec<EchoIndexHistoryBucket>, RepositoryError> {
+        let buckets = sqlx::query_as::<_, EchoIndexHistoryBucket>(
+            "SELECT date_trunc($2, calculated_at) AS bucket_start,
+                    MIN(score) AS min_score, MAX(score) AS max_score, AVG(score) AS avg_score,
+                    COUNT(*) AS samples
+             FROM echo_index_history
+             WHERE content_id = $1 AND calculated_at >= NOW() - make_interval(days => $3)
+             GROUP BY bucket_start
+             ORDER BY bucket_start",
+        )
+        .bind(content_id)
+        .bind(granularity.date_trunc_field())
+        .bind(days as i32)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(buckets)
+    }
+
+    /// Active content ranked by the highest score it reached, highest first. With `since`,
+    /// only content created since then and scores calculated since then count.
+    pub async fn peak_scores(
+        &self,
+        since: Option<DateTime<Utc>>,
+        platform: Option<&Platform>,
+        limit: i64,
+    ) -> Result<Vec<PeakScore>, RepositoryError> {
+        let peaks = sqlx::query_as::<_, PeakScore>(
+            "SELECT c.id AS content_id,
+                    COALESCE(NULLIF(c.title, ''), LEFT(COALESCE(c.body, ''), 100)) AS title,
+                    COALESCE(u.display_name, u.username, u.wallet_address) AS author,
+                    MAX(h.score) AS peak_score, COALESCE(c.propagation_count, 0) AS propagation_count,
+                    c.created_at
+             FROM echo_index_history h
+             JOIN content c ON c.id = h.content_id
+             JOIN users u ON u.id = c.user_id
+             WHERE c.status = 'active' AND c.deleted_at IS NULL
+               AND ($1::timestamptz IS NULL OR (c.created_at >= $1 AND h.calculated_at >= $1))
+               AND ($2::text IS NULL OR c.platform::text = $2)
+             GROUP BY c.id, u.id
+             ORDER BY peak_score DESC, c.id
+             LIMIT $3",
+        )
+        .bind(since)
+        .bind(platform.map(Platform::as_str))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(peaks)
+    }
+
+    /// Active content created since `since` ranked by its current standing, highest first.
+    /// Ranked by trending score, content without one is left out.
+    pub async fn standings(
+        &self,
+        since: Option<DateTime<Utc>>,
+        platform: Option<&Platform>,
+        sort: LeaderboardSort,
+        limit: i64,
+    ) -> Result<Vec<ContentStanding>, RepositoryError> {
+        let (order, condition) = match sort {
+            LeaderboardSort::Trending => ("trending_score", "AND t.score > 0"),
+            LeaderboardSort::EchoIndex => ("echo_index", ""),
+            LeaderboardSort::Rewards => ("total_rewards", ""),
+        };
+        let standings = sqlx::query_as::<_, ContentStanding>(&format!(
+            "SELECT c.id AS content_id,
+                    COALESCE(NULLIF(c.title, ''), LEFT(COALESCE(c.body, ''), 100)) AS title,
+                    COALESCE(u.display_name, u.username, u.wallet_address) AS author,
+                    COALESCE(c.echo_index, 0)::float8 AS echo_index, COALESCE(t.score, 0) AS trending_score,
+                    COALESCE(c.total_rewards, 0)::float8 AS total_rewards,
+                    COALESCE(c.propagation_count, 0) AS propagation_count, c.created_at
+             FROM content c
+             JOIN users u ON u.id = c.user_id
+             LEFT JOIN content_trending_scores t ON t.content_id = c.id
+             WHERE c.status = 'active' AND c.deleted_at IS NULL {}
+               AND ($1::timestamptz IS NULL OR c.created_at >= $1)
+               AND ($2::text IS NULL OR c.platform::text = $2)
+             ORDER BY {} DESC, c.id
+             LIMIT $3",
+            condition, order
+        ))
+        .bind(since)
+        .bind(platform.map(Platform::as_str))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(standings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn insert_content(pool: &PgPool) -> Uuid {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xabc') RETURNING id")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type)
+             VALUES ($1, 'twitter', 'tweet_1', 'text') RETURNING id",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    fn scores(score: f64) -> EchoIndexScores {
+        EchoIndexScores { score, odf: score, awr: score, tpm: score, qf: score }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_delta_across_sequential_calculations(pool: PgPool) {
+        let repository = EchoIndexHistoryRepository::new(pool.clone());
+        let content_id = insert_content(&pool).await;
+
+        let record = |score, trigger| repository.record(content_id, scores(score), trigger, None);
+        let first = record(50.0, EchoIndexTrigger::Scheduled).await.unwrap();
+        let second = record(55.5, EchoIndexTrigger::PropagationAdded).await.unwrap();
+        let third = record(52.0, EchoIndexTrigger::Recalculation).await.unwrap();
+
+        assert_eq!(first.trigger, EchoIndexTrigger::Initial);
+        assert_eq!(first.delta_score, 0.0);
+        assert_eq!(second.trigger, EchoIndexTrigger::PropagationAdded);
+        assert!((second.delta_score - 5.5).abs() < 1e-9);
+        assert!((third.delta_score + 3.5).abs() < 1e-9);
+
+        let buckets = repository.buckets(content_id, HistoryGranularity::Daily, 30).await.unwrap();
+        let total: i64 = buckets.iter().map(|bucket| bucket.samples).sum();
+        assert_eq!(total, 3);
+        assert!(buckets.iter().all(|bucket| bucket.min_score >= 50.0 && bucket.max_score <= 55.5));
+
+        let series = repository.series(content_id, 2).await.unwrap();
+        let latest: Vec<f64> = series.iter().map(|(_, score)| *score).collect();
+        assert_eq!(latest, vec![55.5, 52.0]);
+    }
+}
@@ -0,0 +1,277 @@
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::content::EchoIndexWeights;
+use crate::models::experiment::{Experiment, ExperimentResults, ExperimentVariant, VariantSummary};
+
+const EXPERIMENT_COLUMNS: &str = "id, name, control_weights, treatment_weights, traffic_split, active, created_at";
+
+/// Definition of an experiment, as created or replaced by an admin
+#[derive(Debug, Clone)]
+pub struct NewExperiment<'a> {
+    pub name: &'a str,
+    pub control_weights: EchoIndexWeights,
+    pub treatment_weights: EchoIndexWeights,
+    pub traffic_split: f64,
+    pub active: bool,
+}
+
+#[derive(FromRow)]
+struct ExperimentRow {
+    id: Uuid,
+    name: String,
+    control_weights: Json<EchoIndexWeights>,
+    treatment_weights: Json<EchoIndexWeights>,
+    traffic_split: f64,
+    active: bool,
+    created_at: DateTime<Utc>,
+}
+
+impl From<ExperimentRow> for Experiment {
+    fn from(row: ExperimentRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            control_weights: row.control_weights.0,
+            treatment_weights: row.treatment_weights.0,
+            traffic_split: row.traffic_split,
+            active: row.active,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct VariantRow {
+    variant: ExperimentVariant,
+    samples: i64,
+    mean_score: f64,
+    std_dev: f64,
+}
+
+pub struct ExperimentRepository {
+    pool: PgPool,
+}
+
+impl ExperimentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// `Conflict` if the experiment is active while another one is
+    pub async fn create(&self, experiment: &NewExperiment<'_>) -> Result<Experiment, RepositoryError> {
+        let row = sqlx::query_as::<_, ExperimentRow>(&format!(
+            "INSERT INTO experiments (name, control_weights, treatment_weights, traffic_split, active)
+             VALUES ($1, $2, $3, $4, $5) RETURNING {}",
+            EXPERIMENT_COLUMNS
+        ))
+        .bind(experiment.name)
+        .bind(Json(experiment.control_weights))
+        .bind(Json(experiment.treatment_weights))
+        .bind(experiment.traffic_split)
+        .bind(experiment.active)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    /// All experiments, newest first
+    pub async fn list(&self) -> Result<Vec<Experiment>, RepositoryError> {
+        let rows = sqlx::query_as::<_, ExperimentRow>(&format!(
+            "SELECT {} FROM experiments ORDER BY created_at DESC, id",
+            EXPERIMENT_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Experiment::from).collect())
+    }
+
+    pub async fn find(&self, experiment_id: Uuid) -> Result<Experiment, RepositoryError> {
+        let row = sqlx::query_as::<_, ExperimentRow>(&format!(
+            "SELECT {} FROM experiments WHERE id = $1",
+            EXPERIMENT_COLUMNS
+        ))
+        .bind(experiment_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    /// The experiment new Echo Index calculations are scored under, if any
+    pub async fn active(&self) -> Result<Option<Experiment>, RepositoryError> {
+        let row = sqlx::query_as::<_, ExperimentRow>(&format!(
+            "SELECT {} FROM experiments WHERE active",
+            EXPERIMENT_COLUMNS
+        ))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Experiment::from))
+    }
+
+    /// Replace an experiment's definition. `NotFound` if it does not exist, `Conflict`
+    /// if it is activated while another one is active.
+    pub async fn update(
+        &self,
+        experiment_id: Uuid,
+        experiment: &NewExperiment<'_>,
+    ) -> Result<Experiment, RepositoryError> {
+        let row = sqlx::query_as::<_, ExperimentRow>(&format!(
+            "UPDATE experiments
+             SET name = $2, control_weights = $3, treatment_weights = $4, traffic_split = $5, active = $6
+             WHERE id = $1 RETURNING {}",
+            EXPERIMENT_COLUMNS
+        ))
+        .bind(experiment_id)
+        .bind(experiment.name)
+        .bind(Json(experiment.control_weights))
+        .bind(Json(experiment.treatment_weights))
+        .bind(experiment.traffic_split)
+        .bind(experiment.active)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    /// Delete an experiment; scores calculated under it stay in the history untagged.
+    /// `NotFound` if it does not exist.
+    pub async fn delete(&self, experiment_id: Uuid) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE echo_index_history SET experiment_id = NULL, variant = NULL WHERE experiment_id = $1")
+            .bind(experiment_id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM experiments WHERE id = $1")
+            .bind(experiment_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Compare the variants by the latest score of each content item calculated under
+    /// the experiment. `NotFound` if it does not exist.
+    pub async fn results(&self, experiment_id: Uuid) -> Result<ExperimentResults, RepositoryError> {
+        self.find(experiment_id).await?;
+
+        let rows = sqlx::query_as::<_, VariantRow>(
+            "WITH latest AS (
+                 SELECT DISTINCT ON (content_id) variant, score
+                 FROM echo_index_history
+                 WHERE experiment_id = $1
+                 ORDER BY content_id, calculated_at DESC
+             )
+             SELECT variant, COUNT(*) AS samples, AVG(score) AS mean_score,
+                    COALESCE(STDDEV_SAMP(score), 0) AS std_dev
+             FROM latest
+             GROUP BY variant",
+        )
+        .bind(experiment_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let summary = |variant| {
+            rows.iter()
+                .find(|row| row.variant == variant)
+                .map(|row| VariantSummary::new(row.samples, row.mean_score, row.std_dev))
+                .unwrap_or_else(|| VariantSummary::new(0, 0.0, 0.0))
+        };
+        Ok(ExperimentResults::new(
+            experiment_id,
+            summary(ExperimentVariant::Control),
+            summary(ExperimentVariant::Treatment),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::echo_index_history::EchoIndexTrigger;
+    use crate::models::experiment::ExperimentAssignment;
+    use crate::repositories::{EchoIndexHistoryRepository, EchoIndexScores};
+
+    fn definition(active: bool) -> NewExperiment<'static> {
+        NewExperiment {
+            name: "originality first",
+            control_weights: EchoIndexWeights::default(),
+            treatment_weights: EchoIndexWeights { odf: 0.5, awr: 0.2, tpm: 0.2, qf: 0.1 },
+            traffic_split: 0.5,
+            active,
+        }
+    }
+
+    async fn insert_content(pool: &PgPool, count: usize) -> Vec<Uuid> {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xab') RETURNING id")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        let mut ids = Vec::new();
+        for i in 0..count {
+            let id = sqlx::query_scalar(
+                "INSERT INTO content (user_id, platform, external_id, content_type)
+                 VALUES ($1, 'twitter', $2, 'text') RETURNING id",
+            )
+            .bind(user_id)
+            .bind(format!("tweet_{}", i))
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            ids.push(id);
+        }
+        ids
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_one_active_experiment_and_results_per_variant(pool: PgPool) {
+        let repository = ExperimentRepository::new(pool.clone());
+        let experiment = repository.create(&definition(true)).await.unwrap();
+        assert!(matches!(repository.create(&definition(true)).await, Err(RepositoryError::Conflict(_))));
+        let paused = repository.create(&definition(false)).await.unwrap();
+        assert_eq!(repository.active().await.unwrap().unwrap().id, experiment.id);
+
+        let history = EchoIndexHistoryRepository::new(pool.clone());
+        let content = insert_content(&pool, 4).await;
+        let scored = [
+            (content[0], ExperimentVariant::Control, 0.4),
+            (content[0], ExperimentVariant::Control, 0.5),
+            (content[1], ExperimentVariant::Control, 0.7),
+            (content[2], ExperimentVariant::Treatment, 0.8),
+            (content[3], ExperimentVariant::Treatment, 0.9),
+        ];
+        for (content_id, variant, score) in scored {
+            let scores = EchoIndexScores { score, odf: score, awr: score, tpm: score, qf: score };
+            let assignment = ExperimentAssignment { experiment_id: experiment.id, variant };
+            history.record(content_id, scores, EchoIndexTrigger::Scheduled, Some(assignment)).await.unwrap();
+        }
+
+        // Only the latest score of each content item counts
+        let results = repository.results(experiment.id).await.unwrap();
+        assert_eq!((results.control.samples, results.treatment.samples), (2, 2));
+        assert!((results.control.mean_score - 0.6).abs() < 1e-9);
+        assert!((results.treatment.mean_score - 0.85).abs() < 1e-9);
+        assert!((results.control.standard_error - 0.1).abs() < 1e-9);
+        assert!((results.difference - 0.25).abs() < 1e-9);
+        assert!(results.p_value.is_some_and(|p| p > 0.0 && p < 1.0));
+
+        let empty = repository.results(paused.id).await.unwrap();
+        assert_eq!((empty.control.samples, empty.p_value), (0, None));
+
+        repository.delete(experiment.id).await.unwrap();
+        assert!(matches!(repository.results(experiment.id).await, Err(RepositoryError::NotFound)));
+        let untagged = history.latest(content[0]).await.unwrap().unwrap();
+        assert_eq!((untagged.experiment_id, untagged.variant), (None, None));
+    }
+}
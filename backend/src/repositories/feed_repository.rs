@@ -0,0 +1,196 @@
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::content::ContentSummary;
+use crate::models::feed::{CachedFeed, FeedAlgorithm};
+use crate::models::Platform;
+
+/// Columns of `content` projected onto `ContentSummary`
+const SUMMARY_COLUMNS: &str = "
+    id, COALESCE(body, '') AS text, platform::text AS platform,
+    COALESCE(echo_index, 0)::float8 AS echo_score, COALESCE(propagation_count, 0) AS propagation_count,
+    created_at";
+
+/// Share of users on the affinity feed when the feature flag is missing
+const DEFAULT_AFFINITY_PERCENT: i32 = 100;
+
+pub struct FeedRepository {
+    pool: PgPool,
+}
+
+impl FeedRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Content the user propagated since `since`, most recently propagated first
+    pub async fn engaged_content(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, RepositoryError> {
+        let ids = sqlx::query_scalar(
+            "SELECT content_id FROM propagations
+             WHERE source_user_id = $1 AND created_at > $2
+             GROUP BY content_id
+             ORDER BY MAX(created_at) DESC
+             LIMIT $3",
+        )
+        .bind(user_id)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Content propagated since `since` by the users the user follows, most recently
+    /// propagated first
+    pub async fn propagated_by_following(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, RepositoryError> {
+        let ids = sqlx::query_scalar(
+            "SELECT p.content_id FROM propagations p
+             JOIN user_relationships r ON r.followed_id = p.source_user_id
+             WHERE r.follower_id = $1 AND p.created_at > $2
+             GROUP BY p.content_id
+             ORDER BY MAX(p.created_at) DESC
+             LIMIT $3",
+        )
+        .bind(user_id)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Platform the user propagated content from most since `since`
+    pub async fn favourite_platform(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Option<Platform>, RepositoryError> {
+        let platform = sqlx::query_scalar(
+            "SELECT source_platform::text FROM propagations
+             WHERE source_user_id = $1 AND created_at > $2
+             GROUP BY source_platform
+             ORDER BY COUNT(*) DESC, source_platform
+             LIMIT 1",
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(platform)
+    }
+
+    /// Active content among `ids` not written by `viewer`, in no particular order
+    pub async fn summaries(&self, ids: &[Uuid], viewer: Uuid) -> Result<Vec<ContentSummary>, RepositoryError> {
+        let query = format!(
+            "SELECT {} FROM content
+             WHERE id = ANY($1) AND user_id <> $2 AND status = 'active' AND deleted_at IS NULL",
+            SUMMARY_COLUMNS
+        );
+
+        let summaries = sqlx::query_as::<_, ContentSummary>(&query)
+            .bind(ids)
+            .bind(viewer)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(summaries)
+    }
+
+    /// Active content created since `since`, on `platform` if given and not written by
+    /// `viewer`, highest Echo Index first
+    pub async fn top_content(
+        &self,
+        platform: Option<&Platform>,
+        since: DateTime<Utc>,
+        viewer: Uuid,
+        limit: i64,
+    ) -> Result<Vec<ContentSummary>, RepositoryError> {
+        let query = format!(
+            "SELECT {} FROM content
+             WHERE created_at > $1 AND user_id <> $2 AND status = 'active' AND deleted_at IS NULL
+               AND ($3::text IS NULL OR platform::text = $3)
+             ORDER BY echo_index DESC NULLS LAST, id
+             LIMIT $4",
+            SUMMARY_COLUMNS
+        );
+
+        let summaries = sqlx::query_as::<_, ContentSummary>(&query)
+            .bind(since)
+            .bind(viewer)
+            .bind(platform.map(Platform::as_str))
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(summaries)
+    }
+
+    /// The user's last generated feed, expired or not
+    pub async fn cached_feed(&self, user_id: Uuid) -> Result<Option<CachedFeed>, RepositoryError> {
+        let Some(row) = sqlx::query("SELECT algorithm, entries, generated_at, expires_at FROM user_feeds WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let algorithm: String = row.try_get("algorithm")?;
+        let Json(entries) = row.try_get("entries")?;
+        Ok(Some(CachedFeed {
+            algorithm: FeedAlgorithm::parse(&algorithm)
+                .ok_or_else(|| RepositoryError::InvalidInput(format!("unknown feed algorithm {}", algorithm)))?,
+            entries,
+            generated_at: row.try_get("generated_at")?,
+            expires_at: row.try_get("expires_at")?,
+        }))
+    }
+
+    /// Replace the user's cached feed
+    pub async fn store_feed(&self, user_id: Uuid, feed: &CachedFeed) -> Result<(), RepositoryError> {
+        sqlx::query(
+            "INSERT INTO user_feeds (user_id, algorithm, entries, generated_at, expires_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (user_id)
+             DO UPDATE SET algorithm = $2, entries = $3, generated_at = $4, expires_at = $5",
+        )
+        .bind(user_id)
+        .bind(feed.algorithm.as_str())
+        .bind(Json(&feed.entries))
+        .bind(feed.generated_at)
+        .bind(feed.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Percentage of users whose feed is ranked by affinity, from the
+    /// `discovery_feed_affinity_percent` feature flag
+    pub async fn affinity_percent(&self) -> Result<u8, RepositoryError> {
+        let percent: Option<i32> = sqlx::query_scalar(
+            "SELECT (value->>'discovery_feed_affinity_percent')::int FROM system_settings WHERE key = 'feature_flags'",
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .flatten();
+
+        Ok(percent.unwrap_or(DEFAULT_AFFINITY_PERCENT).clamp(0, 100) as u8)
+    }
+}
@@ -0,0 +1,156 @@
+use chrono::Duration;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::Platform;
+
+/// Uses of a hashtag in the two halves of a window
+#[derive(Debug, Clone, FromRow)]
+pub struct HashtagCounts {
+    pub hashtag: String,
+    /// Content tagged in the latest half of the window
+    pub recent: i64,
+    /// Content tagged in the earlier half of the window
+    pub earlier: i64,
+    pub avg_echo_index: f64,
+}
+
+/// Hashtags of content. They are written by `ContentRepository` whenever content is
+/// created or edited.
+pub struct HashtagRepository {
+    pool: PgPool,
+}
+
+impl HashtagRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Hashtags of live content tagged within `window`, optionally on one platform, most
+    /// used first
+    pub async fn counts(
+        &self,
+        platform: Option<&Platform>,
+        window: Duration,
+        limit: i64,
+    ) -> Result<Vec<HashtagCounts>, RepositoryError> {
+        let counts = sqlx::query_as::<_, HashtagCounts>(
+            "SELECT h.hashtag,
+                    COUNT(*) FILTER (WHERE h.created_at >= NOW() - make_interval(secs => $2 / 2)) AS recent,
+                    COUNT(*) FILTER (WHERE h.created_at < NOW() - make_interval(secs => $2 / 2)) AS earlier,
+                    COALESCE(AVG(c.echo_index), 0)::float8 AS avg_echo_index
+             FROM content_hashtags h
+             JOIN content c ON c.id = h.content_id
+             WHERE h.created_at >= NOW() - make_interval(secs => $2)
+               AND c.deleted_at IS NULL
+               AND ($1::text IS NULL OR c.platform::text = $1)
+             GROUP BY h.hashtag
+             ORDER BY COUNT(*) DESC, h.hashtag
+             LIMIT $3",
+        )
+        .bind(platform.map(Platform::as_str))
+        .bind(window.num_seconds() as f64)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Auto-tags of more than `min_content` live content items tagged within `window` with
+    /// any of `hashtags`, optionally on one platform, with how many items each was
+    /// extracted from. The hashtags themselves are left out; most widespread first.
+    pub async fn auto_tag_counts(
+        &self,
+        platform: Option<&Platform>,
+        window: Duration,
+        hashtags: &[String],
+        min_content: i64,
+    ) -> Result<Vec<(String, i64)>, RepositoryError> {
+        let counts = sqlx::query_as(
+            "SELECT tag, COUNT(*) AS content_count
+             FROM content c
+             CROSS JOIN LATERAL UNNEST(c.auto_tags) AS tag
+             WHERE c.deleted_at IS NULL
+               AND ($1::text IS NULL OR c.platform::text = $1)
+               AND c.id IN (SELECT content_id FROM content_hashtags
+                            WHERE hashtag = ANY($3) AND created_at >= NOW() - make_interval(secs => $2))
+               AND NOT (tag = ANY($3))
+             GROUP BY tag
+             HAVING COUNT(*) > $4
+             ORDER BY COUNT(*) DESC, tag",
+        )
+        .bind(platform.map(Platform::as_str))
+        .bind(window.num_seconds() as f64)
+        .bind(hashtags)
+        .bind(min_content)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Hashtags of a content item, alphabetically
+    pub async fn tags_of(&self, content_id: Uuid) -> Result<Vec<String>, RepositoryError> {
+        let tags = sqlx::query_scalar(
+            "SELECT hashtag FROM content_hashtags WHERE content_id = $1 ORDER BY hashtag",
+        )
+        .bind(content_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::{ContentRepository, NewContent};
+    use std::collections::HashMap;
+
+    fn content(user_id: Uuid, external_id: &str, platform: Platform, body: &str) -> NewContent {
+        NewContent {
+            user_id,
+            platform,
+            external_id: external_id.to_string(),
+            content_type: "text".to_string(),
+            title: String::new(),
+            body: body.to_string(),
+            media_urls: vec![],
+            tags: vec![],
+            reactions: HashMap::new(),
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_hashtags_follow_content_edits(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xabc') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let contents = ContentRepository::new(pool.clone());
+        let hashtags = HashtagRepository::new(pool.clone());
+
+        let tweet = content(user_id, "tweet_1", Platform::Twitter, "Rollups #Ethereum #L2 #ethereum");
+        let created = contents.create(&tweet).await.unwrap();
+        assert_eq!(hashtags.tags_of(created.id).await.unwrap(), vec!["ethereum", "l2"]);
+
+        let edited = content(user_id, "tweet_1", Platform::Twitter, "Rollups #Ethereum #zk");
+        contents.update(created.id, &edited, None).await.unwrap();
+        assert_eq!(hashtags.tags_of(created.id).await.unwrap(), vec!["ethereum", "zk"]);
+
+        contents
+            .create(&content(user_id, "post_1", Platform::Telegram, "#zk proofs"))
+            .await
+            .unwrap();
+        let counts = hashtags.counts(None, Duration::hours(24), 10).await.unwrap();
+        let tags: Vec<(&str, i64)> = counts.iter().map(|c| (c.hashtag.as_str(), c.recent)).collect();
+        assert_eq!(tags, vec![("zk", 2), ("ethereum", 1)]);
+
+        let telegram = hashtags.counts(Some(&Platform::Telegram), Duration::hours(24), 10).await.unwrap();
+        assert_eq!(telegram.len(), 1);
+        assert_eq!(telegram[0].hashtag, "zk");
+    }
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::influence::{InfluenceScore, InfluenceSignals, UserInfluence};
+
+/// Latest influence score of every user with its rank; equal composites share a rank
+pub(crate) const INFLUENCE_RANKS: &str = "
+    SELECT user_id, reach_score, echo_amplification, network_centrality, composite, window_days, calculated_at,
+           RANK() OVER (ORDER BY composite DESC)::int AS influence_rank
+    FROM (
+        SELECT DISTINCT ON (user_id) * FROM user_influence_scores
+        ORDER BY user_id, calculated_at DESC
+    ) latest";
+
+/// Share chains are followed at most this many propagations deep when measuring reach
+const MAX_REACH_HOPS: i32 = 10;
+
+/// Propagation activity of users and the influence scores calculated from it
+pub struct InfluenceRepository {
+    pool: PgPool,
+}
+
+impl InfluenceRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Propagation activity of `user_id` since `since`. Reach follows each share chain the
+    /// user started, through later propagations of the same content by the users it reached.
+    /// Echo Index change compares the latest score before each propagation with the latest
+    /// score since, skipping content not scored on both sides.
+    pub async fn signals(&self, user_id: Uuid, since: DateTime<Utc>) -> Result<InfluenceSignals, RepositoryError> {
+        let signals = sqlx::query_as::<_, InfluenceSignals>(
+            "WITH RECURSIVE reached (content_id, user_id, created_at, hops) AS (
+                 SELECT content_id, target_user_id, created_at, 1 FROM propagations
+                 WHERE source_user_id = $1 AND created_at >= $2 AND target_user_id IS NOT NULL
+                 UNION
+                 SELECT p.content_id, p.target_user_id, p.created_at, reached.hops + 1
+                 FROM propagations p
+                 JOIN reached ON p.content_id = reached.content_id AND p.source_user_id = reached.user_id
+                 WHERE p.target_user_id IS NOT NULL AND p.created_at >= reached.created_at
+                   AND reached.hops < $3
+             )
+             SELECT
+                 (SELECT COUNT(DISTINCT target_user_id) FROM propagations
+                  WHERE source_user_id = $1 AND created_at >= $2 AND target_user_id <> $1) AS out_degree,
+                 (SELECT COUNT(DISTINCT participant)
+                  FROM propagations, LATERAL (VALUES (source_user_id), (target_user_id)) AS p(participant)
+                  WHERE created_at >= $2) AS network_size,
+                 (SELECT AVG(after.score - before.score)
+                  FROM propagations p
+                  JOIN LATERAL (
+                      SELECT score FROM echo_index_history h
+                      WHERE h.content_id = p.content_id AND h.calculated_at <= p.created_at
+                      ORDER BY h.calculated_at DESC LIMIT 1
+                  ) before ON TRUE
+                  JOIN LATERAL (
+                      SELECT score FROM echo_index_history h
+                      WHERE h.content_id = p.content_id AND h.calculated_at > p.created_at
+                      ORDER BY h.calculated_at DESC LIMIT 1
+                  ) after ON TRUE
+                  WHERE p.source_user_id = $1 AND p.created_at >= $2) AS echo_delta,
+                 (SELECT COUNT(DISTINCT user_id) FROM reached WHERE user_id <> $1) AS reach",
+        )
+        .bind(user_id)
+        .bind(since)
+        .bind(MAX_REACH_HOPS)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(signals)
+    }
+
+    /// Active users who propagated content since `since`
+    pub async fn active_users(&self, since: DateTime<Utc>) -> Result<Vec<Uuid>, RepositoryError> {
+        let users = sqlx::query_scalar(
+            "SELECT DISTINCT p.source_user_id FROM propagations p
+             JOIN users u ON u.id = p.source_user_id
+             WHERE p.created_at >= $1 AND u.is_active IS NOT FALSE
+             ORDER BY p.source_user_id",
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    /// Store a user's score, calculated at `calculated_at` over `window_days`
+    pub async fn record(
+        &self,
+        user_id: Uuid,
+        score: &InfluenceScore,
+        window_days: i32,
+        calculated_at: DateTime<Utc>,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query(
+            "INSERT INTO user_influence_scores
+                 (user_id, reach_score, echo_amplification, network_centrality, composite, window_days, calculated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(user_id)
+        .bind(score.reach_score)
+        .bind(score.echo_amplification)
+        .bind(score.network_centrality)
+        .bind(score.composite)
+        .bind(window_days)
+        .bind(calculated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The user's latest score and its rank
+    pub async fn latest(&self, user_id: Uuid) -> Result<Option<UserInfluence>, RepositoryError> {
+        let query = format!("SELECT * FROM ({}) ranks WHERE user_id = $1", INFLUENCE_RANKS);
+        let influence = sqlx::query_as::<_, UserInfluence>(&query)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(influence)
+    }
+
+    /// Composite of the user's latest score
+    pub async fn composite(&self, user_id: Uuid) -> Result<Option<f64>, RepositoryError> {
+        let composite = sqlx::query_scalar(
+            "SELECT composite FROM user_influence_scores
+             WHERE user_id = $1
+             ORDER BY calculated_at DESC
+             LIMIT 1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(composite)
+    }
+}
@@ -0,0 +1,63 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::Platform;
+
+/// Users mentioned in content, resolved through their linked social accounts
+pub struct MentionRepository {
+    pool: PgPool,
+}
+
+impl MentionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// User who linked the `platform` account with handle `mention`, ignoring case
+    pub async fn find_user(&self, platform: &Platform, mention: &str) -> Result<Option<Uuid>, RepositoryError> {
+        let user_id = sqlx::query_scalar(
+            "SELECT user_id FROM social_platforms
+             WHERE platform::text = $1 AND LOWER(platform_username) = LOWER($2)
+             ORDER BY verification_status = 'verified' DESC, created_at
+             LIMIT 1",
+        )
+        .bind(platform.as_str())
+        .bind(mention)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user_id)
+    }
+
+    /// Record that `content_id` mentions `user_id` as `mention`. Returns false if it
+    /// already did.
+    pub async fn record(&self, content_id: Uuid, user_id: Uuid, mention: &str) -> Result<bool, RepositoryError> {
+        let result = sqlx::query(
+            "INSERT INTO content_mentions (content_id, mentioned_user_id, mention)
+             VALUES ($1, $2, $3)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(content_id)
+        .bind(user_id)
+        .bind(mention)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Users mentioned in a content item, in the order they were first mentioned
+    pub async fn mentioned_users(&self, content_id: Uuid) -> Result<Vec<Uuid>, RepositoryError> {
+        let users = sqlx::query_scalar(
+            "SELECT mentioned_user_id FROM content_mentions
+             WHERE content_id = $1
+             ORDER BY created_at, mention",
+        )
+        .bind(content_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users)
+    }
+}
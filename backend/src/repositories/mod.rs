@@ -0,0 +1,138 @@
+pub mod alert_repository;
+pub mod api_key_repository;
+pub mod content_attribution_repository;
+pub mod content_fingerprint_repository;
+pub mod content_repository;
+pub mod content_tfidf_repository;
+pub mod content_version_repository;
+pub mod echo_anomaly_repository;
+pub mod echo_index_history_repository;
+pub mod experiment_repository;
+pub mod feed_repository;
+pub mod hashtag_repository;
+pub mod influence_repository;
+pub mod mention_repository;
+pub mod moderation_repository;
+pub mod oauth_state_repository;
+pub mod platform_stats_repository;
+pub mod propagation_repository;
+pub mod quality_bonus_repository;
+pub mod refresh_token_repository;
+pub mod reward_analytics_repository;
+pub mod reward_checkpoint_repository;
+pub mod reward_pool_repository;
+pub mod session_repository;
+pub mod streak_repository;
+pub mod trending_repository;
+pub mod two_factor_repository;
+pub mod user_event_repository;
+pub mod user_relationship_repository;
+pub mod user_repository;
+pub mod webhook_repository;
+
+pub use alert_repository::AlertRepository;
+pub use api_key_repository::{ApiKeyRepository, NewApiKey};
+pub use content_attribution_repository::ContentAttributionRepository;
+pub use content_fingerprint_repository::ContentFingerprintRepository;
+pub use content_repository::{ContentFilter, ContentRepository, NewContent, TrackedCast};
+pub use content_tfidf_repository::{ClusterInput, ContentTfIdfRepository};
+pub use content_version_repository::ContentVersionRepository;
+pub use echo_anomaly_repository::EchoAnomalyRepository;
+pub use echo_index_history_repository::{EchoIndexHistoryRepository, EchoIndexScores};
+pub use experiment_repository::{ExperimentRepository, NewExperiment};
+pub use feed_repository::FeedRepository;
+pub use hashtag_repository::{HashtagCounts, HashtagRepository};
+pub use influence_repository::InfluenceRepository;
+pub use mention_repository::MentionRepository;
+pub use moderation_repository::ModerationRepository;
+pub use oauth_state_repository::OAuthStateRepository;
+pub use platform_stats_repository::PlatformStatsRepository;
+pub use propagation_repository::{BulkInsertOutcome, NewPropagation, PropagationRepository};
+pub use quality_bonus_repository::{QualityBonusCandidate, QualityBonusRepository};
+pub use refresh_token_repository::RefreshTokenRepository;
+pub use reward_analytics_repository::RewardAnalyticsRepository;
+pub use reward_checkpoint_repository::RewardCheckpointRepository;
+pub use reward_pool_repository::RewardPoolRepository;
+pub use session_repository::{NewSession, SessionRepository};
+pub use streak_repository::StreakRepository;
+pub use trending_repository::{TrendingContent, TrendingRepository};
+pub use two_factor_repository::TwoFactorRepository;
+pub use user_event_repository::UserEventRepository;
+pub use user_relationship_repository::UserRelationshipRepository;
+pub use user_repository::{UserPatch, UserRepository};
+pub use webhook_repository::WebhookRepository;
+
+use sqlx::migrate::Migrator;
+
+/// Schema migrations, embedded at compile time and applied on startup
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Postgres SQLSTATE codes surfaced as client errors
+const UNIQUE_VIOLATION: &str = "23505";
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+const INVALID_TEXT_REPRESENTATION: &str = "22P02";
+
+#[derive(Debug, thiserror::Error)]
+pub enum RepositoryError {
+    #[error("record not found")]
+    NotFound,
+    #[error("conflict: {0}")]
+    Conflict(String),
+    #[error("invalid cursor: {0}")]
+    InvalidCursor(String),
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+    #[error("archive error: {0}")]
+    Archive(String),
+    #[error("database error: {0}")]
+    Database(#[source] sqlx::Error),
+}
+
+impl From<sqlx::Error> for RepositoryError {
+    fn from(error: sqlx::Error) -> Self {
+        if let sqlx::Error::RowNotFound = error {
+            return RepositoryError::NotFound;
+        }
+
+        if let Some(db_error) = error.as_database_error() {
+            let message = db_error.message().to_string();
+            match db_error.code().as_deref() {
+                Some(UNIQUE_VIOLATION) | Some(FOREIGN_KEY_VIOLATION) => {
+                    return RepositoryError::Conflict(message)
+                }
+                Some(INVALID_TEXT_REPRESENTATION) => return RepositoryError::InvalidInput(message),
+                _ => {}
+            }
+        }
+
+        RepositoryError::Database(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    async fn user_tables(pool: &PgPool) -> i64 {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM information_schema.tables
+             WHERE table_schema = 'public' AND table_name <> '_sqlx_migrations'",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn test_migrations_revert_cleanly(pool: PgPool) {
+        MIGRATOR.run(&pool).await.unwrap();
+        assert!(user_tables(&pool).await > 0);
+
+        MIGRATOR.undo(&pool, 0).await.unwrap();
+        assert_eq!(user_tables(&pool).await, 0);
+
+        // Every down migration must leave the schema ready to be rebuilt
+        MIGRATOR.run(&pool).await.unwrap();
+    }
+}
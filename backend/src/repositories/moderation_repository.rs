@@ -0,0 +1,169 @@
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::moderation::{FlagReason, ModerationDecision, ModerationFlag, ModerationStatus};
+
+const FLAG_COLUMNS: &str = "id, content_id, flagged_by, reason, reason_detail, status, reviewed_by, created_at";
+
+pub struct ModerationRepository {
+    pool: PgPool,
+}
+
+impl ModerationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Flags a user has raised since `since`
+    pub async fn flags_since(&self, user_id: Uuid, since: DateTime<Utc>) -> Result<i64, RepositoryError> {
+        let count = sqlx::query_scalar("SELECT COUNT(*) FROM moderation_flags WHERE flagged_by = $1 AND created_at > $2")
+            .bind(user_id)
+            .bind(since)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Record a user's flag on live content. `NotFound` if the content does not exist,
+    /// `Conflict` if the user already flagged it.
+    pub async fn create_flag(
+        &self,
+        content_id: Uuid,
+        flagged_by: Uuid,
+        reason: &FlagReason,
+    ) -> Result<ModerationFlag, RepositoryError> {
+        let row = sqlx::query(&format!(
+            "INSERT INTO moderation_flags (content_id, flagged_by, reason, reason_detail)
+             SELECT id, $2, $3, $4 FROM content WHERE id = $1 AND deleted_at IS NULL
+             RETURNING {}",
+            FLAG_COLUMNS
+        ))
+        .bind(content_id)
+        .bind(flagged_by)
+        .bind(reason.kind())
+        .bind(reason.detail())
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(RepositoryError::NotFound)?;
+
+        flag_from_row(&row)
+    }
+
+    /// Flags on a content item that no moderator has resolved yet
+    pub async fn open_flag_count(&self, content_id: Uuid) -> Result<i64, RepositoryError> {
+        let count = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM moderation_flags
+             WHERE content_id = $1 AND status IN ('pending', 'under_review')",
+        )
+        .bind(content_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Hold active content for review, moving its pending flags along with it. Returns
+    /// whether the content was put under review by this call.
+    pub async fn mark_under_review(&self, content_id: Uuid) -> Result<bool, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            "UPDATE content SET status = 'under_review', updated_at = NOW()
+             WHERE id = $1 AND status = 'active'",
+        )
+        .bind(content_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE moderation_flags SET status = 'under_review' WHERE content_id = $1 AND status = 'pending'")
+            .bind(content_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Unresolved flags, those on content under review first, oldest first
+    pub async fn queue(&self, limit: i64) -> Result<Vec<ModerationFlag>, RepositoryError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM moderation_flags
+             WHERE status IN ('pending', 'under_review')
+             ORDER BY status = 'under_review' DESC, created_at
+             LIMIT $1",
+            FLAG_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(flag_from_row).collect()
+    }
+
+    /// Resolve every open flag on the content a flag was raised against, and make the
+    /// content live again or remove it. Returns the reviewed flag. `NotFound` if there
+    /// is no such flag, `Conflict` if it was already resolved.
+    pub async fn review(
+        &self,
+        flag_id: Uuid,
+        reviewed_by: Uuid,
+        decision: ModerationDecision,
+    ) -> Result<ModerationFlag, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        let status: ModerationStatus =
+            sqlx::query_scalar("SELECT status FROM moderation_flags WHERE id = $1 FOR UPDATE")
+                .bind(flag_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or(RepositoryError::NotFound)?;
+        if !status.is_open() {
+            return Err(RepositoryError::Conflict("flag has already been reviewed".to_string()));
+        }
+
+        sqlx::query(
+            "UPDATE moderation_flags SET status = $3, reviewed_by = $2, reviewed_at = NOW()
+             WHERE content_id = (SELECT content_id FROM moderation_flags WHERE id = $1)
+               AND status IN ('pending', 'under_review')",
+        )
+        .bind(flag_id)
+        .bind(reviewed_by)
+        .bind(decision.resolution())
+        .execute(&mut *tx)
+        .await?;
+
+        let row = sqlx::query(&format!("SELECT {} FROM moderation_flags WHERE id = $1", FLAG_COLUMNS))
+            .bind(flag_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        let flag = flag_from_row(&row)?;
+
+        let content_update = match decision {
+            ModerationDecision::Approve => {
+                "UPDATE content SET status = 'active', updated_at = NOW() WHERE id = $1 AND status = 'under_review'"
+            }
+            ModerationDecision::Reject => "UPDATE content SET status = 'removed', updated_at = NOW() WHERE id = $1",
+        };
+        sqlx::query(content_update).bind(flag.content_id).execute(&mut *tx).await?;
+        tx.commit().await?;
+
+        Ok(flag)
+    }
+}
+
+fn flag_from_row(row: &PgRow) -> Result<ModerationFlag, RepositoryError> {
+    let kind: String = row.try_get("reason")?;
+    let reason = FlagReason::from_parts(&kind, row.try_get("reason_detail")?)
+        .ok_or_else(|| RepositoryError::InvalidInput(format!("unknown flag reason {}", kind)))?;
+
+    Ok(ModerationFlag {
+        id: row.try_get("id")?,
+        content_id: row.try_get("content_id")?,
+        flagged_by: row.try_get("flagged_by")?,
+        reason,
+        status: row.try_get("status")?,
+        reviewed_by: row.try_get("reviewed_by")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
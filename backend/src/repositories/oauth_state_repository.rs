@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::oauth::OAuthState;
+use crate::models::Platform;
+
+pub struct OAuthStateRepository {
+    pool: PgPool,
+}
+
+impl OAuthStateRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// `Conflict` if the user does not exist
+    pub async fn create(&self, state: &OAuthState) -> Result<(), RepositoryError> {
+        sqlx::query(
+            "INSERT INTO oauth_states
+                 (state, user_id, platform, account_id, code_verifier, request_token, request_token_secret, expires_at)
+             VALUES ($1, $2, $3::platform_type, $4, $5, $6, $7, $8)",
+        )
+        .bind(&state.state)
+        .bind(state.user_id)
+        .bind(state.platform.as_str())
+        .bind(&state.account_id)
+        .bind(&state.code_verifier)
+        .bind(&state.request_token)
+        .bind(&state.request_token_secret)
+        .bind(state.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Delete and return a state issued to the user for `platform` that has not expired
+    /// by `now`. A state can be taken once, so a replayed callback finds nothing.
+    pub async fn take(
+        &self,
+        state: &str,
+        user_id: Uuid,
+        platform: &Platform,
+        now: DateTime<Utc>,
+    ) -> Result<Option<OAuthState>, RepositoryError> {
+        let Some(row) = sqlx::query(
+            "DELETE FROM oauth_states
+             WHERE state = $1 AND user_id = $2 AND platform::text = $3 AND expires_at > $4
+             RETURNING state, user_id, platform::text AS platform, account_id, code_verifier,
+                       request_token, request_token_secret, expires_at",
+        )
+        .bind(state)
+        .bind(user_id)
+        .bind(platform.as_str())
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(OAuthState {
+            state: row.try_get("state")?,
+            user_id: row.try_get("user_id")?,
+            platform: Platform::from(row.try_get::<&str, _>("platform")?),
+            account_id: row.try_get("account_id")?,
+            code_verifier: row.try_get("code_verifier")?,
+            request_token: row.try_get("request_token")?,
+            request_token_secret: row.try_get("request_token_secret")?,
+            expires_at: row.try_get("expires_at")?,
+        }))
+    }
+
+    /// Delete states that expired by `now`; returns how many there were
+    pub async fn delete_expired(&self, now: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let result = sqlx::query("DELETE FROM oauth_states WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,60 @@
+use sqlx::PgPool;
+
+use super::RepositoryError;
+use crate::models::platform_stats::PlatformEchoStats;
+
+/// Days of content platform statistics cover
+const STATS_WINDOW_DAYS: i32 = 30;
+
+const PLATFORM_STATS_COLUMNS: &str = "
+    platform::text AS platform, content_count, avg_echo_index, median_echo_index, top_10_pct_threshold,
+    total_propagations, avg_propagation_depth, updated_at";
+
+pub struct PlatformStatsRepository {
+    pool: PgPool,
+}
+
+impl PlatformStatsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Recompute every platform's statistics from its live, scored content of the last 30
+    /// days. Platforms without such content lose their statistics.
+    pub async fn refresh(&self) -> Result<Vec<PlatformEchoStats>, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM platform_echo_stats").execute(&mut *tx).await?;
+        let stats = sqlx::query_as::<_, PlatformEchoStats>(&format!(
+            "WITH scored AS (
+                 SELECT id, platform, echo_index::float8 AS echo_index
+                 FROM content
+                 WHERE deleted_at IS NULL AND echo_index > 0
+                   AND created_at >= NOW() - make_interval(days => $1)
+             ),
+             propagated AS (
+                 SELECT scored.platform, COUNT(p.id) AS total_propagations,
+                        COALESCE(AVG(p.depth), 0)::float8 AS avg_propagation_depth
+                 FROM scored
+                 LEFT JOIN propagations p ON p.content_id = scored.id
+                 GROUP BY scored.platform
+             )
+             INSERT INTO platform_echo_stats (platform, avg_echo_index, content_count, median_echo_index,
+                                              top_10_pct_threshold, total_propagations, avg_propagation_depth)
+             SELECT scored.platform, AVG(scored.echo_index), COUNT(*),
+                    PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY scored.echo_index),
+                    PERCENTILE_CONT(0.9) WITHIN GROUP (ORDER BY scored.echo_index),
+                    propagated.total_propagations, propagated.avg_propagation_depth
+             FROM scored
+             JOIN propagated USING (platform)
+             GROUP BY scored.platform, propagated.total_propagations, propagated.avg_propagation_depth
+             RETURNING {}",
+            PLATFORM_STATS_COLUMNS
+        ))
+        .bind(STATS_WINDOW_DAYS)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(stats)
+    }
+}
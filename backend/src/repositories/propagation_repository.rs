@@ -0,0 +1,663 @@
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::Platform;
+use crate::services::loop_strength::EchoLoopNormalizer;
+use crate::services::propagation::{AmplificationEvent, EchoLoop, PropagationNode, PropagationPath};
+
+const AMPLIFICATION_COLUMNS: &str = "id, loop_id, content_id, trigger_resonance, amplification_factor, \
+                                     affected_path_count, total_weight_before, total_weight_after, created_at, \
+                                     reverted_at";
+
+/// A propagation event ready to be stored
+#[derive(Debug, Clone)]
+pub struct NewPropagation {
+    pub content_id: Uuid,
+    pub source_user_id: Option<Uuid>,
+    pub target_user_id: Option<Uuid>,
+    pub propagation_type: String,
+    pub source_platform: Platform,
+    pub target_platform: Platform,
+    pub source_external_id: Option<String>,
+    pub target_external_id: Option<String>,
+}
+
+impl NewPropagation {
+    /// Events sharing this key describe the same propagation
+    pub fn dedup_key(&self) -> (Uuid, String, String, String) {
+        (
+            self.content_id,
+            self.source_external_id.clone().unwrap_or_default(),
+            self.target_external_id.clone().unwrap_or_default(),
+            self.propagation_type.clone(),
+        )
+    }
+
+    /// SHA-256 of the dedup key, which connectors reporting the same event agree on
+    pub fn fingerprint(&self) -> Vec<u8> {
+        let (content_id, source_external_id, target_external_id, propagation_type) = self.dedup_key();
+        let mut hasher = Sha256::new();
+        for field in [content_id.to_string(), source_external_id, target_external_id, propagation_type] {
+            hasher.update(field.as_bytes());
+            // Keeps ("ab", "c") and ("a", "bc") apart
+            hasher.update([0x1f]);
+        }
+        hasher.finalize().to_vec()
+    }
+}
+
+/// A newly stored propagation
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredPropagation {
+    pub id: Uuid,
+    pub content_id: Uuid,
+    pub propagation_type: String,
+    /// Earlier propagation of the same event, stored before the deduplication window
+    pub duplicate_of: Option<Uuid>,
+    /// Multiplier the propagation applies to its content's Echo Index
+    pub echo_boost: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Result of storing a batch of propagation events
+#[derive(Debug, Default)]
+pub struct BulkInsertOutcome {
+    pub inserted: Vec<StoredPropagation>,
+    /// Events referring to missing content or users, by position in the batch
+    pub rejected: Vec<(usize, String)>,
+    /// Events already stored within the deduplication window, by position in the batch,
+    /// with the propagation they repeat
+    pub duplicates: Vec<(usize, Uuid)>,
+}
+
+#[derive(FromRow)]
+struct EchoLoopRow {
+    id: String,
+    source_content_id: String,
+    total_resonance: f64,
+    loop_strength: f64,
+    created_at: DateTime<Utc>,
+    last_updated: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct PropagationPathRow {
+    loop_id: String,
+    nodes: Json<Vec<PropagationNode>>,
+    total_weight: f64,
+    resonance_factor: f64,
+    decay_rate: f64,
+}
+
+impl From<PropagationPathRow> for PropagationPath {
+    fn from(row: PropagationPathRow) -> Self {
+        Self {
+            nodes: row.nodes.0,
+            total_weight: row.total_weight,
+            resonance_factor: row.resonance_factor,
+            decay_rate: row.decay_rate,
+        }
+    }
+}
+
+impl EchoLoopRow {
+    fn into_echo_loop(self, propagation_paths: Vec<PropagationPath>) -> EchoLoop {
+        let mut echo_loop = EchoLoop {
+            id: self.id,
+            source_content_id: self.source_content_id,
+            propagation_paths,
+            total_resonance: self.total_resonance,
+            raw_loop_strength: self.loop_strength,
+            normalized_loop_strength: 0.0,
+            created_at: self.created_at,
+            last_updated: self.last_updated,
+        };
+        // Only the raw strength is stored; the normalized one follows from the paths
+        echo_loop.normalized_loop_strength = EchoLoopNormalizer::normalize_by_network_size(&echo_loop);
+        echo_loop
+    }
+}
+
+pub struct PropagationRepository {
+    pool: PgPool,
+}
+
+impl PropagationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert or replace an Echo Loop together with all of its paths
+    pub async fn save_loop(&self, echo_loop: &EchoLoop) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Concurrent events on a loop are saved from separate snapshots; the row lock taken
+        // here orders them, and a snapshot older than what is stored is dropped
+        let upserted = sqlx::query(
+            "INSERT INTO echo_loops (id, source_content_id, total_resonance, loop_strength, created_at, last_updated)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (id) DO UPDATE
+             SET total_resonance = EXCLUDED.total_resonance,
+                 loop_strength = EXCLUDED.loop_strength,
+                 last_updated = EXCLUDED.last_updated
+             WHERE echo_loops.last_updated <= EXCLUDED.last_updated",
+        )
+        .bind(&echo_loop.id)
+        .bind(&echo_loop.source_content_id)
+        .bind(echo_loop.total_resonance)
+        .bind(echo_loop.raw_loop_strength)
+        .bind(echo_loop.created_at)
+        .bind(echo_loop.last_updated)
+        .execute(&mut *tx)
+        .await?;
+        if upserted.rows_affected() == 0 {
+            return Ok(());
+        }
+
+        sqlx::query("DELETE FROM propagation_paths WHERE loop_id = $1")
+            .bind(&echo_loop.id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (position, path) in echo_loop.propagation_paths.iter().enumerate() {
+            Self::insert_path(&mut tx, &echo_loop.id, position as i32, path).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Load an Echo Loop and its paths in their original order
+    pub async fn load_loop(&self, loop_id: &str) -> Result<EchoLoop, RepositoryError> {
+        let row = sqlx::query_as::<_, EchoLoopRow>(
+            "SELECT id, source_content_id, total_resonance, loop_strength, created_at, last_updated
+             FROM echo_loops WHERE id = $1",
+        )
+        .bind(loop_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let paths = sqlx::query_as::<_, PropagationPathRow>(
+            "SELECT loop_id, nodes, total_weight, resonance_factor, decay_rate
+             FROM propagation_paths WHERE loop_id = $1 ORDER BY position",
+        )
+        .bind(loop_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(row.into_echo_loop(paths.into_iter().map(PropagationPath::from).collect()))
+    }
+
+    /// All Echo Loops originating from a content piece, oldest first
+    pub async fn list_loops_for_content(&self, content_id: &str) -> Result<Vec<EchoLoop>, RepositoryError> {
+        let rows = sqlx::query_as::<_, EchoLoopRow>(
+            "SELECT id, source_content_id, total_resonance, loop_strength, created_at, last_updated
+             FROM echo_loops WHERE source_content_id = $1 ORDER BY created_at",
+        )
+        .bind(content_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let loop_ids: Vec<String> = rows.iter().map(|row| row.id.clone()).collect();
+        let paths = sqlx::query_as::<_, PropagationPathRow>(
+            "SELECT loop_id, nodes, total_weight, resonance_factor, decay_rate
+             FROM propagation_paths WHERE loop_id = ANY($1) ORDER BY loop_id, position",
+        )
+        .bind(&loop_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut paths_by_loop: HashMap<String, Vec<PropagationPath>> = HashMap::new();
+        for path in paths {
+            paths_by_loop.entry(path.loop_id.clone()).or_default().push(path.into());
+        }
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let paths = paths_by_loop.remove(&row.id).unwrap_or_default();
+                row.into_echo_loop(paths)
+            })
+            .collect())
+    }
+
+    /// Delete loops that are both stale and weak, returning how many were removed
+    pub async fn delete_expired_loops(
+        &self,
+        updated_before: DateTime<Utc>,
+        min_strength: f64,
+    ) -> Result<u64, RepositoryError> {
+        let result = sqlx::query("DELETE FROM echo_loops WHERE last_updated < $1 AND loop_strength < $2")
+            .bind(updated_before)
+            .bind(min_strength)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Record a resonance amplification of a loop
+    pub async fn record_amplification(&self, event: &AmplificationEvent) -> Result<(), RepositoryError> {
+        sqlx::query(&format!(
+            "INSERT INTO amplification_events ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            AMPLIFICATION_COLUMNS
+        ))
+        .bind(event.id)
+        .bind(&event.loop_id)
+        .bind(&event.content_id)
+        .bind(event.trigger_resonance)
+        .bind(event.amplification_factor)
+        .bind(event.affected_path_count)
+        .bind(event.total_weight_before)
+        .bind(event.total_weight_after)
+        .bind(event.created_at)
+        .bind(event.reverted_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_amplification(&self, id: Uuid) -> Result<AmplificationEvent, RepositoryError> {
+        let event = sqlx::query_as::<_, AmplificationEvent>(&format!(
+            "SELECT {} FROM amplification_events WHERE id = $1",
+            AMPLIFICATION_COLUMNS
+        ))
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// Amplifications since a time, or all, of one content item's loops or of every loop,
+    /// most recent first
+    pub async fn list_amplifications(
+        &self,
+        content_id: Option<&str>,
+        since: Option<DateTime<Utc>>,
+        limit: u32,
+    ) -> Result<Vec<AmplificationEvent>, RepositoryError> {
+        let events = sqlx::query_as::<_, AmplificationEvent>(&format!(
+            "SELECT {} FROM amplification_events
+             WHERE ($1::text IS NULL OR content_id = $1) AND ($2::timestamptz IS NULL OR created_at >= $2)
+             ORDER BY created_at DESC, id
+             LIMIT $3",
+            AMPLIFICATION_COLUMNS
+        ))
+        .bind(content_id)
+        .bind(since)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Mark an amplification reverted at `now`; a conflict if it already was
+    pub async fn mark_amplification_reverted(
+        &self,
+        id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<AmplificationEvent, RepositoryError> {
+        let reverted = sqlx::query_as::<_, AmplificationEvent>(&format!(
+            "UPDATE amplification_events SET reverted_at = $2
+             WHERE id = $1 AND reverted_at IS NULL
+             RETURNING {}",
+            AMPLIFICATION_COLUMNS
+        ))
+        .bind(id)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match reverted {
+            Some(event) => Ok(event),
+            None => {
+                self.get_amplification(id).await?;
+                Err(RepositoryError::Conflict("Amplification already reverted".to_string()))
+            }
+        }
+    }
+
+    /// Propagations stored for any of the fingerprints since `since`, by fingerprint
+    pub async fn find_by_fingerprints(
+        &self,
+        fingerprints: &[&[u8]],
+        since: DateTime<Utc>,
+    ) -> Result<HashMap<Vec<u8>, Uuid>, RepositoryError> {
+        let stored = sqlx::query_as(
+            "SELECT fingerprint, propagation_id FROM propagation_fingerprints
+             WHERE fingerprint = ANY($1) AND created_at >= $2",
+        )
+        .bind(fingerprints)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(stored.into_iter().collect())
+    }
+
+    /// Store a batch of propagation events in one transaction. Events whose fingerprint was
+    /// stored within `window` are skipped as duplicates; older ones are stored again, linked
+    /// to the propagation they repeat. Each new propagation bumps its content's
+    /// `propagation_count`.
+    pub async fn insert_bulk(
+        &self,
+        events: &[NewPropagation],
+        window: Duration,
+    ) -> Result<BulkInsertOutcome, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Locked so content cannot be deleted or archived while its propagations go in
+        let content_ids: Vec<Uuid> = events.iter().map(|event| event.content_id).collect();
+        let live_content: HashSet<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM content WHERE id = ANY($1) AND deleted_at IS NULL ORDER BY id FOR UPDATE",
+        )
+        .bind(&content_ids)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        let user_ids: Vec<Uuid> = events
+            .iter()
+            .flat_map(|event| [event.source_user_id, event.target_user_id])
+            .flatten()
+            .collect();
+        let known_users: HashSet<Uuid> = sqlx::query_scalar("SELECT id FROM users WHERE id = ANY($1)")
+            .bind(&user_ids)
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut outcome = BulkInsertOutcome::default();
+        let mut accepted = Vec::with_capacity(events.len());
+        for (index, event) in events.iter().enumerate() {
+            let unknown_user = [event.source_user_id, event.target_user_id]
+                .into_iter()
+                .flatten()
+                .find(|user_id| !known_users.contains(user_id));
+            if !live_content.contains(&event.content_id) {
+                outcome.rejected.push((index, format!("content {} not found", event.content_id)));
+            } else if let Some(user_id) = unknown_user {
+                outcome.rejected.push((index, format!("user {} not found", user_id)));
+            } else {
+                accepted.push((index, event.fingerprint(), Uuid::new_v4(), event));
+            }
+        }
+        let fingerprints: Vec<&[u8]> = accepted.iter().map(|(_, fingerprint, _, _)| fingerprint.as_slice()).collect();
+        let cutoff = Utc::now() - window;
+
+        // Propagations the fingerprints were last stored for, outside the window or not
+        let previous: HashMap<Vec<u8>, Uuid> = sqlx::query_as(
+            "SELECT fingerprint, propagation_id FROM propagation_fingerprints
+             WHERE fingerprint = ANY($1) ORDER BY fingerprint FOR UPDATE",
+        )
+        .bind(&fingerprints)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        // A fingerprint is claimed unless stored within the window, by now or by a concurrent
+        // batch this one waited for. Claimed in order, so concurrent batches cannot deadlock.
+        let mut claims: BTreeMap<&[u8], Uuid> = BTreeMap::new();
+        for (_, fingerprint, id, _) in &accepted {
+            claims.entry(fingerprint.as_slice()).or_insert(*id);
+        }
+        let claimed: HashSet<Vec<u8>> = sqlx::query_scalar(
+            "INSERT INTO propagation_fingerprints (fingerprint, propagation_id)
+             SELECT * FROM UNNEST($1::bytea[], $2::uuid[])
+             ON CONFLICT (fingerprint) DO UPDATE
+                 SET propagation_id = EXCLUDED.propagation_id, created_at = NOW()
+                 WHERE propagation_fingerprints.created_at < $3
+             RETURNING fingerprint",
+        )
+        .bind(claims.keys().copied().collect::<Vec<_>>())
+        .bind(claims.values().copied().collect::<Vec<_>>())
+        .bind(cutoff)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        let unclaimed: Vec<&[u8]> =
+            claims.keys().copied().filter(|fingerprint| !claimed.contains(*fingerprint)).collect();
+        let recent: HashMap<Vec<u8>, Uuid> = sqlx::query_as(
+            "SELECT fingerprint, propagation_id FROM propagation_fingerprints WHERE fingerprint = ANY($1)",
+        )
+        .bind(&unclaimed)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        let mut stored = Vec::with_capacity(accepted.len());
+        for (index, fingerprint, id, event) in &accepted {
+            let claim = claims[fingerprint.as_slice()];
+            if let Some(existing_id) = recent.get(fingerprint) {
+                outcome.duplicates.push((*index, *existing_id));
+            } else if claim != *id {
+                // Repeated within the batch
+                outcome.duplicates.push((*index, claim));
+            } else {
+                stored.push((*id, previous.get(fingerprint).copied(), *event));
+            }
+        }
+
+        outcome.inserted = sqlx::query_as(
+            "WITH inserted AS (
+                 INSERT INTO propagations (id, content_id, source_user_id, target_user_id, propagation_type,
+                                           source_platform, target_platform, source_external_id, target_external_id,
+                                           fingerprint_checked, duplicate_of)
+                 SELECT id, content_id, source_user_id, target_user_id, propagation_type::propagation_type,
+                        source_platform::platform_type, target_platform::platform_type,
+                        source_external_id, target_external_id, TRUE, duplicate_of
+                 FROM UNNEST($1::uuid[], $2::uuid[], $3::uuid[], $4::uuid[], $5::text[], $6::text[], $7::text[],
+                             $8::text[], $9::text[], $10::uuid[])
+                     AS e(id, content_id, source_user_id, target_user_id, propagation_type,
+                          source_platform, target_platform, source_external_id, target_external_id, duplicate_of)
+                 RETURNING id, content_id, propagation_type::text, duplicate_of,
+                           COALESCE(echo_boost, 1)::float8 AS echo_boost, created_at
+             ),
+             counted AS (
+                 UPDATE content c SET propagation_count = COALESCE(c.propagation_count, 0) + i.added
+                 FROM (SELECT content_id, COUNT(*)::int AS added FROM inserted GROUP BY content_id) i
+                 WHERE c.id = i.content_id
+             )
+             SELECT * FROM inserted",
+        )
+        .bind(stored.iter().map(|(id, _, _)| *id).collect::<Vec<_>>())
+        .bind(stored.iter().map(|(_, _, event)| event.content_id).collect::<Vec<_>>())
+        .bind(stored.iter().map(|(_, _, event)| event.source_user_id).collect::<Vec<_>>())
+        .bind(stored.iter().map(|(_, _, event)| event.target_user_id).collect::<Vec<_>>())
+        .bind(stored.iter().map(|(_, _, event)| event.propagation_type.as_str()).collect::<Vec<_>>())
+        .bind(stored.iter().map(|(_, _, event)| event.source_platform.as_str()).collect::<Vec<_>>())
+        .bind(stored.iter().map(|(_, _, event)| event.target_platform.as_str()).collect::<Vec<_>>())
+        .bind(stored.iter().map(|(_, _, event)| event.source_external_id.as_deref()).collect::<Vec<_>>())
+        .bind(stored.iter().map(|(_, _, event)| event.target_external_id.as_deref()).collect::<Vec<_>>())
+        .bind(stored.iter().map(|(_, duplicate_of, _)| *duplicate_of).collect::<Vec<_>>())
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(outcome)
+    }
+
+    async fn insert_path(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        loop_id: &str,
+        position: i32,
+        path: &PropagationPath,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query(
+            "INSERT INTO propagation_paths (loop_id, position, nodes, total_weight, resonance_factor, decay_rate)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(loop_id)
+        .bind(position)
+        .bind(Json(&path.nodes))
+        .bind(path.total_weight)
+        .bind(path.resonance_factor)
+        .bind(path.decay_rate)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::propagation::{NodeType, PropagationService};
+    use std::sync::Arc;
+
+    fn user_node(id: &str) -> PropagationNode {
+        PropagationNode {
+            id: id.to_string(),
+            node_type: NodeType::User,
+            influence_weight: 0.5,
+            reach: 100,
+            engagement_rate: 0.1,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_multi_path_loop_round_trip(pool: PgPool) {
+        let repository = Arc::new(PropagationRepository::new(pool.clone()));
+        let service = PropagationService::with_repository(repository.clone());
+
+        let loop_id = service.create_echo_loop("content_1".to_string()).await.unwrap();
+        service.add_propagation_event(&loop_id, user_node("a"), user_node("b"), 1.0).await.unwrap();
+        service.add_propagation_event(&loop_id, user_node("b"), user_node("c"), 1.0).await.unwrap();
+        service.add_propagation_event(&loop_id, user_node("x"), user_node("y"), 0.5).await.unwrap();
+
+        // A fresh service only sees what was persisted
+        let loaded = PropagationRepository::new(pool).load_loop(&loop_id).await.unwrap();
+        let node_ids: Vec<Vec<&str>> = loaded
+            .propagation_paths
+            .iter()
+            .map(|path| path.nodes.iter().map(|node| node.id.as_str()).collect())
+            .collect();
+        assert_eq!(node_ids, vec![vec!["a", "b", "c"], vec!["x", "y"]]);
+        assert_eq!(loaded.source_content_id, "content_1");
+
+        let cached = &service.get_content_echo_loops("content_1")[0];
+        assert!((loaded.raw_loop_strength - cached.raw_loop_strength).abs() < 1e-9);
+        assert!((loaded.normalized_loop_strength - cached.normalized_loop_strength).abs() < 1e-9);
+
+        let loops = repository.list_loops_for_content("content_1").await.unwrap();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].propagation_paths.len(), 2);
+        assert_eq!(loops[0].propagation_paths[1].nodes[1].id, "y");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_concurrent_saves_keep_the_newest_snapshot(pool: PgPool) {
+        let repository = Arc::new(PropagationRepository::new(pool.clone()));
+        let service = Arc::new(PropagationService::with_repository(repository.clone()));
+        let loop_id = service.create_echo_loop("content_1".to_string()).await.unwrap();
+
+        let events = (0..20).map(|i| {
+            let service = service.clone();
+            let loop_id = loop_id.clone();
+            tokio::spawn(async move {
+                let (from, to) = (user_node(&format!("s{}", i)), user_node(&format!("t{}", i)));
+                service.add_propagation_event(&loop_id, from, to, 1.0).await
+            })
+        });
+        for result in futures_util::future::join_all(events).await {
+            result.unwrap().unwrap();
+        }
+
+        // Whichever save finished last, the stored loop is the one with every event
+        let loaded = repository.load_loop(&loop_id).await.unwrap();
+        assert_eq!(loaded.propagation_paths.len(), 20);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_delete_expired_loops_requires_stale_and_weak(pool: PgPool) {
+        let repository = PropagationRepository::new(pool);
+        let now = Utc::now();
+        let echo_loop = |id: &str, strength: f64, age_hours: i64| EchoLoop {
+            id: id.to_string(),
+            source_content_id: "content_1".to_string(),
+            propagation_paths: Vec::new(),
+            total_resonance: 0.0,
+            raw_loop_strength: strength,
+            normalized_loop_strength: strength,
+            created_at: now - chrono::Duration::hours(age_hours),
+            last_updated: now - chrono::Duration::hours(age_hours),
+        };
+
+        repository.save_loop(&echo_loop("stale_weak", 0.05, 48)).await.unwrap();
+        repository.save_loop(&echo_loop("stale_strong", 0.8, 48)).await.unwrap();
+        repository.save_loop(&echo_loop("fresh_weak", 0.05, 1)).await.unwrap();
+
+        let deleted = repository
+            .delete_expired_loops(now - chrono::Duration::hours(24), 0.1)
+            .await
+            .unwrap();
+        assert_eq!(deleted, 1);
+        assert!(matches!(repository.load_loop("stale_weak").await, Err(RepositoryError::NotFound)));
+        assert_eq!(repository.list_loops_for_content("content_1").await.unwrap().len(), 2);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_depth_follows_the_share_chain(pool: PgPool) {
+        let mut users = Vec::new();
+        for wallet in ["0xa", "0xb", "0xc", "0xd"] {
+            let id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+                .bind(wallet)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            users.push(id);
+        }
+        let content_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type)
+             VALUES ($1, 'twitter', 'tweet_1', 'text') RETURNING id",
+        )
+        .bind(users[0])
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let share = |from: usize, to: usize| NewPropagation {
+            content_id,
+            source_user_id: Some(users[from]),
+            target_user_id: Some(users[to]),
+            propagation_type: "share".to_string(),
+            source_platform: Platform::Twitter,
+            target_platform: Platform::Twitter,
+            source_external_id: Some(format!("from_{}", from)),
+            target_external_id: Some(format!("to_{}", to)),
+        };
+        let repository = PropagationRepository::new(pool.clone());
+        // The author shares to b, who shares to c within the same batch; c shares to d later
+        let window = Duration::hours(24);
+        repository.insert_bulk(&[share(0, 1), share(1, 2)], window).await.unwrap();
+        repository.insert_bulk(&[share(2, 3), share(0, 3)], window).await.unwrap();
+
+        let depths: Vec<(Uuid, Uuid, i32)> = sqlx::query_as(
+            "SELECT source_user_id, target_user_id, depth FROM propagations WHERE content_id = $1",
+        )
+        .bind(content_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        let depth = |from: usize, to: usize| {
+            depths.iter().find(|(source, target, _)| (*source, *target) == (users[from], users[to])).unwrap().2
+        };
+        assert_eq!((depth(0, 1), depth(1, 2), depth(2, 3), depth(0, 3)), (1, 2, 3, 1));
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::Platform;
+
+/// Content eligible for a quality bonus, with its aggregated propagation data
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+pub struct QualityBonusCandidate {
+    pub content_id: Uuid,
+    pub user_id: Uuid,
+    pub title: String,
+    pub platform: Platform,
+    pub echo_index: f64,
+    /// Score of the first recorded calculation, 0 if none was recorded
+    pub first_echo_index: f64,
+    pub created_at: DateTime<Utc>,
+    pub propagations: i64,
+    pub propagators: i64,
+    pub reached_users: i64,
+    /// Quotes and mentions, which add commentary rather than passing the content on
+    pub engaged_propagations: i64,
+    /// Propagations made more than a day after the content was created
+    pub late_propagations: i64,
+    pub platforms_reached: i64,
+}
+
+pub struct QualityBonusRepository {
+    pool: PgPool,
+}
+
+impl QualityBonusRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Unreviewed live content scoring above `min_echo_index` that was created before
+    /// `created_before`, highest Echo Index first
+    pub async fn pending(
+        &self,
+        min_echo_index: f64,
+        created_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<QualityBonusCandidate>, RepositoryError> {
+        let candidates = sqlx::query_as::<_, QualityBonusCandidate>(
+            "SELECT c.id AS content_id, c.user_id, COALESCE(c.title, '') AS title, c.platform::text AS platform,
+                    c.echo_index::float8 AS echo_index, c.created_at,
+                    COALESCE((SELECT h.score FROM echo_index_history h
+                              WHERE h.content_id = c.id
+                              ORDER BY h.calculated_at LIMIT 1), 0) AS first_echo_index,
+                    COUNT(p.id) AS propagations,
+                    COUNT(DISTINCT p.source_user_id) AS propagators,
+                    COUNT(DISTINCT p.target_user_id) AS reached_users,
+                    COUNT(p.id) FILTER (WHERE p.propagation_type IN ('quote', 'mention')) AS engaged_propagations,
+                    COUNT(p.id) FILTER (WHERE p.created_at >= c.created_at + INTERVAL '1 day') AS late_propagations,
+                    COUNT(DISTINCT p.target_platform) AS platforms_reached
+             FROM content c
+             LEFT JOIN propagations p ON p.content_id = c.id
+             WHERE c.deleted_at IS NULL
+               AND c.echo_index > $1
+               AND c.created_at < $2
+               AND NOT EXISTS (SELECT 1 FROM quality_bonus_reviews r WHERE r.content_id = c.id)
+             GROUP BY c.id
+             ORDER BY c.echo_index DESC, c.id
+             LIMIT $3",
+        )
+        .bind(min_echo_index)
+        .bind(created_before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(candidates)
+    }
+
+    /// Mark content as under review. Returns false if it was already reviewed, so a
+    /// bonus is never awarded twice even by concurrent reviewers.
+    pub async fn claim(&self, content_id: Uuid, user_id: Uuid) -> Result<bool, RepositoryError> {
+        let result = sqlx::query(
+            "INSERT INTO quality_bonus_reviews (content_id, user_id) VALUES ($1, $2)
+             ON CONFLICT (content_id) DO NOTHING",
+        )
+        .bind(content_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Record the bonus awarded for claimed content
+    pub async fn record_award(&self, content_id: Uuid, reward_id: &str, amount: f64) -> Result<(), RepositoryError> {
+        sqlx::query("UPDATE quality_bonus_reviews SET reward_id = $2, amount = $3 WHERE content_id = $1")
+            .bind(content_id)
+            .bind(reward_id)
+            .bind(amount)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Give up a claim so the content is reviewed again on the next run
+    pub async fn release(&self, content_id: Uuid) -> Result<(), RepositoryError> {
+        sqlx::query("DELETE FROM quality_bonus_reviews WHERE content_id = $1 AND reward_id IS NULL")
+            .bind(content_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::user::Role;
+
+/// The user and session an unexpired refresh token was issued to
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct RefreshTokenOwner {
+    pub user_id: Uuid,
+    pub wallet_address: String,
+    pub role: Role,
+    pub session_id: Uuid,
+    /// Whether the session was signed in with a second factor
+    pub totp_verified: bool,
+}
+
+pub struct RefreshTokenRepository {
+    pool: PgPool,
+}
+
+impl RefreshTokenRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store the SHA-256 hash of a newly issued refresh token
+    pub async fn store(
+        &self,
+        token_hash: &str,
+        user_id: Uuid,
+        session_id: Uuid,
+        totp_verified: bool,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query(
+            "INSERT INTO refresh_tokens (token_hash, user_id, session_id, totp_verified, expires_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(token_hash)
+        .bind(user_id)
+        .bind(session_id)
+        .bind(totp_verified)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Owner of the refresh token with this hash, unless it is unknown or expired
+    pub async fn find_active(&self, token_hash: &str) -> Result<Option<RefreshTokenOwner>, RepositoryError> {
+        let owner = sqlx::query_as::<_, RefreshTokenOwner>(
+            "SELECT u.id AS user_id, u.wallet_address, u.role, t.session_id, t.totp_verified
+             FROM refresh_tokens t
+             JOIN users u ON u.id = t.user_id
+             WHERE t.token_hash = $1 AND t.expires_at > NOW()",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(owner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::UserRepository;
+    use chrono::Duration;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_expired_tokens_are_not_found(pool: PgPool) {
+        let repository = RefreshTokenRepository::new(pool.clone());
+        let user_id = UserRepository::new(pool).find_or_create_by_wallet("0xrefresh").await.unwrap().id;
+        let session_id = Uuid::new_v4();
+
+        let active = "a".repeat(64);
+        let expired = "b".repeat(64);
+        repository.store(&active, user_id, session_id, true, Utc::now() + Duration::days(1)).await.unwrap();
+        repository.store(&expired, user_id, session_id, false, Utc::now() - Duration::seconds(1)).await.unwrap();
+
+        let owner = repository.find_active(&active).await.unwrap().unwrap();
+        assert_eq!(
+            owner,
+            RefreshTokenOwner {
+                user_id,
+                wallet_address: "0xrefresh".to_string(),
+                role: Role::User,
+                session_id,
+                totp_verified: true,
+            }
+        );
+        assert!(repository.find_active(&expired).await.unwrap().is_none());
+        assert!(repository.find_active(&"c".repeat(64)).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,131 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::reward_analytics::{AnalyticsBucket, Granularity};
+use crate::services::rewards::RewardType;
+
+#[derive(FromRow)]
+struct AnalyticsBucketRow {
+    bucket_start: DateTime<Utc>,
+    total_distributed: f64,
+    by_type: Json<HashMap<RewardType, f64>>,
+    unique_recipients: i64,
+    new_users_rewarded: i64,
+    pool_utilization_pct: f64,
+    rolling_7d_avg: f64,
+}
+
+impl From<AnalyticsBucketRow> for AnalyticsBucket {
+    fn from(row: AnalyticsBucketRow) -> Self {
+        Self {
+            bucket_start: row.bucket_start,
+            total_distributed: row.total_distributed,
+            by_type: row.by_type.0,
+            unique_recipients: row.unique_recipients as u32,
+            new_users_rewarded: row.new_users_rewarded as u32,
+            pool_utilization_pct: row.pool_utilization_pct,
+            rolling_7d_avg: row.rolling_7d_avg,
+        }
+    }
+}
+
+/// Rewards over time, aggregated from the `reward_earned` events of user timelines
+pub struct RewardAnalyticsRepository {
+    pool: PgPool,
+}
+
+impl RewardAnalyticsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Buckets of `granularity` in which rewards were distributed between the start of
+    /// the bucket `since` falls in and `until`, oldest first; all users' rewards, or only
+    /// `user_id`'s
+    pub async fn time_series(
+        &self,
+        user_id: Option<Uuid>,
+        granularity: Granularity,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<AnalyticsBucket>, RepositoryError> {
+        // The 7 days before `since` are aggregated too, so the first buckets' rolling
+        // averages see a full week, and filtered out afterwards
+        let rows = sqlx::query_as::<_, AnalyticsBucketRow>(
+            "WITH rewards AS (
+                 SELECT user_id,
+                        date_trunc($1, created_at AT TIME ZONE 'UTC') AT TIME ZONE 'UTC' AS bucket_start,
+                        payload->>'reward_type' AS reward_type,
+                        (payload->>'amount')::float8 AS amount
+                 FROM user_events
+                 WHERE event_type = 'reward_earned'
+                   AND ($2::uuid IS NULL OR user_id = $2)
+                   AND created_at >= $3 - INTERVAL '7 days' AND created_at < $4
+             ),
+             first_rewards AS (
+                 SELECT user_id,
+                        date_trunc($1, MIN(created_at) AT TIME ZONE 'UTC') AT TIME ZONE 'UTC' AS first_bucket
+                 FROM user_events
+                 WHERE event_type = 'reward_earned' AND user_id IN (SELECT user_id FROM rewards)
+                 GROUP BY user_id
+             ),
+             by_type AS (
+                 SELECT bucket_start, jsonb_object_agg(reward_type, total) AS by_type
+                 FROM (SELECT bucket_start, reward_type, SUM(amount) AS total
+                       FROM rewards GROUP BY bucket_start, reward_type) totals
+                 GROUP BY bucket_start
+             ),
+             buckets AS (
+                 SELECT r.bucket_start, SUM(r.amount) AS total_distributed,
+                        COUNT(DISTINCT r.user_id) AS unique_recipients,
+                        COUNT(DISTINCT r.user_id) FILTER (WHERE f.first_bucket = r.bucket_start)
+                            AS new_users_rewarded
+                 FROM rewards r
+                 JOIN first_rewards f ON f.user_id = r.user_id
+                 GROUP BY r.bucket_start
+             ),
+             windowed AS (
+                 SELECT *,
+                        SUM(total_distributed) OVER (
+                            ORDER BY bucket_start RANGE BETWEEN $5::interval PRECEDING AND CURRENT ROW
+                        ) / 7 AS rolling_7d_avg
+                 FROM buckets
+             )
+             SELECT w.bucket_start, w.total_distributed, t.by_type, w.unique_recipients, w.new_users_rewarded,
+                    COALESCE(100 * w.total_distributed / NULLIF(pool.size, 0), 0) AS pool_utilization_pct,
+                    w.rolling_7d_avg
+             FROM windowed w
+             JOIN by_type t ON t.bucket_start = w.bucket_start
+             -- The pool of each recorded day the bucket overlaps, in proportion to the overlap
+             CROSS JOIN LATERAL (
+                 SELECT SUM(p.pool_size * EXTRACT(EPOCH FROM
+                            LEAST(w.bucket_start + $6::interval, day_start + INTERVAL '1 day')
+                            - GREATEST(w.bucket_start, day_start)) / 86400)::float8 AS size
+                 FROM reward_pool_utilization p,
+                      LATERAL (SELECT p.day::timestamp AT TIME ZONE 'UTC' AS day_start) d
+                 WHERE day_start < w.bucket_start + $6::interval AND day_start + INTERVAL '1 day' > w.bucket_start
+             ) pool
+             WHERE w.bucket_start >= date_trunc($1, $3 AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+             ORDER BY w.bucket_start",
+        )
+        .bind(granularity.as_str())
+        .bind(user_id)
+        .bind(since)
+        .bind(until)
+        .bind(interval(Duration::days(7) - granularity.width()))
+        .bind(interval(granularity.width()))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(AnalyticsBucket::from).collect())
+    }
+}
+
+/// Postgres interval literal of a whole number of seconds
+fn interval(duration: Duration) -> String {
+    format!("{} seconds", duration.num_seconds())
+}
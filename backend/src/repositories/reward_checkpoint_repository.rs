@@ -0,0 +1,83 @@
+use sqlx::types::Json;
+use sqlx::PgPool;
+
+use super::RepositoryError;
+use crate::services::rewards::EchoDropReward;
+
+/// Pending rewards written at shutdown, so the next run can resume paying them out
+pub struct RewardCheckpointRepository {
+    pool: PgPool,
+}
+
+impl RewardCheckpointRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Replace the checkpoint with `rewards`
+    pub async fn save(&self, rewards: &[EchoDropReward]) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM reward_checkpoints").execute(&mut *tx).await?;
+        for reward in rewards {
+            sqlx::query("INSERT INTO reward_checkpoints (reward_id, user_id, reward) VALUES ($1, $2, $3)")
+                .bind(&reward.id)
+                .bind(&reward.user_id)
+                .bind(Json(reward))
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// The rewards of the latest checkpoint, oldest first
+    pub async fn load(&self) -> Result<Vec<EchoDropReward>, RepositoryError> {
+        let rewards = sqlx::query_scalar::<_, Json<EchoDropReward>>(
+            "SELECT reward FROM reward_checkpoints ORDER BY (reward->>'timestamp')::timestamptz, reward_id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rewards.into_iter().map(|reward| reward.0).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::rewards::RewardType;
+    use crate::services::reward_service::QualityMetrics;
+    use crate::services::RewardService;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_pending_rewards_survive_a_restart(pool: PgPool) {
+        let mut service = RewardService::new(10_000.0);
+        let quality = |echo_index_improvement| QualityMetrics {
+            echo_index_improvement,
+            viral_coefficient: 1.0,
+            engagement_rate: 0.5,
+            retention_rate: 0.5,
+            social_impact_score: 0.5,
+        };
+        service.award_quality_bonus("user_1".to_string(), "content_1".to_string(), quality(0.5)).await.unwrap();
+        service.award_quality_bonus("user_2".to_string(), "content_2".to_string(), quality(0.2)).await.unwrap();
+        let pending = service.pending_rewards();
+        assert_eq!(pending.len(), 2);
+
+        let repository = RewardCheckpointRepository::new(pool);
+        repository.save(&pending[..1]).await.unwrap();
+        // A later checkpoint replaces the earlier one
+        repository.save(&pending).await.unwrap();
+
+        let restored = RewardService::new(10_000.0).with_restored_rewards(repository.load().await.unwrap());
+        assert_eq!(restored.pending_rewards().len(), 2);
+        for user_id in ["user_1", "user_2"] {
+            let rewards = |service: &RewardService| serde_json::to_value(service.get_user_rewards(user_id)).unwrap();
+            assert_eq!(rewards(&restored), rewards(&service));
+            assert_eq!(restored.get_user_total_rewards(user_id), service.get_user_total_rewards(user_id));
+        }
+        assert!(matches!(restored.get_user_rewards("user_1")[0].reward_type, RewardType::QualityBonus));
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::{Days, NaiveDate};
+use sqlx::PgPool;
+
+use super::RepositoryError;
+use crate::models::reward_pool::PoolUtilizationDay;
+
+const UTILIZATION_COLUMNS: &str =
+    "day, pool_size, utilized, COALESCE(utilized / NULLIF(pool_size, 0), 0) AS utilization, adjustment";
+
+/// Daily reward pool utilization
+pub struct RewardPoolRepository {
+    pool: PgPool,
+}
+
+impl RewardPoolRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store the utilization of `day`, replacing any recorded for it before
+    pub async fn record(
+        &self,
+        day: NaiveDate,
+        pool_size: f64,
+        utilized: f64,
+        adjustment: f64,
+    ) -> Result<PoolUtilizationDay, RepositoryError> {
+        let query = format!(
+            "INSERT INTO reward_pool_utilization (day, pool_size, utilized, adjustment)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (day) DO UPDATE
+                 SET pool_size = EXCLUDED.pool_size, utilized = EXCLUDED.utilized,
+                     adjustment = EXCLUDED.adjustment, recorded_at = NOW()
+             RETURNING {}",
+            UTILIZATION_COLUMNS
+        );
+        let record = sqlx::query_as::<_, PoolUtilizationDay>(&query)
+            .bind(day)
+            .bind(pool_size)
+            .bind(utilized)
+            .bind(adjustment)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(record)
+    }
+
+    /// Recorded days among the `days` days up to and including `until`, most recent first
+    pub async fn history(&self, until: NaiveDate, days: u32) -> Result<Vec<PoolUtilizationDay>, RepositoryError> {
+        let since = until.checked_sub_days(Days::new(days as u64)).unwrap_or(NaiveDate::MIN);
+        let query = format!(
+            "SELECT {} FROM reward_pool_utilization
+             WHERE day > $1 AND day <= $2
+             ORDER BY day DESC",
+            UTILIZATION_COLUMNS
+        );
+        let history = sqlx::query_as::<_, PoolUtilizationDay>(&query)
+            .bind(since)
+            .bind(until)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(history)
+    }
+}
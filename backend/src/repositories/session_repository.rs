@@ -0,0 +1,211 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use std::net::{IpAddr, Ipv4Addr};
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::session::Session;
+
+const SESSION_COLUMNS: &str = "id, user_id, jti, device_fingerprint, host(ip_address) AS ip_address, user_agent,
+                               created_at, last_active_at, expires_at";
+
+pub struct NewSession<'a> {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub jti: &'a str,
+    pub device_fingerprint: &'a str,
+    pub ip_address: IpAddr,
+    pub user_agent: &'a str,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// `user_sessions` row; sqlx reads `INET` only with the `ipnetwork` feature, so the
+/// address is read as text
+#[derive(FromRow)]
+struct SessionRow {
+    id: Uuid,
+    user_id: Uuid,
+    jti: String,
+    device_fingerprint: String,
+    ip_address: String,
+    user_agent: String,
+    created_at: DateTime<Utc>,
+    last_active_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+impl From<SessionRow> for Session {
+    fn from(row: SessionRow) -> Self {
+        Self {
+            id: row.id,
+            user_id: row.user_id,
+            jti: row.jti,
+            device_fingerprint: row.device_fingerprint,
+            ip_address: row.ip_address.parse().unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+            user_agent: row.user_agent,
+            created_at: row.created_at,
+            last_active_at: row.last_active_at,
+            expires_at: row.expires_at,
+        }
+    }
+}
+
+pub struct SessionRepository {
+    pool: PgPool,
+}
+
+impl SessionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// `Conflict` if the user does not exist
+    pub async fn create(&self, session: &NewSession<'_>) -> Result<Session, RepositoryError> {
+        let row = sqlx::query_as::<_, SessionRow>(&format!(
+            "INSERT INTO user_sessions (id, user_id, jti, device_fingerprint, ip_address, user_agent, expires_at)
+             VALUES ($1, $2, $3, $4, $5::inet, $6, $7)
+             RETURNING {}",
+            SESSION_COLUMNS
+        ))
+        .bind(session.id)
+        .bind(session.user_id)
+        .bind(session.jti)
+        .bind(session.device_fingerprint)
+        .bind(session.ip_address.to_string())
+        .bind(session.user_agent)
+        .bind(session.expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    /// Record a request made in the session; false if it has expired or been revoked
+    pub async fn touch(&self, id: Uuid) -> Result<bool, RepositoryError> {
+        let result =
+            sqlx::query("UPDATE user_sessions SET last_active_at = NOW() WHERE id = $1 AND expires_at > NOW()")
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the access token just issued to the session from its refresh token
+    pub async fn rotate(&self, id: Uuid, jti: &str) -> Result<(), RepositoryError> {
+        sqlx::query("UPDATE user_sessions SET jti = $2, last_active_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(jti)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The user's unexpired sessions, most recently active first
+    pub async fn list_active(&self, user_id: Uuid) -> Result<Vec<Session>, RepositoryError> {
+        let rows = sqlx::query_as::<_, SessionRow>(&format!(
+            "SELECT {} FROM user_sessions
+             WHERE user_id = $1 AND expires_at > NOW()
+             ORDER BY last_active_at DESC, id",
+            SESSION_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Session::from).collect())
+    }
+
+    /// End one of the user's unexpired sessions, along with its refresh tokens; `NotFound`
+    /// if the user has no such session
+    pub async fn revoke(&self, user_id: Uuid, id: Uuid) -> Result<Session, RepositoryError> {
+        let mut revoked = self.delete(user_id, "id = $2", id).await?;
+        revoked.pop().ok_or(RepositoryError::NotFound)
+    }
+
+    /// End every unexpired session of the user except `current`, returning those ended
+    pub async fn revoke_others(&self, user_id: Uuid, current: Uuid) -> Result<Vec<Session>, RepositoryError> {
+        self.delete(user_id, "id <> $2", current).await
+    }
+
+    async fn delete(&self, user_id: Uuid, condition: &str, id: Uuid) -> Result<Vec<Session>, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        let rows = sqlx::query_as::<_, SessionRow>(&format!(
+            "DELETE FROM user_sessions
+             WHERE user_id = $1 AND {} AND expires_at > NOW()
+             RETURNING {}",
+            condition, SESSION_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let session_ids: Vec<Uuid> = rows.iter().map(|row| row.id).collect();
+        sqlx::query("DELETE FROM refresh_tokens WHERE session_id = ANY($1)")
+            .bind(&session_ids)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(rows.into_iter().map(Session::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::{RefreshTokenRepository, UserRepository};
+    use chrono::Duration;
+
+    async fn create_session(repository: &SessionRepository, user_id: Uuid, expires_in: Duration) -> Session {
+        repository
+            .create(&NewSession {
+                id: Uuid::new_v4(),
+                user_id,
+                jti: &Uuid::new_v4().to_string(),
+                device_fingerprint: "fingerprint",
+                ip_address: "2001:db8::1".parse().unwrap(),
+                user_agent: "Mozilla/5.0",
+                expires_at: Utc::now() + expires_in,
+            })
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_revoking_a_session_leaves_the_others(pool: PgPool) {
+        let repository = SessionRepository::new(pool.clone());
+        let refresh_tokens = RefreshTokenRepository::new(pool.clone());
+        let users = UserRepository::new(pool);
+        let user_id = users.find_or_create_by_wallet("0xsessions").await.unwrap().id;
+        let other_user = users.find_or_create_by_wallet("0xother").await.unwrap().id;
+
+        let laptop = create_session(&repository, user_id, Duration::days(30)).await;
+        let phone = create_session(&repository, user_id, Duration::days(30)).await;
+        let expired = create_session(&repository, user_id, Duration::seconds(-1)).await;
+        let elsewhere = create_session(&repository, other_user, Duration::days(30)).await;
+        assert_eq!(laptop.ip_address, "2001:db8::1".parse::<IpAddr>().unwrap());
+        for (token, session) in [("a", &laptop), ("b", &phone)] {
+            let expires_at = Utc::now() + Duration::days(30);
+            refresh_tokens.store(&token.repeat(64), user_id, session.id, false, expires_at).await.unwrap();
+        }
+
+        // Expired sessions are not listed; the most recently active comes first
+        assert!(repository.touch(laptop.id).await.unwrap());
+        assert!(!repository.touch(expired.id).await.unwrap());
+        let active: Vec<Uuid> = repository.list_active(user_id).await.unwrap().iter().map(|s| s.id).collect();
+        assert_eq!(active, [laptop.id, phone.id]);
+        assert!(matches!(repository.revoke(user_id, expired.id).await, Err(RepositoryError::NotFound)));
+        assert!(matches!(repository.revoke(user_id, elsewhere.id).await, Err(RepositoryError::NotFound)));
+
+        let revoked = repository.revoke(user_id, phone.id).await.unwrap();
+        assert_eq!(revoked.jti, phone.jti);
+        assert!(refresh_tokens.find_active(&"b".repeat(64)).await.unwrap().is_none());
+        assert!(refresh_tokens.find_active(&"a".repeat(64)).await.unwrap().is_some());
+        let active: Vec<Uuid> = repository.list_active(user_id).await.unwrap().iter().map(|s| s.id).collect();
+        assert_eq!(active, [laptop.id]);
+        assert_eq!(repository.list_active(other_user).await.unwrap().len(), 1);
+    }
+}
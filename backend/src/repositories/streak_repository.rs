@@ -0,0 +1,74 @@
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::user_streak::UserStreak;
+
+const STREAK_COLUMNS: &str =
+    "user_id, current_streak_days, longest_streak_days, last_activity_date, streak_freeze_tokens";
+
+pub struct StreakRepository {
+    pool: PgPool,
+}
+
+impl StreakRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find(&self, user_id: Uuid) -> Result<Option<UserStreak>, RepositoryError> {
+        let query = format!("SELECT {} FROM user_streaks WHERE user_id = $1", STREAK_COLUMNS);
+        let streak = sqlx::query_as::<_, UserStreak>(&query)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(streak)
+    }
+
+    /// Apply `change` to a user's streak while holding its row lock, starting a new streak
+    /// on `today` if the user has none. A rejected change is reported as a conflict.
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        today: NaiveDate,
+        change: impl FnOnce(&mut UserStreak) -> Result<(), String>,
+    ) -> Result<UserStreak, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO user_streaks (user_id, last_activity_date) VALUES ($1, $2)
+             ON CONFLICT (user_id) DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(today)
+        .execute(&mut *tx)
+        .await?;
+
+        let query = format!("SELECT {} FROM user_streaks WHERE user_id = $1 FOR UPDATE", STREAK_COLUMNS);
+        let mut streak = sqlx::query_as::<_, UserStreak>(&query)
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        change(&mut streak).map_err(RepositoryError::Conflict)?;
+
+        sqlx::query(
+            "UPDATE user_streaks
+             SET current_streak_days = $2, longest_streak_days = $3, last_activity_date = $4,
+                 streak_freeze_tokens = $5, updated_at = NOW()
+             WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .bind(streak.current_streak_days as i32)
+        .bind(streak.longest_streak_days as i32)
+        .bind(streak.last_activity_date)
+        .bind(i16::from(streak.streak_freeze_tokens))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(streak)
+    }
+}
@@ -0,0 +1,150 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::content_repository::CONTENT_COLUMNS;
+use super::RepositoryError;
+use crate::models::content::ContentRecord;
+use crate::models::trending::TrendingScore;
+use crate::models::Platform;
+
+const TRENDING_COLUMNS: &str = "score, velocity, acceleration, peak_predicted_at";
+
+/// A live content item with its trending score
+#[derive(Debug, Clone, FromRow)]
+pub struct TrendingContent {
+    #[sqlx(flatten)]
+    pub content: ContentRecord,
+    #[sqlx(flatten)]
+    pub trending: TrendingScore,
+}
+
+pub struct TrendingRepository {
+    pool: PgPool,
+}
+
+impl TrendingRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Echo Index history since `since` of every live content item scored since then,
+    /// oldest first, each preceded by its last score before `since`
+    pub async fn recent_series(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<HashMap<Uuid, Vec<(DateTime<Utc>, f64)>>, RepositoryError> {
+        let rows: Vec<(Uuid, DateTime<Utc>, f64)> = sqlx::query_as(
+            "WITH recent AS (
+                 SELECT DISTINCT h.content_id FROM echo_index_history h
+                 JOIN content c ON c.id = h.content_id
+                 WHERE h.calculated_at >= $1 AND c.deleted_at IS NULL
+             )
+             SELECT h.content_id, h.calculated_at, h.score FROM echo_index_history h
+             JOIN recent r ON r.content_id = h.content_id
+             WHERE h.calculated_at >= $1
+             UNION ALL
+             SELECT baseline.content_id, baseline.calculated_at, baseline.score FROM recent r
+             CROSS JOIN LATERAL (
+                 SELECT content_id, calculated_at, score FROM echo_index_history
+                 WHERE content_id = r.content_id AND calculated_at < $1
+                 ORDER BY calculated_at DESC
+                 LIMIT 1
+             ) baseline
+             ORDER BY 1, 2",
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut series: HashMap<Uuid, Vec<(DateTime<Utc>, f64)>> = HashMap::new();
+        for (content_id, calculated_at, score) in rows {
+            series.entry(content_id).or_default().push((calculated_at, score));
+        }
+        Ok(series)
+    }
+
+    /// When each of the content items was last propagated, for those propagated at all
+    pub async fn last_propagations(
+        &self,
+        content_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, DateTime<Utc>>, RepositoryError> {
+        let rows: Vec<(Uuid, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT content_id, MAX(created_at) FROM propagations
+             WHERE content_id = ANY($1)
+             GROUP BY content_id",
+        )
+        .bind(content_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Replace every stored trending score with `scores`
+    pub async fn replace(&self, scores: &[(Uuid, TrendingScore)]) -> Result<(), RepositoryError> {
+        let content_ids: Vec<Uuid> = scores.iter().map(|(content_id, _)| *content_id).collect();
+        let values: Vec<f64> = scores.iter().map(|(_, trending)| trending.score).collect();
+        let velocities: Vec<f64> = scores.iter().map(|(_, trending)| trending.velocity).collect();
+        let accelerations: Vec<f64> = scores.iter().map(|(_, trending)| trending.acceleration).collect();
+        let peaks: Vec<Option<DateTime<Utc>>> = scores.iter().map(|(_, trending)| trending.peak_predicted_at).collect();
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM content_trending_scores").execute(&mut *tx).await?;
+        // Content deleted since its history was read is skipped
+        sqlx::query(
+            "INSERT INTO content_trending_scores (content_id, score, velocity, acceleration, peak_predicted_at)
+             SELECT s.content_id, s.score, s.velocity, s.acceleration, s.peak_predicted_at
+             FROM UNNEST($1::uuid[], $2::float8[], $3::float8[], $4::float8[], $5::timestamptz[])
+                 AS s(content_id, score, velocity, acceleration, peak_predicted_at)
+             JOIN content c ON c.id = s.content_id",
+        )
+        .bind(&content_ids)
+        .bind(&values)
+        .bind(&velocities)
+        .bind(&accelerations)
+        .bind(&peaks)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Trending score of a content item, None when it has not changed lately
+    pub async fn find(&self, content_id: Uuid) -> Result<Option<TrendingScore>, RepositoryError> {
+        let trending = sqlx::query_as::<_, TrendingScore>(&format!(
+            "SELECT {} FROM content_trending_scores WHERE content_id = $1",
+            TRENDING_COLUMNS
+        ))
+        .bind(content_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(trending)
+    }
+
+    /// Live content gaining Echo Index fastest, optionally on one platform
+    pub async fn list_by_velocity(
+        &self,
+        platform: Option<&Platform>,
+        limit: u32,
+    ) -> Result<Vec<TrendingContent>, RepositoryError> {
+        let content = sqlx::query_as::<_, TrendingContent>(&format!(
+            "SELECT {}, {} FROM content
+             JOIN content_trending_scores t ON t.content_id = content.id
+             WHERE content.deleted_at IS NULL AND t.velocity > 0
+               AND ($1::text IS NULL OR content.platform::text = $1)
+             ORDER BY t.velocity DESC, content.id ASC
+             LIMIT $2",
+            CONTENT_COLUMNS, TRENDING_COLUMNS
+        ))
+        .bind(platform.map(Platform::as_str))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(content)
+    }
+}
@@ -0,0 +1,105 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::RepositoryError;
+
+/// TOTP secrets and recovery codes of users who sign in with a second factor
+pub struct TwoFactorRepository {
+    pool: PgPool,
+}
+
+impl TwoFactorRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Replace the user's encrypted TOTP secret. `Conflict` once 2FA is enabled, so a
+    /// stolen session cannot swap out the secret of an enabled account.
+    pub async fn store_secret(&self, user_id: Uuid, encrypted_secret: &str) -> Result<(), RepositoryError> {
+        let stored = sqlx::query(
+            "UPDATE users SET totp_secret = $2, updated_at = NOW() WHERE id = $1 AND NOT totp_enabled",
+        )
+        .bind(user_id)
+        .bind(encrypted_secret)
+        .execute(&self.pool)
+        .await?;
+        if stored.rows_affected() > 0 {
+            return Ok(());
+        }
+
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM users WHERE id = $1)")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await?;
+        if exists {
+            Err(RepositoryError::Conflict("two-factor authentication is already enabled".to_string()))
+        } else {
+            Err(RepositoryError::NotFound)
+        }
+    }
+
+    /// Require the stored secret at sign-in, and replace the user's recovery codes
+    pub async fn enable(&self, user_id: Uuid, recovery_code_hashes: &[String]) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let enabled = sqlx::query(
+            "UPDATE users SET totp_enabled = TRUE, updated_at = NOW()
+             WHERE id = $1 AND totp_secret IS NOT NULL AND NOT totp_enabled",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        if enabled.rows_affected() == 0 {
+            return Err(RepositoryError::Conflict("two-factor authentication is already enabled".to_string()));
+        }
+        replace_recovery_codes(&mut tx, user_id, recovery_code_hashes).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Invalidate all of the user's recovery codes, used or not, in favour of new ones
+    pub async fn replace_recovery_codes(
+        &self,
+        user_id: Uuid,
+        recovery_code_hashes: &[String],
+    ) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        replace_recovery_codes(&mut tx, user_id, recovery_code_hashes).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Mark the unused recovery code with this hash used. False if there is none.
+    pub async fn consume_recovery_code(&self, user_id: Uuid, code_hash: &str) -> Result<bool, RepositoryError> {
+        let consumed = sqlx::query(
+            "UPDATE totp_recovery_codes SET used_at = NOW()
+             WHERE id = (SELECT id FROM totp_recovery_codes
+                         WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+                         LIMIT 1 FOR UPDATE)",
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(consumed.rows_affected() > 0)
+    }
+}
+
+async fn replace_recovery_codes(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: Uuid,
+    recovery_code_hashes: &[String],
+) -> Result<(), RepositoryError> {
+    sqlx::query("DELETE FROM totp_recovery_codes WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await?;
+    sqlx::query("INSERT INTO totp_recovery_codes (user_id, code_hash) SELECT $1, UNNEST($2::text[])")
+        .bind(user_id)
+        .bind(recovery_code_hashes)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
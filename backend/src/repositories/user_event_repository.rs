@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::pagination::{Cursor, Page};
+use crate::models::user_event::{UserEvent, UserEventRecord};
+
+#[derive(FromRow)]
+struct UserEventRow {
+    id: Uuid,
+    user_id: Uuid,
+    payload: Json<UserEvent>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<UserEventRow> for UserEventRecord {
+    fn from(row: UserEventRow) -> Self {
+        Self {
+            id: row.id,
+            user_id: row.user_id,
+            created_at: row.created_at,
+            event: row.payload.0,
+        }
+    }
+}
+
+pub struct UserEventRepository {
+    pool: PgPool,
+}
+
+impl UserEventRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Append an event to a user's timeline
+    pub async fn record(&self, user_id: Uuid, event: &UserEvent) -> Result<UserEventRecord, RepositoryError> {
+        let row = sqlx::query_as::<_, UserEventRow>(
+            "INSERT INTO user_events (user_id, event_type, payload)
+             VALUES ($1, $2, $3)
+             RETURNING id, user_id, payload, created_at",
+        )
+        .bind(user_id)
+        .bind(event.event_type())
+        .bind(Json(event))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    /// All of a user's events, oldest first
+    pub async fn all(&self, user_id: Uuid) -> Result<Vec<UserEventRecord>, RepositoryError> {
+        let rows = sqlx::query_as::<_, UserEventRow>(
+            "SELECT id, user_id, payload, created_at FROM user_events
+             WHERE user_id = $1
+             ORDER BY created_at, id",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(UserEventRecord::from).collect())
+    }
+
+    /// A user's events newest first using keyset pagination on `(created_at, id)`
+    pub async fn timeline(
+        &self,
+        user_id: Uuid,
+        after: Option<Cursor>,
+        limit: u32,
+    ) -> Result<Page<UserEventRecord>, RepositoryError> {
+        // Fetch one extra row to learn whether another page exists
+        let rows = sqlx::query_as::<_, UserEventRow>(
+            "SELECT id, user_id, payload, created_at FROM user_events
+             WHERE user_id = $1
+               AND ($2::timestamptz IS NULL OR (created_at, id) < ($2, $3))
+             ORDER BY created_at DESC, id DESC
+             LIMIT $4",
+        )
+        .bind(user_id)
+        .bind(after.map(|cursor| cursor.created_at))
+        .bind(after.map(|cursor| cursor.id))
+        .bind(limit as i64 + 1)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let page = Page::from_rows(rows, limit as usize, |row| Cursor::new(row.created_at, row.id));
+        Ok(page.map(UserEventRecord::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Platform;
+    use crate::services::rewards::RewardType;
+
+    async fn insert_user(pool: &PgPool) -> Uuid {
+        sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+            .bind("0xtimeline")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_timeline_newest_first_with_cursor(pool: PgPool) {
+        let repository = UserEventRepository::new(pool.clone());
+        let user_id = insert_user(&pool).await;
+        let content_id = Uuid::new_v4();
+
+        let events = [
+            UserEvent::ContentCreated { content_id, platform: Platform::Twitter, initial_echo: 0.0 },
+            UserEvent::PropagationMade { content_id, target_platform: Platform::Farcaster, echo_boost: 1.25 },
+            UserEvent::RewardEarned {
+                amount: 12.5,
+                reward_type: RewardType::PropagationBonus,
+                content_id: content_id.to_string(),
+            },
+            UserEvent::EchoIndexMilestone { score: 52.0, milestone_type: "resonant".to_string() },
+            UserEvent::TierChanged { old_tier: "Basic".to_string(), new_tier: "Bronze".to_string() },
+        ];
+        for (recorded, event) in events.iter().enumerate() {
+            repository.record(user_id, event).await.unwrap();
+            let timeline = repository.timeline(user_id, None, 50).await.unwrap();
+            assert_eq!(timeline.data.len(), recorded + 1);
+            assert_eq!(timeline.data[0].event.event_type(), event.event_type());
+        }
+
+        let first = repository.timeline(user_id, None, 3).await.unwrap();
+        assert!(first.has_more);
+        let cursor = Cursor::decode(first.next_cursor.as_deref().unwrap()).unwrap();
+        let rest = repository.timeline(user_id, Some(cursor), 3).await.unwrap();
+        assert!(!rest.has_more);
+
+        let types: Vec<_> = first.data.iter().chain(&rest.data).map(|r| r.event.event_type()).collect();
+        assert_eq!(
+            types,
+            ["tier_changed", "echo_index_milestone", "reward_earned", "propagation_made", "content_created"]
+        );
+
+        let other_user = repository.timeline(Uuid::new_v4(), None, 50).await.unwrap();
+        assert!(other_user.data.is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::user_repository::USER_COLUMNS;
+use super::RepositoryError;
+use crate::models::pagination::{Cursor, Page};
+use crate::models::user::{FollowedUser, UserRelationship};
+
+/// Who follows whom within EchoLayer
+pub struct UserRelationshipRepository {
+    pool: PgPool,
+}
+
+impl UserRelationshipRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Make `follower_id` follow `followed_id`. Following a user again keeps the original
+    /// follow; `Conflict` if either user does not exist.
+    pub async fn follow(&self, follower_id: Uuid, followed_id: Uuid) -> Result<UserRelationship, RepositoryError> {
+        let relationship = sqlx::query_as::<_, UserRelationship>(
+            "INSERT INTO user_relationships (follower_id, followed_id) VALUES ($1, $2)
+             ON CONFLICT (follower_id, followed_id) DO UPDATE SET follower_id = EXCLUDED.follower_id
+             RETURNING follower_id, followed_id, created_at",
+        )
+        .bind(follower_id)
+        .bind(followed_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(relationship)
+    }
+
+    /// Stop `follower_id` following `followed_id`. Returns whether they were following.
+    pub async fn unfollow(&self, follower_id: Uuid, followed_id: Uuid) -> Result<bool, RepositoryError> {
+        let result = sqlx::query("DELETE FROM user_relationships WHERE follower_id = $1 AND followed_id = $2")
+            .bind(follower_id)
+            .bind(followed_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Whether `follower_id` follows `followed_id`
+    pub async fn follows(&self, follower_id: Uuid, followed_id: Uuid) -> Result<bool, RepositoryError> {
+        let follows = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM user_relationships WHERE follower_id = $1 AND followed_id = $2)",
+        )
+        .bind(follower_id)
+        .bind(followed_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(follows)
+    }
+
+    /// Users following `user_id`, most recent follow first
+    pub async fn followers(
+        &self,
+        user_id: Uuid,
+        after: Option<Cursor>,
+        limit: u32,
+    ) -> Result<Page<FollowedUser>, RepositoryError> {
+        self.page("followed_id", "follower_id", user_id, after, limit).await
+    }
+
+    /// Users `user_id` follows, most recent follow first
+    pub async fn following(
+        &self,
+        user_id: Uuid,
+        after: Option<Cursor>,
+        limit: u32,
+    ) -> Result<Page<FollowedUser>, RepositoryError> {
+        self.page("follower_id", "followed_id", user_id, after, limit).await
+    }
+
+    /// Users on the `listed` side of the relationships whose `owner` is `user_id`. A page
+    /// continues after the `(followed_at, id)` position of the previous page's last user.
+    async fn page(
+        &self,
+        owner: &str,
+        listed: &str,
+        user_id: Uuid,
+        after: Option<Cursor>,
+        limit: u32,
+    ) -> Result<Page<FollowedUser>, RepositoryError> {
+        let query = format!(
+            "SELECT {}, r.followed_at FROM users
+             JOIN (SELECT {} AS user_id, created_at AS followed_at FROM user_relationships
+                   WHERE {} = $1) r ON r.user_id = users.id
+             WHERE $2::timestamptz IS NULL OR (r.followed_at, users.id) < ($2, $3)
+             ORDER BY r.followed_at DESC, users.id DESC
+             LIMIT $4",
+            USER_COLUMNS, listed, owner
+        );
+        let rows = sqlx::query_as::<_, FollowedUser>(&query)
+            .bind(user_id)
+            .bind(after.map(|cursor| cursor.created_at))
+            .bind(after.map(|cursor| cursor.id))
+            .bind(limit as i64 + 1)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(Page::from_rows(rows, limit as usize, |row| Cursor::new(row.followed_at, row.user.id)))
+    }
+}
@@ -0,0 +1,455 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::RepositoryError;
+use super::influence_repository::INFLUENCE_RANKS;
+use crate::models::user::{LeaderboardEntry, Role, SocialAccount, User, UserTier};
+use crate::models::wallet_address::EthereumAddress;
+use crate::models::Platform;
+
+/// Columns of `users` projected onto `User`. The rank counts active users with a
+/// strictly higher score, so ties share a position.
+pub(crate) const USER_COLUMNS: &str = "
+    id, wallet_address, username, display_name, bio, avatar_url,
+    echo_score::float8 AS echo_score, COALESCE(total_rewards, 0)::float8 AS total_rewards,
+    (SELECT COUNT(*) FROM users ahead
+     WHERE ahead.is_active IS NOT FALSE AND ahead.echo_score > users.echo_score)::int + 1 AS rank,
+    COALESCE(is_verified, FALSE) AS is_verified, role, tier, totp_secret, totp_enabled,
+    (SELECT COUNT(*) FROM user_relationships WHERE followed_id = users.id)::int AS followers_count,
+    (SELECT COUNT(*) FROM user_relationships WHERE follower_id = users.id)::int AS following_count,
+    created_at, updated_at";
+
+/// Length of an anonymized username, which must fit `users.username`
+const USERNAME_PSEUDONYM_LENGTH: usize = 32;
+/// Tables of credentials, sessions and integrations that go when a user is anonymized
+const ANONYMIZED_USER_TABLES: [&str; 8] = [
+    "social_platforms",
+    "totp_recovery_codes",
+    "oauth_states",
+    "refresh_tokens",
+    "user_sessions",
+    "webhooks",
+    "alert_configs",
+    "user_feeds",
+];
+
+/// Profile fields to change; `None` leaves a field as it is
+#[derive(Debug, Clone, Default)]
+pub struct UserPatch {
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+pub struct UserRepository {
+    pool: PgPool,
+}
+
+impl UserRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Register a wallet. Fails with `Conflict` if the wallet or username is taken.
+    pub async fn create(
+        &self,
+        wallet_address: &str,
+        username: Option<&str>,
+        display_name: Option<&str>,
+    ) -> Result<User, RepositoryError> {
+        let query = format!(
+            "INSERT INTO users (wallet_address, username, display_name) VALUES ($1, $2, $3) RETURNING {}",
+            USER_COLUMNS
+        );
+        let user = sqlx::query_as::<_, User>(&query)
+            .bind(wallet_address)
+            .bind(username)
+            .bind(display_name)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(user)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, RepositoryError> {
+        let query = format!("SELECT {} FROM users WHERE id = $1", USER_COLUMNS);
+        let user = sqlx::query_as::<_, User>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(user)
+    }
+
+    /// Users signed in with any of the Ethereum addresses. Wallets are stored as entered,
+    /// so both the lowercase and the EIP-55 forms are looked up.
+    pub async fn find_ids_by_ethereum_addresses(
+        &self,
+        addresses: &[EthereumAddress],
+    ) -> Result<HashMap<EthereumAddress, Uuid>, RepositoryError> {
+        let forms: Vec<String> = addresses
+            .iter()
+            .flat_map(|address| [format!("0x{}", hex::encode(address.as_bytes())), address.to_checksum()])
+            .collect();
+        let rows: Vec<(String, Uuid)> =
+            sqlx::query_as("SELECT wallet_address, id FROM users WHERE wallet_address = ANY($1)")
+                .bind(&forms)
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(wallet, id)| EthereumAddress::parse(&wallet).ok().map(|address| (address, id)))
+            .collect())
+    }
+
+    /// When each of the users' accounts was created; unknown users are left out
+    pub async fn account_created_at(&self, ids: &[Uuid]) -> Result<HashMap<Uuid, DateTime<Utc>>, RepositoryError> {
+        let rows: Vec<(Uuid, DateTime<Utc>)> = sqlx::query_as("SELECT id, created_at FROM users WHERE id = ANY($1)")
+            .bind(ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// The user owning `wallet_address`, registering the wallet as a new `User` on first sign-in
+    pub async fn find_or_create_by_wallet(&self, wallet_address: &str) -> Result<User, RepositoryError> {
+        let query = format!(
+            "INSERT INTO users (wallet_address) VALUES ($1)
+             ON CONFLICT (wallet_address) DO UPDATE SET updated_at = NOW()
+             RETURNING {}",
+            USER_COLUMNS
+        );
+        let user = sqlx::query_as::<_, User>(&query)
+            .bind(wallet_address)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(user)
+    }
+
+    /// Apply a profile patch, returning the updated user
+    pub async fn update(&self, id: Uuid, patch: &UserPatch) -> Result<User, RepositoryError> {
+        let query = format!(
+            "UPDATE users
+             SET username = COALESCE($2, username), display_name = COALESCE($3, display_name),
+                 bio = COALESCE($4, bio), avatar_url = COALESCE($5, avatar_url), updated_at = NOW()
+             WHERE id = $1
+             RETURNING {}",
+            USER_COLUMNS
+        );
+        let user = sqlx::query_as::<_, User>(&query)
+            .bind(id)
+            .bind(&patch.username)
+            .bind(&patch.display_name)
+            .bind(&patch.bio)
+            .bind(&patch.avatar_url)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(user)
+    }
+
+    /// Set the user's Echo Score to the average Echo Index (0-100 scale) of their scored,
+    /// live content, returning the updated user
+    pub async fn update_echo_score(&self, id: Uuid) -> Result<User, RepositoryError> {
+        let query = format!(
+            "UPDATE users
+             SET echo_score = (SELECT COALESCE(AVG(echo_index) * 100, 0) FROM content
+                               WHERE user_id = users.id AND deleted_at IS NULL AND echo_index IS NOT NULL),
+                 updated_at = NOW()
+             WHERE id = $1
+             RETURNING {}",
+            USER_COLUMNS
+        );
+        let user = sqlx::query_as::<_, User>(&query).bind(id).fetch_optional(&self.pool).await?;
+
+        user.ok_or(RepositoryError::NotFound)
+    }
+
+    /// Add to the rewards the user has earned, returning the updated user
+    pub async fn add_rewards(&self, id: Uuid, amount: f64) -> Result<User, RepositoryError> {
+        let query = format!(
+            "UPDATE users SET total_rewards = COALESCE(total_rewards, 0) + $2, updated_at = NOW()
+             WHERE id = $1
+             RETURNING {}",
+            USER_COLUMNS
+        );
+        let user = sqlx::query_as::<_, User>(&query)
+            .bind(id)
+            .bind(amount)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        user.ok_or(RepositoryError::NotFound)
+    }
+
+    /// Move the user up to `tier`, returning when; None if they already are in it or above
+    pub async fn upgrade_tier(&self, id: Uuid, tier: UserTier) -> Result<Option<DateTime<Utc>>, RepositoryError> {
+        let upgraded_at = sqlx::query_scalar(
+            "UPDATE users SET tier = $2, updated_at = NOW() WHERE id = $1 AND tier < $2 RETURNING updated_at",
+        )
+        .bind(id)
+        .bind(tier)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(upgraded_at)
+    }
+
+    /// Active users by Echo Score, highest first, with their influence rank. Ties are broken
+    /// by id so pages are stable; a page continues after the `(echo_score, id)` position of
+    /// the previous page's last user.
+    pub async fn leaderboard(
+        &self,
+        limit: u32,
+        after_echo_score: Option<f64>,
+        after_id: Option<Uuid>,
+    ) -> Result<Vec<LeaderboardEntry>, RepositoryError> {
+        let query = format!(
+            "SELECT {}, influence.influence_rank FROM users
+             LEFT JOIN ({}) influence ON influence.user_id = users.id
+             WHERE is_active IS NOT FALSE
+               AND ($2::float8 IS NULL OR $3::uuid IS NULL
+                    OR echo_score < $2::numeric OR (echo_score = $2::numeric AND id > $3))
+             ORDER BY echo_score DESC, id ASC
+             LIMIT $1",
+            USER_COLUMNS, INFLUENCE_RANKS
+        );
+        let users = sqlx::query_as::<_, LeaderboardEntry>(&query)
+            .bind(limit as i64)
+            .bind(after_echo_score)
+            .bind(after_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(users)
+    }
+
+    /// The user's connected social platform accounts, oldest first
+    pub async fn social_accounts(&self, user_id: Uuid) -> Result<Vec<SocialAccount>, RepositoryError> {
+        let accounts = sqlx::query_as::<_, SocialAccount>(
+            "SELECT id, user_id, platform::text AS platform, platform_user_id AS account_id,
+                    COALESCE(platform_username, '') AS username,
+                    COALESCE(verification_status = 'verified', FALSE) AS verified, created_at
+             FROM social_platforms WHERE user_id = $1
+             ORDER BY created_at, id",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(accounts)
+    }
+
+    /// Mark the user's account on `platform` as verified, connecting it if it was not.
+    /// `Conflict` if another user already verified the same account.
+    pub async fn verify_social_account(
+        &self,
+        user_id: Uuid,
+        platform: &Platform,
+        account_id: &str,
+        username: &str,
+    ) -> Result<SocialAccount, RepositoryError> {
+        let claimed: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM social_platforms
+                 WHERE platform::text = $1 AND platform_user_id = $2 AND user_id <> $3
+                   AND verification_status = 'verified'
+             )",
+        )
+        .bind(platform.as_str())
+        .bind(account_id)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+        if claimed {
+            return Err(RepositoryError::Conflict(format!(
+                "{} account {} is verified by another user",
+                platform, account_id
+            )));
+        }
+
+        let account = sqlx::query_as::<_, SocialAccount>(
+            "INSERT INTO social_platforms (user_id, platform, platform_user_id, platform_username, verification_status)
+             VALUES ($1, $2::platform_type, $3, $4, 'verified')
+             ON CONFLICT (user_id, platform) DO UPDATE
+             SET platform_user_id = $3, platform_username = $4, verification_status = 'verified',
+                 is_connected = TRUE, updated_at = NOW()
+             RETURNING id, user_id, platform::text AS platform, platform_user_id AS account_id,
+                       COALESCE(platform_username, '') AS username, TRUE AS verified, created_at",
+        )
+        .bind(user_id)
+        .bind(platform.as_str())
+        .bind(account_id)
+        .bind(username)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(account)
+    }
+
+    /// Record a data export at `now` unless the user already had one after `since`.
+    /// Returns whether the export was recorded.
+    pub async fn claim_data_export(
+        &self,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+        since: DateTime<Utc>,
+    ) -> Result<bool, RepositoryError> {
+        let claimed = sqlx::query(
+            "INSERT INTO user_data_exports (user_id, exported_at) VALUES ($1, $2)
+             ON CONFLICT (user_id) DO UPDATE SET exported_at = $2
+             WHERE user_data_exports.exported_at <= $3
+             RETURNING user_id",
+        )
+        .bind(user_id)
+        .bind(now)
+        .bind(since)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(claimed.is_some())
+    }
+
+    /// Replace the user's wallet address, username and email with pseudonyms, clear the
+    /// rest of their profile and drop their credentials, sessions and integrations. Their
+    /// content and its Echo Index history stay. `NotFound` if the user does not exist or
+    /// was already anonymized.
+    pub async fn anonymize(&self, user_id: Uuid, pseudonym: impl Fn(&str) -> String) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(row) = sqlx::query(
+            "SELECT wallet_address, username, email FROM users WHERE id = $1 AND anonymized_at IS NULL FOR UPDATE",
+        )
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Err(RepositoryError::NotFound);
+        };
+        let wallet_address: String = row.try_get("wallet_address")?;
+        let username: Option<String> = row.try_get("username")?;
+        let email: Option<String> = row.try_get("email")?;
+
+        sqlx::query(
+            "UPDATE users
+             SET wallet_address = $2, username = $3, email = $4, display_name = NULL, bio = NULL,
+                 avatar_url = NULL, preferences = '{}', metadata = '{}', is_active = FALSE,
+                 totp_secret = NULL, totp_enabled = FALSE, anonymized_at = NOW(), updated_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(user_id)
+        .bind(pseudonym(&wallet_address))
+        .bind(username.map(|username| pseudonym(&username).chars().take(USERNAME_PSEUDONYM_LENGTH).collect::<String>()))
+        .bind(email.map(|email| pseudonym(&email)))
+        .execute(&mut *tx)
+        .await?;
+
+        for table in ANONYMIZED_USER_TABLES {
+            sqlx::query(&format!("DELETE FROM {} WHERE user_id = $1", table))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        sqlx::query("UPDATE api_keys SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn set_role(&self, user_id: Uuid, role: Role) -> Result<(), RepositoryError> {
+        let result = sqlx::query("UPDATE users SET role = $2 WHERE id = $1")
+            .bind(user_id)
+            .bind(role)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::influence::InfluenceScore;
+    use crate::repositories::InfluenceRepository;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_new_wallets_default_to_user_role(pool: PgPool) {
+        let repository = UserRepository::new(pool);
+
+        let user = repository.find_or_create_by_wallet("0xnewcomer").await.unwrap();
+        assert_eq!(user.role, Role::User);
+
+        repository.set_role(user.id, Role::Admin).await.unwrap();
+        let signed_in = repository.find_or_create_by_wallet("0xnewcomer").await.unwrap();
+        assert_eq!((signed_in.id, signed_in.role), (user.id, Role::Admin));
+
+        let missing = repository.set_role(Uuid::new_v4(), Role::Admin).await;
+        assert!(matches!(missing, Err(RepositoryError::NotFound)));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_create_rejects_taken_wallet_and_patches_profile(pool: PgPool) {
+        let repository = UserRepository::new(pool);
+
+        let user = repository.create("0xprofile", Some("echo_pioneer"), None).await.unwrap();
+        assert_eq!((user.username.as_deref(), user.echo_score, user.rank), (Some("echo_pioneer"), 0.0, 1));
+        assert!(matches!(repository.create("0xprofile", None, None).await, Err(RepositoryError::Conflict(_))));
+        assert_eq!(repository.find_by_id(user.id).await.unwrap(), Some(user.clone()));
+
+        let patch = UserPatch { display_name: Some("Echo Pioneer".to_string()), ..Default::default() };
+        let updated = repository.update(user.id, &patch).await.unwrap();
+        assert_eq!((updated.username, updated.display_name), (user.username, patch.display_name.clone()));
+        assert!(repository.find_by_id(Uuid::new_v4()).await.unwrap().is_none());
+        assert!(matches!(repository.update(Uuid::new_v4(), &patch).await, Err(RepositoryError::NotFound)));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_leaderboard_pages_are_stable_across_ties(pool: PgPool) {
+        let repository = UserRepository::new(pool.clone());
+        // Six users, four of them tied at 50
+        for (i, score) in [90.0, 50.0, 50.0, 50.0, 50.0, 10.0].into_iter().enumerate() {
+            sqlx::query("INSERT INTO users (wallet_address, echo_score) VALUES ($1, $2)")
+                .bind(format!("0xrank{}", i))
+                .bind(score)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        // Only the lowest scorer has had their influence scored
+        let influence = InfluenceRepository::new(pool.clone());
+        let lowest = repository.find_or_create_by_wallet("0xrank5").await.unwrap();
+        influence.record(lowest.id, &InfluenceScore::default(), 30, Utc::now()).await.unwrap();
+
+        let everyone = repository.leaderboard(10, None, None).await.unwrap();
+        let mut paged = Vec::new();
+        let mut after: Option<(f64, Uuid)> = None;
+        loop {
+            let page = repository.leaderboard(2, after.map(|a| a.0), after.map(|a| a.1)).await.unwrap();
+            let Some(last) = page.last() else { break };
+            after = Some((last.user.echo_score, last.user.id));
+            paged.extend(page);
+        }
+
+        assert_eq!(paged, everyone);
+        let ranks: Vec<i32> = everyone.iter().map(|entry| entry.user.rank).collect();
+        assert_eq!(ranks, vec![1, 2, 2, 2, 2, 6]);
+        assert!(everyone[1..5].windows(2).all(|pair| pair[0].user.id < pair[1].user.id));
+        let influence_ranks: Vec<Option<i32>> = everyone.iter().map(|entry| entry.influence_rank).collect();
+        assert_eq!(influence_ranks, vec![None, None, None, None, None, Some(1)]);
+    }
+}
@@ -0,0 +1,158 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::RepositoryError;
+use crate::models::webhook::{Webhook, WebhookDelivery, WebhookEvent};
+
+const WEBHOOK_COLUMNS: &str = "id, user_id, url, secret, events, active, created_at";
+const DELIVERY_COLUMNS: &str = "id, webhook_id, event, payload, attempt, status_code, error, succeeded, attempted_at";
+
+/// Outcome of one attempt at posting an event to a webhook
+pub struct NewDelivery<'a> {
+    pub webhook_id: Uuid,
+    pub event: WebhookEvent,
+    pub payload: &'a serde_json::Value,
+    pub attempt: i32,
+    pub status_code: Option<i32>,
+    pub error: Option<&'a str>,
+    pub succeeded: bool,
+}
+
+pub struct WebhookRepository {
+    pool: PgPool,
+}
+
+impl WebhookRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// `Conflict` if the user does not exist
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        url: &str,
+        secret: &str,
+        events: &[WebhookEvent],
+    ) -> Result<Webhook, RepositoryError> {
+        let webhook = sqlx::query_as::<_, Webhook>(&format!(
+            "INSERT INTO webhooks (user_id, url, secret, events) VALUES ($1, $2, $3, $4) RETURNING {}",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(url)
+        .bind(secret)
+        .bind(events)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(webhook)
+    }
+
+    /// A user's webhooks, oldest first
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<Webhook>, RepositoryError> {
+        let webhooks = sqlx::query_as::<_, Webhook>(&format!(
+            "SELECT {} FROM webhooks WHERE user_id = $1 ORDER BY created_at, id",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(webhooks)
+    }
+
+    /// `NotFound` unless the user has a webhook with this id
+    pub async fn find(&self, user_id: Uuid, webhook_id: Uuid) -> Result<Webhook, RepositoryError> {
+        let webhook = sqlx::query_as::<_, Webhook>(&format!(
+            "SELECT {} FROM webhooks WHERE id = $1 AND user_id = $2",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(webhook_id)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(webhook)
+    }
+
+    /// Delete a webhook and its delivery log. `NotFound` unless the user has a webhook
+    /// with this id.
+    pub async fn delete(&self, user_id: Uuid, webhook_id: Uuid) -> Result<(), RepositoryError> {
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = $1 AND user_id = $2")
+            .bind(webhook_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// The user's active webhooks subscribed to `event`
+    pub async fn subscribed(&self, user_id: Uuid, event: WebhookEvent) -> Result<Vec<Webhook>, RepositoryError> {
+        let webhooks = sqlx::query_as::<_, Webhook>(&format!(
+            "SELECT {} FROM webhooks WHERE user_id = $1 AND active AND $2 = ANY(events)",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(event)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(webhooks)
+    }
+
+    /// Active webhooks subscribed to `event` of the author of a content item
+    pub async fn subscribed_to_content(
+        &self,
+        content_id: Uuid,
+        event: WebhookEvent,
+    ) -> Result<Vec<Webhook>, RepositoryError> {
+        let webhooks = sqlx::query_as::<_, Webhook>(&format!(
+            "SELECT {} FROM webhooks
+             WHERE user_id = (SELECT user_id FROM content WHERE id = $1) AND active AND $2 = ANY(events)",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(content_id)
+        .bind(event)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(webhooks)
+    }
+
+    pub async fn record_delivery(&self, delivery: &NewDelivery<'_>) -> Result<(), RepositoryError> {
+        sqlx::query(
+            "INSERT INTO webhook_deliveries (webhook_id, event, payload, attempt, status_code, error, succeeded)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(delivery.webhook_id)
+        .bind(delivery.event)
+        .bind(delivery.payload)
+        .bind(delivery.attempt)
+        .bind(delivery.status_code)
+        .bind(delivery.error)
+        .bind(delivery.succeeded)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// A webhook's most recent delivery attempts, newest first
+    pub async fn deliveries(&self, webhook_id: Uuid, limit: i64) -> Result<Vec<WebhookDelivery>, RepositoryError> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(&format!(
+            "SELECT {} FROM webhook_deliveries WHERE webhook_id = $1 ORDER BY attempted_at DESC, id LIMIT $2",
+            DELIVERY_COLUMNS
+        ))
+        .bind(webhook_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+}
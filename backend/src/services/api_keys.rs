@@ -0,0 +1,203 @@
+use actix_web::http::Method;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::handlers::auth::Claims;
+use crate::models::api_key::{ApiKey, Permission};
+use crate::models::user::Role;
+use crate::repositories::{ApiKeyRepository, NewApiKey, RepositoryError};
+
+/// Marks a string as an EchoLayer API key, so a leaked one is easy to recognise
+pub const API_KEY_PREFIX: &str = "elk_";
+/// Requests per minute allowed when a key is created without its own limit
+pub const DEFAULT_API_KEY_RATE_LIMIT: u32 = 60;
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiKeyError {
+    #[error("Invalid API key")]
+    Invalid,
+    #[error("API key has expired")]
+    Expired,
+    #[error("API key does not permit this request")]
+    Forbidden,
+    #[error("API key rate limit exceeded")]
+    RateLimited(Duration),
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+/// Issues API keys and authenticates the requests made with them, each key limited to
+/// its own number of requests per minute
+pub struct ApiKeyService {
+    repository: Arc<ApiKeyRepository>,
+    /// Start of the current window and requests made in it, per key
+    windows: DashMap<Uuid, (Instant, u32)>,
+}
+
+impl ApiKeyService {
+    pub fn new(repository: Arc<ApiKeyRepository>) -> Self {
+        Self {
+            repository,
+            windows: DashMap::new(),
+        }
+    }
+
+    /// Create a key for a user, returning the raw key alongside what was stored. The raw
+    /// key cannot be recovered later.
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        permissions: &[Permission],
+        rate_limit_per_minute: u32,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(String, ApiKey), RepositoryError> {
+        let raw_key = generate_key();
+        let key = self
+            .repository
+            .create(&NewApiKey {
+                key_hash: &hash_key(&raw_key),
+                user_id,
+                name,
+                permissions,
+                rate_limit_per_minute,
+                expires_at,
+            })
+            .await?;
+
+        Ok((raw_key, key))
+    }
+
+    pub async fn revoke(&self, user_id: Uuid, key_id: Uuid) -> Result<(), RepositoryError> {
+        self.repository.revoke(user_id, key_id).await?;
+        self.windows.remove(&key_id);
+        Ok(())
+    }
+
+    /// The key a request was made with and claims for its owner. The claims carry the
+    /// owner's role only if the key has the `admin` permission.
+    #[tracing::instrument(skip(self, raw_key))]
+    pub async fn authenticate(&self, raw_key: &str, method: &Method) -> Result<(ApiKey, Claims), ApiKeyError> {
+        let owner = self
+            .repository
+            .find_by_hash(&hash_key(raw_key))
+            .await?
+            .ok_or(ApiKeyError::Invalid)?;
+        let key = owner.key;
+
+        let now = Utc::now();
+        if key.is_expired(now) {
+            return Err(ApiKeyError::Expired);
+        }
+        if !key.allows(method) {
+            return Err(ApiKeyError::Forbidden);
+        }
+        self.check_rate(key.id, key.rate_limit_per_minute).map_err(ApiKeyError::RateLimited)?;
+
+        if let Err(e) = self.repository.touch(key.id).await {
+            tracing::warn!(key_id = %key.id, error = %e, "Failed to record API key use");
+        }
+
+        let role = if key.has_permission(Permission::Admin) { owner.role } else { Role::User };
+        let claims = Claims {
+            sub: key.user_id.to_string(),
+            wallet: owner.wallet_address,
+            // Claims only live for the request they were built for
+            exp: (now + chrono::Duration::seconds(RATE_LIMIT_WINDOW.as_secs() as i64)).timestamp() as usize,
+            iat: now.timestamp() as usize,
+            jti: Uuid::new_v4().to_string(),
+            session_id: key.id.to_string(),
+            role,
+            // A key is not a second factor, so admin keys cannot reach admin endpoints
+            totp_verified: false,
+        };
+        Ok((key, claims))
+    }
+
+    /// Count a request against a key's window, or return how long until the window resets
+    fn check_rate(&self, key_id: Uuid, limit: u32) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut window = self.windows.entry(key_id).or_insert((now, 0));
+        let (started, count) = &mut *window;
+        if now.duration_since(*started) >= RATE_LIMIT_WINDOW {
+            *started = now;
+            *count = 0;
+        }
+        if *count >= limit {
+            return Err(RATE_LIMIT_WINDOW - now.duration_since(*started));
+        }
+        *count += 1;
+        Ok(())
+    }
+
+    /// Drop windows that have ended
+    pub fn evict_expired(&self) -> usize {
+        let before = self.windows.len();
+        let now = Instant::now();
+        self.windows.retain(|_, (started, _)| now.duration_since(*started) < RATE_LIMIT_WINDOW);
+        before - self.windows.len()
+    }
+
+    pub fn spawn_eviction_task(self: Arc<Self>, period: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let evicted = self.evict_expired();
+                if evicted > 0 {
+                    log::debug!("Evicted {} API key rate limit windows", evicted);
+                }
+            }
+        })
+    }
+}
+
+/// A random 256-bit key, hex encoded after the key prefix
+fn generate_key() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("{}{}", API_KEY_PREFIX, hex::encode(bytes))
+}
+
+/// SHA-256 of a raw key, the only form in which it is stored
+pub fn hash_key(raw_key: &str) -> String {
+    hex::encode(Sha256::digest(raw_key.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_rate_limit_is_per_key(pool: PgPool) {
+        let service = ApiKeyService::new(Arc::new(ApiKeyRepository::new(pool.clone())));
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xbot') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        let (limited, _) = service.create(user_id, "limited", &[Permission::Read], 2, None).await.unwrap();
+        let (other, _) = service.create(user_id, "other", &[Permission::Read], 2, None).await.unwrap();
+        assert!(limited.starts_with(API_KEY_PREFIX));
+
+        for _ in 0..2 {
+            service.authenticate(&limited, &Method::GET).await.unwrap();
+        }
+        assert!(matches!(
+            service.authenticate(&limited, &Method::GET).await,
+            Err(ApiKeyError::RateLimited(retry_after)) if retry_after <= RATE_LIMIT_WINDOW
+        ));
+        service.authenticate(&other, &Method::GET).await.unwrap();
+
+        // A read-only key cannot change anything
+        assert!(matches!(service.authenticate(&other, &Method::POST).await, Err(ApiKeyError::Forbidden)));
+    }
+}
@@ -0,0 +1,566 @@
+use chrono::Utc;
+use dashmap::DashMap;
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+use tokio::sync::{Notify, Semaphore};
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::content::{Content, EchoIndex};
+use crate::models::echo_index::EchoIndexCalculator;
+use crate::models::echo_index_history::{EchoIndexHistory, EchoIndexTrigger};
+use crate::models::user_event::UserEvent;
+use crate::models::webhook::WebhookEvent;
+use crate::repositories::{
+    ContentRepository, EchoIndexHistoryRepository, EchoIndexScores, ExperimentRepository, UserEventRepository,
+    UserRepository,
+};
+use crate::services::cold_start::data_sufficiency;
+use crate::services::{
+    BotDetector, ColdStartService, EchoIndexAnomalyDetector, EchoIndexCache, EchoIndexComponents, EchoIndexUpdates,
+    EchoService, EngineConfigStore, HashtagTrendService, MetricsRegistry, PropagationService,
+    PropagationWeightNormalizer, UserTierProgressionService, WebhookDispatcher,
+};
+
+/// Content calculated more recently than this is skipped unless the job is forced
+const FRESHNESS_WINDOW_MINUTES: i64 = 60;
+
+/// Lifecycle of a batch recalculation job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[schema(example = "running")]
+pub enum JobState {
+    Running,
+    Done,
+    Failed,
+}
+
+/// Progress of a batch recalculation job
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobStatus {
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub status: JobState,
+    #[serde(skip)]
+    finished_at: Option<Instant>,
+}
+
+impl JobStatus {
+    fn new(total: usize) -> Self {
+        let mut status = Self {
+            total,
+            completed: 0,
+            failed: 0,
+            status: JobState::Running,
+            finished_at: None,
+        };
+        status.settle();
+        status
+    }
+
+    /// Mark the job finished once every item has been accounted for
+    fn settle(&mut self) {
+        if self.completed + self.failed < self.total {
+            return;
+        }
+        self.status = if self.failed > 0 { JobState::Failed } else { JobState::Done };
+        self.finished_at = Some(Instant::now());
+    }
+}
+
+/// Everything a recalculation task needs, shared by all items of a job
+pub struct RecalculationContext {
+    pub content: Arc<ContentRepository>,
+    pub history: Arc<EchoIndexHistoryRepository>,
+    pub updates: Arc<EchoIndexUpdates>,
+    pub metrics: Arc<MetricsRegistry>,
+    pub events: Arc<UserEventRepository>,
+    /// Accounts created around the time of the content propagate like bots
+    pub users: Arc<UserRepository>,
+    pub calculator: EchoIndexCalculator,
+    /// Read for every item, so language normalization changes apply to running jobs
+    pub engine_config: Arc<EngineConfigStore>,
+    /// Notifies authors' integrations of new scores and tier changes
+    pub webhooks: Option<Arc<WebhookDispatcher>>,
+    /// Looked up for every item, so starting or stopping an experiment applies to running jobs
+    pub experiments: Option<Arc<ExperimentRepository>>,
+    /// Content tagged with a currently trending hashtag earns a TPM bonus
+    pub hashtag_trends: Option<Arc<HashtagTrendService>>,
+    /// Calculations reused for content not propagated since
+    pub echo_cache: Option<Arc<EchoIndexCache>>,
+    /// Flags anomalous score jumps and holds the rewards of the content until reviewed
+    pub anomalies: Option<Arc<EchoIndexAnomalyDetector>>,
+    /// Scores content of new authors that has not propagated yet from its quality and the
+    /// platform baseline
+    pub cold_start: Option<Arc<ColdStartService>>,
+    /// Echo Loops add their normalized strength to TPM
+    pub propagation: Option<Arc<PropagationService>>,
+    /// Authors' Echo Scores follow their content's, moving them up the tiers
+    pub tiers: Option<Arc<UserTierProgressionService>>,
+}
+
+/// Outcome of recalculating a content item
+pub struct Recalculation {
+    pub echo_index: EchoIndex,
+    /// Calculation the new one replaces, if the content was scored before
+    pub previous: Option<EchoIndexHistory>,
+    /// Share of the data the score needs, if it was scored from a cold start
+    pub data_sufficiency: Option<f64>,
+}
+
+/// Bounded-concurrency runner for bulk Echo Index recalculation
+pub struct BatchJobs {
+    jobs: DashMap<Uuid, JobStatus>,
+    permits: Arc<Semaphore>,
+    in_flight: AtomicUsize,
+    idle: Notify,
+    runtime: Handle,
+}
+
+impl BatchJobs {
+    /// Create a runner that spawns onto the current Tokio runtime
+    pub fn new(concurrency: usize) -> Self {
+        Self {
+            jobs: DashMap::new(),
+            permits: Arc::new(Semaphore::new(concurrency.max(1))),
+            in_flight: AtomicUsize::new(0),
+            idle: Notify::new(),
+            runtime: Handle::current(),
+        }
+    }
+
+    /// Concurrency from `BATCH_CONCURRENCY`, defaulting to 10
+    pub fn from_env() -> Self {
+        let concurrency = std::env::var("BATCH_CONCURRENCY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(10);
+        Self::new(concurrency)
+    }
+
+    /// Queue one recalculation task per content item and return the job id
+    pub fn submit(
+        self: &Arc<Self>,
+        content_ids: Vec<Uuid>,
+        force: bool,
+        context: RecalculationContext,
+    ) -> Uuid {
+        let job_id = Uuid::new_v4();
+        self.jobs.insert(job_id, JobStatus::new(content_ids.len()));
+
+        let context = Arc::new(context);
+        for content_id in content_ids {
+            self.in_flight.fetch_add(1, Ordering::SeqCst);
+            let jobs = Arc::clone(self);
+            let context = Arc::clone(&context);
+
+            // Spawned onto the runtime captured at startup rather than the calling
+            // worker's, so tasks survive worker shutdown and can be drained
+            self.runtime.spawn(async move {
+                let outcome = match jobs.permits.clone().acquire_owned().await {
+                    Ok(_permit) => recalculate(&context, content_id, force).await.map(|_| ()),
+                    Err(_) => Err("batch runner closed".to_string()),
+                };
+                if let Err(e) = &outcome {
+                    log::warn!("Recalculation of {} in job {} failed: {}", content_id, job_id, e);
+                }
+
+                jobs.record(job_id, outcome.is_ok());
+                if jobs.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+                    jobs.idle.notify_waiters();
+                }
+            });
+        }
+
+        job_id
+    }
+
+    /// Current progress of a job
+    pub fn status(&self, job_id: Uuid) -> Option<JobStatus> {
+        self.jobs.get(&job_id).map(|status| status.clone())
+    }
+
+    fn record(&self, job_id: Uuid, succeeded: bool) {
+        if let Some(mut status) = self.jobs.get_mut(&job_id) {
+            if succeeded {
+                status.completed += 1;
+            } else {
+                status.failed += 1;
+            }
+            status.settle();
+        }
+    }
+
+    /// Wait until every queued recalculation has finished
+    pub async fn drain(&self) {
+        loop {
+            let idle = self.idle.notified();
+            if self.in_flight.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+
+    /// Drop jobs that finished longer than `retention` ago
+    pub fn evict_finished(&self, retention: Duration) -> usize {
+        let before = self.jobs.len();
+        self.jobs.retain(|_, status| {
+            status.finished_at.is_none_or(|finished_at| finished_at.elapsed() < retention)
+        });
+        before - self.jobs.len()
+    }
+
+    /// Spawn a background task that evicts finished jobs on a fixed interval
+    pub fn spawn_eviction_task(self: Arc<Self>, period: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let evicted = self.evict_finished(period);
+                if evicted > 0 {
+                    log::debug!("Evicted {} finished batch jobs", evicted);
+                }
+            }
+        })
+    }
+}
+
+/// Recalculate, persist and broadcast the Echo Index of a single content item from its
+/// stored propagations and audience. Returns None if it was skipped as fresh. A cached
+/// calculation made since the content was last propagated is returned as it is.
+pub(crate) async fn recalculate(
+    context: &RecalculationContext,
+    content_id: Uuid,
+    force: bool,
+) -> Result<Option<Recalculation>, String> {
+    let latest = context.history.latest(content_id).await.map_err(|e| e.to_string())?;
+    if !force
+        && latest
+            .as_ref()
+            .is_some_and(|latest| Utc::now() - latest.calculated_at < chrono::Duration::minutes(FRESHNESS_WINDOW_MINUTES))
+    {
+        return Ok(None);
+    }
+    if let Some(cache) = &context.echo_cache {
+        let last_propagated_at = context.content.last_propagated_at(content_id).await.map_err(|e| e.to_string())?;
+        if let Some(echo_index) = cache.get(content_id, last_propagated_at) {
+            return Ok(Some(Recalculation { echo_index, previous: latest, data_sufficiency: None }));
+        }
+    }
+
+    // Anything propagated after this is missing from the calculation
+    let calculated_at = Utc::now();
+    let record = context.content.find_by_id(content_id).await.map_err(|e| e.to_string())?;
+    let mut propagations = context.content.list_propagations(content_id).await.map_err(|e| e.to_string())?;
+    let audience = context.content.audience_metrics(content_id).await.map_err(|e| e.to_string())?;
+    let mut content = Content::from(record);
+    content.author_verified = context.content.author_verified(content_id).await.map_err(|e| e.to_string())?;
+    if content.author_verified {
+        let reaches = context.content.cross_post_reaches(content_id).await.map_err(|e| e.to_string())?;
+        content.cross_platform_bonus = reaches
+            .into_iter()
+            .map(|(own, linked)| EchoIndexCalculator::cross_platform_bonus(own, linked))
+            .fold(0.0, f64::max);
+    }
+    if let Some(hashtag_trends) = &context.hashtag_trends {
+        content.trending_hashtag_bonus = hashtag_trends.tpm_bonus(content_id).await.map_err(|e| e.to_string())?;
+    }
+    if let Some(propagation) = &context.propagation {
+        let loop_content_id = content_id.to_string();
+        propagation.load_content_echo_loops(&loop_content_id).await?;
+        content.echo_loop_strength = propagation.mean_loop_strength(&loop_content_id);
+    }
+    let propagators: Vec<Uuid> = propagations.iter().map(|propagation| propagation.from_user_id).collect();
+    let account_ages = context.users.account_created_at(&propagators).await.map_err(|e| e.to_string())?;
+    let bot_detector = BotDetector::new(content.created_at).with_account_ages(account_ages);
+    let engine_config = context.engine_config.load();
+    // A single high-reach propagation must not dominate TPM
+    PropagationWeightNormalizer::new(engine_config.config.weight_normalization)
+        .normalize_propagations(&mut propagations);
+
+    let experiment = match &context.experiments {
+        Some(experiments) => experiments.active().await.map_err(|e| e.to_string())?,
+        None => None,
+    };
+
+    let cold_start = match &context.cold_start {
+        Some(cold_start) if propagations.is_empty() => {
+            content.author_content_count =
+                context.content.prior_content_count(content_id).await.map_err(|e| e.to_string())?;
+            ColdStartService::applies(&content, propagations.len()).then_some(cold_start)
+        }
+        _ => None,
+    };
+    let echo_index = match cold_start {
+        Some(cold_start) => {
+            let echo_index = cold_start
+                .calculate_initial_echo(&content, &engine_config.config.language_normalization_factors)
+                .await
+                .map_err(|e| e.to_string())?;
+            let scores = EchoIndexScores {
+                score: echo_index.overall_score,
+                odf: echo_index.originality_depth_factor,
+                awr: echo_index.audience_weight_rating,
+                tpm: echo_index.transmission_path_mapping,
+                qf: echo_index.quote_frequency,
+            };
+            context
+                .history
+                .record(content_id, scores, EchoIndexTrigger::Recalculation, None)
+                .await
+                .map_err(|e| e.to_string())?;
+            echo_index
+        }
+        None => EchoService::calculate_echo_index(
+            &content,
+            &propagations,
+            &[audience],
+            &context.calculator,
+            &engine_config.config.language_normalization_factors,
+            &bot_detector,
+            &context.history,
+            EchoIndexTrigger::Recalculation,
+            experiment.as_ref(),
+        )
+        .await
+        .map_err(|e| e.to_string())?,
+    };
+    let data_sufficiency = cold_start.map(|_| data_sufficiency(content.author_content_count));
+    // Cold-start scores are not cached, so a cached calculation is never one
+    if let (Some(cache), None) = (&context.echo_cache, data_sufficiency) {
+        cache.insert(content_id, echo_index.clone(), calculated_at);
+    }
+
+    context.content
+        .set_echo_index(content_id, echo_index.overall_score)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(tiers) = &context.tiers {
+        if let Err(e) = tiers.update_echo_score(content.author_id).await {
+            log::warn!("Failed to update the Echo Score of {}: {}", content.author_id, e);
+        }
+    }
+
+    context.metrics.record_echo_index(&content.platform, echo_index.overall_score * 100.0);
+    if let (Some(anomalies), Some(latest)) = (&context.anomalies, &latest) {
+        let elapsed = (calculated_at - latest.calculated_at).num_seconds().max(0) as u64;
+        let (previous_score, new_score) = (latest.score * 100.0, echo_index.overall_score * 100.0);
+        if let Err(e) = anomalies.detect(content_id, previous_score, new_score, elapsed).await {
+            log::warn!("Anomaly check of {} failed: {}", content_id, e);
+        }
+    }
+    let transitions = UserEvent::echo_index_transitions(
+        latest.as_ref().map(|latest| latest.score * 100.0),
+        echo_index.overall_score * 100.0,
+    );
+    for event in &transitions {
+        if let Err(e) = context.events.record(content.author_id, event).await {
+            log::warn!("Failed to record {} for {}: {}", event.event_type(), content.author_id, e);
+        }
+    }
+    let update = context.updates.publish(
+        &content_id.to_string(),
+        echo_index.overall_score,
+        EchoIndexComponents {
+            odf: echo_index.originality_depth_factor,
+            awr: echo_index.audience_weight_rating,
+            tpm: echo_index.transmission_path_mapping,
+            qf: echo_index.quote_frequency,
+        },
+    );
+
+    if let Some(webhooks) = &context.webhooks {
+        webhooks.notify(content.author_id, WebhookEvent::EchoIndexCalculated, serde_json::json!(update));
+        for event in transitions {
+            if let UserEvent::TierChanged { old_tier, new_tier } = event {
+                let data = serde_json::json!({ "content_id": content_id, "old_tier": old_tier, "new_tier": new_tier });
+                webhooks.notify(content.author_id, WebhookEvent::TierChanged, data);
+            }
+        }
+    }
+
+    Ok(Some(Recalculation { echo_index, previous: latest, data_sufficiency }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::content::EchoIndexWeights;
+    use crate::models::experiment::ExperimentVariant;
+    use crate::models::Platform;
+    use crate::repositories::{NewContent, NewExperiment};
+    use sqlx::PgPool;
+    use std::collections::HashMap;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_batch_job_counts_failures_and_drains(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xbatch') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let content = Arc::new(ContentRepository::new(pool.clone()));
+        let created = content
+            .create(&NewContent {
+                user_id,
+                platform: Platform::Twitter,
+                external_id: "tweet_batch".to_string(),
+                content_type: "text".to_string(),
+                title: "Batch".to_string(),
+                body: "Echoes recalculated in bulk".to_string(),
+                media_urls: vec![],
+                tags: vec![],
+                reactions: HashMap::new(),
+            })
+            .await
+            .unwrap();
+
+        // Every item falls into the treatment
+        let experiments = Arc::new(ExperimentRepository::new(pool.clone()));
+        let experiment = experiments
+            .create(&NewExperiment {
+                name: "quotes count double",
+                control_weights: EchoIndexWeights::default(),
+                treatment_weights: EchoIndexWeights { odf: 0.2, awr: 0.2, tpm: 0.2, qf: 0.4 },
+                traffic_split: 1.0,
+                active: true,
+            })
+            .await
+            .unwrap();
+
+        let history = Arc::new(EchoIndexHistoryRepository::new(pool.clone()));
+        let jobs = Arc::new(BatchJobs::new(2));
+        let job_id = jobs.submit(
+            vec![created.id, Uuid::new_v4()],
+            true,
+            RecalculationContext {
+                content,
+                history: history.clone(),
+                updates: Arc::new(EchoIndexUpdates::new()),
+                metrics: Arc::new(MetricsRegistry::new()),
+                events: Arc::new(UserEventRepository::new(pool.clone())),
+                users: Arc::new(UserRepository::new(pool.clone())),
+                calculator: EchoIndexCalculator::default(),
+                engine_config: Arc::new(EngineConfigStore::default()),
+                webhooks: None,
+                experiments: Some(experiments),
+                hashtag_trends: None,
+                echo_cache: None,
+                anomalies: None,
+                cold_start: None,
+                propagation: None,
+                tiers: None,
+            },
+        );
+
+        jobs.drain().await;
+
+        let status = jobs.status(job_id).unwrap();
+        assert_eq!((status.total, status.completed, status.failed), (2, 1, 1));
+        assert_eq!(status.status, JobState::Failed);
+        let latest = history.latest(created.id).await.unwrap().unwrap();
+        assert_eq!((latest.experiment_id, latest.variant), (Some(experiment.id), Some(ExperimentVariant::Treatment)));
+        let expected_score = 0.2 * (latest.odf + latest.awr + latest.tpm) + 0.4 * latest.qf;
+        assert!((latest.score - expected_score).abs() < 1e-9);
+
+        // Milestones and tier changes reached by the first calculation land on the author's timeline
+        let expected = UserEvent::echo_index_transitions(None, latest.score * 100.0);
+        let timeline = UserEventRepository::new(pool).timeline(user_id, None, 50).await.unwrap();
+        assert_eq!(timeline.data.len(), expected.len());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_cached_calculation_is_reused_until_propagated(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xcached') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let content = Arc::new(ContentRepository::new(pool.clone()));
+        let created = content
+            .create(&NewContent {
+                user_id,
+                platform: Platform::Twitter,
+                external_id: "tweet_cached".to_string(),
+                content_type: "text".to_string(),
+                title: "Cached".to_string(),
+                body: "Echoes are scored once per propagation".to_string(),
+                media_urls: vec![],
+                tags: vec![],
+                reactions: HashMap::new(),
+            })
+            .await
+            .unwrap();
+        let cache = Arc::new(EchoIndexCache::new());
+        let context = RecalculationContext {
+            content,
+            history: Arc::new(EchoIndexHistoryRepository::new(pool.clone())),
+            updates: Arc::new(EchoIndexUpdates::new()),
+            metrics: Arc::new(MetricsRegistry::new()),
+            events: Arc::new(UserEventRepository::new(pool.clone())),
+            users: Arc::new(UserRepository::new(pool.clone())),
+            calculator: EchoIndexCalculator::default(),
+            engine_config: Arc::new(EngineConfigStore::default()),
+            webhooks: None,
+            experiments: None,
+            hashtag_trends: None,
+            echo_cache: Some(cache.clone()),
+            anomalies: None,
+            cold_start: None,
+            propagation: None,
+            tiers: None,
+        };
+        let calculations = || async {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM echo_index_history WHERE content_id = $1")
+                .bind(created.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        };
+
+        let first = recalculate(&context, created.id, true).await.unwrap().unwrap();
+        // Served from the cache without calculating or storing anything
+        let second = recalculate(&context, created.id, true).await.unwrap().unwrap();
+        assert_eq!(second.echo_index.overall_score, first.echo_index.overall_score);
+        assert_eq!(calculations().await, 1);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.size), (1, 1, 1));
+
+        sqlx::query(
+            "INSERT INTO propagations
+                 (content_id, source_user_id, target_user_id, propagation_type, source_platform, target_platform,
+                  source_external_id, target_external_id)
+             VALUES ($1, $2, NULL, 'share', 'twitter', 'twitter', 'tweet_cached', 'tweet_shared')",
+        )
+        .bind(created.id)
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        recalculate(&context, created.id, true).await.unwrap().unwrap();
+        assert_eq!(calculations().await, 2);
+        assert_eq!(cache.stats().misses, 2);
+    }
+
+    #[tokio::test]
+    async fn test_finished_jobs_are_evicted() {
+        let jobs = BatchJobs::new(1);
+        let running = Uuid::new_v4();
+        let empty = Uuid::new_v4();
+        jobs.jobs.insert(running, JobStatus::new(1));
+        jobs.jobs.insert(empty, JobStatus::new(0));
+        assert_eq!(jobs.status(empty).unwrap().status, JobState::Done);
+
+        assert_eq!(jobs.evict_finished(Duration::ZERO), 1);
+        assert!(jobs.status(running).is_some());
+
+        jobs.record(running, true);
+        assert_eq!(jobs.status(running).unwrap().status, JobState::Done);
+        jobs.drain().await;
+    }
+}
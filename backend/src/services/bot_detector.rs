@@ -0,0 +1,157 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::models::content::Propagation;
+
+/// Accounts created this close to the content are treated as suspicious
+const NEW_ACCOUNT_WINDOW_HOURS: i64 = 24;
+/// More propagations than this from one source within a minute count as a burst
+const BURST_THRESHOLD: usize = 5;
+
+/// Score contribution of each heuristic; the sum is capped at 1.0
+const NEW_ACCOUNT_PENALTY: f64 = 0.4;
+const BURST_PENALTY: f64 = 0.4;
+const ZERO_ENGAGEMENT_PENALTY: f64 = 0.3;
+
+/// Heuristic detection of scripted propagations
+pub struct BotDetector {
+    content_created_at: DateTime<Utc>,
+    account_created_at: HashMap<Uuid, DateTime<Utc>>,
+}
+
+impl BotDetector {
+    pub fn new(content_created_at: DateTime<Utc>) -> Self {
+        Self {
+            content_created_at,
+            account_created_at: HashMap::new(),
+        }
+    }
+
+    /// Provide account creation times for propagating users
+    pub fn with_account_ages(mut self, account_created_at: HashMap<Uuid, DateTime<Utc>>) -> Self {
+        self.account_created_at = account_created_at;
+        self
+    }
+
+    /// Per-propagation bot score in `[0.0, 1.0]`, in input order
+    pub fn score(&self, propagations: &[Propagation]) -> Vec<f64> {
+        let bursts = Self::burst_members(propagations);
+
+        propagations
+            .iter()
+            .enumerate()
+            .map(|(index, propagation)| {
+                let mut score = 0.0;
+                if self.is_new_account(&propagation.from_user_id) {
+                    score += NEW_ACCOUNT_PENALTY;
+                }
+                if bursts[index] {
+                    score += BURST_PENALTY;
+                }
+                if propagation.reach > 0 && propagation.engagement == 0 {
+                    score += ZERO_ENGAGEMENT_PENALTY;
+                }
+                f64::min(score, 1.0)
+            })
+            .collect()
+    }
+
+    fn is_new_account(&self, user_id: &Uuid) -> bool {
+        self.account_created_at
+            .get(user_id)
+            .map(|created_at| {
+                (*created_at - self.content_created_at).num_hours().abs() < NEW_ACCOUNT_WINDOW_HOURS
+            })
+            .unwrap_or(false)
+    }
+
+    /// Flag every propagation inside a one-minute window holding more than
+    /// `BURST_THRESHOLD` propagations from the same source
+    fn burst_members(propagations: &[Propagation]) -> Vec<bool> {
+        let mut by_source: HashMap<Uuid, Vec<usize>> = HashMap::new();
+        for (index, propagation) in propagations.iter().enumerate() {
+            by_source.entry(propagation.from_user_id).or_default().push(index);
+        }
+
+        let mut flagged = vec![false; propagations.len()];
+        for mut indices in by_source.into_values() {
+            indices.sort_by_key(|&index| propagations[index].timestamp);
+
+            let mut start = 0;
+            for end in 0..indices.len() {
+                let end_time = propagations[indices[end]].timestamp;
+                while end_time - propagations[indices[start]].timestamp >= Duration::minutes(1) {
+                    start += 1;
+                }
+                if end - start + 1 > BURST_THRESHOLD {
+                    for &index in &indices[start..=end] {
+                        flagged[index] = true;
+                    }
+                }
+            }
+        }
+
+        flagged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Platform;
+
+    fn propagation(from_user_id: Uuid, timestamp: DateTime<Utc>, reach: i64, engagement: i64) -> Propagation {
+        Propagation {
+            id: Uuid::new_v4(),
+            content_id: Uuid::new_v4(),
+            from_user_id,
+            to_user_id: None,
+            platform: Platform::Twitter,
+            propagation_type: "share".to_string(),
+            depth: 1,
+            weight: 1.0,
+            timestamp,
+            reach,
+            engagement,
+            bot_score: 0.0,
+            fingerprint_checked: true,
+            duplicate_of: None,
+        }
+    }
+
+    #[test]
+    fn test_organic_propagation_scores_zero() {
+        let content_created_at = Utc::now() - Duration::days(3);
+        let detector = BotDetector::new(content_created_at);
+        let scores = detector.score(&[propagation(Uuid::new_v4(), Utc::now(), 500, 20)]);
+        assert_eq!(scores, vec![0.0]);
+    }
+
+    #[test]
+    fn test_scripted_burst_scores_as_bot() {
+        let content_created_at = Utc::now() - Duration::hours(2);
+        let bot = Uuid::new_v4();
+        let detector = BotDetector::new(content_created_at)
+            .with_account_ages(HashMap::from([(bot, content_created_at + Duration::hours(1))]));
+
+        let start = Utc::now();
+        let propagations: Vec<_> = (0..6)
+            .map(|i| propagation(bot, start + Duration::seconds(i * 5), 100, 0))
+            .collect();
+
+        assert!(detector.score(&propagations).iter().all(|&score| score == 1.0));
+    }
+
+    #[test]
+    fn test_five_per_minute_is_not_a_burst() {
+        let source = Uuid::new_v4();
+        let start = Utc::now();
+        let propagations: Vec<_> = (0..5)
+            .map(|i| propagation(source, start + Duration::seconds(i * 5), 100, 3))
+            .collect();
+
+        let detector = BotDetector::new(start - Duration::days(7));
+        assert!(detector.score(&propagations).iter().all(|&score| score == 0.0));
+    }
+}
@@ -0,0 +1,188 @@
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::Url;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Host names that only ever lead back into the local network
+const LOCAL_HOST_SUFFIXES: &[&str] = &["localhost", "local", "internal", "localdomain", "home.arpa"];
+
+/// Why EchoLayer will not call a URL back
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CallbackUrlError {
+    #[error("must be a valid URL")]
+    Invalid,
+    #[error("must be an https URL")]
+    NotHttps,
+    #[error("must not point to a loopback, private, link-local or otherwise non-public address")]
+    NonPublicHost,
+    #[error("host {0} could not be resolved")]
+    Unresolvable(String),
+}
+
+/// Parse a URL users want called back, rejecting all but https URLs whose host is a public
+/// IP address or a name that is not obviously local. Names are checked again once resolved.
+pub fn parse_callback_url(url: &str) -> Result<Url, CallbackUrlError> {
+    let url = Url::parse(url.trim()).map_err(|_| CallbackUrlError::Invalid)?;
+    if url.scheme() != "https" {
+        return Err(CallbackUrlError::NotHttps);
+    }
+    let host = url.host_str().ok_or(CallbackUrlError::Invalid)?;
+    let public = match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(ip) => is_public_ip(ip),
+        Err(_) => !is_local_name(host),
+    };
+    if !public {
+        return Err(CallbackUrlError::NonPublicHost);
+    }
+    Ok(url)
+}
+
+/// Parse a URL users want called back and resolve its host, rejecting it unless every
+/// address it resolves to is public
+pub async fn check_callback_url(url: &str) -> Result<Url, CallbackUrlError> {
+    let url = parse_callback_url(url)?;
+    if let Some(host) = url.host_str() {
+        resolve_public(host.trim_start_matches('[').trim_end_matches(']'), 443).await?;
+    }
+    Ok(url)
+}
+
+/// HTTP client for calling URLs back. It connects only to public addresses, whatever the
+/// URL's host resolves to by the time of the call, and does not follow redirects.
+pub fn callback_client(timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicResolver))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Whether an address can be reached from the public internet
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network", shared address space (carrier-grade NAT), IETF protocol
+        // assignments, benchmarking and reserved
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 192 && b == 0 && c == 0)
+        || (a == 198 && (18..20).contains(&b))
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let [first, second, ..] = ip.segments();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local, link-local and documentation
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first == 0x2001 && second == 0x0db8))
+}
+
+fn is_local_name(domain: &str) -> bool {
+    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+    LOCAL_HOST_SUFFIXES
+        .iter()
+        .any(|suffix| domain == *suffix || domain.ends_with(&format!(".{}", suffix)))
+}
+
+/// Addresses of `host`, provided all of them are public
+async fn resolve_public(host: &str, port: u16) -> Result<Vec<SocketAddr>, CallbackUrlError> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|_| CallbackUrlError::Unresolvable(host.to_string()))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(CallbackUrlError::Unresolvable(host.to_string()));
+    }
+    if addrs.iter().any(|addr| !is_public_ip(addr.ip())) {
+        return Err(CallbackUrlError::NonPublicHost);
+    }
+    Ok(addrs)
+}
+
+/// Resolves host names for `callback_client`, failing for hosts with any non-public
+/// address so a name cannot be re-pointed at the local network after it was checked
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = resolve_public(name.as_str(), 0).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_https_urls_of_public_hosts_are_accepted() {
+        assert!(parse_callback_url("https://hooks.example.com/echo").is_ok());
+        assert!(parse_callback_url("https://93.184.216.34:8443/echo").is_ok());
+        assert!(parse_callback_url("https://[2606:4700:4700::1111]/echo").is_ok());
+
+        assert_eq!(parse_callback_url("http://hooks.example.com/echo"), Err(CallbackUrlError::NotHttps));
+        assert_eq!(parse_callback_url("https://"), Err(CallbackUrlError::Invalid));
+        for url in [
+            "https://127.0.0.1/echo",
+            "https://localhost:8080/echo",
+            "https://metadata.google.internal/computeMetadata",
+            "https://printer.local/",
+            "https://10.1.2.3/",
+            "https://172.16.0.1/",
+            "https://192.168.1.1/",
+            "https://169.254.169.254/latest/meta-data",
+            "https://100.64.0.1/",
+            "https://0.0.0.0/",
+            "https://[::1]/",
+            "https://[fd00::1]/",
+            "https://[fe80::1]/",
+            "https://[::ffff:127.0.0.1]/",
+            // Decimal form of 127.0.0.1
+            "https://2130706433/",
+        ] {
+            assert_eq!(parse_callback_url(url), Err(CallbackUrlError::NonPublicHost), "{}", url);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_client_does_not_connect_to_loopback_names() {
+        let server = actix_web::HttpServer::new(|| {
+            actix_web::App::new().route("/", actix_web::web::get().to(actix_web::HttpResponse::Ok))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let port = server.addrs()[0].port();
+        tokio::spawn(server.run());
+
+        let client = callback_client(Duration::from_secs(5));
+        let direct = reqwest::Client::new().get(format!("http://127.0.0.1:{}/", port)).send().await.unwrap();
+        assert!(direct.status().is_success());
+        // Whatever URL it is handed, the client will not resolve a name to loopback
+        assert!(client.get(format!("http://localhost:{}/", port)).send().await.is_err());
+        assert!(resolve_public("127.0.0.1", 443).await.is_err());
+        assert!(resolve_public("93.184.216.34", 443).await.is_ok());
+    }
+}
@@ -0,0 +1,134 @@
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Lifetime of an issued authentication challenge
+pub const CHALLENGE_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum ChallengeError {
+    #[error("Unknown or already used nonce")]
+    UnknownNonce,
+    #[error("Nonce was issued for a different wallet")]
+    WalletMismatch,
+    #[error("Challenge has expired")]
+    Expired,
+}
+
+/// Single-use login challenges, keyed by nonce
+pub struct ChallengeStore {
+    challenges: DashMap<String, (Instant, String)>,
+    ttl: Duration,
+}
+
+impl ChallengeStore {
+    pub fn new() -> Self {
+        Self::with_ttl(CHALLENGE_TTL)
+    }
+
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            challenges: DashMap::new(),
+            ttl,
+        }
+    }
+
+    /// Record a freshly issued nonce for a wallet
+    pub fn issue(&self, nonce: &str, wallet_address: &str) {
+        self.challenges.insert(
+            nonce.to_string(),
+            (Instant::now() + self.ttl, wallet_address.to_string()),
+        );
+    }
+
+    /// Check that a nonce exists, belongs to the wallet and has not expired
+    pub fn validate(&self, nonce: &str, wallet_address: &str) -> Result<(), ChallengeError> {
+        let (expires_at, issued_to) = self.challenges
+            .get(nonce)
+            .map(|entry| entry.value().clone())
+            .ok_or(ChallengeError::UnknownNonce)?;
+
+        if Instant::now() >= expires_at {
+            self.challenges.remove(nonce);
+            return Err(ChallengeError::Expired);
+        }
+
+        if issued_to != wallet_address {
+            return Err(ChallengeError::WalletMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Remove a nonce after successful authentication.
+    /// Returns false if it was already consumed, so concurrent replays lose the race.
+    pub fn consume(&self, nonce: &str) -> bool {
+        self.challenges.remove(nonce).is_some()
+    }
+
+    /// Drop challenges that were never redeemed
+    pub fn evict_expired(&self) -> usize {
+        let before = self.challenges.len();
+        let now = Instant::now();
+        self.challenges.retain(|_, (expires_at, _)| *expires_at > now);
+        before - self.challenges.len()
+    }
+
+    /// Spawn a background task that evicts expired challenges on a fixed interval
+    pub fn spawn_eviction_task(self: Arc<Self>, period: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                self.evict_expired();
+            }
+        })
+    }
+}
+
+impl Default for ChallengeStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+
+    #[test]
+    fn test_expired_nonce_rejected() {
+        let store = ChallengeStore::with_ttl(Duration::ZERO);
+        store.issue("nonce-1", WALLET);
+
+        assert_eq!(store.validate("nonce-1", WALLET), Err(ChallengeError::Expired));
+        // Expired challenges are dropped on first sight
+        assert_eq!(store.validate("nonce-1", WALLET), Err(ChallengeError::UnknownNonce));
+    }
+
+    #[test]
+    fn test_nonce_cannot_be_used_twice() {
+        let store = ChallengeStore::new();
+        store.issue("nonce-2", WALLET);
+
+        assert_eq!(store.validate("nonce-2", WALLET), Ok(()));
+        assert!(store.consume("nonce-2"));
+
+        assert_eq!(store.validate("nonce-2", WALLET), Err(ChallengeError::UnknownNonce));
+        assert!(!store.consume("nonce-2"));
+    }
+
+    #[test]
+    fn test_nonce_bound_to_wallet() {
+        let store = ChallengeStore::new();
+        store.issue("nonce-3", WALLET);
+
+        assert_eq!(
+            store.validate("nonce-3", "0x0000000000000000000000000000000000000000"),
+            Err(ChallengeError::WalletMismatch)
+        );
+    }
+}
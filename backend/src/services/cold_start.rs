@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::models::content::{Content, EchoIndex};
+use crate::models::echo_index::{EchoIndexCalculator, QuoteMetrics};
+use crate::models::moderation::{CONTENT_UNDER_REVIEW, UNDER_REVIEW_ECHO_WEIGHT};
+use crate::services::{ContentNormalizer, EchoService, PlatformNormalizer, PlatformStatsService};
+
+/// Boost of the first content of a new author, halved with each piece they publish
+pub const COLD_START_BONUS: f64 = 0.4;
+/// Authors with fewer pieces of prior content than this are scored from a cold start
+pub const COLD_START_MAX_PRIOR_CONTENT: u32 = 3;
+/// From this many pieces of prior content on, an author is past the cold-start bonus and
+/// there is enough data to score their content on its own
+const SUFFICIENT_PRIOR_CONTENT: u32 = 5;
+
+/// Cold-start bonus of an author's next piece after `user_content_count` pieces: halved
+/// with each piece, and gone from the fifth on
+pub fn cold_start_bonus(user_content_count: u32) -> f64 {
+    if user_content_count >= SUFFICIENT_PRIOR_CONTENT {
+        return 0.0;
+    }
+    COLD_START_BONUS * 0.5f64.powi(user_content_count as i32)
+}
+
+/// Share of the data an Echo Index needs that an author's next piece after
+/// `user_content_count` pieces has, from 0.2 for a first piece up to 1.0
+pub fn data_sufficiency(user_content_count: u32) -> f64 {
+    ((user_content_count + 1) as f64 / SUFFICIENT_PRIOR_CONTENT as f64).min(1.0)
+}
+
+/// Scores the first content of new authors. Without propagations every propagation-based
+/// component is 0, so the score rests on the content's own quality and the platform's
+/// statistics instead, boosted while the author is new.
+pub struct ColdStartService {
+    platform_stats: Arc<PlatformStatsService>,
+}
+
+impl ColdStartService {
+    pub fn new(platform_stats: Arc<PlatformStatsService>) -> Self {
+        Self { platform_stats }
+    }
+
+    /// Whether content is scored from a cold start: it has not propagated, and its
+    /// author published fewer than `COLD_START_MAX_PRIOR_CONTENT` pieces before it
+    pub fn applies(content: &Content, propagation_count: usize) -> bool {
+        propagation_count == 0 && content.author_content_count < COLD_START_MAX_PRIOR_CONTENT
+    }
+
+    /// Echo Index of content scored from a cold start. The content's quality, from its
+    /// text alone, is weighed against the platform's average by how much data there is on
+    /// the author, then boosted by the cold-start bonus. The bonus lifts a score no higher
+    /// than the platform's top 10%, which content has to reach by propagating.
+    pub async fn calculate_initial_echo(
+        &self,
+        content: &Content,
+        language_factors: &HashMap<String, f64>,
+    ) -> Result<EchoIndex, Box<dyn std::error::Error>> {
+        let normalized = PlatformNormalizer::normalize(&content.text, &content.platform);
+        let metrics = EchoService::analyze_content(&normalized, language_factors).await?;
+        let odf = EchoIndexCalculator::apply_verified_account_bonus(
+            EchoIndexCalculator::calculate_odf(&content.text, &metrics),
+            content.author_verified,
+        );
+        let qf = EchoIndexCalculator::calculate_qf(&QuoteMetrics {
+            direct_quotes: 0,
+            indirect_references: 0,
+            discussion_threads: 0,
+            citation_quality: 0.0,
+        });
+        let quality = odf.max(qf);
+
+        // A platform without recently scored content has no statistics to lean on
+        let stats = self.platform_stats.for_platform(&content.platform);
+        let baseline = stats.as_ref().map_or(quality, |stats| stats.avg_echo_index);
+        let sufficiency = data_sufficiency(content.author_content_count);
+        let blended = sufficiency * quality + (1.0 - sufficiency) * baseline;
+        let ceiling = stats.map_or(1.0, |stats| stats.top_10_pct_threshold.max(blended));
+        let boosted = blended * (1.0 + cold_start_bonus(content.author_content_count));
+        let mut overall_score = boosted.min(ceiling).min(1.0);
+        if content.status == CONTENT_UNDER_REVIEW {
+            overall_score *= UNDER_REVIEW_ECHO_WEIGHT;
+        }
+
+        Ok(EchoIndex {
+            originality_depth_factor: odf,
+            audience_weight_rating: 0.0,
+            transmission_path_mapping: 0.0,
+            quote_frequency: qf,
+            overall_score,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Platform;
+    use crate::repositories::PlatformStatsRepository;
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_bonus_halves_per_piece_and_is_gone_after_five() {
+        assert_eq!(cold_start_bonus(0), 0.4);
+        assert_eq!(cold_start_bonus(1), 0.2);
+        assert_eq!(cold_start_bonus(4), 0.025);
+        for count in [5, 6, 100] {
+            assert_eq!(cold_start_bonus(count), 0.0);
+        }
+        assert_eq!(data_sufficiency(0), 0.2);
+        assert_eq!(data_sufficiency(4), 1.0);
+        assert_eq!(data_sufficiency(9), 1.0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_initial_echo_leans_on_platform_baseline(pool: PgPool) {
+        let author: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xveteran') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        for (i, echo_index) in [0.5, 0.7].into_iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO content (user_id, platform, external_id, content_type, title, body, echo_index)
+                 VALUES ($1, 'twitter', $2, 'text', 'Scored', 'Scored before', $3)",
+            )
+            .bind(author)
+            .bind(format!("tweet_scored_{}", i))
+            .bind(echo_index)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let platform_stats = Arc::new(PlatformStatsService::new(Arc::new(PlatformStatsRepository::new(pool))));
+        assert_eq!(platform_stats.refresh().await.unwrap(), 1);
+        assert!((platform_stats.for_platform(&Platform::Twitter).unwrap().avg_echo_index - 0.6).abs() < 1e-9);
+        assert!(platform_stats.for_platform(&Platform::Reddit).is_none());
+        let service = ColdStartService::new(platform_stats);
+
+        let mut content = Content::new(
+            Uuid::new_v4(),
+            "A first post about decentralized content attribution".to_string(),
+            Platform::Twitter,
+            "https://twitter.com/new/status/1".to_string(),
+        );
+        assert!(ColdStartService::applies(&content, 0));
+        assert!(!ColdStartService::applies(&content, 1));
+        let first = service.calculate_initial_echo(&content, &HashMap::new()).await.unwrap();
+        let quality = first.originality_depth_factor.max(first.quote_frequency);
+        // The bonus lifts the score up to the platform's top 10%, at 0.68
+        let blended = 0.2 * quality + 0.8 * 0.6;
+        let expected = (blended * 1.4).min(blended.max(0.68));
+        assert!((first.overall_score - expected).abs() < 1e-9);
+        assert_eq!((first.audience_weight_rating, first.transmission_path_mapping), (0.0, 0.0));
+
+        // By the fifth piece the score is the content's quality alone, without a bonus
+        content.author_content_count = 5;
+        assert!(!ColdStartService::applies(&content, 0));
+        let settled = service.calculate_initial_echo(&content, &HashMap::new()).await.unwrap();
+        assert!((settled.overall_score - quality).abs() < 1e-9);
+    }
+}
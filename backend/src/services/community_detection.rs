@@ -0,0 +1,339 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::handlers::propagation::{NetworkMetrics, PropagationNetwork};
+use crate::models::Platform;
+
+/// Smallest modularity gain that counts as an improvement, so floating point noise
+/// cannot move nodes back and forth forever
+const MIN_MODULARITY_GAIN: f64 = 1e-12;
+
+/// A group of nodes more densely linked to each other than to the rest of the network
+#[derive(Debug, Clone, Serialize)]
+pub struct Community {
+    pub id: Uuid,
+    pub nodes: Vec<String>,
+    /// This community's term of the network's modularity
+    pub modularity_contribution: f64,
+    /// Platform most of the community's nodes are on
+    pub dominant_platform: Platform,
+    /// Node with the highest betweenness centrality within the community
+    pub centroid_node: String,
+}
+
+/// Finds the communities of a propagation network with the Louvain method, treating
+/// propagation edges as undirected links weighted by their `weight`
+pub struct PropagationCommunityDetector;
+
+impl PropagationCommunityDetector {
+    /// Communities of two or more nodes, largest first
+    pub fn detect(network: &PropagationNetwork) -> Vec<Community> {
+        let index: HashMap<&str, usize> = network
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id.as_str(), i))
+            .collect();
+
+        let mut graph = WeightedGraph::new(network.nodes.len());
+        for edge in &network.edges {
+            let (Some(&a), Some(&b)) = (index.get(edge.source_id.as_str()), index.get(edge.target_id.as_str())) else {
+                continue;
+            };
+            graph.add_edge(a, b, edge.weight.max(0.0));
+        }
+        let total_weight = graph.total_weight();
+        if total_weight == 0.0 {
+            return Vec::new();
+        }
+
+        let assignment = louvain(graph.clone());
+        let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (node, community) in assignment.into_iter().enumerate() {
+            members.entry(community).or_default().push(node);
+        }
+
+        let mut communities: Vec<Community> = members
+            .into_values()
+            .filter(|nodes| nodes.len() > 1)
+            .map(|nodes| {
+                let member_set: HashSet<usize> = nodes.iter().copied().collect();
+                // Links between members are seen from both ends
+                let internal: f64 = nodes
+                    .iter()
+                    .map(|&v| {
+                        let links: f64 = graph.adjacency[v]
+                            .iter()
+                            .filter(|(w, _)| member_set.contains(w))
+                            .map(|(_, weight)| weight)
+                            .sum();
+                        graph.loops[v] + links / 2.0
+                    })
+                    .sum();
+                let degree: f64 = nodes.iter().map(|&v| graph.degree(v)).sum();
+
+                Community {
+                    id: Uuid::new_v4(),
+                    nodes: nodes.iter().map(|&v| network.nodes[v].id.clone()).collect(),
+                    modularity_contribution: internal / total_weight - (degree / (2.0 * total_weight)).powi(2),
+                    dominant_platform: dominant_platform(nodes.iter().map(|&v| &network.nodes[v].platform)),
+                    centroid_node: network.nodes[centroid(&nodes, &graph)].id.clone(),
+                }
+            })
+            .collect();
+
+        communities.sort_by_key(|community| std::cmp::Reverse(community.nodes.len()));
+        communities
+    }
+}
+
+/// Undirected weighted graph whose nodes may carry weight on themselves, which is where
+/// the edges inside a community end up once it is collapsed into one node
+#[derive(Debug, Clone)]
+struct WeightedGraph {
+    /// Links to other nodes; parallel edges are summed
+    adjacency: Vec<HashMap<usize, f64>>,
+    /// Weight of each node's self-loop, counted once
+    loops: Vec<f64>,
+}
+
+impl WeightedGraph {
+    fn new(n: usize) -> Self {
+        Self {
+            adjacency: vec![HashMap::new(); n],
+            loops: vec![0.0; n],
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    fn add_edge(&mut self, a: usize, b: usize, weight: f64) {
+        if a == b {
+            self.loops[a] += weight;
+        } else {
+            *self.adjacency[a].entry(b).or_insert(0.0) += weight;
+            *self.adjacency[b].entry(a).or_insert(0.0) += weight;
+        }
+    }
+
+    /// A self-loop counts twice towards a node's degree, as both its ends are there
+    fn degree(&self, v: usize) -> f64 {
+        self.adjacency[v].values().sum::<f64>() + 2.0 * self.loops[v]
+    }
+
+    /// Sum of all edge weights, `m` in the modularity formula
+    fn total_weight(&self) -> f64 {
+        (0..self.len()).map(|v| self.degree(v)).sum::<f64>() / 2.0
+    }
+}
+
+/// Community of every node of `graph`, numbered densely from 0
+fn louvain(mut graph: WeightedGraph) -> Vec<usize> {
+    let mut assignment: Vec<usize> = (0..graph.len()).collect();
+
+    loop {
+        let (communities, moved) = local_moves(&graph);
+        if !moved {
+            return assignment;
+        }
+
+        for community in assignment.iter_mut() {
+            *community = communities[*community];
+        }
+        graph = aggregate(&graph, &communities);
+    }
+}
+
+/// First Louvain phase: move nodes one at a time into the neighbouring community that
+/// raises modularity the most, until no move does. Returns each node's community,
+/// numbered densely, and whether any node moved.
+fn local_moves(graph: &WeightedGraph) -> (Vec<usize>, bool) {
+    let n = graph.len();
+    let m = graph.total_weight();
+    let degrees: Vec<f64> = (0..n).map(|v| graph.degree(v)).collect();
+    let mut community: Vec<usize> = (0..n).collect();
+    let mut totals = degrees.clone();
+    let mut moved = false;
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for v in 0..n {
+            let current = community[v];
+            totals[current] -= degrees[v];
+
+            // Ordered so ties between communities are always broken the same way
+            let mut links: BTreeMap<usize, f64> = BTreeMap::new();
+            links.insert(current, 0.0);
+            for (&w, &weight) in &graph.adjacency[v] {
+                *links.entry(community[w]).or_insert(0.0) += weight;
+            }
+
+            let gain = |c: usize, weight: f64| weight / m - totals[c] * degrees[v] / (2.0 * m * m);
+            let mut best = (current, gain(current, links[&current]));
+            for (&c, &weight) in &links {
+                let candidate = gain(c, weight);
+                if candidate > best.1 + MIN_MODULARITY_GAIN {
+                    best = (c, candidate);
+                }
+            }
+
+            totals[best.0] += degrees[v];
+            if best.0 != current {
+                community[v] = best.0;
+                improved = true;
+                moved = true;
+            }
+        }
+    }
+
+    let mut renumbered = HashMap::new();
+    let communities = community
+        .into_iter()
+        .map(|c| {
+            let next = renumbered.len();
+            *renumbered.entry(c).or_insert(next)
+        })
+        .collect();
+    (communities, moved)
+}
+
+/// Second Louvain phase: collapse each community into a single node
+fn aggregate(graph: &WeightedGraph, communities: &[usize]) -> WeightedGraph {
+    let size = communities.iter().max().map_or(0, |c| c + 1);
+    let mut collapsed = WeightedGraph::new(size);
+
+    for v in 0..graph.len() {
+        collapsed.loops[communities[v]] += graph.loops[v];
+        for (&w, &weight) in &graph.adjacency[v] {
+            // Each link is seen from both ends
+            if v < w {
+                collapsed.add_edge(communities[v], communities[w], weight);
+            }
+        }
+    }
+
+    collapsed
+}
+
+/// Member with the highest betweenness centrality over the links inside the community
+fn centroid(nodes: &[usize], graph: &WeightedGraph) -> usize {
+    let local: HashMap<usize, usize> = nodes.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+    let neighbors: Vec<HashSet<usize>> = nodes
+        .iter()
+        .map(|v| graph.adjacency[*v].keys().filter_map(|w| local.get(w).copied()).collect())
+        .collect();
+
+    let centrality = NetworkMetrics::betweenness(&neighbors);
+    let mut best = 0;
+    for (i, &value) in centrality.iter().enumerate() {
+        if value > centrality[best] {
+            best = i;
+        }
+    }
+    nodes[best]
+}
+
+/// Most common platform, the first seen among equally common ones
+fn dominant_platform<'a>(platforms: impl Iterator<Item = &'a Platform>) -> Platform {
+    let mut counts: Vec<(&Platform, usize)> = Vec::new();
+    for platform in platforms {
+        match counts.iter_mut().find(|(seen, _)| *seen == platform) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((platform, 1)),
+        }
+    }
+
+    let mut best = counts[0];
+    for &(platform, count) in &counts[1..] {
+        if count > best.1 {
+            best = (platform, count);
+        }
+    }
+    best.0.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::propagation::{PropagationEdge, PropagationNode};
+
+    fn network(nodes: &[(&str, Platform)], edges: &[(&str, &str)]) -> PropagationNetwork {
+        PropagationNetwork::new(
+            nodes
+                .iter()
+                .map(|(id, platform)| PropagationNode {
+                    id: id.to_string(),
+                    user_id: id.to_string(),
+                    platform: platform.clone(),
+                    influence: 0.0,
+                    echo_score: 0.0,
+                })
+                .collect(),
+            edges
+                .iter()
+                .map(|(source, target)| PropagationEdge {
+                    source_id: source.to_string(),
+                    target_id: target.to_string(),
+                    weight: 1.0,
+                    propagation_type: "share".to_string(),
+                    timestamp: String::new(),
+                })
+                .collect(),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_star_forms_one_community_around_its_hub() {
+        let mut nodes = vec![("hub", Platform::Twitter)];
+        let leaves = ["a", "b", "c", "d", "e", "f"];
+        nodes.extend(leaves.iter().map(|leaf| (*leaf, Platform::Farcaster)));
+        let edges: Vec<(&str, &str)> = leaves.iter().map(|leaf| ("hub", *leaf)).collect();
+
+        let communities = PropagationCommunityDetector::detect(&network(&nodes, &edges));
+
+        assert_eq!(communities.len(), 1);
+        assert_eq!(communities[0].nodes.len(), 7);
+        assert_eq!(communities[0].centroid_node, "hub");
+        assert_eq!(communities[0].dominant_platform, Platform::Farcaster);
+    }
+
+    #[test]
+    fn test_two_clusters_joined_by_a_bridge_are_separated() {
+        let nodes = [
+            ("a1", Platform::Twitter),
+            ("a2", Platform::Twitter),
+            ("a3", Platform::Twitter),
+            ("a4", Platform::Telegram),
+            ("b1", Platform::LinkedIn),
+            ("b2", Platform::LinkedIn),
+            ("b3", Platform::LinkedIn),
+            ("b4", Platform::LinkedIn),
+            ("loner", Platform::Medium),
+        ];
+        let mut edges = Vec::new();
+        for cluster in [["a1", "a2", "a3", "a4"], ["b1", "b2", "b3", "b4"]] {
+            for (i, source) in cluster.iter().enumerate() {
+                edges.extend(cluster[i + 1..].iter().map(|target| (*source, *target)));
+            }
+        }
+        edges.push(("a1", "b1"));
+
+        let communities = PropagationCommunityDetector::detect(&network(&nodes, &edges));
+
+        assert_eq!(communities.len(), 2);
+        let mut groups: Vec<Vec<String>> = communities.iter().map(|c| c.nodes.clone()).collect();
+        groups.sort();
+        assert_eq!(groups, [["a1", "a2", "a3", "a4"], ["b1", "b2", "b3", "b4"]]);
+
+        let a = communities.iter().find(|c| c.nodes.contains(&"a1".to_string())).unwrap();
+        assert_eq!(a.dominant_platform, Platform::Twitter);
+        assert!(communities.iter().all(|c| c.modularity_contribution > 0.0));
+        let modularity: f64 = communities.iter().map(|c| c.modularity_contribution).sum();
+        assert!((modularity - (12.0 / 13.0 - 2.0 * (13.0f64 / 26.0).powi(2))).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::repositories::{ContentRepository, RepositoryError};
+
+/// Content scoring at or above this Echo Index stays live however old it is
+pub const ARCHIVE_MAX_ECHO_INDEX: f64 = 20.0;
+/// Items archived per sweep, so a backlog drains without long-running transactions
+const ARCHIVE_BATCH_SIZE: u32 = 500;
+
+/// Periodically moves old low-echo content to cold storage
+pub struct ContentArchiver {
+    repository: Arc<ContentRepository>,
+    archive_after_days: i64,
+}
+
+impl ContentArchiver {
+    pub fn new(repository: Arc<ContentRepository>, archive_after_days: i64) -> Self {
+        Self { repository, archive_after_days }
+    }
+
+    /// Archive content untouched for `ARCHIVE_AFTER_DAYS` days (default 90)
+    pub fn from_env(repository: Arc<ContentRepository>) -> Self {
+        let archive_after_days = std::env::var("ARCHIVE_AFTER_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(90);
+        Self::new(repository, archive_after_days)
+    }
+
+    /// Archive one batch of stale content, returning how many items were archived
+    pub async fn sweep(&self) -> Result<usize, RepositoryError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(self.archive_after_days);
+        self.repository
+            .archive_stale(cutoff, ARCHIVE_MAX_ECHO_INDEX, ARCHIVE_BATCH_SIZE)
+            .await
+    }
+
+    pub fn spawn_archival_task(self: Arc<Self>, period: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                match self.sweep().await {
+                    Ok(archived) if archived > 0 => log::info!("Archived {} stale content items", archived),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Content archival failed: {}", e),
+                }
+            }
+        })
+    }
+}
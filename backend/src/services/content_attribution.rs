@@ -0,0 +1,132 @@
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::content_attribution::{AttributionGraph, AttributionType, ContentAttribution};
+use crate::repositories::{ContentAttributionRepository, RepositoryError};
+
+/// Share of a secondary's own Echo Index its primary is credited with
+pub const PRIMARY_ECHO_SHARE: f64 = 0.7;
+
+/// A primary's own Echo Index plus `PRIMARY_ECHO_SHARE` of each secondary's own. Secondaries
+/// keep their full score; only their own score flows, never anything credited to them.
+pub fn aggregate_echo_index(primary_echo_index: f64, secondary_echo_indexes: impl IntoIterator<Item = f64>) -> f64 {
+    primary_echo_index + secondary_echo_indexes.into_iter().map(|score| score * PRIMARY_ECHO_SHARE).sum::<f64>()
+}
+
+/// Recognizes the same underlying content across platforms, e.g. a Medium article shared
+/// as a Twitter thread and cross-posted to LinkedIn, and credits the original with the
+/// Echo Index its copies earn
+pub struct ContentAttributionService {
+    attributions: Arc<ContentAttributionRepository>,
+}
+
+impl ContentAttributionService {
+    pub fn new(attributions: Arc<ContentAttributionRepository>) -> Self {
+        Self { attributions }
+    }
+
+    /// Attribute a secondary content item to its primary. Attributions are one level deep:
+    /// a secondary has one primary, and a primary is never itself a secondary.
+    pub async fn link(
+        &self,
+        primary_content_id: Uuid,
+        secondary_content_id: Uuid,
+        link_type: AttributionType,
+    ) -> Result<ContentAttribution, RepositoryError> {
+        if primary_content_id == secondary_content_id {
+            return Err(RepositoryError::InvalidInput("content cannot be attributed to itself".to_string()));
+        }
+        self.attributions.link(primary_content_id, secondary_content_id, link_type).await
+    }
+
+    /// The attribution graph a content item belongs to, whether it is the primary or one of
+    /// the secondaries. Content without attributions is a primary without secondaries.
+    pub async fn attribution_graph(&self, content_id: Uuid) -> Result<AttributionGraph, RepositoryError> {
+        let primary_id = self.attributions.primary_of(content_id).await?.unwrap_or(content_id);
+        let primary = self.attributions.attributed_content(primary_id).await?;
+        let secondaries = self.attributions.secondaries(primary_id).await?;
+
+        let aggregate_echo_index =
+            aggregate_echo_index(primary.echo_index, secondaries.iter().map(|secondary| secondary.echo_index));
+        Ok(AttributionGraph { primary, secondaries, aggregate_echo_index })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::ContentRepository;
+    use sqlx::PgPool;
+
+    async fn insert_content(pool: &PgPool, author: Uuid, platform: &str, external_id: &str, echo_index: f64) -> Uuid {
+        sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type, title, body, echo_index)
+             VALUES ($1, $2::platform_type, $3, 'text', 'Rollups', 'Batching transactions', $4) RETURNING id",
+        )
+        .bind(author)
+        .bind(platform)
+        .bind(external_id)
+        .bind(echo_index)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_secondary_echo_index_flows_to_primary_once(pool: PgPool) {
+        let author: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xwriter') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let article = insert_content(&pool, author, "medium", "article_1", 40.0).await;
+        let thread = insert_content(&pool, author, "twitter", "thread_1", 20.0).await;
+        let post = insert_content(&pool, author, "linkedin", "post_1", 10.0).await;
+        let other = insert_content(&pool, author, "reddit", "thread_2", 5.0).await;
+
+        let service = ContentAttributionService::new(Arc::new(ContentAttributionRepository::new(pool.clone())));
+        let linked = service.link(article, thread, AttributionType::CrossPost).await.unwrap();
+        assert_eq!((linked.primary_content_id, linked.secondary_content_id), (article, thread));
+        service.link(article, post, AttributionType::Translation).await.unwrap();
+
+        // The primary is credited with 70% of each secondary, which keep their own score
+        let graph = service.attribution_graph(article).await.unwrap();
+        assert_eq!(graph.primary.echo_index, 40.0);
+        let secondaries: Vec<(Uuid, f64)> = graph.secondaries.iter().map(|s| (s.content_id, s.echo_index)).collect();
+        assert_eq!(secondaries, vec![(thread, 20.0), (post, 10.0)]);
+        assert!((graph.aggregate_echo_index - 61.0).abs() < 1e-9);
+        assert_eq!(service.attribution_graph(thread).await.unwrap(), graph);
+
+        // Links that would credit a score twice, or in a loop, are refused
+        for (primary, secondary) in [(other, thread), (thread, other), (other, article), (thread, article)] {
+            let refused = service.link(primary, secondary, AttributionType::Repost).await;
+            assert!(matches!(refused, Err(RepositoryError::Conflict(_))), "{} -> {}", primary, secondary);
+        }
+        assert!(matches!(
+            service.link(other, other, AttributionType::Quote).await,
+            Err(RepositoryError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            service.link(Uuid::new_v4(), other, AttributionType::Quote).await,
+            Err(RepositoryError::NotFound)
+        ));
+
+        // Only cross-posts count towards the cross-platform ODF bonus
+        sqlx::query(
+            "INSERT INTO propagations (content_id, source_user_id, propagation_type, source_platform, target_platform,
+                                       engagement_metrics)
+             VALUES ($1, $2, 'share', 'medium', 'medium', '{\"reaches\": 1000}')",
+        )
+        .bind(article)
+        .bind(author)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let content = ContentRepository::new(pool.clone());
+        assert_eq!(content.cross_post_reaches(thread).await.unwrap(), vec![(0, 1000)]);
+        assert_eq!(content.cross_post_reaches(article).await.unwrap(), vec![(1000, 0)]);
+
+        let unlinked = service.attribution_graph(other).await.unwrap();
+        assert!(unlinked.secondaries.is_empty());
+        assert_eq!(unlinked.aggregate_echo_index, 5.0);
+    }
+}
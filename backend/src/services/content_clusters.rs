@@ -0,0 +1,409 @@
+use moka::future::Cache;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::Platform;
+use crate::repositories::{ClusterInput, ContentTfIdfRepository, RepositoryError};
+use crate::services::content_similarity::{cosine_similarity, heaviest_terms, rank_similar};
+use crate::services::TfIdfVector;
+
+/// Cluster counts tried when choosing `k` by silhouette score
+pub const MIN_CLUSTERS: usize = 2;
+pub const MAX_CLUSTERS: usize = 15;
+/// Most recent content clustered for platform-wide analytics; silhouette scoring is
+/// quadratic in the content count
+pub const MAX_CLUSTERED_CONTENT: i64 = 1_000;
+/// Heaviest centroid terms describing a cluster
+const CENTROID_TERMS: usize = 10;
+/// Nearest neighbors returned with a content item's cluster
+const CLUSTER_NEIGHBORS: usize = 5;
+const MAX_ITERATIONS: usize = 50;
+/// How long platform-wide cluster assignments are reused
+const ASSIGNMENT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Content grouped around a shared topic
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ContentCluster {
+    pub id: usize,
+    pub centroid_terms: Vec<String>,
+    pub content_ids: Vec<Uuid>,
+    pub avg_echo_index: f64,
+    pub dominant_platform: Platform,
+}
+
+/// The cluster a content item belongs to and its most similar content within it
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ClusterMembership {
+    pub cluster: ContentCluster,
+    pub neighbors: Vec<ClusterNeighbor>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ClusterNeighbor {
+    pub content_id: Uuid,
+    pub similarity: f64,
+}
+
+/// Clusters of the most recent content with the vectors they were computed from
+struct ClusterAssignment {
+    clusters: Vec<ContentCluster>,
+    vectors: HashMap<Uuid, TfIdfVector>,
+}
+
+/// Groups related content into topic clusters by spherical k-means over TF-IDF vectors
+pub struct ContentClusterAnalyzer {
+    repository: Arc<ContentTfIdfRepository>,
+    assignments: Cache<Option<usize>, Arc<ClusterAssignment>>,
+}
+
+impl ContentClusterAnalyzer {
+    pub fn new(repository: Arc<ContentTfIdfRepository>) -> Self {
+        Self {
+            repository,
+            assignments: Cache::builder().time_to_live(ASSIGNMENT_TTL).build(),
+        }
+    }
+
+    /// Cluster the indexed live content among `content_ids` into `k` clusters, or into
+    /// the number with the best silhouette score when `k` is None
+    pub async fn cluster(
+        &self,
+        content_ids: &[Uuid],
+        k: Option<usize>,
+    ) -> Result<Vec<ContentCluster>, RepositoryError> {
+        let inputs = self.repository.cluster_inputs(Some(content_ids), content_ids.len() as i64).await?;
+        Ok(build_clusters(&inputs, k))
+    }
+
+    /// Clusters of the most recent content platform-wide, cached for an hour
+    pub async fn clusters(&self, k: Option<usize>) -> Result<Vec<ContentCluster>, RepositoryError> {
+        Ok(self.assignment(k).await?.clusters.clone())
+    }
+
+    /// The platform-wide cluster of a content item, with the content in it most similar
+    /// to the item. None if the item was not clustered.
+    pub async fn cluster_of(&self, content_id: Uuid) -> Result<Option<ClusterMembership>, RepositoryError> {
+        let assignment = self.assignment(None).await?;
+        let Some(target) = assignment.vectors.get(&content_id) else {
+            return Ok(None);
+        };
+        let Some(cluster) = assignment.clusters.iter().find(|cluster| cluster.content_ids.contains(&content_id))
+        else {
+            return Ok(None);
+        };
+
+        let members: Vec<(Uuid, TfIdfVector)> = cluster
+            .content_ids
+            .iter()
+            .filter(|id| **id != content_id)
+            .filter_map(|id| assignment.vectors.get(id).map(|vector| (*id, vector.clone())))
+            .collect();
+        let neighbors = rank_similar(target, &members, CLUSTER_NEIGHBORS)
+            .into_iter()
+            .map(|(content_id, similarity)| ClusterNeighbor { content_id, similarity })
+            .collect();
+
+        Ok(Some(ClusterMembership { cluster: cluster.clone(), neighbors }))
+    }
+
+    async fn assignment(&self, k: Option<usize>) -> Result<Arc<ClusterAssignment>, RepositoryError> {
+        if let Some(assignment) = self.assignments.get(&k).await {
+            return Ok(assignment);
+        }
+
+        let inputs = self.repository.cluster_inputs(None, MAX_CLUSTERED_CONTENT).await?;
+        let assignment = Arc::new(ClusterAssignment {
+            clusters: build_clusters(&inputs, k),
+            vectors: inputs.into_iter().map(|input| (input.content_id, input.vector)).collect(),
+        });
+        self.assignments.insert(k, assignment.clone()).await;
+        Ok(assignment)
+    }
+}
+
+/// Cluster content into `k` clusters, or into the number of clusters between
+/// `MIN_CLUSTERS` and `MAX_CLUSTERS` with the best silhouette score. Clusters are
+/// numbered largest first.
+pub fn build_clusters(inputs: &[ClusterInput], k: Option<usize>) -> Vec<ContentCluster> {
+    if inputs.is_empty() {
+        return Vec::new();
+    }
+    let vectors: Vec<&TfIdfVector> = inputs.iter().map(|input| &input.vector).collect();
+
+    let assignments = match k {
+        Some(k) => kmeans(&vectors, k.clamp(1, vectors.len())),
+        None => best_assignments(&vectors),
+    };
+
+    let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, cluster) in assignments.into_iter().enumerate() {
+        members.entry(cluster).or_default().push(i);
+    }
+    let mut groups: Vec<Vec<usize>> = members.into_values().collect();
+    groups.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(&b[0])));
+
+    groups
+        .into_iter()
+        .enumerate()
+        .map(|(id, group)| {
+            let centroid = centroid(group.iter().map(|&i| vectors[i]));
+            let mut platforms: HashMap<&Platform, usize> = HashMap::new();
+            for &i in &group {
+                *platforms.entry(&inputs[i].platform).or_default() += 1;
+            }
+            let dominant_platform = platforms
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.as_str().cmp(a.0.as_str())))
+                .map(|(platform, _)| platform.clone())
+                .unwrap_or(Platform::Other(String::new()));
+
+            ContentCluster {
+                id,
+                centroid_terms: heaviest_terms(&centroid, CENTROID_TERMS),
+                avg_echo_index: group.iter().map(|&i| inputs[i].echo_index).sum::<f64>() / group.len() as f64,
+                content_ids: group.iter().map(|&i| inputs[i].content_id).collect(),
+                dominant_platform,
+            }
+        })
+        .collect()
+}
+
+/// Assignments of the cluster count with the highest silhouette score
+fn best_assignments(vectors: &[&TfIdfVector]) -> Vec<usize> {
+    let n = vectors.len();
+    // A silhouette needs at least two clusters and one of them with two members
+    if n <= MIN_CLUSTERS {
+        return vec![0; n];
+    }
+
+    let mut distances = vec![0.0; n * n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let distance = 1.0 - cosine_similarity(vectors[i], vectors[j]);
+            distances[i * n + j] = distance;
+            distances[j * n + i] = distance;
+        }
+    }
+
+    let mut best: Option<(f64, Vec<usize>)> = None;
+    for k in MIN_CLUSTERS..=MAX_CLUSTERS.min(n - 1) {
+        let assignments = kmeans(vectors, k);
+        let score = silhouette(&distances, &assignments, k);
+        if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+            best = Some((score, assignments));
+        }
+    }
+    best.map(|(_, assignments)| assignments).unwrap_or_else(|| vec![0; n])
+}
+
+/// Cluster of each vector after spherical k-means, seeded deterministically with the
+/// first vector and then the vectors farthest from every seed so far
+fn kmeans(vectors: &[&TfIdfVector], k: usize) -> Vec<usize> {
+    let mut centroids: Vec<TfIdfVector> = vec![vectors[0].clone()];
+    while centroids.len() < k {
+        let farthest = vectors
+            .iter()
+            .enumerate()
+            .map(|(i, vector)| {
+                let nearest = centroids.iter().map(|c| cosine_similarity(vector, c)).fold(f64::MIN, f64::max);
+                (i, 1.0 - nearest)
+            })
+            .fold((0, f64::MIN), |best, candidate| if candidate.1 > best.1 { candidate } else { best });
+        centroids.push(vectors[farthest.0].clone());
+    }
+
+    let mut assignments = vec![usize::MAX; vectors.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let next: Vec<usize> = vectors.iter().map(|vector| nearest_centroid(vector, &centroids)).collect();
+        if next == assignments {
+            break;
+        }
+        assignments = next;
+
+        for (cluster, centroid_vector) in centroids.iter_mut().enumerate() {
+            let members = vectors.iter().zip(&assignments).filter(|(_, a)| **a == cluster).map(|(v, _)| *v);
+            let updated = centroid(members);
+            // An emptied cluster keeps its centroid
+            if !updated.is_empty() {
+                *centroid_vector = updated;
+            }
+        }
+    }
+    assignments
+}
+
+fn nearest_centroid(vector: &TfIdfVector, centroids: &[TfIdfVector]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, centroid)| (i, cosine_similarity(vector, centroid)))
+        .fold((0, f64::MIN), |best, candidate| if candidate.1 > best.1 { candidate } else { best })
+        .0
+}
+
+/// Mean of the vectors scaled to unit length
+fn centroid<'a>(vectors: impl Iterator<Item = &'a TfIdfVector>) -> TfIdfVector {
+    let mut sum: TfIdfVector = HashMap::new();
+    for vector in vectors {
+        for (term, weight) in vector {
+            *sum.entry(term.clone()).or_insert(0.0) += weight;
+        }
+    }
+    let norm = sum.values().map(|w| w * w).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        sum.values_mut().for_each(|w| *w /= norm);
+    }
+    sum
+}
+
+/// Mean silhouette of the assignments, from -1 to 1; higher means tighter, better
+/// separated clusters
+fn silhouette(distances: &[f64], assignments: &[usize], k: usize) -> f64 {
+    let n = assignments.len();
+    let mut sizes = vec![0usize; k];
+    for &cluster in assignments {
+        sizes[cluster] += 1;
+    }
+
+    let mut total = 0.0;
+    for i in 0..n {
+        let own = assignments[i];
+        if sizes[own] <= 1 {
+            continue;
+        }
+        let mut sums = vec![0.0; k];
+        for j in (0..n).filter(|&j| j != i) {
+            sums[assignments[j]] += distances[i * n + j];
+        }
+
+        let a = sums[own] / (sizes[own] - 1) as f64;
+        let b = (0..k)
+            .filter(|&cluster| cluster != own && sizes[cluster] > 0)
+            .map(|cluster| sums[cluster] / sizes[cluster] as f64)
+            .fold(f64::MAX, f64::min);
+        if b == f64::MAX || a.max(b) == 0.0 {
+            continue;
+        }
+        total += (b - a) / a.max(b);
+    }
+    total / n as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::{ContentRepository, NewContent};
+    use crate::services::content_similarity::{term_counts, tfidf};
+    use crate::services::ContentSimilarityService;
+    use sqlx::PgPool;
+
+    const BLOCKCHAIN: &[&str] = &[
+        "blockchain", "validators", "staking", "ethereum", "solana", "tokens", "consensus", "wallet", "rollups",
+        "ledger",
+    ];
+    const COOKING: &[&str] = &[
+        "recipe", "garlic", "oven", "roast", "butter", "dough", "simmer", "onions", "sauce", "bake",
+    ];
+
+    /// Post `i` on a topic, drawing six of its words; the first 20 posts are distinct
+    fn post(words: &[&str], i: usize) -> String {
+        let stride = if i < 10 { 1 } else { 3 };
+        (0..6).map(|j| words[(i + j * stride) % words.len()]).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn test_blockchain_and_cooking_posts_form_two_clusters() {
+        let posts: Vec<(String, Platform)> = (0..20)
+            .map(|i| (post(BLOCKCHAIN, i), Platform::Twitter))
+            .chain((0..20).map(|i| (post(COOKING, i), Platform::Medium)))
+            .collect();
+        let counts: Vec<HashMap<String, u32>> = posts.iter().map(|(text, _)| term_counts(text)).collect();
+        let inputs: Vec<ClusterInput> = counts
+            .iter()
+            .enumerate()
+            .map(|(i, document)| {
+                let mut frequencies = HashMap::new();
+                for other in counts.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, other)| other) {
+                    for term in other.keys().filter(|term| document.contains_key(*term)) {
+                        *frequencies.entry(term.clone()).or_insert(0) += 1;
+                    }
+                }
+                ClusterInput {
+                    content_id: Uuid::from_u128(i as u128),
+                    vector: tfidf(document, counts.len() as i64 - 1, &frequencies),
+                    platform: posts[i].1.clone(),
+                    echo_index: if i < 20 { 80.0 } else { 40.0 },
+                }
+            })
+            .collect();
+
+        let clusters = build_clusters(&inputs, None);
+        assert_eq!(clusters.len(), 2);
+        for cluster in &clusters {
+            assert_eq!(cluster.content_ids.len(), 20);
+            assert_eq!(cluster.centroid_terms.len(), CENTROID_TERMS);
+            let blockchain = cluster.content_ids[0] < Uuid::from_u128(20);
+            assert!(cluster.content_ids.iter().all(|id| (*id < Uuid::from_u128(20)) == blockchain));
+            let (topic, platform, echo_index) =
+                if blockchain { (BLOCKCHAIN, Platform::Twitter, 80.0) } else { (COOKING, Platform::Medium, 40.0) };
+            assert!(cluster.centroid_terms.iter().all(|term| topic.contains(&term.as_str())));
+            assert_eq!(cluster.dominant_platform, platform);
+            assert_eq!(cluster.avg_echo_index, echo_index);
+        }
+
+        // A requested k is used as is
+        assert_eq!(build_clusters(&inputs, Some(4)).len(), 4);
+        assert_eq!(build_clusters(&inputs[..1], Some(3)).len(), 1);
+        assert!(build_clusters(&[], None).is_empty());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_content_is_placed_with_its_nearest_neighbors(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xclusters') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let content = ContentRepository::new(pool.clone());
+        let repository = Arc::new(ContentTfIdfRepository::new(pool.clone()));
+        let similarity = ContentSimilarityService::new(repository.clone());
+
+        let mut ids = Vec::new();
+        for (i, body) in (0..4).map(|i| post(BLOCKCHAIN, i)).chain((0..4).map(|i| post(COOKING, i))).enumerate() {
+            let record = content
+                .create(&NewContent {
+                    user_id,
+                    platform: Platform::Twitter,
+                    external_id: format!("post_{}", i),
+                    content_type: "text".to_string(),
+                    title: String::new(),
+                    body,
+                    media_urls: vec![],
+                    tags: vec![],
+                    reactions: HashMap::new(),
+                })
+                .await
+                .unwrap();
+            similarity.index(&record).await.unwrap();
+            ids.push(record.id);
+        }
+
+        let analyzer = ContentClusterAnalyzer::new(repository);
+        let clusters = analyzer.cluster(&ids[..6], Some(2)).await.unwrap();
+        assert_eq!(clusters.iter().map(|cluster| cluster.content_ids.len()).sum::<usize>(), 6);
+
+        let membership = analyzer.cluster_of(ids[0]).await.unwrap().unwrap();
+        let mut members = membership.cluster.content_ids.clone();
+        members.sort();
+        let mut blockchain = ids[..4].to_vec();
+        blockchain.sort();
+        assert_eq!(members, blockchain);
+        assert!(!membership.neighbors.is_empty());
+        assert!(membership.neighbors.iter().all(|n| n.content_id != ids[0] && ids[1..4].contains(&n.content_id)));
+
+        assert!(analyzer.cluster_of(Uuid::new_v4()).await.unwrap().is_none());
+    }
+}
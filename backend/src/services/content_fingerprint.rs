@@ -0,0 +1,195 @@
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Number of MinHash permutations in a fingerprint
+pub const MINHASH_PERMUTATIONS: usize = 128;
+/// LSH bands; two fingerprints become candidates if any band matches exactly
+pub const LSH_BANDS: usize = 16;
+const ROWS_PER_BAND: usize = MINHASH_PERMUTATIONS / LSH_BANDS;
+/// Estimated Jaccard similarity above which content counts as a near-duplicate
+pub const DUPLICATE_SIMILARITY_THRESHOLD: f64 = 0.85;
+
+/// Characters per shingle
+const SHINGLE_SIZE: usize = 3;
+/// Mersenne prime modulus of the permutation hashes
+const MERSENNE_61: u64 = (1 << 61) - 1;
+/// Fingerprints are persisted, so the permutations must never change between releases
+const PERMUTATION_SEED: u64 = 0xEC40_1A7E_5EED_0001;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// MinHash signature of a content body
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentFingerprint {
+    pub minhash: Vec<u64>,
+}
+
+impl ContentFingerprint {
+    /// Estimated Jaccard similarity of the underlying shingle sets
+    pub fn similarity(&self, other: &ContentFingerprint) -> f64 {
+        let matching = self
+            .minhash
+            .iter()
+            .zip(&other.minhash)
+            .filter(|(a, b)| a == b)
+            .count();
+        matching as f64 / self.minhash.len().max(1) as f64
+    }
+
+    /// One hash per LSH band, salted with the band position so equal values in
+    /// different bands do not collide
+    pub fn band_hashes(&self) -> Vec<u64> {
+        self.minhash
+            .chunks(ROWS_PER_BAND)
+            .enumerate()
+            .map(|(band, rows)| {
+                let mut bytes = Vec::with_capacity(8 * (rows.len() + 1));
+                bytes.extend_from_slice(&(band as u64).to_le_bytes());
+                for row in rows {
+                    bytes.extend_from_slice(&row.to_le_bytes());
+                }
+                fnv1a(&bytes)
+            })
+            .collect()
+    }
+}
+
+/// Computes MinHash fingerprints of content bodies for near-duplicate detection
+pub struct ContentFingerprintService {
+    /// `(a, b)` of each permutation `h(x) = (a * x + b) mod p`
+    permutations: Vec<(u64, u64)>,
+}
+
+impl ContentFingerprintService {
+    pub fn new() -> Self {
+        let mut state = PERMUTATION_SEED;
+        let permutations = (0..MINHASH_PERMUTATIONS)
+            .map(|_| {
+                let a = splitmix64(&mut state) % (MERSENNE_61 - 1) + 1;
+                let b = splitmix64(&mut state) % MERSENNE_61;
+                (a, b)
+            })
+            .collect();
+
+        Self { permutations }
+    }
+
+    /// Fingerprint the character 3-shingles of `text` after normalizing case, punctuation
+    /// and whitespace. Text too short to shingle has no fingerprint.
+    pub fn fingerprint(&self, text: &str) -> Option<ContentFingerprint> {
+        let shingles = shingles(text);
+        if shingles.is_empty() {
+            return None;
+        }
+
+        let minhash = self
+            .permutations
+            .iter()
+            .map(|&(a, b)| {
+                shingles
+                    .iter()
+                    .map(|&shingle| permute(shingle, a, b))
+                    .min()
+                    .expect("shingles are not empty")
+            })
+            .collect();
+
+        Some(ContentFingerprint { minhash })
+    }
+
+    /// The most similar candidate above the duplicate threshold
+    pub fn best_duplicate(
+        &self,
+        fingerprint: &ContentFingerprint,
+        candidates: &[(Uuid, ContentFingerprint)],
+    ) -> Option<(Uuid, f64)> {
+        candidates
+            .iter()
+            .map(|(content_id, candidate)| (*content_id, fingerprint.similarity(candidate)))
+            .filter(|(_, similarity)| *similarity > DUPLICATE_SIMILARITY_THRESHOLD)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+impl Default for ContentFingerprintService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hashes of the distinct character shingles of normalized text
+fn shingles(text: &str) -> HashSet<u64> {
+    let normalized: Vec<char> = text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .collect();
+
+    normalized
+        .windows(SHINGLE_SIZE)
+        .map(|window| fnv1a(window.iter().collect::<String>().as_bytes()))
+        .collect()
+}
+
+fn permute(x: u64, a: u64, b: u64) -> u64 {
+    ((u128::from(a) * u128::from(x % MERSENNE_61) + u128::from(b)) % u128::from(MERSENNE_61)) as u64
+}
+
+/// FNV-1a, chosen over `DefaultHasher` because persisted hashes must be stable across builds
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = "Layer two rollups batch thousands of transactions off chain and post a single \
+        compressed proof to the base layer, which keeps fees low while inheriting the security of Ethereum.";
+    const PARAPHRASED: &str = "Layer two rollups batch thousands of transactions off-chain, then post a single \
+        compressed proof to the base layer; this keeps fees low while inheriting the security of Ethereum!";
+    const UNRELATED: &str = "My grandmother's sourdough starter is older than I am and still bubbles \
+        happily on the kitchen counter every morning.";
+
+    #[test]
+    fn test_paraphrase_is_near_duplicate() {
+        let service = ContentFingerprintService::new();
+        let original = service.fingerprint(ORIGINAL).unwrap();
+        let paraphrased = service.fingerprint(PARAPHRASED).unwrap();
+
+        assert!(original.similarity(&paraphrased) > DUPLICATE_SIMILARITY_THRESHOLD);
+        assert_eq!(
+            service.best_duplicate(&paraphrased, &[(Uuid::nil(), original.clone())]).map(|(id, _)| id),
+            Some(Uuid::nil())
+        );
+
+        // Near-duplicates share at least one LSH band
+        let shared = original.band_hashes().iter().zip(paraphrased.band_hashes()).any(|(a, b)| *a == b);
+        assert!(shared);
+    }
+
+    #[test]
+    fn test_unrelated_text_is_dissimilar() {
+        let service = ContentFingerprintService::new();
+        let original = service.fingerprint(ORIGINAL).unwrap();
+        let unrelated = service.fingerprint(UNRELATED).unwrap();
+
+        assert!(original.similarity(&unrelated) < 0.2);
+        assert!(service.best_duplicate(&unrelated, &[(Uuid::nil(), original)]).is_none());
+        assert!(service.fingerprint("?!").is_none());
+    }
+}
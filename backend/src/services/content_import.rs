@@ -0,0 +1,449 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::content_import::{ContentImportRequest, ImportFormat, ImportSummary, ImportedContent, ParsedExport};
+use crate::models::echo_index::{EchoIndexCalculator, QuoteMetrics};
+use crate::models::content::ReactionType;
+use crate::models::Platform;
+use crate::repositories::{ContentRepository, NewContent, RepositoryError};
+use crate::services::{ContentNormalizer, EchoService, EngineConfigStore, PlatformNormalizer};
+
+/// Shortest time between two content imports of the same user
+pub const IMPORT_INTERVAL_HOURS: i64 = 24;
+/// Longest external ID the content table holds
+const MAX_EXTERNAL_ID_LENGTH: usize = 255;
+/// Imported posts are titled with the start of their text
+const TITLE_LENGTH: usize = 100;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ContentImportError {
+    #[error("content may be imported once every {IMPORT_INTERVAL_HOURS} hours")]
+    ImportLimitReached,
+    #[error("{0}")]
+    InvalidExport(String),
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+/// Imports the posts users published before joining EchoLayer from their platform
+/// exports. With no propagation data to go on, imported posts are scored by their
+/// quality factor alone.
+pub struct ContentImportService {
+    content: Arc<ContentRepository>,
+    engine_config: Arc<EngineConfigStore>,
+}
+
+impl ContentImportService {
+    pub fn new(content: Arc<ContentRepository>, engine_config: Arc<EngineConfigStore>) -> Self {
+        Self { content, engine_config }
+    }
+
+    /// Import the posts of an export as the user's content, at most once every
+    /// `IMPORT_INTERVAL_HOURS`. Posts already stored for the platform are skipped.
+    pub async fn import(
+        &self,
+        user_id: Uuid,
+        request: &ContentImportRequest,
+        now: DateTime<Utc>,
+    ) -> Result<ImportSummary, ContentImportError> {
+        if let Some(platform) = request.format.platform().filter(|platform| *platform != request.platform) {
+            return Err(ContentImportError::InvalidExport(format!(
+                "a {:?} export holds {} content, not {}",
+                request.format, platform, request.platform
+            )));
+        }
+        if let Platform::Other(name) = &request.platform {
+            return Err(ContentImportError::InvalidExport(format!("Unknown platform: {}", name)));
+        }
+        let parsed = Self::parse(request.format, &request.payload)?;
+
+        // A malformed export does not use up the day's import
+        if !self.content.claim_import(user_id, now, now - Duration::hours(IMPORT_INTERVAL_HOURS)).await? {
+            return Err(ContentImportError::ImportLimitReached);
+        }
+
+        let language_factors = self.engine_config.load().config.language_normalization_factors.clone();
+        let mut summary = ImportSummary { failed: parsed.failed, ..ImportSummary::default() };
+        for item in parsed.content {
+            let Some(echo_index) = Self::quality_factor(&item, &request.platform, &language_factors).await else {
+                summary.failed += 1;
+                continue;
+            };
+            let content = NewContent {
+                user_id,
+                platform: request.platform.clone(),
+                title: item.text.chars().take(TITLE_LENGTH).collect(),
+                body: item.text.clone(),
+                external_id: item.external_id.clone(),
+                content_type: "text".to_string(),
+                media_urls: Vec::new(),
+                tags: Vec::new(),
+                reactions: Self::reactions(&item, &request.platform),
+            };
+            let metadata = serde_json::json!({
+                "imported": {"format": request.format, "reshares": item.reshares, "likes": item.likes}
+            });
+            if self.content.create_imported(&content, item.created_at, echo_index, &metadata).await? {
+                summary.imported += 1;
+            } else {
+                summary.skipped_duplicates += 1;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// The posts of an export in any format
+    pub fn parse(format: ImportFormat, payload: &Value) -> Result<ParsedExport, ContentImportError> {
+        match format {
+            ImportFormat::TwitterArchive => Self::parse_twitter_archive(payload),
+            ImportFormat::LinkedInDataExport => Self::parse_linkedin_export(payload),
+            ImportFormat::TelegramExport => Self::parse_telegram_export(payload),
+            ImportFormat::GenericCSV => Self::parse_generic_csv(payload),
+        }
+    }
+
+    /// Tweets of a Twitter archive's `tweet.js`: an array of `{ "tweet": { "id_str",
+    /// "full_text", "created_at", "retweet_count", "favorite_count" } }`. Retweets of
+    /// other users' tweets are left out.
+    pub fn parse_twitter_archive(payload: &Value) -> Result<ParsedExport, ContentImportError> {
+        let entries = payload
+            .as_array()
+            .ok_or_else(|| ContentImportError::InvalidExport("tweet.js must hold an array of tweets".to_string()))?;
+
+        let mut parsed = ParsedExport::default();
+        for entry in entries {
+            let tweet = entry.get("tweet").unwrap_or(entry);
+            let text = tweet["full_text"].as_str().unwrap_or_default();
+            if text.starts_with("RT @") {
+                continue;
+            }
+            let created_at = tweet["created_at"]
+                .as_str()
+                .and_then(|created_at| DateTime::parse_from_str(created_at, "%a %b %d %H:%M:%S %z %Y").ok())
+                .map(|created_at| created_at.with_timezone(&Utc));
+            match (tweet["id_str"].as_str(), created_at) {
+                (Some(id), Some(created_at)) => parsed.push(ImportedContent {
+                    external_id: id.to_string(),
+                    text: text.to_string(),
+                    created_at,
+                    reshares: count(&tweet["retweet_count"]),
+                    likes: count(&tweet["favorite_count"]),
+                }),
+                _ => parsed.failed += 1,
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Posts of a LinkedIn data export's `Shares.csv`, given as a string, with `Date`,
+    /// `ShareLink` and `ShareCommentary` columns. Shares without commentary of their own
+    /// are left out.
+    pub fn parse_linkedin_export(payload: &Value) -> Result<ParsedExport, ContentImportError> {
+        let mut parsed = ParsedExport::default();
+        for row in csv_rows(payload)? {
+            let Some(row) = row else {
+                parsed.failed += 1;
+                continue;
+            };
+            let text = row.get("ShareCommentary").map(|text| text.trim()).unwrap_or_default();
+            if text.is_empty() {
+                continue;
+            }
+            let created_at = row
+                .get("Date")
+                .and_then(|date| NaiveDateTime::parse_from_str(date.trim(), "%Y-%m-%d %H:%M:%S").ok())
+                .map(|date| date.and_utc());
+            match (row.get("ShareLink").map(|link| link.trim()), created_at) {
+                (Some(link), Some(created_at)) if !link.is_empty() => parsed.push(ImportedContent {
+                    external_id: link.to_string(),
+                    text: text.to_string(),
+                    created_at,
+                    reshares: 0,
+                    likes: 0,
+                }),
+                _ => parsed.failed += 1,
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Messages of a Telegram Desktop chat export's `result.json`. Service messages,
+    /// forwarded messages and messages without text are left out.
+    pub fn parse_telegram_export(payload: &Value) -> Result<ParsedExport, ContentImportError> {
+        let messages = payload["messages"]
+            .as_array()
+            .ok_or_else(|| ContentImportError::InvalidExport("result.json must hold a chat's messages".to_string()))?;
+        // Message IDs are only unique within a chat
+        let chat_id = payload["id"].to_string();
+
+        let mut parsed = ParsedExport::default();
+        for message in messages {
+            if message["type"] != "message" || message.get("forwarded_from").is_some() {
+                continue;
+            }
+            let text = telegram_text(&message["text"]);
+            if text.trim().is_empty() {
+                continue;
+            }
+            let created_at = message["date_unixtime"]
+                .as_str()
+                .and_then(|seconds| seconds.parse().ok())
+                .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+                .or_else(|| {
+                    let date = message["date"].as_str()?;
+                    Some(NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S").ok()?.and_utc())
+                });
+            match (message["id"].as_i64(), created_at) {
+                (Some(id), Some(created_at)) => parsed.push(ImportedContent {
+                    external_id: format!("{}/{}", chat_id, id),
+                    text,
+                    created_at,
+                    reshares: count(&message["forwards"]),
+                    likes: 0,
+                }),
+                _ => parsed.failed += 1,
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Posts of a CSV, given as a string, with `external_id`, `text` and `created_at` (RFC
+    /// 3339 or `YYYY-MM-DD`) columns, and optionally `reshares` and `likes`
+    pub fn parse_generic_csv(payload: &Value) -> Result<ParsedExport, ContentImportError> {
+        let mut parsed = ParsedExport::default();
+        for row in csv_rows(payload)? {
+            let Some(row) = row else {
+                parsed.failed += 1;
+                continue;
+            };
+            let created_at = row.get("created_at").map(|date| date.trim()).and_then(|date| {
+                DateTime::parse_from_rfc3339(date).map(|date| date.with_timezone(&Utc)).ok().or_else(|| {
+                    Some(NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0)?.and_utc())
+                })
+            });
+            let number = |column: &str| row.get(column).and_then(|value| value.trim().parse().ok()).unwrap_or(0);
+            match (row.get("external_id").map(|id| id.trim()), row.get("text"), created_at) {
+                (Some(id), Some(text), Some(created_at)) if !id.is_empty() && !text.trim().is_empty() => {
+                    parsed.push(ImportedContent {
+                        external_id: id.to_string(),
+                        text: text.trim().to_string(),
+                        created_at,
+                        reshares: number("reshares"),
+                        likes: number("likes"),
+                    })
+                }
+                _ => parsed.failed += 1,
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Retroactive Echo Index of an imported post: its quality factor, the larger of its
+    /// originality and its quote factor, with reshares as quotes and likes standing in for
+    /// the discussion the export does not record. None for posts without words to score.
+    async fn quality_factor(
+        item: &ImportedContent,
+        platform: &Platform,
+        language_factors: &HashMap<String, f64>,
+    ) -> Option<f64> {
+        let normalized = PlatformNormalizer::normalize(&item.text, platform);
+        let metrics = EchoService::analyze_content(&normalized, language_factors).await.ok()?;
+        if metrics.word_count == 0 {
+            return None;
+        }
+        let odf = EchoIndexCalculator::calculate_odf(&item.text, &metrics);
+        let qf = EchoIndexCalculator::calculate_qf(&QuoteMetrics {
+            direct_quotes: item.reshares.min(i32::MAX as u64) as i32,
+            indirect_references: 0,
+            discussion_threads: item.likes.min(i32::MAX as u64) as i32,
+            citation_quality: 0.0,
+        });
+        Some(odf.max(qf))
+    }
+
+    /// Likes and reshares the export recorded, as the platform's reactions
+    fn reactions(item: &ImportedContent, platform: &Platform) -> HashMap<ReactionType, u64> {
+        [(ReactionType::Like, item.likes), (ReactionType::Repost, item.reshares)]
+            .into_iter()
+            .filter(|(reaction, count)| *count > 0 && ReactionType::supported_on(platform).contains(reaction))
+            .collect()
+    }
+}
+
+impl ParsedExport {
+    /// Keep a post, or count it failed when its ID is too long to store
+    fn push(&mut self, content: ImportedContent) {
+        if content.external_id.len() > MAX_EXTERNAL_ID_LENGTH {
+            self.failed += 1;
+        } else {
+            self.content.push(content);
+        }
+    }
+}
+
+/// Counts exports write as numbers or as strings of digits
+fn count(value: &Value) -> u64 {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|count| count.parse().ok()))
+        .unwrap_or(0)
+}
+
+/// Telegram message text: a string, or an array of strings and `{ "text" }` entities
+fn telegram_text(text: &Value) -> String {
+    match text {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| part.as_str().or_else(|| part["text"].as_str()))
+            .collect(),
+        _ => String::new(),
+    }
+}
+
+/// Rows of a CSV string payload by column name, None for rows that could not be read
+fn csv_rows(payload: &Value) -> Result<Vec<Option<HashMap<String, String>>>, ContentImportError> {
+    let text = payload
+        .as_str()
+        .ok_or_else(|| ContentImportError::InvalidExport("CSV exports must be given as a string".to_string()))?;
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(text.as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|e| ContentImportError::InvalidExport(format!("CSV header could not be read: {}", e)))?
+        .clone();
+
+    Ok(reader
+        .records()
+        .map(|record| {
+            let record = record.ok()?;
+            Some(headers.iter().zip(record.iter()).map(|(name, value)| (name.to_string(), value.to_string())).collect())
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::EchoEngineConfig;
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    #[test]
+    fn test_parse_twitter_archive() {
+        let payload = ImportFormat::TwitterArchive
+            .payload_from_file(
+                br#"window.YTD.tweets.part0 = [
+                  {"tweet": {"id_str": "1001", "full_text": "Decentralized reputation is coming",
+                             "created_at": "Wed Oct 10 20:19:24 +0000 2018",
+                             "retweet_count": "12", "favorite_count": "40"}},
+                  {"tweet": {"id_str": "1002", "full_text": "RT @someone: not mine",
+                             "created_at": "Wed Oct 10 21:00:00 +0000 2018"}},
+                  {"tweet": {"id_str": "1003", "full_text": "No date"}}
+                ]"#,
+            )
+            .unwrap();
+
+        let parsed = ContentImportService::parse_twitter_archive(&payload).unwrap();
+        assert_eq!(parsed.failed, 1);
+        assert_eq!(
+            parsed.content,
+            vec![ImportedContent {
+                external_id: "1001".to_string(),
+                text: "Decentralized reputation is coming".to_string(),
+                created_at: "2018-10-10T20:19:24Z".parse().unwrap(),
+                reshares: 12,
+                likes: 40,
+            }]
+        );
+        assert!(ContentImportService::parse_twitter_archive(&json!({"tweet": {}})).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_exports() {
+        let shares = "Date,ShareLink,ShareCommentary,SharedUrl,MediaUrl,Visibility\n\
+                      2023-01-15 14:30:00,https://linkedin.com/share/1,\
+                      \"Hiring, \"\"again\"\"\nApply below\",,,MEMBER_NETWORK\n\
+                      2023-01-16 09:00:00,https://linkedin.com/share/2,,https://example.com,,MEMBER_NETWORK\n\
+                      yesterday,https://linkedin.com/share/3,Undated,,,MEMBER_NETWORK\n";
+        let parsed = ContentImportService::parse_linkedin_export(&Value::String(shares.to_string())).unwrap();
+        assert_eq!(parsed.failed, 1);
+        assert_eq!(parsed.content.len(), 1);
+        assert_eq!(parsed.content[0].external_id, "https://linkedin.com/share/1");
+        assert_eq!(parsed.content[0].text, "Hiring, \"again\"\nApply below");
+
+        let generic = "external_id,text,created_at,reshares\nabc,Long form essay,2024-03-01,5\n,No id,2024-03-01,\n";
+        let parsed = ContentImportService::parse_generic_csv(&Value::String(generic.to_string())).unwrap();
+        assert_eq!(parsed.failed, 1);
+        assert_eq!(parsed.content[0].created_at, "2024-03-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert_eq!((parsed.content[0].reshares, parsed.content[0].likes), (5, 0));
+    }
+
+    #[test]
+    fn test_parse_telegram_export() {
+        let payload = json!({
+            "name": "Channel", "type": "public_channel", "id": 777,
+            "messages": [
+                {"id": 1, "type": "service", "date": "2024-01-01T10:00:00", "text": ""},
+                {"id": 2, "type": "message", "date": "2024-01-01T11:00:00", "date_unixtime": "1704106800",
+                 "text": ["Read ", {"type": "link", "text": "https://echolayer.io"}, " today"]},
+                {"id": 3, "type": "message", "date": "2024-01-01T12:00:00", "forwarded_from": "Other",
+                 "text": "Forwarded"}
+            ]
+        });
+
+        let parsed = ContentImportService::parse_telegram_export(&payload).unwrap();
+        assert_eq!(parsed.failed, 0);
+        assert_eq!(parsed.content.len(), 1);
+        assert_eq!(parsed.content[0].external_id, "777/2");
+        assert_eq!(parsed.content[0].text, "Read https://echolayer.io today");
+        assert_eq!(parsed.content[0].created_at, "2024-01-01T11:00:00Z".parse::<DateTime<Utc>>().unwrap());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_import_skips_duplicates_once_a_day(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0ximporter') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let service = ContentImportService::new(
+            Arc::new(ContentRepository::new(pool.clone())),
+            Arc::new(EngineConfigStore::new(EchoEngineConfig::default(), "missing.toml")),
+        );
+        let csv = |rows: &str| ContentImportRequest {
+            platform: Platform::Medium,
+            format: ImportFormat::GenericCSV,
+            payload: Value::String(format!("external_id,text,created_at\n{}", rows)),
+        };
+        let now = Utc::now();
+
+        let first = csv("a,A thoughtful essay on decentralized media,2024-03-01\nb,Another essay,2024-03-02\n");
+        let summary = service.import(user_id, &first, now).await.unwrap();
+        assert_eq!(summary, ImportSummary { imported: 2, skipped_duplicates: 0, failed: 0 });
+        let (created_at, echo_index): (DateTime<Utc>, f64) = sqlx::query_as(
+            "SELECT created_at, echo_index::float8 FROM content WHERE external_id = 'a' AND platform = 'medium'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(created_at, "2024-03-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert!(echo_index > 0.0);
+
+        let second = csv("b,Another essay,2024-03-02\nc,A third essay,2024-03-03\nd,,2024-03-04\n");
+        assert!(matches!(
+            service.import(user_id, &second, now + Duration::hours(23)).await,
+            Err(ContentImportError::ImportLimitReached)
+        ));
+        let summary = service.import(user_id, &second, now + Duration::hours(24)).await.unwrap();
+        assert_eq!(summary, ImportSummary { imported: 1, skipped_duplicates: 1, failed: 1 });
+
+        let mismatched = ContentImportRequest { format: ImportFormat::TwitterArchive, ..first };
+        assert!(matches!(
+            service.import(user_id, &mismatched, now + Duration::hours(48)).await,
+            Err(ContentImportError::InvalidExport(_))
+        ));
+    }
+}
@@ -0,0 +1,400 @@
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+/// Tech topics embedded with the terms that signal them
+const TECH_TOPICS: &str = include_str!("vocabularies/tech_topics.json");
+/// Places embedded as written, capitalized
+const LOCATIONS: &str = include_str!("vocabularies/locations.json");
+
+/// Words that end an organization's name, e.g. `Solana Foundation`
+const ORGANIZATION_SUFFIXES: &[&str] = &["Inc", "Corp", "Foundation", "Protocol"];
+/// Capitalized words that are never part of a person's name
+const NON_NAME_WORDS: &[&str] = &[
+    "A", "An", "The", "This", "That", "My", "Our", "Your", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday",
+    "Saturday", "Sunday", "January", "February", "March", "April", "May", "June", "July", "August", "September",
+    "October", "November", "December",
+];
+/// Longest run of capitalized words taken as a person's name
+const MAX_NAME_WORDS: usize = 4;
+/// Most auto-tags kept per text
+pub const MAX_AUTO_TAGS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityType {
+    Person,
+    Organization,
+    Topic,
+    MonetaryAmount,
+    Location,
+}
+
+/// Entities named in a text and the tags they suggest for it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NlpResult {
+    /// Each distinct entity once, grouped by type in the order of `EntityType`, in order
+    /// of first appearance within a type
+    pub entities: Vec<(String, EntityType)>,
+    /// Lowercase tags of the topics, organizations and locations found, topics first.
+    /// People and amounts are left out: they say who or how much a text mentions, not
+    /// what it is about.
+    pub auto_tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TopicEntry {
+    tag: String,
+    name: String,
+    terms: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TopicList {
+    topics: Vec<TopicEntry>,
+}
+
+#[derive(Deserialize)]
+struct LocationList {
+    locations: Vec<String>,
+}
+
+/// A topic with its terms split into lowercase words
+struct Topic {
+    tag: String,
+    name: String,
+    terms: Vec<Vec<String>>,
+}
+
+struct Vocabulary {
+    topics: Vec<Topic>,
+    /// Place names split into words, as capitalized in the vocabulary
+    locations: Vec<Vec<String>>,
+}
+
+fn vocabulary() -> &'static Vocabulary {
+    static VOCABULARY: OnceLock<Vocabulary> = OnceLock::new();
+    VOCABULARY.get_or_init(|| {
+        let topics: TopicList = serde_json::from_str(TECH_TOPICS).expect("embedded vocabularies are valid JSON");
+        let locations: LocationList = serde_json::from_str(LOCATIONS).expect("embedded vocabularies are valid JSON");
+        Vocabulary {
+            topics: topics
+                .topics
+                .into_iter()
+                .map(|topic| Topic {
+                    tag: topic.tag,
+                    name: topic.name,
+                    terms: topic.terms.iter().map(|term| lowercase_words(term)).collect(),
+                })
+                .collect(),
+            locations: locations
+                .locations
+                .iter()
+                .map(|location| location.split_whitespace().map(str::to_string).collect())
+                .collect(),
+        }
+    })
+}
+
+/// A whitespace-separated word with its surrounding punctuation stripped
+#[derive(Debug)]
+struct Word<'a> {
+    text: &'a str,
+    /// First word of a sentence, so capitalized whatever it is
+    starts_sentence: bool,
+    /// Preceded by punctuation such as an opening bracket or quote
+    breaks_before: bool,
+    /// Followed by punctuation such as a comma or full stop
+    breaks_after: bool,
+}
+
+impl Word<'_> {
+    /// Starts with an uppercase letter and is not all caps, so `Vitalik` but not `ETH`
+    fn is_capitalized(&self) -> bool {
+        self.text.chars().next().is_some_and(char::is_uppercase) && self.text.chars().any(char::is_lowercase)
+    }
+
+    /// Digits with optional separators and a magnitude suffix, e.g. `1,000` or `2.5k`
+    fn is_number(&self) -> bool {
+        let digits = self.text.trim_end_matches(['k', 'K', 'm', 'M', 'b', 'B']);
+        digits.starts_with(|c: char| c.is_ascii_digit())
+            && digits.chars().all(|c| c.is_ascii_digit() || c == ',' || c == '.')
+    }
+}
+
+/// Finds people, organizations, topics, amounts and places named in text by pattern and
+/// dictionary matching, and derives topic tags from them
+pub struct ContentNLPPipeline;
+
+impl ContentNLPPipeline {
+    pub fn extract_entities(text: &str) -> NlpResult {
+        let vocabulary = vocabulary();
+        let words = words(text);
+        let lowercase = lowercase_words(text);
+
+        let (organizations, mut persons) = capitalized_names(&words);
+        let topics: Vec<&Topic> = topics(&lowercase, vocabulary);
+        let amounts = monetary_amounts(&words);
+        let locations = locations(&words, vocabulary);
+        // Places and topics spelled in capitals look like names too
+        persons.retain(|person| {
+            let lowercase_person = lowercase_words(person);
+            !locations.contains(person)
+                && !vocabulary.topics.iter().any(|topic| topic.terms.contains(&lowercase_person))
+        });
+
+        let mut result = NlpResult::default();
+        let typed = [
+            (persons, EntityType::Person),
+            (organizations.clone(), EntityType::Organization),
+            (topics.iter().map(|topic| topic.name.clone()).collect(), EntityType::Topic),
+            (amounts, EntityType::MonetaryAmount),
+            (locations.clone(), EntityType::Location),
+        ];
+        for (names, entity_type) in typed {
+            for name in names {
+                let entity = (name, entity_type);
+                if !result.entities.contains(&entity) {
+                    result.entities.push(entity);
+                }
+            }
+        }
+
+        let tags = topics
+            .iter()
+            .map(|topic| topic.tag.clone())
+            .chain(organizations.iter().chain(&locations).map(|name| tag_of(name)));
+        for tag in tags {
+            if result.auto_tags.len() == MAX_AUTO_TAGS {
+                break;
+            }
+            if !tag.is_empty() && !result.auto_tags.contains(&tag) {
+                result.auto_tags.push(tag);
+            }
+        }
+        result
+    }
+}
+
+/// Words of `text`, with where sentences and phrases break. Lines break sentences too,
+/// so a title ends before the body starts.
+fn words(text: &str) -> Vec<Word<'_>> {
+    let mut words = Vec::new();
+    for line in text.lines() {
+        let mut starts_sentence = true;
+        for raw in line.split_whitespace() {
+            // `$`, `#` and `@` stay, so amounts, hashtags and handles can be told apart
+            let start = raw.trim_start_matches(|c: char| !c.is_alphanumeric() && !matches!(c, '$' | '#' | '@'));
+            let text = start.trim_end_matches(|c: char| !c.is_alphanumeric());
+            if text.is_empty() {
+                continue;
+            }
+            let trailing = &start[text.len()..];
+            words.push(Word {
+                text,
+                starts_sentence,
+                breaks_before: start.len() < raw.len(),
+                breaks_after: !trailing.is_empty(),
+            });
+            starts_sentence = trailing.contains(['.', '!', '?']);
+        }
+    }
+    words
+}
+
+/// Lowercase alphanumeric words of `text`, so `#DeFi` and `layer-2` match `defi` and
+/// `layer 2`
+fn lowercase_words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Organizations and people among runs of capitalized words. A run up to an
+/// organization suffix names an organization; what remains of it names a person when at
+/// least two words long once a sentence's capitalized first word is dropped.
+fn capitalized_names(words: &[Word]) -> (Vec<String>, Vec<String>) {
+    let mut organizations = Vec::new();
+    let mut persons = Vec::new();
+    let mut run: Vec<&Word> = Vec::new();
+
+    let mut end_run = |run: &mut Vec<&Word>| {
+        let mut name: Vec<&Word> = Vec::new();
+        for word in run.drain(..) {
+            if ORGANIZATION_SUFFIXES.contains(&word.text) && !name.is_empty() {
+                name.push(word);
+                organizations.push(join(&name));
+                name.clear();
+            } else {
+                name.push(word);
+            }
+        }
+        if name.first().is_some_and(|word| word.starts_sentence) {
+            name.remove(0);
+        }
+        if (2..=MAX_NAME_WORDS).contains(&name.len()) {
+            persons.push(join(&name));
+        }
+    };
+
+    for word in words {
+        let joins_run = word.is_capitalized() && !NON_NAME_WORDS.contains(&word.text);
+        if !joins_run || word.breaks_before {
+            end_run(&mut run);
+        }
+        if joins_run {
+            run.push(word);
+            if word.breaks_after {
+                end_run(&mut run);
+            }
+        }
+    }
+    end_run(&mut run);
+
+    (organizations, persons)
+}
+
+fn join(words: &[&Word]) -> String {
+    words.iter().map(|word| word.text).collect::<Vec<_>>().join(" ")
+}
+
+/// Topics any of whose terms occur in the lowercase words, in order of first appearance
+fn topics<'v>(lowercase: &[String], vocabulary: &'v Vocabulary) -> Vec<&'v Topic> {
+    let mut found: Vec<(usize, &Topic)> = vocabulary
+        .topics
+        .iter()
+        .filter_map(|topic| {
+            topic
+                .terms
+                .iter()
+                .filter_map(|term| position(lowercase, term, |word, term| word == term))
+                .min()
+                .map(|first| (first, topic))
+        })
+        .collect();
+    found.sort_by_key(|(first, _)| *first);
+    found.into_iter().map(|(_, topic)| topic).collect()
+}
+
+/// Places of the vocabulary written as they are there, capitalized, in order of first
+/// appearance
+fn locations(words: &[Word], vocabulary: &Vocabulary) -> Vec<String> {
+    let mut found: Vec<(usize, String)> = vocabulary
+        .locations
+        .iter()
+        .filter_map(|location| {
+            position(words, location, |word, name| word.text == *name).map(|first| (first, location.join(" ")))
+        })
+        .collect();
+    found.sort_by_key(|(first, _)| *first);
+    found.into_iter().map(|(_, location)| location).collect()
+}
+
+/// Index of the first run of `haystack` matching `phrase` word by word
+fn position<T, P>(haystack: &[T], phrase: &[P], matches: impl Fn(&T, &P) -> bool) -> Option<usize> {
+    if phrase.is_empty() {
+        return None;
+    }
+    haystack
+        .windows(phrase.len())
+        .position(|window| window.iter().zip(phrase).all(|(word, term)| matches(word, term)))
+}
+
+/// Dollar amounts such as `$1,000` or `$2.5M`, and token amounts such as `500 tokens`
+fn monetary_amounts(words: &[Word]) -> Vec<String> {
+    let mut amounts = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        if let Some(dollars) = word.text.strip_prefix('$') {
+            if dollars.starts_with(|c: char| c.is_ascii_digit()) {
+                amounts.push(word.text.to_string());
+            }
+            continue;
+        }
+        let Some(unit) = words.get(i + 1) else {
+            continue;
+        };
+        if word.is_number()
+            && !word.breaks_after
+            && (unit.text.eq_ignore_ascii_case("token") || unit.text.eq_ignore_ascii_case("tokens"))
+        {
+            amounts.push(format!("{} {}", word.text, unit.text));
+        }
+    }
+    amounts
+}
+
+/// A name as a tag: its letters and digits, lowercased, like an indexed hashtag
+fn tag_of(name: &str) -> String {
+    name.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn of_type(result: &NlpResult, entity_type: EntityType) -> Vec<&str> {
+        result
+            .entities
+            .iter()
+            .filter(|(_, t)| *t == entity_type)
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_vocabularies_parse() {
+        let vocabulary = vocabulary();
+        assert!(!vocabulary.topics.is_empty());
+        assert!(!vocabulary.locations.is_empty());
+        assert!(vocabulary.topics.iter().all(|topic| topic.terms.iter().all(|term| !term.is_empty())));
+    }
+
+    #[test]
+    fn test_extracts_each_entity_type() {
+        let result = ContentNLPPipeline::extract_entities(
+            "Big week for rollups. Yesterday Vitalik Buterin met the Ethereum Foundation in Singapore, \
+             and Acme Corp pledged $2.5M plus 10,000 tokens to zero-knowledge research.",
+        );
+
+        assert_eq!(of_type(&result, EntityType::Person), vec!["Vitalik Buterin"]);
+        assert_eq!(of_type(&result, EntityType::Organization), vec!["Ethereum Foundation", "Acme Corp"]);
+        assert_eq!(of_type(&result, EntityType::Topic), vec!["Layer 2", "Ethereum", "Zero Knowledge"]);
+        assert_eq!(of_type(&result, EntityType::MonetaryAmount), vec!["$2.5M", "10,000 tokens"]);
+        assert_eq!(of_type(&result, EntityType::Location), vec!["Singapore"]);
+        assert_eq!(
+            result.auto_tags,
+            vec!["layer2", "ethereum", "zk", "ethereumfoundation", "acmecorp", "singapore"]
+        );
+    }
+
+    #[test]
+    fn test_sentence_openers_are_not_names() {
+        let result = ContentNLPPipeline::extract_entities("Great thread.\nThanks Alice for sharing. Bob Smith agreed.");
+        assert!(of_type(&result, EntityType::Person).is_empty());
+
+        let result = ContentNLPPipeline::extract_entities("I asked Ada Lovelace, then Grace Hopper (via DM).");
+        assert_eq!(of_type(&result, EntityType::Person), vec!["Ada Lovelace", "Grace Hopper"]);
+    }
+
+    #[test]
+    fn test_places_and_topics_are_not_people() {
+        let result = ContentNLPPipeline::extract_entities("Builders from New York love Zero Knowledge proofs");
+        assert!(of_type(&result, EntityType::Person).is_empty());
+        assert_eq!(of_type(&result, EntityType::Location), vec!["New York"]);
+        assert_eq!(result.auto_tags, vec!["zk", "newyork"]);
+    }
+
+    #[test]
+    fn test_topics_match_hashtags_and_cashtags() {
+        let result = ContentNLPPipeline::extract_entities("Bullish on #DeFi and $SOL, bearish on layer-2 fees");
+        assert_eq!(result.auto_tags, vec!["defi", "solana", "layer2"]);
+        // A cashtag is not an amount
+        assert!(of_type(&result, EntityType::MonetaryAmount).is_empty());
+    }
+
+    #[test]
+    fn test_plain_text_has_no_entities() {
+        assert_eq!(ContentNLPPipeline::extract_entities("just vibing today"), NlpResult::default());
+        assert_eq!(ContentNLPPipeline::extract_entities(""), NlpResult::default());
+    }
+}
@@ -0,0 +1,273 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::models::Platform;
+
+/// Placeholder left in `clean_text` where a URL was removed
+pub const URL_PLACEHOLDER: &str = "<url>";
+
+/// Characters that open a bold, italic, strikethrough or spoiler span in chat markdown
+const MARKDOWN_MARKERS: &[char] = &['*', '_', '~', '|', '`'];
+
+/// Punctuation that may trail a URL, mention or hashtag without being part of it
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '"', '\''];
+
+/// Platform text reduced to prose, with the markup that was removed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NormalizedContent {
+    /// Text with URLs replaced by `<url>`, and mentions, hashtags, emoji and markup removed
+    pub clean_text: String,
+    /// Mentioned handles, without the `@`. LinkedIn-style `firstname.lastname` handles
+    /// keep their dots.
+    pub mentions: Vec<String>,
+    /// Hashtags in order of first use, without the `#`
+    pub hashtags: Vec<String>,
+    pub urls: Vec<String>,
+    pub emoji_count: u32,
+}
+
+/// Turns raw platform text into something the text analysis can score
+pub trait ContentNormalizer {
+    fn normalize(raw: &str, platform: &Platform) -> NormalizedContent;
+
+    /// Every URL in `raw` in order of appearance, including ones wrapped in markup such as
+    /// `[docs](https://example.com)` that `normalize` keeps as text
+    fn extract_urls(raw: &str) -> Vec<String> {
+        static URL: OnceLock<Regex> = OnceLock::new();
+        let url = URL.get_or_init(|| {
+            Regex::new(r#"(?i)(?:https?://|www\.|\bt\.co/)[^\s<>"\[\]]+"#).expect("valid URL pattern")
+        });
+
+        url.find_iter(raw)
+            .map(|found| found.as_str().trim_end_matches(TRAILING_PUNCTUATION))
+            .filter(|candidate| is_url(candidate))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Normalizer for the markup each supported platform uses
+pub struct PlatformNormalizer;
+
+impl ContentNormalizer for PlatformNormalizer {
+    fn normalize(raw: &str, platform: &Platform) -> NormalizedContent {
+        // Chat platforms format with markdown; elsewhere '*' and '_' are plain text
+        let strip_markdown = matches!(platform, Platform::Telegram | Platform::Discord | Platform::Reddit);
+
+        let mut normalized = NormalizedContent::default();
+        let mut paragraphs = Vec::new();
+
+        // Paragraph breaks (LinkedIn posts, long-form) separate sentences even without punctuation
+        let raw_paragraphs = split_paragraphs(raw);
+        for (i, paragraph) in raw_paragraphs.iter().enumerate() {
+            let mut words = Vec::new();
+            for token in paragraph.split_whitespace() {
+                let token = strip_emoji(token, &mut normalized.emoji_count);
+                let token = if strip_markdown { strip_markdown_markers(&token) } else { token };
+                normalized.extract_markup(&token, &mut words);
+            }
+
+            let Some(last) = words.last_mut() else {
+                continue;
+            };
+            if i + 1 < raw_paragraphs.len() && last.ends_with(|c: char| c.is_alphanumeric()) {
+                last.push('.');
+            }
+            paragraphs.push(words.join(" "));
+        }
+
+        normalized.clean_text = paragraphs.join(" ");
+        normalized
+    }
+}
+
+impl NormalizedContent {
+    /// Record any URL, mention or hashtag in `token` and push what stays in the clean text
+    fn extract_markup(&mut self, token: &str, words: &mut Vec<String>) {
+        let leading = token.len() - token.trim_start_matches(['(', '[', '"', '\'']).len();
+        let (prefix, rest) = token.split_at(leading);
+        let body = rest.trim_end_matches(TRAILING_PUNCTUATION);
+        let suffix = &rest[body.len()..];
+
+        if is_url(body) {
+            self.urls.push(body.to_string());
+            words.push(format!("{}{}{}", prefix, URL_PLACEHOLDER, suffix));
+            return;
+        }
+
+        if let Some(handle) = body.strip_prefix('@').filter(|handle| is_mention(handle)) {
+            self.mentions.push(handle.to_string());
+        } else if let Some(tag) = body
+            .strip_prefix('#')
+            .filter(|tag| is_handle(tag) && tag.chars().any(char::is_alphabetic))
+        {
+            if !self.hashtags.iter().any(|seen| seen.eq_ignore_ascii_case(tag)) {
+                self.hashtags.push(tag.to_string());
+            }
+        } else {
+            if !token.is_empty() {
+                words.push(token.to_string());
+            }
+            return;
+        }
+
+        // A sentence ending after a removed mention or hashtag still ends the sentence
+        let ending: String = suffix.chars().filter(|c| matches!(c, '.' | '!' | '?')).collect();
+        if let Some(last) = words.last_mut() {
+            last.push_str(&ending);
+        }
+    }
+}
+
+fn split_paragraphs(raw: &str) -> Vec<String> {
+    let mut paragraphs = vec![String::new()];
+    for line in raw.lines() {
+        if line.trim().is_empty() {
+            paragraphs.push(String::new());
+        } else if let Some(current) = paragraphs.last_mut() {
+            current.push(' ');
+            current.push_str(line);
+        }
+    }
+    paragraphs.retain(|paragraph| !paragraph.trim().is_empty());
+    paragraphs
+}
+
+/// Drop markdown markers around a word ("**bold**:", "_italic_.") but not inside it ("snake_case")
+fn strip_markdown_markers(token: &str) -> String {
+    let Some(first) = token.find(|c: char| c.is_alphanumeric()) else {
+        return token.replace(MARKDOWN_MARKERS, "");
+    };
+    let last = token.rfind(|c: char| c.is_alphanumeric()).unwrap_or(first);
+    let end = last + token[last..].chars().next().map_or(1, char::len_utf8);
+
+    format!(
+        "{}{}{}",
+        token[..first].replace(MARKDOWN_MARKERS, ""),
+        &token[first..end],
+        token[end..].replace(MARKDOWN_MARKERS, ""),
+    )
+}
+
+fn is_url(token: &str) -> bool {
+    let lowercase = token.to_ascii_lowercase();
+    ["http://", "https://", "www.", "t.co/"]
+        .iter()
+        .any(|scheme| lowercase.starts_with(scheme) && lowercase.len() > scheme.len())
+}
+
+fn is_handle(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// A handle, or handles joined by single dots as in `@jane.doe`
+fn is_mention(name: &str) -> bool {
+    name.split('.').all(is_handle)
+}
+
+/// Remove emoji from a token, counting each pictograph once. Skin tone modifiers,
+/// variation selectors and zero-width joiners belong to the emoji before them.
+fn strip_emoji(token: &str, emoji_count: &mut u32) -> String {
+    let mut kept = String::with_capacity(token.len());
+    let mut joined = false;
+    let mut pending_flag = false;
+
+    for c in token.chars() {
+        match c as u32 {
+            // Zero-width joiner: the next pictograph is part of the same emoji
+            0x200D => joined = true,
+            // Variation selectors, skin tones and tag characters
+            0xFE00..=0xFE0F | 0x1F3FB..=0x1F3FF | 0xE0020..=0xE007F => {}
+            // Regional indicators pair up into a single flag
+            0x1F1E6..=0x1F1FF => {
+                if !pending_flag {
+                    *emoji_count += 1;
+                }
+                pending_flag = !pending_flag;
+            }
+            0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF => {
+                if !joined {
+                    *emoji_count += 1;
+                }
+                joined = false;
+            }
+            _ => {
+                joined = false;
+                pending_flag = false;
+                kept.push(c);
+            }
+        }
+    }
+
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tweet_with_emoji_and_url() {
+        let normalized = PlatformNormalizer::normalize(
+            "Shipping day 🚀🚀 thanks @alice_dev and @bob! Read more https://t.co/AbC123 #Rust #rust #buildinpublic 👩‍💻",
+            &Platform::Twitter,
+        );
+
+        assert_eq!(normalized.clean_text, "Shipping day thanks and! Read more <url>");
+        assert_eq!(normalized.mentions, vec!["alice_dev", "bob"]);
+        assert_eq!(normalized.hashtags, vec!["Rust", "buildinpublic"]);
+        assert_eq!(normalized.urls, vec!["https://t.co/AbC123"]);
+        assert_eq!(normalized.emoji_count, 3);
+    }
+
+    #[test]
+    fn test_linkedin_mentions_keep_their_dots() {
+        let normalized = PlatformNormalizer::normalize(
+            "Great panel with @jane.doe and @john_smith. Thanks @acme..corp!",
+            &Platform::LinkedIn,
+        );
+
+        assert_eq!(normalized.mentions, vec!["jane.doe", "john_smith"]);
+        assert_eq!(normalized.clean_text, "Great panel with and. Thanks @acme..corp!");
+    }
+
+    #[test]
+    fn test_urls_are_extracted_from_markup() {
+        let urls = PlatformNormalizer::extract_urls(
+            "See [the docs](https://docs.example.com/guide?lang=en), www.example.org and \
+             (HTTP://Mirror.example.net/page). Not a link: https:// or www.",
+        );
+        assert_eq!(
+            urls,
+            ["https://docs.example.com/guide?lang=en", "www.example.org", "HTTP://Mirror.example.net/page"]
+        );
+        assert!(PlatformNormalizer::extract_urls("Layer two rollups keep fees low.").is_empty());
+    }
+
+    #[test]
+    fn test_telegram_markdown_is_stripped() {
+        let normalized = PlatformNormalizer::normalize(
+            "**Big news:** the __testnet__ is *live*.\n\n~~Old~~ docs moved to `docs_v2`",
+            &Platform::Telegram,
+        );
+
+        assert_eq!(normalized.clean_text, "Big news: the testnet is live. Old docs moved to docs_v2");
+        assert!(normalized.mentions.is_empty() && normalized.urls.is_empty());
+
+        // Outside chat platforms the markers are left alone
+        let tweet = PlatformNormalizer::normalize("2*3 = 6 *not* a snake_case_name", &Platform::Twitter);
+        assert_eq!(tweet.clean_text, "2*3 = 6 *not* a snake_case_name");
+    }
+
+    #[test]
+    fn test_plain_text_is_unchanged() {
+        let text = "Layer two rollups keep fees low. They inherit the security of Ethereum.";
+        let normalized = PlatformNormalizer::normalize(text, &Platform::Medium);
+
+        assert_eq!(normalized, NormalizedContent { clean_text: text.to_string(), ..Default::default() });
+
+        // Paragraph breaks end a sentence even without punctuation
+        let post = PlatformNormalizer::normalize("Hiring update\n\nWe are growing the team", &Platform::LinkedIn);
+        assert_eq!(post.clean_text, "Hiring update. We are growing the team");
+    }
+}
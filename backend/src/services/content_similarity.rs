@@ -0,0 +1,206 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::content::ContentRecord;
+use crate::repositories::{ContentTfIdfRepository, RepositoryError};
+use crate::services::content_normalizer::URL_PLACEHOLDER;
+use crate::services::{ContentNormalizer, PlatformNormalizer};
+
+/// Sparse TF-IDF vector of unit length, keyed by term
+pub type TfIdfVector = HashMap<String, f64>;
+
+/// Heaviest terms kept per vector; the long tail barely moves cosine similarity
+pub const MAX_VECTOR_TERMS: usize = 100;
+/// Heaviest terms of the target looked up in the inverted index to find candidates
+const CANDIDATE_QUERY_TERMS: usize = 20;
+/// Occurrences each auto-tag counts as, so a shared topic outweighs a shared word
+const AUTO_TAG_TERM_COUNT: u32 = 2;
+
+/// Words too common to say anything about what a text is about
+const STOPWORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "been", "but", "by",
+    "can", "could", "did", "do", "does", "for", "from", "had", "has", "have", "he", "her", "his", "how",
+    "i", "if", "in", "into", "is", "it", "its", "just", "me", "more", "my", "no", "not", "of", "on", "one",
+    "or", "our", "out", "she", "so", "some", "than", "that", "the", "their", "them", "then", "there",
+    "these", "they", "this", "to", "up", "us", "was", "we", "were", "what", "when", "which", "who", "will",
+    "with", "would", "you", "your",
+];
+
+/// Finds related content by cosine similarity of TF-IDF vectors over normalized text
+pub struct ContentSimilarityService {
+    repository: Arc<ContentTfIdfRepository>,
+}
+
+impl ContentSimilarityService {
+    pub fn new(repository: Arc<ContentTfIdfRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Compute and store the vector of a created or updated content item, over the words
+    /// of its body and its auto-tags. Document frequencies come from the content indexed so
+    /// far, so early vectors weigh terms against a smaller corpus until the content is next
+    /// updated.
+    pub async fn index(&self, content: &ContentRecord) -> Result<(), RepositoryError> {
+        let clean_text = PlatformNormalizer::normalize(&content.body, &content.platform).clean_text;
+        let mut counts = term_counts(&clean_text);
+        for tag in &content.auto_tags {
+            counts.insert(auto_tag_term(tag), AUTO_TAG_TERM_COUNT);
+        }
+        if counts.is_empty() {
+            return self.repository.remove(content.id).await;
+        }
+
+        let terms: Vec<String> = counts.keys().cloned().collect();
+        let (documents, frequencies) = self.repository.document_frequencies(content.id, &terms).await?;
+        self.repository.store(content.id, &tfidf(&counts, documents, &frequencies)).await
+    }
+
+    /// Up to `k` other live content items most similar to `content_id`, most similar
+    /// first. Content by the same author is left out unless `include_own` is set.
+    /// `NotFound` if `content_id` has not been indexed.
+    pub async fn find_similar(
+        &self,
+        content_id: Uuid,
+        k: usize,
+        include_own: bool,
+    ) -> Result<Vec<(Uuid, f64)>, RepositoryError> {
+        let target = self.repository.find(content_id).await?;
+        let query_terms = heaviest_terms(&target, CANDIDATE_QUERY_TERMS);
+        let candidates = self.repository.candidates(content_id, &query_terms, include_own).await?;
+
+        Ok(rank_similar(&target, &candidates, k))
+    }
+}
+
+/// Occurrences of each indexable term: lowercase words of two or more characters that
+/// are not stopwords or plain numbers
+pub fn term_counts(clean_text: &str) -> HashMap<String, u32> {
+    let mut counts = HashMap::new();
+    for word in clean_text.replace(URL_PLACEHOLDER, " ").split(|c: char| !c.is_alphanumeric()) {
+        let word = word.to_lowercase();
+        if word.chars().count() < 2 || word.chars().all(|c| c.is_numeric()) || STOPWORDS.contains(&word.as_str()) {
+            continue;
+        }
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Term an auto-tag is indexed under, apart from the same word in the text
+pub fn auto_tag_term(tag: &str) -> String {
+    format!("tag:{}", tag)
+}
+
+/// TF-IDF vector of a document with these term counts, among `documents` other documents
+/// of which `frequencies[term]` contain the term. Uses sublinear term frequency and
+/// smoothed IDF, keeps the `MAX_VECTOR_TERMS` heaviest terms and scales to unit length.
+pub fn tfidf(counts: &HashMap<String, u32>, documents: i64, frequencies: &HashMap<String, i64>) -> TfIdfVector {
+    // The document itself is part of the corpus
+    let documents = (documents + 1) as f64;
+    let mut weights: Vec<(String, f64)> = counts
+        .iter()
+        .map(|(term, &count)| {
+            let frequency = (frequencies.get(term).copied().unwrap_or(0) + 1) as f64;
+            let tf = 1.0 + (count as f64).ln();
+            let idf = ((1.0 + documents) / (1.0 + frequency)).ln() + 1.0;
+            (term.clone(), tf * idf)
+        })
+        .collect();
+
+    weights.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+    weights.truncate(MAX_VECTOR_TERMS);
+
+    let norm = weights.iter().map(|(_, weight)| weight * weight).sum::<f64>().sqrt();
+    weights.into_iter().map(|(term, weight)| (term, weight / norm)).collect()
+}
+
+/// Cosine similarity of two vectors, 0 when either is empty
+pub fn cosine_similarity(a: &TfIdfVector, b: &TfIdfVector) -> f64 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    let dot: f64 = small.iter().filter_map(|(term, weight)| large.get(term).map(|other| weight * other)).sum();
+    let norms = a.values().map(|w| w * w).sum::<f64>().sqrt() * b.values().map(|w| w * w).sum::<f64>().sqrt();
+
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+/// The `k` candidates most similar to `target`, leaving out those sharing no terms
+pub fn rank_similar(target: &TfIdfVector, candidates: &[(Uuid, TfIdfVector)], k: usize) -> Vec<(Uuid, f64)> {
+    let mut ranked: Vec<(Uuid, f64)> = candidates
+        .iter()
+        .map(|(id, vector)| (*id, cosine_similarity(target, vector)))
+        .filter(|(_, similarity)| *similarity > 0.0)
+        .collect();
+
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(k);
+    ranked
+}
+
+/// The `n` terms of a vector with the greatest weight, heaviest first
+pub fn heaviest_terms(vector: &TfIdfVector, n: usize) -> Vec<String> {
+    let mut terms: Vec<(&String, f64)> = vector.iter().map(|(term, weight)| (term, *weight)).collect();
+    terms.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal).then_with(|| a.0.cmp(b.0)));
+    terms.into_iter().take(n).map(|(term, _)| term.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Vectors of a small corpus, each weighed against all the others
+    fn vectorize(corpus: &[&str]) -> Vec<TfIdfVector> {
+        let counts: Vec<HashMap<String, u32>> = corpus.iter().map(|text| term_counts(text)).collect();
+        counts
+            .iter()
+            .enumerate()
+            .map(|(i, document)| {
+                let mut frequencies = HashMap::new();
+                for (_, other) in counts.iter().enumerate().filter(|(j, _)| *j != i) {
+                    for term in other.keys().filter(|term| document.contains_key(*term)) {
+                        *frequencies.entry(term.clone()).or_insert(0) += 1;
+                    }
+                }
+                tfidf(document, counts.len() as i64 - 1, &frequencies)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_blockchain_posts_are_more_similar_than_cooking() {
+        let vectors = vectorize(&[
+            "Ethereum validators stake ETH to secure the blockchain and earn rewards for proposing blocks.",
+            "Solana processes blockchain transactions quickly, and validators vote on blocks every slot.",
+            "Slow roast the chicken with garlic and rosemary, then rest it before carving for the table.",
+            "Knead the bread dough until smooth and let it rise somewhere warm for an hour.",
+        ]);
+
+        let blockchain = cosine_similarity(&vectors[0], &vectors[1]);
+        let cooking = cosine_similarity(&vectors[0], &vectors[2]);
+        assert!(blockchain > cooking, "{} <= {}", blockchain, cooking);
+        assert_eq!(cooking, 0.0);
+
+        let ids: Vec<Uuid> = (0..vectors.len()).map(|_| Uuid::new_v4()).collect();
+        let candidates: Vec<(Uuid, TfIdfVector)> = ids[1..].iter().copied().zip(vectors[1..].iter().cloned()).collect();
+        let related = rank_similar(&vectors[0], &candidates, 10);
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].0, ids[1]);
+    }
+
+    #[test]
+    fn test_vectors_are_unit_length() {
+        let counts = term_counts("Rollups rollups ROLLUPS batch transactions. Read more <url> in 2024 and the docs");
+        assert_eq!(counts.get("rollups"), Some(&3));
+        assert!(!counts.contains_key("url") && !counts.contains_key("2024") && !counts.contains_key("the"));
+
+        let vector = tfidf(&counts, 0, &HashMap::new());
+        assert!((cosine_similarity(&vector, &vector) - 1.0).abs() < 1e-9);
+        assert!((vector.values().map(|w| w * w).sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(tfidf(&HashMap::new(), 0, &HashMap::new()).is_empty());
+    }
+}
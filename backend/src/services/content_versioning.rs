@@ -0,0 +1,160 @@
+use chrono::Duration;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::content_version::{ContentVersion, VersionDiff};
+use crate::repositories::{ContentRepository, ContentVersionRepository, RepositoryError};
+
+/// Echo Index points content may lose within `EDIT_REVIEW_WINDOW_HOURS` of an edit
+/// before the edit is flagged for moderation
+pub const EDIT_DROP_THRESHOLD: f64 = 20.0;
+pub const EDIT_REVIEW_WINDOW_HOURS: i64 = 1;
+
+/// Previous versions of a content item and the number of its live version
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VersionHistory {
+    pub content_id: Uuid,
+    pub current_version: i32,
+    /// Newest first
+    pub versions: Vec<ContentVersion>,
+}
+
+/// Audits edits to content. Every edit keeps the title and body it replaced as a
+/// numbered version, and edits after which the Echo Index fell sharply are flagged
+/// for moderators, as they may retroactively change what high-echo content says.
+pub struct ContentVersioningService {
+    versions: Arc<ContentVersionRepository>,
+    content: Arc<ContentRepository>,
+}
+
+impl ContentVersioningService {
+    pub fn new(versions: Arc<ContentVersionRepository>, content: Arc<ContentRepository>) -> Self {
+        Self { versions, content }
+    }
+
+    /// `NotFound` if the content does not exist or was deleted
+    pub async fn history(&self, content_id: Uuid) -> Result<VersionHistory, RepositoryError> {
+        self.content.find_by_id(content_id).await?;
+        let versions = self.versions.list(content_id).await?;
+        let current_version = versions.first().map_or(1, |version| version.version_number + 1);
+        Ok(VersionHistory { content_id, current_version, versions })
+    }
+
+    /// A previous version. `NotFound` if there is no such version; the live version is
+    /// the content itself.
+    pub async fn version(&self, content_id: Uuid, version_number: i32) -> Result<ContentVersion, RepositoryError> {
+        self.content.find_by_id(content_id).await?;
+        self.versions.find(content_id, version_number).await
+    }
+
+    /// Line diff of the bodies of two versions, either of which may be the live one
+    pub async fn diff(&self, content_id: Uuid, from: i32, to: i32) -> Result<VersionDiff, RepositoryError> {
+        let live = self.content.find_by_id(content_id).await?;
+        let current_version = self.versions.latest_version_number(content_id).await? + 1;
+
+        let mut texts = Vec::with_capacity(2);
+        for version_number in [from, to] {
+            if version_number == current_version {
+                texts.push((live.title.clone(), live.body.clone()));
+            } else {
+                let version = self.versions.find(content_id, version_number).await?;
+                texts.push((version.title, version.body));
+            }
+        }
+
+        Ok(VersionDiff::new(
+            content_id,
+            (from, &texts[0].0, &texts[0].1),
+            (to, &texts[1].0, &texts[1].1),
+        ))
+    }
+
+    /// Flag edits the Echo Index fell sharply after, returning the newly flagged ones
+    pub async fn review_edits(&self) -> Result<Vec<ContentVersion>, RepositoryError> {
+        let flagged = self
+            .versions
+            .flag_drops_after_edits(Duration::hours(EDIT_REVIEW_WINDOW_HOURS), EDIT_DROP_THRESHOLD)
+            .await?;
+        for version in &flagged {
+            log::warn!(
+                "Flagged edit {} of content {} for review: Echo Index fell more than {} points after it",
+                version.version_number,
+                version.content_id,
+                EDIT_DROP_THRESHOLD
+            );
+        }
+        Ok(flagged)
+    }
+
+    /// Edits flagged for moderation, most recently flagged first
+    pub async fn flagged_edits(&self, limit: i64) -> Result<Vec<ContentVersion>, RepositoryError> {
+        self.versions.flagged(limit).await
+    }
+
+    /// Review recent edits every `period`
+    pub fn spawn_review_task(self: Arc<Self>, period: std::time::Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.review_edits().await {
+                    log::warn!("Content edit review failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::content_version::DiffOp;
+    use crate::models::Platform;
+    use crate::repositories::NewContent;
+    use sqlx::PgPool;
+    use std::collections::HashMap;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_diff_between_previous_and_live_versions(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xabc') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let content = Arc::new(ContentRepository::new(pool.clone()));
+        let service = ContentVersioningService::new(Arc::new(ContentVersionRepository::new(pool)), content.clone());
+
+        let mut text = NewContent {
+            user_id,
+            platform: Platform::Twitter,
+            external_id: "tweet_1".to_string(),
+            content_type: "text".to_string(),
+            title: "Roadmap".to_string(),
+            body: "ship v1\nship v2".to_string(),
+            media_urls: vec![],
+            tags: vec![],
+            reactions: HashMap::new(),
+        };
+        let created = content.create(&text).await.unwrap();
+        assert_eq!(service.history(created.id).await.unwrap().current_version, 1);
+
+        text.body = "ship v1\nship v3".to_string();
+        content.update(created.id, &text, Some(user_id)).await.unwrap();
+        text.body = "ship v1\nship v3\nship v4".to_string();
+        content.update(created.id, &text, Some(user_id)).await.unwrap();
+
+        let history = service.history(created.id).await.unwrap();
+        assert_eq!(history.current_version, 3);
+        assert_eq!(history.versions.len(), 2);
+
+        let diff = service.diff(created.id, 1, 3).await.unwrap();
+        assert_eq!((diff.added, diff.removed, diff.unchanged), (2, 1, 1));
+        assert_eq!(diff.lines[1].op, DiffOp::Removed);
+        assert_eq!(diff.lines[1].line, "ship v2");
+
+        assert!(matches!(service.diff(created.id, 1, 4).await, Err(RepositoryError::NotFound)));
+        assert!(matches!(service.history(Uuid::new_v4()).await, Err(RepositoryError::NotFound)));
+    }
+}
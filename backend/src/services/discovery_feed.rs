@@ -0,0 +1,431 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::content::ContentSummary;
+use crate::models::feed::{CachedFeed, FeedAlgorithm, FeedEntry, RelevanceReason};
+use crate::models::pagination::Cursor;
+use crate::repositories::{FeedRepository, RepositoryError};
+use crate::services::ContentSimilarityService;
+
+/// How long a generated feed is served before it is ranked again
+pub const FEED_TTL_MINUTES: i64 = 15;
+/// Entries ranked per feed; pages are cut from these
+pub const FEED_SIZE: usize = 100;
+/// How far back the user's propagations count as engagement
+const ENGAGEMENT_WINDOW_DAYS: i64 = 30;
+/// How recent trending and viral content must be
+const TRENDING_WINDOW_DAYS: i64 = 7;
+/// Most recently engaged content items similar content is looked up for
+const ENGAGED_SEEDS: i64 = 20;
+const SIMILAR_PER_SEED: usize = 10;
+const FOLLOWING_CANDIDATES: i64 = 50;
+const TRENDING_CANDIDATES: i64 = 30;
+const VIRAL_CANDIDATES: i64 = 50;
+/// Affinity of content propagated by followed users and of content trending on the
+/// user's platform; similar content's affinity is its similarity
+const FOLLOWING_AFFINITY: f64 = 0.6;
+const TRENDING_AFFINITY: f64 = 0.4;
+/// Every this many entries, one is viral content, so feeds do not narrow to one topic
+const DIVERSITY_INTERVAL: usize = 5;
+/// Score kept by content served in the user's previous feed
+const SEEN_DEMOTION: f64 = 0.25;
+/// Score multiplier of content propagated by users the user follows
+pub const DEFAULT_FOLLOWER_FEED_BOOST: f64 = 1.3;
+
+/// Content that may go into a feed, and why
+#[derive(Debug, Clone)]
+pub struct FeedCandidate {
+    pub content: ContentSummary,
+    pub reason: RelevanceReason,
+    /// How closely the content matches the user's interests, in [0, 1]
+    pub affinity: f64,
+}
+
+/// Builds personalized discovery feeds from the content a user propagated: similar
+/// content, content propagated by the users they follow and content trending on their
+/// platform, boosted by Echo Index and by being propagated by followed users, and mixed
+/// with viral content. Feeds are cached for `FEED_TTL_MINUTES`; content from the previous
+/// feed is demoted in the next one.
+pub struct DiscoveryFeedService {
+    repository: Arc<FeedRepository>,
+    similarity: Arc<ContentSimilarityService>,
+    follower_boost: f64,
+}
+
+impl DiscoveryFeedService {
+    pub fn new(repository: Arc<FeedRepository>, similarity: Arc<ContentSimilarityService>) -> Self {
+        Self { repository, similarity, follower_boost: DEFAULT_FOLLOWER_FEED_BOOST }
+    }
+
+    /// Boost from `FOLLOWER_FEED_BOOST`, 1.3 by default
+    pub fn from_env(repository: Arc<FeedRepository>, similarity: Arc<ContentSimilarityService>) -> Self {
+        let follower_boost = std::env::var("FOLLOWER_FEED_BOOST")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|boost| boost.is_finite() && *boost >= 1.0)
+            .unwrap_or(DEFAULT_FOLLOWER_FEED_BOOST);
+        Self { follower_boost, ..Self::new(repository, similarity) }
+    }
+
+    /// Up to `limit` entries of the user's feed following the entry `after` points at.
+    /// `InvalidCursor` if that entry is no longer in the feed because it was ranked again.
+    pub async fn generate_feed(
+        &self,
+        user_id: Uuid,
+        limit: u32,
+        after: Option<Cursor>,
+    ) -> Result<Vec<FeedEntry>, RepositoryError> {
+        let feed = self.feed(user_id, Utc::now()).await?;
+        let start = match after {
+            Some(cursor) => feed
+                .entries
+                .iter()
+                .position(|entry| entry.content.id == cursor.id)
+                .map(|i| i + 1)
+                .ok_or_else(|| RepositoryError::InvalidCursor("the feed has been refreshed".to_string()))?,
+            None => 0,
+        };
+
+        Ok(feed.entries.into_iter().skip(start).take(limit as usize).collect())
+    }
+
+    /// The user's cached feed, ranked again once it has expired
+    pub async fn feed(&self, user_id: Uuid, now: DateTime<Utc>) -> Result<CachedFeed, RepositoryError> {
+        let previous = match self.repository.cached_feed(user_id).await? {
+            Some(feed) if feed.expires_at > now => return Ok(feed),
+            previous => previous,
+        };
+        let seen: HashSet<Uuid> = previous
+            .into_iter()
+            .flat_map(|feed| feed.entries)
+            .map(|entry| entry.content.id)
+            .collect();
+
+        let algorithm = FeedAlgorithm::for_user(user_id, self.repository.affinity_percent().await?);
+        let engaged: HashSet<Uuid> = self
+            .repository
+            .engaged_content(user_id, now - Duration::days(ENGAGEMENT_WINDOW_DAYS), ENGAGED_SEEDS)
+            .await?
+            .into_iter()
+            .collect();
+        let viral: Vec<ContentSummary> = self
+            .repository
+            .top_content(None, now - Duration::days(TRENDING_WINDOW_DAYS), user_id, VIRAL_CANDIDATES)
+            .await?
+            .into_iter()
+            .filter(|content| !engaged.contains(&content.id))
+            .collect();
+        let followed: HashSet<Uuid> = self
+            .repository
+            .propagated_by_following(user_id, now - Duration::days(ENGAGEMENT_WINDOW_DAYS), FOLLOWING_CANDIDATES)
+            .await?
+            .into_iter()
+            .collect();
+        let candidates = match algorithm {
+            FeedAlgorithm::Affinity => self.affinity_candidates(user_id, &engaged, &followed, now).await?,
+            FeedAlgorithm::Popular => Vec::new(),
+        };
+
+        let feed = CachedFeed {
+            algorithm,
+            entries: rank_feed(candidates, viral, &seen, &followed, self.follower_boost, FEED_SIZE),
+            generated_at: now,
+            expires_at: now + Duration::minutes(FEED_TTL_MINUTES),
+        };
+        self.repository.store_feed(user_id, &feed).await?;
+        log::debug!(
+            "Ranked {} feed entries for {} with the {} algorithm",
+            feed.entries.len(),
+            user_id,
+            algorithm.as_str()
+        );
+
+        Ok(feed)
+    }
+
+    /// Content related to what the user engaged with or propagated by the users they
+    /// follow, other than what the user engaged with itself
+    async fn affinity_candidates(
+        &self,
+        user_id: Uuid,
+        engaged: &HashSet<Uuid>,
+        followed: &HashSet<Uuid>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<FeedCandidate>, RepositoryError> {
+        let mut affinities: HashMap<Uuid, (RelevanceReason, f64)> = HashMap::new();
+        let mut consider = |id: Uuid, reason: RelevanceReason, affinity: f64| {
+            if engaged.contains(&id) {
+                return;
+            }
+            let best = affinities.entry(id).or_insert((reason, affinity));
+            if affinity > best.1 {
+                *best = (reason, affinity);
+            }
+        };
+
+        for &seed in engaged {
+            let similar = match self.similarity.find_similar(seed, SIMILAR_PER_SEED, true).await {
+                Ok(similar) => similar,
+                // Content without indexable text has no similar content
+                Err(RepositoryError::NotFound) => continue,
+                Err(e) => return Err(e),
+            };
+            for (id, similarity) in similar {
+                consider(id, RelevanceReason::SimilarToEngaged, similarity);
+            }
+        }
+
+        for &id in followed {
+            consider(id, RelevanceReason::PropagatedByFollowing, FOLLOWING_AFFINITY);
+        }
+
+        let mut candidates = Vec::new();
+        let engaged_since = now - Duration::days(ENGAGEMENT_WINDOW_DAYS);
+        if let Some(platform) = self.repository.favourite_platform(user_id, engaged_since).await? {
+            let trending_since = now - Duration::days(TRENDING_WINDOW_DAYS);
+            for content in self
+                .repository
+                .top_content(Some(&platform), trending_since, user_id, TRENDING_CANDIDATES)
+                .await?
+            {
+                if !engaged.contains(&content.id) && !affinities.contains_key(&content.id) {
+                    candidates.push(FeedCandidate {
+                        content,
+                        reason: RelevanceReason::TrendingInPlatform,
+                        affinity: TRENDING_AFFINITY,
+                    });
+                }
+            }
+        }
+
+        let ids: Vec<Uuid> = affinities.keys().copied().collect();
+        for content in self.repository.summaries(&ids, user_id).await? {
+            let (reason, affinity) = affinities[&content.id];
+            candidates.push(FeedCandidate { content, reason, affinity });
+        }
+
+        Ok(candidates)
+    }
+}
+
+/// Rank candidates by affinity boosted by Echo Index, boosting those in `followed` by
+/// `follower_boost` and demoting those in `seen`, and mix in viral content, boosted and
+/// demoted alike, every `DIVERSITY_INTERVAL` entries. Viral content also fills the feed
+/// when there are too few candidates, as for users who have not engaged yet.
+pub fn rank_feed(
+    candidates: Vec<FeedCandidate>,
+    viral: Vec<ContentSummary>,
+    seen: &HashSet<Uuid>,
+    followed: &HashSet<Uuid>,
+    follower_boost: f64,
+    size: usize,
+) -> Vec<FeedEntry> {
+    let adjustment = |id: &Uuid| {
+        let boost = if followed.contains(id) { follower_boost } else { 1.0 };
+        let demotion = if seen.contains(id) { SEEN_DEMOTION } else { 1.0 };
+        boost * demotion
+    };
+
+    let mut personalized: Vec<(f64, FeedCandidate)> = candidates
+        .into_iter()
+        .map(|candidate| {
+            let boost = 1.0 + candidate.content.echo_score.clamp(0.0, 100.0) / 100.0;
+            (candidate.affinity * boost * adjustment(&candidate.content.id), candidate)
+        })
+        .collect();
+    personalized.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.content.id.cmp(&b.1.content.id)));
+
+    let mut viral: Vec<(f64, ContentSummary)> = viral
+        .into_iter()
+        .map(|content| (content.echo_score * adjustment(&content.id), content))
+        .collect();
+    viral.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.id.cmp(&b.1.id)));
+
+    let mut personalized = personalized.into_iter().map(|(_, candidate)| FeedEntry {
+        content: candidate.content,
+        relevance_reason: candidate.reason,
+    });
+    let mut viral = viral.into_iter().map(|(_, content)| FeedEntry {
+        content,
+        relevance_reason: RelevanceReason::HighEchoIndex,
+    });
+
+    let mut feed = Vec::with_capacity(size);
+    let mut included = HashSet::new();
+    while feed.len() < size {
+        let diversity_slot = (feed.len() + 1) % DIVERSITY_INTERVAL == 0;
+        let next = if diversity_slot {
+            viral.find(|entry| !included.contains(&entry.content.id))
+        } else {
+            None
+        };
+        let Some(entry) = next
+            .or_else(|| personalized.find(|entry| !included.contains(&entry.content.id)))
+            .or_else(|| viral.find(|entry| !included.contains(&entry.content.id)))
+        else {
+            break;
+        };
+
+        included.insert(entry.content.id);
+        feed.push(entry);
+    }
+
+    feed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Platform;
+    use crate::repositories::{ContentRepository, ContentTfIdfRepository, UserRelationshipRepository};
+    use sqlx::PgPool;
+
+    fn summary(echo_score: f64) -> ContentSummary {
+        ContentSummary {
+            id: Uuid::new_v4(),
+            text: String::new(),
+            platform: Platform::Twitter,
+            echo_score,
+            propagation_count: 0,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_echo_index_boosts_seen_content_is_demoted_and_viral_content_is_mixed_in() {
+        let candidate = |content: &ContentSummary, affinity| FeedCandidate {
+            content: content.clone(),
+            reason: RelevanceReason::SimilarToEngaged,
+            affinity,
+        };
+        let quiet = summary(0.0);
+        let echoing = summary(90.0);
+        let seen_before = summary(100.0);
+        let mut candidates = vec![
+            candidate(&quiet, 0.7),
+            candidate(&echoing, 0.5),
+            candidate(&seen_before, 0.9),
+        ];
+        candidates.extend((0..3).map(|_| candidate(&summary(10.0), 0.1)));
+        let viral: Vec<ContentSummary> = vec![summary(99.0), summary(98.0)];
+        let seen = HashSet::from([seen_before.id]);
+
+        let feed = rank_feed(candidates, viral.clone(), &seen, &HashSet::new(), DEFAULT_FOLLOWER_FEED_BOOST, 10);
+
+        let ids: Vec<Uuid> = feed.iter().map(|entry| entry.content.id).collect();
+        assert_eq!(ids[..3], [echoing.id, quiet.id, seen_before.id]);
+        assert_eq!(feed[4].content.id, viral[0].id);
+        assert_eq!(feed[4].relevance_reason, RelevanceReason::HighEchoIndex);
+        // Once candidates run out, what is left of the viral content fills the feed
+        assert_eq!(feed.len(), 8);
+        assert_eq!(feed[7].content.id, viral[1].id);
+    }
+
+    #[test]
+    fn test_content_propagated_by_followed_users_is_boosted() {
+        let candidate = |content: &ContentSummary, affinity| FeedCandidate {
+            content: content.clone(),
+            reason: RelevanceReason::SimilarToEngaged,
+            affinity,
+        };
+        let closer = summary(50.0);
+        let followed_share = summary(50.0);
+        let candidates = vec![candidate(&closer, 0.5), candidate(&followed_share, 0.45)];
+        let followed = HashSet::from([followed_share.id]);
+
+        let ids = |feed: Vec<FeedEntry>| feed.into_iter().map(|entry| entry.content.id).collect::<Vec<_>>();
+        let unboosted = rank_feed(candidates.clone(), Vec::new(), &HashSet::new(), &HashSet::new(), 1.3, 10);
+        assert_eq!(ids(unboosted), [closer.id, followed_share.id]);
+        // 0.45 × 1.3 outranks 0.5
+        let boosted = rank_feed(candidates, Vec::new(), &HashSet::new(), &followed, 1.3, 10);
+        assert_eq!(ids(boosted), [followed_share.id, closer.id]);
+    }
+
+    async fn user(pool: &PgPool, wallet: &str) -> Uuid {
+        sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+            .bind(wallet)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn content(pool: &PgPool, author: Uuid, external_id: &str, body: &str, echo_index: f64) -> Uuid {
+        sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type, title, body, echo_index)
+             VALUES ($1, 'twitter', $2, 'text', '', $3, $4) RETURNING id",
+        )
+        .bind(author)
+        .bind(external_id)
+        .bind(body)
+        .bind(echo_index)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    async fn propagate(pool: &PgPool, content_id: Uuid, source: Uuid, target: Uuid, external_id: &str) {
+        sqlx::query(
+            "INSERT INTO propagations (content_id, source_user_id, target_user_id, propagation_type,
+                                       source_platform, target_platform, target_external_id)
+             VALUES ($1, $2, $3, 'share', 'twitter', 'twitter', $4)",
+        )
+        .bind(content_id)
+        .bind(source)
+        .bind(target)
+        .bind(external_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_feed_follows_engagement_and_is_cached(pool: PgPool) {
+        let viewer = user(&pool, "0xviewer").await;
+        let author = user(&pool, "0xauthor").await;
+        let friend = user(&pool, "0xfriend").await;
+
+        let shared = content(&pool, author, "tweet_shared", "Validator staking rewards on ethereum keep rising", 40.0).await;
+        let similar = content(&pool, author, "tweet_similar", "Ethereum validator staking rewards explained", 30.0).await;
+        let unrelated = content(&pool, author, "tweet_pasta", "Fresh pasta recipes for the weekend", 95.0).await;
+        let from_friend = content(&pool, author, "tweet_friend", "Bridges between rollups", 20.0).await;
+        let own = content(&pool, viewer, "tweet_own", "My ethereum validator staking setup", 99.0).await;
+
+        let contents = ContentRepository::new(pool.clone());
+        let similarity = Arc::new(ContentSimilarityService::new(Arc::new(ContentTfIdfRepository::new(pool.clone()))));
+        for id in [shared, similar, unrelated, from_friend, own] {
+            similarity.index(&contents.find_by_id(id).await.unwrap()).await.unwrap();
+        }
+
+        propagate(&pool, shared, viewer, author, "share_1").await;
+        propagate(&pool, shared, friend, viewer, "share_2").await;
+        propagate(&pool, from_friend, friend, author, "share_3").await;
+        UserRelationshipRepository::new(pool.clone()).follow(viewer, friend).await.unwrap();
+
+        let service = DiscoveryFeedService::new(Arc::new(FeedRepository::new(pool.clone())), similarity);
+        let feed = service.generate_feed(viewer, 10, None).await.unwrap();
+
+        let reason_of = |id: Uuid| feed.iter().find(|entry| entry.content.id == id).map(|entry| entry.relevance_reason);
+        assert_eq!(reason_of(similar), Some(RelevanceReason::SimilarToEngaged));
+        assert_eq!(reason_of(from_friend), Some(RelevanceReason::PropagatedByFollowing));
+        assert_eq!(reason_of(unrelated), Some(RelevanceReason::TrendingInPlatform));
+        assert_eq!(reason_of(shared), None);
+        assert_eq!(reason_of(own), None);
+
+        // Served from the cache, a page at a time, until it expires
+        content(&pool, author, "tweet_new", "Ethereum validator staking rewards news", 80.0).await;
+        let first = service.generate_feed(viewer, 1, None).await.unwrap();
+        assert_eq!(first, feed[..1]);
+        let cursor = Cursor::new(first[0].content.created_at, first[0].content.id);
+        assert_eq!(service.generate_feed(viewer, 10, Some(cursor)).await.unwrap(), feed[1..]);
+        let stranger = Cursor::new(Utc::now(), Uuid::new_v4());
+        assert!(matches!(
+            service.generate_feed(viewer, 10, Some(stranger)).await,
+            Err(RepositoryError::InvalidCursor(_))
+        ));
+
+        let refreshed = service.feed(viewer, Utc::now() + Duration::minutes(FEED_TTL_MINUTES + 1)).await.unwrap();
+        assert_eq!(refreshed.entries.len(), feed.len() + 1);
+    }
+}
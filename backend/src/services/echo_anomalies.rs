@@ -0,0 +1,238 @@
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::models::echo_anomaly::{AnomalyFlag, AnomalyStatus, EchoAnomaly};
+use crate::models::echo_index_history::EchoIndexHistory;
+use crate::repositories::{EchoAnomalyRepository, EchoIndexHistoryRepository, RepositoryError};
+use crate::services::RewardService;
+
+/// Points the Echo Index may rise within an hour before the rise is anomalous
+pub const MAX_HOURLY_RISE: f64 = 30.0;
+/// Smallest rise the propagator and component checks look at
+const MIN_CHECKED_RISE: f64 = 10.0;
+/// Points originality may rise in one recalculation; the text itself has not changed
+const MAX_ODF_RISE: f64 = 15.0;
+/// Points of quote frequency gained in one recalculation that count as a spike
+const QF_SPIKE_POINTS: f64 = 25.0;
+/// History stores scores and components as fractions of 1
+const HISTORY_SCALE: f64 = 100.0;
+
+/// Flags an Echo Index rise that moved too fast, beyond what the content's propagators
+/// or components can explain
+pub fn score_jump(previous_score: f64, new_score: f64, time_delta_seconds: u64) -> Option<AnomalyFlag> {
+    let rise = new_score - previous_score;
+    if rise < MAX_HOURLY_RISE {
+        return None;
+    }
+    // Calculations in the same second still count as a second apart
+    let hours = time_delta_seconds.max(1) as f64 / 3600.0;
+    let rate_per_hour = rise / hours;
+    (rate_per_hour >= MAX_HOURLY_RISE).then_some(AnomalyFlag::ScoreJumpTooFast { rate_per_hour })
+}
+
+/// Component moves between two consecutive calculations that no organic propagation
+/// produces
+pub fn component_spikes(previous: &EchoIndexHistory, latest: &EchoIndexHistory) -> Vec<AnomalyFlag> {
+    let mut flags = Vec::new();
+
+    let new_odf = latest.odf * HISTORY_SCALE;
+    let expected_max = (previous.odf * HISTORY_SCALE + MAX_ODF_RISE).min(100.0);
+    if new_odf > expected_max {
+        flags.push(AnomalyFlag::OdfOvertaken { new_odf, expected_max });
+    }
+    if (latest.qf - previous.qf) * HISTORY_SCALE >= QF_SPIKE_POINTS {
+        flags.push(AnomalyFlag::QfSpike);
+    }
+    flags
+}
+
+/// Watches Echo Index recalculations for statistically unusual jumps. Anomalous content
+/// has its rewards held until an operator dismisses or confirms the anomaly.
+pub struct EchoIndexAnomalyDetector {
+    anomalies: Arc<EchoAnomalyRepository>,
+    history: Arc<EchoIndexHistoryRepository>,
+    rewards: Option<Arc<RwLock<RewardService>>>,
+}
+
+impl EchoIndexAnomalyDetector {
+    pub fn new(anomalies: Arc<EchoAnomalyRepository>, history: Arc<EchoIndexHistoryRepository>) -> Self {
+        Self { anomalies, history, rewards: None }
+    }
+
+    /// Hold the rewards of anomalous content until the anomaly is reviewed
+    pub fn with_reward_service(mut self, rewards: Arc<RwLock<RewardService>>) -> Self {
+        self.rewards = Some(rewards);
+        self
+    }
+
+    /// Reasons a rise of the content's Echo Index, already recorded in its history, looks
+    /// manipulated. Scores are on the 0-100 scale; falls are never anomalous.
+    pub async fn check_for_anomalies(
+        &self,
+        content_id: Uuid,
+        previous_score: f64,
+        new_score: f64,
+        time_delta_seconds: u64,
+    ) -> Result<Vec<AnomalyFlag>, RepositoryError> {
+        let mut flags: Vec<_> = score_jump(previous_score, new_score, time_delta_seconds).into_iter().collect();
+        if new_score - previous_score < MIN_CHECKED_RISE {
+            return Ok(flags);
+        }
+
+        let propagator_scores = self.anomalies.propagator_echo_scores(content_id).await?;
+        if !propagator_scores.is_empty() && propagator_scores.iter().all(|score| new_score > *score) {
+            flags.push(AnomalyFlag::ScoreHigherThanAllPropagators);
+        }
+        if let [latest, previous] = &self.history.recent(content_id, 2).await?[..] {
+            flags.extend(component_spikes(previous, latest));
+        }
+        Ok(flags)
+    }
+
+    /// Check a rise and, if it looks manipulated, record the anomaly and hold the
+    /// content's rewards
+    pub async fn detect(
+        &self,
+        content_id: Uuid,
+        previous_score: f64,
+        new_score: f64,
+        time_delta_seconds: u64,
+    ) -> Result<Option<EchoAnomaly>, RepositoryError> {
+        let flags = self.check_for_anomalies(content_id, previous_score, new_score, time_delta_seconds).await?;
+        if flags.is_empty() {
+            return Ok(None);
+        }
+
+        let anomaly = self
+            .anomalies
+            .create(content_id, previous_score, new_score, time_delta_seconds, &flags)
+            .await?;
+        log::warn!(
+            "Echo Index of {} jumped from {:.1} to {:.1} in {}s: {:?}",
+            content_id, previous_score, new_score, time_delta_seconds, flags
+        );
+        if let Some(rewards) = &self.rewards {
+            let held = rewards.write().await.hold_content_rewards(&content_id.to_string());
+            log::info!("Holding {} pending rewards of {} for anomaly review", held, content_id);
+        }
+        Ok(Some(anomaly))
+    }
+
+    /// Anomalies detected since `since`, or ever, most recent first
+    pub async fn detected_since(
+        &self,
+        since: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Vec<EchoAnomaly>, RepositoryError> {
+        self.anomalies.detected_since(since).await
+    }
+
+    /// Find a jump legitimate. The content's rewards are released once none of its
+    /// anomalies is open.
+    pub async fn dismiss(&self, anomaly_id: Uuid, reviewed_by: Uuid) -> Result<EchoAnomaly, RepositoryError> {
+        let anomaly = self.anomalies.review(anomaly_id, reviewed_by, AnomalyStatus::Dismissed).await?;
+        if let Some(rewards) = &self.rewards {
+            if self.anomalies.open_count(anomaly.content_id).await? == 0 {
+                let released = rewards.write().await.release_content_rewards(&anomaly.content_id.to_string());
+                log::info!("Dismissed anomaly {}, releasing {} held rewards", anomaly_id, released);
+            }
+        }
+        Ok(anomaly)
+    }
+
+    /// Find a jump manipulated, freezing the content's rewards
+    pub async fn confirm(&self, anomaly_id: Uuid, reviewed_by: Uuid) -> Result<EchoAnomaly, RepositoryError> {
+        let anomaly = self.anomalies.review(anomaly_id, reviewed_by, AnomalyStatus::Confirmed).await?;
+        if let Some(rewards) = &self.rewards {
+            let frozen = rewards.write().await.freeze_content_rewards(&anomaly.content_id.to_string());
+            log::info!("Confirmed anomaly {}, freezing {} pending rewards", anomaly_id, frozen);
+        }
+        Ok(anomaly)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::echo_index_history::EchoIndexTrigger;
+    use crate::repositories::EchoIndexScores;
+    use sqlx::PgPool;
+
+    #[test]
+    fn test_forty_point_jump_in_five_minutes_is_flagged() {
+        assert_eq!(score_jump(20.0, 60.0, 300), Some(AnomalyFlag::ScoreJumpTooFast { rate_per_hour: 480.0 }));
+
+        // The same rise spread over two hours, and a small rise at once, are organic
+        assert_eq!(score_jump(20.0, 60.0, 2 * 3600), None);
+        assert_eq!(score_jump(20.0, 29.0, 0), None);
+        assert_eq!(score_jump(60.0, 20.0, 300), None);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_anomalies_hold_rewards_until_reviewed(pool: PgPool) {
+        let (author, propagator): (Uuid, Uuid) = sqlx::query_as(
+            "WITH author AS (INSERT INTO users (wallet_address) VALUES ('0xauthor') RETURNING id),
+                  propagator AS (INSERT INTO users (wallet_address, echo_score) VALUES ('0xpropagator', 25)
+                                 RETURNING id)
+             SELECT author.id, propagator.id FROM author, propagator",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let content_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type, title, body)
+             VALUES ($1, 'twitter', 'tweet_pumped', 'text', 'Pumped', 'To the moon') RETURNING id",
+        )
+        .bind(author)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO propagations
+                 (content_id, source_user_id, propagation_type, source_platform, target_platform)
+             VALUES ($1, $2, 'share', 'twitter', 'twitter')",
+        )
+        .bind(content_id)
+        .bind(propagator)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let history = Arc::new(EchoIndexHistoryRepository::new(pool.clone()));
+        for (score, odf, qf) in [(0.2, 0.25, 0.1), (0.6, 0.5, 0.4)] {
+            let scores = EchoIndexScores { score, odf, awr: 0.5, tpm: 0.5, qf };
+            history.record(content_id, scores, EchoIndexTrigger::Recalculation, None).await.unwrap();
+        }
+
+        let rewards = Arc::new(RwLock::new(RewardService::new(1_000.0)));
+        rewards
+            .write()
+            .await
+            .award_community_contribution(author.to_string(), content_id.to_string(), 10.0, 0.1)
+            .await
+            .unwrap();
+        let detector = EchoIndexAnomalyDetector::new(Arc::new(EchoAnomalyRepository::new(pool.clone())), history)
+            .with_reward_service(rewards.clone());
+
+        let anomaly = detector.detect(content_id, 20.0, 60.0, 300).await.unwrap().unwrap();
+        assert_eq!(
+            anomaly.flags,
+            vec![
+                AnomalyFlag::ScoreJumpTooFast { rate_per_hour: 480.0 },
+                AnomalyFlag::ScoreHigherThanAllPropagators,
+                AnomalyFlag::OdfOvertaken { new_odf: 50.0, expected_max: 40.0 },
+                AnomalyFlag::QfSpike,
+            ]
+        );
+        assert_eq!(rewards.read().await.get_on_hold_rewards().len(), 1);
+        assert!(detector.detect(content_id, 58.0, 60.0, 300).await.unwrap().is_none());
+
+        let listed = detector.detected_since(None).await.unwrap();
+        assert_eq!(listed, vec![anomaly.clone()]);
+        let dismissed = detector.dismiss(anomaly.id, propagator).await.unwrap();
+        assert_eq!((dismissed.status, dismissed.reviewed_by), (AnomalyStatus::Dismissed, Some(propagator)));
+        assert!(rewards.read().await.get_on_hold_rewards().is_empty());
+        assert!(matches!(detector.confirm(anomaly.id, propagator).await, Err(RepositoryError::Conflict(_))));
+        assert!(matches!(detector.confirm(Uuid::new_v4(), propagator).await, Err(RepositoryError::NotFound)));
+    }
+}
@@ -0,0 +1,227 @@
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::models::echo_index_history::EchoIndexTrigger;
+use crate::models::user_event::{echo_tier, lower_tier_floor, UserEvent};
+use crate::models::webhook::WebhookEvent;
+use crate::repositories::{
+    ContentRepository, EchoIndexHistoryRepository, EchoIndexScores, RepositoryError, UserEventRepository,
+};
+use crate::services::{EchoEngine, EngineConfigStore, WebhookDispatcher};
+
+pub const DEFAULT_DECAY_INTERVAL_HOURS: u64 = 6;
+/// Content decayed per query while working through the stale scores
+const DECAY_BATCH_SIZE: u32 = 500;
+
+/// Applies the echo engine's temporal decay to stored Echo Index scores that have not been
+/// recalculated within the decay interval. A single run lowers a score by at most one
+/// tier; the author is told when it does.
+pub struct DecayScheduler {
+    content: Arc<ContentRepository>,
+    history: Arc<EchoIndexHistoryRepository>,
+    events: Arc<UserEventRepository>,
+    /// Read on every run, so decay factor changes apply without a restart
+    engine_config: Arc<EngineConfigStore>,
+    webhooks: Option<Arc<WebhookDispatcher>>,
+    interval: std::time::Duration,
+}
+
+impl DecayScheduler {
+    pub fn new(
+        content: Arc<ContentRepository>,
+        history: Arc<EchoIndexHistoryRepository>,
+        events: Arc<UserEventRepository>,
+        engine_config: Arc<EngineConfigStore>,
+        interval: std::time::Duration,
+    ) -> Self {
+        Self { content, history, events, engine_config, webhooks: None, interval }
+    }
+
+    /// Scheduler running every `DECAY_INTERVAL_HOURS`, 6 by default
+    pub fn from_env(
+        content: Arc<ContentRepository>,
+        history: Arc<EchoIndexHistoryRepository>,
+        events: Arc<UserEventRepository>,
+        engine_config: Arc<EngineConfigStore>,
+    ) -> Self {
+        let hours = std::env::var("DECAY_INTERVAL_HOURS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|hours| *hours > 0)
+            .unwrap_or(DEFAULT_DECAY_INTERVAL_HOURS);
+        Self::new(content, history, events, engine_config, std::time::Duration::from_secs(hours * 3600))
+    }
+
+    /// Notify authors' webhooks of tier changes caused by decay
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookDispatcher>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Decay every score last updated more than one interval before `now`, by the hours
+    /// since its update. Returns how many scores were decayed.
+    pub async fn run(&self, now: DateTime<Utc>) -> Result<usize, RepositoryError> {
+        let interval = Duration::from_std(self.interval).unwrap_or(Duration::hours(DEFAULT_DECAY_INTERVAL_HOURS as i64));
+        let engine = EchoEngine::new(self.engine_config.load().config.clone());
+
+        let mut decayed = 0;
+        let mut after = None;
+        loop {
+            let stale = self.content.list_stale_scores(now - interval, after, DECAY_BATCH_SIZE).await?;
+            let Some(last) = stale.last() else {
+                break;
+            };
+            after = Some(last.id);
+
+            for record in &stale {
+                let hours = (now - record.updated_at).num_seconds() as f64 / 3600.0;
+                match self.decay(&engine, record.id, record.user_id, record.echo_index, hours).await {
+                    Ok(()) => decayed += 1,
+                    // Deleted since it was listed
+                    Err(RepositoryError::NotFound) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        Ok(decayed)
+    }
+
+    async fn decay(
+        &self,
+        engine: &EchoEngine,
+        content_id: Uuid,
+        author_id: Uuid,
+        score: f64,
+        hours: f64,
+    ) -> Result<(), RepositoryError> {
+        let decayed = engine.apply_temporal_decay(score, hours).max(lower_tier_floor(score));
+        self.content.set_echo_index(content_id, decayed).await?;
+
+        // The components of the latest calculation shrink with the score
+        if let Some(latest) = self.history.latest(content_id).await? {
+            let ratio = decayed / score;
+            let scores = EchoIndexScores {
+                score: latest.score * ratio,
+                odf: latest.odf * ratio,
+                awr: latest.awr * ratio,
+                tpm: latest.tpm * ratio,
+                qf: latest.qf * ratio,
+            };
+            self.history.record(content_id, scores, EchoIndexTrigger::Decay, None).await?;
+        }
+
+        let (old_tier, new_tier) = (echo_tier(score), echo_tier(decayed));
+        if old_tier != new_tier {
+            let event = UserEvent::TierChanged { old_tier: old_tier.to_string(), new_tier: new_tier.to_string() };
+            if let Err(e) = self.events.record(author_id, &event).await {
+                log::warn!("Failed to record decay of {} on timeline of {}: {}", content_id, author_id, e);
+            }
+            if let Some(webhooks) = &self.webhooks {
+                let data = serde_json::json!({ "content_id": content_id, "old_tier": old_tier, "new_tier": new_tier });
+                webhooks.notify(author_id, WebhookEvent::TierChanged, data);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decay stale scores every interval
+    pub fn spawn_decay_task(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            loop {
+                interval.tick().await;
+                match self.run(Utc::now()).await {
+                    Ok(decayed) if decayed > 0 => log::info!("Decayed {} Echo Index scores", decayed),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Echo Index decay failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Platform;
+    use crate::repositories::NewContent;
+    use crate::services::EchoEngineConfig;
+    use sqlx::PgPool;
+    use std::collections::HashMap;
+
+    async fn content(pool: &PgPool, user_id: Uuid, external_id: &str, echo_index: f64) -> Uuid {
+        let content = ContentRepository::new(pool.clone());
+        let created = content
+            .create(&NewContent {
+                user_id,
+                platform: Platform::Twitter,
+                external_id: external_id.to_string(),
+                content_type: "text".to_string(),
+                title: String::new(),
+                body: "Fading echoes".to_string(),
+                media_urls: vec![],
+                tags: vec![],
+                reactions: HashMap::new(),
+            })
+            .await
+            .unwrap();
+        content.set_echo_index(created.id, echo_index).await.unwrap();
+        created.id
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_scores_decay_by_the_hours_since_their_update(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xdecay') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let gold = content(&pool, user_id, "tweet_gold", 80.0).await;
+        let history = Arc::new(EchoIndexHistoryRepository::new(pool.clone()));
+        let scores = EchoIndexScores { score: 80.0, odf: 0.8, awr: 0.6, tpm: 0.4, qf: 0.2 };
+        history.record(gold, scores, EchoIndexTrigger::Recalculation, None).await.unwrap();
+        let long_gone = content(&pool, user_id, "tweet_long_gone", 90.0).await;
+
+        let engine_config = EchoEngineConfig { decay_factor: 0.95, ..EchoEngineConfig::default() };
+        let content_repository = Arc::new(ContentRepository::new(pool.clone()));
+        let events = Arc::new(UserEventRepository::new(pool.clone()));
+        let scheduler = DecayScheduler::new(
+            content_repository.clone(),
+            history.clone(),
+            events.clone(),
+            Arc::new(EngineConfigStore::new(engine_config, "missing.toml")),
+            std::time::Duration::from_secs(6 * 3600),
+        );
+
+        // Nothing is older than the interval yet
+        assert_eq!(scheduler.run(Utc::now()).await.unwrap(), 0);
+
+        assert_eq!(scheduler.run(Utc::now() + Duration::hours(48)).await.unwrap(), 2);
+        let decayed = content_repository.find_by_id(gold).await.unwrap().echo_index;
+        assert!((decayed - 80.0 * 0.95f64.powi(2)).abs() < 0.01, "decayed to {}", decayed);
+
+        let latest = history.latest(gold).await.unwrap().unwrap();
+        assert_eq!(latest.trigger, EchoIndexTrigger::Decay);
+        assert!((latest.odf - 0.8 * 0.95f64.powi(2)).abs() < 1e-3);
+        assert!((latest.delta_score - (latest.score - 80.0)).abs() < 1e-9);
+
+        // A single run drops a score by at most one tier
+        let decayed = scheduler.run(Utc::now() + Duration::days(365)).await.unwrap();
+        assert_eq!(decayed, 2);
+        assert_eq!(content_repository.find_by_id(long_gone).await.unwrap().echo_index, 60.0);
+        assert_eq!(content_repository.find_by_id(gold).await.unwrap().echo_index, 40.0);
+
+        let tier_changes: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM user_events WHERE user_id = $1 AND event_type = 'tier_changed'",
+        )
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        // Gold to Silver then Silver to Bronze, and Gold to Silver
+        assert_eq!(tier_changes, 3);
+    }
+}
@@ -1,4 +1,24 @@
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::models::content::ReactionType;
+use crate::models::Platform;
+use crate::services::link_quality::LinkQualityReport;
+use crate::services::propagation_weights::WeightNormalizationStrategy;
+
+/// Sub-weights of the Quality Factor, summing to 1.0
+const QF_SENTIMENT_WEIGHT: f64 = 0.15;
+const QF_CREDIBILITY_WEIGHT: f64 = 0.24;
+const QF_RELEVANCE_WEIGHT: f64 = 0.24;
+const QF_ORIGINALITY_WEIGHT: f64 = 0.17;
+const QF_LINK_QUALITY_WEIGHT: f64 = 0.10;
+const QF_REACTION_QUALITY_WEIGHT: f64 = 0.10;
+
+/// Weight of a reaction type missing from `reaction_weights`, that of a like
+const DEFAULT_REACTION_WEIGHT: f64 = 1.0;
+
+/// Tolerance of the check that the component weights sum to 1.0
+const WEIGHT_SUM_TOLERANCE: f64 = 1e-6;
 
 #[derive(Debug, Clone)]
 pub struct EchoMetrics {
@@ -19,7 +39,9 @@ impl Default for EchoMetrics {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Echo engine tuning. Deserialized configs may omit fields, which keep their defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct EchoEngineConfig {
     pub odf_weight: f64,
     pub awr_weight: f64,
@@ -27,6 +49,18 @@ pub struct EchoEngineConfig {
     pub qf_weight: f64,
     pub decay_factor: f64,
     pub boost_threshold: f64,
+    /// Multiplier applied to raw ODF so share velocity is comparable across platforms
+    pub platform_odf_normalization: HashMap<Platform, f64>,
+    /// Multiplier applied to the readability of content by ISO 639-3 language, since each
+    /// language's Flesch formula runs higher or lower than English; unlisted languages keep 1.0
+    pub language_normalization_factors: HashMap<String, f64>,
+    /// How propagation weights are rescaled within a content's propagation graph before
+    /// TPM and path resonance are calculated
+    #[serde(rename = "weight_normalization_strategy")]
+    pub weight_normalization: WeightNormalizationStrategy,
+    /// How much one reaction of each type counts towards AWR and the Quality Factor;
+    /// unlisted types count as much as a like
+    pub reaction_weights: HashMap<ReactionType, f64>,
 }
 
 impl Default for EchoEngineConfig {
@@ -38,6 +72,162 @@ impl Default for EchoEngineConfig {
             qf_weight: 0.2,
             decay_factor: 0.95,
             boost_threshold: 0.8,
+            platform_odf_normalization: HashMap::from([
+                (Platform::Twitter, 1.0),
+                (Platform::LinkedIn, 1.4),
+                (Platform::Telegram, 0.9),
+                (Platform::Reddit, 1.1),
+                (Platform::Medium, 1.6),
+            ]),
+            language_normalization_factors: HashMap::from([
+                ("eng".to_string(), 1.0),
+                ("spa".to_string(), 0.95),
+                ("deu".to_string(), 1.05),
+                ("fra".to_string(), 1.0),
+                ("ita".to_string(), 0.95),
+                ("por".to_string(), 0.9),
+            ]),
+            weight_normalization: WeightNormalizationStrategy::RankBased,
+            reaction_weights: HashMap::from([
+                (ReactionType::Like, 1.0),
+                (ReactionType::Love, 1.5),
+                (ReactionType::Celebrate, 1.5),
+                (ReactionType::Repost, 1.5),
+                (ReactionType::Bookmark, 2.0),
+                (ReactionType::ThreadReply, 2.0),
+                (ReactionType::Insightful, 2.5),
+                (ReactionType::Save, 2.5),
+                (ReactionType::QuoteComment, 3.0),
+            ]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum EngineConfigError {
+    #[error("`{field}` must be between {min} and {max}, got {value}")]
+    OutOfRange { field: &'static str, value: f64, min: f64, max: f64 },
+    #[error("weights must sum to 1.0, got {0}")]
+    InvalidWeightSum(f64),
+    #[error("normalization factor for `{0}` must be a finite, positive number")]
+    InvalidNormalization(String),
+    #[error("weight of `{0}` reactions must be a finite, non-negative number")]
+    InvalidReactionWeight(ReactionType),
+}
+
+impl EchoEngineConfig {
+    /// Names of the scalar settings, as they appear in config files and `ECHO_ENGINE_*` variables
+    pub const SCALAR_FIELDS: [&'static str; 6] =
+        ["odf_weight", "awr_weight", "tpm_weight", "qf_weight", "decay_factor", "boost_threshold"];
+
+    pub fn scalar(&self, field: &str) -> Option<f64> {
+        match field {
+            "odf_weight" => Some(self.odf_weight),
+            "awr_weight" => Some(self.awr_weight),
+            "tpm_weight" => Some(self.tpm_weight),
+            "qf_weight" => Some(self.qf_weight),
+            "decay_factor" => Some(self.decay_factor),
+            "boost_threshold" => Some(self.boost_threshold),
+            _ => None,
+        }
+    }
+
+    pub fn scalar_mut(&mut self, field: &str) -> Option<&mut f64> {
+        match field {
+            "odf_weight" => Some(&mut self.odf_weight),
+            "awr_weight" => Some(&mut self.awr_weight),
+            "tpm_weight" => Some(&mut self.tpm_weight),
+            "qf_weight" => Some(&mut self.qf_weight),
+            "decay_factor" => Some(&mut self.decay_factor),
+            "boost_threshold" => Some(&mut self.boost_threshold),
+            _ => None,
+        }
+    }
+
+    /// Every setting lies between 0 and 1, the weights sum to 1.0, the normalization
+    /// factors are positive and no reaction weight is negative
+    pub fn validate(&self) -> Result<(), EngineConfigError> {
+        for field in Self::SCALAR_FIELDS {
+            let value = self.scalar(field).unwrap_or_default();
+            if !(0.0..=1.0).contains(&value) {
+                return Err(EngineConfigError::OutOfRange { field, value, min: 0.0, max: 1.0 });
+            }
+        }
+
+        let sum = self.odf_weight + self.awr_weight + self.tpm_weight + self.qf_weight;
+        if (sum - 1.0).abs() > WEIGHT_SUM_TOLERANCE {
+            return Err(EngineConfigError::InvalidWeightSum(sum));
+        }
+
+        let platforms = self.platform_odf_normalization.iter().map(|(platform, factor)| (platform.as_str(), *factor));
+        let languages = self.language_normalization_factors.iter().map(|(code, factor)| (code.as_str(), *factor));
+        if let Some((key, _)) = platforms.chain(languages).find(|(_, factor)| !factor.is_finite() || *factor <= 0.0) {
+            return Err(EngineConfigError::InvalidNormalization(key.to_string()));
+        }
+        if let Some((reaction, _)) =
+            self.reaction_weights.iter().find(|(_, weight)| !weight.is_finite() || **weight < 0.0)
+        {
+            return Err(EngineConfigError::InvalidReactionWeight(*reaction));
+        }
+
+        Ok(())
+    }
+
+    /// One line per setting that differs in `other`, e.g. `decay_factor: 0.95 -> 0.9`
+    pub fn diff(&self, other: &Self) -> Vec<String> {
+        let mut changes = Vec::new();
+        for field in Self::SCALAR_FIELDS {
+            let (old, new) = (self.scalar(field), other.scalar(field));
+            if old != new {
+                changes.push(format!("{}: {} -> {}", field, old.unwrap_or_default(), new.unwrap_or_default()));
+            }
+        }
+        if self.weight_normalization != other.weight_normalization {
+            changes.push(format!(
+                "weight_normalization_strategy: {} -> {}",
+                self.weight_normalization.as_str(),
+                other.weight_normalization.as_str()
+            ));
+        }
+
+        let platforms = |config: &Self| -> HashMap<String, f64> {
+            config
+                .platform_odf_normalization
+                .iter()
+                .map(|(platform, factor)| (platform.to_string(), *factor))
+                .collect()
+        };
+        diff_factors("platform_odf_normalization", &platforms(self), &platforms(other), &mut changes);
+        diff_factors(
+            "language_normalization_factors",
+            &self.language_normalization_factors,
+            &other.language_normalization_factors,
+            &mut changes,
+        );
+        let reactions = |config: &Self| -> HashMap<String, f64> {
+            config
+                .reaction_weights
+                .iter()
+                .map(|(reaction, weight)| (reaction.to_string(), *weight))
+                .collect()
+        };
+        diff_factors("reaction_weights", &reactions(self), &reactions(other), &mut changes);
+
+        changes
+    }
+}
+
+/// Append the added, changed and removed entries of a factor table, in key order
+fn diff_factors(name: &str, old: &HashMap<String, f64>, new: &HashMap<String, f64>, changes: &mut Vec<String>) {
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for key in keys {
+        match (old.get(key), new.get(key)) {
+            (Some(before), Some(after)) if before != after => {
+                changes.push(format!("{}.{}: {} -> {}", name, key, before, after))
+            }
+            (Some(before), None) => changes.push(format!("{}.{}: {} -> (removed)", name, key, before)),
+            (None, Some(after)) => changes.push(format!("{}.{}: (unset) -> {}", name, key, after)),
+            _ => {}
         }
     }
 }
@@ -46,15 +236,17 @@ pub struct EchoEngine {
     config: EchoEngineConfig,
 }
 
+impl Default for EchoEngine {
+    fn default() -> Self {
+        Self::new(EchoEngineConfig::default())
+    }
+}
+
 impl EchoEngine {
     pub fn new(config: EchoEngineConfig) -> Self {
         Self { config }
     }
 
-    pub fn default() -> Self {
-        Self::new(EchoEngineConfig::default())
-    }
-
     /// Calculate the Echo Index for given content
     pub fn calculate_echo_index(&self, metrics: &EchoMetrics) -> f64 {
         let weighted_score = 
@@ -75,27 +267,43 @@ impl EchoEngine {
     pub fn calculate_odf(&self, 
         shares_from_discovery: u32,
         total_shares: u32,
-        platform_reach: u32
+        platform_reach: u32,
+        platform: &Platform
     ) -> f64 {
         if total_shares == 0 {
             return 0.0;
         }
 
         let organic_ratio = shares_from_discovery as f64 / total_shares as f64;
-        let reach_factor = (platform_reach as f64).ln() / 10.0; // Logarithmic scaling
-        
-        (organic_ratio * 0.7 + reach_factor.min(1.0) * 0.3).min(1.0)
+        let reach_factor = (platform_reach.max(1) as f64).ln() / 10.0; // Logarithmic scaling
+        let raw_odf = organic_ratio * 0.7 + reach_factor.min(1.0) * 0.3;
+
+        // Unknown platforms are left unnormalized
+        let normalization = self.config.platform_odf_normalization
+            .get(platform)
+            .unwrap_or(&1.0);
+
+        (raw_odf * normalization).min(1.0)
+    }
+
+    fn reaction_weight(&self, reaction: &ReactionType) -> f64 {
+        self.config.reaction_weights.get(reaction).copied().unwrap_or(DEFAULT_REACTION_WEIGHT)
+    }
+
+    /// Reactions counted by weight, so a quote comment outweighs a like
+    pub fn weighted_reactions(&self, reactions: &HashMap<ReactionType, u64>) -> f64 {
+        reactions.iter().map(|(reaction, count)| self.reaction_weight(reaction) * *count as f64).sum()
     }
 
     /// Calculate Attention Weight Ratio
     pub fn calculate_awr(&self,
-        engagement_metrics: &HashMap<String, f64>,
+        reactions: &HashMap<ReactionType, u64>,
         view_time: f64,
         total_views: u32
     ) -> f64 {
-        let engagement_score: f64 = engagement_metrics.values().sum();
+        let engagement_score = ((1.0 + self.weighted_reactions(reactions)).ln() / 10.0).min(1.0); // Logarithmic scaling
         let time_factor = (view_time / 60.0).min(1.0); // Normalize to minutes
-        let popularity_factor = (total_views as f64).ln() / 15.0; // Logarithmic scaling
+        let popularity_factor = (total_views.max(1) as f64).ln() / 15.0; // Logarithmic scaling
 
         (engagement_score * 0.5 + time_factor * 0.3 + popularity_factor.min(1.0) * 0.2).min(1.0)
     }
@@ -122,18 +330,35 @@ impl EchoEngine {
         sentiment_score: f64,
         credibility_score: f64,
         relevance_score: f64,
-        originality_score: f64
+        originality_score: f64,
+        link_quality_score: f64,
+        reactions: &HashMap<ReactionType, u64>
     ) -> f64 {
         // Normalize all scores to 0-1 range
         let normalized_sentiment = (sentiment_score + 1.0) / 2.0; // From [-1,1] to [0,1]
-        let normalized_credibility = credibility_score.max(0.0).min(1.0);
-        let normalized_relevance = relevance_score.max(0.0).min(1.0);
-        let normalized_originality = originality_score.max(0.0).min(1.0);
+        let normalized_credibility = credibility_score.clamp(0.0, 1.0);
+        let normalized_relevance = relevance_score.clamp(0.0, 1.0);
+        let normalized_originality = originality_score.clamp(0.0, 1.0);
+        let normalized_link_quality = link_quality_score.clamp(0.0, 1.0);
+        let reaction_quality = self.reaction_quality(reactions);
+
+        (normalized_sentiment * QF_SENTIMENT_WEIGHT + 
+         normalized_credibility * QF_CREDIBILITY_WEIGHT + 
+         normalized_relevance * QF_RELEVANCE_WEIGHT + 
+         normalized_originality * QF_ORIGINALITY_WEIGHT +
+         normalized_link_quality * QF_LINK_QUALITY_WEIGHT +
+         reaction_quality * QF_REACTION_QUALITY_WEIGHT).min(1.0)
+    }
 
-        (normalized_sentiment * 0.2 + 
-         normalized_credibility * 0.3 + 
-         normalized_relevance * 0.3 + 
-         normalized_originality * 0.2).min(1.0)
+    /// Mean weight of the content's reactions relative to the heaviest configured weight,
+    /// so content drawing quote comments scores above content drawing only likes
+    fn reaction_quality(&self, reactions: &HashMap<ReactionType, u64>) -> f64 {
+        let count: u64 = reactions.values().sum();
+        let heaviest = ReactionType::ALL.iter().map(|reaction| self.reaction_weight(reaction)).fold(0.0, f64::max);
+        if count == 0 || heaviest <= 0.0 {
+            return 0.0;
+        }
+        (self.weighted_reactions(reactions) / count as f64 / heaviest).min(1.0)
     }
 
     /// Apply temporal decay to existing Echo Index
@@ -143,11 +368,13 @@ impl EchoEngine {
     }
 
     /// Calculate complete Echo Index with all components
+    #[allow(clippy::too_many_arguments)]
     pub fn calculate_complete_echo_index(&self,
+        platform: &Platform,
         shares_from_discovery: u32,
         total_shares: u32,
         platform_reach: u32,
-        engagement_metrics: &HashMap<String, f64>,
+        reactions: &HashMap<ReactionType, u64>,
         view_time: f64,
         total_views: u32,
         creation_time: i64,
@@ -156,12 +383,20 @@ impl EchoEngine {
         sentiment_score: f64,
         credibility_score: f64,
         relevance_score: f64,
-        originality_score: f64
+        originality_score: f64,
+        links: &LinkQualityReport
     ) -> (f64, EchoMetrics) {
-        let odf = self.calculate_odf(shares_from_discovery, total_shares, platform_reach);
-        let awr = self.calculate_awr(engagement_metrics, view_time, total_views);
+        let odf = self.calculate_odf(shares_from_discovery, total_shares, platform_reach, platform);
+        let awr = self.calculate_awr(reactions, view_time, total_views);
         let tpm = self.calculate_tpm(creation_time, last_interaction, interaction_frequency);
-        let qf = self.calculate_qf(sentiment_score, credibility_score, relevance_score, originality_score);
+        let qf = self.calculate_qf(
+            sentiment_score,
+            credibility_score,
+            relevance_score,
+            originality_score,
+            links.score,
+            reactions,
+        );
 
         let metrics = EchoMetrics {
             organic_discovery_factor: odf,
@@ -173,4 +408,114 @@ impl EchoEngine {
         let echo_index = self.calculate_echo_index(&metrics);
         (echo_index, metrics)
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linkedin_odf_normalized_above_twitter() {
+        let engine = EchoEngine::new(EchoEngineConfig::default());
+
+        let twitter = engine.calculate_odf(30, 100, 1_000, &Platform::Twitter);
+        let linkedin = engine.calculate_odf(30, 100, 1_000, &Platform::LinkedIn);
+        let unknown = engine.calculate_odf(30, 100, 1_000, &Platform::from("myspace"));
+
+        assert!(linkedin > twitter);
+        assert_eq!(unknown, twitter);
+    }
+
+    #[test]
+    fn test_link_quality_counts_for_a_tenth_of_quality() {
+        let engine = EchoEngine::default();
+        let cited = engine.calculate_qf(0.0, 0.5, 0.5, 0.5, 1.0, &HashMap::new());
+        let spammy = engine.calculate_qf(0.0, 0.5, 0.5, 0.5, 0.0, &HashMap::new());
+        assert!((cited - spammy - 0.1).abs() < 1e-9);
+        let quoted = HashMap::from([(ReactionType::QuoteComment, 3)]);
+        assert!((engine.calculate_qf(1.0, 1.0, 1.0, 1.0, 1.0, &quoted) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reactions_count_by_weight() {
+        let engine = EchoEngine::default();
+        let liked = HashMap::from([(ReactionType::Like, 30)]);
+        let quoted = HashMap::from([(ReactionType::Like, 10), (ReactionType::QuoteComment, 10)]);
+        assert_eq!(engine.weighted_reactions(&quoted), 40.0);
+
+        // Fewer reactions, but heavier ones
+        assert!(engine.calculate_awr(&quoted, 30.0, 500) > engine.calculate_awr(&liked, 30.0, 500));
+        let qf = |reactions| engine.calculate_qf(0.5, 0.5, 0.5, 0.5, 0.5, reactions);
+        assert!(qf(&quoted) > qf(&liked));
+        assert!(qf(&liked) > qf(&HashMap::new()));
+        assert!(engine.calculate_awr(&HashMap::from([(ReactionType::Save, u64::MAX)]), 0.0, 1) <= 1.0);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert_eq!(EchoEngineConfig::default().validate(), Ok(()));
+
+        let skewed = EchoEngineConfig { odf_weight: 0.5, ..EchoEngineConfig::default() };
+        assert!(matches!(skewed.validate(), Err(EngineConfigError::InvalidWeightSum(_))));
+
+        let decay = EchoEngineConfig { decay_factor: 1.5, ..EchoEngineConfig::default() };
+        assert!(matches!(decay.validate(), Err(EngineConfigError::OutOfRange { field: "decay_factor", .. })));
+        let nan = EchoEngineConfig { boost_threshold: f64::NAN, ..EchoEngineConfig::default() };
+        assert!(nan.validate().is_err());
+
+        let mut negative = EchoEngineConfig::default();
+        negative.language_normalization_factors.insert("jpn".to_string(), -1.0);
+        assert_eq!(negative.validate(), Err(EngineConfigError::InvalidNormalization("jpn".to_string())));
+        let mut unliked = EchoEngineConfig::default();
+        unliked.reaction_weights.insert(ReactionType::Like, -0.5);
+        assert_eq!(unliked.validate(), Err(EngineConfigError::InvalidReactionWeight(ReactionType::Like)));
+    }
+
+    #[test]
+    fn test_config_diff_lists_changed_settings() {
+        let old = EchoEngineConfig::default();
+        let mut new = EchoEngineConfig {
+            decay_factor: 0.9,
+            weight_normalization: WeightNormalizationStrategy::MinMax,
+            ..old.clone()
+        };
+        new.platform_odf_normalization.insert(Platform::Medium, 1.5);
+        new.platform_odf_normalization.insert(Platform::Discord, 1.2);
+        new.language_normalization_factors.remove("ita");
+        new.reaction_weights.insert(ReactionType::QuoteComment, 4.0);
+
+        assert_eq!(
+            old.diff(&new),
+            [
+                "decay_factor: 0.95 -> 0.9",
+                "weight_normalization_strategy: rank_based -> min_max",
+                "platform_odf_normalization.discord: (unset) -> 1.2",
+                "platform_odf_normalization.medium: 1.6 -> 1.5",
+                "language_normalization_factors.ita: 0.95 -> (removed)",
+                "reaction_weights.quote_comment: 3 -> 4",
+            ]
+        );
+        assert!(new.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn test_config_parses_partial_toml() {
+        let config: EchoEngineConfig = toml::from_str(
+            "decay_factor = 0.9\n\n[platform_odf_normalization]\ntwitter = 1.0\nlinkedin = 1.3\n\n\
+             [reaction_weights]\nquote_comment = 4.0\n",
+        )
+        .unwrap();
+        assert_eq!(config.decay_factor, 0.9);
+        assert_eq!(config.odf_weight, EchoEngineConfig::default().odf_weight);
+        assert_eq!(config.platform_odf_normalization.get(&Platform::LinkedIn), Some(&1.3));
+        assert_eq!(config.platform_odf_normalization.len(), 2);
+        assert_eq!(config.reaction_weights, HashMap::from([(ReactionType::QuoteComment, 4.0)]));
+        assert!(toml::from_str::<EchoEngineConfig>("decay = 0.9").is_err());
+    }
+
+    #[test]
+    fn test_shipped_config_file_matches_defaults() {
+        let config: EchoEngineConfig = toml::from_str(include_str!("../../config/echo_engine.toml")).unwrap();
+        assert_eq!(config, EchoEngineConfig::default());
+    }
+}
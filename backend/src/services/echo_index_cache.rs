@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use moka::sync::Cache;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::models::content::EchoIndex;
+
+/// Content items whose latest calculation is kept
+pub const ECHO_INDEX_CACHE_CAPACITY: u64 = 10_000;
+/// How long a calculation is reused at most, even without new propagations
+const ECHO_INDEX_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Lookups served by `EchoIndexCache` since startup
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EchoIndexCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+    pub miss_rate: f64,
+    /// Calculations currently cached
+    pub size: u64,
+}
+
+/// Latest Echo Index calculation of each content item with the time it was made. A
+/// calculation is reused until the content is propagated again or it expires.
+pub struct EchoIndexCache {
+    calculations: Cache<Uuid, (EchoIndex, DateTime<Utc>)>,
+    // moka keeps no hit counts of its own
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl EchoIndexCache {
+    pub fn new() -> Self {
+        Self {
+            calculations: Cache::builder()
+                .max_capacity(ECHO_INDEX_CACHE_CAPACITY)
+                .time_to_live(ECHO_INDEX_CACHE_TTL)
+                .build(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The cached calculation of the content, unless it was made before
+    /// `last_propagated_at`
+    pub fn get(&self, content_id: Uuid, last_propagated_at: Option<DateTime<Utc>>) -> Option<EchoIndex> {
+        let fresh = self
+            .calculations
+            .get(&content_id)
+            .filter(|(_, calculated_at)| last_propagated_at.is_none_or(|propagated| propagated <= *calculated_at));
+
+        let counter = if fresh.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        fresh.map(|(echo_index, _)| echo_index)
+    }
+
+    /// Cache a calculation made at `calculated_at` from the propagations recorded by then
+    pub fn insert(&self, content_id: Uuid, echo_index: EchoIndex, calculated_at: DateTime<Utc>) {
+        self.calculations.insert(content_id, (echo_index, calculated_at));
+    }
+
+    pub fn stats(&self) -> EchoIndexCacheStats {
+        let (hits, misses) = (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed));
+        let lookups = hits + misses;
+        let rate = |count: u64| if lookups > 0 { count as f64 / lookups as f64 } else { 0.0 };
+        self.calculations.run_pending_tasks();
+
+        EchoIndexCacheStats {
+            hits,
+            misses,
+            hit_rate: rate(hits),
+            miss_rate: rate(misses),
+            size: self.calculations.entry_count(),
+        }
+    }
+}
+
+impl Default for EchoIndexCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
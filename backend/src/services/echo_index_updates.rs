@@ -0,0 +1,134 @@
+use dashmap::DashMap;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// Buffered updates per content before slow subscribers start lagging
+const CHANNEL_CAPACITY: usize = 16;
+/// Buffered updates of all content before slow subscribers start lagging
+const ALL_CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct EchoIndexComponents {
+    pub odf: f64,
+    pub awr: f64,
+    pub tpm: f64,
+    pub qf: f64,
+}
+
+/// Score change pushed to subscribers of a content item
+#[derive(Debug, Clone, Serialize)]
+pub struct EchoIndexUpdate {
+    pub content_id: String,
+    pub score: f64,
+    pub delta: f64,
+    pub components: EchoIndexComponents,
+}
+
+/// Fan-out of Echo Index results to live subscribers, one broadcast channel per content
+pub struct EchoIndexUpdates {
+    channels: DashMap<String, broadcast::Sender<EchoIndexUpdate>>,
+    latest: DashMap<String, EchoIndexUpdate>,
+    /// Every update, for subscribers that follow all content
+    all: broadcast::Sender<EchoIndexUpdate>,
+}
+
+impl EchoIndexUpdates {
+    pub fn new() -> Self {
+        Self {
+            channels: DashMap::new(),
+            latest: DashMap::new(),
+            all: broadcast::channel(ALL_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    /// Subscribe to a content item, returning its last known score alongside the receiver
+    pub fn subscribe(&self, content_id: &str) -> (Option<EchoIndexUpdate>, broadcast::Receiver<EchoIndexUpdate>) {
+        let receiver = self
+            .channels
+            .entry(content_id.to_string())
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe();
+        let current = self.latest.get(content_id).map(|update| update.clone());
+        (current, receiver)
+    }
+
+    /// Subscribe to the updates of every content item
+    pub fn subscribe_all(&self) -> broadcast::Receiver<EchoIndexUpdate> {
+        self.all.subscribe()
+    }
+
+    /// Record a freshly calculated score and push it to any subscribers
+    pub fn publish(&self, content_id: &str, score: f64, components: EchoIndexComponents) -> EchoIndexUpdate {
+        let previous = self.latest.get(content_id).map(|update| update.score);
+        let update = EchoIndexUpdate {
+            content_id: content_id.to_string(),
+            score,
+            delta: previous.map(|previous| score - previous).unwrap_or(0.0),
+            components,
+        };
+        self.latest.insert(content_id.to_string(), update.clone());
+
+        if let Some(sender) = self.channels.get(content_id) {
+            // An error only means every subscriber has gone away
+            let _ = sender.send(update.clone());
+        }
+        let _ = self.all.send(update.clone());
+
+        update
+    }
+
+    /// Drop the channel for a content item once its last subscriber has disconnected
+    pub fn release(&self, content_id: &str) {
+        self.channels
+            .remove_if(content_id, |_, sender| sender.receiver_count() == 0);
+    }
+}
+
+impl Default for EchoIndexUpdates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPONENTS: EchoIndexComponents = EchoIndexComponents {
+        odf: 70.0,
+        awr: 80.0,
+        tpm: 60.0,
+        qf: 50.0,
+    };
+
+    #[tokio::test]
+    async fn test_publish_reaches_subscribers_with_delta() {
+        let updates = EchoIndexUpdates::new();
+        let (current, mut receiver) = updates.subscribe("content_1");
+        assert!(current.is_none());
+
+        updates.publish("content_1", 72.3, COMPONENTS);
+        updates.publish("content_1", 74.4, COMPONENTS);
+
+        assert_eq!(receiver.recv().await.unwrap().delta, 0.0);
+        let second = receiver.recv().await.unwrap();
+        assert_eq!(second.score, 74.4);
+        assert!((second.delta - 2.1).abs() < 1e-9);
+
+        let (current, _) = updates.subscribe("content_1");
+        assert_eq!(current.unwrap().score, 74.4);
+    }
+
+    #[test]
+    fn test_release_drops_idle_channels() {
+        let updates = EchoIndexUpdates::new();
+        let (_, receiver) = updates.subscribe("content_1");
+
+        updates.release("content_1");
+        assert!(updates.channels.contains_key("content_1"));
+
+        drop(receiver);
+        updates.release("content_1");
+        assert!(updates.channels.is_empty());
+    }
+}
@@ -0,0 +1,215 @@
+use ordered_float::OrderedFloat;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::repositories::{ContentRepository, RepositoryError};
+use crate::services::EchoIndexUpdates;
+
+/// Share of scored content, from the top, in the Gold tier, in Silver or above and in
+/// Bronze or above
+const GOLD_SHARE: f64 = 0.05;
+const SILVER_SHARE: f64 = 0.20;
+const BRONZE_SHARE: f64 = 0.50;
+/// Until this much content has been scored, tiers keep their fixed cutoffs
+pub const MIN_RANKED_CONTENT: usize = 20;
+
+/// Lowest score of each tier
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+pub struct TierCutoffs {
+    pub gold_min: f64,
+    pub silver_min: f64,
+    pub bronze_min: f64,
+}
+
+impl TierCutoffs {
+    /// Cutoffs used while too little content has been scored to rank by percentile
+    pub const FIXED: TierCutoffs = TierCutoffs { gold_min: 80.0, silver_min: 60.0, bronze_min: 40.0 };
+
+    pub fn tier(&self, score: f64) -> &'static str {
+        match score {
+            s if s >= self.gold_min => "Gold",
+            s if s >= self.silver_min => "Silver",
+            s if s >= self.bronze_min => "Bronze",
+            _ => "Basic",
+        }
+    }
+}
+
+impl Default for TierCutoffs {
+    fn default() -> Self {
+        Self::FIXED
+    }
+}
+
+#[derive(Default)]
+struct RankedScores {
+    by_score: BTreeMap<OrderedFloat<f64>, Vec<Uuid>>,
+    by_content: HashMap<Uuid, OrderedFloat<f64>>,
+}
+
+/// Latest Echo Index of all scored content in score order, for ranking a score against the
+/// rest of the platform. Kept up to date from live Echo Index updates.
+pub struct EchoIndexPercentileCache {
+    scores: RwLock<RankedScores>,
+}
+
+impl EchoIndexPercentileCache {
+    pub fn new() -> Self {
+        Self { scores: RwLock::new(RankedScores::default()) }
+    }
+
+    /// Rank every scored content item in the database. Returns how many were ranked.
+    pub async fn load(&self, content: &ContentRepository) -> Result<usize, RepositoryError> {
+        let scores = content.echo_index_scores().await?;
+        for (content_id, score) in &scores {
+            self.record(*content_id, *score);
+        }
+        Ok(scores.len())
+    }
+
+    /// Rank a content item at its latest score
+    pub fn record(&self, content_id: Uuid, score: f64) {
+        if !score.is_finite() {
+            return;
+        }
+        let Ok(mut guard) = self.scores.write() else { return };
+        let scores = &mut *guard;
+        let score = OrderedFloat(score);
+
+        if let Some(previous) = scores.by_content.insert(content_id, score) {
+            if previous == score {
+                return;
+            }
+            if let Some(content) = scores.by_score.get_mut(&previous) {
+                content.retain(|id| *id != content_id);
+                if content.is_empty() {
+                    scores.by_score.remove(&previous);
+                }
+            }
+        }
+        scores.by_score.entry(score).or_default().push(content_id);
+    }
+
+    /// Fraction of scored content with a lower score than `score`, counting content with
+    /// the same score as half lower, so the median content sits at 0.5. 0 with no content.
+    pub fn percentile_for(&self, score: f64) -> f64 {
+        let Ok(scores) = self.scores.read() else { return 0.0 };
+        let total = scores.by_content.len();
+        if total == 0 {
+            return 0.0;
+        }
+
+        let score = OrderedFloat(score);
+        let lower: usize = scores.by_score.range(..score).map(|(_, content)| content.len()).sum();
+        let tied = scores.by_score.get(&score).map_or(0, Vec::len);
+        (lower as f64 + tied as f64 / 2.0) / total as f64
+    }
+
+    /// `percentile_for` on a 0-100 scale
+    pub fn percentile_rank(&self, score: f64) -> f64 {
+        self.percentile_for(score) * 100.0
+    }
+
+    /// Current lowest score of each tier: the top 5% of content is Gold, the next 15%
+    /// Silver and the next 30% Bronze. Fixed cutoffs until `MIN_RANKED_CONTENT` items are
+    /// scored.
+    pub fn tier_cutoffs(&self) -> TierCutoffs {
+        let Ok(scores) = self.scores.read() else { return TierCutoffs::FIXED };
+        let total = scores.by_content.len();
+        if total < MIN_RANKED_CONTENT {
+            return TierCutoffs::FIXED;
+        }
+
+        // Score of the lowest content within the top `share`
+        let lowest_in_top = |share: f64| {
+            let position = ((total as f64 * (1.0 - share)).floor() as usize).min(total - 1);
+            let mut seen = 0;
+            for (score, content) in &scores.by_score {
+                seen += content.len();
+                if seen > position {
+                    return score.0;
+                }
+            }
+            f64::MAX
+        };
+        TierCutoffs {
+            gold_min: lowest_in_top(GOLD_SHARE),
+            silver_min: lowest_in_top(SILVER_SHARE),
+            bronze_min: lowest_in_top(BRONZE_SHARE),
+        }
+    }
+
+    /// Rank every Echo Index update of stored content as it is published
+    pub fn spawn_update_task(self: Arc<Self>, updates: Arc<EchoIndexUpdates>) -> JoinHandle<()> {
+        let mut receiver = updates.subscribe_all();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    // Scores calculated on request for arbitrary ids are not platform content
+                    Ok(update) => {
+                        if let Ok(content_id) = Uuid::parse_str(&update.content_id) {
+                            self.record(content_id, update.score);
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        log::warn!("Echo Index percentiles missed {} updates", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+impl Default for EchoIndexPercentileCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranked(scores: impl IntoIterator<Item = f64>) -> EchoIndexPercentileCache {
+        let cache = EchoIndexPercentileCache::new();
+        for score in scores {
+            cache.record(Uuid::new_v4(), score);
+        }
+        cache
+    }
+
+    #[test]
+    fn test_median_content_is_at_the_50th_percentile() {
+        let cache = ranked([12.0, 30.5, 47.0, 74.4, 91.0]);
+        assert_eq!(cache.percentile_rank(47.0), 50.0);
+        assert_eq!(cache.percentile_rank(5.0), 0.0);
+        assert_eq!(cache.percentile_rank(100.0), 100.0);
+        assert_eq!(cache.percentile_for(74.4), 0.7);
+
+        // Rescored content is ranked at its new score only
+        let rescored = Uuid::new_v4();
+        cache.record(rescored, 20.0);
+        cache.record(rescored, 95.0);
+        assert_eq!(cache.scores.read().unwrap().by_content.len(), 6);
+        assert_eq!(cache.percentile_rank(93.0), 5.0 / 6.0 * 100.0);
+        assert_eq!(EchoIndexPercentileCache::new().percentile_for(50.0), 0.0);
+    }
+
+    #[test]
+    fn test_tier_cutoffs_follow_the_score_distribution() {
+        assert_eq!(ranked([10.0, 20.0, 30.0]).tier_cutoffs(), TierCutoffs::FIXED);
+
+        let cutoffs = ranked((1..=100).map(f64::from)).tier_cutoffs();
+        assert_eq!(cutoffs, TierCutoffs { gold_min: 96.0, silver_min: 81.0, bronze_min: 51.0 });
+        assert_eq!(cutoffs.tier(96.0), "Gold");
+        assert_eq!(cutoffs.tier(95.9), "Silver");
+        assert_eq!(cutoffs.tier(51.0), "Bronze");
+        assert_eq!(cutoffs.tier(50.0), "Basic");
+    }
+}
@@ -1,18 +1,67 @@
-use crate::models::{content::*, echo_index::*};
+use crate::models::{content::*, echo_index::*, echo_index_history::EchoIndexTrigger, experiment::Experiment};
+use crate::models::moderation::{CONTENT_UNDER_REVIEW, UNDER_REVIEW_ECHO_WEIGHT};
+use crate::repositories::{EchoIndexHistoryRepository, EchoIndexScores};
+use crate::services::language::{detect_language, Lexicon};
+use crate::services::{BotDetector, ContentNormalizer, NormalizedContent, PlatformNormalizer, Readability};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 
 pub struct EchoService;
 
 impl EchoService {
-    /// Calculate comprehensive Echo Index for content
+    /// Calculate comprehensive Echo Index for content. During an experiment the content is
+    /// scored with the weights of its variant instead of `calculator`'s.
+    #[tracing::instrument(
+        skip_all,
+        fields(content_id = %content.id, platform = ?content.platform, propagations = propagations.len(), ?trigger),
+        err
+    )]
+    #[allow(clippy::too_many_arguments)]
     pub async fn calculate_echo_index(
         content: &Content,
         propagations: &[Propagation],
         interactions: &[AudienceMetrics],
+        calculator: &EchoIndexCalculator,
+        language_factors: &HashMap<String, f64>,
+        bot_detector: &BotDetector,
+        history: &EchoIndexHistoryRepository,
+        trigger: EchoIndexTrigger,
+        experiment: Option<&Experiment>,
     ) -> Result<EchoIndex, Box<dyn std::error::Error>> {
-        // Analyze content to extract metrics
-        let content_metrics = Self::analyze_content(&content.text).await?;
+        let assignment = experiment.map(|experiment| experiment.assign(content.id));
+        let variant_calculator = match (experiment, assignment) {
+            (Some(experiment), Some(assignment)) => Some(experiment.weights(assignment.variant).calculator()?),
+            _ => None,
+        };
+        let calculator = variant_calculator.as_ref().unwrap_or(calculator);
+        let echo_index = Self::score(content, propagations, interactions, calculator, language_factors, bot_detector)
+            .await?;
+
+        // Record the calculation so score changes can be tracked over time
+        let scores = EchoIndexScores {
+            score: echo_index.overall_score,
+            odf: echo_index.originality_depth_factor,
+            awr: echo_index.audience_weight_rating,
+            tpm: echo_index.transmission_path_mapping,
+            qf: echo_index.quote_frequency,
+        };
+        history.record(content.id, scores, trigger, assignment).await?;
+
+        Ok(echo_index)
+    }
+
+    /// Echo Index of content scored with `calculator`'s weights, without recording it
+    pub async fn score(
+        content: &Content,
+        propagations: &[Propagation],
+        interactions: &[AudienceMetrics],
+        calculator: &EchoIndexCalculator,
+        language_factors: &HashMap<String, f64>,
+        bot_detector: &BotDetector,
+    ) -> Result<EchoIndex, Box<dyn std::error::Error>> {
+        // Analyze the prose, without platform markup, to extract metrics
+        let normalized = PlatformNormalizer::normalize(&content.text, &content.platform);
+        let content_metrics = Self::analyze_content(&normalized, language_factors).await?;
         
         // Calculate propagation metrics
         let propagation_metrics = Self::calculate_propagation_metrics(propagations).await?;
@@ -23,14 +72,37 @@ impl EchoService {
         // Calculate quote metrics
         let quote_metrics = Self::calculate_quote_metrics(content, propagations).await?;
         
-        // Calculate individual components
-        let odf = EchoIndexCalculator::calculate_odf(&content.text, &content_metrics);
+        // Scripted shares should not inflate originality
+        let bot_scores = bot_detector.score(propagations);
+        
+        // Calculate individual components; verified authors earn an originality bonus, also
+        // for cross-posting to platforms with another reach, and content riding a trending
+        // hashtag or echoing back through loops a transmission one
+        let odf = EchoIndexCalculator::apply_cross_platform_bonus(
+            EchoIndexCalculator::apply_verified_account_bonus(
+                EchoIndexCalculator::discount_bot_propagation(
+                    EchoIndexCalculator::calculate_odf(&content.text, &content_metrics),
+                    &bot_scores,
+                ),
+                content.author_verified,
+            ),
+            content.cross_platform_bonus,
+        );
         let awr = EchoIndexCalculator::calculate_awr(&audience_metrics);
-        let tpm = EchoIndexCalculator::calculate_tpm(&propagation_metrics);
+        let tpm = EchoIndexCalculator::apply_echo_loop_contribution(
+            EchoIndexCalculator::apply_trending_hashtag_bonus(
+                EchoIndexCalculator::calculate_tpm(&propagation_metrics),
+                content.trending_hashtag_bonus,
+            ),
+            content.echo_loop_strength,
+        );
         let qf = EchoIndexCalculator::calculate_qf(&quote_metrics);
         
-        // Calculate overall score
-        let overall_score = EchoIndexCalculator::calculate_overall_score(odf, awr, tpm, qf);
+        // Calculate overall score; flagged content counts for less until a moderator clears it
+        let mut overall_score = calculator.calculate_overall_score(odf, awr, tpm, qf);
+        if content.status == CONTENT_UNDER_REVIEW {
+            overall_score *= UNDER_REVIEW_ECHO_WEIGHT;
+        }
         
         Ok(EchoIndex {
             originality_depth_factor: odf,
@@ -41,17 +113,24 @@ impl EchoService {
         })
     }
     
-    /// Analyze content to extract meaningful metrics
-    async fn analyze_content(text: &str) -> Result<EchoMetrics, Box<dyn std::error::Error>> {
+    /// Analyze content to extract meaningful metrics, scored by the rules of the language
+    /// it is written in. Readability is scaled by that language's factor in `language_factors`.
+    pub(crate) async fn analyze_content(
+        content: &NormalizedContent,
+        language_factors: &HashMap<String, f64>,
+    ) -> Result<EchoMetrics, Box<dyn std::error::Error>> {
+        let text = content.clean_text.as_str();
         let words: Vec<&str> = text.split_whitespace().collect();
         let word_count = words.len();
         let unique_words = words.iter().collect::<std::collections::HashSet<_>>().len();
+        let detected_language = detect_language(text);
         
-        // Simple sentiment analysis (placeholder for more sophisticated analysis)
-        let sentiment_score = Self::calculate_sentiment(text).await?;
+        // Word-list sentiment analysis in the content's language
+        let sentiment_score = Lexicon::for_language(&detected_language).sentiment(text);
         
-        // Basic readability score using Flesch formula approximation
-        let readability_score = Self::calculate_readability(text).await?;
+        // Flesch Reading Ease and Flesch-Kincaid Grade Level
+        let readability = Readability::of_language(text, &detected_language);
+        let language_factor = language_factors.get(&detected_language).copied().unwrap_or(1.0);
         
         // Detect originality markers
         let originality_markers = Self::detect_originality_markers(text).await?;
@@ -61,8 +140,10 @@ impl EchoService {
             word_count,
             unique_words,
             sentiment_score,
-            readability_score,
+            readability_score: (readability.normalized() * language_factor).clamp(0.0, 1.0),
+            grade_level: readability.grade_level,
             originality_markers,
+            detected_language,
         })
     }
     
@@ -109,7 +190,7 @@ impl EchoService {
     
     /// Calculate quote-related metrics
     async fn calculate_quote_metrics(
-        content: &Content,
+        _content: &Content,
         propagations: &[Propagation]
     ) -> Result<QuoteMetrics, Box<dyn std::error::Error>> {
         let direct_quotes = propagations
@@ -143,81 +224,6 @@ impl EchoService {
         })
     }
     
-    /// Calculate sentiment score using simple heuristics
-    async fn calculate_sentiment(text: &str) -> Result<f64, Box<dyn std::error::Error>> {
-        let positive_words = [
-            "good", "great", "excellent", "amazing", "brilliant", "innovative",
-            "revolutionary", "breakthrough", "success", "positive", "love", "like"
-        ];
-        
-        let negative_words = [
-            "bad", "terrible", "awful", "horrible", "failure", "problem",
-            "issue", "wrong", "negative", "hate", "dislike", "poor"
-        ];
-        
-        let words: Vec<&str> = text.to_lowercase().split_whitespace().collect();
-        let mut sentiment_score = 0.0;
-        
-        for word in &words {
-            if positive_words.contains(word) {
-                sentiment_score += 1.0;
-            } else if negative_words.contains(word) {
-                sentiment_score -= 1.0;
-            }
-        }
-        
-        // Normalize to [-1, 1] range
-        if !words.is_empty() {
-            sentiment_score /= words.len() as f64;
-        }
-        
-        Ok(sentiment_score.max(-1.0).min(1.0))
-    }
-    
-    /// Calculate readability score (simplified Flesch formula)
-    async fn calculate_readability(text: &str) -> Result<f64, Box<dyn std::error::Error>> {
-        let sentences = text.split(&['.', '!', '?'][..]).count() as f64;
-        let words = text.split_whitespace().count() as f64;
-        let syllables = Self::count_syllables(text).await? as f64;
-        
-        if sentences == 0.0 || words == 0.0 {
-            return Ok(0.0);
-        }
-        
-        let avg_sentence_length = words / sentences;
-        let avg_syllables_per_word = syllables / words;
-        
-        // Simplified Flesch Reading Ease formula
-        let score = 206.835 - (1.015 * avg_sentence_length) - (84.6 * avg_syllables_per_word);
-        
-        // Normalize to [0, 1] range
-        Ok((score / 100.0).max(0.0).min(1.0))
-    }
-    
-    /// Count syllables in text (approximation)
-    async fn count_syllables(text: &str) -> Result<usize, Box<dyn std::error::Error>> {
-        let vowels = ['a', 'e', 'i', 'o', 'u', 'y'];
-        let mut syllable_count = 0;
-        
-        for word in text.to_lowercase().split_whitespace() {
-            let mut word_syllables = 0;
-            let mut prev_was_vowel = false;
-            
-            for ch in word.chars() {
-                let is_vowel = vowels.contains(&ch);
-                if is_vowel && !prev_was_vowel {
-                    word_syllables += 1;
-                }
-                prev_was_vowel = is_vowel;
-            }
-            
-            // Every word has at least one syllable
-            syllable_count += word_syllables.max(1);
-        }
-        
-        Ok(syllable_count)
-    }
-    
     /// Detect originality markers in content
     async fn detect_originality_markers(text: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
         let originality_keywords = [
@@ -246,7 +252,7 @@ impl EchoService {
         
         let timestamps: Vec<DateTime<Utc>> = propagations
             .iter()
-            .map(|p| p.timestamp.parse().unwrap_or_else(|_| Utc::now()))
+            .map(|p| p.timestamp)
             .collect();
         
         if let (Some(earliest), Some(latest)) = (timestamps.iter().min(), timestamps.iter().max()) {
@@ -279,13 +285,14 @@ impl EchoService {
             quality_score += propagation.weight * type_weight;
         }
         
-        Ok((quality_score / total_citations).max(0.0).min(1.0))
+        Ok((quality_score / total_citations).clamp(0.0, 1.0))
     }
     
     /// Update Echo Index for existing content
     pub async fn update_echo_index(
-        content_id: &str,
-        new_propagations: &[Propagation]
+        _content_id: &str,
+        new_propagations: &[Propagation],
+        calculator: &EchoIndexCalculator,
     ) -> Result<EchoIndex, Box<dyn std::error::Error>> {
         // This would typically fetch the content from database
         // For now, we'll use placeholder logic
@@ -301,7 +308,7 @@ impl EchoService {
         let awr = 0.7; // Placeholder - would come from existing calculation
         let qf = 0.6;  // Placeholder - would come from existing calculation
         
-        let overall_score = EchoIndexCalculator::calculate_overall_score(odf, awr, tpm, qf);
+        let overall_score = calculator.calculate_overall_score(odf, awr, tpm, qf);
         
         Ok(EchoIndex {
             originality_depth_factor: odf,
@@ -323,4 +330,61 @@ impl Default for AudienceMetrics {
             engagement_depth: 0.0,
         }
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Platform;
+    use chrono::Duration;
+    use uuid::Uuid;
+
+    fn propagation(content_id: Uuid, from_user_id: Uuid, timestamp: DateTime<Utc>, engagement: i64) -> Propagation {
+        Propagation {
+            id: Uuid::new_v4(),
+            content_id,
+            from_user_id,
+            to_user_id: None,
+            platform: Platform::Twitter,
+            propagation_type: "share".to_string(),
+            depth: 1,
+            weight: 1.0,
+            timestamp,
+            reach: 100,
+            engagement,
+            bot_score: 0.0,
+            fingerprint_checked: true,
+            duplicate_of: None,
+        }
+    }
+
+    async fn odf(content: &Content, propagations: &[Propagation], bot_detector: &BotDetector) -> f64 {
+        EchoService::score(content, propagations, &[], &EchoIndexCalculator::default(), &HashMap::new(), bot_detector)
+            .await
+            .unwrap()
+            .originality_depth_factor
+    }
+
+    #[tokio::test]
+    async fn test_odf_ignores_bot_propagation() {
+        let content = Content::new(
+            Uuid::new_v4(),
+            "Decentralized reputation should travel with its owner across every network they use.".to_string(),
+            Platform::Twitter,
+            "https://twitter.com/echolayer/status/1".to_string(),
+        );
+
+        // A burst of zero-engagement shares from an account created alongside the content
+        let bot = Uuid::new_v4();
+        let bot_detector = BotDetector::new(content.created_at)
+            .with_account_ages(HashMap::from([(bot, content.created_at + Duration::minutes(5))]));
+        let start = Utc::now();
+        let scripted: Vec<_> =
+            (0..6).map(|i| propagation(content.id, bot, start + Duration::seconds(i * 5), 0)).collect();
+        assert!(bot_detector.score(&scripted).iter().all(|&score| score == 1.0));
+        assert_eq!(odf(&content, &scripted, &bot_detector).await, 0.0);
+
+        let organic: Vec<_> =
+            (0..6).map(|i| propagation(content.id, Uuid::new_v4(), start + Duration::minutes(i * 10), 12)).collect();
+        assert!(odf(&content, &organic, &bot_detector).await > 0.0);
+    }
+}
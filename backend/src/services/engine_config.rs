@@ -0,0 +1,344 @@
+use arc_swap::ArcSwap;
+use chrono::{DateTime, Utc};
+use notify::event::{AccessKind, AccessMode};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::services::echo_engine::{EchoEngineConfig, EngineConfigError};
+
+pub const DEFAULT_CONFIG_FILE_PATH: &str = "config/echo_engine.toml";
+/// Editors and deploy tools often write a file in several steps; changes arriving within
+/// this window are applied with a single reload
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Where the live config came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    Default,
+    File,
+    /// Pushed through the admin API
+    Api,
+}
+
+/// The config in force, swapped as a whole so readers never see a mix of two versions
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveEngineConfig {
+    pub config: EchoEngineConfig,
+    pub source: ConfigSource,
+    /// Settings taken from `ECHO_ENGINE_*` environment variables rather than the source
+    pub env_overrides: Vec<&'static str>,
+    pub loaded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigReloadError {
+    #[error("failed to read config file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse config file: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error(transparent)]
+    Invalid(#[from] EngineConfigError),
+}
+
+/// Echo engine config that operators can change without a restart, by editing the
+/// TOML file at `CONFIG_FILE_PATH` or through the admin API. Every change is validated
+/// before it is swapped in and the settings it changed are logged.
+pub struct EngineConfigStore {
+    live: ArcSwap<LiveEngineConfig>,
+    path: PathBuf,
+    /// Applied on top of the file, which cannot change them
+    env_overrides: Vec<(&'static str, f64)>,
+}
+
+impl EngineConfigStore {
+    /// Store serving `config` until the file at `path` changes
+    pub fn new(config: EchoEngineConfig, path: impl Into<PathBuf>) -> Self {
+        Self {
+            live: ArcSwap::from_pointee(LiveEngineConfig {
+                config,
+                source: ConfigSource::Default,
+                env_overrides: Vec::new(),
+                loaded_at: Utc::now(),
+            }),
+            path: path.into(),
+            env_overrides: Vec::new(),
+        }
+    }
+
+    /// Load the file at `CONFIG_FILE_PATH` (default `config/echo_engine.toml`), falling back
+    /// to the defaults while it is missing or invalid. `ECHO_ENGINE_<SETTING>` variables,
+    /// e.g. `ECHO_ENGINE_DECAY_FACTOR`, override single settings; `DECAY_FACTOR` also
+    /// overrides the decay factor when `ECHO_ENGINE_DECAY_FACTOR` is unset.
+    pub fn from_env() -> Self {
+        let path = std::env::var("CONFIG_FILE_PATH").unwrap_or_else(|_| DEFAULT_CONFIG_FILE_PATH.to_string());
+        let mut store = Self::new(EchoEngineConfig::default(), path);
+
+        for field in EchoEngineConfig::SCALAR_FIELDS {
+            let mut variables = vec![format!("ECHO_ENGINE_{}", field.to_uppercase())];
+            if field == "decay_factor" {
+                variables.push("DECAY_FACTOR".to_string());
+            }
+            let Some((variable, value)) = variables
+                .into_iter()
+                .find_map(|variable| std::env::var(&variable).ok().map(|value| (variable, value)))
+            else {
+                continue;
+            };
+            match value.parse() {
+                Ok(value) => store.env_overrides.push((field, value)),
+                Err(_) => log::warn!("Ignoring {}: `{}` is not a number", variable, value),
+            }
+        }
+        let defaults = LiveEngineConfig {
+            config: store.with_env_overrides(EchoEngineConfig::default()),
+            source: ConfigSource::Default,
+            env_overrides: store.env_override_names(),
+            loaded_at: Utc::now(),
+        };
+        match defaults.config.validate() {
+            Ok(()) => store.live.store(Arc::new(defaults)),
+            Err(e) => log::warn!("Ignoring ECHO_ENGINE_* overrides of the defaults: {}", e),
+        }
+
+        match store.reload_file() {
+            Ok(_) => log::info!("Loaded echo engine config from {}", store.path.display()),
+            Err(ConfigReloadError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                log::info!("No echo engine config at {}; using the defaults", store.path.display())
+            }
+            Err(e) => log::warn!("Using the default echo engine config; {} is invalid: {}", store.path.display(), e),
+        }
+        store
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The config in force
+    pub fn load(&self) -> Arc<LiveEngineConfig> {
+        self.live.load_full()
+    }
+
+    /// Validate and swap in the config file's current contents, returning the changed settings
+    pub fn reload_file(&self) -> Result<Vec<String>, ConfigReloadError> {
+        let contents = std::fs::read_to_string(&self.path)?;
+        let config = self.with_env_overrides(toml::from_str(&contents)?);
+        Ok(self.swap(config, ConfigSource::File, self.env_override_names())?)
+    }
+
+    /// Validate and swap in a config pushed through the API, returning the changed settings
+    pub fn replace(&self, config: EchoEngineConfig) -> Result<Vec<String>, EngineConfigError> {
+        self.update(ConfigSource::Api, |current| *current = config.clone())
+    }
+
+    /// Apply `change` to the config in force and swap in the result if it is valid,
+    /// returning the changed settings. Concurrent updates are applied one after another.
+    pub fn update(
+        &self,
+        source: ConfigSource,
+        change: impl Fn(&mut EchoEngineConfig),
+    ) -> Result<Vec<String>, EngineConfigError> {
+        loop {
+            let current = self.live.load_full();
+            let mut config = current.config.clone();
+            change(&mut config);
+            config.validate()?;
+
+            let changes = current.config.diff(&config);
+            let next = Arc::new(LiveEngineConfig { config, source, env_overrides: Vec::new(), loaded_at: Utc::now() });
+            let previous = self.live.compare_and_swap(&current, next);
+            if Arc::ptr_eq(&previous, &current) {
+                log_changes(source, &changes);
+                return Ok(changes);
+            }
+        }
+    }
+
+    fn swap(
+        &self,
+        config: EchoEngineConfig,
+        source: ConfigSource,
+        env_overrides: Vec<&'static str>,
+    ) -> Result<Vec<String>, EngineConfigError> {
+        config.validate()?;
+        let next = Arc::new(LiveEngineConfig { config, source, env_overrides, loaded_at: Utc::now() });
+        let previous = self.live.swap(next.clone());
+
+        let changes = previous.config.diff(&next.config);
+        log_changes(source, &changes);
+        Ok(changes)
+    }
+
+    fn with_env_overrides(&self, mut config: EchoEngineConfig) -> EchoEngineConfig {
+        for (field, value) in &self.env_overrides {
+            if let Some(setting) = config.scalar_mut(field) {
+                *setting = *value;
+            }
+        }
+        config
+    }
+
+    fn env_override_names(&self) -> Vec<&'static str> {
+        self.env_overrides.iter().map(|(field, _)| *field).collect()
+    }
+
+    /// Reload the config file whenever it changes. The file's directory is watched, so
+    /// a file replaced by a rename or created after startup is picked up too.
+    pub fn spawn_watch_task(self: Arc<Self>) -> JoinHandle<()> {
+        let (changed, mut changes) = mpsc::unbounded_channel();
+        let file_name = self.path.file_name().map(|name| name.to_os_string());
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            let written = matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Access(AccessKind::Close(AccessMode::Write))
+            );
+            if written && event.paths.iter().any(|path| path.file_name() == file_name.as_deref()) {
+                let _ = changed.send(());
+            }
+        });
+        let directory = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let watcher = watcher.and_then(|mut watcher| {
+            watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        });
+
+        tokio::spawn(async move {
+            // Dropping the watcher stops the notifications
+            let _watcher = match watcher {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    log::warn!("Not watching {} for echo engine config changes: {}", directory.display(), e);
+                    return;
+                }
+            };
+
+            while changes.recv().await.is_some() {
+                tokio::time::sleep(RELOAD_DEBOUNCE).await;
+                while changes.try_recv().is_ok() {}
+
+                if let Err(e) = self.reload_file() {
+                    log::warn!("Keeping the current echo engine config; {} is invalid: {}", self.path.display(), e);
+                }
+            }
+        })
+    }
+}
+
+impl Default for EngineConfigStore {
+    fn default() -> Self {
+        Self::new(EchoEngineConfig::default(), DEFAULT_CONFIG_FILE_PATH)
+    }
+}
+
+fn log_changes(source: ConfigSource, changes: &[String]) {
+    if changes.is_empty() {
+        log::info!("Echo engine config reloaded from {:?} without changes", source);
+    } else {
+        log::info!("Echo engine config updated from {:?}: {}", source, changes.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Platform;
+
+    fn alternative() -> EchoEngineConfig {
+        let mut config = EchoEngineConfig {
+            odf_weight: 0.25,
+            awr_weight: 0.25,
+            tpm_weight: 0.25,
+            qf_weight: 0.25,
+            decay_factor: 0.9,
+            boost_threshold: 0.7,
+            ..EchoEngineConfig::default()
+        };
+        config.platform_odf_normalization.insert(Platform::Medium, 1.2);
+        config.language_normalization_factors.insert("jpn".to_string(), 1.1);
+        config
+    }
+
+    #[test]
+    fn test_concurrent_reads_see_whole_configs() {
+        let store = Arc::new(EngineConfigStore::default());
+        let (old, new) = (EchoEngineConfig::default(), alternative());
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let (store, old, new) = (store.clone(), old.clone(), new.clone());
+                std::thread::spawn(move || {
+                    for _ in 0..20_000 {
+                        let live = store.load();
+                        assert!(live.config == old || live.config == new, "torn config {:?}", live.config);
+                    }
+                })
+            })
+            .collect();
+        for i in 0..2_000 {
+            store.replace(if i % 2 == 0 { new.clone() } else { old.clone() }).unwrap();
+        }
+
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(store.load().source, ConfigSource::Api);
+    }
+
+    #[test]
+    fn test_invalid_updates_keep_the_current_config() {
+        let store = EngineConfigStore::default();
+
+        let skewed = EchoEngineConfig { qf_weight: 0.5, ..EchoEngineConfig::default() };
+        assert!(matches!(store.replace(skewed), Err(EngineConfigError::InvalidWeightSum(_))));
+        assert_eq!(store.load().source, ConfigSource::Default);
+
+        let changes = store.update(ConfigSource::Api, |config| config.decay_factor = 0.9).unwrap();
+        assert_eq!(changes, ["decay_factor: 0.95 -> 0.9"]);
+        assert_eq!(store.load().config.decay_factor, 0.9);
+    }
+
+    #[tokio::test]
+    async fn test_file_changes_are_applied() {
+        let directory = std::env::temp_dir().join(format!("echo_engine_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("echo_engine.toml");
+        std::fs::write(&path, "decay_factor = 0.9\n").unwrap();
+
+        let store = Arc::new(EngineConfigStore::new(EchoEngineConfig::default(), &path));
+        store.reload_file().unwrap();
+        assert_eq!((store.load().config.decay_factor, store.load().source), (0.9, ConfigSource::File));
+        let watch = store.clone().spawn_watch_task();
+
+        let wait_for = |decay_factor: f64| {
+            let store = store.clone();
+            tokio::time::timeout(Duration::from_secs(5), async move {
+                while store.load().config.decay_factor != decay_factor {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+            })
+        };
+        std::fs::write(&path, "decay_factor = 0.8\nboost_threshold = 0.75\n").unwrap();
+        wait_for(0.8).await.expect("edited file is reloaded");
+        assert_eq!(store.load().config.boost_threshold, 0.75);
+
+        // An invalid file is not applied
+        std::fs::write(&path, "decay_factor = 0.7\nodf_weight = 0.9\n").unwrap();
+        assert!(wait_for(0.7).await.is_err());
+        assert_eq!(store.load().config.decay_factor, 0.8);
+
+        watch.abort();
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
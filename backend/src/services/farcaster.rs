@@ -0,0 +1,723 @@
+use moka::sync::Cache;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::handlers::propagation::{
+    BulkPropagationRequest, BulkPropagationResponse, CreatePropagationRequest, MAX_BULK_EVENTS,
+};
+use crate::middleware::jwt::API_KEY_HEADER;
+use crate::models::wallet_address::EthereumAddress;
+use crate::models::Platform;
+use crate::repositories::{ContentRepository, TrackedCast, UserRepository};
+
+pub const DEFAULT_HUB_URL: &str = "https://hub.farcaster.xyz";
+const HUB_TIMEOUT: Duration = Duration::from_secs(10);
+/// Largest page the Hub serves
+const HUB_PAGE_SIZE: u32 = 1000;
+/// Pages read per listing, so a cast mentioned endlessly cannot stall an indexing run
+const MAX_HUB_PAGES: usize = 10;
+
+/// Casts are polled for recasts this long after they were published
+const TRACKED_CAST_DAYS: i64 = 30;
+const DEFAULT_INDEX_INTERVAL_SECS: u64 = 300;
+/// FIDs of authors change only when they transfer their account
+const AUTHOR_FID_CACHE_TTL: Duration = Duration::from_secs(24 * 3600);
+const ADDRESS_CACHE_TTL: Duration = Duration::from_secs(3600);
+const CACHE_CAPACITY: u64 = 50_000;
+
+/// `0x` and 40 lowercase hex digits, the form the Hub expects, or None when `hash` is not
+/// a 20-byte cast hash
+pub fn normalize_cast_hash(hash: &str) -> Option<String> {
+    let digits = hash.strip_prefix("0x")?;
+    (digits.len() == 40 && digits.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| format!("0x{}", digits.to_ascii_lowercase()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FarcasterPropagationKind {
+    Recast,
+    /// A cast embedding the tracked cast
+    Quote,
+}
+
+/// A recast or quote of a tracked cast
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FarcasterPropagation {
+    /// Farcaster ID of the user who recast or quoted
+    pub fid: u64,
+    /// Hash of the recast reaction or of the quoting cast
+    pub message_hash: String,
+    pub kind: FarcasterPropagationKind,
+}
+
+impl FarcasterPropagation {
+    /// Propagation from the cast's author to `target_user_id`, the EchoLayer user owning one
+    /// of the propagator's verified addresses
+    pub fn to_request(&self, cast: &TrackedCast, target_user_id: Option<Uuid>) -> CreatePropagationRequest {
+        let propagation_type = match self.kind {
+            FarcasterPropagationKind::Recast => "repost",
+            FarcasterPropagationKind::Quote => "quote",
+        };
+        CreatePropagationRequest {
+            content_id: cast.content_id.to_string(),
+            source_user_id: Some(cast.author_id.to_string()),
+            target_user_id: target_user_id.map(|id| id.to_string()),
+            propagation_type: propagation_type.to_string(),
+            source_platform: Platform::Farcaster,
+            target_platform: Platform::Farcaster,
+            source_external_id: Some(cast.cast_hash.clone()),
+            target_external_id: Some(self.message_hash.clone()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HubMessages {
+    #[serde(default)]
+    messages: Vec<HubMessage>,
+    #[serde(default)]
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HubMessage {
+    data: Option<MessageData>,
+    hash: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageData {
+    fid: u64,
+    reaction_body: Option<ReactionBody>,
+    cast_add_body: Option<CastAddBody>,
+    // Hubs before protocol 2024.3 name it after Ethereum alone
+    #[serde(alias = "verificationAddEthAddressBody")]
+    verification_add_address_body: Option<VerificationBody>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReactionBody {
+    #[serde(rename = "type")]
+    reaction_type: String,
+    target_cast_id: Option<CastId>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CastAddBody {
+    #[serde(default)]
+    embeds: Vec<Embed>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Embed {
+    cast_id: Option<CastId>,
+}
+
+#[derive(Deserialize)]
+struct CastId {
+    fid: u64,
+    hash: String,
+}
+
+impl CastId {
+    fn is(&self, fid: u64, hash: &str) -> bool {
+        self.fid == fid && self.hash.eq_ignore_ascii_case(hash)
+    }
+}
+
+#[derive(Deserialize)]
+struct VerificationBody {
+    address: String,
+    /// Absent for Ethereum on hubs that predate Solana verifications
+    protocol: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IdRegistryEvent {
+    fid: u64,
+}
+
+/// Reads casts, reactions and verifications from a Farcaster Hub's HTTP API
+pub struct FarcasterConnector {
+    client: reqwest::Client,
+    /// `https://hub.farcaster.xyz`, or a mock in tests
+    hub_url: String,
+}
+
+impl FarcasterConnector {
+    pub fn new(hub_url: String) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(HUB_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            hub_url: hub_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Connector for `FARCASTER_HUB_URL`, or the public Hub
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("FARCASTER_HUB_URL").unwrap_or_else(|_| DEFAULT_HUB_URL.to_string()))
+    }
+
+    /// Recasts of the cast and casts quoting it. The Hub indexes both by the cast's author,
+    /// so their FID is needed alongside the hash. The author's own recasts are left out.
+    pub async fn fetch_recasts(&self, author_fid: u64, cast_hash: &str) -> Result<Vec<FarcasterPropagation>, String> {
+        let target_fid = author_fid.to_string();
+        let reactions = self
+            .messages(
+                "/v1/reactionsByCast",
+                &[
+                    ("target_fid", target_fid.as_str()),
+                    ("target_hash", cast_hash),
+                    ("reaction_type", "REACTION_TYPE_RECAST"),
+                ],
+            )
+            .await?;
+        let mentions = self.messages("/v1/castsByMention", &[("fid", target_fid.as_str())]).await?;
+
+        let recasts = reactions.into_iter().filter_map(|message| {
+            let data = message.data?;
+            let reaction = data.reaction_body?;
+            let recasts_cast = reaction.reaction_type == "REACTION_TYPE_RECAST"
+                && reaction.target_cast_id.is_some_and(|cast| cast.is(author_fid, cast_hash));
+            recasts_cast.then_some(FarcasterPropagation {
+                fid: data.fid,
+                message_hash: message.hash,
+                kind: FarcasterPropagationKind::Recast,
+            })
+        });
+        // Most casts mentioning the author are about something else
+        let quotes = mentions.into_iter().filter_map(|message| {
+            let data = message.data?;
+            let quotes_cast = data.cast_add_body?.embeds.iter().any(|embed| {
+                embed.cast_id.as_ref().is_some_and(|cast| cast.is(author_fid, cast_hash))
+            });
+            quotes_cast.then_some(FarcasterPropagation {
+                fid: data.fid,
+                message_hash: message.hash,
+                kind: FarcasterPropagationKind::Quote,
+            })
+        });
+
+        let mut seen = HashSet::new();
+        Ok(recasts
+            .chain(quotes)
+            .filter(|propagation| propagation.fid != author_fid && seen.insert(propagation.message_hash.clone()))
+            .collect())
+    }
+
+    /// FID of the account whose custody address is `address`, or None when it holds none
+    pub async fn fid_by_custody_address(&self, address: &EthereumAddress) -> Result<Option<u64>, String> {
+        let response = self
+            .client
+            .get(format!("{}/v1/onChainIdRegistryEventByAddress", self.hub_url))
+            .query(&[("address", format!("0x{}", hex::encode(address.as_bytes())))])
+            .send()
+            .await
+            .map_err(|e| format!("Farcaster Hub request failed: {}", e))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let event: IdRegistryEvent = response
+            .error_for_status()
+            .map_err(|e| format!("Farcaster Hub request failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid Farcaster Hub response: {}", e))?;
+        Ok(Some(event.fid))
+    }
+
+    /// Ethereum addresses the user verified in their profile
+    pub async fn ethereum_addresses(&self, fid: u64) -> Result<Vec<EthereumAddress>, String> {
+        let fid = fid.to_string();
+        let verifications = self.messages("/v1/verificationsByFid", &[("fid", fid.as_str())]).await?;
+
+        Ok(verifications
+            .into_iter()
+            .filter_map(|message| message.data?.verification_add_address_body)
+            .filter(|body| matches!(body.protocol.as_deref(), None | Some("PROTOCOL_ETHEREUM")))
+            .filter_map(|body| EthereumAddress::parse(&body.address.to_ascii_lowercase()).ok())
+            .collect())
+    }
+
+    /// Every message of a paged listing, up to `MAX_HUB_PAGES` pages
+    async fn messages(&self, path: &str, query: &[(&str, &str)]) -> Result<Vec<HubMessage>, String> {
+        let page_size = HUB_PAGE_SIZE.to_string();
+        let mut messages = Vec::new();
+        let mut page_token = None;
+        for _ in 0..MAX_HUB_PAGES {
+            let mut request = self
+                .client
+                .get(format!("{}{}", self.hub_url, path))
+                .query(query)
+                .query(&[("pageSize", page_size.as_str())]);
+            if let Some(token) = &page_token {
+                request = request.query(&[("pageToken", token)]);
+            }
+            let page: HubMessages = request
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .map_err(|e| format!("Farcaster Hub request failed: {}", e))?
+                .json()
+                .await
+                .map_err(|e| format!("Invalid Farcaster Hub response: {}", e))?;
+
+            messages.extend(page.messages);
+            match page.next_page_token.filter(|token| !token.is_empty()) {
+                Some(token) => page_token = Some(token),
+                None => return Ok(messages),
+            }
+        }
+
+        log::warn!("Farcaster Hub listing {} has more than {} pages; the rest was skipped", path, MAX_HUB_PAGES);
+        Ok(messages)
+    }
+}
+
+/// Where the indexer submits propagations
+#[derive(Debug, Clone)]
+pub struct FarcasterIndexerConfig {
+    /// Base of the EchoLayer API, such as `http://127.0.0.1:8080/api/v1`
+    pub api_base: String,
+    /// API key the bulk endpoint authenticates the indexer by
+    pub api_key: String,
+    pub interval: Duration,
+}
+
+impl FarcasterIndexerConfig {
+    /// `None` unless `FARCASTER_INDEXER_API_KEY` is set. The API defaults to this server,
+    /// listening on `port`.
+    pub fn from_env(port: u16) -> Option<Self> {
+        let api_key = std::env::var("FARCASTER_INDEXER_API_KEY").ok().filter(|key| !key.is_empty())?;
+        let api_base = std::env::var("FARCASTER_INDEXER_API_URL")
+            .unwrap_or_else(|_| format!("http://127.0.0.1:{}/api/v1", port));
+        let interval_secs = std::env::var("FARCASTER_INDEX_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_INDEX_INTERVAL_SECS);
+        Some(Self {
+            api_base: api_base.trim_end_matches('/').to_string(),
+            api_key,
+            interval: Duration::from_secs(interval_secs),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct BulkEnvelope {
+    data: BulkPropagationResponse,
+}
+
+/// Polls a Farcaster Hub for recasts and quotes of tracked casts and submits them to the
+/// bulk propagation endpoint like any other platform connector. Casts are matched to their
+/// author through the custody address the author signed in with, and propagators to users
+/// through the Ethereum addresses verified in their Farcaster profile.
+pub struct FarcasterIndexer {
+    connector: FarcasterConnector,
+    content: Arc<ContentRepository>,
+    users: Arc<UserRepository>,
+    client: reqwest::Client,
+    config: FarcasterIndexerConfig,
+    /// FID by custody address; None for addresses without an account
+    author_fids: Cache<EthereumAddress, Option<u64>>,
+    addresses: Cache<u64, Arc<Vec<EthereumAddress>>>,
+}
+
+impl FarcasterIndexer {
+    pub fn new(
+        connector: FarcasterConnector,
+        content: Arc<ContentRepository>,
+        users: Arc<UserRepository>,
+        config: FarcasterIndexerConfig,
+    ) -> Self {
+        Self {
+            connector,
+            content,
+            users,
+            client: reqwest::Client::builder()
+                .timeout(HUB_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            config,
+            author_fids: Cache::builder()
+                .max_capacity(CACHE_CAPACITY)
+                .time_to_live(AUTHOR_FID_CACHE_TTL)
+                .build(),
+            addresses: Cache::builder()
+                .max_capacity(CACHE_CAPACITY)
+                .time_to_live(ADDRESS_CACHE_TTL)
+                .build(),
+        }
+    }
+
+    /// Indexer configured from the environment, or None with a warning when
+    /// `FARCASTER_INDEXER_API_KEY` is not set
+    pub fn from_env(content: Arc<ContentRepository>, users: Arc<UserRepository>, port: u16) -> Option<Self> {
+        let Some(config) = FarcasterIndexerConfig::from_env(port) else {
+            log::warn!("FARCASTER_INDEXER_API_KEY is not set; Farcaster recasts are not tracked");
+            return None;
+        };
+        Some(Self::new(FarcasterConnector::from_env(), content, users, config))
+    }
+
+    /// Submit the recasts and quotes of every tracked cast, returning how many new
+    /// propagations were stored. A cast the Hub fails on is skipped until the next run.
+    pub async fn index(&self) -> Result<usize, String> {
+        let since = chrono::Utc::now() - chrono::Duration::days(TRACKED_CAST_DAYS);
+        let casts = self.content.list_tracked_casts(since).await.map_err(|e| e.to_string())?;
+
+        let mut processed = 0;
+        for cast in &casts {
+            match self.index_cast(cast).await {
+                Ok(stored) => processed += stored,
+                Err(e) => log::warn!("Failed to index recasts of Farcaster cast {}: {}", cast.cast_hash, e),
+            }
+        }
+        Ok(processed)
+    }
+
+    async fn index_cast(&self, cast: &TrackedCast) -> Result<usize, String> {
+        // Authors who signed in with a Solana wallet hold no Farcaster account
+        let Ok(author_wallet) = EthereumAddress::parse(&cast.author_wallet) else {
+            return Ok(0);
+        };
+        let Some(author_fid) = self.author_fid(author_wallet).await? else {
+            return Ok(0);
+        };
+        let propagations = self.connector.fetch_recasts(author_fid, &cast.cast_hash).await?;
+        if propagations.is_empty() {
+            return Ok(0);
+        }
+
+        let mut addresses_by_fid = HashMap::new();
+        for fid in propagations.iter().map(|propagation| propagation.fid) {
+            if let Entry::Vacant(entry) = addresses_by_fid.entry(fid) {
+                entry.insert(self.verified_addresses(fid).await?);
+            }
+        }
+        let wallets: Vec<EthereumAddress> = addresses_by_fid.values().flat_map(|a| a.iter().copied()).collect();
+        let users = self
+            .users
+            .find_ids_by_ethereum_addresses(&wallets)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let events: Vec<CreatePropagationRequest> = propagations
+            .iter()
+            .map(|propagation| {
+                let user_id = addresses_by_fid[&propagation.fid].iter().find_map(|address| users.get(address));
+                propagation.to_request(cast, user_id.copied())
+            })
+            .collect();
+
+        let mut processed = 0;
+        for batch in events.chunks(MAX_BULK_EVENTS) {
+            processed += self.submit(cast, batch.to_vec()).await?.processed;
+        }
+        Ok(processed)
+    }
+
+    async fn author_fid(&self, wallet: EthereumAddress) -> Result<Option<u64>, String> {
+        if let Some(fid) = self.author_fids.get(&wallet) {
+            return Ok(fid);
+        }
+        let fid = self.connector.fid_by_custody_address(&wallet).await?;
+        self.author_fids.insert(wallet, fid);
+        Ok(fid)
+    }
+
+    async fn verified_addresses(&self, fid: u64) -> Result<Arc<Vec<EthereumAddress>>, String> {
+        if let Some(addresses) = self.addresses.get(&fid) {
+            return Ok(addresses);
+        }
+        let addresses = Arc::new(self.connector.ethereum_addresses(fid).await?);
+        self.addresses.insert(fid, addresses.clone());
+        Ok(addresses)
+    }
+
+    /// Post a batch to the bulk endpoint. The idempotency key is derived from the batch, so
+    /// a cast whose propagations have not changed since the last run is answered from the
+    /// endpoint's replay cache.
+    async fn submit(
+        &self,
+        cast: &TrackedCast,
+        events: Vec<CreatePropagationRequest>,
+    ) -> Result<BulkPropagationResponse, String> {
+        let mut hasher = Sha256::new();
+        hasher.update(cast.content_id.as_bytes());
+        for event in &events {
+            hasher.update(event.target_external_id.as_deref().unwrap_or_default().as_bytes());
+            hasher.update(event.target_user_id.as_deref().unwrap_or_default().as_bytes());
+        }
+        let request = BulkPropagationRequest {
+            events,
+            idempotency_key: format!("farcaster-{}", hex::encode(hasher.finalize())),
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/propagation/bulk", self.config.api_base))
+            .header(API_KEY_HEADER, &self.config.api_key)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| format!("Bulk propagation request failed: {}", e))?;
+        // Every event was already reported, e.g. by another connector
+        if response.status() == reqwest::StatusCode::CONFLICT {
+            return Ok(BulkPropagationResponse {
+                processed: 0,
+                duplicates_skipped: request.events.len(),
+                failed: Vec::new(),
+                echo_index_updates_queued: 0,
+            });
+        }
+        let envelope: BulkEnvelope = response
+            .error_for_status()
+            .map_err(|e| format!("Bulk propagation request failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid bulk propagation response: {}", e))?;
+        Ok(envelope.data)
+    }
+
+    pub fn spawn_index_task(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.config.interval);
+            loop {
+                interval.tick().await;
+                match self.index().await {
+                    Ok(processed) if processed > 0 => log::info!("Indexed {} Farcaster propagations", processed),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Farcaster indexing failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use std::sync::Mutex;
+
+    const AUTHOR_FID: u64 = 3;
+    const CAST: &str = "0x1a2b3c4d5e6f708192a3b4c5d6e7f80910111213";
+    const AUTHOR_WALLET: &str = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+    /// Verified by FID 7, and signed in to EchoLayer in its checksum form
+    const RECASTER_WALLET: &str = "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359";
+
+    /// Bulk batches the mock API received
+    struct Submissions(Mutex<Vec<Value>>);
+
+    fn cast_id(hash: &str) -> Value {
+        json!({"fid": AUTHOR_FID, "hash": hash})
+    }
+
+    async fn reactions_by_cast(query: web::Query<HashMap<String, String>>) -> HttpResponse {
+        let target = (query.get("target_fid").map(String::as_str), query.get("target_hash").map(String::as_str));
+        if target != (Some("3"), Some(CAST)) {
+            return HttpResponse::Ok().json(json!({"messages": [], "nextPageToken": ""}));
+        }
+        let recast = |fid: u64, hash: &str| {
+            json!({"data": {"type": "MESSAGE_TYPE_REACTION_ADD", "fid": fid,
+                "reactionBody": {"type": "REACTION_TYPE_RECAST", "targetCastId": cast_id(CAST)}}, "hash": hash})
+        };
+        // Two pages, the second holding the author's own recast
+        match query.get("pageToken").map(String::as_str) {
+            None => HttpResponse::Ok().json(json!({"messages": [recast(7, "0xr1"), recast(8, "0xr2")],
+                "nextPageToken": "page2"})),
+            Some(_) => HttpResponse::Ok().json(json!({"messages": [recast(AUTHOR_FID, "0xr3")], "nextPageToken": ""})),
+        }
+    }
+
+    async fn casts_by_mention() -> HttpResponse {
+        let cast = |fid: u64, hash: &str, embeds: Value| {
+            json!({"data": {"type": "MESSAGE_TYPE_CAST_ADD", "fid": fid,
+                "castAddBody": {"text": "gm @author", "mentions": [AUTHOR_FID], "embeds": embeds}}, "hash": hash})
+        };
+        HttpResponse::Ok().json(json!({"messages": [
+            cast(9, "0xq1", json!([{"url": "https://echolayer.xyz"}, {"castId": cast_id(CAST)}])),
+            // Quotes another cast of the author
+            cast(10, "0xq2", json!([{"castId": cast_id("0x00000000000000000000000000000000000000ff")}])),
+            cast(11, "0xq3", json!([])),
+        ]}))
+    }
+
+    async fn verifications_by_fid(query: web::Query<HashMap<String, String>>) -> HttpResponse {
+        let verification = |address: &str, protocol: &str, hash: &str| {
+            json!({"data": {"fid": 7, "verificationAddAddressBody": {"address": address, "protocol": protocol}},
+                "hash": hash})
+        };
+        let messages = match query.get("fid").map(String::as_str) {
+            Some("7") => json!([
+                verification("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU", "PROTOCOL_SOLANA", "0xv1"),
+                verification(RECASTER_WALLET, "PROTOCOL_ETHEREUM", "0xv2"),
+            ]),
+            _ => json!([]),
+        };
+        HttpResponse::Ok().json(json!({"messages": messages, "nextPageToken": ""}))
+    }
+
+    async fn id_registry_event(query: web::Query<HashMap<String, String>>) -> HttpResponse {
+        match query.get("address").map(String::as_str) {
+            Some(AUTHOR_WALLET) => {
+                HttpResponse::Ok().json(json!({"fid": AUTHOR_FID, "type": "ID_REGISTER_EVENT_TYPE_REGISTER"}))
+            }
+            _ => HttpResponse::NotFound().json(json!({"errCode": "not_found"})),
+        }
+    }
+
+    async fn bulk(request: HttpRequest, body: web::Json<Value>, submissions: web::Data<Submissions>) -> HttpResponse {
+        if request.headers().get(API_KEY_HEADER).is_none_or(|key| key != "indexer-key") {
+            return HttpResponse::Unauthorized().finish();
+        }
+        let processed = body["events"].as_array().map_or(0, Vec::len);
+        submissions.0.lock().unwrap().push(body.into_inner());
+        HttpResponse::Ok().json(json!({"success": true, "data": {"processed": processed, "duplicates_skipped": 0,
+            "failed": [], "echo_index_updates_queued": 1}, "timestamp": "2024-10-21T00:00:00Z"}))
+    }
+
+    /// A Hub and EchoLayer API in one server
+    fn mock_hub() -> (String, web::Data<Submissions>) {
+        let submissions = web::Data::new(Submissions(Mutex::new(Vec::new())));
+        let server_submissions = submissions.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(server_submissions.clone())
+                .route("/v1/reactionsByCast", web::get().to(reactions_by_cast))
+                .route("/v1/castsByMention", web::get().to(casts_by_mention))
+                .route("/v1/verificationsByFid", web::get().to(verifications_by_fid))
+                .route("/v1/onChainIdRegistryEventByAddress", web::get().to(id_registry_event))
+                .route("/api/v1/propagation/bulk", web::post().to(bulk))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let base = format!("http://{}", server.addrs()[0]);
+        tokio::spawn(server.run());
+        (base, submissions)
+    }
+
+    #[test]
+    fn test_cast_hashes_are_normalized() {
+        assert_eq!(normalize_cast_hash(&CAST.to_uppercase().replace("0X", "0x")), Some(CAST.to_string()));
+        assert_eq!(normalize_cast_hash("1a2b3c4d5e6f708192a3b4c5d6e7f80910111213"), None);
+        assert_eq!(normalize_cast_hash("0x1a2b"), None);
+        assert_eq!(normalize_cast_hash("0xzz2b3c4d5e6f708192a3b4c5d6e7f80910111213"), None);
+    }
+
+    #[actix_web::test]
+    async fn test_recasts_and_quotes_are_fetched_from_the_hub() {
+        let (base, _) = mock_hub();
+        let connector = FarcasterConnector::new(base);
+
+        let propagations = connector.fetch_recasts(AUTHOR_FID, CAST).await.unwrap();
+        let found: Vec<_> = propagations.iter().map(|p| (p.fid, p.message_hash.as_str(), p.kind)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (7, "0xr1", FarcasterPropagationKind::Recast),
+                (8, "0xr2", FarcasterPropagationKind::Recast),
+                (9, "0xq1", FarcasterPropagationKind::Quote),
+            ]
+        );
+
+        let cast = TrackedCast {
+            content_id: Uuid::new_v4(),
+            author_id: Uuid::new_v4(),
+            cast_hash: CAST.to_string(),
+            author_wallet: AUTHOR_WALLET.to_string(),
+        };
+        let recaster = Uuid::new_v4();
+        let request = propagations[0].to_request(&cast, Some(recaster));
+        assert_eq!(request.content_id, cast.content_id.to_string());
+        assert_eq!(request.source_user_id, Some(cast.author_id.to_string()));
+        assert_eq!(request.target_user_id, Some(recaster.to_string()));
+        assert_eq!(request.propagation_type, "repost");
+        assert_eq!((request.source_platform, request.target_platform), (Platform::Farcaster, Platform::Farcaster));
+        assert_eq!(request.source_external_id.as_deref(), Some(CAST));
+        assert_eq!(request.target_external_id.as_deref(), Some("0xr1"));
+        assert_eq!(propagations[2].to_request(&cast, None).propagation_type, "quote");
+
+        let author = EthereumAddress::parse(AUTHOR_WALLET).unwrap();
+        assert_eq!(connector.fid_by_custody_address(&author).await, Ok(Some(AUTHOR_FID)));
+        let stranger = EthereumAddress::parse(RECASTER_WALLET).unwrap();
+        assert_eq!(connector.fid_by_custody_address(&stranger).await, Ok(None));
+        assert_eq!(connector.ethereum_addresses(7).await, Ok(vec![stranger]));
+
+        // Nothing listens on the discard port
+        assert!(FarcasterConnector::new("http://127.0.0.1:9".into()).fetch_recasts(AUTHOR_FID, CAST).await.is_err());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_indexer_submits_propagations_of_tracked_casts(pool: PgPool) {
+        let (base, submissions) = mock_hub();
+        let wallet = |address: &str| EthereumAddress::parse(address).unwrap().to_checksum();
+        let author: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+            .bind(AUTHOR_WALLET)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let recaster: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+            .bind(wallet(RECASTER_WALLET))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let content_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type, title, body)
+             VALUES ($1, 'farcaster', $2, 'text', 'Cast', 'gm') RETURNING id",
+        )
+        .bind(author)
+        .bind(CAST)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let indexer = FarcasterIndexer::new(
+            FarcasterConnector::new(base.clone()),
+            Arc::new(ContentRepository::new(pool.clone())),
+            Arc::new(UserRepository::new(pool)),
+            FarcasterIndexerConfig {
+                api_base: format!("{}/api/v1", base),
+                api_key: "indexer-key".to_string(),
+                interval: Duration::from_secs(60),
+            },
+        );
+        assert_eq!(indexer.index().await, Ok(3));
+
+        let submissions = submissions.0.lock().unwrap();
+        assert_eq!(submissions.len(), 1);
+        let batch = &submissions[0];
+        assert!(batch["idempotency_key"].as_str().unwrap().starts_with("farcaster-"));
+        let events: Vec<CreatePropagationRequest> = serde_json::from_value(batch["events"].clone()).unwrap();
+        let targets: Vec<_> = events
+            .iter()
+            .map(|event| (event.target_external_id.as_deref().unwrap(), event.target_user_id.clone()))
+            .collect();
+        assert_eq!(
+            targets,
+            vec![("0xr1", Some(recaster.to_string())), ("0xr2", None), ("0xq1", None)]
+        );
+        assert!(events.iter().all(|event| event.content_id == content_id.to_string()
+            && event.source_user_id == Some(author.to_string())
+            && event.source_platform == Platform::Farcaster));
+    }
+}
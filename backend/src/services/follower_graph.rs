@@ -0,0 +1,110 @@
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::pagination::{Cursor, Page};
+use crate::models::user::{FollowedUser, UserRelationship};
+use crate::repositories::{RepositoryError, UserRelationshipRepository};
+
+/// Who follows whom within EchoLayer. Follows are made by users themselves, unlike the
+/// propagation network, which only records who shared content with whom.
+pub struct UserFollowerGraph {
+    repository: Arc<UserRelationshipRepository>,
+}
+
+impl UserFollowerGraph {
+    pub fn new(repository: Arc<UserRelationshipRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Make `follower_id` follow `followed_id`; following someone again changes nothing.
+    /// `InvalidInput` for users following themselves.
+    pub async fn follow(&self, follower_id: Uuid, followed_id: Uuid) -> Result<UserRelationship, RepositoryError> {
+        if follower_id == followed_id {
+            return Err(RepositoryError::InvalidInput("users cannot follow themselves".to_string()));
+        }
+        self.repository.follow(follower_id, followed_id).await
+    }
+
+    /// Stop `follower_id` following `followed_id`. Returns whether they were following.
+    pub async fn unfollow(&self, follower_id: Uuid, followed_id: Uuid) -> Result<bool, RepositoryError> {
+        self.repository.unfollow(follower_id, followed_id).await
+    }
+
+    pub async fn follows(&self, follower_id: Uuid, followed_id: Uuid) -> Result<bool, RepositoryError> {
+        self.repository.follows(follower_id, followed_id).await
+    }
+
+    pub async fn followers(
+        &self,
+        user_id: Uuid,
+        after: Option<Cursor>,
+        limit: u32,
+    ) -> Result<Page<FollowedUser>, RepositoryError> {
+        self.repository.followers(user_id, after, limit).await
+    }
+
+    pub async fn following(
+        &self,
+        user_id: Uuid,
+        after: Option<Cursor>,
+        limit: u32,
+    ) -> Result<Page<FollowedUser>, RepositoryError> {
+        self.repository.following(user_id, after, limit).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::UserRepository;
+    use sqlx::PgPool;
+
+    async fn user(pool: &PgPool, wallet: &str) -> Uuid {
+        sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+            .bind(wallet)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_follows_are_counted_and_paged(pool: PgPool) {
+        let star = user(&pool, "0xstar").await;
+        let mut fans = Vec::new();
+        for i in 0..3 {
+            fans.push(user(&pool, &format!("0xfan{}", i)).await);
+        }
+        let graph = UserFollowerGraph::new(Arc::new(UserRelationshipRepository::new(pool.clone())));
+
+        for fan in &fans {
+            graph.follow(*fan, star).await.unwrap();
+        }
+        // Following again keeps the original follow
+        let first = graph.follow(fans[0], star).await.unwrap();
+        assert_eq!(graph.follow(fans[0], star).await.unwrap(), first);
+        graph.follow(star, fans[0]).await.unwrap();
+        assert!(matches!(graph.follow(star, star).await, Err(RepositoryError::InvalidInput(_))));
+        assert!(matches!(graph.follow(star, Uuid::new_v4()).await, Err(RepositoryError::Conflict(_))));
+
+        let users = UserRepository::new(pool.clone());
+        let profile = users.find_by_id(star).await.unwrap().unwrap();
+        assert_eq!((profile.followers_count, profile.following_count), (3, 1));
+        let profile = users.find_by_id(fans[0]).await.unwrap().unwrap();
+        assert_eq!((profile.followers_count, profile.following_count), (1, 1));
+
+        // Most recent follow first, two at a time
+        let page = graph.followers(star, None, 2).await.unwrap();
+        let ids: Vec<Uuid> = page.data.iter().map(|follower| follower.user.id).collect();
+        assert_eq!(ids, [fans[2], fans[1]]);
+        let after = Cursor::decode(page.next_cursor.as_deref().unwrap()).unwrap();
+        let rest = graph.followers(star, Some(after), 2).await.unwrap();
+        assert_eq!(rest.data.iter().map(|follower| follower.user.id).collect::<Vec<_>>(), [fans[0]]);
+        assert!(!rest.has_more);
+        assert_eq!(graph.following(star, None, 10).await.unwrap().data[0].user.id, fans[0]);
+
+        assert!(graph.unfollow(fans[1], star).await.unwrap());
+        assert!(!graph.unfollow(fans[1], star).await.unwrap());
+        assert!(!graph.follows(fans[1], star).await.unwrap());
+        assert_eq!(users.find_by_id(star).await.unwrap().unwrap().followers_count, 2);
+    }
+}
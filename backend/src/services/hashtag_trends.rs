@@ -0,0 +1,328 @@
+use arc_swap::ArcSwap;
+use chrono::Duration;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::Platform;
+use crate::repositories::{HashtagCounts, HashtagRepository, RepositoryError};
+
+pub const DEFAULT_TRENDING_HASHTAG_TPM_BONUS: f64 = 0.05;
+pub const DEFAULT_TREND_WINDOW_HOURS: u32 = 24;
+pub const MAX_TREND_WINDOW_HOURS: u32 = 24 * 7;
+/// Most used hashtags of the default window considered when refreshing the set of
+/// currently trending ones
+const TRENDING_SET_CANDIDATES: u32 = 100;
+/// Auto-tags extracted from more trending content items than this are suggested as
+/// hashtags
+pub const HASHTAG_SUGGESTION_MIN_CONTENT: u32 = 10;
+/// Changes between the two halves of a window within this share of the busier half
+/// count as noise
+const STABLE_VELOCITY_SHARE: f64 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[schema(example = "trending")]
+pub enum TrendDirection {
+    Trending,
+    Declining,
+    Stable,
+}
+
+impl TrendDirection {
+    /// Direction of a hashtag used `recent` times in the latest half of a window and
+    /// `earlier` times in the half before it
+    pub fn of(recent: u32, earlier: u32) -> Self {
+        let velocity = recent as f64 - earlier as f64;
+        if velocity.abs() <= STABLE_VELOCITY_SHARE * recent.max(earlier) as f64 {
+            TrendDirection::Stable
+        } else if velocity > 0.0 {
+            TrendDirection::Trending
+        } else {
+            TrendDirection::Declining
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct TrendingHashtag {
+    pub tag: String,
+    /// Content tagged within the window
+    pub count: u32,
+    pub avg_echo_index: f64,
+    /// Content tagged in the latest half of the window less that tagged in the earlier half
+    pub velocity: f64,
+    pub trend_direction: TrendDirection,
+}
+
+impl From<HashtagCounts> for TrendingHashtag {
+    fn from(counts: HashtagCounts) -> Self {
+        let (recent, earlier) = (counts.recent as u32, counts.earlier as u32);
+        Self {
+            tag: counts.hashtag,
+            count: recent + earlier,
+            avg_echo_index: counts.avg_echo_index,
+            velocity: recent as f64 - earlier as f64,
+            trend_direction: TrendDirection::of(recent, earlier),
+        }
+    }
+}
+
+/// An auto-tag common in content riding trending hashtags, not yet a hashtag of its own
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct HashtagSuggestion {
+    pub tag: String,
+    /// Trending content the tag was extracted from
+    pub content_count: u32,
+}
+
+/// Detects hashtags gaining or losing use, and rewards content riding the ones gaining it
+/// with a TPM bonus while they do
+pub struct HashtagTrendService {
+    hashtags: Arc<HashtagRepository>,
+    tpm_bonus: f64,
+    /// Hashtags trending in the default window across platforms, as of the latest refresh
+    trending: ArcSwap<HashSet<String>>,
+}
+
+impl HashtagTrendService {
+    pub fn new(hashtags: Arc<HashtagRepository>, tpm_bonus: f64) -> Self {
+        Self {
+            hashtags,
+            tpm_bonus,
+            trending: ArcSwap::from_pointee(HashSet::new()),
+        }
+    }
+
+    /// Bonus from `TRENDING_HASHTAG_TPM_BONUS`, 0.05 by default
+    pub fn from_env(hashtags: Arc<HashtagRepository>) -> Self {
+        let tpm_bonus = std::env::var("TRENDING_HASHTAG_TPM_BONUS")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|bonus| bonus.is_finite() && *bonus >= 0.0)
+            .unwrap_or(DEFAULT_TRENDING_HASHTAG_TPM_BONUS);
+        Self::new(hashtags, tpm_bonus)
+    }
+
+    /// The `limit` hashtags most used within the last `window_hours`, optionally on one
+    /// platform
+    pub async fn trending(
+        &self,
+        platform: Option<Platform>,
+        window_hours: u32,
+        limit: u32,
+    ) -> Result<Vec<TrendingHashtag>, RepositoryError> {
+        let counts = self
+            .hashtags
+            .counts(platform.as_ref(), Duration::hours(window_hours as i64), limit as i64)
+            .await?;
+        Ok(counts.into_iter().map(TrendingHashtag::from).collect())
+    }
+
+    /// Auto-tags of more than `HASHTAG_SUGGESTION_MIN_CONTENT` content items tagged with
+    /// the `trending` hashtags gaining use within the last `window_hours`, optionally on
+    /// one platform, most widespread first
+    pub async fn suggestions(
+        &self,
+        platform: Option<Platform>,
+        window_hours: u32,
+        trending: &[TrendingHashtag],
+    ) -> Result<Vec<HashtagSuggestion>, RepositoryError> {
+        let hashtags: Vec<String> = trending
+            .iter()
+            .filter(|hashtag| hashtag.trend_direction == TrendDirection::Trending)
+            .map(|hashtag| hashtag.tag.clone())
+            .collect();
+        if hashtags.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let counts = self
+            .hashtags
+            .auto_tag_counts(
+                platform.as_ref(),
+                Duration::hours(window_hours as i64),
+                &hashtags,
+                HASHTAG_SUGGESTION_MIN_CONTENT as i64,
+            )
+            .await?;
+        Ok(counts
+            .into_iter()
+            .map(|(tag, content_count)| HashtagSuggestion { tag, content_count: content_count as u32 })
+            .collect())
+    }
+
+    /// Recompute which hashtags currently trend
+    pub async fn refresh(&self) -> Result<(), RepositoryError> {
+        let trending: HashSet<String> = self
+            .trending(None, DEFAULT_TREND_WINDOW_HOURS, TRENDING_SET_CANDIDATES)
+            .await?
+            .into_iter()
+            .filter(|hashtag| hashtag.trend_direction == TrendDirection::Trending)
+            .map(|hashtag| hashtag.tag)
+            .collect();
+        self.trending.store(Arc::new(trending));
+        Ok(())
+    }
+
+    /// TPM bonus of a content item: the configured bonus if any of its hashtags
+    /// currently trends, otherwise 0
+    pub async fn tpm_bonus(&self, content_id: Uuid) -> Result<f64, RepositoryError> {
+        let trending = self.trending.load();
+        if trending.is_empty() {
+            return Ok(0.0);
+        }
+        let tags = self.hashtags.tags_of(content_id).await?;
+        Ok(if tags.iter().any(|tag| trending.contains(tag)) { self.tpm_bonus } else { 0.0 })
+    }
+
+    /// Refresh the trending hashtags every `period`
+    pub fn spawn_refresh_task(self: Arc<Self>, period: std::time::Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.refresh().await {
+                    log::warn!("Trending hashtag refresh failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::{ContentRepository, NewContent};
+    use sqlx::PgPool;
+    use std::collections::HashMap;
+
+    /// Tag content with `hashtag` once per entry of `hours_ago`
+    async fn tag(pool: &PgPool, user_id: Uuid, hashtag: &str, hours_ago: &[i64]) {
+        let content = ContentRepository::new(pool.clone());
+        for (i, hours) in hours_ago.iter().enumerate() {
+            let created = content
+                .create(&NewContent {
+                    user_id,
+                    platform: Platform::Twitter,
+                    external_id: format!("{}_{}", hashtag, i),
+                    content_type: "text".to_string(),
+                    title: String::new(),
+                    body: format!("Thoughts on #{}", hashtag),
+                    media_urls: vec![],
+                    tags: vec![],
+                    reactions: HashMap::new(),
+                })
+                .await
+                .unwrap();
+            sqlx::query(
+                "UPDATE content_hashtags SET created_at = NOW() - make_interval(hours => $2) WHERE content_id = $1",
+            )
+            .bind(created.id)
+            .bind(*hours as i32)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_trend_direction() {
+        assert_eq!(TrendDirection::of(10, 2), TrendDirection::Trending);
+        assert_eq!(TrendDirection::of(1, 0), TrendDirection::Trending);
+        assert_eq!(TrendDirection::of(2, 10), TrendDirection::Declining);
+        assert_eq!(TrendDirection::of(10, 9), TrendDirection::Stable);
+        assert_eq!(TrendDirection::of(0, 0), TrendDirection::Stable);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_trend_direction_of_synthetic_series(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xabc') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        // Accelerating, fading and steady use over the last day; hours past the window are ignored
+        tag(&pool, user_id, "rising", &[1, 2, 3, 4, 5, 6, 20]).await;
+        tag(&pool, user_id, "fading", &[3, 14, 16, 18, 22, 30]).await;
+        tag(&pool, user_id, "steady", &[2, 8, 14, 20, 40]).await;
+
+        let service = HashtagTrendService::new(Arc::new(HashtagRepository::new(pool)), 0.05);
+        let trends = service.trending(None, 24, 10).await.unwrap();
+        let summary: Vec<(&str, u32, f64, TrendDirection)> = trends
+            .iter()
+            .map(|trend| (trend.tag.as_str(), trend.count, trend.velocity, trend.trend_direction))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("rising", 7, 5.0, TrendDirection::Trending),
+                ("fading", 5, -3.0, TrendDirection::Declining),
+                ("steady", 4, 0.0, TrendDirection::Stable),
+            ]
+        );
+        assert_eq!(service.trending(None, 24, 1).await.unwrap().len(), 1);
+        assert!(service.trending(Some(Platform::Telegram), 24, 10).await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_only_trending_hashtags_earn_the_bonus(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xabc') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        tag(&pool, user_id, "rising", &[1, 2, 20]).await;
+        tag(&pool, user_id, "fading", &[14, 16]).await;
+        let tagged: Vec<(Uuid, String)> = sqlx::query_as("SELECT content_id, hashtag FROM content_hashtags")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let content_of = |hashtag: &str| tagged.iter().find(|(_, tag)| tag == hashtag).unwrap().0;
+
+        let service = HashtagTrendService::new(Arc::new(HashtagRepository::new(pool)), 0.05);
+        // Nothing trends until the first refresh
+        assert_eq!(service.tpm_bonus(content_of("rising")).await.unwrap(), 0.0);
+
+        service.refresh().await.unwrap();
+        assert_eq!(service.tpm_bonus(content_of("rising")).await.unwrap(), 0.05);
+        assert_eq!(service.tpm_bonus(content_of("fading")).await.unwrap(), 0.0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_auto_tags_of_trending_content_become_suggestions(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xabc') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let content = ContentRepository::new(pool.clone());
+        // Rollups and zero knowledge in 11 items, DeFi in only 10; zk is a hashtag already
+        let bodies = (0..11)
+            .map(|i| (format!("rollup_{}", i), "#rising #zk rollups with zero knowledge"))
+            .chain((0..10).map(|i| (format!("defi_{}", i), "#rising DeFi yields")));
+        for (external_id, body) in bodies {
+            content
+                .create(&NewContent {
+                    user_id,
+                    platform: Platform::Twitter,
+                    external_id,
+                    content_type: "text".to_string(),
+                    title: String::new(),
+                    body: body.to_string(),
+                    media_urls: vec![],
+                    tags: vec![],
+                    reactions: HashMap::new(),
+                })
+                .await
+                .unwrap();
+        }
+
+        let service = HashtagTrendService::new(Arc::new(HashtagRepository::new(pool)), 0.05);
+        let trends = service.trending(None, 24, 10).await.unwrap();
+        let suggestions = service.suggestions(None, 24, &trends).await.unwrap();
+        assert_eq!(suggestions, vec![HashtagSuggestion { tag: "layer2".to_string(), content_count: 11 }]);
+        assert!(service.suggestions(Some(Platform::Telegram), 24, &trends).await.unwrap().is_empty());
+        assert!(service.suggestions(None, 24, &[]).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// How long a response is replayed for a repeated idempotency key
+pub const IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Responses to completed requests, keyed by the client's idempotency key
+pub struct IdempotencyCache<T> {
+    responses: DashMap<String, (Instant, T)>,
+    ttl: Duration,
+}
+
+impl<T: Clone> IdempotencyCache<T> {
+    pub fn new() -> Self {
+        Self::with_ttl(IDEMPOTENCY_TTL)
+    }
+
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            responses: DashMap::new(),
+            ttl,
+        }
+    }
+
+    /// The response recorded for `key`, unless it has expired
+    pub fn get(&self, key: &str) -> Option<T> {
+        self.responses
+            .get(key)
+            .filter(|entry| entry.0 > Instant::now())
+            .map(|entry| entry.1.clone())
+    }
+
+    /// Record the response to replay for `key`
+    pub fn insert(&self, key: &str, response: T) {
+        self.responses.insert(key.to_string(), (Instant::now() + self.ttl, response));
+    }
+
+    /// Drop responses past their TTL
+    pub fn evict_expired(&self) -> usize {
+        let before = self.responses.len();
+        let now = Instant::now();
+        self.responses.retain(|_, (expires_at, _)| *expires_at > now);
+        before - self.responses.len()
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> IdempotencyCache<T> {
+    /// Spawn a background task that evicts expired responses on a fixed interval
+    pub fn spawn_eviction_task(self: Arc<Self>, period: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let evicted = self.evict_expired();
+                if evicted > 0 {
+                    log::debug!("Evicted {} expired idempotent responses", evicted);
+                }
+            }
+        })
+    }
+}
+
+impl<T: Clone> Default for IdempotencyCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_responses_expire() {
+        let cache = IdempotencyCache::with_ttl(Duration::ZERO);
+        cache.insert("key", 1);
+        assert_eq!(cache.get("key"), None);
+        assert_eq!(cache.evict_expired(), 1);
+
+        let cache = IdempotencyCache::new();
+        cache.insert("key", 2);
+        assert_eq!(cache.get("key"), Some(2));
+        assert_eq!(cache.get("other"), None);
+        assert_eq!(cache.evict_expired(), 0);
+    }
+}
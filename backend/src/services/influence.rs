@@ -0,0 +1,168 @@
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::models::influence::{InfluenceScore, UserInfluence};
+use crate::repositories::{InfluenceRepository, RepositoryError};
+
+/// Propagations older than this do not count towards a user's influence
+pub const DEFAULT_INFLUENCE_WINDOW_DAYS: i64 = 30;
+/// Influence of every active user is recalculated this often
+const RECALCULATION_INTERVAL: std::time::Duration = std::time::Duration::from_secs(24 * 3600);
+
+/// Scores how far and how effectively a user's propagations spread content: the users
+/// they reach, the Echo Index gained by what they share, and their share of the network.
+/// The composite is used as the user's `influence_weight` in propagation paths.
+pub struct InfluenceScoreCalculator {
+    repository: Arc<InfluenceRepository>,
+    window: Duration,
+}
+
+impl InfluenceScoreCalculator {
+    pub fn new(repository: Arc<InfluenceRepository>) -> Self {
+        Self { repository, window: Duration::days(DEFAULT_INFLUENCE_WINDOW_DAYS) }
+    }
+
+    /// Influence of the user over the `time_window` before `now`
+    async fn calculate(
+        &self,
+        user_id: Uuid,
+        time_window: Duration,
+        now: DateTime<Utc>,
+    ) -> Result<InfluenceScore, RepositoryError> {
+        let signals = self.repository.signals(user_id, now - time_window).await?;
+        Ok(InfluenceScore::from_signals(&signals))
+    }
+
+    /// The user's latest stored influence, calculated and stored first if there is none
+    pub async fn influence(&self, user_id: Uuid) -> Result<UserInfluence, RepositoryError> {
+        if let Some(influence) = self.repository.latest(user_id).await? {
+            return Ok(influence);
+        }
+
+        let now = Utc::now();
+        let score = self.calculate(user_id, self.window, now).await?;
+        self.repository.record(user_id, &score, self.window.num_days() as i32, now).await?;
+        self.repository.latest(user_id).await?.ok_or(RepositoryError::NotFound)
+    }
+
+    /// Recalculate and store the influence of every user who propagated content within the
+    /// window before `now`. Returns how many users were scored.
+    pub async fn recalculate_all(&self, now: DateTime<Utc>) -> Result<usize, RepositoryError> {
+        let users = self.repository.active_users(now - self.window).await?;
+        for user_id in &users {
+            let score = self.calculate(*user_id, self.window, now).await?;
+            self.repository.record(*user_id, &score, self.window.num_days() as i32, now).await?;
+        }
+        Ok(users.len())
+    }
+
+    /// Recalculate influence daily
+    pub fn spawn_recalculation_task(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RECALCULATION_INTERVAL);
+            loop {
+                interval.tick().await;
+                match self.recalculate_all(Utc::now()).await {
+                    Ok(scored) => log::info!("Recalculated influence of {} users", scored),
+                    Err(e) => tracing::error!(error = %e, "Influence recalculation failed"),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::echo_index_history::EchoIndexTrigger;
+    use crate::models::influence::InfluenceSignals;
+    use crate::repositories::{EchoIndexHistoryRepository, EchoIndexScores};
+    use sqlx::PgPool;
+
+    async fn user(pool: &PgPool, wallet: &str) -> Uuid {
+        sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+            .bind(wallet)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn content(pool: &PgPool, author: Uuid) -> Uuid {
+        sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type, title)
+             VALUES ($1, 'twitter', 'influence', 'text', 'Influence') RETURNING id",
+        )
+        .bind(author)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    async fn propagate(pool: &PgPool, content_id: Uuid, from: Uuid, to: Uuid, at: DateTime<Utc>) {
+        sqlx::query(
+            "INSERT INTO propagations
+                 (content_id, source_user_id, target_user_id, propagation_type, source_platform, target_platform,
+                  source_external_id, target_external_id, created_at)
+             VALUES ($1, $2, $3, 'share', 'twitter', 'twitter', $2::text, $3::text, $4)",
+        )
+        .bind(content_id)
+        .bind(from)
+        .bind(to)
+        .bind(at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn scores(score: f64) -> EchoIndexScores {
+        EchoIndexScores { score, odf: score, awr: score, tpm: score, qf: score }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_influence_follows_share_chains(pool: PgPool) {
+        let mut users = Vec::new();
+        for i in 0..5 {
+            users.push(user(&pool, &format!("0xinfluence{}", i)).await);
+        }
+        let (sharer, a, b, c, idle) = (users[0], users[1], users[2], users[3], users[4]);
+        let post = content(&pool, sharer).await;
+        let now = Utc::now();
+
+        let history = EchoIndexHistoryRepository::new(pool.clone());
+        history.record(post, scores(40.0), EchoIndexTrigger::Initial, None).await.unwrap();
+        propagate(&pool, post, sharer, a, now).await;
+        propagate(&pool, post, sharer, b, now).await;
+        propagate(&pool, post, a, c, now + Duration::seconds(1)).await;
+        sqlx::query("UPDATE echo_index_history SET calculated_at = $1")
+            .bind(now - Duration::hours(1))
+            .execute(&pool)
+            .await
+            .unwrap();
+        history.record(post, scores(50.0), EchoIndexTrigger::PropagationAdded, None).await.unwrap();
+        // Too old to count
+        propagate(&pool, post, idle, sharer, now - Duration::days(40)).await;
+
+        let calculator = InfluenceScoreCalculator::new(Arc::new(InfluenceRepository::new(pool.clone())));
+        let score = calculator.calculate(sharer, Duration::days(30), now).await.unwrap();
+        // Reached a and b directly and c through a; two of the three other participants
+        let expected = InfluenceScore::from_signals(&InfluenceSignals {
+            out_degree: 2,
+            network_size: 4,
+            echo_delta: Some(10.0),
+            reach: 3,
+        });
+        assert_eq!(score, expected);
+        assert_eq!(calculator.calculate(idle, Duration::days(30), now).await.unwrap(), InfluenceScore::default());
+
+        assert_eq!(calculator.recalculate_all(Utc::now()).await.unwrap(), 2);
+        let stored = calculator.influence(sharer).await.unwrap();
+        assert_eq!((stored.score, stored.influence_rank, stored.window_days), (expected, 1, 30));
+        let follower = calculator.influence(a).await.unwrap();
+        assert_eq!(follower.influence_rank, 2);
+
+        // Users without a stored score are scored on first request
+        assert_eq!(calculator.influence(idle).await.unwrap().score, InfluenceScore::default());
+    }
+}
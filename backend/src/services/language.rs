@@ -0,0 +1,100 @@
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Language assumed when detection fails, as all analysis was English-only before
+pub const DEFAULT_LANGUAGE: &str = "eng";
+
+/// Word lists embedded per language, keyed by ISO 639-3 code
+const LEXICON_SOURCES: &[(&str, &str)] = &[
+    ("eng", include_str!("lexicons/eng.json")),
+    ("spa", include_str!("lexicons/spa.json")),
+    ("deu", include_str!("lexicons/deu.json")),
+    ("fra", include_str!("lexicons/fra.json")),
+    ("ita", include_str!("lexicons/ita.json")),
+    ("por", include_str!("lexicons/por.json")),
+];
+
+/// ISO 639-3 code of the language `text` is written in, or `DEFAULT_LANGUAGE` if it
+/// cannot be told
+pub fn detect_language(text: &str) -> String {
+    whatlang::detect(text)
+        .map(|info| info.lang().code().to_string())
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string())
+}
+
+/// Stopwords and sentiment words of one language
+#[derive(Debug, Default, Deserialize)]
+pub struct Lexicon {
+    pub stopwords: HashSet<String>,
+    pub positive: HashSet<String>,
+    pub negative: HashSet<String>,
+}
+
+impl Lexicon {
+    /// Word lists for a language, falling back to English for languages without their own
+    pub fn for_language(language: &str) -> &'static Lexicon {
+        static LEXICONS: OnceLock<HashMap<&'static str, Lexicon>> = OnceLock::new();
+        let lexicons = LEXICONS.get_or_init(|| {
+            LEXICON_SOURCES
+                .iter()
+                .map(|(code, json)| (*code, serde_json::from_str(json).expect("embedded lexicons are valid JSON")))
+                .collect()
+        });
+
+        lexicons
+            .get(language)
+            .or_else(|| lexicons.get(DEFAULT_LANGUAGE))
+            .expect("the English lexicon is embedded")
+    }
+
+    /// Positive minus negative words, over the words that are not stopwords, in [-1, 1]
+    pub fn sentiment(&self, text: &str) -> f64 {
+        let lowercase = text.to_lowercase();
+        let words: Vec<&str> = lowercase
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty() && !self.stopwords.contains(*word))
+            .collect();
+        if words.is_empty() {
+            return 0.0;
+        }
+
+        let score: f64 = words
+            .iter()
+            .map(|word| {
+                if self.positive.contains(*word) {
+                    1.0
+                } else if self.negative.contains(*word) {
+                    -1.0
+                } else {
+                    0.0
+                }
+            })
+            .sum();
+        (score / words.len() as f64).clamp(-1.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_language_and_scores_sentiment_in_it() {
+        let spanish = "El nuevo protocolo es excelente y el equipo ha tenido un gran éxito con el lanzamiento.";
+        let german = "Das neue Protokoll ist ein schrecklicher Fehler und das Team hat ein großes Problem.";
+        assert_eq!(detect_language(spanish), "spa");
+        assert_eq!(detect_language(german), "deu");
+        assert_eq!(detect_language(""), DEFAULT_LANGUAGE);
+
+        assert!(Lexicon::for_language("spa").sentiment(spanish) > 0.0);
+        assert!(Lexicon::for_language("deu").sentiment(german) < 0.0);
+        // English word lists know nothing of Spanish
+        assert_eq!(Lexicon::for_language("eng").sentiment(spanish), 0.0);
+
+        for (code, _) in LEXICON_SOURCES {
+            assert!(!Lexicon::for_language(code).positive.is_empty(), "{}", code);
+        }
+        assert!(std::ptr::eq(Lexicon::for_language("jpn"), Lexicon::for_language("eng")));
+    }
+}
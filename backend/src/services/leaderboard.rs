@@ -0,0 +1,525 @@
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use moka::future::Cache;
+use serde::Serialize;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::handlers::echo_index::LeaderboardEntry;
+use crate::models::echo_index::ConfidenceLevel;
+use crate::models::echo_index_history::LeaderboardSort;
+use crate::models::pagination::ScoreCursor;
+use crate::models::user_event::echo_tier;
+use crate::models::Platform;
+use crate::repositories::{EchoIndexHistoryRepository, RepositoryError};
+use crate::services::{EchoIndexUpdate, EchoIndexUpdates};
+
+/// Positions kept on the live leaderboard
+pub const LEADERBOARD_SIZE: usize = 10;
+/// Positions ranked per windowed leaderboard; pages past them come back empty
+const RANKING_DEPTH: i64 = 1_000;
+/// How long a windowed leaderboard is served before it is ranked again
+const RANKING_TTL: Duration = Duration::from_secs(5 * 60);
+/// Shortest time between leaderboard events caused by the same content item
+const MIN_EVENT_INTERVAL: Duration = Duration::from_secs(1);
+/// Buffered events before slow subscribers start lagging
+const EVENT_CHANNEL_CAPACITY: usize = 32;
+
+/// A content item that moved on, onto or off the leaderboard
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RankChange {
+    pub content_id: String,
+    /// `None` for content that just entered the leaderboard
+    pub previous_rank: Option<u32>,
+    /// `None` for content that dropped off the leaderboard
+    pub rank: Option<u32>,
+}
+
+/// Change to the leaderboard since the previous event
+#[derive(Debug, Clone, Serialize)]
+pub struct LeaderboardEvent {
+    pub rank_changes: Vec<RankChange>,
+    pub full_leaderboard: Vec<LeaderboardEntry>,
+}
+
+/// Top Echo Index scores kept up to date from live Echo Index updates. A change to
+/// the top positions is broadcast as a `LeaderboardEvent`, at most once per second per
+/// content item; changes held back are carried by the next event.
+pub struct LeaderboardCache {
+    entries: Arc<RwLock<Vec<LeaderboardEntry>>>,
+    /// Leaderboard as of the last event, which rank changes are measured against
+    published: Mutex<Vec<LeaderboardEntry>>,
+    /// When each content item last caused an event
+    last_events: DashMap<String, Instant>,
+    size: usize,
+    events: broadcast::Sender<LeaderboardEvent>,
+}
+
+impl LeaderboardCache {
+    pub fn new(size: usize) -> Self {
+        Self {
+            entries: Arc::new(RwLock::new(Vec::with_capacity(size))),
+            published: Mutex::new(Vec::new()),
+            last_events: DashMap::new(),
+            size,
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LeaderboardEvent> {
+        self.events.subscribe()
+    }
+
+    /// Refresh the leaderboard with a new score, broadcasting and returning the event
+    /// it causes, if any
+    pub fn apply(&self, update: &EchoIndexUpdate, now: Instant) -> Option<LeaderboardEvent> {
+        let snapshot = {
+            let mut entries = self.entries.write().ok()?;
+            match entries.iter_mut().find(|entry| entry.content_id == update.content_id) {
+                Some(entry) => {
+                    entry.echo_index = update.score;
+                    entry.tier = echo_tier(update.score).to_string();
+                }
+                None => {
+                    let qualifies = entries.len() < self.size
+                        || entries.last().is_some_and(|last| update.score > last.echo_index);
+                    if !qualifies {
+                        return None;
+                    }
+                    entries.push(LeaderboardEntry {
+                        rank: 0,
+                        content_id: update.content_id.clone(),
+                        // Updates carry scores only
+                        title: String::new(),
+                        author: String::new(),
+                        echo_index: update.score,
+                        tier: echo_tier(update.score).to_string(),
+                        confidence_level: None,
+                        trending_score: None,
+                        total_rewards: None,
+                        created_at: Utc::now(),
+                    });
+                }
+            }
+
+            entries.sort_by(|a, b| b.echo_index.total_cmp(&a.echo_index));
+            entries.truncate(self.size);
+            for (i, entry) in entries.iter_mut().enumerate() {
+                entry.rank = i as u32 + 1;
+            }
+            entries.clone()
+        };
+
+        if let Some(last) = self.last_events.get(&update.content_id) {
+            if now.duration_since(*last) < MIN_EVENT_INTERVAL {
+                return None;
+            }
+        }
+
+        let mut published = self.published.lock().ok()?;
+        if *published == snapshot {
+            return None;
+        }
+
+        let event = LeaderboardEvent {
+            rank_changes: rank_changes(&published, &snapshot),
+            full_leaderboard: snapshot.clone(),
+        };
+        *published = snapshot;
+        self.last_events.insert(update.content_id.clone(), now);
+
+        // An error only means nobody is listening
+        let _ = self.events.send(event.clone());
+        Some(event)
+    }
+
+    /// Apply every Echo Index update as it is published
+    pub fn spawn_update_task(self: Arc<Self>, updates: Arc<EchoIndexUpdates>) -> JoinHandle<()> {
+        let mut receiver = updates.subscribe_all();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(update) => {
+                        self.apply(&update, Instant::now());
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        log::warn!("Leaderboard missed {} Echo Index updates", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+/// Content whose rank differs between two leaderboards, in the order of the new one
+/// followed by content that dropped off
+fn rank_changes(previous: &[LeaderboardEntry], current: &[LeaderboardEntry]) -> Vec<RankChange> {
+    let rank_in = |entries: &[LeaderboardEntry], content_id: &str| {
+        entries.iter().find(|entry| entry.content_id == content_id).map(|entry| entry.rank)
+    };
+
+    let moved = current.iter().filter_map(|entry| {
+        let previous_rank = rank_in(previous, &entry.content_id);
+        (previous_rank != Some(entry.rank)).then(|| RankChange {
+            content_id: entry.content_id.clone(),
+            previous_rank,
+            rank: Some(entry.rank),
+        })
+    });
+    let dropped = previous
+        .iter()
+        .filter(|entry| rank_in(current, &entry.content_id).is_none())
+        .map(|entry| RankChange {
+            content_id: entry.content_id.clone(),
+            previous_rank: Some(entry.rank),
+            rank: None,
+        });
+
+    moved.chain(dropped).collect()
+}
+
+/// Period a windowed leaderboard covers, ending now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeWindow {
+    Hours(u32),
+    Days(u32),
+    AllTime,
+}
+
+impl TimeWindow {
+    /// Parse a `time_range` query value: `24h`, `7d`, `30d` or `all`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "24h" => Some(TimeWindow::Hours(24)),
+            "7d" => Some(TimeWindow::Days(7)),
+            "30d" => Some(TimeWindow::Days(30)),
+            "all" => Some(TimeWindow::AllTime),
+            _ => None,
+        }
+    }
+
+    /// Start of the window ending at `now`; `None` for all time
+    pub fn since(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            TimeWindow::Hours(hours) => Some(now - chrono::Duration::hours(*hours as i64)),
+            TimeWindow::Days(days) => Some(now - chrono::Duration::days(*days as i64)),
+            TimeWindow::AllTime => None,
+        }
+    }
+}
+
+/// Leaderboards of content created within a time window, ranked by the highest Echo
+/// Index each item reached within it, so that old viral content does not dominate
+/// short-term leaderboards, or by current trending score or rewards. Rankings are cached
+/// per window, platform and sort.
+pub struct LeaderboardService {
+    history: Arc<EchoIndexHistoryRepository>,
+    rankings: Cache<(TimeWindow, Option<Platform>, LeaderboardSort), Arc<Vec<LeaderboardEntry>>>,
+}
+
+impl LeaderboardService {
+    pub fn new(history: Arc<EchoIndexHistoryRepository>) -> Self {
+        Self {
+            history,
+            rankings: Cache::builder().time_to_live(RANKING_TTL).build(),
+        }
+    }
+
+    /// Up to `limit` entries of the window's leaderboard following `after`, leaving out
+    /// content scored with less than `min_confidence`. Entries keep their overall ranks.
+    pub async fn build_leaderboard(
+        &self,
+        window: TimeWindow,
+        platform: Option<Platform>,
+        sort: LeaderboardSort,
+        min_confidence: Option<ConfidenceLevel>,
+        limit: u32,
+        after: Option<ScoreCursor>,
+    ) -> Result<Vec<LeaderboardEntry>, RepositoryError> {
+        let key = (window, platform, sort);
+        let ranking = match self.rankings.get(&key).await {
+            Some(ranking) => ranking,
+            None => {
+                let ranking = Arc::new(self.rank(window, key.1.as_ref(), sort).await?);
+                self.rankings.insert(key, ranking.clone()).await;
+                ranking
+            }
+        };
+
+        let start = after.map_or(0, |after| {
+            ranking.partition_point(|entry| {
+                let score = entry.sort_score(sort);
+                score > after.score
+                    || (score == after.score && Uuid::parse_str(&entry.content_id).is_ok_and(|id| id <= after.id))
+            })
+        });
+        Ok(ranking
+            .iter()
+            .skip(start)
+            .filter(|entry| min_confidence.is_none_or(|min| entry.confidence_level.is_some_and(|level| level >= min)))
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn rank(
+        &self,
+        window: TimeWindow,
+        platform: Option<&Platform>,
+        sort: LeaderboardSort,
+    ) -> Result<Vec<LeaderboardEntry>, RepositoryError> {
+        if sort != LeaderboardSort::EchoIndex {
+            return self.rank_standings(window, platform, sort).await;
+        }
+        let peaks = self.history.peak_scores(window.since(Utc::now()), platform, RANKING_DEPTH).await?;
+
+        Ok(peaks
+            .into_iter()
+            .enumerate()
+            .map(|(i, peak)| {
+                let echo_index = peak.peak_score * 100.0;
+                LeaderboardEntry {
+                    rank: i as u32 + 1,
+                    content_id: peak.content_id.to_string(),
+                    title: peak.title,
+                    author: peak.author,
+                    echo_index,
+                    tier: echo_tier(echo_index).to_string(),
+                    confidence_level: Some(ConfidenceLevel::from_sample_size(peak.propagation_count.max(0) as u32)),
+                    trending_score: None,
+                    total_rewards: None,
+                    created_at: peak.created_at,
+                }
+            })
+            .collect())
+    }
+
+    /// Ranking by current trending score or rewards, showing each item's current Echo Index
+    async fn rank_standings(
+        &self,
+        window: TimeWindow,
+        platform: Option<&Platform>,
+        sort: LeaderboardSort,
+    ) -> Result<Vec<LeaderboardEntry>, RepositoryError> {
+        let standings = self.history.standings(window.since(Utc::now()), platform, sort, RANKING_DEPTH).await?;
+
+        Ok(standings
+            .into_iter()
+            .enumerate()
+            .map(|(i, standing)| {
+                let echo_index = standing.echo_index * 100.0;
+                LeaderboardEntry {
+                    rank: i as u32 + 1,
+                    content_id: standing.content_id.to_string(),
+                    title: standing.title,
+                    author: standing.author,
+                    echo_index,
+                    tier: echo_tier(echo_index).to_string(),
+                    confidence_level: Some(ConfidenceLevel::from_sample_size(standing.propagation_count.max(0) as u32)),
+                    trending_score: Some(standing.trending_score),
+                    total_rewards: Some(standing.total_rewards),
+                    created_at: standing.created_at,
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::echo_index_history::EchoIndexTrigger;
+    use crate::repositories::EchoIndexScores;
+    use crate::services::EchoIndexComponents;
+    use sqlx::PgPool;
+
+    fn update(content_id: &str, score: f64) -> EchoIndexUpdate {
+        EchoIndexUpdate {
+            content_id: content_id.to_string(),
+            score,
+            delta: 0.0,
+            components: EchoIndexComponents { odf: 0.0, awr: 0.0, tpm: 0.0, qf: 0.0 },
+        }
+    }
+
+    #[test]
+    fn test_events_are_throttled_per_content_and_ranks_capped() {
+        let cache = LeaderboardCache::new(2);
+        let start = Instant::now();
+
+        assert!(cache.apply(&update("a", 50.0), start).is_some());
+        // Within a second of a's last event; the cache still takes the new score
+        assert!(cache.apply(&update("a", 70.0), start + Duration::from_millis(300)).is_none());
+        assert_eq!(cache.entries.read().unwrap()[0].echo_index, 70.0);
+
+        // Another item is not held back, and its event carries a's held back change
+        let event = cache.apply(&update("b", 60.0), start + Duration::from_millis(400)).unwrap();
+        assert_eq!(
+            event.rank_changes,
+            [RankChange { content_id: "b".to_string(), previous_rank: None, rank: Some(2) }]
+        );
+        assert_eq!(event.full_leaderboard[0].echo_index, 70.0);
+
+        // Too low for a full leaderboard
+        assert!(cache.apply(&update("c", 10.0), start + Duration::from_secs(2)).is_none());
+
+        let event = cache.apply(&update("c", 80.0), start + Duration::from_secs(3)).unwrap();
+        let ranks: Vec<_> = event
+            .rank_changes
+            .iter()
+            .map(|change| (change.content_id.as_str(), change.previous_rank, change.rank))
+            .collect();
+        assert_eq!(ranks, [("c", None, Some(1)), ("a", Some(1), Some(2)), ("b", Some(2), None)]);
+        assert_eq!(cache.entries.read().unwrap().len(), 2);
+    }
+
+    async fn insert_scored_content(
+        pool: &PgPool,
+        history: &EchoIndexHistoryRepository,
+        user_id: Uuid,
+        title: &str,
+        age_hours: i32,
+        scores: &[f64],
+    ) -> Uuid {
+        let content_id = sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type, title, created_at)
+             VALUES ($1, 'twitter', $2, 'text', $2, NOW() - make_interval(hours => $3))
+             RETURNING id",
+        )
+        .bind(user_id)
+        .bind(title)
+        .bind(age_hours)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        for &score in scores {
+            let scores = EchoIndexScores { score, odf: score, awr: score, tpm: score, qf: score };
+            history.record(content_id, scores, EchoIndexTrigger::Recalculation, None).await.unwrap();
+        }
+        content_id
+    }
+
+    #[test]
+    fn test_time_windows() {
+        let now = Utc::now();
+        assert_eq!(TimeWindow::parse("24h"), Some(TimeWindow::Hours(24)));
+        assert_eq!(TimeWindow::parse("30d").unwrap().since(now), Some(now - chrono::Duration::days(30)));
+        assert_eq!(TimeWindow::parse("all").unwrap().since(now), None);
+        assert_eq!(TimeWindow::parse("1y"), None);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_windowed_leaderboard_ranks_peaks_of_content_created_within_the_window(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xranked') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let history = Arc::new(EchoIndexHistoryRepository::new(pool.clone()));
+
+        // Viral three days ago and still scoring highest, but created before the 24h window
+        insert_scored_content(&pool, &history, user_id, "old_viral", 72, &[0.95]).await;
+        // Peaked at 0.8 and has since cooled off below the steady one
+        insert_scored_content(&pool, &history, user_id, "peaked", 2, &[0.8, 0.3]).await;
+        let steady = insert_scored_content(&pool, &history, user_id, "steady", 1, &[0.5]).await;
+        sqlx::query("UPDATE content SET propagation_count = 150 WHERE id = $1")
+            .bind(steady)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let service = LeaderboardService::new(history);
+        let titles = |entries: &[LeaderboardEntry]| entries.iter().map(|entry| entry.title.clone()).collect::<Vec<_>>();
+        let echo = LeaderboardSort::EchoIndex;
+
+        let day = service.build_leaderboard(TimeWindow::Hours(24), None, echo, None, 10, None).await.unwrap();
+        assert_eq!(titles(&day), ["peaked", "steady"]);
+        assert_eq!((day[0].rank, day[0].echo_index, day[0].tier.as_str()), (1, 80.0, "Gold"));
+
+        let all_time = service.build_leaderboard(TimeWindow::AllTime, None, echo, None, 10, None).await.unwrap();
+        assert_eq!(titles(&all_time), ["old_viral", "peaked", "steady"]);
+
+        // Pages continue after the cursor, keeping their overall ranks
+        let cursor = ScoreCursor::new(all_time[0].echo_index, Uuid::parse_str(&all_time[0].content_id).unwrap());
+        let page = service.build_leaderboard(TimeWindow::AllTime, None, echo, None, 1, Some(cursor)).await.unwrap();
+        assert_eq!((page[0].title.as_str(), page[0].rank), ("peaked", 2));
+
+        // Only the steady item rests on enough propagations
+        let confident = service
+            .build_leaderboard(TimeWindow::AllTime, None, echo, Some(ConfidenceLevel::Medium), 10, None)
+            .await
+            .unwrap();
+        assert_eq!(titles(&confident), ["steady"]);
+        assert_eq!((confident[0].rank, confident[0].confidence_level), (3, Some(ConfidenceLevel::High)));
+        assert_eq!(all_time[0].confidence_level, Some(ConfidenceLevel::Low));
+
+        assert!(service
+            .build_leaderboard(TimeWindow::Days(7), Some(Platform::Reddit), echo, None, 10, None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_leaderboard_sorts_by_trending_score_or_rewards(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xabc') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let history = Arc::new(EchoIndexHistoryRepository::new(pool.clone()));
+        let established = insert_scored_content(&pool, &history, user_id, "established", 48, &[0.9]).await;
+        let rising = insert_scored_content(&pool, &history, user_id, "rising", 2, &[0.4]).await;
+        let rewarded = insert_scored_content(&pool, &history, user_id, "rewarded", 24, &[0.6]).await;
+        let standings = [(established, 0.9, 10.0), (rising, 0.4, 0.0), (rewarded, 0.6, 50.0)];
+        for (content_id, echo_index, total_rewards) in standings {
+            sqlx::query("UPDATE content SET echo_index = $2, total_rewards = $3 WHERE id = $1")
+                .bind(content_id)
+                .bind(echo_index)
+                .bind(total_rewards)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        for (content_id, score) in [(rising, 3.0), (rewarded, 0.5), (established, 0.0)] {
+            sqlx::query(
+                "INSERT INTO content_trending_scores (content_id, score, velocity, acceleration)
+                 VALUES ($1, $2, $2, 0)",
+            )
+            .bind(content_id)
+            .bind(score)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let service = LeaderboardService::new(history);
+        let titles = |entries: &[LeaderboardEntry]| entries.iter().map(|entry| entry.title.clone()).collect::<Vec<_>>();
+
+        // Only content currently trending is ranked by trending score
+        let trending = service
+            .build_leaderboard(TimeWindow::AllTime, None, LeaderboardSort::Trending, None, 10, None)
+            .await
+            .unwrap();
+        assert_eq!(titles(&trending), ["rising", "rewarded"]);
+        assert_eq!((trending[0].echo_index, trending[0].trending_score), (40.0, Some(3.0)));
+
+        let rewards = service
+            .build_leaderboard(TimeWindow::AllTime, None, LeaderboardSort::Rewards, None, 10, None)
+            .await
+            .unwrap();
+        assert_eq!(titles(&rewards), ["rewarded", "established", "rising"]);
+        assert_eq!(rewards[0].total_rewards, Some(50.0));
+
+        // Cursors carry the sorted-by value
+        let cursor = ScoreCursor::new(rewards[0].sort_score(LeaderboardSort::Rewards), rewarded);
+        let page = service
+            .build_leaderboard(TimeWindow::AllTime, None, LeaderboardSort::Rewards, None, 1, Some(cursor))
+            .await
+            .unwrap();
+        assert_eq!((page[0].title.as_str(), page[0].rank), ("established", 2));
+    }
+}
@@ -0,0 +1,204 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use crate::models::Platform;
+
+/// URL shorteners and spam domains, one per line
+const BLOCKLIST_SOURCE: &str = include_str!("link_blocklist.txt");
+
+/// Score of content without links, which neither cites sources nor spams
+pub const NO_LINKS_SCORE: f64 = 0.5;
+/// Beyond this many links, each additional one dilutes the score
+const MAX_CREDIBLE_LINKS: u32 = 3;
+
+/// Per-link credit
+const BASE_LINK_SCORE: f64 = 0.6;
+const HTTPS_BONUS: f64 = 0.4;
+/// Links back to the platform itself cite nothing new
+const SELF_LINK_SCORE: f64 = 0.3;
+
+/// How much the outbound links of a piece of content add to or take from its credibility
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LinkQualityReport {
+    pub link_count: u32,
+    pub unique_domains: u32,
+    pub https_links: u32,
+    /// Links to shorteners or known spam domains
+    pub blocked_links: u32,
+    /// Links to the platform the content was posted on
+    pub self_links: u32,
+    /// Between 0 and 1
+    pub score: f64,
+}
+
+impl Default for LinkQualityReport {
+    fn default() -> Self {
+        Self {
+            link_count: 0,
+            unique_domains: 0,
+            https_links: 0,
+            blocked_links: 0,
+            self_links: 0,
+            score: NO_LINKS_SCORE,
+        }
+    }
+}
+
+/// Scores the links in content posted on one platform
+pub struct LinkQualityAnalyzer {
+    platform_domains: &'static [&'static str],
+}
+
+impl LinkQualityAnalyzer {
+    pub fn new(platform: &Platform) -> Self {
+        Self { platform_domains: platform_domains(platform) }
+    }
+
+    /// Links to distinct, reputable https sites score highest. Shorteners and spam domains
+    /// score nothing, links back to the platform little, and piling on links dilutes the
+    /// score.
+    pub fn score_links(&self, urls: &[String]) -> LinkQualityReport {
+        if urls.is_empty() {
+            return LinkQualityReport::default();
+        }
+
+        let mut report = LinkQualityReport { score: 0.0, ..LinkQualityReport::default() };
+        let mut domains = HashSet::new();
+        let mut total = 0.0;
+        for url in urls {
+            let https = url.to_ascii_lowercase().starts_with("https://");
+            let domain = domain(url);
+            report.link_count += 1;
+            report.https_links += u32::from(https);
+
+            total += if is_blocked(&domain) {
+                report.blocked_links += 1;
+                0.0
+            } else if self.platform_domains.iter().any(|platform| within(&domain, platform)) {
+                report.self_links += 1;
+                SELF_LINK_SCORE
+            } else if https {
+                BASE_LINK_SCORE + HTTPS_BONUS
+            } else {
+                BASE_LINK_SCORE
+            };
+            domains.insert(domain);
+        }
+        report.unique_domains = domains.len() as u32;
+
+        let mean = total / report.link_count as f64;
+        // Repeating one domain is weaker evidence than citing several
+        let diversity = report.unique_domains as f64 / report.link_count as f64;
+        let dilution = MAX_CREDIBLE_LINKS as f64 / report.link_count.max(MAX_CREDIBLE_LINKS) as f64;
+        report.score = (mean * (0.7 + 0.3 * diversity) * dilution).clamp(0.0, 1.0);
+        report
+    }
+}
+
+/// Lowercase host of a URL, without `www.`, credentials or port
+fn domain(url: &str) -> String {
+    let lowercase = url.to_ascii_lowercase();
+    let without_scheme = lowercase.split_once("://").map_or(lowercase.as_str(), |(_, rest)| rest);
+    let authority = without_scheme.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    let host = host.split(':').next().unwrap_or_default();
+    host.strip_prefix("www.").unwrap_or(host).trim_end_matches('.').to_string()
+}
+
+/// Whether `domain` is `parent` or one of its subdomains
+fn within(domain: &str, parent: &str) -> bool {
+    domain == parent || domain.strip_suffix(parent).is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+fn is_blocked(domain: &str) -> bool {
+    static BLOCKLIST: OnceLock<HashSet<&'static str>> = OnceLock::new();
+    let blocklist = BLOCKLIST.get_or_init(|| {
+        BLOCKLIST_SOURCE
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect()
+    });
+
+    // The domain itself or any parent of it
+    let mut candidate = domain;
+    loop {
+        if blocklist.contains(candidate) {
+            return true;
+        }
+        match candidate.split_once('.') {
+            Some((_, parent)) if parent.contains('.') => candidate = parent,
+            _ => return false,
+        }
+    }
+}
+
+fn platform_domains(platform: &Platform) -> &'static [&'static str] {
+    match platform {
+        Platform::Twitter => &["twitter.com", "x.com", "t.co"],
+        Platform::Telegram => &["t.me", "telegram.org", "telegram.me"],
+        Platform::LinkedIn => &["linkedin.com", "lnkd.in"],
+        Platform::Reddit => &["reddit.com", "redd.it"],
+        Platform::Medium => &["medium.com"],
+        Platform::Discord => &["discord.com", "discord.gg"],
+        Platform::Farcaster => &["warpcast.com"],
+        Platform::Other(_) => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(urls: &[&str]) -> Vec<String> {
+        urls.iter().map(|url| url.to_string()).collect()
+    }
+
+    #[test]
+    fn test_reputable_https_links_outscore_spam_links() {
+        let analyzer = LinkQualityAnalyzer::new(&Platform::Twitter);
+        let cited = analyzer.score_links(&urls(&["https://arxiv.org/abs/2401.00001", "https://www.nature.com/articles/1"]));
+        let spam = analyzer.score_links(&urls(&[
+            "http://bit.ly/3xYz",
+            "http://free-crypto.win/claim",
+            "http://tinyurl.com/abc",
+            "http://claim-rewards.xyz/?ref=1",
+            "http://airdrop-claim.io",
+        ]));
+
+        assert_eq!((cited.link_count, cited.unique_domains, cited.https_links), (2, 2, 2));
+        assert_eq!((spam.link_count, spam.blocked_links, spam.https_links), (5, 5, 0));
+        assert!(cited.score > spam.score);
+        assert!((cited.score - 1.0).abs() < 1e-9);
+        assert_eq!(spam.score, 0.0);
+    }
+
+    #[test]
+    fn test_self_links_and_repeated_domains_count_for_less() {
+        let analyzer = LinkQualityAnalyzer::new(&Platform::Twitter);
+        let own = analyzer.score_links(&urls(&["https://x.com/alice/status/1", "https://mobile.twitter.com/bob"]));
+        assert_eq!(own.self_links, 2);
+        assert!(own.score < analyzer.score_links(&urls(&["https://example.com/a"])).score);
+
+        let repeated = analyzer.score_links(&urls(&["https://example.com/a", "https://example.com/b"]));
+        let distinct = analyzer.score_links(&urls(&["https://example.com/a", "https://example.org/b"]));
+        assert_eq!(repeated.unique_domains, 1);
+        assert!(repeated.score < distinct.score);
+
+        // Not self-referential elsewhere
+        let reddit = LinkQualityAnalyzer::new(&Platform::Reddit);
+        assert_eq!(reddit.score_links(&urls(&["https://x.com/alice/status/1"])).self_links, 0);
+        assert_eq!(analyzer.score_links(&[]), LinkQualityReport::default());
+    }
+
+    #[test]
+    fn test_domains_are_normalized() {
+        assert_eq!(domain("HTTPS://user:pw@WWW.Example.com:8443/path?q=1#top"), "example.com");
+        assert_eq!(domain("www.example.org"), "example.org");
+        assert!(is_blocked("go.bit.ly"));
+        assert!(!is_blocked("notbit.ly"));
+        assert!(within("old.reddit.com", "reddit.com"));
+        assert!(!within("fakereddit.com", "reddit.com"));
+    }
+}
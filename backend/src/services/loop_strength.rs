@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::services::propagation::EchoLoop;
+
+/// Rescales Echo Loop strength so loops of differently sized propagation networks can be
+/// compared
+pub struct EchoLoopNormalizer;
+
+impl EchoLoopNormalizer {
+    /// Distinct nodes across all of a loop's paths
+    pub fn unique_node_count(echo_loop: &EchoLoop) -> usize {
+        echo_loop
+            .propagation_paths
+            .iter()
+            .flat_map(|path| &path.nodes)
+            .map(|node| node.id.as_str())
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Raw loop strength divided by the log of the loop's network size. Networks of up to
+    /// e nodes divide by 1, so small and empty loops keep their raw strength.
+    pub fn normalize_by_network_size(echo_loop: &EchoLoop) -> f64 {
+        let network_size = Self::unique_node_count(echo_loop).max(1) as f64;
+        echo_loop.raw_loop_strength / network_size.ln().max(1.0)
+    }
+
+    /// Share of `strengths` at or below `strength`, from 0.0 to 1.0. Without other loops to
+    /// compare against a loop ranks at the top.
+    pub fn percentile(strength: f64, strengths: &[f64]) -> f64 {
+        if strengths.is_empty() {
+            return 1.0;
+        }
+        let at_or_below = strengths.iter().filter(|other| other.total_cmp(&strength).is_le()).count();
+        at_or_below as f64 / strengths.len() as f64
+    }
+}
+
+/// How strong an Echo Loop is, raw and compared across content
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LoopStrength {
+    pub loop_id: String,
+    pub raw_loop_strength: f64,
+    pub normalized_loop_strength: f64,
+    /// Share of all active loops, across all content, with at most this normalized strength
+    pub loop_strength_percentile: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::propagation::{NodeType, PropagationNode, PropagationPath};
+    use chrono::Utc;
+
+    /// A loop of one chain through `size` nodes, whose raw strength grows with the log of
+    /// its size as it does for otherwise identical networks
+    fn chain_loop(size: usize, strength_per_log_node: f64) -> EchoLoop {
+        let nodes = (0..size)
+            .map(|i| PropagationNode {
+                id: format!("node_{}", i),
+                node_type: NodeType::User,
+                influence_weight: 0.5,
+                reach: 100,
+                engagement_rate: 0.1,
+                timestamp: Utc::now(),
+            })
+            .collect();
+        EchoLoop {
+            id: format!("loop_{}", size),
+            source_content_id: format!("content_{}", size),
+            propagation_paths: vec![PropagationPath {
+                nodes,
+                total_weight: 1.0,
+                resonance_factor: 0.5,
+                decay_rate: 0.9,
+            }],
+            total_resonance: 0.5,
+            raw_loop_strength: strength_per_log_node * (size as f64).ln(),
+            normalized_loop_strength: 0.0,
+            created_at: Utc::now(),
+            last_updated: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_loops_of_different_network_sizes_normalize_alike() {
+        let small = chain_loop(3, 0.25);
+        let large = chain_loop(30, 0.25);
+        assert_eq!(EchoLoopNormalizer::unique_node_count(&small), 3);
+        assert_eq!(EchoLoopNormalizer::unique_node_count(&large), 30);
+        assert!(large.raw_loop_strength > 3.0 * small.raw_loop_strength);
+
+        let small_normalized = EchoLoopNormalizer::normalize_by_network_size(&small);
+        let large_normalized = EchoLoopNormalizer::normalize_by_network_size(&large);
+        assert!((small_normalized - 0.25).abs() < 1e-9);
+        assert!((small_normalized - large_normalized).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_small_and_empty_loops_keep_raw_strength() {
+        let mut pair = chain_loop(2, 0.0);
+        pair.raw_loop_strength = 0.4;
+        assert_eq!(EchoLoopNormalizer::normalize_by_network_size(&pair), 0.4);
+
+        let mut empty = chain_loop(0, 0.0);
+        empty.propagation_paths.clear();
+        empty.raw_loop_strength = 0.2;
+        assert_eq!(EchoLoopNormalizer::normalize_by_network_size(&empty), 0.2);
+    }
+
+    #[test]
+    fn test_percentile_counts_loops_at_or_below() {
+        let strengths = [0.1, 0.2, 0.2, 0.4];
+        assert_eq!(EchoLoopNormalizer::percentile(0.2, &strengths), 0.75);
+        assert_eq!(EchoLoopNormalizer::percentile(0.05, &strengths), 0.0);
+        assert_eq!(EchoLoopNormalizer::percentile(0.4, &strengths), 1.0);
+        assert_eq!(EchoLoopNormalizer::percentile(0.3, &[]), 1.0);
+    }
+}
@@ -0,0 +1,253 @@
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::models::content::ContentRecord;
+use crate::models::Platform;
+use crate::repositories::{InfluenceRepository, MentionRepository, RepositoryError};
+use crate::services::propagation::{NodeType, PropagationNode};
+use crate::services::{ContentNormalizer, PlatformNormalizer, PropagationService, RewardService};
+
+/// Strength of the implicit propagation from an author to a user they mention
+pub const MENTION_INTERACTION_STRENGTH: f64 = 0.5;
+/// Share of the base reward rate a mentioned user earns for each further propagation of
+/// the content mentioning them
+pub const MENTION_REWARD_SHARE: f64 = 0.1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MentionError {
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+    #[error("propagation error: {0}")]
+    Propagation(String),
+    #[error("reward error: {0}")]
+    Reward(String),
+}
+
+/// Finds the handles mentioned in content text
+pub struct MentionDetector;
+
+impl MentionDetector {
+    /// Distinct handles mentioned in `text`, without the `@`, in order of first mention.
+    /// Handles differing only in case are the same handle.
+    pub fn detect(text: &str, platform: &Platform) -> Vec<String> {
+        let mut mentions: Vec<String> = Vec::new();
+        for mention in PlatformNormalizer::normalize(text, platform).mentions {
+            if !mentions.iter().any(|seen| seen.eq_ignore_ascii_case(&mention)) {
+                mentions.push(mention);
+            }
+        }
+        mentions
+    }
+}
+
+/// Links content to the users it mentions. A mention is an implicit propagation from the
+/// author to the mentioned user, who earns a small community contribution reward
+/// whenever the content propagates further.
+pub struct MentionLinker {
+    mentions: Arc<MentionRepository>,
+    propagation: Arc<PropagationService>,
+    rewards: Arc<RwLock<RewardService>>,
+    influence: Option<Arc<InfluenceRepository>>,
+}
+
+impl MentionLinker {
+    pub fn new(
+        mentions: Arc<MentionRepository>,
+        propagation: Arc<PropagationService>,
+        rewards: Arc<RwLock<RewardService>>,
+    ) -> Self {
+        Self { mentions, propagation, rewards, influence: None }
+    }
+
+    /// Weight the propagation nodes of users by their latest influence score instead of
+    /// the fixed author and mention weights
+    pub fn with_influence(mut self, influence: Arc<InfluenceRepository>) -> Self {
+        self.influence = Some(influence);
+        self
+    }
+
+    /// User of each of `mentions` on `platform`, through the social accounts users linked
+    pub async fn resolve(&self, mentions: &[String], platform: Platform) -> Result<Vec<Option<Uuid>>, RepositoryError> {
+        let mut users = Vec::with_capacity(mentions.len());
+        for mention in mentions {
+            users.push(self.mentions.find_user(&platform, mention).await?);
+        }
+        Ok(users)
+    }
+
+    /// Record the users `content` mentions and add a propagation from its author to each
+    /// of them. Returns the users mentioned for the first time; authors mentioning
+    /// themselves are ignored.
+    pub async fn link(&self, content: &ContentRecord) -> Result<Vec<Uuid>, MentionError> {
+        let mentions = MentionDetector::detect(&content.body, &content.platform);
+        if mentions.is_empty() {
+            return Ok(Vec::new());
+        }
+        let users = self.resolve(&mentions, content.platform.clone()).await?;
+
+        let mut linked = Vec::new();
+        for (mention, user_id) in mentions.iter().zip(users) {
+            let Some(user_id) = user_id.filter(|user_id| *user_id != content.user_id) else {
+                continue;
+            };
+            if self.mentions.record(content.id, user_id, mention).await? {
+                linked.push(user_id);
+            }
+        }
+        if linked.is_empty() {
+            return Ok(linked);
+        }
+
+        let loop_id = self.echo_loop_of(content.id).await?;
+        let author_weight = self.influence_weight(content.user_id, 1.0).await?;
+        for user_id in &linked {
+            let author = mention_node(content.user_id, NodeType::User, author_weight);
+            let mentioned_weight = self.influence_weight(*user_id, MENTION_INTERACTION_STRENGTH).await?;
+            let mentioned = mention_node(*user_id, NodeType::Mention, mentioned_weight);
+            self.propagation
+                .add_propagation_event(&loop_id, author, mentioned, MENTION_INTERACTION_STRENGTH)
+                .await
+                .map_err(|e| MentionError::Propagation(e.to_string()))?;
+        }
+        Ok(linked)
+    }
+
+    /// Reward the users mentioned in a content item for `propagations` further
+    /// propagations of it. Returns the ids of the rewards awarded.
+    pub async fn reward_propagations(&self, content_id: Uuid, propagations: usize) -> Result<Vec<String>, MentionError> {
+        let users = self.mentions.mentioned_users(content_id).await?;
+        if users.is_empty() || propagations == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut rewards = self.rewards.write().await;
+        let amount = rewards.base_rate() * MENTION_REWARD_SHARE * propagations as f64;
+        let mut reward_ids = Vec::with_capacity(users.len());
+        for user_id in users {
+            let reward_id = rewards
+                .award_community_contribution(user_id.to_string(), content_id.to_string(), amount, 0.0)
+                .await
+                .map_err(MentionError::Reward)?;
+            reward_ids.push(reward_id);
+        }
+        Ok(reward_ids)
+    }
+
+    /// Composite influence of the user, or `default` for users not scored yet
+    async fn influence_weight(&self, user_id: Uuid, default: f64) -> Result<f64, RepositoryError> {
+        let Some(influence) = &self.influence else {
+            return Ok(default);
+        };
+        Ok(influence.composite(user_id).await?.unwrap_or(default))
+    }
+
+    /// The content's first echo loop, created if it has none
+    async fn echo_loop_of(&self, content_id: Uuid) -> Result<String, MentionError> {
+        self.propagation
+            .content_echo_loop(&content_id.to_string())
+            .await
+            .map_err(MentionError::Propagation)
+    }
+}
+
+fn mention_node(user_id: Uuid, node_type: NodeType, influence_weight: f64) -> PropagationNode {
+    PropagationNode {
+        id: user_id.to_string(),
+        node_type,
+        influence_weight,
+        reach: 0,
+        engagement_rate: 0.0,
+        timestamp: Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::influence::InfluenceScore;
+    use crate::repositories::{ContentRepository, NewContent};
+    use crate::services::rewards::RewardType;
+    use sqlx::PgPool;
+    use std::collections::HashMap;
+
+    async fn user(pool: &PgPool, wallet: &str) -> Uuid {
+        sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+            .bind(wallet)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_mentions_are_detected_once() {
+        let mentions = MentionDetector::detect("cc @Alice_Dev @jane.doe @alice_dev", &Platform::Twitter);
+        assert_eq!(mentions, vec!["Alice_Dev", "jane.doe"]);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_mentioning_a_known_user_links_and_rewards_them(pool: PgPool) {
+        let author = user(&pool, "0xauthor").await;
+        let alice = user(&pool, "0xalice").await;
+        sqlx::query(
+            "INSERT INTO social_platforms (user_id, platform, platform_user_id, platform_username)
+             VALUES ($1, 'twitter', '42', 'Alice_Dev'), ($2, 'twitter', '7', 'me')",
+        )
+        .bind(alice)
+        .bind(author)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let content = ContentRepository::new(pool.clone())
+            .create(&NewContent {
+                user_id: author,
+                platform: Platform::Twitter,
+                external_id: "tweet_1".to_string(),
+                content_type: "text".to_string(),
+                title: String::new(),
+                body: "gm @alice_dev, @nobody and @me".to_string(),
+                media_urls: vec![],
+                tags: vec![],
+                reactions: HashMap::new(),
+            })
+            .await
+            .unwrap();
+
+        let propagation = Arc::new(PropagationService::new());
+        let rewards = Arc::new(RwLock::new(RewardService::new(10_000.0)));
+        let influence = Arc::new(InfluenceRepository::new(pool.clone()));
+        let author_influence = InfluenceScore { composite: 0.8, ..InfluenceScore::default() };
+        influence.record(author, &author_influence, 30, chrono::Utc::now()).await.unwrap();
+        let linker = MentionLinker::new(Arc::new(MentionRepository::new(pool)), propagation.clone(), rewards.clone())
+            .with_influence(influence);
+
+        let mentions = ["alice_dev".to_string(), "nobody".to_string()];
+        assert_eq!(linker.resolve(&mentions, Platform::Twitter).await.unwrap(), vec![Some(alice), None]);
+        assert_eq!(linker.resolve(&mentions, Platform::Telegram).await.unwrap(), vec![None, None]);
+
+        // The author mentioning themselves is not linked, nor is linking again
+        assert_eq!(linker.link(&content).await.unwrap(), vec![alice]);
+        assert!(linker.link(&content).await.unwrap().is_empty());
+
+        let loops = propagation.get_content_echo_loops(&content.id.to_string());
+        assert_eq!(loops.len(), 1);
+        let nodes = &loops[0].propagation_paths[0].nodes;
+        assert_eq!(loops[0].propagation_paths.len(), 1);
+        assert_eq!(nodes[0].id, author.to_string());
+        assert_eq!(nodes[1].id, alice.to_string());
+        assert!(matches!(nodes[1].node_type, NodeType::Mention));
+        // The author is weighted by their influence; Alice has not been scored yet
+        assert_eq!((nodes[0].influence_weight, nodes[1].influence_weight), (0.8, MENTION_INTERACTION_STRENGTH));
+
+        // Two further propagations of the content earn Alice a contribution reward
+        assert_eq!(linker.reward_propagations(content.id, 2).await.unwrap().len(), 1);
+        let rewards = rewards.read().await;
+        let earned = rewards.get_user_rewards(&alice.to_string());
+        assert_eq!(earned.len(), 1);
+        assert!(matches!(earned[0].reward_type, RewardType::CommunityContribution));
+        assert!((earned[0].amount - 2.0 * MENTION_REWARD_SHARE * rewards.base_rate()).abs() < 1e-9);
+        assert!(rewards.get_user_rewards(&author.to_string()).is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+use rs_merkle::algorithms::Sha256;
+use serde::Serialize;
+use sha2::Digest;
+use utoipa::ToSchema;
+
+use crate::services::token_vesting::to_base_units;
+use crate::services::EchoDropReward;
+
+/// Merkle tree over reward leaves. An unpaired node is carried up to the next level
+/// unchanged, and pairs are hashed left to right, so verifiers need each leaf's index.
+pub type MerkleTree = rs_merkle::MerkleTree<Sha256>;
+
+/// Leaf of a reward: `sha256(user_id || content_id || amount || timestamp)`, with the IDs as
+/// UTF-8, the amount in token base units and the timestamp in Unix seconds, both as
+/// little-endian 64-bit integers the way the reward program reads them
+pub fn reward_leaf(reward: &EchoDropReward) -> [u8; 32] {
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(reward.user_id.as_bytes());
+    hasher.update(reward.content_id.as_bytes());
+    hasher.update(to_base_units(reward.amount).to_le_bytes());
+    hasher.update(reward.timestamp.timestamp().to_le_bytes());
+    hasher.finalize().into()
+}
+
+pub struct MerkleTreeBuilder;
+
+impl MerkleTreeBuilder {
+    /// Tree whose leaves are the rewards' leaves, in the order given
+    pub fn build(rewards: &[EchoDropReward]) -> MerkleTree {
+        let leaves: Vec<[u8; 32]> = rewards.iter().map(reward_leaf).collect();
+        MerkleTree::from_leaves(&leaves)
+    }
+}
+
+pub struct MerkleProofGenerator;
+
+impl MerkleProofGenerator {
+    /// Sibling hashes from the reward's leaf up to the root; empty for an index outside the tree
+    pub fn proof_for(tree: &MerkleTree, reward_index: usize) -> Vec<[u8; 32]> {
+        if reward_index >= tree.leaves_len() {
+            return Vec::new();
+        }
+        tree.proof(&[reward_index]).proof_hashes().to_vec()
+    }
+}
+
+/// Proof that a reward is one of the leaves of a claim's Merkle root
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RewardProof {
+    pub reward_id: String,
+    /// Hex sibling hashes, leaf level first
+    pub proof: Vec<String>,
+    pub leaf_index: usize,
+}
+
+/// Rewards claimed on chain in one transaction: the reward program checks each reward's
+/// proof against the root instead of paying rewards one transaction at a time
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct MerkleRewardClaim {
+    /// Hex root of the tree over the rewards' leaves
+    pub merkle_root: String,
+    pub proofs: Vec<RewardProof>,
+}
+
+impl MerkleRewardClaim {
+    /// Claim of the rewards, leaf indices following their order; None without rewards
+    pub fn new(rewards: &[EchoDropReward]) -> Option<Self> {
+        let tree = MerkleTreeBuilder::build(rewards);
+        let root = tree.root()?;
+        let proofs = rewards
+            .iter()
+            .enumerate()
+            .map(|(leaf_index, reward)| RewardProof {
+                reward_id: reward.id.clone(),
+                proof: MerkleProofGenerator::proof_for(&tree, leaf_index).iter().map(hex::encode).collect(),
+                leaf_index,
+            })
+            .collect();
+
+        Some(Self { merkle_root: hex::encode(root), proofs })
+    }
+
+    /// Store the root on chain for the reward program to check proofs against. The program
+    /// is not deployed yet, so the root is only logged.
+    pub fn publish_root(&self, user_id: &str) {
+        log::info!(
+            "Publishing Merkle root {} of {} rewards claimed by {}",
+            self.merkle_root,
+            self.proofs.len(),
+            user_id
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::rewards::{RewardType, VestingSchedule};
+    use chrono::{Duration, TimeZone, Utc};
+    use rs_merkle::{Hasher, MerkleProof};
+
+    fn reward(index: usize) -> EchoDropReward {
+        let timestamp = Utc.with_ymd_and_hms(2024, 11, 1, 12, 0, 0).unwrap() + Duration::minutes(index as i64);
+        EchoDropReward {
+            id: format!("reward_{}", index),
+            user_id: "user_1".to_string(),
+            content_id: format!("content_{}", index),
+            reward_type: RewardType::ContentCreation,
+            amount: 1.5 + index as f64,
+            echo_index_contribution: 0.5,
+            timestamp,
+            transaction_hash: None,
+            vesting_schedule: VestingSchedule::Immediate,
+            vested_amount: 0.0,
+            vest_start: timestamp,
+            on_hold: false,
+            frozen: false,
+        }
+    }
+
+    #[test]
+    fn test_every_proof_validates_against_the_root() {
+        // Odd counts leave unpaired nodes at some levels
+        for count in 1..=9 {
+            let rewards: Vec<EchoDropReward> = (0..count).map(reward).collect();
+            let claim = MerkleRewardClaim::new(&rewards).unwrap();
+            let root: [u8; 32] = hex::decode(&claim.merkle_root).unwrap().try_into().unwrap();
+
+            for (reward, proof) in rewards.iter().zip(&claim.proofs) {
+                assert_eq!(proof.reward_id, reward.id);
+                let hashes: Vec<[u8; 32]> =
+                    proof.proof.iter().map(|hash| hex::decode(hash).unwrap().try_into().unwrap()).collect();
+                let leaf = [reward_leaf(reward)];
+                assert!(MerkleProof::<Sha256>::new(hashes.clone()).verify(root, &[proof.leaf_index], &leaf, count));
+
+                // A reward with a different amount is not in the tree
+                let inflated = [reward_leaf(&EchoDropReward { amount: reward.amount * 2.0, ..reward.clone() })];
+                assert!(!MerkleProof::<Sha256>::new(hashes).verify(root, &[proof.leaf_index], &inflated, count));
+            }
+        }
+    }
+
+    #[test]
+    fn test_proofs_hold_sibling_hashes() {
+        let rewards: Vec<EchoDropReward> = (0..4).map(reward).collect();
+        let leaves: Vec<[u8; 32]> = rewards.iter().map(reward_leaf).collect();
+        let tree = MerkleTreeBuilder::build(&rewards);
+
+        let right_pair = Sha256::concat_and_hash(&leaves[2], Some(&leaves[3]));
+        assert_eq!(MerkleProofGenerator::proof_for(&tree, 1), vec![leaves[0], right_pair]);
+        assert!(MerkleProofGenerator::proof_for(&tree, 4).is_empty());
+        assert_eq!(MerkleRewardClaim::new(&[]), None);
+    }
+}
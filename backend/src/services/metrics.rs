@@ -0,0 +1,179 @@
+use prometheus::{
+    Encoder, Gauge, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts,
+    Registry, TextEncoder,
+};
+
+use crate::models::Platform;
+
+/// Echo Index scores are reported on the 0-100 scale
+const ECHO_INDEX_SCORE_BUCKETS: [f64; 6] = [10.0, 25.0, 50.0, 75.0, 90.0, 100.0];
+
+/// Values of the `propagation_type` enum; anything else is labelled `other`
+const PROPAGATION_TYPES: [&str; 7] = ["share", "repost", "quote", "mention", "link", "embed", "cross_post"];
+
+/// Outcome label of `echolayer_auth_requests_total`
+#[derive(Debug, Clone, Copy)]
+pub enum AuthOutcome {
+    Success,
+    Failure,
+    RateLimited,
+}
+
+impl AuthOutcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuthOutcome::Success => "success",
+            AuthOutcome::Failure => "failure",
+            AuthOutcome::RateLimited => "rate_limited",
+        }
+    }
+}
+
+/// Prometheus metrics exported on `GET /metrics`
+pub struct MetricsRegistry {
+    registry: Registry,
+    pub echo_index_calculations: IntCounterVec,
+    pub echo_index_scores: Histogram,
+    pub active_echo_loops: IntGauge,
+    pub reward_pool_remaining: Gauge,
+    pub propagation_events: IntCounterVec,
+    pub auth_requests: IntCounterVec,
+    pub http_request_duration: HistogramVec,
+    pub http_requests_in_flight: IntGauge,
+    pub http_cache_requests: IntCounterVec,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let echo_index_calculations = IntCounterVec::new(
+            Opts::new("echolayer_echo_index_calculations_total", "Echo Index calculations by platform"),
+            &["platform"],
+        )
+        .expect("valid metric definition");
+        let echo_index_scores = Histogram::with_opts(
+            HistogramOpts::new("echolayer_echo_index_score_histogram", "Distribution of calculated Echo Index scores")
+                .buckets(ECHO_INDEX_SCORE_BUCKETS.to_vec()),
+        )
+        .expect("valid metric definition");
+        let active_echo_loops = IntGauge::new("echolayer_active_echo_loops", "Echo Loops currently tracked")
+            .expect("valid metric definition");
+        let reward_pool_remaining = Gauge::new("echolayer_reward_pool_remaining", "EchoDrop tokens left in today's pool")
+            .expect("valid metric definition");
+        let propagation_events = IntCounterVec::new(
+            Opts::new("echolayer_propagation_events_total", "Propagation events by type"),
+            &["type"],
+        )
+        .expect("valid metric definition");
+        let auth_requests = IntCounterVec::new(
+            Opts::new("echolayer_auth_requests_total", "Authentication requests by outcome"),
+            &["outcome"],
+        )
+        .expect("valid metric definition");
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new("echolayer_http_request_duration_seconds", "HTTP request latency"),
+            &["method", "route"],
+        )
+        .expect("valid metric definition");
+        let http_requests_in_flight =
+            IntGauge::new("echolayer_http_requests_in_flight", "HTTP requests currently being handled")
+                .expect("valid metric definition");
+
+        let http_cache_requests = IntCounterVec::new(
+            Opts::new("echolayer_http_cache_requests_total", "Requests to cached routes by cache result"),
+            &["result"],
+        )
+        .expect("valid metric definition");
+
+        for collector in [
+            Box::new(echo_index_calculations.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(echo_index_scores.clone()),
+            Box::new(active_echo_loops.clone()),
+            Box::new(reward_pool_remaining.clone()),
+            Box::new(propagation_events.clone()),
+            Box::new(auth_requests.clone()),
+            Box::new(http_request_duration.clone()),
+            Box::new(http_requests_in_flight.clone()),
+            Box::new(http_cache_requests.clone()),
+        ] {
+            registry.register(collector).expect("metric names are unique");
+        }
+
+        Self {
+            registry,
+            echo_index_calculations,
+            echo_index_scores,
+            active_echo_loops,
+            reward_pool_remaining,
+            propagation_events,
+            auth_requests,
+            http_request_duration,
+            http_requests_in_flight,
+            http_cache_requests,
+        }
+    }
+
+    /// Record a completed Echo Index calculation; `score` is on the 0-100 scale
+    pub fn record_echo_index(&self, platform: &Platform, score: f64) {
+        self.echo_index_calculations.with_label_values(&[platform.as_str()]).inc();
+        self.echo_index_scores.observe(score);
+    }
+
+    /// Record a propagation event; unknown types share one label to bound cardinality
+    pub fn record_propagation(&self, propagation_type: &str) {
+        let label = if PROPAGATION_TYPES.contains(&propagation_type) { propagation_type } else { "other" };
+        self.propagation_events.with_label_values(&[label]).inc();
+    }
+
+    pub fn record_auth(&self, outcome: AuthOutcome) {
+        self.auth_requests.with_label_values(&[outcome.as_str()]).inc();
+    }
+
+    /// Counter for authentication requests rejected by the rate limiter
+    pub fn auth_rate_limited(&self) -> IntCounter {
+        self.auth_requests.with_label_values(&[AuthOutcome::RateLimited.as_str()])
+    }
+
+    /// Counter for requests to cached routes served from the response cache
+    pub fn cache_hits(&self) -> IntCounter {
+        self.http_cache_requests.with_label_values(&["hit"])
+    }
+
+    /// Counter for requests to cached routes passed on to their handler
+    pub fn cache_misses(&self) -> IntCounter {
+        self.http_cache_requests.with_label_values(&["miss"])
+    }
+
+    /// Render every metric in the Prometheus text exposition format
+    pub fn encode(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        String::from_utf8(buffer).map_err(|e| prometheus::Error::Msg(e.to_string()))
+    }
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_includes_recorded_metrics() {
+        let metrics = MetricsRegistry::new();
+        metrics.record_echo_index(&Platform::LinkedIn, 62.0);
+        metrics.record_auth(AuthOutcome::Success);
+        metrics.auth_rate_limited().inc();
+
+        let output = metrics.encode().unwrap();
+
+        assert!(output.contains("echolayer_echo_index_calculations_total{platform=\"linkedin\"} 1"));
+        assert!(output.contains("echolayer_echo_index_score_histogram_bucket{le=\"75\"} 1"));
+        assert!(output.contains("echolayer_auth_requests_total{outcome=\"rate_limited\"} 1"));
+    }
+}
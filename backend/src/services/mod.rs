@@ -1,11 +1,125 @@
 pub mod echo_engine;
+pub mod engine_config;
 pub mod propagation;
 pub mod rewards;
 pub mod echo_service;
 pub mod reward_service;
+pub mod reward_forecast;
+pub mod token_blacklist;
+pub mod challenge_store;
+pub mod echo_index_updates;
+pub mod echo_decay;
+pub mod bot_detector;
+pub mod batch_jobs;
+pub mod suspicion;
+pub mod metrics;
+pub mod streaks;
+pub mod content_fingerprint;
+pub mod content_archive;
+pub mod content_versioning;
+pub mod readability;
+pub mod language;
+pub mod link_quality;
+pub mod idempotency;
+pub mod recalculation_queue;
+pub mod content_normalizer;
+pub mod hashtag_trends;
+pub mod mentions;
+pub mod token_vesting;
+pub mod solana_client;
+pub mod content_similarity;
+pub mod content_nlp;
+pub mod content_clusters;
+pub mod quality_bonus;
+pub mod api_keys;
+pub mod velocity_alerts;
+pub mod community_detection;
+pub mod propagation_depth;
+pub mod propagation_weights;
+pub mod leaderboard;
+pub mod moderation;
+pub mod discovery_feed;
+pub mod webhooks;
+pub mod user_data;
+pub mod social_verification;
+pub mod influence;
+pub mod follower_graph;
+pub mod pool_governor;
+pub mod echo_index_cache;
+pub mod mpc_wallet;
+pub mod echo_percentiles;
+pub mod echo_anomalies;
+pub mod content_attribution;
+pub mod cold_start;
+pub mod loop_strength;
+pub mod two_factor;
+pub mod reward_analytics;
+pub mod platform_stats;
+pub mod content_import;
+pub mod farcaster;
+pub mod trending_scores;
+pub mod merkle_rewards;
+pub mod propagation_dedup;
+pub mod tier_progression;
+pub mod callback_urls;
 
 pub use echo_service::EchoService;
-pub use reward_service::RewardService;
+pub use reward_service::{ContentCreationData, PropagationData, RewardService};
+pub use reward_forecast::RewardForecastService;
 pub use echo_engine::{EchoEngine, EchoMetrics, EchoEngineConfig};
-pub use propagation::{PropagationService, EchoLoop, PropagationNode, NodeType};
-pub use rewards::{RewardsService, RewardType, EchoDropReward, UserRewardStats}; 
\ No newline at end of file
+pub use engine_config::{ConfigSource, EngineConfigStore};
+pub use propagation::{PropagationService, EchoLoop, PropagationEventError, PropagationNode, NodeType};
+pub use loop_strength::LoopStrength;
+pub use rewards::{RewardsService, RewardType, EchoDropReward, UserRewardStats};
+pub use token_blacklist::TokenBlacklist;
+pub use challenge_store::ChallengeStore;
+pub use echo_index_updates::{EchoIndexComponents, EchoIndexUpdate, EchoIndexUpdates};
+pub use echo_decay::DecayScheduler;
+pub use bot_detector::BotDetector;
+pub use batch_jobs::{BatchJobs, RecalculationContext};
+pub use metrics::{AuthOutcome, MetricsRegistry};
+pub use streaks::StreakService;
+pub use content_fingerprint::{ContentFingerprint, ContentFingerprintService};
+pub use content_archive::ContentArchiver;
+pub use content_versioning::ContentVersioningService;
+pub use readability::Readability;
+pub use idempotency::IdempotencyCache;
+pub use recalculation_queue::RecalculationQueue;
+pub use content_normalizer::{ContentNormalizer, NormalizedContent, PlatformNormalizer};
+pub use hashtag_trends::HashtagTrendService;
+pub use mentions::MentionLinker;
+pub use token_vesting::{SettlementError, TokenVestingService};
+pub use solana_client::SolanaBlockchainClient;
+pub use content_similarity::{ContentSimilarityService, TfIdfVector};
+pub use content_nlp::ContentNLPPipeline;
+pub use content_clusters::ContentClusterAnalyzer;
+pub use quality_bonus::QualityBonusScheduler;
+pub use api_keys::{ApiKeyError, ApiKeyService};
+pub use velocity_alerts::{AlertWebhookDispatcher, DbDispatcher, LogDispatcher, VelocityAlertService};
+pub use community_detection::{Community, PropagationCommunityDetector};
+pub use propagation_depth::PropagationDepthAnalyzer;
+pub use propagation_weights::PropagationWeightNormalizer;
+pub use leaderboard::{LeaderboardCache, LeaderboardService, TimeWindow};
+pub use moderation::{ContentModerationService, ModerationError};
+pub use discovery_feed::DiscoveryFeedService;
+pub use webhooks::WebhookDispatcher;
+pub use user_data::{UserDataError, UserDataService};
+pub use social_verification::{SocialAccountVerifier, VerificationError};
+pub use influence::InfluenceScoreCalculator;
+pub use follower_graph::UserFollowerGraph;
+pub use pool_governor::PoolUtilizationGovernor;
+pub use echo_index_cache::EchoIndexCache;
+pub use mpc_wallet::{MpcWalletVerifier, PrivyMpcVerifier};
+pub use echo_percentiles::{EchoIndexPercentileCache, TierCutoffs};
+pub use echo_anomalies::EchoIndexAnomalyDetector;
+pub use content_attribution::ContentAttributionService;
+pub use cold_start::ColdStartService;
+pub use two_factor::{SecretCipher, TwoFactorError, TwoFactorService};
+pub use reward_analytics::RewardAnalyticsService;
+pub use platform_stats::PlatformStatsService;
+pub use content_import::{ContentImportError, ContentImportService};
+pub use farcaster::FarcasterIndexer;
+pub use trending_scores::TrendingScoreService;
+pub use merkle_rewards::MerkleRewardClaim;
+pub use propagation_dedup::PropagationEventDeduplicator;
+pub use tier_progression::{TierConfig, UserTierProgressionService};
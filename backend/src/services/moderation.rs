@@ -0,0 +1,219 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::moderation::{FlagReason, ModerationDecision, ModerationFlag};
+use crate::models::webhook::WebhookEvent;
+use crate::repositories::{ModerationRepository, RepositoryError};
+use crate::services::{RewardService, WebhookDispatcher};
+
+/// Open flags that put content under review
+pub const REVIEW_FLAG_THRESHOLD: i64 = 3;
+/// Flags a user may raise per day
+pub const DAILY_FLAG_LIMIT: i64 = 5;
+/// Flags listed per page of the moderation queue, by default
+pub const DEFAULT_QUEUE_LIMIT: i64 = 50;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ModerationError {
+    #[error("at most {DAILY_FLAG_LIMIT} flags may be raised per day")]
+    DailyLimitReached,
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+/// A flag that was raised and what it did to the content
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FlagOutcome {
+    pub flag: ModerationFlag,
+    /// Open flags on the content, including this one
+    pub flag_count: i64,
+    /// Whether this flag put the content under review
+    pub under_review: bool,
+}
+
+/// Lets users flag harmful or spammy content and moderators review it. Content with
+/// enough open flags is held for review; content a moderator rejects is removed and
+/// its rewards are frozen.
+pub struct ContentModerationService {
+    repository: Arc<ModerationRepository>,
+    rewards: Option<Arc<RwLock<RewardService>>>,
+    webhooks: Option<Arc<WebhookDispatcher>>,
+}
+
+impl ContentModerationService {
+    pub fn new(repository: Arc<ModerationRepository>) -> Self {
+        Self { repository, rewards: None, webhooks: None }
+    }
+
+    /// Freeze the rewards of content moderators remove
+    pub fn with_reward_service(mut self, rewards: Arc<RwLock<RewardService>>) -> Self {
+        self.rewards = Some(rewards);
+        self
+    }
+
+    /// Notify authors' webhooks when their content is flagged. Flaggers stay anonymous.
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookDispatcher>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    pub async fn flag(
+        &self,
+        content_id: Uuid,
+        flagged_by: Uuid,
+        reason: FlagReason,
+        now: DateTime<Utc>,
+    ) -> Result<FlagOutcome, ModerationError> {
+        if self.repository.flags_since(flagged_by, now - Duration::days(1)).await? >= DAILY_FLAG_LIMIT {
+            return Err(ModerationError::DailyLimitReached);
+        }
+
+        let flag = self.repository.create_flag(content_id, flagged_by, &reason).await?;
+        let flag_count = self.repository.open_flag_count(content_id).await?;
+        let under_review = flag_count >= REVIEW_FLAG_THRESHOLD && self.repository.mark_under_review(content_id).await?;
+        if under_review {
+            log::info!("Content {} is under review after {} flags", content_id, flag_count);
+        }
+        if let Some(webhooks) = &self.webhooks {
+            let data = serde_json::json!({
+                "content_id": content_id,
+                "flag_id": flag.id,
+                "reason": flag.reason,
+                "flag_count": flag_count,
+                "under_review": under_review,
+            });
+            webhooks.notify_content_author(content_id, WebhookEvent::ContentFlagged, data);
+        }
+
+        Ok(FlagOutcome { flag, flag_count, under_review })
+    }
+
+    pub async fn queue(&self, limit: i64) -> Result<Vec<ModerationFlag>, RepositoryError> {
+        self.repository.queue(limit).await
+    }
+
+    /// Resolve a flag, and every other open flag on its content, with a moderator's decision
+    pub async fn review(
+        &self,
+        flag_id: Uuid,
+        reviewed_by: Uuid,
+        decision: ModerationDecision,
+    ) -> Result<ModerationFlag, RepositoryError> {
+        let flag = self.repository.review(flag_id, reviewed_by, decision).await?;
+
+        if decision == ModerationDecision::Reject {
+            if let Some(rewards) = &self.rewards {
+                let frozen = rewards.write().await.freeze_content_rewards(&flag.content_id.to_string());
+                log::info!("Removed content {}, freezing {} pending rewards", flag.content_id, frozen);
+            }
+        }
+
+        Ok(flag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::moderation::ModerationStatus;
+    use sqlx::PgPool;
+
+    async fn user(pool: &PgPool, wallet: &str) -> Uuid {
+        sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+            .bind(wallet)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn content_status(pool: &PgPool, content_id: Uuid) -> String {
+        sqlx::query_scalar("SELECT status::text FROM content WHERE id = $1")
+            .bind(content_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_third_flag_puts_content_under_review(pool: PgPool) {
+        let author = user(&pool, "0xauthor").await;
+        let content_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type, title, body)
+             VALUES ($1, 'twitter', 'tweet_spam', 'text', 'Free tokens', 'Click here') RETURNING id",
+        )
+        .bind(author)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let rewards = Arc::new(RwLock::new(RewardService::new(1_000.0)));
+        let service = ContentModerationService::new(Arc::new(ModerationRepository::new(pool.clone())))
+            .with_reward_service(rewards.clone());
+        let now = Utc::now();
+
+        let first = user(&pool, "0xflagger_1").await;
+        assert!(!service.flag(content_id, first, FlagReason::Spam, now).await.unwrap().under_review);
+        // Flagging twice does not count twice
+        assert!(matches!(
+            service.flag(content_id, first, FlagReason::Spam, now).await,
+            Err(ModerationError::Repository(RepositoryError::Conflict(_)))
+        ));
+        let second = user(&pool, "0xflagger_2").await;
+        let outcome = service.flag(content_id, second, FlagReason::Misinformation, now).await.unwrap();
+        assert_eq!((outcome.flag_count, outcome.under_review), (2, false));
+        assert_eq!(content_status(&pool, content_id).await, "active");
+
+        let third = user(&pool, "0xflagger_3").await;
+        let outcome = service
+            .flag(content_id, third, FlagReason::Other("scam link".to_string()), now)
+            .await
+            .unwrap();
+        assert_eq!((outcome.flag_count, outcome.under_review), (3, true));
+        assert_eq!(content_status(&pool, content_id).await, "under_review");
+
+        let queue = service.queue(DEFAULT_QUEUE_LIMIT).await.unwrap();
+        assert_eq!(queue.len(), 3);
+        assert!(queue.iter().all(|flag| flag.status == ModerationStatus::UnderReview));
+
+        let moderator = user(&pool, "0xmoderator").await;
+        let reviewed = service.review(queue[0].id, moderator, ModerationDecision::Reject).await.unwrap();
+        assert_eq!((reviewed.status, reviewed.reviewed_by), (ModerationStatus::Removed, Some(moderator)));
+        assert_eq!(content_status(&pool, content_id).await, "removed");
+        assert!(service.queue(DEFAULT_QUEUE_LIMIT).await.unwrap().is_empty());
+        assert!(matches!(
+            service.review(queue[1].id, moderator, ModerationDecision::Approve).await,
+            Err(RepositoryError::Conflict(_))
+        ));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_flags_are_limited_per_day(pool: PgPool) {
+        let author = user(&pool, "0xauthor").await;
+        let flagger = user(&pool, "0xflagger").await;
+        let service = ContentModerationService::new(Arc::new(ModerationRepository::new(pool.clone())));
+        let now = Utc::now();
+
+        for i in 0..=DAILY_FLAG_LIMIT {
+            let content_id: Uuid = sqlx::query_scalar(
+                "INSERT INTO content (user_id, platform, external_id, content_type, title, body)
+                 VALUES ($1, 'twitter', $2, 'text', 'Post', 'Body') RETURNING id",
+            )
+            .bind(author)
+            .bind(format!("tweet_{}", i))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+            let result = service.flag(content_id, flagger, FlagReason::Spam, now).await;
+            if i < DAILY_FLAG_LIMIT {
+                result.unwrap();
+            } else {
+                assert!(matches!(result, Err(ModerationError::DailyLimitReached)));
+            }
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use futures_util::future::BoxFuture;
+use moka::sync::Cache;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+/// How long a successful verification of a message is reused for the same wallet
+const VERIFICATION_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+const VERIFICATION_CACHE_CAPACITY: u64 = 10_000;
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Checks signatures of MPC wallets, whose key is split between parties and never held
+/// whole, by asking the wallet provider
+pub trait MpcWalletVerifier: Send + Sync {
+    /// Whether `signature` is the wallet's signature of `message`. Errors when the provider
+    /// could not be asked.
+    fn verify<'a>(
+        &'a self,
+        wallet_address: &'a str,
+        signature: &'a str,
+        message: &'a str,
+    ) -> BoxFuture<'a, Result<bool, String>>;
+}
+
+/// Privy app credentials
+#[derive(Debug, Clone)]
+pub struct PrivyConfig {
+    pub app_id: String,
+    pub app_secret: String,
+    /// `https://auth.privy.io`, or a mock in tests
+    pub api_base: String,
+}
+
+impl PrivyConfig {
+    pub fn new(app_id: String, app_secret: String) -> Self {
+        Self { app_id, app_secret, api_base: "https://auth.privy.io".to_string() }
+    }
+
+    /// `None` unless `PRIVY_APP_ID` and `PRIVY_APP_SECRET` are set
+    pub fn from_env() -> Option<Self> {
+        let id = std::env::var("PRIVY_APP_ID").ok()?;
+        let secret = std::env::var("PRIVY_APP_SECRET").ok()?;
+        Some(Self::new(id, secret))
+    }
+}
+
+#[derive(Deserialize)]
+struct PrivyVerification {
+    valid: bool,
+}
+
+/// Verifies MPC wallet signatures with Privy. Successful verifications are cached per wallet
+/// and message for five minutes; failed ones are always asked again.
+pub struct PrivyMpcVerifier {
+    client: reqwest::Client,
+    config: Option<PrivyConfig>,
+    verified: Cache<(String, [u8; 32]), ()>,
+}
+
+impl PrivyMpcVerifier {
+    pub fn new(config: Option<PrivyConfig>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(PROVIDER_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            config,
+            verified: Cache::builder()
+                .max_capacity(VERIFICATION_CACHE_CAPACITY)
+                .time_to_live(VERIFICATION_CACHE_TTL)
+                .build(),
+        }
+    }
+
+    /// Verifier for the Privy app configured in the environment. Without one, every MPC
+    /// signature fails to verify.
+    pub fn from_env() -> Self {
+        let config = PrivyConfig::from_env();
+        if config.is_none() {
+            log::warn!("PRIVY_APP_ID or PRIVY_APP_SECRET is not set; MPC wallets cannot sign in");
+        }
+        Self::new(config)
+    }
+
+    async fn ask_privy(
+        &self,
+        config: &PrivyConfig,
+        wallet_address: &str,
+        signature: &str,
+        message: &str,
+    ) -> Result<bool, String> {
+        let url = format!("{}/api/v1/wallets/{}/verify", config.api_base, wallet_address);
+        let response = self
+            .client
+            .post(url)
+            .basic_auth(&config.app_id, Some(&config.app_secret))
+            .header("privy-app-id", &config.app_id)
+            .json(&serde_json::json!({ "signature": signature, "message": message }))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| format!("Privy verification request failed: {}", e))?;
+
+        let verification: PrivyVerification = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid Privy verification response: {}", e))?;
+        Ok(verification.valid)
+    }
+}
+
+impl MpcWalletVerifier for PrivyMpcVerifier {
+    fn verify<'a>(
+        &'a self,
+        wallet_address: &'a str,
+        signature: &'a str,
+        message: &'a str,
+    ) -> BoxFuture<'a, Result<bool, String>> {
+        Box::pin(async move {
+            let config = self.config.as_ref().ok_or("MPC wallet verification is not configured")?;
+            let key = (wallet_address.to_string(), Sha256::digest(message.as_bytes()).into());
+            if self.verified.contains_key(&key) {
+                return Ok(true);
+            }
+
+            let valid = self.ask_privy(config, wallet_address, signature, message).await?;
+            if valid {
+                self.verified.insert(key, ());
+            }
+            Ok(valid)
+        })
+    }
+}
+
+/// Answers every verification the same way without asking a provider
+#[cfg(test)]
+pub struct MockMpcVerifier {
+    pub valid: bool,
+    /// Fail as if the provider could not be reached
+    pub unreachable: bool,
+}
+
+#[cfg(test)]
+impl MockMpcVerifier {
+    pub fn new() -> Self {
+        Self { valid: true, unreachable: false }
+    }
+}
+
+#[cfg(test)]
+impl Default for MockMpcVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+impl MpcWalletVerifier for MockMpcVerifier {
+    fn verify<'a>(
+        &'a self,
+        _wallet_address: &'a str,
+        _signature: &'a str,
+        _message: &'a str,
+    ) -> BoxFuture<'a, Result<bool, String>> {
+        Box::pin(async move {
+            if self.unreachable {
+                return Err("Privy verification request failed: connection refused".to_string());
+            }
+            Ok(self.valid)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const WALLET: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+
+    /// Requests the mock Privy API answered
+    struct Requests(AtomicUsize);
+
+    /// Accepts the signature "valid" from the app "app" with secret "secret"
+    async fn verify(
+        request: HttpRequest,
+        path: web::Path<String>,
+        body: web::Json<serde_json::Value>,
+        requests: web::Data<Requests>,
+    ) -> HttpResponse {
+        requests.0.fetch_add(1, Ordering::SeqCst);
+        let authorized = request.headers().get("privy-app-id").is_some_and(|id| id == "app")
+            && request.headers().get("Authorization").is_some_and(|auth| auth == "Basic YXBwOnNlY3JldA==");
+        if !authorized {
+            return HttpResponse::Unauthorized().finish();
+        }
+        let valid = path.as_str() == WALLET && body["signature"] == "valid" && body["message"].is_string();
+        HttpResponse::Ok().json(serde_json::json!({ "valid": valid }))
+    }
+
+    fn mock_privy() -> (String, web::Data<Requests>) {
+        let requests = web::Data::new(Requests(AtomicUsize::new(0)));
+        let server_requests = requests.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(server_requests.clone())
+                .route("/api/v1/wallets/{wallet_address}/verify", web::post().to(verify))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let base = format!("http://{}", server.addrs()[0]);
+        tokio::spawn(server.run());
+        (base, requests)
+    }
+
+    fn privy(api_base: String) -> PrivyMpcVerifier {
+        PrivyMpcVerifier::new(Some(PrivyConfig { api_base, ..PrivyConfig::new("app".into(), "secret".into()) }))
+    }
+
+    #[actix_web::test]
+    async fn test_successful_verifications_are_cached() {
+        let (base, requests) = mock_privy();
+        let verifier = privy(base);
+
+        assert_eq!(verifier.verify(WALLET, "valid", "Sign in to EchoLayer: 1").await, Ok(true));
+        assert_eq!(verifier.verify(WALLET, "valid", "Sign in to EchoLayer: 1").await, Ok(true));
+        assert_eq!(requests.0.load(Ordering::SeqCst), 1);
+
+        // Rejections are not cached, and other messages are asked about
+        assert_eq!(verifier.verify(WALLET, "forged", "Sign in to EchoLayer: 2").await, Ok(false));
+        assert_eq!(verifier.verify(WALLET, "valid", "Sign in to EchoLayer: 2").await, Ok(true));
+        assert_eq!(requests.0.load(Ordering::SeqCst), 3);
+    }
+
+    #[actix_web::test]
+    async fn test_provider_failures_are_errors() {
+        let (base, _) = mock_privy();
+        let wrong_secret = PrivyMpcVerifier::new(Some(PrivyConfig {
+            api_base: base,
+            ..PrivyConfig::new("app".into(), "wrong".into())
+        }));
+        assert!(wrong_secret.verify(WALLET, "valid", "message").await.is_err());
+
+        // Nothing listens on the discard port
+        assert!(privy("http://127.0.0.1:9".to_string()).verify(WALLET, "valid", "message").await.is_err());
+        assert!(PrivyMpcVerifier::new(None).verify(WALLET, "valid", "message").await.is_err());
+    }
+}
@@ -0,0 +1,163 @@
+use arc_swap::ArcSwap;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+
+use crate::models::platform_stats::PlatformEchoStats;
+use crate::models::Platform;
+use crate::repositories::{PlatformStatsRepository, RepositoryError};
+
+/// How often platform statistics are recomputed
+pub const PLATFORM_STATS_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+#[derive(Default)]
+struct Snapshot {
+    /// Highest average Echo Index first
+    stats: Vec<PlatformEchoStats>,
+    refreshed_at: Option<DateTime<Utc>>,
+}
+
+/// When platform statistics were last recomputed and when they will be next
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RefreshSchedule {
+    /// None until the first refresh after startup
+    pub last_updated: Option<DateTime<Utc>>,
+    pub next_update: Option<DateTime<Utc>>,
+    pub refresh_interval_seconds: u64,
+}
+
+/// Echo Index statistics of every platform, recomputed periodically and served from
+/// memory
+pub struct PlatformStatsService {
+    repository: Arc<PlatformStatsRepository>,
+    snapshot: ArcSwap<Snapshot>,
+}
+
+impl PlatformStatsService {
+    pub fn new(repository: Arc<PlatformStatsRepository>) -> Self {
+        Self {
+            repository,
+            snapshot: ArcSwap::from_pointee(Snapshot::default()),
+        }
+    }
+
+    /// Statistics of every platform with recent scored content, highest average Echo
+    /// Index first
+    pub fn all(&self) -> Vec<PlatformEchoStats> {
+        self.snapshot.load().stats.clone()
+    }
+
+    pub fn for_platform(&self, platform: &Platform) -> Option<PlatformEchoStats> {
+        self.snapshot.load().stats.iter().find(|stats| stats.platform == *platform).cloned()
+    }
+
+    pub fn schedule(&self) -> RefreshSchedule {
+        let last_updated = self.snapshot.load().refreshed_at;
+        let interval = PLATFORM_STATS_REFRESH_INTERVAL.as_secs();
+        RefreshSchedule {
+            last_updated,
+            next_update: last_updated.map(|last| last + chrono::Duration::seconds(interval as i64)),
+            refresh_interval_seconds: interval,
+        }
+    }
+
+    /// Recompute the statistics. Returns how many platforms have any.
+    pub async fn refresh(&self) -> Result<usize, RepositoryError> {
+        let mut stats = self.repository.refresh().await?;
+        stats.sort_by(|a, b| b.avg_echo_index.total_cmp(&a.avg_echo_index));
+        let count = stats.len();
+        self.snapshot.store(Arc::new(Snapshot { stats, refreshed_at: Some(Utc::now()) }));
+        Ok(count)
+    }
+
+    /// Refresh the statistics now and then every refresh interval
+    pub fn spawn_refresh_task(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PLATFORM_STATS_REFRESH_INTERVAL);
+            loop {
+                interval.tick().await;
+                match self.refresh().await {
+                    Ok(platforms) => log::debug!("Refreshed Echo Index statistics of {} platforms", platforms),
+                    Err(e) => log::warn!("Platform statistics refresh failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    async fn insert_content(pool: &PgPool, author: Uuid, platform: &str, echo_index: f64) -> Uuid {
+        sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type, title, body, echo_index)
+             VALUES ($1, $2::platform_type, $3, 'text', 'Scored', 'Scored content', $4) RETURNING id",
+        )
+        .bind(author)
+        .bind(platform)
+        .bind(Uuid::new_v4().to_string())
+        .bind(echo_index)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_stats_are_recomputed_after_new_content(pool: PgPool) {
+        let author: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xstats') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        for echo_index in [0.2, 0.4] {
+            insert_content(&pool, author, "twitter", echo_index).await;
+        }
+        insert_content(&pool, author, "linkedin", 0.6).await;
+        // Unscored content is left out
+        insert_content(&pool, author, "linkedin", 0.0).await;
+
+        let service = PlatformStatsService::new(Arc::new(PlatformStatsRepository::new(pool.clone())));
+        assert_eq!(service.schedule().last_updated, None);
+        assert_eq!(service.refresh().await.unwrap(), 2);
+        let platforms: Vec<Platform> = service.all().into_iter().map(|stats| stats.platform).collect();
+        assert_eq!(platforms, vec![Platform::LinkedIn, Platform::Twitter]);
+        let twitter = service.for_platform(&Platform::Twitter).unwrap();
+        assert_eq!(twitter.content_count, 2);
+        assert!((twitter.avg_echo_index - 0.3).abs() < 1e-9);
+        assert_eq!((twitter.total_propagations, twitter.avg_propagation_depth), (0, 0.0));
+
+        let viral = insert_content(&pool, author, "twitter", 0.9).await;
+        for target in ["reshare-1", "reshare-2"] {
+            sqlx::query(
+                "INSERT INTO propagations (content_id, source_user_id, propagation_type, source_platform,
+                                           target_platform, target_external_id)
+                 VALUES ($1, $2, 'share', 'twitter', 'twitter', $3)",
+            )
+            .bind(viral)
+            .bind(author)
+            .bind(target)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        service.refresh().await.unwrap();
+
+        let twitter = service.for_platform(&Platform::Twitter).unwrap();
+        assert_eq!(twitter.content_count, 3);
+        assert!((twitter.avg_echo_index - 0.5).abs() < 1e-9);
+        assert!((twitter.median_echo_index - 0.4).abs() < 1e-9);
+        // Interpolated between the two highest scores
+        assert!((twitter.top_10_pct_threshold - 0.8).abs() < 1e-9);
+        assert_eq!(twitter.total_propagations, 2);
+        assert_eq!(twitter.avg_propagation_depth, 1.0);
+        assert_eq!(service.all()[0].platform, Platform::LinkedIn);
+
+        let schedule = service.schedule();
+        assert_eq!(schedule.refresh_interval_seconds, 3600);
+        assert_eq!(schedule.next_update.unwrap() - schedule.last_updated.unwrap(), chrono::Duration::hours(1));
+    }
+}
@@ -0,0 +1,202 @@
+use chrono::{Days, NaiveDate, Utc};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+use crate::models::reward_pool::PoolUtilizationDay;
+use crate::repositories::{RepositoryError, RewardPoolRepository};
+use crate::services::RewardService;
+
+/// Consecutive days of utilization a pool resize is judged on
+pub const UTILIZATION_WINDOW_DAYS: usize = 7;
+/// Utilization above which every day of the window grows the pool, and its growth
+const HIGH_UTILIZATION: f64 = 0.95;
+const POOL_INCREASE: f64 = 0.10;
+/// Utilization below which every day of the window shrinks the pool, and its shrinkage
+const LOW_UTILIZATION: f64 = 0.60;
+const POOL_DECREASE: f64 = -0.05;
+/// The pool is never shrunk below this many EchoDrops
+pub const DEFAULT_MIN_DAILY_POOL: f64 = 1_000.0;
+
+/// Fractional change to the daily pool after `utilization`, the utilization of consecutive
+/// days oldest first: +10% if each of the last seven was above 95%, -5% if each was below
+/// 60%, and none otherwise or with fewer than seven days.
+pub fn pool_adjustment(utilization: &[f64]) -> f64 {
+    if utilization.len() < UTILIZATION_WINDOW_DAYS {
+        return 0.0;
+    }
+    let window = &utilization[utilization.len() - UTILIZATION_WINDOW_DAYS..];
+    if window.iter().all(|&day| day > HIGH_UTILIZATION) {
+        POOL_INCREASE
+    } else if window.iter().all(|&day| day < LOW_UTILIZATION) {
+        POOL_DECREASE
+    } else {
+        0.0
+    }
+}
+
+/// Closes each UTC day of the reward pool: records how much of it was awarded, resizes the
+/// pool when it has been consistently over- or under-used, and refills it for the next day
+pub struct PoolUtilizationGovernor {
+    repository: Arc<RewardPoolRepository>,
+    rewards: Arc<RwLock<RewardService>>,
+    min_daily_pool: f64,
+}
+
+impl PoolUtilizationGovernor {
+    pub fn new(repository: Arc<RewardPoolRepository>, rewards: Arc<RwLock<RewardService>>) -> Self {
+        Self { repository, rewards, min_daily_pool: DEFAULT_MIN_DAILY_POOL }
+    }
+
+    /// Floor of the pool from `MIN_DAILY_POOL`
+    pub fn from_env(repository: Arc<RewardPoolRepository>, rewards: Arc<RwLock<RewardService>>) -> Self {
+        let min_daily_pool = std::env::var("MIN_DAILY_POOL")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|pool| pool.is_finite() && *pool >= 0.0)
+            .unwrap_or(DEFAULT_MIN_DAILY_POOL);
+        Self { min_daily_pool, ..Self::new(repository, rewards) }
+    }
+
+    /// Record the utilization of `day`, resize the pool from it and the days before, and
+    /// reset the pool for the next day. Only days since the pool was last resized count,
+    /// so each resize takes another seven days of the same utilization.
+    pub async fn close_day(&self, day: NaiveDate) -> Result<PoolUtilizationDay, RepositoryError> {
+        let before = match day.checked_sub_days(Days::new(1)) {
+            Some(yesterday) => self.repository.history(yesterday, UTILIZATION_WINDOW_DAYS as u32 - 1).await?,
+            None => Vec::new(),
+        };
+        let mut rewards = self.rewards.write().await;
+        let (pool_size, remaining, _) = rewards.get_pool_status();
+        let utilized = (pool_size - remaining).max(0.0);
+
+        // Consecutive unresized days before this one, oldest first
+        let mut utilization: Vec<f64> = before
+            .iter()
+            .zip(1..)
+            .take_while(|(recorded, age)| {
+                recorded.adjustment == 0.0 && day.checked_sub_days(Days::new(*age)) == Some(recorded.day)
+            })
+            .map(|(recorded, _)| recorded.utilization)
+            .collect();
+        utilization.reverse();
+        utilization.push(if pool_size > 0.0 { utilized / pool_size } else { 0.0 });
+
+        let mut next_pool = pool_size * (1.0 + pool_adjustment(&utilization));
+        if next_pool < pool_size {
+            next_pool = next_pool.max(self.min_daily_pool.min(pool_size));
+        }
+        let adjustment = if pool_size > 0.0 { next_pool / pool_size - 1.0 } else { 0.0 };
+
+        let record = self.repository.record(day, pool_size, utilized, adjustment).await?;
+        rewards.set_daily_pool(next_pool);
+        rewards.reset_daily_pool();
+        Ok(record)
+    }
+
+    /// Closed days among the last `days`, most recent first
+    pub async fn history(&self, days: u32) -> Result<Vec<PoolUtilizationDay>, RepositoryError> {
+        self.repository.history(Utc::now().date_naive(), days).await
+    }
+
+    /// Close each UTC day at midnight
+    pub fn spawn_daily_task(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let now = Utc::now();
+                let tomorrow = now.date_naive().succ_opt().expect("date in range");
+                let midnight = tomorrow.and_hms_opt(0, 0, 0).expect("valid time").and_utc();
+                let wait = (midnight - now).to_std().unwrap_or_default();
+                tokio::time::sleep(wait).await;
+
+                let day = tomorrow.pred_opt().expect("date in range");
+                match self.close_day(day).await {
+                    Ok(closed) if closed.adjustment != 0.0 => log::info!(
+                        "Reward pool resized by {:+.1}% after {:.1}% utilization on {}",
+                        closed.adjustment * 100.0,
+                        closed.utilization * 100.0,
+                        day
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::error!(error = %e, %day, "Failed to close the reward pool day"),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    #[test]
+    fn test_adjustment_needs_seven_days_past_threshold() {
+        assert_eq!(pool_adjustment(&[0.99; 6]), 0.0);
+        assert_eq!(pool_adjustment(&[0.99; 7]), POOL_INCREASE);
+        assert_eq!(pool_adjustment(&[0.5; 7]), POOL_DECREASE);
+        // One ordinary day in the window holds the pool
+        assert_eq!(pool_adjustment(&[0.99, 0.99, 0.99, 0.8, 0.99, 0.99, 0.99]), 0.0);
+        assert_eq!(pool_adjustment(&[0.8, 0.99, 0.99, 0.99, 0.99, 0.99, 0.99, 0.99]), POOL_INCREASE);
+        assert_eq!(pool_adjustment(&[0.95; 7]), 0.0);
+    }
+
+    /// Award `share` of the day's pool, then close the day
+    async fn close(
+        governor: &PoolUtilizationGovernor,
+        rewards: &RwLock<RewardService>,
+        day: NaiveDate,
+        share: f64,
+    ) -> f64 {
+        {
+            let mut rewards = rewards.write().await;
+            let amount = rewards.get_pool_status().0 * share;
+            rewards
+                .award_community_contribution(format!("user_{}", day), format!("content_{}", day), amount, 0.1)
+                .await
+                .unwrap();
+        }
+        governor.close_day(day).await.unwrap();
+        rewards.read().await.get_pool_status().0
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_pool_resizes_after_a_week_past_threshold(pool: PgPool) {
+        let rewards = Arc::new(RwLock::new(RewardService::new(10_000.0)));
+        let governor = PoolUtilizationGovernor::new(Arc::new(RewardPoolRepository::new(pool.clone())), rewards.clone());
+        let start = NaiveDate::from_ymd_opt(2024, 8, 1).unwrap();
+        let day = |offset: u64| start + Days::new(offset);
+
+        // Six days above 95% are not enough; the seventh grows the pool by 10%
+        for offset in 0..6 {
+            assert_eq!(close(&governor, &rewards, day(offset), 0.97).await, 10_000.0);
+        }
+        assert!((close(&governor, &rewards, day(6), 0.97).await - 11_000.0).abs() < 1e-6);
+
+        // A week below 60% of the grown pool shrinks it by 5%
+        for offset in 7..13 {
+            assert!((close(&governor, &rewards, day(offset), 0.3).await - 11_000.0).abs() < 1e-6);
+        }
+        assert!((close(&governor, &rewards, day(13), 0.3).await - 10_450.0).abs() < 1e-6);
+
+        let history = governor.repository.history(day(13), 30).await.unwrap();
+        assert_eq!(history.len(), 14);
+        assert_eq!(history[0].day, day(13));
+        assert!((history[0].adjustment - POOL_DECREASE).abs() < 1e-9);
+        assert!((history[0].utilization - 0.3).abs() < 1e-9);
+        assert!((history[7].adjustment - POOL_INCREASE).abs() < 1e-9);
+        assert!(history[1..7].iter().all(|closed| closed.adjustment == 0.0));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_pool_never_shrinks_below_minimum(pool: PgPool) {
+        let rewards = Arc::new(RwLock::new(RewardService::new(1_020.0)));
+        let governor = PoolUtilizationGovernor::new(Arc::new(RewardPoolRepository::new(pool.clone())), rewards.clone());
+        let start = NaiveDate::from_ymd_opt(2024, 8, 1).unwrap();
+
+        for offset in 0..7 {
+            governor.close_day(start + Days::new(offset)).await.unwrap();
+        }
+        assert_eq!(rewards.read().await.get_pool_status().0, DEFAULT_MIN_DAILY_POOL);
+    }
+}
@@ -1,7 +1,32 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use chrono::{DateTime, Utc};
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-#[derive(Debug, Clone)]
+use crate::repositories::{PropagationRepository, RepositoryError};
+use crate::services::loop_strength::{EchoLoopNormalizer, LoopStrength};
+use crate::services::propagation_weights::{PropagationWeightNormalizer, WeightNormalizationStrategy};
+use crate::services::{EngineConfigStore, UserFollowerGraph};
+
+/// How long an untouched Echo Loop stays cached once it has been persisted
+const DEFAULT_CACHE_TTL_MINUTES: i64 = 15;
+/// Stale loops weaker than this are dropped by `cleanup_expired_loops`
+const MIN_LOOP_STRENGTH: f64 = 0.1;
+/// Added to the interaction strength of a propagation from a user to one of their followers
+pub const FOLLOWER_INTERACTION_BONUS: f64 = 0.1;
+/// Nodes a propagation path may hold beyond its source
+pub const DEFAULT_MAX_LOOP_DEPTH: usize = 10;
+/// Total resonance above which a loop's paths are amplified
+pub const DEFAULT_RESONANCE_THRESHOLD: f64 = 0.3;
+/// Share of path resonance kept per day
+pub const DEFAULT_DECAY_FACTOR: f64 = 0.9;
+/// Largest factor a resonating loop's paths are amplified by at once
+pub const DEFAULT_MAX_AMPLIFICATION_FACTOR: f64 = 1.3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PropagationNode {
     pub id: String,
     pub node_type: NodeType,
@@ -11,11 +36,25 @@ pub struct PropagationNode {
     pub timestamp: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum NodeType {
     User,
     Content,
     Platform,
+    /// A user mentioned in content, linked to its author without a share
+    Mention,
+}
+
+impl NodeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeType::User => "user",
+            NodeType::Content => "content",
+            NodeType::Platform => "platform",
+            NodeType::Mention => "mention",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -32,88 +71,388 @@ pub struct EchoLoop {
     pub source_content_id: String,
     pub propagation_paths: Vec<PropagationPath>,
     pub total_resonance: f64,
-    pub loop_strength: f64,
+    /// Strength in [0, 1], on a scale that grows with the size of the loop's network
+    pub raw_loop_strength: f64,
+    /// Raw strength rescaled by network size, comparable across content
+    pub normalized_loop_strength: f64,
     pub created_at: DateTime<Utc>,
     pub last_updated: DateTime<Utc>,
 }
 
+/// Metadata about a circular propagation detected within an Echo Loop
+#[derive(Debug, Clone, Serialize)]
+pub struct CycleReport {
+    pub loop_id: String,
+    pub cycle_nodes: Vec<String>,
+    pub detection_count: u32,
+    pub first_detected_at: DateTime<Utc>,
+    pub last_detected_at: DateTime<Utc>,
+}
+
+/// A resonance amplification of an Echo Loop's paths, kept so boosted Echo Indexes can be
+/// investigated and the boost undone
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct AmplificationEvent {
+    pub id: Uuid,
+    pub loop_id: String,
+    pub content_id: String,
+    /// Total resonance of the loop that set off the amplification
+    pub trigger_resonance: f64,
+    pub amplification_factor: f64,
+    /// The loop's first paths, which the amplification applied to
+    pub affected_path_count: i32,
+    /// Summed weight of the affected paths
+    pub total_weight_before: f64,
+    pub total_weight_after: f64,
+    pub created_at: DateTime<Utc>,
+    /// When an operator undid the amplification
+    pub reverted_at: Option<DateTime<Utc>>,
+}
+
+/// Why a propagation event was not added to an Echo Loop
+#[derive(Debug, thiserror::Error)]
+pub enum PropagationEventError {
+    /// The event leads back into the path it extends
+    #[error("Circular propagation detected: node {node_id} already in path")]
+    Cycle { node_id: String },
+    /// The event would make its path longer than the configured maximum depth
+    #[error("Maximum propagation depth exceeded")]
+    TooDeep,
+    /// The loop could not be loaded or saved
+    #[error("{0}")]
+    Loop(String),
+}
+
+/// Tracks Echo Loops. Shared between workers without an outer lock: each loop is
+/// updated under its map shard's lock, which is never held across an `.await`.
 pub struct PropagationService {
-    active_loops: HashMap<String, EchoLoop>,
+    /// Recently used loops; the repository, when configured, is the source of truth
+    active_loops: DashMap<String, EchoLoop>,
+    cycle_reports: DashMap<String, CycleReport>,
+    repository: Option<Arc<PropagationRepository>>,
+    /// Source of the weight normalization strategy; the default one without it
+    engine_config: Option<Arc<EngineConfigStore>>,
+    /// Without it, propagations to followers earn no bonus
+    follower_graph: Option<Arc<UserFollowerGraph>>,
+    cache_ttl: chrono::Duration,
     max_loop_depth: usize,
     resonance_threshold: f64,
+    /// Amplifications above this factor are capped to it
+    max_amplification_factor: f64,
+    /// Daily decay of path resonance, unless overridden for the content
     decay_factor: f64,
+    /// Per-content decay factors set by admins, keyed by content ID
+    content_decay_factors: DashMap<String, f64>,
+}
+
+impl Default for PropagationService {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PropagationService {
+    /// In-memory service; loops are lost on restart
     pub fn new() -> Self {
         Self {
-            active_loops: HashMap::new(),
-            max_loop_depth: 10,
-            resonance_threshold: 0.3,
-            decay_factor: 0.9,
+            active_loops: DashMap::new(),
+            cycle_reports: DashMap::new(),
+            repository: None,
+            engine_config: None,
+            follower_graph: None,
+            cache_ttl: chrono::Duration::minutes(DEFAULT_CACHE_TTL_MINUTES),
+            max_loop_depth: DEFAULT_MAX_LOOP_DEPTH,
+            resonance_threshold: DEFAULT_RESONANCE_THRESHOLD,
+            max_amplification_factor: DEFAULT_MAX_AMPLIFICATION_FACTOR,
+            decay_factor: DEFAULT_DECAY_FACTOR,
+            content_decay_factors: DashMap::new(),
+        }
+    }
+
+    /// Service persisting loops through `repository` and caching them briefly in memory
+    pub fn with_repository(repository: Arc<PropagationRepository>) -> Self {
+        Self {
+            repository: Some(repository),
+            ..Self::new()
         }
     }
 
+    /// Normalize propagation weights with the strategy of the live engine config
+    pub fn with_engine_config(mut self, engine_config: Arc<EngineConfigStore>) -> Self {
+        self.engine_config = Some(engine_config);
+        self
+    }
+
+    /// Reject propagations deeper than `max_depth`, amplify loops resonating above
+    /// `resonance_threshold` and decay resonance by `decay_factor` a day
+    pub fn configure(mut self, max_depth: usize, resonance_threshold: f64, decay_factor: f64) -> Self {
+        self.max_loop_depth = max_depth;
+        self.resonance_threshold = resonance_threshold;
+        self.decay_factor = decay_factor;
+        self
+    }
+
+    /// Amplify resonating loops by at most `factor` at once
+    pub fn with_max_amplification_factor(mut self, factor: f64) -> Self {
+        self.max_amplification_factor = factor;
+        self
+    }
+
+    /// Strengthen propagations from users to their followers
+    pub fn with_follower_graph(mut self, follower_graph: Arc<UserFollowerGraph>) -> Self {
+        self.follower_graph = Some(follower_graph);
+        self
+    }
+
+    /// Decay path resonance of the content by `factor` per day instead of the default, from
+    /// its next propagation event on. Lower factors fade faster.
+    pub fn set_decay_factor_for_content(&self, content_id: &str, factor: f64) -> Result<(), String> {
+        if !(factor > 0.0 && factor <= 1.0) {
+            return Err("decay_factor must be greater than 0 and at most 1".to_string());
+        }
+        self.content_decay_factors.insert(content_id.to_string(), factor);
+        Ok(())
+    }
+
+    /// Decay path resonance of the content by the default factor again
+    pub fn reset_decay_factor_for_content(&self, content_id: &str) {
+        self.content_decay_factors.remove(content_id);
+    }
+
+    /// Daily decay factor in force for the content
+    pub fn decay_factor_for(&self, content_id: &str) -> f64 {
+        self.content_decay_factors.get(content_id).map_or(self.decay_factor, |factor| *factor)
+    }
+
     /// Initialize a new Echo Loop for content
-    pub fn create_echo_loop(&mut self, content_id: String) -> String {
+    pub async fn create_echo_loop(&self, content_id: String) -> Result<String, String> {
         let loop_id = format!("loop_{}", uuid::Uuid::new_v4());
         let echo_loop = EchoLoop {
             id: loop_id.clone(),
             source_content_id: content_id,
             propagation_paths: Vec::new(),
             total_resonance: 0.0,
-            loop_strength: 0.0,
+            raw_loop_strength: 0.0,
+            normalized_loop_strength: 0.0,
             created_at: Utc::now(),
             last_updated: Utc::now(),
         };
 
+        if let Some(repository) = &self.repository {
+            repository.save_loop(&echo_loop).await.map_err(|e| e.to_string())?;
+        }
+
         self.active_loops.insert(loop_id.clone(), echo_loop);
-        loop_id
+        Ok(loop_id)
     }
 
     /// Add a propagation event to an existing Echo Loop
-    pub fn add_propagation_event(
-        &mut self,
+    #[tracing::instrument(skip(self, from_node, to_node), fields(from = %from_node.id, to = %to_node.id), err)]
+    pub async fn add_propagation_event(
+        &self,
         loop_id: &str,
         from_node: PropagationNode,
         to_node: PropagationNode,
         interaction_strength: f64,
-    ) -> Result<(), String> {
-        let echo_loop = self.active_loops.get_mut(loop_id)
-            .ok_or_else(|| "Echo Loop not found".to_string())?;
+    ) -> Result<(), PropagationEventError> {
+        self.ensure_loaded(loop_id).await.map_err(PropagationEventError::Loop)?;
 
         // Calculate propagation weight
+        let interaction_strength = interaction_strength + self
+            .follower_bonus(&from_node, &to_node)
+            .await
+            .map_err(PropagationEventError::Loop)?;
         let propagation_weight = self.calculate_propagation_weight(&from_node, &to_node, interaction_strength);
-        
-        // Create or update propagation path
-        let mut path_updated = false;
-        for path in &mut echo_loop.propagation_paths {
-            if let Some(last_node) = path.nodes.last() {
-                if last_node.id == from_node.id {
-                    path.nodes.push(to_node.clone());
-                    path.total_weight += propagation_weight;
-                    path_updated = true;
-                    break;
-                }
-            }
+
+        let mut echo_loop = self.active_loops.get_mut(loop_id)
+            .ok_or_else(|| PropagationEventError::Loop("Echo Loop not found".to_string()))?;
+
+        // Find the path this event extends, if any
+        let extended_path = echo_loop.propagation_paths
+            .iter()
+            .position(|path| path.nodes.last().is_some_and(|last| last.id == from_node.id));
+
+        // Refuse to extend a path back onto itself
+        let cycle = match extended_path {
+            Some(index) => Self::detect_cycle(&echo_loop.propagation_paths[index], &from_node.id, &to_node.id),
+            None if from_node.id == to_node.id => Some(vec![to_node.id.clone()]),
+            None => None,
+        };
+        if let Some(cycle_nodes) = cycle {
+            drop(echo_loop);
+            self.record_cycle(loop_id, cycle_nodes);
+            return Err(PropagationEventError::Cycle { node_id: to_node.id });
         }
 
-        if !path_updated {
+        // Hops from the path's source to `to_node`; chains this deep are often bot rings
+        let depth = extended_path.map_or(1, |index| echo_loop.propagation_paths[index].nodes.len());
+        if depth >= self.max_loop_depth {
+            drop(echo_loop);
+            tracing::warn!(loop_id, to = %to_node.id, depth, max_depth = self.max_loop_depth, "Propagation too deep");
+            return Err(PropagationEventError::TooDeep);
+        }
+
+        if let Some(index) = extended_path {
+            let path = &mut echo_loop.propagation_paths[index];
+            path.nodes.push(to_node);
+            path.total_weight += propagation_weight;
+        } else {
             let new_path = PropagationPath {
                 nodes: vec![from_node, to_node],
                 total_weight: propagation_weight,
                 resonance_factor: 0.0,
-                decay_rate: self.decay_factor,
+                decay_rate: self.decay_factor_for(&echo_loop.source_content_id),
             };
             echo_loop.propagation_paths.push(new_path);
         }
 
-        echo_loop.last_updated = Utc::now();
-        self.update_echo_loop_metrics(loop_id)?;
+        // Strictly increasing at the database's microsecond precision, so the repository
+        // can tell which of two concurrently saved snapshots is newer
+        echo_loop.last_updated = Utc::now().max(echo_loop.last_updated + chrono::Duration::microseconds(1));
+        let amplification = self.update_echo_loop_metrics(&mut echo_loop);
+
+        // Resonance is recomputed for every path, so the whole loop is written back
+        let snapshot = self.repository.as_ref().map(|_| echo_loop.clone());
+        drop(echo_loop);
+        if let (Some(repository), Some(snapshot)) = (&self.repository, snapshot) {
+            repository.save_loop(&snapshot).await.map_err(|e| PropagationEventError::Loop(e.to_string()))?;
+            if let Some(amplification) = amplification {
+                repository
+                    .record_amplification(&amplification)
+                    .await
+                    .map_err(|e| PropagationEventError::Loop(e.to_string()))?;
+            }
+        }
 
         Ok(())
     }
 
+    /// `FOLLOWER_INTERACTION_BONUS` if `to_node` is a user following the user `from_node`
+    async fn follower_bonus(&self, from_node: &PropagationNode, to_node: &PropagationNode) -> Result<f64, String> {
+        let Some(follower_graph) = &self.follower_graph else {
+            return Ok(0.0);
+        };
+        let (Ok(from_user), Ok(to_user)) = (Uuid::parse_str(&from_node.id), Uuid::parse_str(&to_node.id)) else {
+            return Ok(0.0);
+        };
+        if !matches!(from_node.node_type, NodeType::User) {
+            return Ok(0.0);
+        }
+
+        let follows = follower_graph.follows(to_user, from_user).await.map_err(|e| e.to_string())?;
+        Ok(if follows { FOLLOWER_INTERACTION_BONUS } else { 0.0 })
+    }
+
+    /// Bring a loop evicted from the cache back from the repository
+    async fn ensure_loaded(&self, loop_id: &str) -> Result<(), String> {
+        if self.active_loops.contains_key(loop_id) {
+            return Ok(());
+        }
+
+        let repository = self.repository.as_ref().ok_or_else(|| "Echo Loop not found".to_string())?;
+        let echo_loop = repository.load_loop(loop_id).await.map_err(|e| match e {
+            RepositoryError::NotFound => "Echo Loop not found".to_string(),
+            e => e.to_string(),
+        })?;
+
+        // Another worker may have loaded and updated the loop in the meantime
+        self.active_loops.entry(loop_id.to_string()).or_insert(echo_loop);
+        Ok(())
+    }
+
+    /// Refresh the cached loops of a content piece from the repository
+    pub async fn load_content_echo_loops(&self, content_id: &str) -> Result<(), String> {
+        let Some(repository) = &self.repository else {
+            return Ok(());
+        };
+
+        let loops = repository.list_loops_for_content(content_id).await.map_err(|e| e.to_string())?;
+        for echo_loop in loops {
+            // Keep cached loops updated since the listing was read
+            match self.active_loops.entry(echo_loop.id.clone()) {
+                Entry::Occupied(mut cached) if cached.get().last_updated < echo_loop.last_updated => {
+                    cached.insert(echo_loop);
+                }
+                Entry::Occupied(_) => {}
+                Entry::Vacant(vacant) => {
+                    vacant.insert(echo_loop);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Echo Loop the propagations of a content piece are added to, created with its first
+    pub async fn content_echo_loop(&self, content_id: &str) -> Result<String, String> {
+        self.load_content_echo_loops(content_id).await?;
+
+        match self.get_content_echo_loops(content_id).first() {
+            Some(echo_loop) => Ok(echo_loop.id.clone()),
+            None => self.create_echo_loop(content_id.to_string()).await,
+        }
+    }
+
+    /// Detect whether adding the edge `from_node_id -> to_node_id` to a path closes a cycle.
+    /// Walks the path depth-first from `to_node_id`; reaching `from_node_id` means the new
+    /// edge leads back into the path. Returns the cycle participants in traversal order.
+    pub fn detect_cycle(
+        path: &PropagationPath,
+        from_node_id: &str,
+        to_node_id: &str,
+    ) -> Option<Vec<String>> {
+        if from_node_id == to_node_id {
+            return Some(vec![to_node_id.to_string()]);
+        }
+
+        let mut adjacency: HashMap<&str, Vec<&str>> = HashMap::new();
+        for pair in path.nodes.windows(2) {
+            adjacency.entry(pair[0].id.as_str()).or_default().push(pair[1].id.as_str());
+        }
+
+        let mut visited = HashSet::new();
+        let mut stack = vec![(to_node_id, vec![to_node_id])];
+        visited.insert(to_node_id);
+
+        while let Some((node_id, trail)) = stack.pop() {
+            if node_id == from_node_id {
+                return Some(trail.into_iter().map(String::from).collect());
+            }
+
+            for &next in adjacency.get(node_id).into_iter().flatten() {
+                if visited.insert(next) {
+                    let mut next_trail = trail.clone();
+                    next_trail.push(next);
+                    stack.push((next, next_trail));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Record a detected cycle for analytics
+    fn record_cycle(&self, loop_id: &str, cycle_nodes: Vec<String>) {
+        let now = Utc::now();
+        let mut report = self.cycle_reports
+            .entry(loop_id.to_string())
+            .or_insert_with(|| CycleReport {
+                loop_id: loop_id.to_string(),
+                cycle_nodes: Vec::new(),
+                detection_count: 0,
+                first_detected_at: now,
+                last_detected_at: now,
+            });
+
+        report.cycle_nodes = cycle_nodes;
+        report.detection_count += 1;
+        report.last_detected_at = now;
+    }
+
+    /// Get metadata about circular propagation detected in an Echo Loop
+    pub fn get_cycle_report(&self, loop_id: &str) -> Option<CycleReport> {
+        self.cycle_reports.get(loop_id).map(|report| report.clone())
+    }
+
     /// Calculate propagation weight between two nodes
     fn calculate_propagation_weight(
         &self,
@@ -129,33 +468,35 @@ impl PropagationService {
         (influence_factor + reach_factor + engagement_factor + target_receptivity) * interaction_strength
     }
 
-    /// Update Echo Loop metrics and detect resonance
-    fn update_echo_loop_metrics(&mut self, loop_id: &str) -> Result<(), String> {
-        let echo_loop = self.active_loops.get_mut(loop_id)
-            .ok_or_else(|| "Echo Loop not found".to_string())?;
+    /// Update Echo Loop metrics and detect resonance, returning the amplification applied
+    fn update_echo_loop_metrics(&self, echo_loop: &mut EchoLoop) -> Option<AmplificationEvent> {
+        // Calculate total resonance over normalized weights, keeping the raw ones stored
+        let strategy = self
+            .engine_config
+            .as_ref()
+            .map_or_else(WeightNormalizationStrategy::default, |config| config.load().config.weight_normalization);
+        let mut normalized = echo_loop.propagation_paths.clone();
+        PropagationWeightNormalizer::new(strategy).normalize(&mut normalized);
 
-        // Calculate total resonance
+        let decay_factor = self.decay_factor_for(&echo_loop.source_content_id);
         let mut total_resonance = 0.0;
-        for path in &mut echo_loop.propagation_paths {
-            path.resonance_factor = self.calculate_path_resonance(path);
+        for (path, normalized) in echo_loop.propagation_paths.iter_mut().zip(&normalized) {
+            path.resonance_factor = self.calculate_path_resonance(normalized, decay_factor);
             total_resonance += path.resonance_factor;
         }
 
         echo_loop.total_resonance = total_resonance;
 
         // Calculate loop strength based on path convergence and resonance
-        echo_loop.loop_strength = self.calculate_loop_strength(echo_loop);
+        echo_loop.raw_loop_strength = self.calculate_loop_strength(echo_loop);
+        echo_loop.normalized_loop_strength = EchoLoopNormalizer::normalize_by_network_size(echo_loop);
 
         // Check for resonance amplification
-        if echo_loop.total_resonance > self.resonance_threshold {
-            self.apply_resonance_amplification(echo_loop);
-        }
-
-        Ok(())
+        (echo_loop.total_resonance > self.resonance_threshold).then(|| self.apply_resonance_amplification(echo_loop))
     }
 
-    /// Calculate resonance factor for a propagation path
-    fn calculate_path_resonance(&self, path: &PropagationPath) -> f64 {
+    /// Calculate resonance factor for a propagation path, decaying by `decay_factor` a day
+    fn calculate_path_resonance(&self, path: &PropagationPath, decay_factor: f64) -> f64 {
         if path.nodes.len() < 2 {
             return 0.0;
         }
@@ -173,7 +514,7 @@ impl PropagationService {
 
             // Apply temporal decay
             let time_diff = (Utc::now() - current_node.timestamp).num_hours() as f64;
-            let decay = self.decay_factor.powf(time_diff / 24.0);
+            let decay = decay_factor.powf(time_diff / 24.0);
             
             resonance += compatibility * decay;
         }
@@ -242,55 +583,286 @@ impl PropagationService {
         }
     }
 
-    /// Apply resonance amplification when threshold is exceeded
-    fn apply_resonance_amplification(&mut self, echo_loop: &mut EchoLoop) {
-        let amplification_factor = 1.0 + (echo_loop.total_resonance - self.resonance_threshold) * 0.5;
-        
+    /// Apply resonance amplification when threshold is exceeded, capped at the maximum
+    /// amplification factor
+    fn apply_resonance_amplification(&self, echo_loop: &mut EchoLoop) -> AmplificationEvent {
+        let mut amplification_factor = 1.0 + (echo_loop.total_resonance - self.resonance_threshold) * 0.5;
+        if amplification_factor > self.max_amplification_factor {
+            tracing::warn!(
+                loop_id = %echo_loop.id,
+                amplification_factor,
+                max_amplification_factor = self.max_amplification_factor,
+                "Amplification capped"
+            );
+            amplification_factor = self.max_amplification_factor;
+        }
+
+        let trigger_resonance = echo_loop.total_resonance;
+        let total_weight_before: f64 = echo_loop.propagation_paths.iter().map(|path| path.total_weight).sum();
         for path in &mut echo_loop.propagation_paths {
             path.total_weight *= amplification_factor;
-            path.resonance_factor *= amplification_factor.min(1.5);
+            path.resonance_factor *= amplification_factor;
         }
+        echo_loop.total_resonance *= amplification_factor;
 
-        echo_loop.total_resonance *= amplification_factor.min(1.3);
+        AmplificationEvent {
+            id: Uuid::new_v4(),
+            loop_id: echo_loop.id.clone(),
+            content_id: echo_loop.source_content_id.clone(),
+            trigger_resonance,
+            amplification_factor,
+            affected_path_count: echo_loop.propagation_paths.len() as i32,
+            total_weight_before,
+            total_weight_after: echo_loop.propagation_paths.iter().map(|path| path.total_weight).sum(),
+            created_at: echo_loop.last_updated,
+            reverted_at: None,
+        }
     }
 
-    /// Get active Echo Loops for a content piece
-    pub fn get_content_echo_loops(&self, content_id: &str) -> Vec<&EchoLoop> {
+    /// Undo a recorded amplification: the paths it applied to get their weights scaled
+    /// back by its before/after ratio. Resonance is recomputed on the loop's next event.
+    pub async fn revert_amplification(&self, event_id: Uuid) -> Result<AmplificationEvent, RepositoryError> {
+        let repository = self.repository.as_ref().ok_or(RepositoryError::NotFound)?;
+        let event = repository.get_amplification(event_id).await?;
+        if event.reverted_at.is_some() {
+            return Err(RepositoryError::Conflict("Amplification already reverted".to_string()));
+        }
+        if !self.active_loops.contains_key(&event.loop_id) {
+            let echo_loop = repository.load_loop(&event.loop_id).await?;
+            self.active_loops.entry(event.loop_id.clone()).or_insert(echo_loop);
+        }
+
+        // Claimed first, so concurrent reverts scale the weights back only once
+        let event = repository.mark_amplification_reverted(event_id, Utc::now()).await?;
+        let snapshot = {
+            let mut echo_loop = self.active_loops.get_mut(&event.loop_id).ok_or(RepositoryError::NotFound)?;
+            if event.total_weight_after > 0.0 {
+                let ratio = event.total_weight_before / event.total_weight_after;
+                for path in echo_loop.propagation_paths.iter_mut().take(event.affected_path_count.max(0) as usize) {
+                    path.total_weight *= ratio;
+                }
+            }
+            echo_loop.last_updated = Utc::now().max(echo_loop.last_updated + chrono::Duration::microseconds(1));
+            echo_loop.clone()
+        };
+        repository.save_loop(&snapshot).await?;
+
+        Ok(event)
+    }
+
+    /// Number of Echo Loops currently held in memory
+    pub fn active_loop_count(&self) -> usize {
+        self.active_loops.len()
+    }
+
+    /// Snapshots of the active Echo Loops for a content piece
+    pub fn get_content_echo_loops(&self, content_id: &str) -> Vec<EchoLoop> {
         self.active_loops
-            .values()
+            .iter()
             .filter(|loop_| loop_.source_content_id == content_id)
+            .map(|loop_| loop_.clone())
+            .collect()
+    }
+
+    /// Strength of each active Echo Loop of a content piece, ranked against the active
+    /// loops of all content
+    pub fn content_loop_strengths(&self, content_id: &str) -> Vec<LoopStrength> {
+        let strengths: Vec<f64> = self.active_loops.iter().map(|loop_| loop_.normalized_loop_strength).collect();
+        let mut loops = self.get_content_echo_loops(content_id);
+        loops.sort_by_key(|echo_loop| echo_loop.created_at);
+        loops
+            .into_iter()
+            .map(|echo_loop| LoopStrength {
+                loop_strength_percentile: EchoLoopNormalizer::percentile(
+                    echo_loop.normalized_loop_strength,
+                    &strengths,
+                ),
+                loop_id: echo_loop.id,
+                raw_loop_strength: echo_loop.raw_loop_strength,
+                normalized_loop_strength: echo_loop.normalized_loop_strength,
+            })
             .collect()
     }
 
-    /// Clean up expired Echo Loops
-    pub fn cleanup_expired_loops(&mut self, max_age_hours: i64) {
+    /// Mean normalized strength of a content piece's active Echo Loops, 0.0 without any
+    pub fn mean_loop_strength(&self, content_id: &str) -> f64 {
+        let loops = self.get_content_echo_loops(content_id);
+        if loops.is_empty() {
+            return 0.0;
+        }
+        loops.iter().map(|echo_loop| echo_loop.normalized_loop_strength).sum::<f64>() / loops.len() as f64
+    }
+
+    /// Collect the deduplicated nodes and per-hop edges of all Echo Loops for a content piece
+    fn collect_graph(&self, content_id: &str) -> (Vec<PropagationNode>, Vec<GraphEdge>) {
+        let mut loops = self.get_content_echo_loops(content_id);
+        loops.sort_by_key(|echo_loop| echo_loop.created_at);
+
+        let mut seen = HashSet::new();
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+
+        for echo_loop in &loops {
+            for path in &echo_loop.propagation_paths {
+                for node in &path.nodes {
+                    if seen.insert(node.id.as_str()) {
+                        nodes.push(node.clone());
+                    }
+                }
+
+                // Paths only track an aggregate weight, so spread it evenly over the hops
+                let hops = path.nodes.len().saturating_sub(1).max(1) as f64;
+                for pair in path.nodes.windows(2) {
+                    edges.push(GraphEdge {
+                        source: pair[0].id.clone(),
+                        target: pair[1].id.clone(),
+                        weight: path.total_weight / hops,
+                        resonance_factor: path.resonance_factor,
+                    });
+                }
+            }
+        }
+
+        (nodes, edges)
+    }
+
+    /// Export the propagation graph of a content piece as a GraphML document
+    pub fn export_graphml(&self, content_id: &str) -> String {
+        let (nodes, edges) = self.collect_graph(content_id);
+        let mut xml = String::new();
+
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        xml.push_str("  <key id=\"node_type\" for=\"node\" attr.name=\"node_type\" attr.type=\"string\"/>\n");
+        xml.push_str("  <key id=\"influence_weight\" for=\"node\" attr.name=\"influence_weight\" attr.type=\"double\"/>\n");
+        xml.push_str("  <key id=\"reach\" for=\"node\" attr.name=\"reach\" attr.type=\"long\"/>\n");
+        xml.push_str("  <key id=\"engagement_rate\" for=\"node\" attr.name=\"engagement_rate\" attr.type=\"double\"/>\n");
+        xml.push_str("  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>\n");
+        xml.push_str("  <key id=\"resonance_factor\" for=\"edge\" attr.name=\"resonance_factor\" attr.type=\"double\"/>\n");
+        xml.push_str(&format!(
+            "  <graph id=\"{}\" edgedefault=\"directed\">\n",
+            xml_escape(content_id)
+        ));
+
+        for node in nodes {
+            xml.push_str(&format!("    <node id=\"{}\">\n", xml_escape(&node.id)));
+            xml.push_str(&format!("      <data key=\"node_type\">{}</data>\n", node.node_type.as_str()));
+            xml.push_str(&format!("      <data key=\"influence_weight\">{}</data>\n", node.influence_weight));
+            xml.push_str(&format!("      <data key=\"reach\">{}</data>\n", node.reach));
+            xml.push_str(&format!("      <data key=\"engagement_rate\">{}</data>\n", node.engagement_rate));
+            xml.push_str("    </node>\n");
+        }
+
+        for (index, edge) in edges.iter().enumerate() {
+            xml.push_str(&format!(
+                "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n",
+                index,
+                xml_escape(&edge.source),
+                xml_escape(&edge.target)
+            ));
+            xml.push_str(&format!("      <data key=\"weight\">{}</data>\n", edge.weight));
+            xml.push_str(&format!("      <data key=\"resonance_factor\">{}</data>\n", edge.resonance_factor));
+            xml.push_str("    </edge>\n");
+        }
+
+        xml.push_str("  </graph>\n");
+        xml.push_str("</graphml>\n");
+        xml
+    }
+
+    /// Export the propagation graph of a content piece in Graphviz DOT notation
+    pub fn export_dot(&self, content_id: &str) -> String {
+        let (nodes, edges) = self.collect_graph(content_id);
+        let mut dot = format!("digraph \"{}\" {{\n", dot_escape(content_id));
+
+        for node in nodes {
+            dot.push_str(&format!(
+                "  \"{}\" [node_type=\"{}\", influence_weight={}, reach={}, engagement_rate={}];\n",
+                dot_escape(&node.id),
+                node.node_type.as_str(),
+                node.influence_weight,
+                node.reach,
+                node.engagement_rate
+            ));
+        }
+
+        for edge in &edges {
+            dot.push_str(&format!(
+                "  \"{}\" -> \"{}\" [weight={}, resonance_factor={}];\n",
+                dot_escape(&edge.source),
+                dot_escape(&edge.target),
+                edge.weight,
+                edge.resonance_factor
+            ));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Clean up expired Echo Loops, returning how many were deleted.
+    /// With a repository, stale and weak loops are deleted from the database and the
+    /// cache only keeps loops touched within its TTL.
+    pub async fn cleanup_expired_loops(&self, max_age_hours: i64) -> Result<u64, String> {
         let cutoff_time = Utc::now() - chrono::Duration::hours(max_age_hours);
-        
-        self.active_loops.retain(|_, echo_loop| {
-            echo_loop.last_updated > cutoff_time && echo_loop.loop_strength > 0.1
-        });
+
+        let Some(repository) = &self.repository else {
+            let before = self.active_loops.len();
+            self.active_loops.retain(|_, echo_loop| {
+                echo_loop.last_updated > cutoff_time && echo_loop.raw_loop_strength > MIN_LOOP_STRENGTH
+            });
+            return Ok((before - self.active_loops.len()) as u64);
+        };
+
+        let deleted = repository
+            .delete_expired_loops(cutoff_time, MIN_LOOP_STRENGTH)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let cache_cutoff = Utc::now() - self.cache_ttl;
+        self.active_loops.retain(|_, echo_loop| echo_loop.last_updated > cache_cutoff);
+
+        Ok(deleted)
+    }
+
+    /// Write every cached loop back to the repository, returning how many were saved.
+    /// Each loop is already saved as it changes, so this only matters for saves a
+    /// shutdown interrupted; the repository keeps whichever copy is newer.
+    pub async fn checkpoint(&self) -> Result<usize, String> {
+        let Some(repository) = &self.repository else {
+            return Ok(0);
+        };
+
+        let snapshots: Vec<EchoLoop> = self.active_loops.iter().map(|entry| entry.value().clone()).collect();
+        for echo_loop in &snapshots {
+            repository.save_loop(echo_loop).await.map_err(|e| e.to_string())?;
+        }
+
+        Ok(snapshots.len())
     }
 
     /// Get propagation analytics for a time period
     pub fn get_propagation_analytics(&self, since: DateTime<Utc>) -> PropagationAnalytics {
-        let relevant_loops: Vec<&EchoLoop> = self.active_loops
-            .values()
-            .filter(|loop_| loop_.created_at >= since)
-            .collect();
+        // One pass, holding each shard's read lock only while it is visited
+        let mut total_loops = 0;
+        let mut total_strength = 0.0;
+        let mut total_paths = 0;
+        let mut high_resonance_loops = 0;
+        for echo_loop in self.active_loops.iter().filter(|loop_| loop_.created_at >= since) {
+            total_loops += 1;
+            total_strength += echo_loop.normalized_loop_strength;
+            total_paths += echo_loop.propagation_paths.len();
+            if echo_loop.total_resonance > self.resonance_threshold {
+                high_resonance_loops += 1;
+            }
+        }
 
-        let total_loops = relevant_loops.len();
         let avg_loop_strength = if total_loops > 0 {
-            relevant_loops.iter().map(|l| l.loop_strength).sum::<f64>() / total_loops as f64
+            total_strength / total_loops as f64
         } else {
             0.0
         };
 
-        let total_paths: usize = relevant_loops.iter().map(|l| l.propagation_paths.len()).sum();
-        let high_resonance_loops = relevant_loops
-            .iter()
-            .filter(|l| l.total_resonance > self.resonance_threshold)
-            .count();
-
         PropagationAnalytics {
             total_loops,
             avg_loop_strength,
@@ -301,11 +873,314 @@ impl PropagationService {
     }
 }
 
+/// A single hop in an exported propagation graph
+struct GraphEdge {
+    source: String,
+    target: String,
+    weight: f64,
+    resonance_factor: f64,
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 #[derive(Debug)]
 pub struct PropagationAnalytics {
     pub total_loops: usize,
+    /// Mean normalized loop strength
     pub avg_loop_strength: f64,
     pub total_propagation_paths: usize,
     pub high_resonance_loops: usize,
     pub resonance_threshold: f64,
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_node(id: &str) -> PropagationNode {
+        PropagationNode {
+            id: id.to_string(),
+            node_type: NodeType::User,
+            influence_weight: 0.5,
+            reach: 100,
+            engagement_rate: 0.1,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_self_loop_rejected() {
+        let service = PropagationService::new();
+        let loop_id = service.create_echo_loop("content_1".to_string()).await.unwrap();
+
+        let result = service.add_propagation_event(&loop_id, user_node("a"), user_node("a"), 1.0).await;
+        assert!(result.unwrap_err().to_string().contains("node a already in path"));
+
+        let report = service.get_cycle_report(&loop_id).unwrap();
+        assert_eq!(report.cycle_nodes, vec!["a".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_two_node_ping_pong_rejected() {
+        let service = PropagationService::new();
+        let loop_id = service.create_echo_loop("content_1".to_string()).await.unwrap();
+
+        service.add_propagation_event(&loop_id, user_node("a"), user_node("b"), 1.0).await.unwrap();
+        assert!(service.add_propagation_event(&loop_id, user_node("b"), user_node("a"), 1.0).await.is_err());
+
+        let report = service.get_cycle_report(&loop_id).unwrap();
+        assert_eq!(report.cycle_nodes, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(report.detection_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_multi_hop_cycle_rejected() {
+        let service = PropagationService::new();
+        let loop_id = service.create_echo_loop("content_1".to_string()).await.unwrap();
+
+        service.add_propagation_event(&loop_id, user_node("a"), user_node("b"), 1.0).await.unwrap();
+        service.add_propagation_event(&loop_id, user_node("b"), user_node("c"), 1.0).await.unwrap();
+        service.add_propagation_event(&loop_id, user_node("c"), user_node("d"), 1.0).await.unwrap();
+        assert!(service.get_cycle_report(&loop_id).is_none());
+
+        assert!(service.add_propagation_event(&loop_id, user_node("d"), user_node("b"), 1.0).await.is_err());
+
+        let report = service.get_cycle_report(&loop_id).unwrap();
+        assert_eq!(report.cycle_nodes, vec!["b", "c", "d"]);
+
+        let echo_loop = &service.get_content_echo_loops("content_1")[0];
+        assert_eq!(echo_loop.propagation_paths[0].nodes.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_propagations_beyond_max_depth_rejected() {
+        let service = PropagationService::new().configure(10, DEFAULT_RESONANCE_THRESHOLD, DEFAULT_DECAY_FACTOR);
+        let loop_id = service.create_echo_loop("content_1".to_string()).await.unwrap();
+
+        // A chain of 10 nodes, the last at depth 9
+        for i in 0..9 {
+            let (from, to) = (user_node(&format!("n{}", i)), user_node(&format!("n{}", i + 1)));
+            service.add_propagation_event(&loop_id, from, to, 1.0).await.unwrap();
+        }
+
+        let result = service.add_propagation_event(&loop_id, user_node("n9"), user_node("n10"), 1.0).await;
+        assert!(matches!(result, Err(PropagationEventError::TooDeep)));
+        let echo_loop = &service.get_content_echo_loops("content_1")[0];
+        assert_eq!(echo_loop.propagation_paths[0].nodes.len(), 10);
+
+        // New paths start over at depth 1
+        service.add_propagation_event(&loop_id, user_node("m0"), user_node("m1"), 1.0).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_loop_strengths_are_ranked_across_content() {
+        let service = PropagationService::new();
+        let small = service.create_echo_loop("content_small".to_string()).await.unwrap();
+        service.add_propagation_event(&small, user_node("a"), user_node("b"), 1.0).await.unwrap();
+        let large = service.create_echo_loop("content_large".to_string()).await.unwrap();
+        for i in 0..20 {
+            let (from, to) = (user_node(&format!("s{}", i)), user_node(&format!("t{}", i)));
+            service.add_propagation_event(&large, from, to, 1.0).await.unwrap();
+        }
+
+        let large_loop = &service.get_content_echo_loops("content_large")[0];
+        // 40 nodes divide the raw strength by ln(40)
+        let expected = large_loop.raw_loop_strength / 40f64.ln();
+        assert!((large_loop.normalized_loop_strength - expected).abs() < 1e-9);
+
+        let strengths = service.content_loop_strengths("content_small");
+        assert_eq!(strengths.len(), 1);
+        assert_eq!(strengths[0].loop_id, small);
+        assert_eq!(strengths[0].loop_strength_percentile, 1.0);
+        assert_eq!(service.content_loop_strengths("content_large")[0].loop_strength_percentile, 0.5);
+        assert_eq!(service.mean_loop_strength("content_small"), strengths[0].normalized_loop_strength);
+        assert_eq!(service.mean_loop_strength("content_none"), 0.0);
+
+        let analytics = service.get_propagation_analytics(Utc::now() - chrono::Duration::hours(1));
+        let mean = (strengths[0].normalized_loop_strength + large_loop.normalized_loop_strength) / 2.0;
+        assert!((analytics.avg_loop_strength - mean).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_breaking_news_loses_resonance_faster_than_analysis() {
+        use crate::models::content::ContentCategory;
+
+        let service = PropagationService::new();
+        let breaking = ContentCategory::BreakingNews.decay_factor().unwrap();
+        let analysis = ContentCategory::Analysis.decay_factor().unwrap();
+        service.set_decay_factor_for_content("content_news", breaking).unwrap();
+        service.set_decay_factor_for_content("content_analysis", analysis).unwrap();
+        assert!(service.set_decay_factor_for_content("content_news", 0.0).is_err());
+        assert!(service.set_decay_factor_for_content("content_news", 1.5).is_err());
+        assert_eq!(service.decay_factor_for("content_news"), 0.7);
+        assert_eq!(service.decay_factor_for("content_other"), 0.9);
+
+        // Propagated three days ago
+        let stale_node = |id: &str| PropagationNode {
+            timestamp: Utc::now() - chrono::Duration::days(3),
+            ..user_node(id)
+        };
+        let mut resonance = HashMap::new();
+        for content_id in ["content_news", "content_analysis", "content_other"] {
+            let loop_id = service.create_echo_loop(content_id.to_string()).await.unwrap();
+            service.add_propagation_event(&loop_id, stale_node("a"), stale_node("b"), 1.0).await.unwrap();
+            let path = service.get_content_echo_loops(content_id)[0].propagation_paths[0].clone();
+            assert_eq!(path.decay_rate, service.decay_factor_for(content_id));
+            resonance.insert(content_id, path.resonance_factor);
+        }
+
+        // 0.7^3 of the resonance is left after three days, against 0.98^3
+        assert!(resonance["content_news"] < 0.5 * resonance["content_analysis"]);
+        assert!(resonance["content_news"] < resonance["content_other"]);
+        assert!(resonance["content_other"] < resonance["content_analysis"]);
+    }
+
+    #[tokio::test]
+    async fn test_export_graphml_structure() {
+        use quick_xml::events::Event;
+        use quick_xml::Reader;
+
+        let service = PropagationService::new();
+        let loop_id = service.create_echo_loop("content_<1>".to_string()).await.unwrap();
+        service.add_propagation_event(&loop_id, user_node("a"), user_node("b"), 1.0).await.unwrap();
+        service.add_propagation_event(&loop_id, user_node("b"), user_node("c"), 1.0).await.unwrap();
+        service.add_propagation_event(&loop_id, user_node("a"), user_node("c"), 1.0).await.unwrap();
+
+        let xml = service.export_graphml("content_<1>");
+        let mut reader = Reader::from_str(&xml);
+        let mut node_ids = Vec::new();
+        let mut edge_count = 0;
+        let mut data_keys = HashSet::new();
+        let mut depth = 0;
+
+        loop {
+            match reader.read_event().expect("emitted GraphML must be well-formed") {
+                Event::Start(element) => {
+                    depth += 1;
+                    match element.name().as_ref() {
+                        b"node" => {
+                            let id = element.try_get_attribute("id").unwrap().unwrap();
+                            node_ids.push(String::from_utf8(id.value.to_vec()).unwrap());
+                        }
+                        b"edge" => edge_count += 1,
+                        b"data" => {
+                            let key = element.try_get_attribute("key").unwrap().unwrap();
+                            data_keys.insert(String::from_utf8(key.value.to_vec()).unwrap());
+                        }
+                        _ => {}
+                    }
+                }
+                Event::End(_) => depth -= 1,
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        assert_eq!(depth, 0);
+        assert_eq!(node_ids, vec!["a", "b", "c"]);
+        assert_eq!(edge_count, 3);
+        for key in ["node_type", "influence_weight", "reach", "engagement_rate", "weight", "resonance_factor"] {
+            assert!(data_keys.contains(key), "missing data key {}", key);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_dot() {
+        let service = PropagationService::new();
+        let loop_id = service.create_echo_loop("content_1".to_string()).await.unwrap();
+        service.add_propagation_event(&loop_id, user_node("a"), user_node("b"), 1.0).await.unwrap();
+
+        let dot = service.export_dot("content_1");
+        assert!(dot.starts_with("digraph \"content_1\" {"));
+        assert!(dot.contains("\"a\" -> \"b\""));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_events_on_one_loop_are_all_kept() {
+        let service = Arc::new(PropagationService::new());
+        let loop_id = service.create_echo_loop("content_1".to_string()).await.unwrap();
+
+        // One task per event, so the calls race on the same loop from separate threads
+        let events = (0..100).map(|i| {
+            let service = service.clone();
+            let loop_id = loop_id.clone();
+            tokio::spawn(async move {
+                let (from, to) = (user_node(&format!("s{}", i)), user_node(&format!("t{}", i)));
+                service.add_propagation_event(&loop_id, from, to, 1.0).await
+            })
+        });
+        for result in futures_util::future::join_all(events).await {
+            result.unwrap().unwrap();
+        }
+
+        let echo_loop = &service.get_content_echo_loops("content_1")[0];
+        assert_eq!(echo_loop.propagation_paths.len(), 100);
+        assert_eq!(service.get_propagation_analytics(echo_loop.created_at).total_propagation_paths, 100);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_propagations_to_followers_are_stronger(pool: sqlx::PgPool) {
+        use crate::repositories::UserRelationshipRepository;
+
+        let mut users = Vec::new();
+        for wallet in ["0xsharer", "0xfollower", "0xstranger"] {
+            let id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+                .bind(wallet)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            users.push(id.to_string());
+        }
+        let graph = Arc::new(UserFollowerGraph::new(Arc::new(UserRelationshipRepository::new(pool))));
+        graph.follow(Uuid::parse_str(&users[1]).unwrap(), Uuid::parse_str(&users[0]).unwrap()).await.unwrap();
+
+        let service = PropagationService::new().with_follower_graph(graph);
+        let to_follower = service.create_echo_loop("content_1".to_string()).await.unwrap();
+        service.add_propagation_event(&to_follower, user_node(&users[0]), user_node(&users[1]), 1.0).await.unwrap();
+        let to_stranger = service.create_echo_loop("content_2".to_string()).await.unwrap();
+        service.add_propagation_event(&to_stranger, user_node(&users[0]), user_node(&users[2]), 1.0).await.unwrap();
+
+        let weight = |content_id| service.get_content_echo_loops(content_id)[0].propagation_paths[0].total_weight;
+        let expected = weight("content_2") * (1.0 + FOLLOWER_INTERACTION_BONUS);
+        assert!((weight("content_1") - expected).abs() < 1e-9);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_amplifications_are_recorded_capped_and_reverted(pool: sqlx::PgPool) {
+        let repository = Arc::new(PropagationRepository::new(pool));
+        let service = PropagationService::with_repository(repository.clone()).with_max_amplification_factor(1.2);
+        let loop_id = service.create_echo_loop("content_1".to_string()).await.unwrap();
+        service.add_propagation_event(&loop_id, user_node("a"), user_node("b"), 1.0).await.unwrap();
+
+        // A single fresh path resonates far above the threshold, asking for more than the cap
+        let since = Some(Utc::now() - chrono::Duration::hours(1));
+        let events = repository.list_amplifications(Some("content_1"), since, 10).await.unwrap();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert!(event.trigger_resonance > DEFAULT_RESONANCE_THRESHOLD + 0.4);
+        assert_eq!(event.amplification_factor, 1.2);
+        assert_eq!(event.affected_path_count, 1);
+        assert!((event.total_weight_after - event.total_weight_before * 1.2).abs() < 1e-9);
+        assert!(repository.list_amplifications(Some("content_2"), since, 10).await.unwrap().is_empty());
+
+        let reverted = service.revert_amplification(event.id).await.unwrap();
+        assert!(reverted.reverted_at.is_some());
+        let weight = repository.load_loop(&loop_id).await.unwrap().propagation_paths[0].total_weight;
+        assert!((weight - event.total_weight_before).abs() < 1e-9);
+
+        assert!(matches!(service.revert_amplification(event.id).await, Err(RepositoryError::Conflict(_))));
+        assert!(matches!(service.revert_amplification(Uuid::new_v4()).await, Err(RepositoryError::NotFound)));
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::handlers::propagation::CreatePropagationRequest;
+use crate::repositories::{BulkInsertOutcome, NewPropagation, PropagationRepository, RepositoryError};
+
+/// Hours a propagation event counts as a duplicate of one stored with the same fingerprint
+pub const DEFAULT_DEDUP_WINDOW_HOURS: i64 = 24;
+
+/// Keeps the same propagation reported by several connectors, e.g. a retweet also picked up
+/// from Telegram, from being counted twice. Events are fingerprinted by content, external
+/// IDs and type; one stored with a fingerprint seen within the window is a duplicate.
+pub struct PropagationEventDeduplicator {
+    repository: Arc<PropagationRepository>,
+    window: Duration,
+}
+
+impl PropagationEventDeduplicator {
+    pub fn new(repository: Arc<PropagationRepository>, window: Duration) -> Self {
+        Self { repository, window }
+    }
+
+    /// Window from `DEDUP_WINDOW_HOURS`, 24 hours by default
+    pub fn from_env(repository: Arc<PropagationRepository>) -> Self {
+        let hours = std::env::var("DEDUP_WINDOW_HOURS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|hours| *hours >= 0)
+            .unwrap_or(DEFAULT_DEDUP_WINDOW_HOURS);
+        Self::new(repository, Duration::hours(hours))
+    }
+
+    /// Propagation the event repeats, if stored within the window. Invalid events repeat none.
+    pub async fn duplicate_of(&self, event: &CreatePropagationRequest) -> Result<Option<Uuid>, RepositoryError> {
+        match event.to_new_propagation() {
+            Ok(propagation) => Ok(self.duplicates_of(&[propagation]).await?.pop().flatten()),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Propagation each of the events repeats, if stored within the window, in one query
+    pub async fn duplicates_of(&self, events: &[NewPropagation]) -> Result<Vec<Option<Uuid>>, RepositoryError> {
+        let fingerprints: Vec<Vec<u8>> = events.iter().map(NewPropagation::fingerprint).collect();
+        let lookup: Vec<&[u8]> = fingerprints.iter().map(Vec::as_slice).collect();
+        let stored = self.repository.find_by_fingerprints(&lookup, Utc::now() - self.window).await?;
+        Ok(fingerprints.iter().map(|fingerprint| stored.get(fingerprint).copied()).collect())
+    }
+
+    /// Store the events that are not duplicates, reporting the ones that are
+    pub async fn record(&self, events: &[NewPropagation]) -> Result<BulkInsertOutcome, RepositoryError> {
+        self.repository.insert_bulk(events, self.window).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Platform;
+    use sqlx::PgPool;
+
+    fn retweet(content_id: Uuid, source_platform: Platform) -> CreatePropagationRequest {
+        CreatePropagationRequest {
+            content_id: content_id.to_string(),
+            source_user_id: None,
+            target_user_id: None,
+            propagation_type: "repost".to_string(),
+            source_platform,
+            target_platform: Platform::Twitter,
+            source_external_id: Some("tweet_1".to_string()),
+            target_external_id: Some("retweet_1".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_platforms_and_separates_fields() {
+        let content_id = Uuid::new_v4();
+        let from_twitter = retweet(content_id, Platform::Twitter).to_new_propagation().unwrap();
+        let from_telegram = retweet(content_id, Platform::Telegram).to_new_propagation().unwrap();
+        assert_eq!(from_twitter.fingerprint(), from_telegram.fingerprint());
+
+        let shifted = NewPropagation {
+            source_external_id: Some("tweet_1r".to_string()),
+            target_external_id: Some("etweet_1".to_string()),
+            ..from_twitter.clone()
+        };
+        assert_ne!(from_twitter.fingerprint(), shifted.fingerprint());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_duplicates_within_window_only(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xdedup') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let content_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type)
+             VALUES ($1, 'twitter', 'tweet_1', 'text') RETURNING id",
+        )
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let deduplicator =
+            PropagationEventDeduplicator::new(Arc::new(PropagationRepository::new(pool.clone())), Duration::hours(24));
+        let event = retweet(content_id, Platform::Twitter);
+
+        assert_eq!(deduplicator.duplicate_of(&event).await.unwrap(), None);
+        let first = deduplicator.record(&[event.to_new_propagation().unwrap()]).await.unwrap();
+        let first_id = first.inserted[0].id;
+        assert_eq!(deduplicator.duplicate_of(&event).await.unwrap(), Some(first_id));
+
+        // Reported again by another connector
+        let again = retweet(content_id, Platform::Telegram).to_new_propagation().unwrap();
+        let outcome = deduplicator.record(std::slice::from_ref(&again)).await.unwrap();
+        assert_eq!((outcome.inserted.len(), outcome.duplicates), (0, vec![(0, first_id)]));
+
+        // Once the window has passed the event is stored again, linked to the first
+        sqlx::query("UPDATE propagation_fingerprints SET created_at = NOW() - INTERVAL '25 hours'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(deduplicator.duplicate_of(&event).await.unwrap(), None);
+        let outcome = deduplicator.record(&[again]).await.unwrap();
+        assert_eq!(outcome.inserted[0].duplicate_of, Some(first_id));
+
+        let (count, checked): (i64, bool) = sqlx::query_as(
+            "SELECT COUNT(*), BOOL_AND(fingerprint_checked) FROM propagations WHERE content_id = $1",
+        )
+        .bind(content_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((count, checked), (2, true));
+    }
+}
@@ -0,0 +1,159 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::services::propagation::PropagationPath;
+
+/// How far content travelled along its propagation paths and how influence attenuates
+/// with each hop
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DepthReport {
+    /// `(depth, propagations)` for every depth reached, shallowest first. Depth 1 is a
+    /// direct share, depth 2 a share of that share, and so on.
+    pub depth_histogram: Vec<(u32, u32)>,
+    /// `(depth, mean influence_weight)` of the nodes reached at each depth
+    pub influence_by_depth: Vec<(u32, f64)>,
+    pub max_depth_reached: u32,
+    /// Node ids of the longest path, from its origin
+    pub deepest_path: Vec<String>,
+    /// `b` and `a` of `a * e^(-b * depth)` fitted to `influence_by_depth`
+    pub decay_coefficient: f64,
+    pub initial_influence: f64,
+}
+
+/// Depth histograms and influence decay curves of propagation paths
+pub struct PropagationDepthAnalyzer;
+
+impl PropagationDepthAnalyzer {
+    /// Every hop of a path is one propagation; the node it reaches sits at the hop's depth
+    pub fn analyze(paths: &[PropagationPath]) -> DepthReport {
+        // Propagations and their summed influence, by depth
+        let mut by_depth: BTreeMap<u32, (u32, f64)> = BTreeMap::new();
+        for path in paths {
+            for (depth, node) in path.nodes.iter().enumerate().skip(1) {
+                let (count, influence) = by_depth.entry(depth as u32).or_default();
+                *count += 1;
+                *influence += node.influence_weight;
+            }
+        }
+
+        // The first of equally long paths
+        let deepest = paths.iter().fold(None::<&PropagationPath>, |deepest, path| match deepest {
+            Some(deepest) if deepest.nodes.len() >= path.nodes.len() => Some(deepest),
+            _ => Some(path),
+        });
+        let deepest_path: Vec<String> = deepest
+            .filter(|path| path.nodes.len() > 1)
+            .map(|path| path.nodes.iter().map(|node| node.id.clone()).collect())
+            .unwrap_or_default();
+
+        let influence_by_depth: Vec<(u32, f64)> = by_depth
+            .iter()
+            .map(|(&depth, &(count, influence))| (depth, influence / count as f64))
+            .collect();
+        let (initial_influence, decay_coefficient) = fit_exponential_decay(&influence_by_depth);
+
+        DepthReport {
+            depth_histogram: by_depth.iter().map(|(&depth, &(count, _))| (depth, count)).collect(),
+            influence_by_depth,
+            max_depth_reached: deepest_path.len().saturating_sub(1) as u32,
+            deepest_path,
+            decay_coefficient,
+            initial_influence,
+        }
+    }
+}
+
+/// `(a, b)` of `a * e^(-b * depth)`, by least squares on the logarithm of the positive
+/// points. A single point is taken as flat.
+fn fit_exponential_decay(points: &[(u32, f64)]) -> (f64, f64) {
+    let logs: Vec<(f64, f64)> = points
+        .iter()
+        .filter(|(_, influence)| *influence > 0.0)
+        .map(|&(depth, influence)| (depth as f64, influence.ln()))
+        .collect();
+    match logs.as_slice() {
+        [] => return (0.0, 0.0),
+        [(_, ln_influence)] => return (ln_influence.exp(), 0.0),
+        _ => {}
+    }
+
+    let n = logs.len() as f64;
+    let mean_depth = logs.iter().map(|(depth, _)| depth).sum::<f64>() / n;
+    let mean_ln = logs.iter().map(|(_, ln_influence)| ln_influence).sum::<f64>() / n;
+    let covariance: f64 = logs.iter().map(|(depth, ln_influence)| (depth - mean_depth) * (ln_influence - mean_ln)).sum();
+    let variance: f64 = logs.iter().map(|(depth, _)| (depth - mean_depth).powi(2)).sum();
+
+    let slope = covariance / variance;
+    ((mean_ln - slope * mean_depth).exp(), -slope)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::propagation::{NodeType, PropagationNode};
+    use chrono::Utc;
+
+    fn path(nodes: &[(&str, f64)]) -> PropagationPath {
+        PropagationPath {
+            nodes: nodes
+                .iter()
+                .map(|&(id, influence_weight)| PropagationNode {
+                    id: id.to_string(),
+                    node_type: NodeType::User,
+                    influence_weight,
+                    reach: 100,
+                    engagement_rate: 0.1,
+                    timestamp: Utc::now(),
+                })
+                .collect(),
+            total_weight: 1.0,
+            resonance_factor: 0.0,
+            decay_rate: 0.95,
+        }
+    }
+
+    /// Influence of a node `depth` hops from the origin, decaying as 0.8 * e^(-0.5 * depth)
+    fn decayed(depth: u32) -> f64 {
+        0.8 * (-0.5 * depth as f64).exp()
+    }
+
+    #[test]
+    fn test_histogram_sums_to_the_propagation_count() {
+        let paths = vec![
+            path(&[("author", 1.0), ("a", decayed(1))]),
+            path(&[("author", 1.0), ("b", decayed(1)), ("c", decayed(2))]),
+            path(&[("author", 1.0), ("d", decayed(1)), ("e", decayed(2)), ("f", decayed(3))]),
+            path(&[("g", 1.0), ("h", decayed(1)), ("i", decayed(2)), ("j", decayed(3))]),
+        ];
+        let propagations: usize = paths.iter().map(|path| path.nodes.len() - 1).sum();
+
+        let report = PropagationDepthAnalyzer::analyze(&paths);
+        assert_eq!(report.depth_histogram, vec![(1, 4), (2, 3), (3, 2)]);
+        let total: u32 = report.depth_histogram.iter().map(|(_, count)| count).sum();
+        assert_eq!(total as usize, propagations);
+
+        assert_eq!(report.max_depth_reached, 3);
+        assert_eq!(report.deepest_path, vec!["author", "d", "e", "f"]);
+    }
+
+    #[test]
+    fn test_exponential_decay_is_recovered() {
+        let paths: Vec<PropagationPath> = (0..3)
+            .map(|_| path(&[("o", 1.0), ("a", decayed(1)), ("b", decayed(2)), ("c", decayed(3)), ("d", decayed(4))]))
+            .collect();
+
+        let report = PropagationDepthAnalyzer::analyze(&paths);
+        assert_eq!(report.influence_by_depth.len(), 4);
+        assert!((report.influence_by_depth[1].1 - decayed(2)).abs() < 1e-12);
+        assert!((report.decay_coefficient - 0.5).abs() < 1e-9);
+        assert!((report.initial_influence - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_propagations() {
+        assert_eq!(PropagationDepthAnalyzer::analyze(&[]), DepthReport::default());
+
+        let report = PropagationDepthAnalyzer::analyze(&[path(&[("o", 1.0), ("a", 0.4)])]);
+        assert_eq!((report.initial_influence, report.decay_coefficient), (0.4, 0.0));
+    }
+}
@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::content::Propagation;
+use crate::services::propagation::PropagationPath;
+
+/// How the weights of a content's propagations are rescaled before they are scored, so
+/// a single node with an outsized reach cannot dominate TPM and path resonance
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightNormalizationStrategy {
+    /// Raw weights are used as they are
+    None,
+    /// Share of the content's weights at or below each weight
+    #[default]
+    RankBased,
+    /// Weights rescaled linearly so the lowest is 0 and the highest 1
+    MinMax,
+    /// Standard score of each weight, squashed into 0–1 by the logistic function
+    ZScore,
+}
+
+impl WeightNormalizationStrategy {
+    /// Name used in config files and the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WeightNormalizationStrategy::None => "none",
+            WeightNormalizationStrategy::RankBased => "rank_based",
+            WeightNormalizationStrategy::MinMax => "min_max",
+            WeightNormalizationStrategy::ZScore => "z_score",
+        }
+    }
+
+    /// Rescale `weights` in place. Weights that are all equal become 1.0, or 0.5 under
+    /// `ZScore`, the score of an average weight.
+    pub fn apply(self, weights: &mut [f64]) {
+        if weights.is_empty() {
+            return;
+        }
+        let n = weights.len() as f64;
+
+        match self {
+            WeightNormalizationStrategy::None => {}
+            WeightNormalizationStrategy::RankBased => {
+                let mut sorted = weights.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                for weight in weights.iter_mut() {
+                    let at_or_below = sorted.partition_point(|other| other.total_cmp(weight).is_le());
+                    *weight = at_or_below as f64 / n;
+                }
+            }
+            WeightNormalizationStrategy::MinMax => {
+                let min = weights.iter().copied().fold(f64::INFINITY, f64::min);
+                let max = weights.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                for weight in weights.iter_mut() {
+                    *weight = if max > min { (*weight - min) / (max - min) } else { 1.0 };
+                }
+            }
+            WeightNormalizationStrategy::ZScore => {
+                let mean = weights.iter().sum::<f64>() / n;
+                let std_dev = (weights.iter().map(|weight| (weight - mean).powi(2)).sum::<f64>() / n).sqrt();
+                for weight in weights.iter_mut() {
+                    let z = if std_dev > 0.0 { (*weight - mean) / std_dev } else { 0.0 };
+                    *weight = 1.0 / (1.0 + (-z).exp());
+                }
+            }
+        }
+    }
+}
+
+/// Rescales the weights of propagations within one content's propagation graph. The raw
+/// weight of a node or propagation is its weight scaled by the audience it reached.
+pub struct PropagationWeightNormalizer {
+    strategy: WeightNormalizationStrategy,
+}
+
+impl PropagationWeightNormalizer {
+    pub fn new(strategy: WeightNormalizationStrategy) -> Self {
+        Self { strategy }
+    }
+
+    /// Replace the `influence_weight` of every node of `paths` with its normalized raw weight
+    pub fn normalize(&self, paths: &mut [PropagationPath]) {
+        if self.strategy == WeightNormalizationStrategy::None {
+            return;
+        }
+        let mut weights: Vec<f64> = paths
+            .iter()
+            .flat_map(|path| &path.nodes)
+            .map(|node| node.influence_weight * node.reach.max(1) as f64)
+            .collect();
+        self.strategy.apply(&mut weights);
+
+        let nodes = paths.iter_mut().flat_map(|path| path.nodes.iter_mut());
+        for (node, weight) in nodes.zip(weights) {
+            node.influence_weight = weight;
+        }
+    }
+
+    /// Replace the `weight` of every propagation with its normalized raw weight
+    pub fn normalize_propagations(&self, propagations: &mut [Propagation]) {
+        if self.strategy == WeightNormalizationStrategy::None {
+            return;
+        }
+        let mut weights: Vec<f64> = propagations
+            .iter()
+            .map(|propagation| propagation.weight * propagation.reach.max(1) as f64)
+            .collect();
+        self.strategy.apply(&mut weights);
+
+        for (propagation, weight) in propagations.iter_mut().zip(weights) {
+            propagation.weight = weight;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::propagation::{NodeType, PropagationNode};
+    use chrono::Utc;
+
+    fn node(id: &str, reach: u32) -> PropagationNode {
+        PropagationNode {
+            id: id.to_string(),
+            node_type: NodeType::User,
+            influence_weight: 0.5,
+            reach,
+            engagement_rate: 0.1,
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn path(nodes: Vec<PropagationNode>) -> PropagationPath {
+        PropagationPath { nodes, total_weight: 1.0, resonance_factor: 0.0, decay_rate: 0.95 }
+    }
+
+    /// An author, an outlier reaching 1000 times the audience of the others, and the others
+    fn outlier_graph() -> Vec<PropagationPath> {
+        vec![
+            path(vec![node("author", 100), node("outlier", 100_000), node("a", 100)]),
+            path(vec![node("author", 100), node("b", 120), node("c", 90)]),
+        ]
+    }
+
+    fn weights(paths: &[PropagationPath]) -> Vec<(String, f64)> {
+        paths
+            .iter()
+            .flat_map(|path| &path.nodes)
+            .map(|node| (node.id.clone(), node.influence_weight))
+            .collect()
+    }
+
+    #[test]
+    fn test_rank_normalization_caps_an_outlier() {
+        let mut paths = outlier_graph();
+        PropagationWeightNormalizer::new(WeightNormalizationStrategy::RankBased).normalize(&mut paths);
+
+        let mut normalized: Vec<f64> = weights(&paths).into_iter().map(|(_, weight)| weight).collect();
+        normalized.sort_by(f64::total_cmp);
+        let (outlier, next) = (normalized[normalized.len() - 1], normalized[normalized.len() - 2]);
+        assert_eq!(outlier, 1.0);
+        assert!(outlier <= 2.0 * next, "outlier {} vs next {}", outlier, next);
+        assert!(normalized.iter().all(|weight| (0.0..=1.0).contains(weight)));
+
+        // Without normalization the raw weights are left alone
+        let mut raw = outlier_graph();
+        PropagationWeightNormalizer::new(WeightNormalizationStrategy::None).normalize(&mut raw);
+        assert!(weights(&raw).iter().all(|(_, weight)| *weight == 0.5));
+    }
+
+    #[test]
+    fn test_min_max_and_z_score_stay_within_unit_range() {
+        let mut min_max = outlier_graph();
+        PropagationWeightNormalizer::new(WeightNormalizationStrategy::MinMax).normalize(&mut min_max);
+        let min_max = weights(&min_max);
+        assert_eq!(min_max[1], ("outlier".to_string(), 1.0));
+        assert_eq!(min_max[5], ("c".to_string(), 0.0));
+
+        let mut z_score = outlier_graph();
+        PropagationWeightNormalizer::new(WeightNormalizationStrategy::ZScore).normalize(&mut z_score);
+        let z_score = weights(&z_score);
+        assert!(z_score.iter().all(|(_, weight)| *weight > 0.0 && *weight < 1.0));
+        assert!(z_score[1].1 > 0.85);
+    }
+
+    #[test]
+    fn test_equal_weights() {
+        let mut weights = vec![3.0; 4];
+        WeightNormalizationStrategy::RankBased.apply(&mut weights);
+        assert_eq!(weights, vec![1.0; 4]);
+
+        let mut weights = vec![3.0; 4];
+        WeightNormalizationStrategy::ZScore.apply(&mut weights);
+        assert_eq!(weights, vec![0.5; 4]);
+
+        let mut ranked = vec![10.0, 1000.0, 10.0, 20.0];
+        WeightNormalizationStrategy::RankBased.apply(&mut ranked);
+        assert_eq!(ranked, vec![0.5, 1.0, 0.5, 0.75]);
+    }
+}
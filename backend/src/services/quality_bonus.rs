@@ -0,0 +1,208 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+use crate::repositories::{QualityBonusCandidate, QualityBonusRepository, RepositoryError};
+use crate::services::reward_service::QualityMetrics;
+use crate::services::RewardService;
+
+/// Content must score above this Echo Index to earn a quality bonus
+pub const QUALITY_BONUS_MIN_ECHO_INDEX: f64 = 80.0;
+/// Content is only reviewed once it has had a day to gather propagations
+const QUALITY_BONUS_MIN_AGE_HOURS: i64 = 24;
+/// Content reviewed per run; the rest waits for the next run
+const REVIEW_BATCH_SIZE: i64 = 200;
+/// Distinct target platforms at which social impact is full
+const PLATFORMS_FOR_FULL_IMPACT: f64 = 4.0;
+
+/// Periodically awards retroactive quality bonuses to high-echo content, once per item
+pub struct QualityBonusScheduler {
+    repository: Arc<QualityBonusRepository>,
+    rewards: Arc<RwLock<RewardService>>,
+}
+
+impl QualityBonusScheduler {
+    pub fn new(repository: Arc<QualityBonusRepository>, rewards: Arc<RwLock<RewardService>>) -> Self {
+        Self { repository, rewards }
+    }
+
+    /// Content that qualifies for a quality bonus but has not been reviewed yet
+    pub async fn pending(&self, limit: i64) -> Result<Vec<QualityBonusCandidate>, RepositoryError> {
+        let created_before = chrono::Utc::now() - chrono::Duration::hours(QUALITY_BONUS_MIN_AGE_HOURS);
+        self.repository
+            .pending(QUALITY_BONUS_MIN_ECHO_INDEX, created_before, limit)
+            .await
+    }
+
+    /// Review one batch of qualifying content, returning how many bonuses were awarded.
+    /// Stops early once the daily pool cannot cover the next bonus; that content is
+    /// reviewed again on the next run.
+    pub async fn review(&self) -> Result<usize, RepositoryError> {
+        let mut awarded = 0;
+
+        for candidate in self.pending(REVIEW_BATCH_SIZE).await? {
+            let metrics = quality_metrics(&candidate);
+            let amount = metrics.bonus_amount();
+            if !self.repository.claim(candidate.content_id, candidate.user_id).await? {
+                continue;
+            }
+            // Nothing to award; the review is still recorded so the content is not revisited
+            if amount <= 0.0 {
+                continue;
+            }
+
+            let mut rewards = self.rewards.write().await;
+            let (_, pool_remaining, _) = rewards.get_pool_status();
+            if amount > pool_remaining {
+                drop(rewards);
+                self.repository.release(candidate.content_id).await?;
+                log::info!("Daily reward pool exhausted; deferring remaining quality bonuses");
+                break;
+            }
+
+            let result = rewards
+                .award_quality_bonus(candidate.user_id.to_string(), candidate.content_id.to_string(), metrics)
+                .await;
+            drop(rewards);
+
+            match result {
+                Ok(reward_id) => {
+                    self.repository.record_award(candidate.content_id, &reward_id, amount).await?;
+                    awarded += 1;
+                }
+                Err(e) => {
+                    log::warn!("Failed to award quality bonus for {}: {}", candidate.content_id, e);
+                    self.repository.release(candidate.content_id).await?;
+                }
+            }
+        }
+
+        Ok(awarded)
+    }
+
+    pub fn spawn_review_task(self: Arc<Self>, period: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                match self.review().await {
+                    Ok(awarded) if awarded > 0 => log::info!("Awarded {} quality bonuses", awarded),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Quality bonus review failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+/// Quality metrics of a candidate from its aggregated propagations
+pub fn quality_metrics(candidate: &QualityBonusCandidate) -> QualityMetrics {
+    let ratio = |part: i64, whole: i64| if whole > 0 { part as f64 / whole as f64 } else { 0.0 };
+
+    QualityMetrics {
+        // Echo Index gained since the first calculation, on a 0-1 scale
+        echo_index_improvement: ((candidate.echo_index - candidate.first_echo_index) / 100.0).max(0.0),
+        // Users reached per user who propagated the content
+        viral_coefficient: ratio(candidate.reached_users, candidate.propagators),
+        engagement_rate: ratio(candidate.engaged_propagations, candidate.propagations),
+        retention_rate: ratio(candidate.late_propagations, candidate.propagations),
+        social_impact_score: (candidate.platforms_reached as f64 / PLATFORMS_FOR_FULL_IMPACT).min(1.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user_event::UserEvent;
+    use crate::repositories::UserEventRepository;
+    use crate::services::rewards::RewardType;
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    async fn insert_user(pool: &PgPool, wallet: &str) -> Uuid {
+        sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+            .bind(wallet)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn insert_content(pool: &PgPool, user_id: Uuid, external_id: &str, echo_index: f64, age_hours: i32) -> Uuid {
+        sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type, title, body, echo_index, created_at)
+             VALUES ($1, 'twitter', $2, 'text', 'Quality', 'Body', $3, NOW() - make_interval(hours => $4))
+             RETURNING id",
+        )
+        .bind(user_id)
+        .bind(external_id)
+        .bind(echo_index)
+        .bind(age_hours)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_review_awards_qualifying_content_once(pool: PgPool) {
+        let creator = insert_user(&pool, "0xquality").await;
+        let sharers = [insert_user(&pool, "0xsharer_a").await, insert_user(&pool, "0xsharer_b").await];
+
+        let qualifying = insert_content(&pool, creator, "tweet_quality", 92.0, 48).await;
+        insert_content(&pool, creator, "tweet_too_new", 95.0, 2).await;
+        insert_content(&pool, creator, "tweet_low_echo", 60.0, 48).await;
+
+        for (i, (kind, age_hours)) in [("share", 40), ("quote", 30), ("quote", 12)].into_iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO propagations (content_id, source_user_id, target_user_id, propagation_type,
+                                           source_platform, target_platform, target_external_id, created_at)
+                 VALUES ($1, $2, $3, $4::propagation_type, 'twitter', 'farcaster', $5, NOW() - make_interval(hours => $6))",
+            )
+            .bind(qualifying)
+            .bind(sharers[i % 2])
+            .bind(creator)
+            .bind(kind)
+            .bind(format!("cast_{}", i))
+            .bind(age_hours)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let events = Arc::new(UserEventRepository::new(pool.clone()));
+        let rewards = Arc::new(RwLock::new(RewardService::new(10_000.0).with_event_repository(events.clone())));
+        let scheduler = QualityBonusScheduler::new(Arc::new(QualityBonusRepository::new(pool.clone())), rewards);
+
+        let pending = scheduler.pending(10).await.unwrap();
+        assert_eq!(pending.iter().map(|c| c.content_id).collect::<Vec<_>>(), vec![qualifying]);
+        let metrics = quality_metrics(&pending[0]);
+        assert_eq!((pending[0].propagations, pending[0].engaged_propagations, pending[0].late_propagations), (3, 2, 1));
+        assert!((metrics.echo_index_improvement - 0.92).abs() < 1e-9);
+
+        assert_eq!(scheduler.review().await.unwrap(), 1);
+        assert_eq!(scheduler.review().await.unwrap(), 0);
+        assert!(scheduler.pending(10).await.unwrap().is_empty());
+
+        let timeline = events.timeline(creator, None, 10).await.unwrap();
+        assert_eq!(timeline.data.len(), 1);
+        assert!(matches!(
+            &timeline.data[0].event,
+            UserEvent::RewardEarned { content_id, reward_type: RewardType::QualityBonus, amount }
+                if *content_id == qualifying.to_string() && (*amount - metrics.bonus_amount()).abs() < 1e-9
+        ));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_review_respects_daily_pool(pool: PgPool) {
+        let creator = insert_user(&pool, "0xpool").await;
+        let content_id = insert_content(&pool, creator, "tweet_pool", 90.0, 48).await;
+
+        let rewards = Arc::new(RwLock::new(RewardService::new(1.0)));
+        let scheduler = QualityBonusScheduler::new(Arc::new(QualityBonusRepository::new(pool.clone())), rewards.clone());
+
+        // A 9.0 bonus does not fit in a pool of 1.0, so the content stays pending
+        assert_eq!(scheduler.review().await.unwrap(), 0);
+        assert_eq!(scheduler.pending(10).await.unwrap()[0].content_id, content_id);
+        assert_eq!(rewards.read().await.get_pool_status().1, 1.0);
+    }
+}
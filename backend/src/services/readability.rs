@@ -0,0 +1,279 @@
+/// Abbreviations whose trailing period does not end a sentence
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "mt", "vs", "etc", "inc", "ltd", "co", "corp",
+    "dept", "est", "approx", "fig", "no", "vol", "gen", "gov", "sen", "rep", "jan", "feb", "mar", "apr",
+    "jun", "jul", "aug", "sep", "sept", "oct", "nov", "dec",
+];
+
+/// Vowel pairs that are pronounced as two syllables ("li-on", "vi-de-o", "ac-tu-al")
+const SPLIT_DIGRAPHS: &[&str] = &["ia", "io", "eo", "ua", "uo", "iu"];
+
+/// Vowels of the Latin-script languages other than English, where syllables are counted
+/// as vowel groups
+const LATIN_VOWELS: &str = "aeiouyáéíóúàèìòùâêîôûäëïöüãõœæ";
+
+/// Parameters of a Flesch Reading Ease formula:
+/// `base - sentence_weight * words_per_sentence - syllable_weight * syllables_per_word`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FleschCoefficients {
+    pub base: f64,
+    pub sentence_weight: f64,
+    pub syllable_weight: f64,
+}
+
+impl FleschCoefficients {
+    /// Flesch (1948)
+    pub const ENGLISH: Self = Self { base: 206.835, sentence_weight: 1.015, syllable_weight: 84.6 };
+
+    /// Published adaptations for an ISO 639-3 language code, English for the rest
+    pub fn for_language(language: &str) -> Self {
+        match language {
+            // Fernández Huerta (1959)
+            "spa" => Self { base: 206.84, sentence_weight: 1.02, syllable_weight: 60.0 },
+            // Amstad (1978)
+            "deu" => Self { base: 180.0, sentence_weight: 1.0, syllable_weight: 58.5 },
+            // Kandel and Moles (1958)
+            "fra" => Self { base: 207.0, sentence_weight: 1.015, syllable_weight: 73.6 },
+            // Franchina and Vacca (1986)
+            "ita" => Self { base: 217.0, sentence_weight: 1.3, syllable_weight: 60.0 },
+            // Martins et al. (1996)
+            "por" => Self { base: 248.835, sentence_weight: 1.015, syllable_weight: 84.6 },
+            _ => Self::ENGLISH,
+        }
+    }
+}
+
+/// Flesch readability of a text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Readability {
+    /// Flesch Reading Ease clamped to [0, 100]; higher is easier
+    pub reading_ease: f64,
+    /// Flesch-Kincaid Grade Level, the US school grade needed to follow the text
+    pub grade_level: f64,
+}
+
+impl Readability {
+    /// Score `text` written in an ISO 639-3 language, with that language's Flesch
+    /// formula and syllable rules. The grade level is always Flesch-Kincaid, which is
+    /// only calibrated for English. Text without words is treated as unreadable.
+    pub fn of_language(text: &str, language: &str) -> Self {
+        let words: Vec<&str> = text
+            .split_whitespace()
+            .filter(|token| token.chars().any(char::is_alphanumeric))
+            .collect();
+        if words.is_empty() {
+            return Self { reading_ease: 0.0, grade_level: 0.0 };
+        }
+
+        let words_count = words.len() as f64;
+        let sentences = count_sentences(text) as f64;
+        let syllables = words.iter().map(|word| count_syllables_in(word, language)).sum::<usize>() as f64;
+
+        let words_per_sentence = words_count / sentences;
+        let syllables_per_word = syllables / words_count;
+
+        let flesch = FleschCoefficients::for_language(language);
+        let reading_ease =
+            flesch.base - flesch.sentence_weight * words_per_sentence - flesch.syllable_weight * syllables_per_word;
+        let grade_level = 0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59;
+
+        Self {
+            reading_ease: reading_ease.clamp(0.0, 100.0),
+            // Very simple text scores below the first grade
+            grade_level: grade_level.max(0.0),
+        }
+    }
+
+    /// Reading ease normalized to [0, 1]
+    pub fn normalized(&self) -> f64 {
+        self.reading_ease / 100.0
+    }
+}
+
+/// Number of sentences in `text`; any text with words has at least one
+pub fn count_sentences(text: &str) -> usize {
+    let mut sentences = 0;
+    let mut open_sentence = false;
+
+    for token in text.split_whitespace() {
+        if token.chars().any(char::is_alphanumeric) {
+            open_sentence = true;
+        }
+
+        let token = token.trim_end_matches(['"', '\'', ')', ']', '”', '’']);
+        let ends_sentence = token.ends_with(['!', '?'])
+            || (token.ends_with('.') && !is_abbreviation(token));
+
+        if ends_sentence && open_sentence {
+            sentences += 1;
+            open_sentence = false;
+        }
+    }
+
+    sentences + usize::from(open_sentence)
+}
+
+/// Known abbreviations ("Dr.") and dotted initialisms ("U.S.A.", "e.g.")
+fn is_abbreviation(token: &str) -> bool {
+    let token = token.trim_start_matches(|c: char| !c.is_alphanumeric()).trim_end_matches('.');
+    if token.is_empty() {
+        return false;
+    }
+
+    let lowercase = token.to_lowercase();
+    ABBREVIATIONS.contains(&lowercase.as_str())
+        || (token.contains('.') && token.split('.').all(|part| part.chars().count() == 1))
+}
+
+/// Estimated number of syllables in a word of an ISO 639-3 language. Languages other
+/// than English are counted by vowel groups, which their spelling follows closely.
+pub fn count_syllables_in(word: &str, language: &str) -> usize {
+    if language == "eng" {
+        return count_syllables(word);
+    }
+
+    let mut syllables = 0;
+    let mut previous_vowel = false;
+    for c in word.chars().filter(|c| c.is_alphabetic()).flat_map(char::to_lowercase) {
+        let vowel = LATIN_VOWELS.contains(c);
+        if vowel && !previous_vowel {
+            syllables += 1;
+        }
+        previous_vowel = vowel;
+    }
+    syllables.max(usize::from(word.chars().any(char::is_alphabetic)))
+}
+
+/// Estimated number of syllables in a single English word
+pub fn count_syllables(word: &str) -> usize {
+    let word: String = word
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if word.is_empty() {
+        return 0;
+    }
+    if word.chars().count() <= 3 {
+        return 1;
+    }
+
+    let chars: Vec<char> = word.chars().collect();
+    // A leading 'y' is a consonant ("yellow"); elsewhere it acts as a vowel ("gym", "happy")
+    let is_vowel = |i: usize| matches!(chars[i], 'a' | 'e' | 'i' | 'o' | 'u') || (chars[i] == 'y' && i > 0);
+
+    let mut syllables = 0isize;
+    for i in 0..chars.len() {
+        if is_vowel(i) && (i == 0 || !is_vowel(i - 1)) {
+            syllables += 1;
+        }
+    }
+
+    // Vowel digraphs ("ea", "ou", "ai") count once above, but some pairs span two syllables,
+    // except in the "-tion", "-cian", "-sion" and "-gion" endings
+    for (i, pair) in chars.windows(2).enumerate() {
+        let pair: String = pair.iter().collect();
+        let softened = i > 0 && matches!(chars[i - 1], 't' | 'c' | 's' | 'g' | 'x');
+        if SPLIT_DIGRAPHS.contains(&pair.as_str()) && !softened && !(pair == "ua" && i > 0 && chars[i - 1] == 'q') {
+            syllables += 1;
+        }
+    }
+
+    let ends_with_consonant_then = |suffix: &str| {
+        word.strip_suffix(suffix)
+            .and_then(|stem| stem.chars().last())
+            .is_some_and(|c| !matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y'))
+    };
+
+    if word.ends_with('e') && !word.ends_with("ee") && !ends_with_consonant_then("le") {
+        // Silent final 'e' ("make", "whale"), unless it forms "-le" after a consonant ("table")
+        syllables -= 1;
+    } else if word.ends_with("ely") {
+        // Silent 'e' before "-ly": "lonely", "safely"
+        syllables -= 1;
+    } else if word.ends_with("ed") && !word.ends_with("ted") && !word.ends_with("ded") && ends_with_consonant_then("ed") {
+        // "-ed" is only voiced after 't' or 'd': "jumped" vs "wanted"
+        syllables -= 1;
+    } else if word.ends_with("es") && ends_with_consonant_then("es") && !sibilant_plural(&word) {
+        // "-es" is only voiced after a sibilant: "makes" vs "boxes"
+        syllables -= 1;
+    }
+
+    syllables.max(1) as usize
+}
+
+fn sibilant_plural(word: &str) -> bool {
+    ["ses", "xes", "zes", "ches", "shes", "ces", "ges"]
+        .iter()
+        .any(|suffix| word.ends_with(suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syllable_counts() {
+        let expected = [
+            ("cat", 1), ("make", 1), ("whale", 1), ("table", 2), ("little", 2), ("creature", 2),
+            ("reason", 2), ("about", 2), ("rain", 1), ("lion", 2), ("video", 3), ("nation", 2),
+            ("jumped", 1), ("wanted", 2), ("makes", 1), ("boxes", 2), ("lonely", 2), ("yellow", 2),
+            ("happy", 2), ("australian", 4), ("reptilian", 4), ("seemingly", 3), ("platypus", 3),
+        ];
+        for (word, syllables) in expected {
+            assert_eq!(count_syllables(word), syllables, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_abbreviations_do_not_split_sentences() {
+        assert_eq!(count_sentences("Dr. Smith moved to the U.S.A. last year. He likes it."), 2);
+        assert_eq!(count_sentences("Bring fruit, e.g. apples, etc. and bread!"), 1);
+        assert_eq!(count_sentences("Wait... what?! No way"), 3);
+        assert_eq!(count_sentences(""), 0);
+    }
+
+    #[test]
+    fn test_reference_scores() {
+        // Reference sentence for Flesch-Kincaid Grade Level 13.1 (13 words, 26 syllables)
+        let platypus = Readability::of_language(
+            "The Australian platypus is seemingly a hybrid of a mammal and reptilian creature.",
+            "eng",
+        );
+        assert!((platypus.reading_ease - 24.44).abs() < 0.01);
+        assert!((platypus.grade_level - 13.08).abs() < 0.01);
+
+        // 13 words, 2 sentences, 16 syllables
+        let fox = Readability::of_language("The quick brown fox jumps over the lazy dog. It was not amused.", "eng");
+        assert!((fox.reading_ease - 96.11).abs() < 0.01);
+        assert!((fox.grade_level - 1.47).abs() < 0.01);
+
+        let simple = Readability::of_language("The cat sat on the mat.", "eng");
+        assert_eq!((simple.normalized(), simple.grade_level), (1.0, 0.0));
+        assert_eq!(Readability::of_language("  ", "eng").reading_ease, 0.0);
+    }
+
+    #[test]
+    fn test_spanish_and_german_scores() {
+        let simple_spanish = Readability::of_language("El gato duerme en la casa. Los niños juegan en el parque.", "spa");
+        let dense_spanish = Readability::of_language(
+            "La implementación de procedimientos administrativos extraordinariamente complejos \
+             obstaculiza considerablemente la participación ciudadana institucionalizada.",
+            "spa",
+        );
+        assert!(simple_spanish.reading_ease > 90.0, "{:?}", simple_spanish);
+        assert!(dense_spanish.reading_ease < 30.0, "{:?}", dense_spanish);
+
+        let simple_german = Readability::of_language("Der Hund spielt im Garten. Die Kinder lachen laut.", "deu");
+        let dense_german = Readability::of_language(
+            "Die Verantwortlichkeitsverteilung innerhalb der Bundesverwaltungsbehörde erfordert \
+             umfangreiche Koordinationsmaßnahmen zwischen verschiedenen Zuständigkeitsbereichen.",
+            "deu",
+        );
+        assert!((simple_german.reading_ease - 97.5).abs() < 0.01, "{:?}", simple_german);
+        assert!(dense_german.reading_ease < 20.0, "{:?}", dense_german);
+
+        assert_eq!(count_syllables_in("Verantwortlichkeitsverteilung", "deu"), 8);
+        assert_eq!(count_syllables_in("niños", "spa"), 2);
+    }
+}
@@ -0,0 +1,66 @@
+use dashmap::DashSet;
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::models::echo_index::EchoIndexCalculator;
+use crate::services::batch_jobs::{recalculate, RecalculationContext};
+
+/// Content ids that can wait for recalculation before new ones are turned away
+pub const RECALCULATION_QUEUE_CAPACITY: usize = 10_000;
+
+/// Background Echo Index recalculation for content whose propagations changed.
+/// Each content item is queued at most once until its recalculation starts.
+pub struct RecalculationQueue {
+    sender: mpsc::Sender<Uuid>,
+    pending: Arc<DashSet<Uuid>>,
+}
+
+impl RecalculationQueue {
+    /// Start the worker. The calculator is read for every item, so runtime
+    /// reconfiguration applies to recalculations that are already queued.
+    pub fn spawn(
+        context: RecalculationContext,
+        calculator: Arc<RwLock<EchoIndexCalculator>>,
+        capacity: usize,
+    ) -> (Self, JoinHandle<()>) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let pending = Arc::new(DashSet::new());
+        let worker = tokio::spawn(Self::run(receiver, Arc::clone(&pending), context, calculator));
+        (Self { sender, pending }, worker)
+    }
+
+    /// Queue a recalculation, returning false when the queue is full
+    pub fn enqueue(&self, content_id: Uuid) -> bool {
+        // Already waiting, and its recalculation has not read the new propagations yet
+        if !self.pending.insert(content_id) {
+            return true;
+        }
+        if let Err(e) = self.sender.try_send(content_id) {
+            self.pending.remove(&content_id);
+            log::warn!("Echo Index recalculation of {} not queued: {}", content_id, e);
+            return false;
+        }
+        true
+    }
+
+    async fn run(
+        mut receiver: mpsc::Receiver<Uuid>,
+        pending: Arc<DashSet<Uuid>>,
+        mut context: RecalculationContext,
+        calculator: Arc<RwLock<EchoIndexCalculator>>,
+    ) {
+        while let Some(content_id) = receiver.recv().await {
+            pending.remove(&content_id);
+            match calculator.read() {
+                Ok(calculator) => context.calculator = calculator.clone(),
+                Err(_) => log::warn!("Calculator lock poisoned; recalculating with the previous configuration"),
+            }
+            // New propagations invalidate a recent score, so freshness is not checked
+            if let Err(e) = recalculate(&context, content_id, true).await {
+                log::warn!("Queued recalculation of {} failed: {}", content_id, e);
+            }
+        }
+    }
+}
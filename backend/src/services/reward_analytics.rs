@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::reward_analytics::{AnalyticsBucket, Granularity};
+use crate::repositories::{RepositoryError, RewardAnalyticsRepository};
+
+/// Most buckets one time series may span, e.g. 60 days of hourly buckets
+pub const MAX_ANALYTICS_BUCKETS: i64 = 1_500;
+
+/// Reward distribution over time, for the admin dashboard and users' own analytics
+pub struct RewardAnalyticsService {
+    repository: Arc<RewardAnalyticsRepository>,
+}
+
+impl RewardAnalyticsService {
+    pub fn new(repository: Arc<RewardAnalyticsRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Rewards distributed between `since` and `until` in buckets of `granularity`, to
+    /// everyone or to one user. Buckets without rewards are left out.
+    pub async fn time_series(
+        &self,
+        user_id: Option<Uuid>,
+        granularity: Granularity,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<AnalyticsBucket>, RepositoryError> {
+        let buckets = (until - since).num_seconds() / granularity.width().num_seconds();
+        if buckets > MAX_ANALYTICS_BUCKETS {
+            return Err(RepositoryError::InvalidInput(format!(
+                "the range spans {} {} buckets, more than the {} allowed",
+                buckets,
+                granularity.as_str(),
+                MAX_ANALYTICS_BUCKETS
+            )));
+        }
+        self.repository.time_series(user_id, granularity, since, until).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::rewards::RewardType;
+    use chrono::{Duration, TimeZone};
+    use sqlx::PgPool;
+
+    async fn reward(pool: &PgPool, user_id: Uuid, reward_type: &str, amount: f64, at: DateTime<Utc>) {
+        sqlx::query(
+            "INSERT INTO user_events (user_id, event_type, payload, created_at)
+             VALUES ($1, 'reward_earned', $2, $3)",
+        )
+        .bind(user_id)
+        .bind(serde_json::json!({
+            "event_type": "reward_earned",
+            "amount": amount,
+            "reward_type": reward_type,
+            "content_id": "content_1",
+        }))
+        .bind(at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_rewards_over_three_days_make_three_day_buckets(pool: PgPool) {
+        let users: Vec<Uuid> = sqlx::query_scalar(
+            "INSERT INTO users (wallet_address) SELECT '0xrecipient' || n FROM generate_series(1, 3) n RETURNING id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        // Ten rewards a day; the third user is first rewarded on the second day
+        for day in 0..3 {
+            for i in 0..10 {
+                let user = users[i % if day == 0 { 2 } else { 3 }];
+                let reward_type = if i % 2 == 0 { "QualityBonus" } else { "PropagationBonus" };
+                let at = start + Duration::days(day) + Duration::hours(i as i64 * 2);
+                reward(&pool, user, reward_type, 1.0 + day as f64, at).await;
+            }
+        }
+        sqlx::query("INSERT INTO reward_pool_utilization (day, pool_size, utilized) VALUES ('2024-01-02', 100, 20)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let service = RewardAnalyticsService::new(Arc::new(RewardAnalyticsRepository::new(pool)));
+        let until = start + Duration::days(31);
+        let buckets = service.time_series(None, Granularity::Day, start, until).await.unwrap();
+        assert_eq!(buckets.len(), 3);
+        assert_eq!(
+            buckets.iter().map(|b| b.bucket_start).collect::<Vec<_>>(),
+            vec![start, start + Duration::days(1), start + Duration::days(2)]
+        );
+        assert_eq!(
+            buckets.iter().map(|b| b.total_distributed).collect::<Vec<_>>(),
+            vec![10.0, 20.0, 30.0]
+        );
+        assert_eq!(buckets[1].by_type[&RewardType::QualityBonus], 10.0);
+        assert_eq!(buckets[1].by_type[&RewardType::PropagationBonus], 10.0);
+        assert_eq!(buckets.iter().map(|b| b.unique_recipients).collect::<Vec<_>>(), vec![2, 3, 3]);
+        assert_eq!(buckets.iter().map(|b| b.new_users_rewarded).collect::<Vec<_>>(), vec![2, 1, 0]);
+        assert_eq!(buckets.iter().map(|b| b.pool_utilization_pct).collect::<Vec<_>>(), vec![0.0, 20.0, 0.0]);
+        assert_eq!(
+            buckets.iter().map(|b| b.rolling_7d_avg).collect::<Vec<_>>(),
+            vec![10.0 / 7.0, 30.0 / 7.0, 60.0 / 7.0]
+        );
+
+        // Starting later, the rolling average still counts the days before
+        let later = service.time_series(None, Granularity::Day, start + Duration::days(2), until).await.unwrap();
+        assert_eq!(later.len(), 1);
+        assert_eq!(later[0].rolling_7d_avg, 60.0 / 7.0);
+
+        let hourly = service.time_series(Some(users[2]), Granularity::Hour, start, until).await.unwrap();
+        assert_eq!(hourly.len(), 6);
+        assert!(hourly.iter().all(|b| b.unique_recipients == 1));
+        let weekly = service.time_series(None, Granularity::Week, start, until).await.unwrap();
+        assert_eq!(weekly.len(), 1);
+        assert_eq!(weekly[0].total_distributed, 60.0);
+
+        let too_long = service.time_series(None, Granularity::Hour, start, start + Duration::days(90)).await;
+        assert!(matches!(too_long, Err(RepositoryError::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,325 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::repositories::{ContentRepository, EchoIndexHistoryRepository, RepositoryError};
+use crate::services::RewardService;
+
+pub const DEFAULT_HORIZON_HOURS: u32 = 24;
+pub const MAX_HORIZON_HOURS: u32 = 24 * 30;
+/// Most recent calculations a forecast is fitted to
+const SERIES_LENGTH: i64 = 200;
+/// Weight of each new calculation in the smoothed level, and of each new slope in the
+/// smoothed trend
+const LEVEL_SMOOTHING: f64 = 0.5;
+const TREND_SMOOTHING: f64 = 0.3;
+/// Calculations closer together than this count as this far apart, so a burst of
+/// recalculations cannot blow up the trend
+const MIN_STEP_HOURS: f64 = 1.0 / 60.0;
+const MAX_ECHO_INDEX: f64 = 100.0;
+/// Hours until the daily pool is refilled, at the latest
+const POOL_RESET_HOURS: u32 = 24;
+const Z_95: f64 = 1.96;
+
+/// How a forecast was made
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastBasis {
+    /// Fewer than two calculations; the latest score is assumed to hold
+    TooEarlyToPredict,
+    /// The Echo Index is holding or growing, and its trend is extended
+    LinearExtrapolation,
+    /// The Echo Index is falling, and decays toward 0 from its current rate of decline
+    ExponentialDecay,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RewardForecast {
+    pub predicted_echo_index_at_horizon: f64,
+    /// EchoDrops the content is expected to earn over the horizon
+    pub predicted_total_rewards: f64,
+    /// 95% interval of the Echo Index at the horizon
+    pub confidence_interval: (f64, f64),
+    pub prediction_basis: ForecastBasis,
+}
+
+/// Forecast of one content item in a user's earnings forecast
+#[derive(Debug, Clone, Serialize)]
+pub struct ContentForecast {
+    pub content_id: Uuid,
+    #[serde(flatten)]
+    pub forecast: RewardForecast,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EarningsForecast {
+    pub user_id: Uuid,
+    pub horizon_hours: u32,
+    pub predicted_total_rewards: f64,
+    pub content: Vec<ContentForecast>,
+}
+
+/// Holt's linear exponential smoothing of an Echo Index series, with the trend kept
+/// per hour since calculations are not evenly spaced
+#[derive(Debug, Clone, Copy)]
+pub struct EchoIndexModel {
+    level: f64,
+    /// Change of the level per hour
+    trend: f64,
+    /// Standard deviation of the one-step-ahead errors; `None` until there are any
+    residual_std: Option<f64>,
+    mean_step_hours: f64,
+    basis: ForecastBasis,
+}
+
+impl EchoIndexModel {
+    /// Fit the model to `(calculated_at, score)` pairs, oldest first
+    pub fn fit(series: &[(DateTime<Utc>, f64)]) -> Self {
+        let [(first_at, first), (second_at, second), ..] = series else {
+            return Self {
+                level: series.last().map_or(0.0, |(_, score)| *score),
+                trend: 0.0,
+                residual_std: None,
+                mean_step_hours: 1.0,
+                basis: ForecastBasis::TooEarlyToPredict,
+            };
+        };
+
+        let mut level = *first;
+        let mut trend = (second - first) / step_hours(*first_at, *second_at);
+        let mut squared_errors = 0.0;
+        for (i, window) in series.windows(2).enumerate() {
+            let (previous_at, (at, score)) = (window[0].0, window[1]);
+            let step = step_hours(previous_at, at);
+            let predicted = level + trend * step;
+            // The first step is the one the trend was initialized from
+            if i > 0 {
+                squared_errors += (score - predicted).powi(2);
+            }
+
+            let previous_level = level;
+            level = LEVEL_SMOOTHING * score + (1.0 - LEVEL_SMOOTHING) * predicted;
+            trend = TREND_SMOOTHING * (level - previous_level) / step + (1.0 - TREND_SMOOTHING) * trend;
+        }
+
+        let steps = series.len() - 1;
+        let residual_std = if steps > 1 {
+            (squared_errors / (steps - 1) as f64).sqrt()
+        } else {
+            // With a single step, all that is known is that the score moved this much
+            (second - first).abs()
+        };
+        let span = step_hours(*first_at, series[steps].0);
+        let basis = if trend < 0.0 {
+            ForecastBasis::ExponentialDecay
+        } else {
+            ForecastBasis::LinearExtrapolation
+        };
+
+        Self {
+            level,
+            trend,
+            residual_std: Some(residual_std),
+            mean_step_hours: (span / steps as f64).max(MIN_STEP_HOURS),
+            basis,
+        }
+    }
+
+    pub fn basis(&self) -> ForecastBasis {
+        self.basis
+    }
+
+    /// Echo Index expected `hours` after the latest calculation
+    pub fn predict(&self, hours: f64) -> f64 {
+        let predicted = match self.basis {
+            ForecastBasis::ExponentialDecay if self.level > 0.0 => {
+                // Decays at the current rate of decline, so it never crosses 0
+                let decay_rate = -self.trend / self.level;
+                self.level * (-decay_rate * hours).exp()
+            }
+            ForecastBasis::ExponentialDecay => 0.0,
+            _ => self.level + self.trend * hours,
+        };
+        predicted.clamp(0.0, MAX_ECHO_INDEX)
+    }
+
+    /// 95% interval of the Echo Index `hours` after the latest calculation, widening
+    /// with the number of calculations between now and then
+    pub fn interval(&self, hours: f64) -> (f64, f64) {
+        let Some(residual_std) = self.residual_std else {
+            return (0.0, MAX_ECHO_INDEX);
+        };
+        let center = self.predict(hours);
+        let steps_ahead = hours / self.mean_step_hours;
+        let half_width = Z_95 * residual_std * (1.0 + steps_ahead).sqrt();
+        ((center - half_width).max(0.0), (center + half_width).min(MAX_ECHO_INDEX))
+    }
+}
+
+fn step_hours(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    ((to - from).num_milliseconds() as f64 / 3_600_000.0).max(MIN_STEP_HOURS)
+}
+
+/// Forecast the Echo Index of content `horizon_hours` ahead and what it earns until
+/// then. Content earns `base_rate` EchoDrops per Echo Index point per day, as it does
+/// when created; until the daily pool is refilled only the share of it still
+/// unclaimed at `pool_utilization` is available.
+pub fn forecast(
+    series: &[(DateTime<Utc>, f64)],
+    horizon_hours: u32,
+    base_rate: f64,
+    pool_utilization: f64,
+) -> RewardForecast {
+    let model = EchoIndexModel::fit(series);
+    let availability = if pool_utilization.is_finite() {
+        (1.0 - pool_utilization).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    // Trapezoidal integral of the Echo Index, hour by hour
+    let predicted_total_rewards = (0..horizon_hours)
+        .map(|hour| {
+            let echo_index_hours = (model.predict(hour as f64) + model.predict(hour as f64 + 1.0)) / 2.0;
+            let available = if hour < POOL_RESET_HOURS { availability } else { 1.0 };
+            echo_index_hours * base_rate / 24.0 * available
+        })
+        .sum();
+
+    let horizon = horizon_hours as f64;
+    RewardForecast {
+        predicted_echo_index_at_horizon: model.predict(horizon),
+        predicted_total_rewards,
+        confidence_interval: model.interval(horizon),
+        prediction_basis: model.basis(),
+    }
+}
+
+/// Predicts EchoDrop earnings of content still gathering propagations from the trend
+/// of its Echo Index and the state of the daily reward pool
+pub struct RewardForecastService {
+    history: Arc<EchoIndexHistoryRepository>,
+    content: Arc<ContentRepository>,
+    rewards: Arc<RwLock<RewardService>>,
+}
+
+impl RewardForecastService {
+    pub fn new(
+        history: Arc<EchoIndexHistoryRepository>,
+        content: Arc<ContentRepository>,
+        rewards: Arc<RwLock<RewardService>>,
+    ) -> Self {
+        Self { history, content, rewards }
+    }
+
+    /// `NotFound` if the content does not exist or was deleted
+    pub async fn forecast(&self, content_id: Uuid, horizon_hours: u32) -> Result<RewardForecast, RepositoryError> {
+        self.content.find_by_id(content_id).await?;
+        let (base_rate, pool_utilization) = self.pool().await;
+        let series = self.history.series(content_id, SERIES_LENGTH).await?;
+        Ok(forecast(&series, horizon_hours, base_rate, pool_utilization))
+    }
+
+    /// Forecasts of all the user's active content and their combined earnings
+    pub async fn earnings_forecast(
+        &self,
+        user_id: Uuid,
+        horizon_hours: u32,
+    ) -> Result<EarningsForecast, RepositoryError> {
+        let (base_rate, pool_utilization) = self.pool().await;
+
+        let mut content = Vec::new();
+        for record in self.content.list_by_author(user_id).await? {
+            if record.status != "active" {
+                continue;
+            }
+            let series = self.history.series(record.id, SERIES_LENGTH).await?;
+            content.push(ContentForecast {
+                content_id: record.id,
+                forecast: forecast(&series, horizon_hours, base_rate, pool_utilization),
+            });
+        }
+
+        Ok(EarningsForecast {
+            user_id,
+            horizon_hours,
+            predicted_total_rewards: content.iter().map(|c| c.forecast.predicted_total_rewards).sum(),
+            content,
+        })
+    }
+
+    async fn pool(&self) -> (f64, f64) {
+        let rewards = self.rewards.read().await;
+        let (_, _, utilization) = rewards.get_pool_status();
+        (rewards.base_rate(), utilization)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn hourly(scores: impl IntoIterator<Item = f64>) -> Vec<(DateTime<Utc>, f64)> {
+        let start = Utc::now() - Duration::days(7);
+        scores
+            .into_iter()
+            .enumerate()
+            .map(|(hour, score)| (start + Duration::hours(hour as i64), score))
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_growth_is_extrapolated() {
+        // 10 at the first calculation, 2 points more every hour; 28 at the latest
+        let series = hourly((0..10).map(|hour| 10.0 + 2.0 * hour as f64));
+        let forecast = forecast(&series, 24, 1.0, 0.0);
+
+        assert_eq!(forecast.prediction_basis, ForecastBasis::LinearExtrapolation);
+        assert!((forecast.predicted_echo_index_at_horizon - 76.0).abs() < 1e-9);
+        // A perfect fit leaves no uncertainty
+        let (low, high) = forecast.confidence_interval;
+        assert!((low - 76.0).abs() < 1e-9 && (high - 76.0).abs() < 1e-9);
+        // The mean Echo Index over the day is 52, earned at 1 EchoDrop per point per day
+        assert!((forecast.predicted_total_rewards - 52.0).abs() < 1e-9);
+
+        // The pool is only half available until it is refilled
+        let half_pool = super::forecast(&series, 24, 1.0, 0.5);
+        assert!((half_pool.predicted_total_rewards - 26.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_decay_converges_toward_zero() {
+        let series = hourly((0..20).map(|hour| 80.0 * (-0.1 * hour as f64).exp()));
+        let model = EchoIndexModel::fit(&series);
+        assert_eq!(model.basis(), ForecastBasis::ExponentialDecay);
+
+        let latest = series.last().unwrap().1;
+        let predictions: Vec<f64> = [0.0, 6.0, 24.0, 72.0, 240.0, 1000.0]
+            .iter()
+            .map(|&hours| model.predict(hours))
+            .collect();
+        assert!(predictions[0] <= latest * 1.1);
+        assert!(predictions.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(predictions.iter().all(|&prediction| prediction > 0.0));
+        assert!(predictions[5] < 1e-3);
+
+        let (low, high) = model.interval(24.0);
+        assert!(low >= 0.0 && low <= predictions[2] && predictions[2] <= high);
+    }
+
+    #[test]
+    fn test_too_early_to_predict() {
+        let forecast = forecast(&hourly([42.0]), 24, 1.0, 0.0);
+        assert_eq!(forecast.prediction_basis, ForecastBasis::TooEarlyToPredict);
+        assert_eq!(forecast.predicted_echo_index_at_horizon, 42.0);
+        assert_eq!(forecast.confidence_interval, (0.0, MAX_ECHO_INDEX));
+        assert!((forecast.predicted_total_rewards - 42.0).abs() < 1e-9);
+
+        let unscored = super::forecast(&[], 24, 1.0, 0.0);
+        assert_eq!(unscored.prediction_basis, ForecastBasis::TooEarlyToPredict);
+        assert_eq!(unscored.predicted_total_rewards, 0.0);
+    }
+}
@@ -1,13 +1,36 @@
 use crate::services::rewards::{RewardsService, RewardType, EchoDropReward};
 use crate::services::echo_engine::{EchoEngine, EchoMetrics};
+use crate::services::language::{detect_language, Lexicon};
+use crate::services::content_normalizer::{ContentNormalizer, PlatformNormalizer};
+use crate::services::link_quality::{LinkQualityAnalyzer, LinkQualityReport};
+use crate::services::readability::Readability;
+use crate::services::suspicion::SuspicionReport;
+use crate::services::streaks::StreakService;
+use crate::services::tier_progression::UserTierProgressionService;
+use crate::services::webhooks::WebhookDispatcher;
+use crate::models::Platform;
+use crate::models::content::{ContentRecord, ReactionType};
+use crate::models::user_event::UserEvent;
+use crate::models::webhook::WebhookEvent;
+use crate::repositories::UserEventRepository;
 use std::collections::HashMap;
+use std::sync::Arc;
 use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Credibility, relevance and originality of content nothing is known about yet
+const NEUTRAL_SCORE: f64 = 0.5;
 
 pub struct RewardService {
     rewards_engine: RewardsService,
     echo_engine: EchoEngine,
     user_engagement_cache: HashMap<String, f64>,
     content_metrics_cache: HashMap<String, EchoMetrics>,
+    events: Option<Arc<UserEventRepository>>,
+    streaks: Option<Arc<StreakService>>,
+    webhooks: Option<Arc<WebhookDispatcher>>,
+    tiers: Option<Arc<UserTierProgressionService>>,
 }
 
 impl RewardService {
@@ -17,7 +40,128 @@ impl RewardService {
             echo_engine: EchoEngine::default(),
             user_engagement_cache: HashMap::new(),
             content_metrics_cache: HashMap::new(),
+            events: None,
+            streaks: None,
+            webhooks: None,
+            tiers: None,
+        }
+    }
+
+    /// Record awarded rewards on the recipients' timelines
+    pub fn with_event_repository(mut self, events: Arc<UserEventRepository>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Track content creation streaks and multiply creation rewards by them
+    pub fn with_streak_service(mut self, streaks: Arc<StreakService>) -> Self {
+        self.streaks = Some(streaks);
+        self
+    }
+
+    /// Notify recipients' webhooks of awarded rewards
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookDispatcher>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Add awarded rewards to the recipients' totals, moving them up the tiers, and
+    /// withhold the propagation fee of each propagator's tier
+    pub fn with_tier_progression(mut self, tiers: Arc<UserTierProgressionService>) -> Self {
+        self.tiers = Some(tiers);
+        self
+    }
+
+    /// Defer rewards to the next day's pool once less than `reserve` of the daily pool remains
+    pub fn with_emergency_reserve(mut self, reserve: f64) -> Self {
+        self.rewards_engine.set_emergency_reserve(reserve);
+        self
+    }
+
+    /// Resume with the pending rewards a previous run checkpointed at shutdown
+    pub fn with_restored_rewards(mut self, rewards: Vec<EchoDropReward>) -> Self {
+        self.rewards_engine.restore_pending_rewards(rewards);
+        self
+    }
+
+    /// Count today's content creation towards the creator's streak and return the reward
+    /// multiplier the streak earns. Streak failures leave the reward unmultiplied.
+    async fn streak_multiplier(&self, user_id: &str) -> f64 {
+        let (Some(streaks), Ok(user_id)) = (&self.streaks, Uuid::parse_str(user_id)) else {
+            return 1.0;
+        };
+
+        match streaks.record_activity(user_id, Utc::now().date_naive()).await {
+            Ok(streak) => streak.multiplier(),
+            Err(e) => {
+                log::warn!("Failed to record streak activity for {}: {}", user_id, e);
+                1.0
+            }
+        }
+    }
+
+    /// Share of a propagation reward withheld from the propagator; none without tiers
+    async fn propagation_fee(&self, user_id: &str) -> f64 {
+        match (&self.tiers, Uuid::parse_str(user_id)) {
+            (Some(tiers), Ok(user_id)) => tiers.propagation_fee(user_id).await,
+            _ => 0.0,
+        }
+    }
+
+    /// Award a reward, add it to the recipient's timeline and totals and notify their
+    /// webhooks. Held rewards stay off the timeline, totals and webhooks, as do recipients
+    /// whose ids are not user UUIDs.
+    async fn award(
+        &mut self,
+        user_id: String,
+        content_id: String,
+        reward_type: RewardType,
+        amount: f64,
+        echo_index_contribution: f64,
+    ) -> Result<String, String> {
+        let timeline_user = Uuid::parse_str(&user_id).ok();
+        let webhook_data = serde_json::json!({
+            "content_id": content_id,
+            "reward_type": reward_type,
+            "amount": amount,
+        });
+        let event = UserEvent::RewardEarned {
+            amount,
+            reward_type: reward_type.clone(),
+            content_id: content_id.clone(),
+        };
+
+        let reward_id = self.rewards_engine.award_reward(
+            user_id,
+            content_id,
+            reward_type,
+            amount,
+            echo_index_contribution,
+        )?;
+
+        let Some(user_id) = timeline_user else {
+            return Ok(reward_id);
+        };
+        if self.rewards_engine.is_on_hold(&reward_id) {
+            return Ok(reward_id);
+        }
+        if let Some(events) = &self.events {
+            if let Err(e) = events.record(user_id, &event).await {
+                log::warn!("Failed to record reward {} on timeline of {}: {}", reward_id, user_id, e);
+            }
+        }
+        if let Some(webhooks) = &self.webhooks {
+            let mut data = webhook_data;
+            data["reward_id"] = serde_json::Value::String(reward_id.clone());
+            webhooks.notify(user_id, WebhookEvent::RewardAwarded, data);
+        }
+        if let Some(tiers) = &self.tiers {
+            if let Err(e) = tiers.add_rewards(user_id, amount).await {
+                log::warn!("Failed to add reward {} to the total of {}: {}", reward_id, user_id, e);
+            }
         }
+
+        Ok(reward_id)
     }
 
     /// Process content creation and award appropriate rewards
@@ -29,10 +173,11 @@ impl RewardService {
     ) -> Result<String, String> {
         // Calculate Echo Index for new content
         let (echo_index, metrics) = self.echo_engine.calculate_complete_echo_index(
+            &content_data.platform,
             0, // No shares initially
             0, // No total shares initially
             content_data.estimated_reach,
-            &HashMap::new(), // No engagement initially
+            &HashMap::new(), // No reactions initially
             0.0, // No view time initially
             0, // No views initially
             content_data.creation_timestamp,
@@ -42,25 +187,26 @@ impl RewardService {
             content_data.credibility_score,
             content_data.relevance_score,
             content_data.originality_score,
+            &content_data.links,
         );
 
         // Cache the metrics
         self.content_metrics_cache.insert(content_id.clone(), metrics);
 
-        // Calculate and award creation reward
+        // Calculate and award creation reward, boosted by the creator's daily streak
         let reward_amount = self.rewards_engine.calculate_content_creation_reward(
             echo_index,
             content_data.quality_score,
             content_data.initial_engagement,
-        );
+        ) * self.streak_multiplier(&user_id).await;
 
-        let reward_id = self.rewards_engine.award_reward(
+        let reward_id = self.award(
             user_id,
             content_id,
             RewardType::ContentCreation,
             reward_amount,
             echo_index,
-        )?;
+        ).await?;
 
         Ok(reward_id)
     }
@@ -95,26 +241,27 @@ impl RewardService {
             propagation_data.loop_strength,
         );
 
-        // Award propagation reward to propagator
-        let propagator_reward_id = self.rewards_engine.award_reward(
-            propagator_user_id,
+        // Award propagation reward to propagator, less the fee of their tier
+        let propagator_fee = self.propagation_fee(&propagator_user_id).await;
+        let propagator_reward_id = self.award(
+            propagator_user_id.clone(),
             original_content_id.clone(),
             RewardType::PropagationBonus,
-            propagation_reward,
+            propagation_reward * (1.0 - propagator_fee),
             original_echo_index * propagation_data.propagation_weight,
-        )?;
+        ).await?;
         reward_ids.push(propagator_reward_id);
 
         // Award smaller reward to original creator if different user
         if propagation_data.original_creator_id != propagator_user_id {
             let creator_reward = propagation_reward * 0.3; // 30% to original creator
-            let creator_reward_id = self.rewards_engine.award_reward(
+            let creator_reward_id = self.award(
                 propagation_data.original_creator_id,
                 original_content_id,
                 RewardType::EchoLoopParticipation,
                 creator_reward,
                 original_echo_index * 0.1,
-            )?;
+            ).await?;
             reward_ids.push(creator_reward_id);
         }
 
@@ -151,13 +298,13 @@ impl RewardService {
             discoverer_influence,
         );
 
-        let reward_id = self.rewards_engine.award_reward(
+        let reward_id = self.award(
             discoverer_user_id,
             discovered_content_id,
             RewardType::DiscoveryBonus,
             discovery_bonus,
             content_echo_index * 0.1,
-        )?;
+        ).await?;
 
         Ok(reward_id)
     }
@@ -175,10 +322,11 @@ impl RewardService {
     ) -> Result<f64, String> {
         // Recalculate Echo Index with updated data
         let (new_echo_index, new_metrics) = self.echo_engine.calculate_complete_echo_index(
+            &updated_data.platform,
             updated_data.shares_from_discovery,
             updated_data.total_shares,
             updated_data.platform_reach,
-            &updated_data.engagement_metrics,
+            &updated_data.reactions,
             updated_data.avg_view_time,
             updated_data.total_views,
             updated_data.creation_timestamp,
@@ -188,6 +336,7 @@ impl RewardService {
             updated_data.credibility_score,
             updated_data.relevance_score,
             updated_data.originality_score,
+            &updated_data.links,
         );
 
         // Update cache
@@ -196,9 +345,14 @@ impl RewardService {
         Ok(new_echo_index)
     }
 
-    /// Process pending rewards for a user
-    pub async fn process_user_rewards(&mut self, user_id: &str) -> Result<Vec<EchoDropReward>, String> {
-        self.rewards_engine.process_pending_rewards(user_id)
+    /// Release a user's rewards vested by `as_of`, paid out by the transaction `transaction_hash`
+    pub async fn process_user_rewards(
+        &mut self,
+        user_id: &str,
+        as_of: DateTime<Utc>,
+        transaction_hash: &str,
+    ) -> Result<Vec<EchoDropReward>, String> {
+        self.rewards_engine.process_pending_rewards(user_id, as_of, transaction_hash)
     }
 
     /// Get user's total rewards
@@ -206,12 +360,57 @@ impl RewardService {
         self.rewards_engine.get_user_total_rewards(user_id)
     }
 
+    /// Get every reward awarded to a user, outstanding or released
+    pub fn get_user_rewards(&self, user_id: &str) -> Vec<EchoDropReward> {
+        self.rewards_engine.user_rewards(user_id)
+    }
+
     /// Get user's pending rewards
     pub fn get_user_pending_rewards(&self, user_id: &str) -> f64 {
         self.rewards_engine.get_pending_rewards(user_id)
     }
 
-    /// Get leaderboard
+    /// Get the portion of a user's pending rewards that has vested by `as_of`
+    pub fn get_user_claimable_rewards(&self, user_id: &str, as_of: DateTime<Utc>) -> f64 {
+        self.rewards_engine.compute_claimable(user_id, as_of)
+    }
+
+    /// Every reward not fully released yet, for checkpointing at shutdown
+    pub fn pending_rewards(&self) -> Vec<EchoDropReward> {
+        self.rewards_engine.pending_rewards()
+    }
+
+    /// Get rewards held for review after suspicious activity
+    pub fn get_on_hold_rewards(&self) -> Vec<(EchoDropReward, Option<SuspicionReport>)> {
+        self.rewards_engine.on_hold_rewards()
+    }
+
+    /// Approve a held reward so it can be processed
+    pub fn approve_held_reward(&mut self, reward_id: &str) -> Result<(), String> {
+        self.rewards_engine.approve_held_reward(reward_id)
+    }
+
+    /// Reject a held reward, returning its amount to the daily pool
+    pub fn reject_held_reward(&mut self, reward_id: &str) -> Result<EchoDropReward, String> {
+        self.rewards_engine.reject_held_reward(reward_id)
+    }
+
+    /// Freeze the rewards of content removed by moderation
+    pub fn freeze_content_rewards(&mut self, content_id: &str) -> usize {
+        self.rewards_engine.freeze_content_rewards(content_id)
+    }
+
+    /// Hold the rewards of content whose Echo Index jumped anomalously
+    pub fn hold_content_rewards(&mut self, content_id: &str) -> usize {
+        self.rewards_engine.hold_content_rewards(content_id)
+    }
+
+    /// Release the rewards of content whose Echo Index anomalies were dismissed
+    pub fn release_content_rewards(&mut self, content_id: &str) -> usize {
+        self.rewards_engine.release_content_rewards(content_id)
+    }
+
+        /// Get leaderboard
     pub fn get_leaderboard(&mut self) -> Vec<(String, crate::services::rewards::UserRewardStats)> {
         self.rewards_engine.calculate_leaderboard()
     }
@@ -221,11 +420,21 @@ impl RewardService {
         self.rewards_engine.reset_daily_pool();
     }
 
+    /// Resize the daily pool; takes effect at the next reset
+    pub fn set_daily_pool(&mut self, daily_pool: f64) {
+        self.rewards_engine.set_daily_pool(daily_pool);
+    }
+
     /// Get pool status
     pub fn get_pool_status(&self) -> (f64, f64, f64) {
         self.rewards_engine.get_pool_status()
     }
 
+    /// EchoDrops earned per Echo Index point, before bonuses
+    pub fn base_rate(&self) -> f64 {
+        self.rewards_engine.base_rate()
+    }
+
     /// Award quality bonus for high-performing content
     pub async fn award_quality_bonus(
         &mut self,
@@ -233,30 +442,35 @@ impl RewardService {
         content_id: String,
         quality_metrics: QualityMetrics,
     ) -> Result<String, String> {
-        let bonus_multiplier = if quality_metrics.viral_coefficient > 2.0 {
-            2.0
-        } else if quality_metrics.engagement_rate > 0.8 {
-            1.5
-        } else if quality_metrics.retention_rate > 0.7 {
-            1.2
-        } else {
-            1.0
-        };
-
-        let base_bonus = quality_metrics.echo_index_improvement * 10.0;
-        let bonus_amount = base_bonus * bonus_multiplier;
-
-        let reward_id = self.rewards_engine.award_reward(
+        let reward_id = self.award(
             user_id,
             content_id,
             RewardType::QualityBonus,
-            bonus_amount,
+            quality_metrics.bonus_amount(),
             quality_metrics.echo_index_improvement,
-        )?;
+        ).await?;
 
         Ok(reward_id)
     }
 
+    /// Award a community contribution reward, such as the share of a propagation owed to
+    /// a user mentioned in the propagated content
+    pub async fn award_community_contribution(
+        &mut self,
+        user_id: String,
+        content_id: String,
+        amount: f64,
+        echo_index_contribution: f64,
+    ) -> Result<String, String> {
+        self.award(
+            user_id,
+            content_id,
+            RewardType::CommunityContribution,
+            amount,
+            echo_index_contribution,
+        ).await
+    }
+
     /// Get reward analytics
     pub fn get_reward_analytics(&self, since: DateTime<Utc>) -> crate::services::rewards::RewardAnalytics {
         self.rewards_engine.get_reward_analytics(since)
@@ -265,37 +479,62 @@ impl RewardService {
 
 #[derive(Debug)]
 pub struct ContentCreationData {
+    pub platform: Platform,
     pub creation_timestamp: i64,
     pub estimated_reach: u32,
     pub sentiment_score: f64,
     pub credibility_score: f64,
     pub relevance_score: f64,
     pub originality_score: f64,
+    /// Outbound links of the content, scored by `LinkQualityAnalyzer`
+    pub links: LinkQualityReport,
     pub quality_score: f64,
     pub initial_engagement: f64,
 }
 
+impl ContentCreationData {
+    /// Creation data of content just stored, before anyone has reached or engaged with it.
+    /// Sentiment, quality and outbound links are read from its text; scores it cannot tell
+    /// are neutral.
+    pub fn for_content(content: &ContentRecord) -> Self {
+        let language = detect_language(&content.body);
+        let urls = PlatformNormalizer::extract_urls(&content.body);
+        Self {
+            platform: content.platform.clone(),
+            creation_timestamp: content.created_at.timestamp(),
+            estimated_reach: 0,
+            sentiment_score: Lexicon::for_language(&language).sentiment(&content.body),
+            credibility_score: NEUTRAL_SCORE,
+            relevance_score: NEUTRAL_SCORE,
+            originality_score: NEUTRAL_SCORE,
+            links: LinkQualityAnalyzer::new(&content.platform).score_links(&urls),
+            quality_score: Readability::of_language(&content.body, &language).normalized(),
+            initial_engagement: 0.0,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct PropagationData {
     pub original_creator_id: String,
     pub propagation_weight: f64,
     pub loop_strength: f64,
-    pub platform_amplification: f64,
 }
 
 #[derive(Debug)]
 pub struct DiscoveryData {
     pub discovery_timing_factor: f64, // 0.0 = very late, 1.0 = very early
     pub discovery_method: String,
-    pub platform: String,
+    pub platform: Platform,
 }
 
 #[derive(Debug)]
 pub struct ContentUpdateData {
+    pub platform: Platform,
     pub shares_from_discovery: u32,
     pub total_shares: u32,
     pub platform_reach: u32,
-    pub engagement_metrics: HashMap<String, f64>,
+    pub reactions: HashMap<ReactionType, u64>,
     pub avg_view_time: f64,
     pub total_views: u32,
     pub creation_timestamp: i64,
@@ -305,13 +544,173 @@ pub struct ContentUpdateData {
     pub credibility_score: f64,
     pub relevance_score: f64,
     pub originality_score: f64,
+    pub links: LinkQualityReport,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct QualityMetrics {
     pub echo_index_improvement: f64,
     pub viral_coefficient: f64,
     pub engagement_rate: f64,
     pub retention_rate: f64,
     pub social_impact_score: f64,
-} 
\ No newline at end of file
+}
+
+impl QualityMetrics {
+    /// Quality bonus these metrics earn
+    pub fn bonus_amount(&self) -> f64 {
+        let bonus_multiplier = if self.viral_coefficient > 2.0 {
+            2.0
+        } else if self.engagement_rate > 0.8 {
+            1.5
+        } else if self.retention_rate > 0.7 {
+            1.2
+        } else {
+            1.0
+        };
+
+        self.echo_index_improvement * 10.0 * bonus_multiplier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::{StreakRepository, UserRepository};
+    use crate::services::tier_progression::TierConfig;
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_awarded_rewards_reach_timeline(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xrewarded') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let events = Arc::new(UserEventRepository::new(pool));
+        let mut service = RewardService::new(10_000.0).with_event_repository(events.clone());
+        let quality = || QualityMetrics {
+            echo_index_improvement: 0.5,
+            viral_coefficient: 1.0,
+            engagement_rate: 0.5,
+            retention_rate: 0.5,
+            social_impact_score: 0.5,
+        };
+
+        service.award_quality_bonus(user_id.to_string(), "content_1".to_string(), quality()).await.unwrap();
+        service.award_quality_bonus(user_id.to_string(), "content_2".to_string(), quality()).await.unwrap();
+        // Recipients without a user UUID have no timeline
+        service.award_quality_bonus("wallet_only".to_string(), "content_1".to_string(), quality()).await.unwrap();
+
+        let timeline = events.timeline(user_id, None, 50).await.unwrap();
+        assert_eq!(timeline.data.len(), 2);
+        assert!(matches!(
+            &timeline.data[0].event,
+            UserEvent::RewardEarned { content_id, reward_type: RewardType::QualityBonus, .. } if content_id == "content_2"
+        ));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_streak_multiplies_creation_reward(pool: PgPool) {
+        let mut users = Vec::new();
+        for wallet in ["0xsteady", "0xnewcomer"] {
+            let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+                .bind(wallet)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            users.push(user_id.to_string());
+        }
+
+        // Six days in a row up to yesterday; today's creation makes it seven
+        let repository = Arc::new(StreakRepository::new(pool));
+        let today = Utc::now().date_naive();
+        repository
+            .update(Uuid::parse_str(&users[0]).unwrap(), today, |streak| {
+                streak.current_streak_days = 6;
+                streak.last_activity_date = today.pred_opt().unwrap();
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        let streaks = Arc::new(StreakService::new(repository));
+        let mut service = RewardService::new(10_000.0).with_streak_service(streaks.clone());
+        let creation = || ContentCreationData {
+            platform: Platform::Twitter,
+            creation_timestamp: Utc::now().timestamp(),
+            estimated_reach: 1_000,
+            sentiment_score: 0.7,
+            credibility_score: 0.8,
+            relevance_score: 0.6,
+            originality_score: 0.9,
+            links: LinkQualityReport::default(),
+            quality_score: 0.8,
+            initial_engagement: 0.1,
+        };
+        for (i, user_id) in users.iter().enumerate() {
+            service
+                .process_content_creation(user_id.clone(), format!("content_{}", i), creation())
+                .await
+                .unwrap();
+        }
+
+        let steady = service.get_user_pending_rewards(&users[0]);
+        let newcomer = service.get_user_pending_rewards(&users[1]);
+        assert!((steady / newcomer - 1.25).abs() < 1e-9);
+
+        let streak = streaks.get(Uuid::parse_str(&users[1]).unwrap(), today).await.unwrap();
+        assert_eq!(streak.current_streak_days, 1);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_propagation_fee_falls_with_tier(pool: PgPool) {
+        let mut users = Vec::new();
+        for (wallet, tier) in [("0xcreator", "basic"), ("0xbasic", "basic"), ("0xgold", "gold")] {
+            let user_id: Uuid =
+                sqlx::query_scalar("INSERT INTO users (wallet_address, tier) VALUES ($1, $2::user_tier) RETURNING id")
+                    .bind(wallet)
+                    .bind(tier)
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            users.push(user_id.to_string());
+        }
+        let user_repository = Arc::new(UserRepository::new(pool.clone()));
+        let tiers = Arc::new(UserTierProgressionService::new(
+            user_repository.clone(),
+            Arc::new(UserEventRepository::new(pool)),
+            TierConfig::default(),
+        ));
+        let mut service = RewardService::new(10_000.0).with_tier_progression(tiers);
+        let creation = ContentCreationData {
+            platform: Platform::Twitter,
+            creation_timestamp: Utc::now().timestamp(),
+            estimated_reach: 1_000,
+            sentiment_score: 0.7,
+            credibility_score: 0.8,
+            relevance_score: 0.6,
+            originality_score: 0.9,
+            links: LinkQualityReport::default(),
+            quality_score: 0.8,
+            initial_engagement: 0.1,
+        };
+        service.process_content_creation(users[0].clone(), "content_1".to_string(), creation).await.unwrap();
+        for propagator in &users[1..] {
+            let propagation = PropagationData {
+                original_creator_id: users[0].clone(),
+                propagation_weight: 0.8,
+                loop_strength: 0.5,
+            };
+            service
+                .process_content_propagation(propagator.clone(), "content_1".to_string(), propagation)
+                .await
+                .unwrap();
+        }
+
+        let (basic, gold) = (service.get_user_pending_rewards(&users[1]), service.get_user_pending_rewards(&users[2]));
+        assert!((gold / basic - 0.975 / 0.9).abs() < 1e-9);
+        // Awarded rewards count towards the recipients' tiers
+        let propagator = user_repository.find_by_id(Uuid::parse_str(&users[2]).unwrap()).await.unwrap().unwrap();
+        assert!((propagator.total_rewards - gold).abs() < 1e-6);
+    }
+}
@@ -1,7 +1,30 @@
-use std::collections::HashMap;
-use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+use crate::services::suspicion::{SuspicionAnalyzer, SuspicionReport};
+
+/// Rewards at or above this Echo Index contribution vest instead of releasing at once
+const VESTING_ECHO_INDEX_THRESHOLD: f64 = 0.8;
+
+/// Vesting period applied to high-Echo rewards
+const HIGH_ECHO_VESTING_HOURS: u32 = 72;
+
+/// Rewards scoring above this risk are held for operator review
+const HOLD_RISK_THRESHOLD: f64 = 0.8;
+
+/// Window of a user's rewards considered by suspicious activity detection
+const SUSPICION_WINDOW_HOURS: i64 = 24;
+
+/// Share of the daily pool below which new rewards are deferred to the next day's pool
+pub const DEFAULT_POOL_EMERGENCY_RESERVE: f64 = 0.05;
+
+/// ID of the reward with the given UUID
+pub fn format_reward_id(uuid: uuid::Uuid) -> String {
+    format!("reward_{}", uuid)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EchoDropReward {
     pub id: String,
     pub user_id: String,
@@ -11,9 +34,73 @@ pub struct EchoDropReward {
     pub echo_index_contribution: f64,
     pub timestamp: DateTime<Utc>,
     pub transaction_hash: Option<String>,
+    pub vesting_schedule: VestingSchedule,
+    /// Portion of `amount` already released
+    pub vested_amount: f64,
+    pub vest_start: DateTime<Utc>,
+    /// Held for review after suspicious activity; not released until approved
+    pub on_hold: bool,
+    /// Earned by content a moderator removed; never released
+    pub frozen: bool,
 }
 
-#[derive(Debug, Clone)]
+impl EchoDropReward {
+    /// Amount that has vested by `as_of` but has not been released yet
+    pub fn claimable(&self, as_of: DateTime<Utc>) -> f64 {
+        // Nothing vests before vesting starts, even immediately
+        if as_of < self.vest_start {
+            return 0.0;
+        }
+        let vested = self.amount * self.vesting_schedule.vested_fraction(as_of - self.vest_start);
+        (vested - self.vested_amount).max(0.0)
+    }
+
+    fn is_releasable(&self) -> bool {
+        !self.on_hold && !self.frozen
+    }
+
+    fn is_fully_vested(&self) -> bool {
+        self.vested_amount >= self.amount
+    }
+}
+
+/// How an EchoDrop reward is released over time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum VestingSchedule {
+    Immediate,
+    Linear { duration_hours: u32 },
+    /// Nothing vests before the cliff, then the full amount vests linearly
+    Cliff { cliff_hours: u32, then_linear_hours: u32 },
+}
+
+impl VestingSchedule {
+    /// Fraction of the reward vested after `elapsed`, in 0.0..=1.0
+    pub fn vested_fraction(&self, elapsed: Duration) -> f64 {
+        let linear = |elapsed: Duration, hours: u32| {
+            if hours == 0 {
+                return 1.0;
+            }
+            let total = Duration::hours(hours as i64);
+            (elapsed.num_seconds() as f64 / total.num_seconds() as f64).clamp(0.0, 1.0)
+        };
+
+        match *self {
+            VestingSchedule::Immediate => 1.0,
+            VestingSchedule::Linear { duration_hours } => linear(elapsed, duration_hours),
+            VestingSchedule::Cliff { cliff_hours, then_linear_hours } => {
+                let after_cliff = elapsed - Duration::hours(cliff_hours as i64);
+                if after_cliff < Duration::zero() {
+                    0.0
+                } else {
+                    linear(after_cliff, then_linear_hours)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RewardType {
     ContentCreation,
     QualityBonus,
@@ -22,6 +109,9 @@ pub enum RewardType {
     EngagementReward,
     EchoLoopParticipation,
     CommunityContribution,
+    /// Awarded while the pool was down to its emergency reserve; charged against the
+    /// next day's pool instead
+    Deferred,
 }
 
 #[derive(Debug, Clone)]
@@ -45,7 +135,7 @@ impl Default for RewardMultiplier {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct UserRewardStats {
     pub total_earned: f64,
     pub content_rewards: f64,
@@ -63,6 +153,16 @@ pub struct RewardsService {
     user_stats: HashMap<String, UserRewardStats>,
     daily_pool: f64,
     current_pool_remaining: f64,
+    /// Share of `daily_pool` kept in reserve; rewards awarded below it are deferred
+    emergency_reserve: f64,
+    /// Deferred rewards, charged against the pool when it is next reset
+    deferred_pool_charge: f64,
+    /// Why each held reward was held, keyed by reward id
+    hold_reports: HashMap<String, SuspicionReport>,
+    /// Content removed by moderation, whose rewards are frozen
+    frozen_content: HashSet<String>,
+    /// Content whose Echo Index jumped anomalously, whose rewards are held until reviewed
+    held_content: HashSet<String>,
 }
 
 impl RewardsService {
@@ -74,9 +174,24 @@ impl RewardsService {
             user_stats: HashMap::new(),
             daily_pool,
             current_pool_remaining: daily_pool,
+            emergency_reserve: DEFAULT_POOL_EMERGENCY_RESERVE,
+            deferred_pool_charge: 0.0,
+            hold_reports: HashMap::new(),
+            frozen_content: HashSet::new(),
+            held_content: HashSet::new(),
         }
     }
 
+    /// Defer rewards once less than `reserve` of the daily pool remains
+    pub fn set_emergency_reserve(&mut self, reserve: f64) {
+        self.emergency_reserve = reserve.clamp(0.0, 1.0);
+    }
+
+    /// Whether the pool is down to its emergency reserve, so new rewards are deferred
+    pub fn is_pool_in_reserve(&self) -> bool {
+        self.current_pool_remaining < self.daily_pool * self.emergency_reserve
+    }
+
     /// Calculate reward for content creation
     pub fn calculate_content_creation_reward(
         &self,
@@ -128,7 +243,10 @@ impl RewardsService {
         base_discovery_reward * (1.0 + timing_bonus + influence_factor)
     }
 
-    /// Award reward to user
+    /// Award reward to user. Once the pool is down to its emergency reserve the reward is
+    /// recorded as `Deferred` and charged against the next day's pool instead, at most
+    /// a full day's pool of it.
+    #[tracing::instrument(skip(self), err)]
     pub fn award_reward(
         &mut self,
         user_id: String,
@@ -137,37 +255,220 @@ impl RewardsService {
         amount: f64,
         echo_index_contribution: f64,
     ) -> Result<String, String> {
-        if amount > self.current_pool_remaining {
+        let deferred = self.is_pool_in_reserve();
+        let available = if deferred {
+            self.daily_pool - self.deferred_pool_charge
+        } else {
+            self.current_pool_remaining
+        };
+        if amount > available {
             return Err("Daily reward pool exhausted".to_string());
         }
 
-        let reward_id = format!("reward_{}", uuid::Uuid::new_v4());
-        let reward = EchoDropReward {
+        let reward_id = format_reward_id(uuid::Uuid::new_v4());
+        let now = Utc::now();
+        let frozen = self.frozen_content.contains(&content_id);
+        let mut reward = EchoDropReward {
             id: reward_id.clone(),
             user_id: user_id.clone(),
             content_id,
-            reward_type: reward_type.clone(),
+            reward_type: if deferred { RewardType::Deferred } else { reward_type.clone() },
             amount,
             echo_index_contribution,
-            timestamp: Utc::now(),
+            timestamp: now,
             transaction_hash: None,
+            vesting_schedule: Self::vesting_schedule_for(echo_index_contribution),
+            vested_amount: 0.0,
+            vest_start: now,
+            on_hold: false,
+            frozen,
         };
 
+        // Hold rewards that look farmed until an operator reviews them
+        let mut recent = self.recent_rewards(&user_id, now - Duration::hours(SUSPICION_WINDOW_HOURS));
+        recent.push(reward.clone());
+        let report = SuspicionAnalyzer::default()
+            .with_platform_velocities(self.platform_velocities(now))
+            .analyze(&user_id, &recent);
+        if report.risk_score > HOLD_RISK_THRESHOLD {
+            log::warn!("Holding reward {} for {}: {:?}", reward_id, user_id, report.flags);
+            reward.on_hold = true;
+            reward.transaction_hash = None;
+            self.hold_reports.insert(reward_id.clone(), report);
+        } else if self.held_content.contains(&reward.content_id) {
+            reward.on_hold = true;
+            let report = SuspicionReport::echo_index_anomaly(&user_id, &reward.content_id);
+            self.hold_reports.insert(reward_id.clone(), report);
+        }
+
         // Add to pending rewards
         self.pending_rewards
             .entry(user_id.clone())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(reward);
 
         // Update user stats
         self.update_user_stats(&user_id, amount, &reward_type);
 
         // Reduce pool
-        self.current_pool_remaining -= amount;
+        if deferred {
+            self.deferred_pool_charge += amount;
+        } else {
+            self.current_pool_remaining -= amount;
+        }
 
         Ok(reward_id)
     }
 
+    /// A user's pending and processed rewards since `since`
+    fn recent_rewards(&self, user_id: &str, since: DateTime<Utc>) -> Vec<EchoDropReward> {
+        self.pending_rewards
+            .get(user_id)
+            .into_iter()
+            .chain(self.processed_rewards.get(user_id))
+            .flatten()
+            .filter(|r| r.timestamp >= since)
+            .cloned()
+            .collect()
+    }
+
+    /// Rewards per hour over the last day for every rewarded user
+    fn platform_velocities(&self, now: DateTime<Utc>) -> Vec<f64> {
+        let since = now - Duration::hours(SUSPICION_WINDOW_HOURS);
+        self.user_stats
+            .keys()
+            .map(|user_id| {
+                let earned: f64 = self.recent_rewards(user_id, since).iter().map(|r| r.amount).sum();
+                earned / SUSPICION_WINDOW_HOURS as f64
+            })
+            .collect()
+    }
+
+    /// Rewards currently held for review, with the report that caused each hold
+    pub fn on_hold_rewards(&self) -> Vec<(EchoDropReward, Option<SuspicionReport>)> {
+        self.pending_rewards
+            .values()
+            .flatten()
+            .filter(|r| r.on_hold)
+            .map(|r| (r.clone(), self.hold_reports.get(&r.id).cloned()))
+            .collect()
+    }
+
+    pub fn is_on_hold(&self, reward_id: &str) -> bool {
+        self.hold_reports.contains_key(reward_id)
+    }
+
+    /// Every reward not fully released yet, including held and frozen ones
+    pub fn pending_rewards(&self) -> Vec<EchoDropReward> {
+        self.pending_rewards.values().flatten().cloned().collect()
+    }
+
+    /// Take back pending rewards checkpointed by a previous run. They were paid from
+    /// that run's pool, so today's pool is left untouched; held rewards stay held
+    /// without their suspicion reports.
+    pub fn restore_pending_rewards(&mut self, rewards: Vec<EchoDropReward>) {
+        for reward in rewards {
+            let (user_id, amount, reward_type) = (reward.user_id.clone(), reward.amount, reward.reward_type.clone());
+            self.pending_rewards.entry(user_id.clone()).or_default().push(reward);
+            self.update_user_stats(&user_id, amount, &reward_type);
+        }
+    }
+
+    /// Release a held reward back into the normal processing flow
+    pub fn approve_held_reward(&mut self, reward_id: &str) -> Result<(), String> {
+        let reward = self.pending_rewards
+            .values_mut()
+            .flatten()
+            .find(|r| r.id == reward_id && r.on_hold)
+            .ok_or_else(|| format!("No held reward with id {}", reward_id))?;
+
+        reward.on_hold = false;
+        self.hold_reports.remove(reward_id);
+        Ok(())
+    }
+
+    /// Cancel a held reward and return its unreleased amount to the daily pool
+    pub fn reject_held_reward(&mut self, reward_id: &str) -> Result<EchoDropReward, String> {
+        let (user_id, index) = self.pending_rewards
+            .iter()
+            .find_map(|(user_id, rewards)| {
+                rewards
+                    .iter()
+                    .position(|r| r.id == reward_id && r.on_hold)
+                    .map(|index| (user_id.clone(), index))
+            })
+            .ok_or_else(|| format!("No held reward with id {}", reward_id))?;
+
+        let pending = self.pending_rewards.get_mut(&user_id).expect("user located above");
+        let reward = pending.remove(index);
+        if pending.is_empty() {
+            self.pending_rewards.remove(&user_id);
+        }
+
+        let refund = reward.amount - reward.vested_amount;
+        if matches!(reward.reward_type, RewardType::Deferred) {
+            self.deferred_pool_charge = (self.deferred_pool_charge - refund).max(0.0);
+        } else {
+            self.current_pool_remaining = (self.current_pool_remaining + refund).min(self.daily_pool);
+        }
+        if let Some(stats) = self.user_stats.get_mut(&user_id) {
+            stats.total_earned -= refund;
+        }
+        self.hold_reports.remove(reward_id);
+
+        Ok(reward)
+    }
+
+    /// Freeze the pending rewards of removed content, and any it earns later. Returns
+    /// the number of pending rewards frozen.
+    pub fn freeze_content_rewards(&mut self, content_id: &str) -> usize {
+        self.frozen_content.insert(content_id.to_string());
+        let mut frozen = 0;
+        for reward in self.pending_rewards.values_mut().flatten().filter(|r| r.content_id == content_id) {
+            reward.frozen = true;
+            frozen += 1;
+        }
+        frozen
+    }
+
+    /// Hold the pending rewards of content whose Echo Index jumped anomalously, and any it
+    /// earns later, until the anomaly is reviewed. Returns the number of rewards held.
+    pub fn hold_content_rewards(&mut self, content_id: &str) -> usize {
+        self.held_content.insert(content_id.to_string());
+        let mut held = 0;
+        for reward in self.pending_rewards.values_mut().flatten().filter(|r| r.content_id == content_id && !r.on_hold) {
+            reward.on_hold = true;
+            self.hold_reports
+                .insert(reward.id.clone(), SuspicionReport::echo_index_anomaly(&reward.user_id, content_id));
+            held += 1;
+        }
+        held
+    }
+
+    /// Release the rewards `hold_content_rewards` held. Rewards also held for their
+    /// user's suspicious activity stay held. Returns the number of rewards released.
+    pub fn release_content_rewards(&mut self, content_id: &str) -> usize {
+        self.held_content.remove(content_id);
+        let mut released = 0;
+        for reward in self.pending_rewards.values_mut().flatten().filter(|r| r.content_id == content_id && r.on_hold) {
+            if self.hold_reports.get(&reward.id).is_some_and(SuspicionReport::is_echo_index_anomaly) {
+                reward.on_hold = false;
+                self.hold_reports.remove(&reward.id);
+                released += 1;
+            }
+        }
+        released
+    }
+
+    /// High-Echo rewards vest gradually to discourage pump-and-dump behavior
+    fn vesting_schedule_for(echo_index_contribution: f64) -> VestingSchedule {
+        if echo_index_contribution >= VESTING_ECHO_INDEX_THRESHOLD {
+            VestingSchedule::Linear { duration_hours: HIGH_ECHO_VESTING_HOURS }
+        } else {
+            VestingSchedule::Immediate
+        }
+    }
+
     /// Update user reward statistics
     fn update_user_stats(&mut self, user_id: &str, amount: f64, reward_type: &RewardType) {
         let stats = self.user_stats
@@ -194,10 +495,16 @@ impl RewardsService {
         }
 
         // Calculate reward velocity (rewards per hour over last 24h)
-        stats.reward_velocity = self.calculate_reward_velocity(user_id);
+        let reward_velocity = self.calculate_reward_velocity(user_id);
+        if let Some(stats) = self.user_stats.get_mut(user_id) {
+            stats.reward_velocity = reward_velocity;
+        }
         
         // Update multiplier based on recent activity
-        stats.current_multiplier = self.calculate_user_multiplier(user_id);
+        let current_multiplier = self.calculate_user_multiplier(user_id);
+        if let Some(stats) = self.user_stats.get_mut(user_id) {
+            stats.current_multiplier = current_multiplier;
+        }
     }
 
     /// Calculate user's reward velocity
@@ -246,26 +553,54 @@ impl RewardsService {
         multiplier.min(3.0) // Cap at 3x multiplier
     }
 
-    /// Process pending rewards and prepare for blockchain distribution
-    pub fn process_pending_rewards(&mut self, user_id: &str) -> Result<Vec<EchoDropReward>, String> {
-        let pending = self.pending_rewards.remove(user_id).unwrap_or_default();
-        
-        if pending.is_empty() {
+    /// Sum of vested but unreleased amounts across a user's pending rewards
+    pub fn compute_claimable(&self, user_id: &str, as_of: DateTime<Utc>) -> f64 {
+        self.pending_rewards
+            .get(user_id)
+            .map(|rewards| rewards.iter().filter(|r| r.is_releasable()).map(|r| r.claimable(as_of)).sum())
+            .unwrap_or(0.0)
+    }
+
+    /// Release the portion of pending rewards vested by `as_of`, settled on chain by the
+    /// transaction `transaction_hash`
+    pub fn process_pending_rewards(
+        &mut self,
+        user_id: &str,
+        as_of: DateTime<Utc>,
+        transaction_hash: &str,
+    ) -> Result<Vec<EchoDropReward>, String> {
+        let Some(pending) = self.pending_rewards.get_mut(user_id) else {
             return Ok(Vec::new());
-        }
+        };
 
-        // Simulate blockchain transaction processing
         let mut processed = Vec::new();
-        for mut reward in pending {
-            // In real implementation, this would create actual blockchain transaction
-            reward.transaction_hash = Some(format!("tx_{}", uuid::Uuid::new_v4()));
-            processed.push(reward);
+        for reward in pending.iter_mut().filter(|r| r.is_releasable()) {
+            let claimable = reward.claimable(as_of);
+            if claimable <= 0.0 {
+                continue;
+            }
+            reward.vested_amount += claimable;
+
+            let mut release = reward.clone();
+            release.amount = claimable;
+            release.transaction_hash = Some(transaction_hash.to_string());
+            processed.push(release);
+        }
+
+        // Unvested remainders stay pending
+        pending.retain(|reward| !reward.is_fully_vested());
+        if pending.is_empty() {
+            self.pending_rewards.remove(user_id);
+        }
+
+        if processed.is_empty() {
+            return Ok(processed);
         }
 
         // Move to processed rewards
         self.processed_rewards
             .entry(user_id.to_string())
-            .or_insert_with(Vec::new)
+            .or_default()
             .extend(processed.clone());
 
         Ok(processed)
@@ -279,11 +614,19 @@ impl RewardsService {
             .unwrap_or(0.0)
     }
 
+    /// A user's outstanding rewards followed by the releases already made from them,
+    /// which carry a transaction hash
+    pub fn user_rewards(&self, user_id: &str) -> Vec<EchoDropReward> {
+        let pending = self.pending_rewards.get(user_id).into_iter().flatten();
+        let processed = self.processed_rewards.get(user_id).into_iter().flatten();
+        pending.chain(processed).cloned().collect()
+    }
+
     /// Get user's pending rewards
     pub fn get_pending_rewards(&self, user_id: &str) -> f64 {
         self.pending_rewards
             .get(user_id)
-            .map(|rewards| rewards.iter().map(|r| r.amount).sum())
+            .map(|rewards| rewards.iter().map(|r| r.amount - r.vested_amount).sum())
             .unwrap_or(0.0)
     }
 
@@ -306,9 +649,20 @@ impl RewardsService {
         users
     }
 
-    /// Reset daily reward pool
+    /// Reset daily reward pool, charging it with the rewards deferred since the last reset
     pub fn reset_daily_pool(&mut self) {
-        self.current_pool_remaining = self.daily_pool;
+        self.current_pool_remaining = (self.daily_pool - self.deferred_pool_charge).max(0.0);
+        self.deferred_pool_charge = 0.0;
+    }
+
+    /// Resize the daily pool; takes effect at the next reset
+    pub fn set_daily_pool(&mut self, daily_pool: f64) {
+        self.daily_pool = daily_pool.max(0.0);
+    }
+
+    /// EchoDrops earned per Echo Index point, before bonuses
+    pub fn base_rate(&self) -> f64 {
+        self.multipliers.base_rate
     }
 
     /// Get pool status
@@ -353,4 +707,151 @@ pub struct RewardAnalytics {
     pub unique_recipients: usize,
     pub rewards_by_type: HashMap<String, f64>,
     pub pool_utilization: f64,
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_vesting_progress() {
+        let mut service = RewardsService::new(1_000.0);
+        service.award_reward("user_1".into(), "content_1".into(), RewardType::ContentCreation, 100.0, 0.9).unwrap();
+        let start = service.pending_rewards["user_1"][0].vest_start;
+        let half = Duration::hours(HIGH_ECHO_VESTING_HOURS as i64 / 2);
+
+        assert_eq!(service.compute_claimable("user_1", start), 0.0);
+        assert!((service.compute_claimable("user_1", start + half) - 50.0).abs() < 1e-9);
+        assert!((service.compute_claimable("user_1", start + half * 2) - 100.0).abs() < 1e-9);
+        assert!((service.compute_claimable("user_1", start + half * 4) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_processing_releases_only_vested_portion() {
+        let mut service = RewardsService::new(1_000.0);
+        service.award_reward("user_1".into(), "content_1".into(), RewardType::ContentCreation, 100.0, 0.9).unwrap();
+        service.award_reward("user_1".into(), "content_2".into(), RewardType::DiscoveryBonus, 10.0, 0.1).unwrap();
+
+        // Rewind the high-Echo reward to the middle of its vesting period
+        let half = Duration::hours(HIGH_ECHO_VESTING_HOURS as i64 / 2);
+        service.pending_rewards.get_mut("user_1").unwrap()[0].vest_start -= half;
+
+        let released = service.process_pending_rewards("user_1", Utc::now(), "tx_1").unwrap();
+        assert!(released.iter().all(|r| r.transaction_hash.as_deref() == Some("tx_1")));
+        let released: f64 = released.iter().map(|r| r.amount).sum();
+        assert!((released - 60.0).abs() < 0.01);
+
+        let pending = &service.pending_rewards["user_1"];
+        assert_eq!(pending.len(), 1);
+        assert!((service.get_pending_rewards("user_1") - 50.0).abs() < 0.01);
+        assert!(service.process_pending_rewards("user_1", Utc::now(), "tx").unwrap().iter().all(|r| r.amount < 0.01));
+    }
+
+    #[test]
+    fn test_farmed_rewards_are_held_until_approved() {
+        let mut service = RewardsService::new(1_000.0);
+        for i in 0..11 {
+            service.award_reward("farmer".into(), format!("content_{}", i), RewardType::ContentCreation, 1.0, 0.1).unwrap();
+        }
+        assert!(service.on_hold_rewards().is_empty());
+
+        // Propagating their own content on top of rapid creation tips the risk over
+        let held_id = service
+            .award_reward("farmer".into(), "content_0".into(), RewardType::PropagationBonus, 5.0, 0.1)
+            .unwrap();
+        let held = service.on_hold_rewards();
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].0.id, held_id);
+        assert!(held[0].1.as_ref().unwrap().risk_score > HOLD_RISK_THRESHOLD);
+
+        let released = service.process_pending_rewards("farmer", Utc::now(), "tx").unwrap();
+        assert!(released.iter().all(|r| r.id != held_id));
+        assert_eq!(service.compute_claimable("farmer", Utc::now()), 0.0);
+
+        service.approve_held_reward(&held_id).unwrap();
+        let released = service.process_pending_rewards("farmer", Utc::now(), "tx").unwrap();
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].id, held_id);
+    }
+
+    #[test]
+    fn test_rejected_reward_refunds_pool() {
+        let mut service = RewardsService::new(1_000.0);
+        for i in 0..11 {
+            service.award_reward("farmer".into(), format!("content_{}", i), RewardType::ContentCreation, 1.0, 0.1).unwrap();
+        }
+        let held_id = service
+            .award_reward("farmer".into(), "content_0".into(), RewardType::PropagationBonus, 5.0, 0.1)
+            .unwrap();
+
+        service.reject_held_reward(&held_id).unwrap();
+        assert!(service.on_hold_rewards().is_empty());
+        assert!((service.get_pool_status().1 - 989.0).abs() < 1e-9);
+        assert!(service.reject_held_reward(&held_id).is_err());
+    }
+
+    #[test]
+    fn test_removed_content_rewards_are_frozen() {
+        let mut service = RewardsService::new(1_000.0);
+        service.award_reward("user_1".into(), "content_1".into(), RewardType::ContentCreation, 10.0, 0.1).unwrap();
+        service.award_reward("user_1".into(), "content_2".into(), RewardType::ContentCreation, 5.0, 0.1).unwrap();
+
+        assert_eq!(service.freeze_content_rewards("content_1"), 1);
+        service.award_reward("user_1".into(), "content_1".into(), RewardType::PropagationBonus, 2.0, 0.1).unwrap();
+
+        assert_eq!(service.compute_claimable("user_1", Utc::now()), 5.0);
+        let released = service.process_pending_rewards("user_1", Utc::now(), "tx").unwrap();
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].content_id, "content_2");
+    }
+
+    #[test]
+    fn test_anomalous_content_rewards_are_held_until_released() {
+        let mut service = RewardsService::new(1_000.0);
+        service.award_reward("user_1".into(), "content_1".into(), RewardType::ContentCreation, 10.0, 0.1).unwrap();
+        service.award_reward("user_1".into(), "content_2".into(), RewardType::ContentCreation, 5.0, 0.1).unwrap();
+
+        assert_eq!(service.hold_content_rewards("content_1"), 1);
+        service.award_reward("user_2".into(), "content_1".into(), RewardType::PropagationBonus, 2.0, 0.1).unwrap();
+        let held = service.on_hold_rewards();
+        assert_eq!(held.len(), 2);
+        assert!(held.iter().all(|(_, report)| report.as_ref().is_some_and(SuspicionReport::is_echo_index_anomaly)));
+        assert_eq!(service.compute_claimable("user_1", Utc::now()), 5.0);
+
+        assert_eq!(service.release_content_rewards("content_1"), 2);
+        assert!(service.on_hold_rewards().is_empty());
+        assert_eq!(service.compute_claimable("user_1", Utc::now()), 15.0);
+    }
+
+    #[test]
+    fn test_rewards_below_emergency_reserve_are_deferred() {
+        let mut service = RewardsService::new(1_000.0);
+        service.award_reward("user_1".into(), "content_1".into(), RewardType::ContentCreation, 940.0, 0.1).unwrap();
+        assert!(!service.is_pool_in_reserve());
+        service.award_reward("user_2".into(), "content_2".into(), RewardType::ContentCreation, 20.0, 0.1).unwrap();
+        assert!(service.is_pool_in_reserve());
+
+        // Recorded in full, but the remaining 40 stays in reserve
+        service.award_reward("user_3".into(), "content_3".into(), RewardType::DiscoveryBonus, 30.0, 0.1).unwrap();
+        assert!(matches!(service.pending_rewards["user_3"][0].reward_type, RewardType::Deferred));
+        assert_eq!(service.get_pending_rewards("user_3"), 30.0);
+        assert_eq!(service.get_pool_status().1, 40.0);
+        // No more than a day's pool is deferred
+        assert!(service
+            .award_reward("user_4".into(), "content_4".into(), RewardType::DiscoveryBonus, 980.0, 0.1)
+            .is_err());
+
+        service.reset_daily_pool();
+        assert_eq!(service.get_pool_status().1, 970.0);
+        assert!(!service.is_pool_in_reserve());
+    }
+
+    #[test]
+    fn test_cliff_vests_nothing_before_cliff() {
+        let schedule = VestingSchedule::Cliff { cliff_hours: 24, then_linear_hours: 48 };
+
+        assert_eq!(schedule.vested_fraction(Duration::hours(23)), 0.0);
+        assert_eq!(schedule.vested_fraction(Duration::hours(48)), 0.5);
+        assert_eq!(schedule.vested_fraction(Duration::hours(72)), 1.0);
+    }
+}
@@ -0,0 +1,747 @@
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine as _;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::oauth::{OAuthCallback, OAuthState};
+use crate::models::user::SocialAccount;
+use crate::models::Platform;
+use crate::repositories::{OAuthStateRepository, RepositoryError, UserRepository};
+
+/// How long a started verification waits for the provider's callback
+pub const OAUTH_STATE_TTL_MINUTES: i64 = 10;
+const PROVIDER_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// Scopes granting LinkedIn's OpenID Connect user info
+const LINKEDIN_SCOPE: &str = "openid profile";
+
+#[derive(Debug, thiserror::Error)]
+pub enum VerificationError {
+    #[error("verification of {0} accounts is not available")]
+    UnsupportedPlatform(Platform),
+    #[error("OAuth state is unknown, expired or was issued for another verification")]
+    InvalidState,
+    #[error("invalid OAuth callback: {0}")]
+    InvalidCallback(&'static str),
+    #[error("the authorized {platform} account is {actual}, not {claimed}")]
+    AccountMismatch { platform: Platform, claimed: String, actual: String },
+    #[error("OAuth provider error: {0}")]
+    Provider(String),
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+impl From<reqwest::Error> for VerificationError {
+    fn from(error: reqwest::Error) -> Self {
+        VerificationError::Provider(error.to_string())
+    }
+}
+
+/// Twitter app credentials, for OAuth 1.0a
+#[derive(Debug, Clone)]
+pub struct TwitterOAuthConfig {
+    pub consumer_key: String,
+    pub consumer_secret: String,
+    /// `https://api.twitter.com`, or a mock in tests
+    pub api_base: String,
+}
+
+impl TwitterOAuthConfig {
+    pub fn new(consumer_key: String, consumer_secret: String) -> Self {
+        Self { consumer_key, consumer_secret, api_base: "https://api.twitter.com".to_string() }
+    }
+
+    /// `None` unless `TWITTER_CONSUMER_KEY` and `TWITTER_CONSUMER_SECRET` are set
+    pub fn from_env() -> Option<Self> {
+        let key = std::env::var("TWITTER_CONSUMER_KEY").ok()?;
+        let secret = std::env::var("TWITTER_CONSUMER_SECRET").ok()?;
+        Some(Self::new(key, secret))
+    }
+}
+
+/// LinkedIn app credentials, for OAuth 2.0 with PKCE
+#[derive(Debug, Clone)]
+pub struct LinkedInOAuthConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    /// `https://www.linkedin.com`, serving authorization and tokens, or a mock in tests
+    pub auth_base: String,
+    /// `https://api.linkedin.com`, serving user info, or a mock in tests
+    pub api_base: String,
+}
+
+impl LinkedInOAuthConfig {
+    pub fn new(client_id: String, client_secret: String) -> Self {
+        Self {
+            client_id,
+            client_secret,
+            auth_base: "https://www.linkedin.com".to_string(),
+            api_base: "https://api.linkedin.com".to_string(),
+        }
+    }
+
+    /// `None` unless `LINKEDIN_CLIENT_ID` and `LINKEDIN_CLIENT_SECRET` are set
+    pub fn from_env() -> Option<Self> {
+        let id = std::env::var("LINKEDIN_CLIENT_ID").ok()?;
+        let secret = std::env::var("LINKEDIN_CLIENT_SECRET").ok()?;
+        Some(Self::new(id, secret))
+    }
+}
+
+/// Where to send the user to authorize, and until when the callback is accepted
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationStart {
+    pub authorization_url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// The account an access token belongs to, according to the provider
+struct ProviderAccount {
+    id: String,
+    username: String,
+}
+
+#[derive(Deserialize)]
+struct TwitterUser {
+    id_str: String,
+    screen_name: String,
+}
+
+#[derive(Deserialize)]
+struct LinkedInToken {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct LinkedInUserInfo {
+    sub: String,
+    #[serde(default)]
+    name: String,
+}
+
+/// Proves that users own the social accounts they connect by having them authorize
+/// EchoLayer with the platform, then asking the platform whose account that is.
+///
+/// Providers redirect to `redirect_url`, a frontend page that forwards the query to the
+/// callback endpoint along with the user's token. Each flow's state is bound to the user
+/// and platform it was started for, can be used once and expires after
+/// `OAUTH_STATE_TTL_MINUTES`.
+pub struct SocialAccountVerifier {
+    states: Arc<OAuthStateRepository>,
+    users: Arc<UserRepository>,
+    client: reqwest::Client,
+    redirect_url: String,
+    twitter: Option<TwitterOAuthConfig>,
+    linkedin: Option<LinkedInOAuthConfig>,
+}
+
+impl SocialAccountVerifier {
+    pub fn new(states: Arc<OAuthStateRepository>, users: Arc<UserRepository>, redirect_url: String) -> Self {
+        Self {
+            states,
+            users,
+            client: reqwest::Client::builder()
+                .timeout(PROVIDER_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            redirect_url,
+            twitter: None,
+            linkedin: None,
+        }
+    }
+
+    /// Verifier for the providers configured in the environment, redirecting to
+    /// `OAUTH_REDIRECT_URL`
+    pub fn from_env(states: Arc<OAuthStateRepository>, users: Arc<UserRepository>) -> Self {
+        let redirect_url = std::env::var("OAUTH_REDIRECT_URL")
+            .unwrap_or_else(|_| "http://localhost:3000/verify/callback".to_string());
+        let mut verifier = Self::new(states, users, redirect_url);
+        verifier.twitter = TwitterOAuthConfig::from_env();
+        verifier.linkedin = LinkedInOAuthConfig::from_env();
+        verifier
+    }
+
+    /// Start proving that the user owns `account_id` on `platform`
+    pub async fn start(
+        &self,
+        user_id: Uuid,
+        platform: &Platform,
+        account_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<VerificationStart, VerificationError> {
+        self.states.delete_expired(now).await?;
+
+        let mut state = OAuthState {
+            state: hex::encode(random_bytes()),
+            user_id,
+            platform: platform.clone(),
+            account_id: account_id.to_string(),
+            code_verifier: None,
+            request_token: None,
+            request_token_secret: None,
+            expires_at: now + Duration::minutes(OAUTH_STATE_TTL_MINUTES),
+        };
+        let authorization_url = match (platform, &self.twitter, &self.linkedin) {
+            (Platform::Twitter, Some(twitter), _) => {
+                // OAuth 1.0a has no state parameter, so it rides along in the callback URL
+                let callback = Url::parse_with_params(&self.redirect_url, [("state", &state.state)])
+                    .map_err(|e| VerificationError::Provider(format!("invalid redirect URL: {}", e)))?;
+                let (token, token_secret) = self.twitter_request_token(twitter, callback.as_str()).await?;
+                let url = format!("{}/oauth/authorize?oauth_token={}", twitter.api_base, percent_encode(&token));
+                state.request_token = Some(token);
+                state.request_token_secret = Some(token_secret);
+                url
+            }
+            (Platform::LinkedIn, _, Some(linkedin)) => {
+                let code_verifier = URL_SAFE_NO_PAD.encode(random_bytes());
+                let url = Url::parse_with_params(
+                    &format!("{}/oauth/v2/authorization", linkedin.auth_base),
+                    [
+                        ("response_type", "code"),
+                        ("client_id", &linkedin.client_id),
+                        ("redirect_uri", &self.redirect_url),
+                        ("state", &state.state),
+                        ("scope", LINKEDIN_SCOPE),
+                        ("code_challenge", &pkce_challenge(&code_verifier)),
+                        ("code_challenge_method", "S256"),
+                    ],
+                )
+                .map_err(|e| VerificationError::Provider(format!("invalid authorization URL: {}", e)))?;
+                state.code_verifier = Some(code_verifier);
+                url.to_string()
+            }
+            _ => return Err(VerificationError::UnsupportedPlatform(platform.clone())),
+        };
+
+        self.states.create(&state).await?;
+        Ok(VerificationStart { authorization_url, expires_at: state.expires_at })
+    }
+
+    /// Finish a verification the provider redirected back from, marking the account
+    /// verified if the user authorized as the account they claimed
+    pub async fn complete(
+        &self,
+        user_id: Uuid,
+        platform: &Platform,
+        callback: &OAuthCallback,
+        now: DateTime<Utc>,
+    ) -> Result<SocialAccount, VerificationError> {
+        let state = self
+            .states
+            .take(&callback.state, user_id, platform, now)
+            .await?
+            .ok_or(VerificationError::InvalidState)?;
+
+        let account = match (platform, &self.twitter, &self.linkedin) {
+            (Platform::Twitter, Some(twitter), _) => {
+                let (Some(token), Some(verifier)) = (&callback.oauth_token, &callback.oauth_verifier) else {
+                    return Err(VerificationError::InvalidCallback("oauth_token and oauth_verifier are required"));
+                };
+                if state.request_token.as_ref() != Some(token) {
+                    return Err(VerificationError::InvalidState);
+                }
+                let token_secret = state.request_token_secret.as_deref().unwrap_or_default();
+                self.twitter_account(twitter, token, token_secret, verifier).await?
+            }
+            (Platform::LinkedIn, _, Some(linkedin)) => {
+                let Some(code) = &callback.code else {
+                    return Err(VerificationError::InvalidCallback("code is required"));
+                };
+                let code_verifier = state.code_verifier.as_deref().unwrap_or_default();
+                self.linkedin_account(linkedin, code, code_verifier).await?
+            }
+            _ => return Err(VerificationError::UnsupportedPlatform(platform.clone())),
+        };
+
+        if account.id != state.account_id {
+            return Err(VerificationError::AccountMismatch {
+                platform: platform.clone(),
+                claimed: state.account_id,
+                actual: account.id,
+            });
+        }
+        let verified = self
+            .users
+            .verify_social_account(user_id, platform, &account.id, &account.username)
+            .await?;
+        log::info!("User {} verified {} account {}", user_id, platform, account.id);
+        Ok(verified)
+    }
+
+    /// Temporary credentials to send the user to Twitter's authorization page with
+    async fn twitter_request_token(
+        &self,
+        twitter: &TwitterOAuthConfig,
+        callback: &str,
+    ) -> Result<(String, String), VerificationError> {
+        let url = format!("{}/oauth/request_token", twitter.api_base);
+        let authorization = oauth1_authorization(twitter, "POST", &url, None, &[("oauth_callback", callback)]);
+        let body = self
+            .client
+            .post(&url)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let mut form = parse_form(&body)?;
+        match (form.remove("oauth_token"), form.remove("oauth_token_secret")) {
+            (Some(token), Some(secret)) => Ok((token, secret)),
+            _ => Err(VerificationError::Provider("request token response lacks credentials".to_string())),
+        }
+    }
+
+    /// Exchange authorized temporary credentials for an access token, then ask Twitter
+    /// who it belongs to
+    async fn twitter_account(
+        &self,
+        twitter: &TwitterOAuthConfig,
+        request_token: &str,
+        request_token_secret: &str,
+        verifier: &str,
+    ) -> Result<ProviderAccount, VerificationError> {
+        let url = format!("{}/oauth/access_token", twitter.api_base);
+        let authorization = oauth1_authorization(
+            twitter,
+            "POST",
+            &url,
+            Some((request_token, request_token_secret)),
+            &[("oauth_verifier", verifier)],
+        );
+        let body = self
+            .client
+            .post(&url)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let mut form = parse_form(&body)?;
+        let (Some(token), Some(token_secret)) = (form.remove("oauth_token"), form.remove("oauth_token_secret")) else {
+            return Err(VerificationError::Provider("access token response lacks credentials".to_string()));
+        };
+
+        let url = format!("{}/1.1/account/verify_credentials.json", twitter.api_base);
+        let authorization = oauth1_authorization(twitter, "GET", &url, Some((&token, &token_secret)), &[]);
+        let user: TwitterUser = self
+            .client
+            .get(&url)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(ProviderAccount { id: user.id_str, username: user.screen_name })
+    }
+
+    /// Exchange an authorization code and its PKCE verifier for an access token, then
+    /// ask LinkedIn who it belongs to
+    async fn linkedin_account(
+        &self,
+        linkedin: &LinkedInOAuthConfig,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<ProviderAccount, VerificationError> {
+        let token: LinkedInToken = self
+            .client
+            .post(format!("{}/oauth/v2/accessToken", linkedin.auth_base))
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", &self.redirect_url),
+                ("client_id", &linkedin.client_id),
+                ("client_secret", &linkedin.client_secret),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let user: LinkedInUserInfo = self
+            .client
+            .get(format!("{}/v2/userinfo", linkedin.api_base))
+            .bearer_auth(&token.access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(ProviderAccount { id: user.sub, username: user.name })
+    }
+}
+
+fn random_bytes() -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+/// S256 PKCE challenge of a code verifier
+fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+fn parse_form(body: &str) -> Result<HashMap<String, String>, VerificationError> {
+    serde_urlencoded::from_str(body).map_err(|e| VerificationError::Provider(format!("malformed response: {}", e)))
+}
+
+/// `OAuth ...` authorization header of a request without query or form parameters,
+/// signed with the app's and `token`'s secrets
+fn oauth1_authorization(
+    twitter: &TwitterOAuthConfig,
+    method: &str,
+    url: &str,
+    token: Option<(&str, &str)>,
+    extra: &[(&str, &str)],
+) -> String {
+    let nonce = hex::encode(random_bytes());
+    let timestamp = Utc::now().timestamp().to_string();
+    let mut params = vec![
+        ("oauth_consumer_key", twitter.consumer_key.as_str()),
+        ("oauth_nonce", nonce.as_str()),
+        ("oauth_signature_method", "HMAC-SHA1"),
+        ("oauth_timestamp", timestamp.as_str()),
+        ("oauth_version", "1.0"),
+    ];
+    if let Some((token, _)) = token {
+        params.push(("oauth_token", token));
+    }
+    params.extend_from_slice(extra);
+
+    let token_secret = token.map(|(_, secret)| secret).unwrap_or_default();
+    let signature = oauth1_signature(method, url, &params, &twitter.consumer_secret, token_secret);
+    params.push(("oauth_signature", signature.as_str()));
+
+    let fields: Vec<String> = params
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", percent_encode(key), percent_encode(value)))
+        .collect();
+    format!("OAuth {}", fields.join(", "))
+}
+
+/// Base64 HMAC-SHA1 signature of a request per RFC 5849, over all of its `oauth_*`,
+/// query and form parameters
+pub(crate) fn oauth1_signature(
+    method: &str,
+    url: &str,
+    params: &[(&str, &str)],
+    consumer_secret: &str,
+    token_secret: &str,
+) -> String {
+    let mut encoded: Vec<(String, String)> = params
+        .iter()
+        .map(|(key, value)| (percent_encode(key), percent_encode(value)))
+        .collect();
+    encoded.sort();
+    let parameter_string = encoded
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&");
+    let base_string = format!(
+        "{}&{}&{}",
+        method.to_uppercase(),
+        percent_encode(url),
+        percent_encode(&parameter_string)
+    );
+    let key = format!("{}&{}", percent_encode(consumer_secret), percent_encode(token_secret));
+
+    let mut mac = Hmac::<Sha1>::new_from_slice(key.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(base_string.as_bytes());
+    STANDARD.encode(mac.finalize().into_bytes())
+}
+
+/// RFC 3986 percent-encoding, leaving only unreserved characters as they are
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+    use sqlx::PgPool;
+    use std::sync::Mutex;
+
+    const REDIRECT_URL: &str = "https://app.echolayer.io/verify/callback";
+
+    /// A local stand-in for Twitter's and LinkedIn's OAuth endpoints, authorizing as
+    /// `account_id`
+    struct MockProvider {
+        account_id: String,
+        /// PKCE challenge of the authorization the test approved
+        code_challenge: Mutex<Option<String>>,
+    }
+
+    fn header(request: &HttpRequest) -> String {
+        request
+            .headers()
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    async fn request_token(request: HttpRequest) -> HttpResponse {
+        let authorization = header(&request);
+        if !authorization.contains("oauth_callback=\"https%3A%2F%2Fapp.echolayer.io%2Fverify%2Fcallback%3Fstate%3D")
+            || !authorization.contains("oauth_signature=")
+        {
+            return HttpResponse::Unauthorized().finish();
+        }
+        HttpResponse::Ok()
+            .body("oauth_token=request-token&oauth_token_secret=request-secret&oauth_callback_confirmed=true")
+    }
+
+    async fn access_token(request: HttpRequest, provider: web::Data<MockProvider>) -> HttpResponse {
+        let authorization = header(&request);
+        if !authorization.contains("oauth_token=\"request-token\"")
+            || !authorization.contains("oauth_verifier=\"verifier\"")
+        {
+            return HttpResponse::Unauthorized().finish();
+        }
+        HttpResponse::Ok().body(format!(
+            "oauth_token=access-token&oauth_token_secret=access-secret&user_id={}&screen_name=echo_fan",
+            provider.account_id
+        ))
+    }
+
+    async fn verify_credentials(request: HttpRequest, provider: web::Data<MockProvider>) -> HttpResponse {
+        if !header(&request).contains("oauth_token=\"access-token\"") {
+            return HttpResponse::Unauthorized().finish();
+        }
+        HttpResponse::Ok().json(serde_json::json!({ "id_str": provider.account_id, "screen_name": "echo_fan" }))
+    }
+
+    async fn linkedin_token(
+        form: web::Form<HashMap<String, String>>,
+        provider: web::Data<MockProvider>,
+    ) -> HttpResponse {
+        let expected = provider.code_challenge.lock().unwrap().clone();
+        let challenge = form.get("code_verifier").map(|verifier| pkce_challenge(verifier));
+        if form.get("code").map(String::as_str) != Some("code") || challenge.is_none() || challenge != expected {
+            return HttpResponse::BadRequest().json(serde_json::json!({ "error": "invalid_grant" }));
+        }
+        HttpResponse::Ok().json(serde_json::json!({ "access_token": "linkedin-token", "expires_in": 3600 }))
+    }
+
+    async fn linkedin_userinfo(request: HttpRequest, provider: web::Data<MockProvider>) -> HttpResponse {
+        if header(&request) != "Bearer linkedin-token" {
+            return HttpResponse::Unauthorized().finish();
+        }
+        HttpResponse::Ok().json(serde_json::json!({ "sub": provider.account_id, "name": "Echo Fan" }))
+    }
+
+    /// Base URL of a mock provider authorizing as `account_id`
+    fn mock_provider(account_id: &str) -> (String, web::Data<MockProvider>) {
+        let provider = web::Data::new(MockProvider {
+            account_id: account_id.to_string(),
+            code_challenge: Mutex::new(None),
+        });
+
+        let server_provider = provider.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(server_provider.clone())
+                .route("/oauth/request_token", web::post().to(request_token))
+                .route("/oauth/access_token", web::post().to(access_token))
+                .route("/1.1/account/verify_credentials.json", web::get().to(verify_credentials))
+                .route("/oauth/v2/accessToken", web::post().to(linkedin_token))
+                .route("/v2/userinfo", web::get().to(linkedin_userinfo))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let base = format!("http://{}", server.addrs()[0]);
+        tokio::spawn(server.run());
+
+        (base, provider)
+    }
+
+    fn verifier(pool: &PgPool, base: &str) -> SocialAccountVerifier {
+        let twitter = TwitterOAuthConfig {
+            api_base: base.to_string(),
+            ..TwitterOAuthConfig::new("key".into(), "secret".into())
+        };
+        let linkedin = LinkedInOAuthConfig {
+            auth_base: base.to_string(),
+            api_base: base.to_string(),
+            ..LinkedInOAuthConfig::new("client".into(), "secret".into())
+        };
+        SocialAccountVerifier {
+            twitter: Some(twitter),
+            linkedin: Some(linkedin),
+            ..SocialAccountVerifier::new(
+                Arc::new(OAuthStateRepository::new(pool.clone())),
+                Arc::new(UserRepository::new(pool.clone())),
+                REDIRECT_URL.to_string(),
+            )
+        }
+    }
+
+    async fn insert_user(pool: &PgPool, wallet: &str) -> Uuid {
+        sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+            .bind(wallet)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    fn query_param(url: &str, name: &str) -> String {
+        Url::parse(url)
+            .unwrap()
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+            .unwrap()
+    }
+
+    /// Start a LinkedIn verification and approve it at the mock provider; returns the state
+    async fn authorize_linkedin(
+        verifier: &SocialAccountVerifier,
+        provider: &MockProvider,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> String {
+        let start = verifier.start(user_id, &Platform::LinkedIn, "li-42", now).await.unwrap();
+        assert_eq!(query_param(&start.authorization_url, "code_challenge_method"), "S256");
+        assert_eq!(query_param(&start.authorization_url, "redirect_uri"), REDIRECT_URL);
+        *provider.code_challenge.lock().unwrap() = Some(query_param(&start.authorization_url, "code_challenge"));
+        query_param(&start.authorization_url, "state")
+    }
+
+    fn linkedin_callback(state: &str) -> OAuthCallback {
+        OAuthCallback { state: state.to_string(), code: Some("code".to_string()), ..Default::default() }
+    }
+
+    #[test]
+    fn test_oauth1_signature_matches_twitter_reference() {
+        let params = [
+            ("status", "Hello Ladies + Gentlemen, a signed OAuth request!"),
+            ("include_entities", "true"),
+            ("oauth_consumer_key", "xvz1evFS4wEEPTGEFPHBog"),
+            ("oauth_nonce", "kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg"),
+            ("oauth_signature_method", "HMAC-SHA1"),
+            ("oauth_timestamp", "1318622958"),
+            ("oauth_token", "370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb"),
+            ("oauth_version", "1.0"),
+        ];
+        let signature = oauth1_signature(
+            "POST",
+            "https://api.twitter.com/1.1/statuses/update.json",
+            &params,
+            "kAcSOqF21Fu85e7zjz7ZN2U4ZRhfV3WpwPAoE3Z7kBw",
+            "LswwdoUaIvS8ltyTt5jkRh4J50vUPVVHtR2YPi5kE",
+        );
+        assert_eq!(signature, "hCtSmYh+iHYCEqBWrE7C7hYmtUk=");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_linkedin_pkce_flow_verifies_the_claimed_account_once(pool: PgPool) {
+        let (base, provider) = mock_provider("li-42");
+        let verifier = verifier(&pool, &base);
+        let user_id = insert_user(&pool, "0xlinkedin").await;
+        let now = Utc::now();
+
+        let state = authorize_linkedin(&verifier, &provider, user_id, now).await;
+        let account = verifier
+            .complete(user_id, &Platform::LinkedIn, &linkedin_callback(&state), now)
+            .await
+            .unwrap();
+        assert_eq!((account.account_id.as_str(), account.username.as_str()), ("li-42", "Echo Fan"));
+        assert!(account.verified);
+        let accounts = UserRepository::new(pool.clone()).social_accounts(user_id).await.unwrap();
+        assert!(accounts[0].verified);
+
+        // The state was consumed, so a replayed callback is refused
+        assert!(matches!(
+            verifier.complete(user_id, &Platform::LinkedIn, &linkedin_callback(&state), now).await,
+            Err(VerificationError::InvalidState)
+        ));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_callback_rejects_forged_foreign_and_expired_states(pool: PgPool) {
+        let (base, provider) = mock_provider("li-42");
+        let verifier = verifier(&pool, &base);
+        let victim = insert_user(&pool, "0xvictim").await;
+        let attacker = insert_user(&pool, "0xattacker").await;
+        let now = Utc::now();
+        let state = authorize_linkedin(&verifier, &provider, victim, now).await;
+
+        let attempts = [
+            (victim, Platform::LinkedIn, linkedin_callback(&hex::encode(random_bytes())), now),
+            (attacker, Platform::LinkedIn, linkedin_callback(&state), now),
+            (victim, Platform::Twitter, linkedin_callback(&state), now),
+            (victim, Platform::LinkedIn, linkedin_callback(&state), now + Duration::minutes(OAUTH_STATE_TTL_MINUTES)),
+        ];
+        for (user_id, platform, callback, at) in attempts {
+            assert!(matches!(
+                verifier.complete(user_id, &platform, &callback, at).await,
+                Err(VerificationError::InvalidState)
+            ));
+        }
+        assert!(UserRepository::new(pool.clone()).social_accounts(attacker).await.unwrap().is_empty());
+
+        // None of the rejected callbacks used up the genuine one
+        let account = verifier
+            .complete(victim, &Platform::LinkedIn, &linkedin_callback(&state), now)
+            .await
+            .unwrap();
+        assert_eq!(account.user_id, victim);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_twitter_flow_requires_the_authorized_account_to_match(pool: PgPool) {
+        let (base, _provider) = mock_provider("1234");
+        let verifier = verifier(&pool, &base);
+        let user_id = insert_user(&pool, "0xtwitter").await;
+        let now = Utc::now();
+
+        for (claimed, verified) in [("999", false), ("1234", true)] {
+            let start = verifier.start(user_id, &Platform::Twitter, claimed, now).await.unwrap();
+            assert_eq!(start.authorization_url, format!("{}/oauth/authorize?oauth_token=request-token", base));
+            let state: String = sqlx::query_scalar("SELECT state FROM oauth_states WHERE account_id = $1")
+                .bind(claimed)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            let callback = OAuthCallback {
+                state,
+                oauth_token: Some("request-token".to_string()),
+                oauth_verifier: Some("verifier".to_string()),
+                ..Default::default()
+            };
+
+            let result = verifier.complete(user_id, &Platform::Twitter, &callback, now).await;
+            if verified {
+                let account = result.unwrap();
+                assert_eq!((account.account_id.as_str(), account.username.as_str()), ("1234", "echo_fan"));
+            } else {
+                assert!(matches!(result, Err(VerificationError::AccountMismatch { .. })));
+            }
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use base64::Engine;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use ed25519_dalek::{Signer, SigningKey};
+use futures_util::future::BoxFuture;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+use crate::models::wallet_address::SolanaAddress;
+use crate::services::token_vesting::BlockchainClient;
+
+/// The echo_layer program deployed from smart-contracts/Anchor.toml
+pub const DEFAULT_PROGRAM_ID: &str = "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS";
+pub const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+/// Seed of the reward pool PDA the program pays rewards from
+const REWARD_POOL_SEED: &[u8] = b"reward_pool";
+/// Anchor instruction releasing vested rewards from the pool. No deployed program
+/// implements it yet, so transfers are only submitted once explicitly enabled.
+const CLAIM_REWARDS_INSTRUCTION: &str = "global:claim_rewards";
+/// Longest a single RPC request may take
+const RPC_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often, and how many times, a submitted transaction's status is polled. A blockhash
+/// expires after about a minute, so a transaction not confirmed by then never will be.
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const CONFIRMATION_POLLS: usize = 120;
+
+type Pubkey = [u8; 32];
+
+/// An account an instruction reads or writes
+struct AccountMeta {
+    pubkey: Pubkey,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+/// Builds reward pool transfers for the echo_layer program as legacy Solana transactions.
+/// By default they are only logged; once submission is enabled they are sent through the
+/// RPC node, signed against its latest blockhash.
+pub struct SolanaBlockchainClient {
+    client: reqwest::Client,
+    rpc_url: String,
+    program_id: Pubkey,
+    authority: SigningKey,
+    /// Whether transfers are sent to the cluster rather than only logged
+    submit: bool,
+}
+
+impl SolanaBlockchainClient {
+    pub fn new(rpc_url: String, program_id: Pubkey, authority: SigningKey) -> Self {
+        Self {
+            client: reqwest::Client::builder().timeout(RPC_TIMEOUT).build().unwrap_or_default(),
+            rpc_url,
+            program_id,
+            authority,
+            submit: false,
+        }
+    }
+
+    /// Send transfers to the cluster instead of only logging them
+    pub fn submitting(mut self) -> Self {
+        self.submit = true;
+        self
+    }
+
+    /// Client of the default program that only logs transfers, signed by a throwaway key
+    pub fn logging_only() -> Self {
+        let program_id = parse_pubkey(DEFAULT_PROGRAM_ID).expect("the default program ID is a valid address");
+        Self::new(DEFAULT_RPC_URL.to_string(), program_id, SigningKey::from_bytes(&rand::random()))
+    }
+
+    /// Client for `SOLANA_RPC_URL` and `SOLANA_PROGRAM_ID`, signing with `SOLANA_PRIVATE_KEY`
+    /// as a base58 secret key or a keypair file's JSON byte array. Transfers are only
+    /// submitted if `SOLANA_SUBMIT_TRANSACTIONS` is `true`, which requires the key; otherwise
+    /// a missing key is replaced by a throwaway one.
+    pub fn from_env() -> Result<Self, String> {
+        let rpc_url = std::env::var("SOLANA_RPC_URL").unwrap_or_else(|_| DEFAULT_RPC_URL.to_string());
+        let program_id = std::env::var("SOLANA_PROGRAM_ID").unwrap_or_else(|_| DEFAULT_PROGRAM_ID.to_string());
+        let program_id = parse_pubkey(&program_id).map_err(|e| format!("invalid SOLANA_PROGRAM_ID: {}", e))?;
+        let submit = std::env::var("SOLANA_SUBMIT_TRANSACTIONS")
+            .map(|value| value == "true" || value == "1")
+            .unwrap_or(false);
+
+        let authority = match std::env::var("SOLANA_PRIVATE_KEY") {
+            Ok(secret) => parse_keypair(&secret).map_err(|e| format!("invalid SOLANA_PRIVATE_KEY: {}", e))?,
+            Err(_) if submit => return Err("SOLANA_PRIVATE_KEY must be set to submit reward transfers".to_string()),
+            Err(_) => SigningKey::from_bytes(&rand::random()),
+        };
+
+        let client = Self::new(rpc_url, program_id, authority);
+        Ok(if submit { client.submitting() } else { client })
+    }
+
+    /// The reward pool account rewards are paid from
+    pub fn reward_pool(&self) -> Pubkey {
+        find_program_address(&[REWARD_POOL_SEED], &self.program_id)
+    }
+
+    fn claim_instruction(&self, wallet: Pubkey, amount: u64) -> (Vec<AccountMeta>, Vec<u8>) {
+        let mut data = Sha256::digest(CLAIM_REWARDS_INSTRUCTION.as_bytes())[..8].to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        let accounts = vec![
+            AccountMeta { pubkey: self.reward_pool(), is_signer: false, is_writable: true },
+            AccountMeta { pubkey: wallet, is_signer: false, is_writable: true },
+            AccountMeta { pubkey: self.authority.verifying_key().to_bytes(), is_signer: true, is_writable: false },
+        ];
+        (accounts, data)
+    }
+
+    /// Wire format of `message` signed by the authority, its only signer
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        let mut transaction = compact_u16(1);
+        transaction.extend_from_slice(&self.authority.sign(message).to_bytes());
+        transaction.extend_from_slice(message);
+        transaction
+    }
+
+    /// Call a JSON-RPC method of the node
+    async fn rpc<T: DeserializeOwned>(&self, method: &str, params: serde_json::Value) -> Result<T, String> {
+        let response: RpcResponse<T> = self
+            .client
+            .post(&self.rpc_url)
+            .json(&serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| format!("{} request failed: {}", method, e))?
+            .json()
+            .await
+            .map_err(|e| format!("invalid {} response: {}", method, e))?;
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(format!("{} failed: {}", method, error.message)),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(format!("{} returned no result", method)),
+        }
+    }
+
+    /// Wait until the cluster confirms the transaction, failing if it was rejected or
+    /// is still unconfirmed once its blockhash has expired
+    async fn confirm(&self, signature: &str) -> Result<(), String> {
+        for _ in 0..CONFIRMATION_POLLS {
+            let statuses: RpcContext<Vec<Option<SignatureStatus>>> =
+                self.rpc("getSignatureStatuses", serde_json::json!([[signature]])).await?;
+            if let Some(Some(status)) = statuses.value.into_iter().next() {
+                if let Some(err) = status.err {
+                    return Err(format!("transaction {} failed: {}", signature, err));
+                }
+                if matches!(status.confirmation_status.as_deref(), Some("confirmed" | "finalized")) {
+                    return Ok(());
+                }
+            }
+            tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+        }
+        Err(format!("transaction {} was not confirmed", signature))
+    }
+}
+
+impl BlockchainClient for SolanaBlockchainClient {
+    fn transfer_transaction(&self, wallet: &str, amount: u64) -> Result<Vec<u8>, String> {
+        let wallet = SolanaAddress::parse(wallet).map_err(|e| format!("{} is not a Solana wallet: {}", wallet, e))?;
+        let (accounts, data) = self.claim_instruction(*wallet.as_bytes(), amount);
+
+        // Signed against a placeholder blockhash until submission fetches the latest one
+        let payer = self.authority.verifying_key().to_bytes();
+        let message = compile_message(payer, self.program_id, accounts, &data, [0; 32]);
+        Ok(self.sign(&message))
+    }
+
+    fn send_transaction<'a>(&'a self, transaction: &'a [u8]) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(async move {
+            if !self.submit {
+                log::info!(
+                    "Not submitting reward transfer {}: SOLANA_SUBMIT_TRANSACTIONS is not enabled",
+                    base64::engine::general_purpose::STANDARD.encode(transaction)
+                );
+                return Err("submitting transactions is not enabled".to_string());
+            }
+            let mut message = unsigned_message(transaction, &self.authority.verifying_key().to_bytes())?;
+
+            let latest: RpcContext<LatestBlockhash> =
+                self.rpc("getLatestBlockhash", serde_json::json!([{ "commitment": "confirmed" }])).await?;
+            let blockhash = parse_pubkey(&latest.value.blockhash).map_err(|e| format!("invalid blockhash: {}", e))?;
+            let offset = blockhash_offset(&message).ok_or("transaction message is truncated")?;
+            message[offset..offset + 32].copy_from_slice(&blockhash);
+
+            let transaction = base64::engine::general_purpose::STANDARD.encode(self.sign(&message));
+            let signature: String = self
+                .rpc(
+                    "sendTransaction",
+                    serde_json::json!([transaction, { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+                )
+                .await?;
+            log::info!("Submitted transaction {} to {}", signature, self.rpc_url);
+
+            self.confirm(&signature).await?;
+            Ok(signature)
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+/// Result of an RPC method answered as of a slot
+#[derive(Deserialize)]
+struct RpcContext<T> {
+    value: T,
+}
+
+#[derive(Deserialize)]
+struct LatestBlockhash {
+    blockhash: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignatureStatus {
+    err: Option<serde_json::Value>,
+    confirmation_status: Option<String>,
+}
+
+/// A base58 account address; unlike wallets, these may lie off the Ed25519 curve
+fn parse_pubkey(s: &str) -> Result<Pubkey, String> {
+    let bytes = bs58::decode(s.trim()).into_vec().map_err(|e| e.to_string())?;
+    bytes.try_into().map_err(|bytes: Vec<u8>| format!("expected 32 bytes, got {}", bytes.len()))
+}
+
+/// A keypair as 64 bytes, the secret key followed by its public key, written in base58 or
+/// as a keypair file's JSON byte array
+fn parse_keypair(secret: &str) -> Result<SigningKey, String> {
+    let bytes = if secret.trim_start().starts_with('[') {
+        serde_json::from_str::<Vec<u8>>(secret).map_err(|e| e.to_string())?
+    } else {
+        bs58::decode(secret.trim()).into_vec().map_err(|e| e.to_string())?
+    };
+    let bytes: [u8; 64] =
+        bytes.try_into().map_err(|bytes: Vec<u8>| format!("expected 64 bytes, got {}", bytes.len()))?;
+    SigningKey::from_keypair_bytes(&bytes).map_err(|e| e.to_string())
+}
+
+/// The program derived address of `seeds`: the first hash, counting the bump seed down from
+/// 255, that is not an Ed25519 public key, so that no private key can sign for it
+fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+    (0..=u8::MAX)
+        .rev()
+        .find_map(|bump| {
+            let mut hasher = Sha256::new();
+            for seed in seeds {
+                hasher.update(seed);
+            }
+            hasher.update([bump]);
+            hasher.update(program_id);
+            hasher.update(b"ProgramDerivedAddress");
+            let address: Pubkey = hasher.finalize().into();
+            CompressedEdwardsY(address).decompress().is_none().then_some(address)
+        })
+        .expect("a bump seed yields an off-curve address")
+}
+
+/// Solana's shortvec length prefix: seven bits per byte, lowest first
+fn compact_u16(mut value: u16) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(3);
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+/// A shortvec length at the start of `bytes`, and how many bytes it took
+fn read_compact_u16(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut value = 0usize;
+    for (i, byte) in bytes.iter().take(3).enumerate() {
+        value |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Legacy message of a single instruction paid for by `payer`. Accounts are listed
+/// writable signers first, then read-only signers, writable and read-only others, with
+/// the payer always first.
+fn compile_message(
+    payer: Pubkey,
+    program_id: Pubkey,
+    accounts: Vec<AccountMeta>,
+    data: &[u8],
+    blockhash: [u8; 32],
+) -> Vec<u8> {
+    let instruction_accounts: Vec<Pubkey> = accounts.iter().map(|account| account.pubkey).collect();
+    let program = AccountMeta { pubkey: program_id, is_signer: false, is_writable: false };
+
+    let mut keys = vec![AccountMeta { pubkey: payer, is_signer: true, is_writable: true }];
+    for account in accounts.into_iter().chain([program]) {
+        match keys.iter_mut().find(|key| key.pubkey == account.pubkey) {
+            Some(key) => {
+                key.is_signer |= account.is_signer;
+                key.is_writable |= account.is_writable;
+            }
+            None => keys.push(account),
+        }
+    }
+    // Stable, so the payer stays first
+    keys.sort_by_key(|key| (!key.is_signer, !key.is_writable));
+
+    let count = |signer: bool, writable: bool| {
+        keys.iter().filter(|key| key.is_signer == signer && key.is_writable == writable).count() as u8
+    };
+    let index = |pubkey: &Pubkey| keys.iter().position(|key| key.pubkey == *pubkey).unwrap_or_default() as u8;
+
+    let signers = count(true, true) + count(true, false);
+    let mut message = vec![signers, count(true, false), count(false, false)];
+    message.extend(compact_u16(keys.len() as u16));
+    for key in &keys {
+        message.extend_from_slice(&key.pubkey);
+    }
+    message.extend_from_slice(&blockhash);
+
+    // One instruction: the program, its accounts by index and its data
+    message.extend(compact_u16(1));
+    message.push(index(&program_id));
+    message.extend(compact_u16(instruction_accounts.len() as u16));
+    message.extend(instruction_accounts.iter().map(index));
+    message.extend(compact_u16(data.len() as u16));
+    message.extend_from_slice(data);
+    message
+}
+
+/// Where the recent blockhash starts in a message: after its header and account keys
+fn blockhash_offset(message: &[u8]) -> Option<usize> {
+    let (keys, length) = read_compact_u16(message.get(3..)?)?;
+    let offset = 3 + length + keys * 32;
+    (message.len() >= offset + 32).then_some(offset)
+}
+
+/// The message of a transaction `transfer_transaction` built, which only `authority` signs
+fn unsigned_message(transaction: &[u8], authority: &Pubkey) -> Result<Vec<u8>, String> {
+    let (signatures, length) = read_compact_u16(transaction).ok_or("transaction is empty")?;
+    let message = transaction.get(length + signatures * 64..).ok_or("transaction is truncated")?;
+    let signer = message.get(4..36).ok_or("transaction message is truncated")?;
+    if signatures != 1 || message[0] != 1 || signer != authority {
+        return Err("transaction is not signed by the reward authority alone".to_string());
+    }
+    Ok(message.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier};
+
+    fn client() -> SolanaBlockchainClient {
+        let program_id = parse_pubkey(DEFAULT_PROGRAM_ID).unwrap();
+        SolanaBlockchainClient::new(DEFAULT_RPC_URL.to_string(), program_id, SigningKey::from_bytes(&[7; 32]))
+    }
+
+    #[test]
+    fn test_keypairs_parse_from_base58_and_json() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let bytes = key.to_keypair_bytes();
+        assert_eq!(parse_keypair(&bs58::encode(bytes).into_string()).unwrap(), key);
+        assert_eq!(parse_keypair(&serde_json::to_string(&bytes.to_vec()).unwrap()).unwrap(), key);
+
+        // The public half must match the secret one
+        let mut mismatched = bytes;
+        mismatched[63] ^= 1;
+        assert!(parse_keypair(&bs58::encode(mismatched).into_string()).is_err());
+        assert!(parse_keypair("[1, 2, 3]").is_err());
+    }
+
+    #[test]
+    fn test_shortvec_lengths() {
+        let cases = [(0, vec![0x00]), (0x7f, vec![0x7f]), (0x80, vec![0x80, 0x01]), (0x3fff, vec![0xff, 0x7f])];
+        for (value, encoded) in cases {
+            assert_eq!(compact_u16(value), encoded);
+            assert_eq!(read_compact_u16(&encoded), Some((value as usize, encoded.len())));
+        }
+    }
+
+    #[test]
+    fn test_transfer_is_a_signed_claim_from_the_pool() {
+        let client = client();
+        let authority = client.authority.verifying_key();
+        let wallet = parse_pubkey("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU").unwrap();
+        let transaction = client.transfer_transaction(&bs58::encode(wallet).into_string(), 1_500).unwrap();
+
+        let message = unsigned_message(&transaction, &authority.to_bytes()).unwrap();
+        let signature = Signature::from_slice(&transaction[1..65]).unwrap();
+        assert!(authority.verify(&message, &signature).is_ok());
+
+        // Payer; writable pool and wallet; the read-only program
+        assert_eq!(message[..4], [1, 0, 1, 4]);
+        let keys: Vec<&[u8]> = message[4..4 + 4 * 32].chunks(32).collect();
+        let pool = client.reward_pool();
+        assert_eq!(keys, [&authority.to_bytes()[..], &pool, &wallet, &client.program_id]);
+        assert!(CompressedEdwardsY(pool).decompress().is_none());
+
+        let instruction = &message[blockhash_offset(&message).unwrap() + 32..];
+        assert_eq!(instruction[..6], [1, 3, 3, 1, 2, 0]);
+        assert_eq!(instruction[6], 16);
+        assert_eq!(instruction[15..], 1_500u64.to_le_bytes());
+
+        assert!(client.transfer_transaction("not-a-wallet", 1).is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_transfers_are_only_logged_unless_submission_is_enabled() {
+        let client = SolanaBlockchainClient::logging_only();
+        let transaction = client.transfer_transaction("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU", 1_500).unwrap();
+        let refused = client.send_transaction(&transaction).await.unwrap_err();
+        assert!(refused.contains("not enabled"), "{}", refused);
+        assert!(!client.submit && client.submitting().submit);
+    }
+}
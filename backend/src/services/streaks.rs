@@ -0,0 +1,71 @@
+use chrono::NaiveDate;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::user_streak::UserStreak;
+use crate::repositories::{RepositoryError, StreakRepository};
+
+/// Daily content creation streaks
+pub struct StreakService {
+    repository: Arc<StreakRepository>,
+}
+
+impl StreakService {
+    pub fn new(repository: Arc<StreakRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// A user's streak; users who never created content have an empty one
+    pub async fn get(&self, user_id: Uuid, today: NaiveDate) -> Result<UserStreak, RepositoryError> {
+        Ok(self
+            .repository
+            .find(user_id)
+            .await?
+            .unwrap_or_else(|| UserStreak::new(user_id, today)))
+    }
+
+    /// Count content creation on `today` towards the user's streak
+    pub async fn record_activity(&self, user_id: Uuid, today: NaiveDate) -> Result<UserStreak, RepositoryError> {
+        self.repository
+            .update(user_id, today, |streak| {
+                streak.record_activity(today);
+                Ok(())
+            })
+            .await
+    }
+
+    /// Spend a freeze token so that `today` counts without new content
+    pub async fn freeze(&self, user_id: Uuid, today: NaiveDate) -> Result<UserStreak, RepositoryError> {
+        self.repository.update(user_id, today, |streak| streak.freeze(today)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Days;
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_streak_persists_across_days(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xstreak') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let service = StreakService::new(Arc::new(StreakRepository::new(pool)));
+        let start = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        assert_eq!(service.get(user_id, start).await.unwrap().current_streak_days, 0);
+        assert!(matches!(service.freeze(user_id, start).await, Err(RepositoryError::Conflict(_))));
+
+        for offset in 0..7 {
+            service.record_activity(user_id, start + Days::new(offset)).await.unwrap();
+        }
+        let frozen = service.freeze(user_id, start + Days::new(7)).await.unwrap();
+        assert_eq!(frozen.streak_freeze_tokens, 0);
+
+        let streak = service.record_activity(user_id, start + Days::new(8)).await.unwrap();
+        assert_eq!(streak.current_streak_days, 8);
+        assert_eq!(service.get(user_id, start).await.unwrap(), streak);
+    }
+}
@@ -0,0 +1,220 @@
+use chrono::Duration;
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::services::rewards::{EchoDropReward, RewardType};
+
+/// More content creation rewards than this within an hour look like farming
+const DEFAULT_MAX_CREATIONS_PER_HOUR: usize = 10;
+/// Velocities this many standard deviations above the platform mean are outliers
+const VELOCITY_OUTLIER_SIGMAS: f64 = 3.0;
+
+/// Score contribution of each heuristic; the sum is capped at 1.0
+const RAPID_CREATION_RISK: f64 = 0.5;
+const SELF_PROPAGATION_RISK: f64 = 0.5;
+const VELOCITY_OUTLIER_RISK: f64 = 0.4;
+const ECHO_INDEX_ANOMALY_RISK: f64 = 1.0;
+
+/// A reason a user's rewards look gamed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum SuspicionFlag {
+    RapidContentCreation { count: usize },
+    SelfPropagation { content_id: String },
+    VelocityOutlier { velocity: f64, platform_mean: f64 },
+    /// The rewarded content's Echo Index jumped anomalously and awaits operator review
+    EchoIndexAnomaly { content_id: String },
+}
+
+/// Outcome of analyzing a user's recent rewards
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SuspicionReport {
+    pub user_id: String,
+    /// Likelihood the rewards are being farmed (0.0 = clean, 1.0 = certain)
+    pub risk_score: f64,
+    pub flags: Vec<SuspicionFlag>,
+}
+
+impl SuspicionReport {
+    /// Report holding a reward while its content's Echo Index anomaly awaits review
+    pub fn echo_index_anomaly(user_id: &str, content_id: &str) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            risk_score: ECHO_INDEX_ANOMALY_RISK,
+            flags: vec![SuspicionFlag::EchoIndexAnomaly { content_id: content_id.to_string() }],
+        }
+    }
+
+    /// Whether the report holds a reward only for its content's Echo Index anomaly
+    pub fn is_echo_index_anomaly(&self) -> bool {
+        self.flags.iter().all(|flag| matches!(flag, SuspicionFlag::EchoIndexAnomaly { .. }))
+    }
+}
+
+/// Heuristic detection of reward gaming
+pub struct SuspicionAnalyzer {
+    max_creations_per_hour: usize,
+    platform_velocities: Vec<f64>,
+}
+
+impl SuspicionAnalyzer {
+    pub fn new(max_creations_per_hour: usize) -> Self {
+        Self {
+            max_creations_per_hour,
+            platform_velocities: Vec::new(),
+        }
+    }
+
+    /// Provide the reward velocity (per hour) of every user on the platform
+    pub fn with_platform_velocities(mut self, platform_velocities: Vec<f64>) -> Self {
+        self.platform_velocities = platform_velocities;
+        self
+    }
+
+    pub fn analyze(&self, user_id: &str, recent_rewards: &[EchoDropReward]) -> SuspicionReport {
+        let mut flags = Vec::new();
+
+        let creations = self.creations_in_last_hour(recent_rewards);
+        if creations > self.max_creations_per_hour {
+            flags.push(SuspicionFlag::RapidContentCreation { count: creations });
+        }
+
+        // Propagating one's own content shows up as a propagation reward on content
+        // the same user was rewarded for creating
+        let created: HashSet<&str> = recent_rewards
+            .iter()
+            .filter(|r| matches!(r.reward_type, RewardType::ContentCreation))
+            .map(|r| r.content_id.as_str())
+            .collect();
+        let mut self_propagated: Vec<&str> = recent_rewards
+            .iter()
+            .filter(|r| matches!(r.reward_type, RewardType::PropagationBonus))
+            .map(|r| r.content_id.as_str())
+            .filter(|content_id| created.contains(content_id))
+            .collect();
+        self_propagated.sort_unstable();
+        self_propagated.dedup();
+        flags.extend(self_propagated.into_iter().map(|content_id| SuspicionFlag::SelfPropagation {
+            content_id: content_id.to_string(),
+        }));
+
+        if let Some(flag) = self.velocity_outlier(recent_rewards) {
+            flags.push(flag);
+        }
+
+        let risk_score = flags
+            .iter()
+            .map(|flag| match flag {
+                SuspicionFlag::RapidContentCreation { .. } => RAPID_CREATION_RISK,
+                SuspicionFlag::SelfPropagation { .. } => SELF_PROPAGATION_RISK,
+                SuspicionFlag::VelocityOutlier { .. } => VELOCITY_OUTLIER_RISK,
+                SuspicionFlag::EchoIndexAnomaly { .. } => ECHO_INDEX_ANOMALY_RISK,
+            })
+            .sum::<f64>()
+            .min(1.0);
+
+        SuspicionReport {
+            user_id: user_id.to_string(),
+            risk_score,
+            flags,
+        }
+    }
+
+    /// Content creation rewards in the hour up to the most recent reward
+    fn creations_in_last_hour(&self, rewards: &[EchoDropReward]) -> usize {
+        let Some(latest) = rewards.iter().map(|r| r.timestamp).max() else {
+            return 0;
+        };
+        let cutoff = latest - Duration::hours(1);
+
+        rewards
+            .iter()
+            .filter(|r| matches!(r.reward_type, RewardType::ContentCreation) && r.timestamp > cutoff)
+            .count()
+    }
+
+    /// Rewards per hour over the last day, compared against the platform distribution
+    fn velocity_outlier(&self, rewards: &[EchoDropReward]) -> Option<SuspicionFlag> {
+        if self.platform_velocities.len() < 2 {
+            return None;
+        }
+
+        let latest = rewards.iter().map(|r| r.timestamp).max()?;
+        let cutoff = latest - Duration::hours(24);
+        let velocity = rewards
+            .iter()
+            .filter(|r| r.timestamp >= cutoff)
+            .map(|r| r.amount)
+            .sum::<f64>()
+            / 24.0;
+
+        let n = self.platform_velocities.len() as f64;
+        let mean = self.platform_velocities.iter().sum::<f64>() / n;
+        let variance = self.platform_velocities.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        let threshold = mean + VELOCITY_OUTLIER_SIGMAS * variance.sqrt();
+
+        (velocity > threshold).then_some(SuspicionFlag::VelocityOutlier {
+            velocity,
+            platform_mean: mean,
+        })
+    }
+}
+
+impl Default for SuspicionAnalyzer {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CREATIONS_PER_HOUR)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::rewards::VestingSchedule;
+    use chrono::Utc;
+
+    fn reward(content_id: &str, reward_type: RewardType, amount: f64) -> EchoDropReward {
+        let now = Utc::now();
+        EchoDropReward {
+            id: format!("reward_{}", content_id),
+            user_id: "farmer".to_string(),
+            content_id: content_id.to_string(),
+            reward_type,
+            amount,
+            echo_index_contribution: 0.1,
+            timestamp: now,
+            transaction_hash: None,
+            vesting_schedule: VestingSchedule::Immediate,
+            vested_amount: 0.0,
+            vest_start: now,
+            on_hold: false,
+            frozen: false,
+        }
+    }
+
+    #[test]
+    fn test_rapid_self_propagation_is_high_risk() {
+        let mut rewards: Vec<_> = (0..4)
+            .map(|i| reward(&format!("content_{}", i), RewardType::ContentCreation, 1.0))
+            .collect();
+        rewards.push(reward("content_0", RewardType::PropagationBonus, 1.0));
+
+        let report = SuspicionAnalyzer::new(3).analyze("farmer", &rewards);
+
+        assert_eq!(report.flags.len(), 2);
+        assert!(report.flags.contains(&SuspicionFlag::RapidContentCreation { count: 4 }));
+        assert!(report.risk_score > 0.8);
+    }
+
+    #[test]
+    fn test_velocity_outlier_against_platform() {
+        let rewards = vec![reward("content_0", RewardType::QualityBonus, 2_400.0)];
+        let analyzer = SuspicionAnalyzer::default().with_platform_velocities(vec![1.0, 2.0, 1.5, 0.5, 20.0]);
+
+        let report = analyzer.analyze("farmer", &rewards);
+        assert!(matches!(report.flags[..], [SuspicionFlag::VelocityOutlier { .. }]));
+        assert!(report.risk_score < 0.8);
+
+        let clean = analyzer.analyze("farmer", &[reward("content_0", RewardType::QualityBonus, 24.0)]);
+        assert!(clean.flags.is_empty());
+    }
+}
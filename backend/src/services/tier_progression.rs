@@ -0,0 +1,230 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::user::UserTier;
+use crate::models::user_event::UserEvent;
+use crate::models::webhook::WebhookEvent;
+use crate::repositories::{RepositoryError, UserEventRepository, UserRepository};
+use crate::services::WebhookDispatcher;
+
+/// Echo Score and rewards a user needs for each tier above Basic, and how much of the
+/// propagation reward fee each tier gets back
+#[derive(Debug, Clone, PartialEq)]
+pub struct TierConfig {
+    pub bronze_echo_score: f64,
+    pub silver_echo_score: f64,
+    pub gold_echo_score: f64,
+    pub bronze_rewards: f64,
+    pub silver_rewards: f64,
+    pub gold_rewards: f64,
+    /// Share of propagation rewards withheld as a fee, in percent
+    pub propagation_fee_pct: f64,
+    /// Share of the propagation fee rebated for each tier above Basic, in percent
+    pub fee_rebate_pct: f64,
+}
+
+impl Default for TierConfig {
+    fn default() -> Self {
+        Self {
+            bronze_echo_score: 40.0,
+            silver_echo_score: 60.0,
+            gold_echo_score: 80.0,
+            bronze_rewards: 100.0,
+            silver_rewards: 1_000.0,
+            gold_rewards: 10_000.0,
+            propagation_fee_pct: 10.0,
+            fee_rebate_pct: 25.0,
+        }
+    }
+}
+
+impl TierConfig {
+    /// Settings from `TIER_<SETTING>`, e.g. `TIER_GOLD_ECHO_SCORE`, each defaulting on its own
+    pub fn from_env() -> Self {
+        let setting = |name: &str, default: f64| {
+            std::env::var(format!("TIER_{}", name))
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(default)
+        };
+        let defaults = Self::default();
+        Self {
+            bronze_echo_score: setting("BRONZE_ECHO_SCORE", defaults.bronze_echo_score),
+            silver_echo_score: setting("SILVER_ECHO_SCORE", defaults.silver_echo_score),
+            gold_echo_score: setting("GOLD_ECHO_SCORE", defaults.gold_echo_score),
+            bronze_rewards: setting("BRONZE_REWARDS", defaults.bronze_rewards),
+            silver_rewards: setting("SILVER_REWARDS", defaults.silver_rewards),
+            gold_rewards: setting("GOLD_REWARDS", defaults.gold_rewards),
+            propagation_fee_pct: setting("PROPAGATION_FEE_PCT", defaults.propagation_fee_pct).min(100.0),
+            fee_rebate_pct: setting("FEE_REBATE_PCT", defaults.fee_rebate_pct),
+        }
+    }
+
+    /// Highest tier whose Echo Score and rewards thresholds are both met
+    pub fn tier_for(&self, echo_score: f64, total_rewards: f64) -> UserTier {
+        [
+            (UserTier::Gold, self.gold_echo_score, self.gold_rewards),
+            (UserTier::Silver, self.silver_echo_score, self.silver_rewards),
+            (UserTier::Bronze, self.bronze_echo_score, self.bronze_rewards),
+        ]
+        .into_iter()
+        .find(|(_, min_echo_score, min_rewards)| echo_score >= *min_echo_score && total_rewards >= *min_rewards)
+        .map_or(UserTier::Basic, |(tier, _, _)| tier)
+    }
+
+    /// Share of a propagation reward withheld from a user in `tier`, in [0, 1]
+    pub fn propagation_fee(&self, tier: UserTier) -> f64 {
+        let rebate = (self.fee_rebate_pct * tier.level() as f64).min(100.0);
+        self.propagation_fee_pct / 100.0 * (1.0 - rebate / 100.0)
+    }
+}
+
+/// A user moving up a tier
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TierChange {
+    pub old_tier: UserTier,
+    pub new_tier: UserTier,
+    pub upgraded_at: DateTime<Utc>,
+}
+
+/// Moves users up the tiers as their Echo Score and rewards grow. Tiers are sticky: a
+/// user whose score or rewards fall back keeps the tier they earned.
+pub struct UserTierProgressionService {
+    users: Arc<UserRepository>,
+    events: Arc<UserEventRepository>,
+    webhooks: Option<Arc<WebhookDispatcher>>,
+    config: TierConfig,
+}
+
+impl UserTierProgressionService {
+    pub fn new(users: Arc<UserRepository>, events: Arc<UserEventRepository>, config: TierConfig) -> Self {
+        Self { users, events, webhooks: None, config }
+    }
+
+    /// Notify users' webhooks of their upgrades
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookDispatcher>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Move the user up to the tier their Echo Score and rewards earn, recording the
+    /// upgrade on their timeline and notifying their webhooks. None if their tier stays.
+    pub async fn check_and_upgrade(&self, user_id: Uuid) -> Result<Option<TierChange>, RepositoryError> {
+        let user = self.users.find_by_id(user_id).await?.ok_or(RepositoryError::NotFound)?;
+        let new_tier = self.config.tier_for(user.echo_score, user.total_rewards);
+        if new_tier <= user.tier {
+            return Ok(None);
+        }
+        // Lost to a concurrent upgrade that got there first
+        let Some(upgraded_at) = self.users.upgrade_tier(user_id, new_tier).await? else {
+            return Ok(None);
+        };
+
+        let change = TierChange { old_tier: user.tier, new_tier, upgraded_at };
+        let event = UserEvent::TierChanged {
+            old_tier: change.old_tier.as_str().to_string(),
+            new_tier: change.new_tier.as_str().to_string(),
+        };
+        if let Err(e) = self.events.record(user_id, &event).await {
+            log::warn!("Failed to record tier upgrade of {} on timeline: {}", user_id, e);
+        }
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.notify(user_id, WebhookEvent::TierChanged, serde_json::json!(change));
+        }
+
+        Ok(Some(change))
+    }
+
+    /// Recompute the user's Echo Score from their content, then check for an upgrade
+    pub async fn update_echo_score(&self, user_id: Uuid) -> Result<Option<TierChange>, RepositoryError> {
+        self.users.update_echo_score(user_id).await?;
+        self.check_and_upgrade(user_id).await
+    }
+
+    /// Add to the user's earned rewards, then check for an upgrade
+    pub async fn add_rewards(&self, user_id: Uuid, amount: f64) -> Result<Option<TierChange>, RepositoryError> {
+        self.users.add_rewards(user_id, amount).await?;
+        self.check_and_upgrade(user_id).await
+    }
+
+    /// Share of a propagation reward withheld from the user, the Basic tier's if they
+    /// cannot be looked up
+    pub async fn propagation_fee(&self, user_id: Uuid) -> f64 {
+        let tier = match self.users.find_by_id(user_id).await {
+            Ok(user) => user.map(|user| user.tier).unwrap_or_default(),
+            Err(e) => {
+                log::warn!("Failed to look up tier of {}: {}", user_id, e);
+                UserTier::Basic
+            }
+        };
+        self.config.propagation_fee(tier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    #[test]
+    fn test_both_thresholds_must_be_met() {
+        let config = TierConfig::default();
+        assert_eq!(config.tier_for(0.0, 0.0), UserTier::Basic);
+        assert_eq!(config.tier_for(95.0, 150.0), UserTier::Bronze);
+        assert_eq!(config.tier_for(45.0, 50_000.0), UserTier::Bronze);
+        assert_eq!(config.tier_for(65.0, 1_000.0), UserTier::Silver);
+        assert_eq!(config.tier_for(80.0, 10_000.0), UserTier::Gold);
+    }
+
+    #[test]
+    fn test_higher_tiers_pay_lower_fees() {
+        let config = TierConfig::default();
+        let tiers = [UserTier::Basic, UserTier::Bronze, UserTier::Silver, UserTier::Gold];
+        for (tier, fee) in tiers.into_iter().zip([0.1, 0.075, 0.05, 0.025]) {
+            assert!((config.propagation_fee(tier) - fee).abs() < 1e-9, "{:?}", tier);
+        }
+
+        // Rebates never exceed the fee
+        let generous = TierConfig { fee_rebate_pct: 50.0, ..config };
+        assert_eq!(generous.propagation_fee(UserTier::Gold), 0.0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_upgrades_are_sticky(pool: PgPool) {
+        let users = Arc::new(UserRepository::new(pool.clone()));
+        let events = Arc::new(UserEventRepository::new(pool.clone()));
+        let tiers = UserTierProgressionService::new(users.clone(), events.clone(), TierConfig::default());
+        let user_id = users.create("0xtiers", None, None).await.unwrap().id;
+        let set_score = |score: f64| {
+            sqlx::query("UPDATE users SET echo_score = $2 WHERE id = $1").bind(user_id).bind(score).execute(&pool)
+        };
+
+        // Rewards alone earn nothing
+        assert_eq!(tiers.add_rewards(user_id, 2_000.0).await.unwrap(), None);
+
+        set_score(70.0).await.unwrap();
+        let change = tiers.check_and_upgrade(user_id).await.unwrap().unwrap();
+        assert_eq!((change.old_tier, change.new_tier), (UserTier::Basic, UserTier::Silver));
+        assert_eq!(tiers.check_and_upgrade(user_id).await.unwrap(), None);
+
+        // A falling score keeps the tier
+        set_score(10.0).await.unwrap();
+        assert_eq!(tiers.check_and_upgrade(user_id).await.unwrap(), None);
+        let user = users.find_by_id(user_id).await.unwrap().unwrap();
+        assert_eq!((user.tier, user.total_rewards), (UserTier::Silver, 2_000.0));
+        assert!((tiers.propagation_fee(user_id).await - 0.05).abs() < 1e-9);
+
+        let upgrades: Vec<UserEvent> = events
+            .all(user_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|record| record.event)
+            .filter(|event| matches!(event, UserEvent::TierChanged { .. }))
+            .collect();
+        assert_eq!(upgrades.len(), 1);
+    }
+}
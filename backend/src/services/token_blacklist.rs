@@ -0,0 +1,91 @@
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+use crate::handlers::auth::ACCESS_TOKEN_TTL_HOURS;
+
+/// Longest a token stays blacklisted, as no access token is valid for longer
+const MAX_REVOCATION: Duration = Duration::from_secs(ACCESS_TOKEN_TTL_HOURS as u64 * 3600);
+
+/// In-memory blacklist of revoked access tokens, keyed by the JWT `jti` claim
+pub struct TokenBlacklist {
+    revoked: DashMap<String, Instant>,
+}
+
+impl TokenBlacklist {
+    pub fn new() -> Self {
+        Self {
+            revoked: DashMap::new(),
+        }
+    }
+
+    /// Revoke a token until its `exp` timestamp (seconds since epoch), or for at most the
+    /// access token TTL
+    pub fn revoke(&self, jti: &str, exp: usize) {
+        let now = chrono::Utc::now().timestamp().max(0) as usize;
+        let remaining = Duration::from_secs(exp.saturating_sub(now) as u64).min(MAX_REVOCATION);
+        match Instant::now().checked_add(remaining) {
+            Some(evict_at) => {
+                self.revoked.insert(jti.to_string(), evict_at);
+            }
+            None => log::warn!("Cannot blacklist token {} for {:?}", jti, remaining),
+        }
+    }
+
+    /// Check whether a token has been revoked and has not yet expired
+    pub fn is_revoked(&self, jti: &str) -> bool {
+        self.revoked
+            .get(jti)
+            .map(|evict_at| *evict_at > Instant::now())
+            .unwrap_or(false)
+    }
+
+    /// Drop entries whose tokens have expired on their own
+    pub fn evict_expired(&self) -> usize {
+        let before = self.revoked.len();
+        let now = Instant::now();
+        self.revoked.retain(|_, evict_at| *evict_at > now);
+        before - self.revoked.len()
+    }
+
+    /// Number of tokens currently tracked
+    pub fn blacklist_size(&self) -> usize {
+        self.revoked.len()
+    }
+
+    /// Spawn a background task that evicts expired entries on a fixed interval
+    pub fn spawn_eviction_task(self: Arc<Self>, period: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let evicted = self.evict_expired();
+                if evicted > 0 {
+                    log::debug!("Evicted {} expired tokens from blacklist", evicted);
+                }
+            }
+        })
+    }
+}
+
+impl Default for TokenBlacklist {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_far_future_expiry_is_capped() {
+        let blacklist = TokenBlacklist::new();
+        blacklist.revoke("jti_forever", usize::MAX);
+        assert!(blacklist.is_revoked("jti_forever"));
+
+        let evict_at = *blacklist.revoked.get("jti_forever").unwrap();
+        assert!(evict_at <= Instant::now() + MAX_REVOCATION);
+    }
+}
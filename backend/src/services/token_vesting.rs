@@ -0,0 +1,271 @@
+use chrono::{DateTime, Utc};
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::repositories::{RepositoryError, UserRepository};
+use crate::services::{EchoDropReward, RewardService};
+
+/// Decimals of the EchoDrop token mint; rewards are whole tokens
+pub const ECHODROP_DECIMALS: i32 = 9;
+
+/// Builds and submits reward pool transfers on chain
+pub trait BlockchainClient: Send + Sync {
+    /// Serialized transaction transferring `amount` base units from the reward pool to `wallet`
+    fn transfer_transaction(&self, wallet: &str, amount: u64) -> Result<Vec<u8>, String>;
+
+    /// Submit a serialized transaction, returning its signature
+    fn send_transaction<'a>(&'a self, transaction: &'a [u8]) -> BoxFuture<'a, Result<String, String>>;
+}
+
+/// Token base units in `amount` whole EchoDrops
+pub fn to_base_units(amount: f64) -> u64 {
+    (amount * 10f64.powi(ECHODROP_DECIMALS)).round().max(0.0) as u64
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SettlementError {
+    #[error("a settlement is already in flight for this user")]
+    InFlight,
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+    #[error("transaction failed: {0}")]
+    Transaction(String),
+    #[error("reward error: {0}")]
+    Reward(String),
+}
+
+/// A reward payout submitted on chain and not yet recorded as released
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingSettlement {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub wallet: String,
+    pub amount: f64,
+    pub base_units: u64,
+    /// Set once the transaction was submitted
+    pub signature: Option<String>,
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// A completed payout of a user's vested rewards
+#[derive(Debug, Clone, Serialize)]
+pub struct Settlement {
+    pub signature: String,
+    pub amount: f64,
+    pub base_units: u64,
+    pub releases: Vec<EchoDropReward>,
+}
+
+/// Pays out vested rewards from the reward pool to users' wallets. A user has at most
+/// one settlement in flight, so a payout can never be sent twice.
+pub struct TokenVestingService {
+    users: Arc<UserRepository>,
+    rewards: Arc<RwLock<RewardService>>,
+    client: Arc<dyn BlockchainClient>,
+    in_flight: DashMap<Uuid, PendingSettlement>,
+}
+
+impl TokenVestingService {
+    pub fn new(
+        users: Arc<UserRepository>,
+        rewards: Arc<RwLock<RewardService>>,
+        client: Arc<dyn BlockchainClient>,
+    ) -> Self {
+        Self { users, rewards, client, in_flight: DashMap::new() }
+    }
+
+    /// Settlements of the user that are still in flight
+    pub fn pending_transactions(&self, user_id: Uuid) -> Vec<PendingSettlement> {
+        self.in_flight.get(&user_id).map(|pending| pending.clone()).into_iter().collect()
+    }
+
+    /// Transfer everything the user has vested by `as_of` to their wallet and release it,
+    /// storing the transaction signature on the releases. Returns None when nothing is
+    /// claimable.
+    pub async fn process_batch(
+        &self,
+        user_id: Uuid,
+        as_of: DateTime<Utc>,
+    ) -> Result<Option<Settlement>, SettlementError> {
+        let wallet = self.users.find_by_id(user_id).await?.ok_or(RepositoryError::NotFound)?.wallet_address;
+
+        let amount = self.rewards.read().await.get_user_claimable_rewards(&user_id.to_string(), as_of);
+        let base_units = to_base_units(amount);
+        if base_units == 0 {
+            return Ok(None);
+        }
+
+        let pending = PendingSettlement {
+            id: Uuid::new_v4(),
+            user_id,
+            wallet,
+            amount,
+            base_units,
+            signature: None,
+            submitted_at: Utc::now(),
+        };
+        match self.in_flight.entry(user_id) {
+            Entry::Occupied(_) => return Err(SettlementError::InFlight),
+            Entry::Vacant(entry) => {
+                entry.insert(pending.clone());
+            }
+        }
+
+        let settled = self.settle(pending, as_of).await;
+        self.in_flight.remove(&user_id);
+        settled.map(Some)
+    }
+
+    async fn settle(&self, pending: PendingSettlement, as_of: DateTime<Utc>) -> Result<Settlement, SettlementError> {
+        let transaction = self
+            .client
+            .transfer_transaction(&pending.wallet, pending.base_units)
+            .map_err(SettlementError::Transaction)?;
+        let signature = self.client.send_transaction(&transaction).await.map_err(SettlementError::Transaction)?;
+        if let Some(mut in_flight) = self.in_flight.get_mut(&pending.user_id) {
+            in_flight.signature = Some(signature.clone());
+        }
+
+        // Rewards vesting after `as_of` were not part of the transfer and stay pending
+        let releases = self
+            .rewards
+            .write()
+            .await
+            .process_user_rewards(&pending.user_id.to_string(), as_of, &signature)
+            .await
+            .map_err(SettlementError::Reward)?;
+        let released: f64 = releases.iter().map(|release| release.amount).sum();
+        if (released - pending.amount).abs() > 1e-9 {
+            log::warn!(
+                "Settlement {} of {} transferred {} but released {}",
+                signature,
+                pending.user_id,
+                pending.amount,
+                released
+            );
+        }
+
+        Ok(Settlement { signature, amount: pending.amount, base_units: pending.base_units, releases })
+    }
+}
+
+/// Records transactions instead of submitting them
+#[cfg(test)]
+pub struct MockBlockchainClient {
+    pub sent: std::sync::Mutex<Vec<(String, u64)>>,
+    pub fail: bool,
+}
+
+#[cfg(test)]
+impl MockBlockchainClient {
+    pub fn new() -> Self {
+        Self { sent: std::sync::Mutex::new(Vec::new()), fail: false }
+    }
+}
+
+#[cfg(test)]
+impl Default for MockBlockchainClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+impl BlockchainClient for MockBlockchainClient {
+    fn transfer_transaction(&self, wallet: &str, amount: u64) -> Result<Vec<u8>, String> {
+        Ok(format!("{}:{}", wallet, amount).into_bytes())
+    }
+
+    fn send_transaction<'a>(&'a self, transaction: &'a [u8]) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(async move {
+            if self.fail {
+                return Err("node unavailable".to_string());
+            }
+            let payload = String::from_utf8_lossy(transaction).to_string();
+            let (wallet, amount) = payload.rsplit_once(':').ok_or("malformed transaction")?;
+            let mut sent = self.sent.lock().unwrap();
+            sent.push((wallet.to_string(), amount.parse().map_err(|_| "malformed amount")?));
+            Ok(format!("sig_{}", sent.len()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    async fn user(pool: &PgPool, wallet: &str) -> Uuid {
+        sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+            .bind(wallet)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_amounts_convert_to_base_units() {
+        assert_eq!(to_base_units(1.5), 1_500_000_000);
+        assert_eq!(to_base_units(0.000_000_000_4), 0);
+        assert_eq!(to_base_units(-1.0), 0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_vested_rewards_are_transferred_and_signed(pool: PgPool) {
+        let user_id = user(&pool, "EchoWallet1111111111111111111111111111111111").await;
+        let rewards = Arc::new(RwLock::new(RewardService::new(10_000.0)));
+        {
+            let mut rewards = rewards.write().await;
+            let user = user_id.to_string();
+            rewards.award_community_contribution(user.clone(), "content_1".into(), 12.5, 0.1).await.unwrap();
+            rewards.award_community_contribution(user, "content_2".into(), 2.25, 0.1).await.unwrap();
+        }
+
+        let client = Arc::new(MockBlockchainClient::new());
+        let vesting = TokenVestingService::new(Arc::new(UserRepository::new(pool)), rewards.clone(), client.clone());
+
+        let settlement = vesting.process_batch(user_id, Utc::now()).await.unwrap().unwrap();
+        assert_eq!(settlement.signature, "sig_1");
+        assert!((settlement.amount - 14.75).abs() < 1e-9);
+        assert_eq!(
+            *client.sent.lock().unwrap(),
+            vec![("EchoWallet1111111111111111111111111111111111".to_string(), 14_750_000_000)]
+        );
+
+        // The releases carry the signature and nothing is left in flight or to claim
+        let released = rewards.read().await.get_user_rewards(&user_id.to_string());
+        assert_eq!(released.len(), 2);
+        assert!(released.iter().all(|r| r.transaction_hash.as_deref() == Some("sig_1")));
+        assert!(vesting.pending_transactions(user_id).is_empty());
+        assert!(vesting.process_batch(user_id, Utc::now()).await.unwrap().is_none());
+        assert_eq!(client.sent.lock().unwrap().len(), 1);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_failed_transactions_leave_rewards_pending(pool: PgPool) {
+        let user_id = user(&pool, "0xunsettled").await;
+        let rewards = Arc::new(RwLock::new(RewardService::new(10_000.0)));
+        rewards
+            .write()
+            .await
+            .award_community_contribution(user_id.to_string(), "content_1".into(), 5.0, 0.1)
+            .await
+            .unwrap();
+
+        let client = Arc::new(MockBlockchainClient { fail: true, ..MockBlockchainClient::new() });
+        let vesting = TokenVestingService::new(Arc::new(UserRepository::new(pool)), rewards.clone(), client);
+
+        let error = vesting.process_batch(user_id, Utc::now()).await.unwrap_err();
+        assert!(matches!(error, SettlementError::Transaction(_)));
+        assert!(vesting.pending_transactions(user_id).is_empty());
+        assert_eq!(rewards.read().await.get_user_claimable_rewards(&user_id.to_string(), Utc::now()), 5.0);
+
+        let unknown = vesting.process_batch(Uuid::new_v4(), Utc::now()).await.unwrap_err();
+        assert!(matches!(unknown, SettlementError::Repository(RepositoryError::NotFound)));
+    }
+}
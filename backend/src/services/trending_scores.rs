@@ -0,0 +1,154 @@
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::models::trending::{TrendingScore, SERIES_HOURS};
+use crate::models::Platform;
+use crate::repositories::{RepositoryError, TrendingContent, TrendingRepository};
+
+/// Trending scores of content whose Echo Index changed within the history they are
+/// computed from. Scores are recomputed together, so content that stopped changing drops
+/// out at the next refresh.
+pub struct TrendingScoreService {
+    repository: Arc<TrendingRepository>,
+}
+
+impl TrendingScoreService {
+    pub fn new(repository: Arc<TrendingRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Recompute every trending score, returning how many content items have one
+    pub async fn refresh(&self) -> Result<usize, RepositoryError> {
+        self.refresh_at(Utc::now()).await
+    }
+
+    async fn refresh_at(&self, now: DateTime<Utc>) -> Result<usize, RepositoryError> {
+        let series = self.repository.recent_series(now - Duration::hours(SERIES_HOURS)).await?;
+        let content_ids: Vec<Uuid> = series.keys().copied().collect();
+        let last_propagations = self.repository.last_propagations(&content_ids).await?;
+
+        let scores: Vec<(Uuid, TrendingScore)> = series
+            .iter()
+            .map(|(content_id, series)| {
+                let last_propagated_at = last_propagations.get(content_id).copied();
+                (*content_id, TrendingScore::compute(series, now, last_propagated_at))
+            })
+            .collect();
+        self.repository.replace(&scores).await?;
+        Ok(scores.len())
+    }
+
+    /// Live content gaining Echo Index fastest, optionally on one platform
+    pub async fn trending(
+        &self,
+        platform: Option<&Platform>,
+        limit: u32,
+    ) -> Result<Vec<TrendingContent>, RepositoryError> {
+        self.repository.list_by_velocity(platform, limit).await
+    }
+
+    /// Trending score of a content item as of the latest refresh
+    pub async fn score(&self, content_id: Uuid) -> Result<Option<TrendingScore>, RepositoryError> {
+        self.repository.find(content_id).await
+    }
+
+    /// Recompute trending scores every `period`
+    pub fn spawn_refresh_task(self: Arc<Self>, period: std::time::Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                match self.refresh().await {
+                    Ok(scored) => log::debug!("Refreshed trending scores of {} content items", scored),
+                    Err(e) => log::warn!("Trending score refresh failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    /// Content on `platform` whose Echo Index was `scores` the given hours ago
+    async fn scored_content(pool: &PgPool, user_id: Uuid, platform: &str, scores: &[(i32, f64)]) -> Uuid {
+        let content_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type)
+             VALUES ($1, $2::platform_type, gen_random_uuid()::text, 'text') RETURNING id",
+        )
+        .bind(user_id)
+        .bind(platform)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        for (hours_ago, score) in scores {
+            sqlx::query(
+                "INSERT INTO echo_index_history
+                     (content_id, score, odf, awr, tpm, qf, delta_score, trigger, calculated_at)
+                 VALUES ($1, $2, 0, 0, 0, 0, 0, 'recalculation', NOW() - make_interval(hours => $3))",
+            )
+            .bind(content_id)
+            .bind(score)
+            .bind(hours_ago)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+        content_id
+    }
+
+    async fn propagate(pool: &PgPool, content_id: Uuid, hours_ago: i32) {
+        sqlx::query(
+            "INSERT INTO propagations (content_id, propagation_type, source_platform, target_platform, created_at)
+             VALUES ($1, 'share', 'twitter', 'twitter', NOW() - make_interval(hours => $2))",
+        )
+        .bind(content_id)
+        .bind(hours_ago)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_refresh_ranks_content_by_velocity(pool: PgPool) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xabc') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        // The baseline before the window counts; scores older than it do not
+        let fast = scored_content(&pool, user_id, "twitter", &[(30, 5.0), (10, 10.0), (1, 40.0)]).await;
+        let slow = scored_content(&pool, user_id, "twitter", &[(5, 10.0), (1, 16.0)]).await;
+        let quiet = scored_content(&pool, user_id, "twitter", &[(5, 10.0), (1, 16.0)]).await;
+        let falling = scored_content(&pool, user_id, "twitter", &[(5, 30.0), (1, 20.0)]).await;
+        let stale = scored_content(&pool, user_id, "twitter", &[(48, 10.0), (20, 60.0)]).await;
+        let elsewhere = scored_content(&pool, user_id, "telegram", &[(5, 10.0), (1, 20.0)]).await;
+        for content_id in [fast, slow, elsewhere] {
+            propagate(&pool, content_id, 1).await;
+        }
+        propagate(&pool, quiet, 5).await;
+
+        let service = TrendingScoreService::new(Arc::new(TrendingRepository::new(pool)));
+        assert_eq!(service.refresh().await.unwrap(), 5);
+
+        let trending = service.trending(Some(&Platform::Twitter), 10).await.unwrap();
+        let ranked: Vec<Uuid> = trending.iter().map(|entry| entry.content.id).collect();
+        assert_eq!(ranked[0], fast);
+        assert_eq!(ranked.len(), 3);
+        assert!(!ranked.contains(&falling) && !ranked.contains(&stale));
+        assert_eq!(trending[0].trending.velocity, 5.0);
+
+        // The same growth without propagations for four hours scores half as much
+        let slow = service.score(slow).await.unwrap().unwrap();
+        let quiet = service.score(quiet).await.unwrap().unwrap();
+        assert_eq!(slow.velocity, quiet.velocity);
+        assert!(slow.score > 0.0);
+        assert_eq!(quiet.score, slow.score / 2.0);
+        assert_eq!(service.score(stale).await.unwrap(), None);
+
+        assert_eq!(service.trending(None, 1).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,266 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use rand::RngCore;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+
+use crate::models::user::User;
+use crate::repositories::{RepositoryError, TwoFactorRepository, UserRepository};
+
+/// Single-use codes that stand in for a TOTP code when the authenticator is lost
+pub const RECOVERY_CODE_COUNT: usize = 10;
+/// Shown next to the account in authenticator apps
+const TOTP_ISSUER: &str = "EchoLayer";
+const TOTP_DIGITS: usize = 6;
+/// Codes of the previous and next 30-second step are accepted too, for clock drift
+const TOTP_SKEW: u8 = 1;
+const TOTP_STEP_SECONDS: u64 = 30;
+const NONCE_LENGTH: usize = 12;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TwoFactorError {
+    #[error("two-factor authentication has not been set up")]
+    NotSetUp,
+    #[error("two-factor authentication is already enabled")]
+    AlreadyEnabled,
+    #[error("two-factor authentication is not enabled")]
+    NotEnabled,
+    #[error("invalid two-factor authentication code")]
+    InvalidCode,
+    #[error("stored TOTP secret could not be decrypted")]
+    Cipher,
+    #[error("TOTP error: {0}")]
+    Totp(String),
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+/// Encrypts TOTP secrets at rest with AES-256-GCM, keyed by the SHA-256 of a passphrase
+pub struct SecretCipher {
+    cipher: Aes256Gcm,
+}
+
+impl SecretCipher {
+    pub fn new(passphrase: &str) -> Self {
+        let key = Sha256::digest(passphrase.as_bytes());
+        Self { cipher: Aes256Gcm::new(&key) }
+    }
+
+    /// Load the passphrase from the `ENCRYPTION_KEY` environment variable
+    pub fn from_env() -> Self {
+        Self::new(&std::env::var("ENCRYPTION_KEY").expect("ENCRYPTION_KEY must be set"))
+    }
+
+    /// Base64 of a random nonce followed by the ciphertext
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, TwoFactorError> {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher
+            .encrypt(&Nonce::from(nonce), plaintext.as_bytes())
+            .map_err(|_| TwoFactorError::Cipher)?;
+        Ok(STANDARD.encode([nonce.as_slice(), &ciphertext].concat()))
+    }
+
+    pub fn decrypt(&self, encrypted: &str) -> Result<String, TwoFactorError> {
+        let bytes = STANDARD.decode(encrypted).map_err(|_| TwoFactorError::Cipher)?;
+        let (nonce, ciphertext) = bytes.split_first_chunk::<NONCE_LENGTH>().ok_or(TwoFactorError::Cipher)?;
+        let plaintext = self
+            .cipher
+            .decrypt(&Nonce::from(*nonce), ciphertext)
+            .map_err(|_| TwoFactorError::Cipher)?;
+        String::from_utf8(plaintext).map_err(|_| TwoFactorError::Cipher)
+    }
+}
+
+/// A new TOTP secret, for the user to add to their authenticator app
+#[derive(Debug, Clone, Serialize)]
+pub struct TotpSetup {
+    /// Base32 secret, for authenticators that cannot scan the URI
+    pub secret: String,
+    /// `otpauth://` URI, rendered as a QR code by clients
+    pub otpauth_uri: String,
+}
+
+/// TOTP two-factor authentication, required at sign-in once a user enables it and by
+/// admin endpoints
+pub struct TwoFactorService {
+    users: Arc<UserRepository>,
+    repository: Arc<TwoFactorRepository>,
+    cipher: SecretCipher,
+}
+
+impl TwoFactorService {
+    pub fn new(users: Arc<UserRepository>, repository: Arc<TwoFactorRepository>, cipher: SecretCipher) -> Self {
+        Self { users, repository, cipher }
+    }
+
+    /// Generate and store a new secret, replacing one set up but never confirmed
+    pub async fn setup(&self, user_id: Uuid) -> Result<TotpSetup, TwoFactorError> {
+        let user = self.user(user_id).await?;
+        if user.totp_enabled {
+            return Err(TwoFactorError::AlreadyEnabled);
+        }
+
+        let secret = Secret::generate_secret().to_bytes().map_err(|e| TwoFactorError::Totp(e.to_string()))?;
+        let totp = totp(secret, &user.wallet_address)?;
+        let encoded = totp.get_secret_base32();
+        self.repository.store_secret(user_id, &self.cipher.encrypt(&encoded)?).await?;
+
+        Ok(TotpSetup { secret: encoded, otpauth_uri: totp.get_url() })
+    }
+
+    /// Enable 2FA once the user proves their authenticator works, returning their
+    /// recovery codes
+    pub async fn confirm(&self, user_id: Uuid, code: &str) -> Result<Vec<String>, TwoFactorError> {
+        let user = self.user(user_id).await?;
+        if user.totp_enabled {
+            return Err(TwoFactorError::AlreadyEnabled);
+        }
+        if !self.totp_for(&user)?.check_current(code.trim()).map_err(|e| TwoFactorError::Totp(e.to_string()))? {
+            return Err(TwoFactorError::InvalidCode);
+        }
+
+        let codes = generate_recovery_codes();
+        self.repository.enable(user_id, &hash_recovery_codes(&codes)).await?;
+        Ok(codes)
+    }
+
+    /// Check the second factor of a sign-in: a current TOTP code, or an unused recovery
+    /// code, which is used up
+    pub async fn verify(&self, user: &User, code: &str) -> Result<(), TwoFactorError> {
+        if !user.totp_enabled {
+            return Err(TwoFactorError::NotEnabled);
+        }
+
+        let code = code.trim();
+        let valid = if code.len() == TOTP_DIGITS && code.bytes().all(|b| b.is_ascii_digit()) {
+            self.totp_for(user)?.check_current(code).map_err(|e| TwoFactorError::Totp(e.to_string()))?
+        } else {
+            self.repository.consume_recovery_code(user.id, &hash_recovery_code(code)).await?
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(TwoFactorError::InvalidCode)
+        }
+    }
+
+    /// Replace the user's recovery codes with new ones. Codes are only stored hashed, so
+    /// lost codes cannot be shown again.
+    pub async fn regenerate_recovery_codes(&self, user_id: Uuid) -> Result<Vec<String>, TwoFactorError> {
+        if !self.user(user_id).await?.totp_enabled {
+            return Err(TwoFactorError::NotEnabled);
+        }
+
+        let codes = generate_recovery_codes();
+        self.repository.replace_recovery_codes(user_id, &hash_recovery_codes(&codes)).await?;
+        Ok(codes)
+    }
+
+    async fn user(&self, user_id: Uuid) -> Result<User, TwoFactorError> {
+        Ok(self.users.find_by_id(user_id).await?.ok_or(RepositoryError::NotFound)?)
+    }
+
+    fn totp_for(&self, user: &User) -> Result<TOTP, TwoFactorError> {
+        let encrypted = user.totp_secret.as_deref().ok_or(TwoFactorError::NotSetUp)?;
+        let secret = Secret::Encoded(self.cipher.decrypt(encrypted)?)
+            .to_bytes()
+            .map_err(|e| TwoFactorError::Totp(e.to_string()))?;
+        totp(secret, &user.wallet_address)
+    }
+}
+
+fn totp(secret: Vec<u8>, wallet_address: &str) -> Result<TOTP, TwoFactorError> {
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        TOTP_SKEW,
+        TOTP_STEP_SECONDS,
+        secret,
+        Some(TOTP_ISSUER.to_string()),
+        wallet_address.to_string(),
+    )
+    .map_err(|e| TwoFactorError::Totp(e.to_string()))
+}
+
+/// Ten random `xxxxx-xxxxx` hex codes
+fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; 5];
+            rand::thread_rng().fill_bytes(&mut bytes);
+            let code = hex::encode(bytes);
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+/// SHA-256 of a recovery code, the only form in which it is stored. Case is ignored.
+fn hash_recovery_code(code: &str) -> String {
+    hex::encode(Sha256::digest(code.to_ascii_lowercase().as_bytes()))
+}
+
+fn hash_recovery_codes(codes: &[String]) -> Vec<String> {
+    codes.iter().map(|code| hash_recovery_code(code)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    #[test]
+    fn test_secrets_round_trip_only_with_the_same_key() {
+        let cipher = SecretCipher::new("encryption-key");
+        let encrypted = cipher.encrypt("JBSWY3DPEHPK3PXP").unwrap();
+        assert_ne!(encrypted, cipher.encrypt("JBSWY3DPEHPK3PXP").unwrap());
+        assert_eq!(cipher.decrypt(&encrypted).unwrap(), "JBSWY3DPEHPK3PXP");
+
+        assert!(matches!(SecretCipher::new("other-key").decrypt(&encrypted), Err(TwoFactorError::Cipher)));
+        assert!(matches!(cipher.decrypt("AAAA"), Err(TwoFactorError::Cipher)));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_setup_confirm_and_single_use_recovery_codes(pool: PgPool) {
+        let users = Arc::new(UserRepository::new(pool.clone()));
+        let user_id = users.find_or_create_by_wallet("0xadmin").await.unwrap().id;
+        let service = TwoFactorService::new(
+            users.clone(),
+            Arc::new(TwoFactorRepository::new(pool)),
+            SecretCipher::new("encryption-key"),
+        );
+        assert!(matches!(service.confirm(user_id, "123456").await, Err(TwoFactorError::NotSetUp)));
+
+        let setup = service.setup(user_id).await.unwrap();
+        assert!(setup.otpauth_uri.starts_with("otpauth://totp/EchoLayer:0xadmin?secret="));
+        let stored = users.find_by_id(user_id).await.unwrap().unwrap();
+        assert_ne!(stored.totp_secret.as_deref(), Some(setup.secret.as_str()));
+
+        let authenticator = totp(Secret::Encoded(setup.secret).to_bytes().unwrap(), "0xadmin").unwrap();
+        let code = authenticator.generate_current().unwrap();
+        let wrong_code = format!("{:06}", (code.parse::<u32>().unwrap() + 500_000) % 1_000_000);
+        assert!(matches!(service.confirm(user_id, &wrong_code).await, Err(TwoFactorError::InvalidCode)));
+        let recovery_codes = service.confirm(user_id, &code).await.unwrap();
+        assert_eq!(recovery_codes.len(), RECOVERY_CODE_COUNT);
+        assert!(matches!(service.setup(user_id).await, Err(TwoFactorError::AlreadyEnabled)));
+
+        let user = users.find_by_id(user_id).await.unwrap().unwrap();
+        assert!(user.totp_enabled);
+        service.verify(&user, &code).await.unwrap();
+        assert!(matches!(service.verify(&user, &wrong_code).await, Err(TwoFactorError::InvalidCode)));
+
+        let recovery_code = recovery_codes[0].to_uppercase();
+        service.verify(&user, &recovery_code).await.unwrap();
+        assert!(matches!(service.verify(&user, &recovery_code).await, Err(TwoFactorError::InvalidCode)));
+
+        let regenerated = service.regenerate_recovery_codes(user_id).await.unwrap();
+        assert!(matches!(service.verify(&user, &recovery_codes[1]).await, Err(TwoFactorError::InvalidCode)));
+        service.verify(&user, &regenerated[1]).await.unwrap();
+    }
+}
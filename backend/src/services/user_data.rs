@@ -0,0 +1,260 @@
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::{Cursor, Write};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::repositories::{ContentRepository, RepositoryError, StreakRepository, UserEventRepository, UserRepository};
+use crate::services::RewardService;
+
+/// Shortest time between two data exports of the same user
+pub const EXPORT_INTERVAL_HOURS: i64 = 24;
+
+#[derive(Debug, thiserror::Error)]
+pub enum UserDataError {
+    #[error("a data export may be requested once every {EXPORT_INTERVAL_HOURS} hours")]
+    ExportLimitReached,
+    #[error("failed to write export archive: {0}")]
+    Archive(#[from] zip::result::ZipError),
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+/// Serves users' rights over their personal data: exporting everything stored about
+/// them, and erasing what identifies them while keeping their content's Echo Index
+/// history for platform analytics.
+pub struct UserDataService {
+    users: Arc<UserRepository>,
+    content: Arc<ContentRepository>,
+    events: Arc<UserEventRepository>,
+    streaks: Arc<StreakRepository>,
+    rewards: Arc<RwLock<RewardService>>,
+}
+
+impl UserDataService {
+    pub fn new(
+        users: Arc<UserRepository>,
+        content: Arc<ContentRepository>,
+        events: Arc<UserEventRepository>,
+        streaks: Arc<StreakRepository>,
+        rewards: Arc<RwLock<RewardService>>,
+    ) -> Self {
+        Self { users, content, events, streaks, rewards }
+    }
+
+    /// A ZIP archive of the user's data with one JSON file per kind of record. At most
+    /// one export per user is made every `EXPORT_INTERVAL_HOURS`.
+    pub async fn export(&self, user_id: Uuid, now: DateTime<Utc>) -> Result<Vec<u8>, UserDataError> {
+        let user = self.users.find_by_id(user_id).await?.ok_or(RepositoryError::NotFound)?;
+        let since = now - Duration::hours(EXPORT_INTERVAL_HOURS);
+        if !self.users.claim_data_export(user_id, now, since).await? {
+            return Err(UserDataError::ExportLimitReached);
+        }
+
+        let social_accounts = self.users.social_accounts(user_id).await?;
+        let content = self.content.list_by_author(user_id).await?;
+        let propagations = self.content.list_propagations_by_user(user_id).await?;
+        let rewards = self.rewards.read().await.get_user_rewards(&user_id.to_string());
+        let events = self.events.all(user_id).await?;
+        let streak = self.streaks.find(user_id).await?;
+
+        let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+        write_json(&mut archive, "user.json", &user)?;
+        write_json(&mut archive, "social_accounts.json", &social_accounts)?;
+        write_json(&mut archive, "content.json", &content)?;
+        write_json(&mut archive, "propagations.json", &propagations)?;
+        write_json(&mut archive, "rewards.json", &rewards)?;
+        write_json(&mut archive, "events.json", &events)?;
+        write_json(&mut archive, "streak.json", &streak)?;
+
+        Ok(archive.finish()?.into_inner())
+    }
+
+    /// Replace the user's wallet address, username and email with salted hashes and drop
+    /// the rest of their personal data. The salt is discarded, so the hashes cannot be
+    /// matched against known wallets.
+    pub async fn anonymize(&self, user_id: Uuid) -> Result<(), RepositoryError> {
+        let mut salt = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut salt);
+
+        self.users
+            .anonymize(user_id, |value| {
+                hex::encode(Sha256::new().chain_update(salt).chain_update(value.as_bytes()).finalize())
+            })
+            .await
+    }
+}
+
+fn write_json<T: Serialize>(
+    archive: &mut ZipWriter<Cursor<Vec<u8>>>,
+    name: &str,
+    value: &T,
+) -> Result<(), zip::result::ZipError> {
+    archive.start_file(name, SimpleFileOptions::default())?;
+    let json = serde_json::to_vec_pretty(value).map_err(std::io::Error::from)?;
+    archive.write_all(&json)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user_event::UserEvent;
+    use sqlx::PgPool;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    const WALLET: &str = "0x9f8e7d6c5b4a39281706f5e4d3c2b1a098765432";
+
+    fn service(pool: &PgPool) -> UserDataService {
+        UserDataService::new(
+            Arc::new(UserRepository::new(pool.clone())),
+            Arc::new(ContentRepository::new(pool.clone())),
+            Arc::new(UserEventRepository::new(pool.clone())),
+            Arc::new(StreakRepository::new(pool.clone())),
+            Arc::new(RwLock::new(RewardService::new(10_000.0))),
+        )
+    }
+
+    async fn insert_user(pool: &PgPool) -> Uuid {
+        let user_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO users (wallet_address, username, email, display_name)
+             VALUES ($1, 'echo_fan', 'fan@example.com', 'Echo Fan') RETURNING id",
+        )
+        .bind(WALLET)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO social_platforms (user_id, platform, platform_user_id, platform_username, verification_status)
+             VALUES ($1, 'twitter', '1234', 'echo_fan', 'verified')",
+        )
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        let content_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type, title, body)
+             VALUES ($1, 'twitter', 'tweet_export', 'text', 'Mine', 'Body') RETURNING id",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO propagations (content_id, source_user_id, propagation_type, source_platform, target_platform)
+             VALUES ($1, $2, 'share', 'twitter', 'reddit')",
+        )
+        .bind(content_id)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        UserEventRepository::new(pool.clone())
+            .record(user_id, &UserEvent::TierChanged { old_tier: "Basic".to_string(), new_tier: "Bronze".to_string() })
+            .await
+            .unwrap();
+        user_id
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_export_archives_every_kind_of_record_once_a_day(pool: PgPool) {
+        let user_id = insert_user(&pool).await;
+        let service = service(&pool);
+        let now = Utc::now();
+
+        let bytes = service.export(user_id, now).await.unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut names: Vec<_> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "content.json",
+                "events.json",
+                "propagations.json",
+                "rewards.json",
+                "social_accounts.json",
+                "streak.json",
+                "user.json"
+            ]
+        );
+
+        let mut json = String::new();
+        archive.by_name("propagations.json").unwrap().read_to_string(&mut json).unwrap();
+        let propagations: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(propagations.as_array().unwrap().len(), 1);
+        json.clear();
+        archive.by_name("social_accounts.json").unwrap().read_to_string(&mut json).unwrap();
+        assert!(json.contains("\"verified\": true"));
+
+        assert!(matches!(
+            service.export(user_id, now + Duration::hours(23)).await,
+            Err(UserDataError::ExportLimitReached)
+        ));
+        assert!(service.export(user_id, now + Duration::hours(24)).await.is_ok());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_anonymization_leaves_no_plaintext_identity(pool: PgPool) {
+        let user_id = insert_user(&pool).await;
+        sqlx::query(
+            "INSERT INTO echo_index_history (content_id, score, odf, awr, tpm, qf, trigger)
+             SELECT id, 0.7, 0.7, 0.7, 0.7, 0.7, 'initial' FROM content WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let service = service(&pool);
+
+        service.anonymize(user_id).await.unwrap();
+
+        // No column of any row anywhere still holds the wallet address, username or email
+        let tables: Vec<String> = sqlx::query_scalar(
+            "SELECT table_name::text FROM information_schema.tables
+             WHERE table_schema = 'public' AND table_type = 'BASE TABLE' AND table_name <> '_sqlx_migrations'",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        for table in tables {
+            let leaks: i64 = sqlx::query_scalar(&format!(
+                "SELECT COUNT(*) FROM {} t
+                 WHERE to_jsonb(t)::text ILIKE '%' || $1 || '%'
+                    OR to_jsonb(t)::text LIKE '%fan@example.com%'
+                    OR to_jsonb(t)::text LIKE '%Echo Fan%'",
+                table
+            ))
+            .bind(WALLET)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(leaks, 0, "{} still holds personal data", table);
+        }
+
+        let (wallet, username, active): (String, Option<String>, bool) =
+            sqlx::query_as("SELECT wallet_address, username, is_active FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(wallet.len(), 64);
+        assert_ne!(username.as_deref(), Some("echo_fan"));
+        assert!(!active);
+
+        // Echo Index history stays for analytics
+        let history: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM echo_index_history")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(history, 1);
+
+        assert!(matches!(service.anonymize(user_id).await, Err(RepositoryError::NotFound)));
+    }
+}
@@ -0,0 +1,321 @@
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::models::velocity_alert::{AlertConfig, VelocityAlert, VelocityThreshold};
+use crate::repositories::{AlertRepository, RepositoryError};
+use crate::services::callback_urls::{callback_client, parse_callback_url};
+
+/// Window velocity is measured over; thresholds are rates per hour
+const VELOCITY_WINDOW_MINUTES: i64 = 60;
+/// How long a threshold stays quiet for a content item after firing, by default
+pub const DEFAULT_ALERT_COOLDOWN_HOURS: i64 = 24;
+/// Alerts kept per user for listing
+pub const RECENT_ALERTS_LIMIT: i64 = 50;
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delivers fired velocity alerts somewhere
+pub trait AlertDispatcher: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn dispatch<'a>(&'a self, alert: &'a VelocityAlert, config: &'a AlertConfig) -> BoxFuture<'a, Result<(), String>>;
+}
+
+/// Writes alerts to the application log
+pub struct LogDispatcher;
+
+impl AlertDispatcher for LogDispatcher {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn dispatch<'a>(&'a self, alert: &'a VelocityAlert, _config: &'a AlertConfig) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            log::info!(
+                "Content {} of user {} exceeded {} {} at {:.2}",
+                alert.content_id,
+                alert.user_id,
+                alert.threshold_type.kind(),
+                alert.threshold_type.value(),
+                alert.current_velocity
+            );
+            Ok(())
+        })
+    }
+}
+
+/// Posts alerts as JSON to the webhook of the threshold that fired, if it has one and it
+/// is still an https URL of a public host
+pub struct AlertWebhookDispatcher {
+    client: reqwest::Client,
+}
+
+impl AlertWebhookDispatcher {
+    pub fn new() -> Self {
+        Self {
+            client: callback_client(WEBHOOK_TIMEOUT),
+        }
+    }
+}
+
+impl Default for AlertWebhookDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AlertDispatcher for AlertWebhookDispatcher {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn dispatch<'a>(&'a self, alert: &'a VelocityAlert, config: &'a AlertConfig) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let Some(url) = config.webhook_url.as_deref() else {
+                return Ok(());
+            };
+            let url = parse_callback_url(url).map_err(|e| format!("webhook url {}", e))?;
+
+            self.client
+                .post(url)
+                .json(&json!({ "event": "velocity_alert", "alert": alert }))
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .map(|_| ())
+                .map_err(|e| format!("webhook delivery failed: {}", e))
+        })
+    }
+}
+
+/// Records alerts so users can list them
+pub struct DbDispatcher {
+    repository: Arc<AlertRepository>,
+}
+
+impl DbDispatcher {
+    pub fn new(repository: Arc<AlertRepository>) -> Self {
+        Self { repository }
+    }
+}
+
+impl AlertDispatcher for DbDispatcher {
+    fn name(&self) -> &'static str {
+        "db"
+    }
+
+    fn dispatch<'a>(&'a self, alert: &'a VelocityAlert, _config: &'a AlertConfig) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move { self.repository.record_alert(alert).await.map_err(|e| e.to_string()) })
+    }
+}
+
+/// Compares how fast content is spreading against its creator's thresholds and
+/// dispatches an alert when one is exceeded, at most once per cooldown per threshold
+/// and content item
+pub struct VelocityAlertService {
+    repository: Arc<AlertRepository>,
+    dispatchers: Vec<Arc<dyn AlertDispatcher>>,
+    cooldown: chrono::Duration,
+}
+
+impl VelocityAlertService {
+    pub fn new(repository: Arc<AlertRepository>, cooldown: chrono::Duration) -> Self {
+        Self {
+            repository,
+            dispatchers: Vec::new(),
+            cooldown,
+        }
+    }
+
+    pub fn with_dispatcher(mut self, dispatcher: Arc<dyn AlertDispatcher>) -> Self {
+        self.dispatchers.push(dispatcher);
+        self
+    }
+
+    pub async fn configure(
+        &self,
+        user_id: Uuid,
+        threshold: VelocityThreshold,
+        webhook_url: Option<&str>,
+    ) -> Result<AlertConfig, RepositoryError> {
+        threshold.validate().map_err(RepositoryError::InvalidInput)?;
+        self.repository.upsert_config(user_id, threshold, webhook_url).await
+    }
+
+    pub async fn configs(&self, user_id: Uuid) -> Result<Vec<AlertConfig>, RepositoryError> {
+        self.repository.configs_for_user(user_id).await
+    }
+
+    pub async fn recent_alerts(&self, user_id: Uuid) -> Result<Vec<VelocityAlert>, RepositoryError> {
+        self.repository.recent_alerts(user_id, RECENT_ALERTS_LIMIT).await
+    }
+
+    /// Fire alerts for every threshold newly exceeded as of `now`, returning them.
+    /// A dispatcher failing does not stop the others or undo the alert.
+    pub async fn evaluate(&self, now: DateTime<Utc>) -> Result<Vec<VelocityAlert>, RepositoryError> {
+        let configs = self.repository.all_configs().await?;
+        if configs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let user_ids: Vec<Uuid> = configs.iter().map(|config| config.user_id).collect::<HashSet<_>>().into_iter().collect();
+        let velocities = self
+            .repository
+            .velocities(&user_ids, now, chrono::Duration::minutes(VELOCITY_WINDOW_MINUTES))
+            .await?;
+
+        let mut fired = Vec::new();
+        for config in &configs {
+            for velocity in velocities.iter().filter(|velocity| velocity.user_id == config.user_id) {
+                let Some(current_velocity) = config.threshold.exceeded_by(velocity) else {
+                    continue;
+                };
+                if !self.repository.try_trigger(config.id, velocity.content_id, now, now - self.cooldown).await? {
+                    continue;
+                }
+
+                let alert = VelocityAlert {
+                    content_id: velocity.content_id,
+                    user_id: velocity.user_id,
+                    threshold_type: config.threshold,
+                    triggered_at: now,
+                    current_velocity,
+                };
+                for dispatcher in &self.dispatchers {
+                    if let Err(e) = dispatcher.dispatch(&alert, config).await {
+                        log::warn!("{} dispatch of alert for {} failed: {}", dispatcher.name(), alert.content_id, e);
+                    }
+                }
+                fired.push(alert);
+            }
+        }
+
+        Ok(fired)
+    }
+
+    pub fn spawn_evaluation_task(self: Arc<Self>, period: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                match self.evaluate(Utc::now()).await {
+                    Ok(fired) if !fired.is_empty() => log::info!("Fired {} velocity alerts", fired.len()),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Velocity alert evaluation failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+    use std::sync::Mutex;
+
+    /// Keeps dispatched alerts for inspection
+    #[derive(Default)]
+    struct RecordingDispatcher {
+        alerts: Mutex<Vec<VelocityAlert>>,
+    }
+
+    impl AlertDispatcher for RecordingDispatcher {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        fn dispatch<'a>(&'a self, alert: &'a VelocityAlert, _config: &'a AlertConfig) -> BoxFuture<'a, Result<(), String>> {
+            self.alerts.lock().unwrap().push(alert.clone());
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_alert_is_not_refired_within_cooldown(pool: PgPool) {
+        let mut users = Vec::new();
+        for wallet in ["0xviral", "0xsharer_a", "0xsharer_b"] {
+            let id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+                .bind(wallet)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            users.push(id);
+        }
+        let content_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO content (user_id, platform, external_id, content_type, title, body)
+             VALUES ($1, 'twitter', 'tweet_viral', 'text', 'Viral', 'Body') RETURNING id",
+        )
+        .bind(users[0])
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        for (i, sharer) in users[1..].iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO propagations (content_id, source_user_id, target_user_id, propagation_type,
+                                           source_platform, target_platform, target_external_id)
+                 VALUES ($1, $2, $3, 'share', 'twitter', 'farcaster', $4)",
+            )
+            .bind(content_id)
+            .bind(users[0])
+            .bind(sharer)
+            .bind(format!("cast_{}", i))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let repository = Arc::new(AlertRepository::new(pool.clone()));
+        let recorder = Arc::new(RecordingDispatcher::default());
+        let service = VelocityAlertService::new(repository.clone(), chrono::Duration::minutes(30))
+            .with_dispatcher(recorder.clone())
+            .with_dispatcher(Arc::new(DbDispatcher::new(repository)));
+        service.configure(users[0], VelocityThreshold::PropagationsPerHour(1.5), None).await.unwrap();
+        service.configure(users[0], VelocityThreshold::ReachPerHour(5), None).await.unwrap();
+
+        let now = Utc::now();
+        let fired = service.evaluate(now).await.unwrap();
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].content_id, content_id);
+        assert_eq!(fired[0].threshold_type, VelocityThreshold::PropagationsPerHour(1.5));
+        assert_eq!(fired[0].current_velocity, 2.0);
+
+        // Still over the threshold, but within the cooldown
+        assert!(service.evaluate(now + chrono::Duration::minutes(10)).await.unwrap().is_empty());
+
+        // Once the cooldown has passed the threshold fires again
+        assert_eq!(service.evaluate(now + chrono::Duration::minutes(40)).await.unwrap().len(), 1);
+        assert_eq!(recorder.alerts.lock().unwrap().len(), 2);
+        assert_eq!(service.recent_alerts(users[0]).await.unwrap().len(), 2);
+    }
+
+    #[actix_web::test]
+    async fn test_alerts_are_not_posted_to_private_hosts() {
+        let user_id = Uuid::new_v4();
+        let threshold = VelocityThreshold::PropagationsPerHour(1.0);
+        let alert = VelocityAlert {
+            content_id: Uuid::new_v4(),
+            user_id,
+            threshold_type: threshold,
+            triggered_at: Utc::now(),
+            current_velocity: 2.0,
+        };
+        let dispatcher = AlertWebhookDispatcher::new();
+        for url in ["https://169.254.169.254/latest/meta-data", "https://localhost/hook", "http://example.com/hook"] {
+            let config = AlertConfig {
+                id: Uuid::new_v4(),
+                user_id,
+                threshold,
+                webhook_url: Some(url.to_string()),
+                created_at: Utc::now(),
+            };
+            let refused = dispatcher.dispatch(&alert, &config).await.unwrap_err();
+            assert!(refused.starts_with("webhook url"), "{}: {}", url, refused);
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde_json::json;
+use sha2::Sha256;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::models::webhook::{Webhook, WebhookEvent};
+use crate::services::callback_urls::{callback_client, parse_callback_url};
+use crate::repositories::webhook_repository::NewDelivery;
+use crate::repositories::{RepositoryError, WebhookRepository};
+
+/// Header carrying `sha256=<hex HMAC-SHA256 of the body keyed with the webhook secret>`
+pub const SIGNATURE_HEADER: &str = "X-EchoLayer-Signature";
+/// Header naming the event, so receivers can route without parsing the body
+pub const EVENT_HEADER: &str = "X-EchoLayer-Event";
+/// Retries of a delivery answered with a server error or not answered at all
+pub const MAX_RETRIES: u32 = 3;
+/// Delivery attempts listed per webhook
+pub const RECENT_DELIVERIES_LIMIT: i64 = 100;
+/// Wait before the first retry by default; each later retry waits `BACKOFF_FACTOR` times longer
+pub const DEFAULT_FIRST_RETRY_DELAY_SECS: u64 = 1;
+const BACKOFF_FACTOR: u32 = 5;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Notifications still being delivered beyond which deliveries count as backlogged
+pub const MAX_PENDING_NOTIFICATIONS: usize = 1_000;
+
+/// Posts a user's events to the webhooks they registered for them. Payloads are
+/// signed with each webhook's secret, and every attempt is logged so integrators can
+/// debug their endpoints.
+pub struct WebhookDispatcher {
+    repository: Arc<WebhookRepository>,
+    client: reqwest::Client,
+    /// Refuse URLs of loopback, private and other non-public hosts
+    public_hosts_only: bool,
+    first_retry_delay: Duration,
+    /// Notifications spawned and not yet delivered, retries included
+    pending: AtomicUsize,
+}
+
+impl WebhookDispatcher {
+    pub fn new(repository: Arc<WebhookRepository>) -> Self {
+        Self {
+            repository,
+            client: callback_client(DELIVERY_TIMEOUT),
+            public_hosts_only: true,
+            first_retry_delay: Duration::from_secs(DEFAULT_FIRST_RETRY_DELAY_SECS),
+            pending: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn with_first_retry_delay(mut self, delay: Duration) -> Self {
+        self.first_retry_delay = delay;
+        self
+    }
+
+    /// Deliver to any URL, including those of loopback and private hosts, e.g. to a
+    /// receiver on the same machine in development
+    pub fn allowing_private_hosts(mut self) -> Self {
+        self.client = reqwest::Client::builder().timeout(DELIVERY_TIMEOUT).build().unwrap_or_default();
+        self.public_hosts_only = false;
+        self
+    }
+
+    /// Notifications spawned by `notify` and `notify_content_author` still being delivered
+    pub fn pending_notifications(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// Whether deliveries are falling behind the events being raised
+    pub fn is_backlogged(&self) -> bool {
+        self.pending_notifications() >= MAX_PENDING_NOTIFICATIONS
+    }
+
+    /// Deliver an event to the user's webhooks in the background
+    pub fn notify(self: &Arc<Self>, user_id: Uuid, event: WebhookEvent, data: serde_json::Value) {
+        let dispatcher = self.clone();
+        self.pending.fetch_add(1, Ordering::Relaxed);
+        tokio::spawn(async move {
+            match dispatcher.repository.subscribed(user_id, event).await {
+                Ok(webhooks) => dispatcher.deliver_all(&webhooks, event, &data).await,
+                Err(e) => log::warn!("Failed to look up {} webhooks of {}: {}", event.as_str(), user_id, e),
+            }
+            dispatcher.pending.fetch_sub(1, Ordering::Relaxed);
+        });
+    }
+
+    /// Deliver an event about a content item to its author's webhooks in the background
+    pub fn notify_content_author(self: &Arc<Self>, content_id: Uuid, event: WebhookEvent, data: serde_json::Value) {
+        let dispatcher = self.clone();
+        self.pending.fetch_add(1, Ordering::Relaxed);
+        tokio::spawn(async move {
+            match dispatcher.repository.subscribed_to_content(content_id, event).await {
+                Ok(webhooks) => dispatcher.deliver_all(&webhooks, event, &data).await,
+                Err(e) => log::warn!("Failed to look up {} webhooks for {}: {}", event.as_str(), content_id, e),
+            }
+            dispatcher.pending.fetch_sub(1, Ordering::Relaxed);
+        });
+    }
+
+    async fn deliver_all(&self, webhooks: &[Webhook], event: WebhookEvent, data: &serde_json::Value) {
+        for webhook in webhooks {
+            if let Err(e) = self.deliver(webhook, event, data).await {
+                log::warn!("Failed to log delivery to webhook {}: {}", webhook.id, e);
+            }
+        }
+    }
+
+    /// Post an event to a webhook, retrying server errors and unanswered requests with
+    /// exponential backoff. Returns whether the webhook accepted it. Webhooks whose host
+    /// is not, or no longer resolves to, a public address are not called.
+    pub async fn deliver(
+        &self,
+        webhook: &Webhook,
+        event: WebhookEvent,
+        data: &serde_json::Value,
+    ) -> Result<bool, RepositoryError> {
+        let payload = json!({
+            "id": Uuid::new_v4(),
+            "event": event,
+            "created_at": Utc::now(),
+            "data": data,
+        });
+        let body = payload.to_string();
+        let signature = sign(&webhook.secret, body.as_bytes());
+
+        let refused = self.public_hosts_only.then(|| parse_callback_url(&webhook.url).err()).flatten();
+        if let Some(e) = refused {
+            let error = format!("url {}", e);
+            log::warn!("Refused to deliver {} to webhook {}: {}", event.as_str(), webhook.id, error);
+            self.repository
+                .record_delivery(&NewDelivery {
+                    webhook_id: webhook.id,
+                    event,
+                    payload: &payload,
+                    attempt: 1,
+                    status_code: None,
+                    error: Some(&error),
+                    succeeded: false,
+                })
+                .await?;
+            return Ok(false);
+        }
+
+        let mut attempt = 1;
+        let mut delay = self.first_retry_delay;
+        loop {
+            let response = self
+                .client
+                .post(&webhook.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .header(EVENT_HEADER, event.as_str())
+                .body(body.clone())
+                .send()
+                .await;
+            let (status_code, error, retryable) = match response {
+                Ok(response) if response.status().is_success() => (Some(response.status()), None, false),
+                Ok(response) => {
+                    let status = response.status();
+                    (Some(status), Some(format!("HTTP {}", status)), status.is_server_error())
+                }
+                Err(e) => (None, Some(e.to_string()), true),
+            };
+
+            self.repository
+                .record_delivery(&NewDelivery {
+                    webhook_id: webhook.id,
+                    event,
+                    payload: &payload,
+                    attempt: attempt as i32,
+                    status_code: status_code.map(|status| status.as_u16() as i32),
+                    error: error.as_deref(),
+                    succeeded: error.is_none(),
+                })
+                .await?;
+
+            let Some(error) = error else {
+                return Ok(true);
+            };
+            if !retryable || attempt > MAX_RETRIES {
+                log::warn!(
+                    "Gave up delivering {} to webhook {} after {} attempts: {}",
+                    event.as_str(),
+                    webhook.id,
+                    attempt,
+                    error
+                );
+                return Ok(false);
+            }
+
+            tokio::time::sleep(delay).await;
+            delay *= BACKOFF_FACTOR;
+            attempt += 1;
+        }
+    }
+}
+
+/// `sha256=` followed by the hex HMAC-SHA256 of `body` keyed with `secret`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// A random 256-bit webhook secret, hex encoded
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+    use sqlx::PgPool;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    /// Requests a mock endpoint received, and the statuses it answers the next ones with
+    #[derive(Default)]
+    struct MockEndpoint {
+        statuses: Mutex<VecDeque<u16>>,
+        received: Mutex<Vec<(Option<String>, String)>>,
+    }
+
+    async fn receive(request: HttpRequest, body: String, endpoint: web::Data<MockEndpoint>) -> HttpResponse {
+        let signature = request
+            .headers()
+            .get(SIGNATURE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        endpoint.received.lock().unwrap().push((signature, body));
+        let status = endpoint.statuses.lock().unwrap().pop_front().unwrap_or(200);
+        HttpResponse::build(actix_web::http::StatusCode::from_u16(status).unwrap()).finish()
+    }
+
+    /// URL of a local endpoint answering with `statuses`, then 200
+    fn mock_endpoint(statuses: &[u16]) -> (String, web::Data<MockEndpoint>) {
+        let endpoint = web::Data::new(MockEndpoint::default());
+        endpoint.statuses.lock().unwrap().extend(statuses);
+
+        let server_endpoint = endpoint.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(server_endpoint.clone())
+                .route("/hook", web::post().to(receive))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let url = format!("http://{}/hook", server.addrs()[0]);
+        tokio::spawn(server.run());
+
+        (url, endpoint)
+    }
+
+    async fn webhook(pool: &PgPool, url: &str) -> (Arc<WebhookRepository>, Webhook) {
+        let user_id: Uuid = sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ('0xintegrator') RETURNING id")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        let repository = Arc::new(WebhookRepository::new(pool.clone()));
+        let webhook = repository
+            .create(user_id, url, &generate_secret(), &[WebhookEvent::EchoIndexCalculated])
+            .await
+            .unwrap();
+        (repository, webhook)
+    }
+
+    #[test]
+    fn test_signature_is_hmac_sha256_of_the_body() {
+        assert_eq!(
+            sign("key", b"The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_server_errors_are_retried_with_the_same_signed_payload(pool: PgPool) {
+        let (url, endpoint) = mock_endpoint(&[503, 502]);
+        let (repository, webhook) = webhook(&pool, &url).await;
+        let dispatcher = WebhookDispatcher::new(repository.clone())
+            .with_first_retry_delay(Duration::from_millis(10))
+            .allowing_private_hosts();
+
+        let data = json!({ "content_id": "content_1", "score": 0.72 });
+        assert!(dispatcher.deliver(&webhook, WebhookEvent::EchoIndexCalculated, &data).await.unwrap());
+
+        let received = endpoint.received.lock().unwrap().clone();
+        assert_eq!(received.len(), 3);
+        for (signature, body) in &received {
+            assert_eq!(signature.as_deref(), Some(sign(&webhook.secret, body.as_bytes()).as_str()));
+            assert_eq!(body, &received[0].1);
+        }
+        let payload: serde_json::Value = serde_json::from_str(&received[0].1).unwrap();
+        assert_eq!((&payload["event"], &payload["data"]), (&json!("echo_index_calculated"), &data));
+
+        let deliveries = repository.deliveries(webhook.id, RECENT_DELIVERIES_LIMIT).await.unwrap();
+        let attempts: Vec<_> = deliveries
+            .iter()
+            .map(|delivery| (delivery.attempt, delivery.status_code, delivery.succeeded))
+            .collect();
+        assert_eq!(attempts, [(3, Some(200), true), (2, Some(502), false), (1, Some(503), false)]);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_delivery_gives_up_after_three_retries_and_on_client_errors(pool: PgPool) {
+        let (url, endpoint) = mock_endpoint(&[500, 500, 500, 500, 404]);
+        let (repository, webhook) = webhook(&pool, &url).await;
+        let dispatcher = WebhookDispatcher::new(repository.clone())
+            .with_first_retry_delay(Duration::from_millis(1))
+            .allowing_private_hosts();
+        let data = json!({});
+
+        assert!(!dispatcher.deliver(&webhook, WebhookEvent::EchoIndexCalculated, &data).await.unwrap());
+        assert_eq!(endpoint.received.lock().unwrap().len(), 1 + MAX_RETRIES as usize);
+
+        // A client error will not go away by retrying
+        assert!(!dispatcher.deliver(&webhook, WebhookEvent::EchoIndexCalculated, &data).await.unwrap());
+        assert_eq!(endpoint.received.lock().unwrap().len(), 2 + MAX_RETRIES as usize);
+        let latest = &repository.deliveries(webhook.id, 1).await.unwrap()[0];
+        assert_eq!((latest.attempt, latest.status_code), (1, Some(404)));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_webhooks_of_private_hosts_are_not_called(pool: PgPool) {
+        let (url, endpoint) = mock_endpoint(&[]);
+        let (repository, webhook) = webhook(&pool, &url.replace("http://", "https://")).await;
+        let dispatcher = WebhookDispatcher::new(repository.clone());
+
+        assert!(!dispatcher.deliver(&webhook, WebhookEvent::EchoIndexCalculated, &json!({})).await.unwrap());
+        assert!(endpoint.received.lock().unwrap().is_empty());
+        let refused = &repository.deliveries(webhook.id, 1).await.unwrap()[0];
+        assert!(!refused.succeeded);
+        assert!(refused.error.as_deref().unwrap().contains("non-public"));
+    }
+}
@@ -0,0 +1,122 @@
+use actix_web::dev::ServerHandle;
+use prometheus::IntGauge;
+use std::future::Future;
+
+const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+
+/// Seconds in-flight requests get to finish once shutdown starts, from `SHUTDOWN_TIMEOUT_SECS`
+pub fn timeout_secs() -> u64 {
+    std::env::var("SHUTDOWN_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_SECS)
+}
+
+/// Completes on Ctrl-C or, on Unix, SIGTERM
+pub async fn signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!(error = %e, "Failed to listen for Ctrl-C");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => log::info!("Received Ctrl-C"),
+        _ = terminate => log::info!("Received SIGTERM"),
+    }
+}
+
+/// Once `signal` completes, stop accepting connections and wait for the requests in
+/// flight to finish, up to the server's shutdown timeout
+pub async fn stop_on(signal: impl Future<Output = ()>, server: ServerHandle, in_flight: IntGauge) {
+    signal.await;
+    log::info!("Shutting down with {} requests in flight", in_flight.get());
+    server.stop(true).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::RequestMetrics;
+    use crate::services::MetricsRegistry;
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const BODY_LEN: usize = 64 * 1024;
+
+    async fn slow() -> HttpResponse {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        HttpResponse::Ok().body("x".repeat(BODY_LEN))
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_completes_requests_in_flight() {
+        let metrics = Arc::new(MetricsRegistry::new());
+        let server_metrics = metrics.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .wrap(RequestMetrics::new(server_metrics.clone()))
+                .route("/slow", web::get().to(slow))
+        })
+        .workers(2)
+        .disable_signals()
+        .shutdown_timeout(10)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let url = format!("http://{}/slow", server.addrs()[0]);
+        let server = server.run();
+
+        let (trigger, triggered) = tokio::sync::oneshot::channel::<()>();
+        let in_flight = metrics.http_requests_in_flight.clone();
+        tokio::spawn(stop_on(async { triggered.await.unwrap_or(()) }, server.handle(), in_flight));
+        let server = tokio::spawn(server);
+
+        let client = reqwest::Client::new();
+        let requests: Vec<_> = (0..50)
+            .map(|_| {
+                let request = client.get(&url).send();
+                tokio::spawn(async move {
+                    let response = request.await?;
+                    let status = response.status();
+                    Ok::<_, reqwest::Error>((status, response.text().await?))
+                })
+            })
+            .collect();
+
+        // Signal once every request is being handled
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while metrics.http_requests_in_flight.get() < 50 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("all requests reach the handler");
+        trigger.send(()).unwrap();
+
+        for request in requests {
+            let (status, body) = request.await.unwrap().unwrap();
+            assert_eq!(status, 200);
+            assert_eq!(body.len(), BODY_LEN);
+        }
+        server.await.unwrap().unwrap();
+        assert_eq!(metrics.http_requests_in_flight.get(), 0);
+        assert!(client.get(&url).send().await.is_err(), "no connections accepted after shutdown");
+    }
+}
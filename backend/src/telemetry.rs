@@ -0,0 +1,254 @@
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{runtime, Resource};
+use sentry_tracing::EventFilter;
+use serde_json::Value;
+use std::fmt;
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::fmt::format::{Format, Json, JsonFields, Writer};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, MakeWriter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+const SERVICE_NAME: &str = "echolayer-backend";
+const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4317";
+const DEFAULT_ENVIRONMENT: &str = "development";
+/// Fields of the enclosing request span copied onto every JSON log line
+const REQUEST_FIELDS: [&str; 2] = ["request_id", "user_id"];
+
+/// Exporters that must stay alive until shutdown
+pub struct Telemetry {
+    /// Must be shut down before exit to flush buffered spans
+    pub tracer_provider: TracerProvider,
+    /// Flushes queued Sentry events when dropped
+    _sentry: Option<sentry::ClientInitGuard>,
+}
+
+/// Install the global subscriber: log lines filtered by `RUST_LOG` (default `info`) on stdout,
+/// and spans exported over OTLP/gRPC to `OTEL_EXPORTER_OTLP_ENDPOINT` (Jaeger accepts OTLP on 4317).
+/// `log` records from dependencies are forwarded to the same subscriber.
+///
+/// With `LOG_FORMAT=json` log lines are JSON objects (see `JsonLogFormat`) instead of text.
+/// With `SENTRY_DSN` set, ERROR events are also reported to Sentry.
+pub fn init() -> Result<Telemetry, Box<dyn std::error::Error>> {
+    let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .unwrap_or_else(|_| DEFAULT_OTLP_ENDPOINT.to_string());
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()?;
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new(vec![KeyValue::new("service.name", SERVICE_NAME)]))
+        .build();
+
+    // Continue traces started by callers that send a W3C `traceparent` header
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    let environment = std::env::var("RUST_ENV").unwrap_or_else(|_| DEFAULT_ENVIRONMENT.to_string());
+    let json = std::env::var("LOG_FORMAT").is_ok_and(|format| format.eq_ignore_ascii_case("json"));
+    let sentry = std::env::var("SENTRY_DSN").ok().filter(|dsn| !dsn.is_empty()).map(|dsn| {
+        sentry::init((
+            dsn,
+            sentry::ClientOptions {
+                environment: Some(environment.clone().into()),
+                release: sentry::release_name!(),
+                ..Default::default()
+            },
+        ))
+    });
+
+    tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .with(json.then(|| json_layer(std::io::stdout, &environment)))
+        .with((!json).then(tracing_subscriber::fmt::layer))
+        .with(sentry.is_some().then(sentry_layer))
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME)))
+        .try_init()?;
+
+    Ok(Telemetry { tracer_provider: provider, _sentry: sentry })
+}
+
+/// Log layer writing one `JsonLogFormat` line per event
+pub fn json_layer<S, W>(
+    make_writer: W,
+    environment: &str,
+) -> tracing_subscriber::fmt::Layer<S, JsonFields, JsonLogFormat, W>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + 'static,
+{
+    tracing_subscriber::fmt::layer()
+        .fmt_fields(JsonFields::new())
+        .event_format(JsonLogFormat::new(environment))
+        .with_writer(make_writer)
+}
+
+/// Reports ERROR events to Sentry. Lower levels are not sent, not even as breadcrumbs.
+fn sentry_layer<S>() -> sentry_tracing::SentryLayer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    sentry_tracing::layer().event_filter(|metadata| match *metadata.level() {
+        Level::ERROR => EventFilter::Event,
+        _ => EventFilter::Ignore,
+    })
+}
+
+/// Formats an event as a JSON object with its fields at the top level, tagged with
+/// `service_name` and `environment`. Events inside a request also carry the `request_id`
+/// and, once the caller is authenticated, `user_id` recorded on the request span.
+pub struct JsonLogFormat {
+    inner: Format<Json>,
+    environment: String,
+}
+
+impl JsonLogFormat {
+    pub fn new(environment: &str) -> Self {
+        let inner = tracing_subscriber::fmt::format()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(true);
+        Self { inner, environment: environment.to_string() }
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for JsonLogFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        let mut line = String::new();
+        self.inner.format_event(ctx, Writer::new(&mut line), event)?;
+        let Ok(Value::Object(mut fields)) = serde_json::from_str::<Value>(&line) else {
+            return writer.write_str(&line);
+        };
+
+        // Spans are listed from the root, so the innermost value wins
+        if let Some(Value::Array(spans)) = fields.remove("spans") {
+            for span in spans.iter().filter_map(Value::as_object) {
+                for name in REQUEST_FIELDS {
+                    if let Some(value) = span.get(name) {
+                        fields.insert(name.to_string(), value.clone());
+                    }
+                }
+            }
+        }
+        fields.insert("service_name".to_string(), SERVICE_NAME.into());
+        fields.insert("environment".to_string(), self.environment.as_str().into());
+
+        writeln!(writer, "{}", Value::Object(fields))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::auth::{AuthService, JwtConfig};
+    use crate::middleware::{JwtMiddleware, RequestIdMiddleware, RequestTracing, REQUEST_ID_HEADER};
+    use crate::models::user::Role;
+    use crate::services::TokenBlacklist;
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    /// Collects everything the log layer writes
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl CapturedLogs {
+        fn lines(&self) -> Vec<Value> {
+            let bytes = self.0.lock().unwrap();
+            String::from_utf8_lossy(&bytes).lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+        }
+    }
+
+    impl io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for CapturedLogs {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    async fn failing_query() -> HttpResponse {
+        let e = "connection refused";
+        tracing::error!(error = %e, "Profile query failed");
+        HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "error": "Internal server error"
+        }))
+    }
+
+    #[actix_web::test]
+    async fn test_json_logs_carry_request_context() {
+        let logs = CapturedLogs::default();
+        let subscriber = tracing_subscriber::registry().with(json_layer(logs.clone(), "staging"));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let blacklist = web::Data::new(TokenBlacklist::new());
+        let app = init_service(
+            App::new().wrap(RequestIdMiddleware).wrap(RequestTracing).service(
+                web::scope("/users")
+                    .wrap(JwtMiddleware::new(JwtConfig::new("test-secret"), blacklist))
+                    .route("/me", web::get().to(failing_query)),
+            ),
+        )
+        .await;
+        let token = AuthService::generate_access_token(
+            "user_1",
+            "wallet_1",
+            "session_1",
+            Role::User,
+            false,
+            &JwtConfig::new("test-secret"),
+        )
+        .unwrap();
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let request = TestRequest::get()
+            .uri("/users/me")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .insert_header((REQUEST_ID_HEADER, request_id.as_str()))
+            .to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), 500);
+        assert_eq!(response.headers().get(REQUEST_ID_HEADER).unwrap(), request_id.as_str());
+        let body: Value = read_body_json(response).await;
+        assert_eq!(body["request_id"], request_id);
+        tracing::info!("Request finished");
+
+        let lines = logs.lines();
+        let failure = lines.iter().find(|line| line["message"] == "Profile query failed").unwrap();
+        assert_eq!(failure["level"], "ERROR");
+        assert_eq!(failure["error"], "connection refused");
+        assert_eq!(failure["user_id"], "user_1");
+        assert_eq!(failure["service_name"], SERVICE_NAME);
+        assert_eq!(failure["environment"], "staging");
+        // The caller's request ID correlates the log line with the response
+        assert_eq!(failure["request_id"], request_id);
+
+        // Events outside a request still name the service
+        let finished = lines.iter().find(|line| line["message"] == "Request finished").unwrap();
+        assert_eq!(finished["service_name"], SERVICE_NAME);
+        assert!(finished.get("request_id").is_none());
+    }
+}
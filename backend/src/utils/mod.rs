@@ -1,3 +0,0 @@
-pub mod config;
-pub mod auth;
-pub mod validation; 
\ No newline at end of file
@@ -0,0 +1,304 @@
+//! Echo Index Integration Tests
+//! Tests the core Echo Index calculation functionality
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use echolayer_backend::models::content::{Content, EchoIndex, Propagation};
+use echolayer_backend::models::echo_index::{AudienceMetrics, EchoIndexCalculator};
+use echolayer_backend::models::Platform;
+use echolayer_backend::services::{BotDetector, EchoService};
+
+const PLATFORMS: [Platform; 5] =
+    [Platform::Twitter, Platform::Telegram, Platform::LinkedIn, Platform::Reddit, Platform::Discord];
+
+/// Echo Index of content as the service scores it, with the default weights and no
+/// account ages or language factors
+fn score(content: &Content, propagations: &[Propagation], audience: &AudienceMetrics) -> EchoIndex {
+    let bot_detector = BotDetector::new(content.created_at);
+    tokio_test::block_on(EchoService::score(
+        content,
+        propagations,
+        std::slice::from_ref(audience),
+        &EchoIndexCalculator::default(),
+        &HashMap::new(),
+        &bot_detector,
+    ))
+    .unwrap()
+}
+
+fn components(echo_index: &EchoIndex) -> [f64; 4] {
+    [
+        echo_index.originality_depth_factor,
+        echo_index.audience_weight_rating,
+        echo_index.transmission_path_mapping,
+        echo_index.quote_frequency,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_content() -> Content {
+        let mut content = Content::new(
+            Uuid::new_v4(),
+            "This is a comprehensive analysis of the latest blockchain technology that enables decentralized attention tracking across multiple social media platforms. The innovation promises to revolutionize how we measure and reward content quality.".to_string(),
+            Platform::Twitter,
+            "https://twitter.com/echolayer/status/123".to_string(),
+        );
+        content.created_at = Utc::now() - Duration::hours(6);
+        content
+    }
+
+    fn create_test_audience() -> AudienceMetrics {
+        AudienceMetrics {
+            total_interactions: 175,
+            quality_interactions: 75,
+            audience_diversity: 0.8,
+            influencer_ratio: 0.2,
+            engagement_depth: 0.7,
+        }
+    }
+
+    fn create_test_propagation(
+        content_id: Uuid,
+        platform: Platform,
+        propagation_type: &str,
+        hours_ago: i64,
+    ) -> Propagation {
+        Propagation {
+            id: Uuid::new_v4(),
+            content_id,
+            from_user_id: Uuid::new_v4(),
+            to_user_id: Some(Uuid::new_v4()),
+            platform,
+            propagation_type: propagation_type.to_string(),
+            depth: 1,
+            weight: 0.8,
+            timestamp: Utc::now() - Duration::hours(hours_ago),
+            reach: 1000,
+            engagement: 120,
+            bot_score: 0.0,
+            fingerprint_checked: true,
+            duplicate_of: None,
+        }
+    }
+
+    fn create_test_propagations(content_id: Uuid) -> Vec<Propagation> {
+        vec![
+            create_test_propagation(content_id, Platform::Telegram, "share", 5),
+            create_test_propagation(content_id, Platform::LinkedIn, "quote", 3),
+            create_test_propagation(content_id, Platform::Reddit, "reply", 1),
+        ]
+    }
+
+    #[test]
+    fn test_echo_index_calculation_basic() {
+        let content = create_test_content();
+        let propagations = create_test_propagations(content.id);
+
+        let result = score(&content, &propagations, &create_test_audience());
+
+        // Verify all components are calculated, as fractions
+        for component in components(&result) {
+            assert!(component > 0.0 && component <= 1.0, "component {}", component);
+        }
+        assert!(result.overall_score > 0.0 && result.overall_score <= 1.0);
+    }
+
+    #[test]
+    fn test_echo_index_weights() {
+        let calculator = EchoIndexCalculator::default();
+
+        // Verify weights sum to 1.0
+        let total_weight =
+            calculator.odf_weight() + calculator.awr_weight() + calculator.tpm_weight() + calculator.qf_weight();
+        assert!((total_weight - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_quotes_and_discussion_raise_quote_frequency() {
+        let content = create_test_content();
+        let shares: Vec<Propagation> = (0..3)
+            .map(|i| create_test_propagation(content.id, PLATFORMS[i].clone(), "share", i as i64))
+            .collect();
+        let quotes: Vec<Propagation> = ["quote", "quote", "reply"]
+            .iter()
+            .enumerate()
+            .map(|(i, kind)| create_test_propagation(content.id, PLATFORMS[i].clone(), kind, i as i64))
+            .collect();
+
+        let audience = create_test_audience();
+        assert_eq!(score(&content, &shares, &audience).quote_frequency, 0.0);
+        assert!(score(&content, &quotes, &audience).quote_frequency > 0.0);
+    }
+
+    #[test]
+    fn test_cross_platform_propagation() {
+        let content = create_test_content();
+        let audience = create_test_audience();
+        let one_platform: Vec<Propagation> = (0..4)
+            .map(|i| create_test_propagation(content.id, Platform::Telegram, "share", i))
+            .collect();
+        let cross_platform: Vec<Propagation> = (0..4)
+            .map(|i| create_test_propagation(content.id, PLATFORMS[i as usize].clone(), "share", i))
+            .collect();
+
+        // Reaching more platforms maps a wider transmission path
+        let narrow = score(&content, &one_platform, &audience).transmission_path_mapping;
+        let wide = score(&content, &cross_platform, &audience).transmission_path_mapping;
+        assert!(wide > narrow, "TPM {} on one platform, {} on four", narrow, wide);
+    }
+}
+
+#[cfg(test)]
+mod properties {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const WORDS: [&str; 8] = ["echo", "layer", "novel", "attention", "signal", "reward", "network", "ideas"];
+    const PROPAGATION_TYPES: [&str; 6] = ["share", "cross_post", "link", "quote", "reply", "mention"];
+
+    fn hours_ago(hours: i64) -> DateTime<Utc> {
+        Utc::now() - Duration::hours(hours)
+    }
+
+    /// Content has text, so at least one word
+    fn arb_content() -> impl Strategy<Value = Content> {
+        (
+            vec(0..WORDS.len(), 1..200),
+            0..PLATFORMS.len(),
+            0i64..72,
+            any::<bool>(),
+            (0.0..=0.2f64, 0.0..=0.1f64, 0.0..=1.0f64),
+        )
+            .prop_map(|(words, platform, age_hours, author_verified, (trending, cross_platform, loop_strength))| {
+                let text = words.into_iter().map(|word| WORDS[word]).collect::<Vec<_>>().join(" ");
+                let mut content = Content::new(
+                    Uuid::new_v4(),
+                    text,
+                    PLATFORMS[platform].clone(),
+                    "https://example.com/post".to_string(),
+                );
+                content.created_at = hours_ago(age_hours);
+                content.author_verified = author_verified;
+                content.trending_hashtag_bonus = trending;
+                content.cross_platform_bonus = cross_platform;
+                content.echo_loop_strength = loop_strength;
+                content
+            })
+    }
+
+    fn arb_propagation() -> impl Strategy<Value = Propagation> {
+        (
+            0usize..8,
+            0..PLATFORMS.len(),
+            0..PROPAGATION_TYPES.len(),
+            0i64..96 * 60,
+            (0i64..1_000_000, 0i64..1_000_000),
+            0.0..=1.0f64,
+            0.0..=1.0f64,
+        )
+            .prop_map(|(user, platform, propagation_type, age_minutes, (reach, engagement), weight, bot_score)| {
+                Propagation {
+                    id: Uuid::new_v4(),
+                    content_id: Uuid::new_v4(),
+                    // A few users propagating repeatedly
+                    from_user_id: Uuid::from_u128(user as u128),
+                    to_user_id: None,
+                    platform: PLATFORMS[platform].clone(),
+                    propagation_type: PROPAGATION_TYPES[propagation_type].to_string(),
+                    depth: 1,
+                    weight,
+                    timestamp: Utc::now() - Duration::minutes(age_minutes),
+                    reach,
+                    engagement,
+                    bot_score,
+                    fingerprint_checked: true,
+                    duplicate_of: None,
+                }
+            })
+    }
+
+    fn arb_audience() -> impl Strategy<Value = AudienceMetrics> {
+        (0i32..100_000, 0.0..=1.0f64, 0.0..=1.0f64, 0.0..=1.0f64, 0.0..=1.0f64).prop_map(
+            |(total_interactions, audience_quality, audience_diversity, influencer_ratio, engagement_depth)| {
+                AudienceMetrics {
+                    total_interactions,
+                    quality_interactions: (total_interactions as f64 * audience_quality).round() as i32,
+                    audience_diversity,
+                    influencer_ratio,
+                    engagement_depth,
+                }
+            },
+        )
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1000))]
+
+        #[test]
+        fn prop_final_score_is_bounded(
+            content in arb_content(),
+            propagations in vec(arb_propagation(), 0..50),
+            audience in arb_audience(),
+        ) {
+            let result = score(&content, &propagations, &audience);
+            for component in components(&result) {
+                prop_assert!((0.0..=1.0).contains(&component), "component {}", component);
+            }
+            prop_assert!((0.0..=1.0).contains(&result.overall_score), "overall score {}", result.overall_score);
+        }
+
+        #[test]
+        fn prop_organic_propagations_never_lower_odf(
+            odf in 0.0..=1.0f64,
+            bot_scores in vec(0.0..=1.0f64, 0..50),
+            organic in 1usize..10,
+        ) {
+            let before = EchoIndexCalculator::discount_bot_propagation(odf, &bot_scores);
+            let mut more = bot_scores.clone();
+            more.extend(std::iter::repeat_n(0.0, organic));
+            let after = EchoIndexCalculator::discount_bot_propagation(odf, &more);
+            prop_assert!(after >= before, "ODF fell from {} to {}", before, after);
+        }
+
+        #[test]
+        fn prop_audience_quality_raises_awr(audience in arb_audience()) {
+            prop_assume!(audience.total_interactions > 0);
+            let at_quality = |quality: f64| {
+                EchoIndexCalculator::calculate_awr(&AudienceMetrics {
+                    quality_interactions: (audience.total_interactions as f64 * quality).round() as i32,
+                    ..audience.clone()
+                })
+            };
+            let disengaged = at_quality(0.0);
+            let engaged = at_quality(1.0);
+            prop_assert!(engaged > disengaged, "AWR {} at quality 0.0, {} at 1.0", disengaged, engaged);
+        }
+
+        #[test]
+        fn prop_unpropagated_content_has_no_tpm_or_qf(content in arb_content(), audience in arb_audience()) {
+            // Loops and trending hashtags only add to content that travelled
+            let content = Content { trending_hashtag_bonus: 0.0, echo_loop_strength: 0.0, ..content };
+            let result = score(&content, &[], &audience);
+            prop_assert_eq!(result.transmission_path_mapping, 0.0);
+            prop_assert_eq!(result.quote_frequency, 0.0);
+        }
+
+        #[test]
+        fn prop_calculation_is_pure(
+            content in arb_content(),
+            propagations in vec(arb_propagation(), 0..50),
+            audience in arb_audience(),
+        ) {
+            let first = score(&content, &propagations, &audience);
+            let again = score(&content, &propagations, &audience);
+            prop_assert_eq!(components(&first), components(&again));
+            prop_assert_eq!(first.overall_score, again.overall_score);
+        }
+    }
+}